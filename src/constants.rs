@@ -7,7 +7,9 @@ pub const TOKEN_STATE_SEED: &[u8] = b"token_state";
 // ── Token Metadata (unified: ZUPY for all environments) ─────────────
 pub const TOKEN_NAME: &str = "ZUPY";
 pub const TOKEN_SYMBOL: &str = "ZUPY";
+/// Default mint decimals; `initialize_token` may override it (up to `MAX_TOKEN_DECIMALS`).
 pub const TOKEN_DECIMALS: u8 = 6;
+pub const MAX_TOKEN_DECIMALS: u8 = 9;
 
 // ── Metadata URI (feature-flagged: different IPFS hashes) ────────────
 #[cfg(not(feature = "production"))]
@@ -33,8 +35,43 @@ pub const PER_TX_MINT_LIMIT: u64 = 50_000_000_000; // 50K
 pub const PER_TX_AUTO_LIMIT: u64 = 100_000_000_000; // 100K (both envs)
 pub const DAILY_AUTO_LIMIT: u64 = 500_000_000_000; // 500K (both envs)
 
+// ── Basis Points ─────────────────────────────────────────────────────
+/// 100% expressed in basis points (split ratios).
+pub const BPS_DENOMINATOR: u16 = 10_000;
+/// Upper bound for `withdraw_fee_bps` (5%), enforced by `set_withdraw_fee`.
+pub const MAX_WITHDRAW_FEE_BPS: u16 = 500;
+
+// ── Return-to-Pool ───────────────────────────────────────────────────
+/// `amount` sentinel for `return_user_to_pool[_merged,_v1]`: return the full
+/// available balance, resolved on-chain at execution time.
+pub const RETURN_ALL_AMOUNT: u64 = u64::MAX;
+
+// ── Idempotency Receipts ─────────────────────────────────────────────
+/// Minimum age before `close_receipt` may reclaim a receipt's rent. Longer than
+/// any backend retry window, so a late retry still hits `DuplicateOperation`.
+pub const RECEIPT_RETENTION_SECS: i64 = 7 * 24 * 60 * 60;
+
+// ── Batch Limits ─────────────────────────────────────────────────────
+/// Max recipients per `batch_transfer_from_pool`: 64-account transaction
+/// lock limit minus the 16 fixed accounts (15 + Merkle output queue).
+pub const MAX_BATCH_RECIPIENTS: usize = 48;
+/// Max companies per `batch_return_to_pool`: leaves 22 of the 64 account locks
+/// for the Light system accounts after the 10 fixed accounts.
+pub const MAX_BATCH_RETURN_COMPANIES: usize = 32;
+/// Max cNFTs per `mint_coupon_cnft_batch`: 12 shared-name entries with ~36-byte
+/// URIs fit the 1232-byte packet beside the 15 accounts and 2 signatures, and
+/// 12 Bubblegum MintToCollectionV1 CPIs stay under the 1.4M CU transaction cap.
+pub const MAX_CNFT_BATCH: usize = 12;
+
 // ── Program ID (unified: same keypair for devnet + mainnet) ──────────
 pub const PROGRAM_ID: [u8; 32] = decode_32_const("ZUPYzr87cgminBywohtbUxnaiFMwXNy8A5pD9cCcvVU");
+/// token_state PDA = find_program_address(&[TOKEN_STATE_SEED], PROGRAM_ID).
+/// Compared directly in `TokenState::try_from_account` instead of re-deriving
+/// per call; builds with a different PROGRAM_ID enable `custom-program-id`.
+pub const TOKEN_STATE_PDA: [u8; 32] =
+    decode_32_const("6UNF7ECPmEmKrvgpH6abi6XcGmSASvJU9vaDjGgzMVYP");
+/// Canonical bump of `TOKEN_STATE_PDA`; the stored TokenState bump must equal it.
+pub const TOKEN_STATE_BUMP: u8 = 254;
 
 // ── External Program IDs (compile-time constants) ────────────────────
 pub const TOKEN_2022_PROGRAM_ID: [u8; 32] =
@@ -49,6 +86,14 @@ pub const SPL_ACCOUNT_COMPRESSION_ID: [u8; 32] =
     decode_32_const("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
 pub const SPL_NOOP_ID: [u8; 32] =
     decode_32_const("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+/// Metaplex Token Metadata. Owns the coupon collection's metadata and master
+/// edition; Bubblegum CPIs into it to verify collection membership.
+pub const MPL_TOKEN_METADATA_PROGRAM_ID: [u8; 32] =
+    decode_32_const("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+/// SPL Memo v2. Optional trailing account on memo-carrying transfers; when
+/// present the validated memo is re-emitted as a standard memo instruction.
+pub const SPL_MEMO_PROGRAM_ID: [u8; 32] =
+    decode_32_const("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
 
 // ── Light Protocol Program IDs (ZK Compressed Tokens) ────────────────
 /// Light compressed-token program (cToken). CPI target for all compressed
@@ -107,6 +152,15 @@ pub const USER_SEED: &[u8] = b"user";
 pub const USER_PDA_SEED: &[u8] = b"user_pda";
 pub const INCENTIVE_POOL_SEED: &[u8] = b"incentive_pool";
 pub const DISTRIBUTION_POOL_SEED: &[u8] = b"distribution_pool";
+pub const ESCROW_SEED: &[u8] = b"escrow";
+pub const VESTING_SEED: &[u8] = b"vesting";
+pub const RECEIPT_SEED: &[u8] = b"receipt";
+pub const WITHDRAW_LIMIT_SEED: &[u8] = b"withdraw_limit";
+pub const WITHDRAWAL_REQUEST_SEED: &[u8] = b"withdrawal_request";
+pub const COUPON_STATE_SEED: &[u8] = b"coupon_state";
+pub const COMPANY_STATE_SEED: &[u8] = b"company_state";
+pub const USER_STATE_SEED: &[u8] = b"user_state";
+pub const STATS_SEED: &[u8] = b"stats";
 
 // ── Three-Wallet Security Pubkeys ────────────────────────────────────
 // Treasury: Trezor hardware wallet (unified for all environments)
@@ -122,14 +176,57 @@ pub const TRANSFER_AUTHORITY_PUBKEY: [u8; 32] =
 // ── Memo Constants ───────────────────────────────────────────────────
 pub const MEMO_PREFIX: &str = "zupy";
 pub const MEMO_VERSION: &str = "v1";
+/// Memo schema v2: v1 plus a trailing client operation id segment.
+pub const MEMO_VERSION_V2: &str = "v2";
+/// Length bounds of the v2 operation id (base62).
+pub const MEMO_OP_ID_MIN_LEN: usize = 16;
+pub const MEMO_OP_ID_MAX_LEN: usize = 27;
+/// Longest accepted memo, in bytes.
+pub const MEMO_MAX_LEN: usize = 128;
+/// Most colon-separated segments a memo may have (prefix and version included).
+pub const MEMO_MAX_SEGMENTS: usize = 8;
 
 // ── Seconds per day (for rate limit reset) ───────────────────────────
 pub const SECONDS_PER_DAY: i64 = 86_400;
 
+// ── NFT mint rate limit (RateLimitState NFT window) ──────────────────
+/// Length of the per-mint_authority NFT mint window, in seconds.
+pub const NFT_MINT_WINDOW_SECS: i64 = 3_600;
+/// NFTs (coupons + coupon cNFTs) one mint_authority may mint per window.
+pub const MAX_NFT_MINTS_PER_WINDOW: u64 = 500;
+
+// ── Operation rate limit (RateLimitState operation window) ───────────
+/// Default operation window written by `initialize_rate_limit`, in seconds.
+pub const DEFAULT_RATE_LIMIT_WINDOW_SECS: i64 = SECONDS_PER_DAY;
+/// Default operations per window written by `initialize_rate_limit`.
+pub const DEFAULT_RATE_LIMIT_MAX_OPERATIONS: u64 = 1_000;
+
 // ── Token-2022 Mint Account Size ───────────────────────────────────
 /// Standard Token-2022 mint account size (no extensions): 82 bytes.
 pub const BASIC_MINT_SIZE: u64 = 82;
 
+/// Token-2022 mint with the NonTransferable and MetadataPointer extensions
+/// (Zupy Cards), before TokenMetadata is appended:
+/// 165 (padded base) + 1 (AccountType) + (2 + 2 + 0) + (2 + 2 + 64) = 238.
+pub const ZUPY_CARD_MINT_SIZE: u64 = 238;
+
+/// Token-2022 mint with the MintCloseAuthority extension (coupons):
+/// 165 (padded base) + 1 (AccountType) + 2 (ext type LE) + 2 (ext length LE) + 32 = 202.
+pub const CLOSABLE_MINT_SIZE: u64 = 202;
+
+// ── Zupy Card metadata ───────────────────────────────────────────────
+/// TokenMetadata name / symbol written on every card mint; the URI is per card.
+pub const ZUPY_CARD_NAME: &str = "Zuper Card";
+pub const ZUPY_CARD_SYMBOL: &str = "ZCARD";
+
+// ── Zupy Card tiers ──────────────────────────────────────────────────
+/// Highest card tier (0 = none, 1 = bronze, 2 = silver, 3 = gold).
+pub const MAX_CARD_TIER: u8 = 3;
+
+// ── Coupon cNFT royalties ────────────────────────────────────────────
+/// Max creators in `mint_coupon_cnft` metadata (Zupy + one partner brand).
+pub const MAX_CNFT_CREATORS: usize = 2;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -144,6 +241,7 @@ mod tests {
         assert_eq!(TOKEN_NAME, "ZUPY");
         assert_eq!(TOKEN_SYMBOL, "ZUPY");
         assert_eq!(TOKEN_DECIMALS, 6);
+        const { assert!(TOKEN_DECIMALS <= MAX_TOKEN_DECIMALS) };
     }
 
     #[test]
@@ -160,6 +258,30 @@ mod tests {
         assert_eq!(DAILY_AUTO_LIMIT, 500_000_000_000);
     }
 
+    #[test]
+    fn test_max_batch_recipients_fits_account_lock_limit() {
+        assert_eq!(MAX_BATCH_RECIPIENTS, 48);
+        const { assert!(16 + MAX_BATCH_RECIPIENTS <= 64) };
+    }
+
+    #[test]
+    fn test_max_batch_return_companies_leaves_room_for_light_accounts() {
+        assert_eq!(MAX_BATCH_RETURN_COMPANIES, 32);
+        const { assert!(10 + MAX_BATCH_RETURN_COMPANIES + 22 <= 64) };
+    }
+
+    #[test]
+    fn test_max_cnft_batch_fits_packet() {
+        assert_eq!(MAX_CNFT_BATCH, 12);
+        // 2 signatures, header, 16 keys (15 accounts + program), blockhash
+        let message = 1 + 2 * 64 + 3 + 1 + 16 * 32 + 32;
+        // ix count, program index, 15 account indices, data length, disc, count, mode
+        let ix = 1 + 1 + 1 + 15 + 2 + 8 + 4 + 1;
+        // shared "Zupy Coupon" / "ZCPN" + 36-byte URIs
+        let entries = (4 + 11) + (4 + 4) + MAX_CNFT_BATCH * (4 + 36);
+        assert!(message + ix + entries <= 1232);
+    }
+
     #[test]
     fn test_external_program_ids_are_32_bytes() {
         assert_eq!(TOKEN_2022_PROGRAM_ID.len(), 32);
@@ -168,6 +290,8 @@ mod tests {
         assert_eq!(BUBBLEGUM_PROGRAM_ID.len(), 32);
         assert_eq!(SPL_ACCOUNT_COMPRESSION_ID.len(), 32);
         assert_eq!(SPL_NOOP_ID.len(), 32);
+        assert_eq!(MPL_TOKEN_METADATA_PROGRAM_ID.len(), 32);
+        assert_eq!(SPL_MEMO_PROGRAM_ID.len(), 32);
     }
 
     #[test]
@@ -193,6 +317,16 @@ mod tests {
         assert_ne!(LIGHT_COMPRESSED_TOKEN_PROGRAM_ID[31], 0x00);
     }
 
+    #[test]
+    fn test_token_state_pda_matches_derivation() {
+        let (pda, bump) = pinocchio::Address::find_program_address(
+            &[TOKEN_STATE_SEED],
+            &pinocchio::Address::from(PROGRAM_ID),
+        );
+        assert_eq!(pda, pinocchio::Address::from(TOKEN_STATE_PDA));
+        assert_eq!(bump, TOKEN_STATE_BUMP);
+    }
+
     #[test]
     fn test_light_cpi_signer_program_id_matches_our_program() {
         assert_eq!(LIGHT_CPI_SIGNER.program_id, PROGRAM_ID,
@@ -200,7 +334,7 @@ mod tests {
         // CPI signer pubkey is non-zero (derived from program ID + "cpi_authority" seed)
         assert_ne!(LIGHT_CPI_SIGNER.cpi_signer, [0u8; 32]);
         // Bump must be a valid canonical PDA bump (1-255)
-        assert!(LIGHT_CPI_SIGNER.bump >= 1 && LIGHT_CPI_SIGNER.bump <= 255);
+        assert_ne!(LIGHT_CPI_SIGNER.bump, 0);
     }
 
     #[test]
@@ -273,10 +407,26 @@ mod tests {
     fn test_memo_constants() {
         assert_eq!(MEMO_PREFIX, "zupy");
         assert_eq!(MEMO_VERSION, "v1");
+        assert_eq!(MEMO_VERSION_V2, "v2");
+        assert_eq!(MEMO_MAX_LEN, 128);
+        assert_eq!(MEMO_MAX_SEGMENTS, 8);
     }
 
     #[test]
     fn test_basic_mint_size() {
         assert_eq!(BASIC_MINT_SIZE, 82);
     }
+
+    #[test]
+    fn test_zupy_card_mint_size() {
+        // Zero-length NonTransferable TLV + 64-byte MetadataPointer TLV after
+        // the padded base + AccountType byte
+        assert_eq!(ZUPY_CARD_MINT_SIZE, 165 + 1 + (2 + 2) + (2 + 2 + 64));
+    }
+
+    #[test]
+    fn test_closable_mint_size() {
+        // 32-byte close authority TLV after the padded base + AccountType byte
+        assert_eq!(CLOSABLE_MINT_SIZE, 165 + 1 + 2 + 2 + 32);
+    }
 }
@@ -1,6 +1,7 @@
 use pinocchio::error::ProgramError;
 
-/// All 30 error codes matching Anchor program (6000-6029).
+/// Program error codes. 6000-6029 match the Anchor program exactly;
+/// codes from 6030 onward are Pinocchio-only additions.
 /// Django compatibility requires identical Custom(code) values.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u32)]
@@ -35,6 +36,54 @@ pub enum ZupyTokenError {
     InvalidMetadataPDA = 6027,
     InvalidOperationType = 6028,
     SplitCalculationError = 6029,
+    SelfTransfer = 6030,
+    InvalidSplitRatio = 6031,
+    EscrowAlreadySettled = 6032,
+    EscrowExpired = 6033,
+    EscrowNotExpired = 6034,
+    NothingToClaim = 6035,
+    DuplicateOperation = 6036,
+    ReceiptRetentionActive = 6037,
+    InvalidWithdrawFee = 6038,
+    ExceedsPerTxLimit = 6039,
+    WithdrawLimitExceeded = 6040,
+    RequiresApproval = 6041,
+    DuplicateBatchEntry = 6042,
+    StaleNonce = 6043,
+    V1Sunset = 6044,
+    MaxSupplyExceeded = 6045,
+    InvalidDecimals = 6046,
+    InvalidMetadataKey = 6047,
+    InvalidMetadataValue = 6048,
+    MetadataAlreadyInitialized = 6049,
+    MetadataNotInitialized = 6050,
+    MetadataFrozen = 6051,
+    InvalidCardBalance = 6052,
+    InvalidCardTier = 6053,
+    CardRevoked = 6054,
+    CouponAlreadyRedeemed = 6055,
+    CouponExpired = 6056,
+    CouponStillActive = 6057,
+    InvalidCollection = 6058,
+    InvalidCreators = 6059,
+    InvalidRoyaltyBps = 6060,
+    InvalidKsuid = 6061,
+    CardAlreadyExists = 6062,
+    RateLimitExceeded = 6063,
+    UserNotRegistered = 6064,
+    CompanyNotEmpty = 6065,
+    InvalidAccountDiscriminator = 6066,
+    CardFrozen = 6067,
+    AuthorityNotSigner = 6068,
+    AuthorityMismatch = 6069,
+    FeePayerNotSigner = 6070,
+    AccountNotTokenOwned = 6071,
+    TokenOwnerMismatch = 6072,
+    InvalidAssociatedTokenAccount = 6073,
+    InvalidCompressedProof = 6074,
+    InvalidInputCount = 6075,
+    ArithmeticOverflow = 6076,
+    UnknownInstruction = 6077,
 }
 
 impl From<ZupyTokenError> for ProgramError {
@@ -43,14 +92,28 @@ impl From<ZupyTokenError> for ProgramError {
     }
 }
 
+/// Logs `zupy_err:{Variant}:{context}` and evaluates to the matching
+/// `ProgramError`, so a failed transaction names the check that rejected it.
+/// The line is assembled with `concat!` at compile time: no formatting, one
+/// `sol_log` syscall per error.
+///
+/// `return Err(zupy_err!(InvalidPoolAccount, "pool_ata"));`
+macro_rules! zupy_err {
+    ($err:ident, $ctx:literal) => {{
+        $crate::helpers::log::sol_log(concat!("zupy_err:", stringify!($err), ":", $ctx));
+        pinocchio::error::ProgramError::from($crate::error::ZupyTokenError::$err)
+    }};
+}
+pub(crate) use zupy_err;
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    /// AC6: All 30 error codes map to ProgramError::Custom(6000 + N)
+    /// AC6: All 78 error codes map to ProgramError::Custom(6000 + N)
     #[test]
     fn test_all_error_codes_match_anchor_values() {
-        let expected: [(ZupyTokenError, u32); 30] = [
+        let expected: [(ZupyTokenError, u32); 78] = [
             (ZupyTokenError::InvalidAuthority, 6000),
             (ZupyTokenError::DailyLimitExceeded, 6001),
             (ZupyTokenError::TxLimitExceeded, 6002),
@@ -81,6 +144,54 @@ mod tests {
             (ZupyTokenError::InvalidMetadataPDA, 6027),
             (ZupyTokenError::InvalidOperationType, 6028),
             (ZupyTokenError::SplitCalculationError, 6029),
+            (ZupyTokenError::SelfTransfer, 6030),
+            (ZupyTokenError::InvalidSplitRatio, 6031),
+            (ZupyTokenError::EscrowAlreadySettled, 6032),
+            (ZupyTokenError::EscrowExpired, 6033),
+            (ZupyTokenError::EscrowNotExpired, 6034),
+            (ZupyTokenError::NothingToClaim, 6035),
+            (ZupyTokenError::DuplicateOperation, 6036),
+            (ZupyTokenError::ReceiptRetentionActive, 6037),
+            (ZupyTokenError::InvalidWithdrawFee, 6038),
+            (ZupyTokenError::ExceedsPerTxLimit, 6039),
+            (ZupyTokenError::WithdrawLimitExceeded, 6040),
+            (ZupyTokenError::RequiresApproval, 6041),
+            (ZupyTokenError::DuplicateBatchEntry, 6042),
+            (ZupyTokenError::StaleNonce, 6043),
+            (ZupyTokenError::V1Sunset, 6044),
+            (ZupyTokenError::MaxSupplyExceeded, 6045),
+            (ZupyTokenError::InvalidDecimals, 6046),
+            (ZupyTokenError::InvalidMetadataKey, 6047),
+            (ZupyTokenError::InvalidMetadataValue, 6048),
+            (ZupyTokenError::MetadataAlreadyInitialized, 6049),
+            (ZupyTokenError::MetadataNotInitialized, 6050),
+            (ZupyTokenError::MetadataFrozen, 6051),
+            (ZupyTokenError::InvalidCardBalance, 6052),
+            (ZupyTokenError::InvalidCardTier, 6053),
+            (ZupyTokenError::CardRevoked, 6054),
+            (ZupyTokenError::CouponAlreadyRedeemed, 6055),
+            (ZupyTokenError::CouponExpired, 6056),
+            (ZupyTokenError::CouponStillActive, 6057),
+            (ZupyTokenError::InvalidCollection, 6058),
+            (ZupyTokenError::InvalidCreators, 6059),
+            (ZupyTokenError::InvalidRoyaltyBps, 6060),
+            (ZupyTokenError::InvalidKsuid, 6061),
+            (ZupyTokenError::CardAlreadyExists, 6062),
+            (ZupyTokenError::RateLimitExceeded, 6063),
+            (ZupyTokenError::UserNotRegistered, 6064),
+            (ZupyTokenError::CompanyNotEmpty, 6065),
+            (ZupyTokenError::InvalidAccountDiscriminator, 6066),
+            (ZupyTokenError::CardFrozen, 6067),
+            (ZupyTokenError::AuthorityNotSigner, 6068),
+            (ZupyTokenError::AuthorityMismatch, 6069),
+            (ZupyTokenError::FeePayerNotSigner, 6070),
+            (ZupyTokenError::AccountNotTokenOwned, 6071),
+            (ZupyTokenError::TokenOwnerMismatch, 6072),
+            (ZupyTokenError::InvalidAssociatedTokenAccount, 6073),
+            (ZupyTokenError::InvalidCompressedProof, 6074),
+            (ZupyTokenError::InvalidInputCount, 6075),
+            (ZupyTokenError::ArithmeticOverflow, 6076),
+            (ZupyTokenError::UnknownInstruction, 6077),
         ];
 
         for (error, code) in expected {
@@ -101,10 +212,17 @@ mod tests {
         assert_eq!(pe, ProgramError::Custom(6018));
     }
 
+    /// zupy_err! evaluates to the same Custom(code) as `.into()`
+    #[test]
+    fn test_zupy_err_macro_yields_custom_code() {
+        assert_eq!(zupy_err!(InvalidPDA, "company"), ProgramError::Custom(6007));
+        assert_eq!(zupy_err!(FeePayerNotSigner, "fee_payer"), ProgramError::Custom(6070));
+    }
+
     /// Verify all error variants can be converted to ProgramError
     #[test]
     fn test_all_errors_convert_to_program_error() {
-        let errors: [ZupyTokenError; 30] = [
+        let errors: [ZupyTokenError; 78] = [
             ZupyTokenError::InvalidAuthority,
             ZupyTokenError::DailyLimitExceeded,
             ZupyTokenError::TxLimitExceeded,
@@ -135,6 +253,54 @@ mod tests {
             ZupyTokenError::InvalidMetadataPDA,
             ZupyTokenError::InvalidOperationType,
             ZupyTokenError::SplitCalculationError,
+            ZupyTokenError::SelfTransfer,
+            ZupyTokenError::InvalidSplitRatio,
+            ZupyTokenError::EscrowAlreadySettled,
+            ZupyTokenError::EscrowExpired,
+            ZupyTokenError::EscrowNotExpired,
+            ZupyTokenError::NothingToClaim,
+            ZupyTokenError::DuplicateOperation,
+            ZupyTokenError::ReceiptRetentionActive,
+            ZupyTokenError::InvalidWithdrawFee,
+            ZupyTokenError::ExceedsPerTxLimit,
+            ZupyTokenError::WithdrawLimitExceeded,
+            ZupyTokenError::RequiresApproval,
+            ZupyTokenError::DuplicateBatchEntry,
+            ZupyTokenError::StaleNonce,
+            ZupyTokenError::V1Sunset,
+            ZupyTokenError::MaxSupplyExceeded,
+            ZupyTokenError::InvalidDecimals,
+            ZupyTokenError::InvalidMetadataKey,
+            ZupyTokenError::InvalidMetadataValue,
+            ZupyTokenError::MetadataAlreadyInitialized,
+            ZupyTokenError::MetadataNotInitialized,
+            ZupyTokenError::MetadataFrozen,
+            ZupyTokenError::InvalidCardBalance,
+            ZupyTokenError::InvalidCardTier,
+            ZupyTokenError::CardRevoked,
+            ZupyTokenError::CouponAlreadyRedeemed,
+            ZupyTokenError::CouponExpired,
+            ZupyTokenError::CouponStillActive,
+            ZupyTokenError::InvalidCollection,
+            ZupyTokenError::InvalidCreators,
+            ZupyTokenError::InvalidRoyaltyBps,
+            ZupyTokenError::InvalidKsuid,
+            ZupyTokenError::CardAlreadyExists,
+            ZupyTokenError::RateLimitExceeded,
+            ZupyTokenError::UserNotRegistered,
+            ZupyTokenError::CompanyNotEmpty,
+            ZupyTokenError::InvalidAccountDiscriminator,
+            ZupyTokenError::CardFrozen,
+            ZupyTokenError::AuthorityNotSigner,
+            ZupyTokenError::AuthorityMismatch,
+            ZupyTokenError::FeePayerNotSigner,
+            ZupyTokenError::AccountNotTokenOwned,
+            ZupyTokenError::TokenOwnerMismatch,
+            ZupyTokenError::InvalidAssociatedTokenAccount,
+            ZupyTokenError::InvalidCompressedProof,
+            ZupyTokenError::InvalidInputCount,
+            ZupyTokenError::ArithmeticOverflow,
+            ZupyTokenError::UnknownInstruction,
         ];
         for error in errors {
             let code = error as u32;
@@ -143,10 +309,10 @@ mod tests {
         }
     }
 
-    /// Verify contiguous range — no gaps in 6000..=6029
+    /// Verify contiguous range — no gaps in 6000..=6077
     #[test]
     fn test_error_codes_contiguous() {
-        let all_codes: [u32; 30] = [
+        let all_codes: [u32; 78] = [
             ZupyTokenError::InvalidAuthority as u32,
             ZupyTokenError::DailyLimitExceeded as u32,
             ZupyTokenError::TxLimitExceeded as u32,
@@ -177,6 +343,54 @@ mod tests {
             ZupyTokenError::InvalidMetadataPDA as u32,
             ZupyTokenError::InvalidOperationType as u32,
             ZupyTokenError::SplitCalculationError as u32,
+            ZupyTokenError::SelfTransfer as u32,
+            ZupyTokenError::InvalidSplitRatio as u32,
+            ZupyTokenError::EscrowAlreadySettled as u32,
+            ZupyTokenError::EscrowExpired as u32,
+            ZupyTokenError::EscrowNotExpired as u32,
+            ZupyTokenError::NothingToClaim as u32,
+            ZupyTokenError::DuplicateOperation as u32,
+            ZupyTokenError::ReceiptRetentionActive as u32,
+            ZupyTokenError::InvalidWithdrawFee as u32,
+            ZupyTokenError::ExceedsPerTxLimit as u32,
+            ZupyTokenError::WithdrawLimitExceeded as u32,
+            ZupyTokenError::RequiresApproval as u32,
+            ZupyTokenError::DuplicateBatchEntry as u32,
+            ZupyTokenError::StaleNonce as u32,
+            ZupyTokenError::V1Sunset as u32,
+            ZupyTokenError::MaxSupplyExceeded as u32,
+            ZupyTokenError::InvalidDecimals as u32,
+            ZupyTokenError::InvalidMetadataKey as u32,
+            ZupyTokenError::InvalidMetadataValue as u32,
+            ZupyTokenError::MetadataAlreadyInitialized as u32,
+            ZupyTokenError::MetadataNotInitialized as u32,
+            ZupyTokenError::MetadataFrozen as u32,
+            ZupyTokenError::InvalidCardBalance as u32,
+            ZupyTokenError::InvalidCardTier as u32,
+            ZupyTokenError::CardRevoked as u32,
+            ZupyTokenError::CouponAlreadyRedeemed as u32,
+            ZupyTokenError::CouponExpired as u32,
+            ZupyTokenError::CouponStillActive as u32,
+            ZupyTokenError::InvalidCollection as u32,
+            ZupyTokenError::InvalidCreators as u32,
+            ZupyTokenError::InvalidRoyaltyBps as u32,
+            ZupyTokenError::InvalidKsuid as u32,
+            ZupyTokenError::CardAlreadyExists as u32,
+            ZupyTokenError::RateLimitExceeded as u32,
+            ZupyTokenError::UserNotRegistered as u32,
+            ZupyTokenError::CompanyNotEmpty as u32,
+            ZupyTokenError::InvalidAccountDiscriminator as u32,
+            ZupyTokenError::CardFrozen as u32,
+            ZupyTokenError::AuthorityNotSigner as u32,
+            ZupyTokenError::AuthorityMismatch as u32,
+            ZupyTokenError::FeePayerNotSigner as u32,
+            ZupyTokenError::AccountNotTokenOwned as u32,
+            ZupyTokenError::TokenOwnerMismatch as u32,
+            ZupyTokenError::InvalidAssociatedTokenAccount as u32,
+            ZupyTokenError::InvalidCompressedProof as u32,
+            ZupyTokenError::InvalidInputCount as u32,
+            ZupyTokenError::ArithmeticOverflow as u32,
+            ZupyTokenError::UnknownInstruction as u32,
         ];
 
         for (i, &code) in all_codes.iter().enumerate() {
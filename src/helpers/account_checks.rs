@@ -2,6 +2,7 @@ use pinocchio::AccountView;
 use pinocchio::Address;
 use pinocchio::error::ProgramError;
 
+use crate::constants::ATA_PROGRAM_ID;
 use crate::error::ZupyTokenError;
 
 /// Assert that the account is a signer.
@@ -31,12 +32,91 @@ pub fn assert_key_eq(account: &AccountView, expected_key: &Address) -> Result<()
     Ok(())
 }
 
+/// Assert that a state account's stored Anchor discriminator matches `expected`.
+/// Every program-owned state read checks this after owner + size, so a
+/// foreign account of the right size fails with `InvalidAccountDiscriminator`.
+#[inline(always)]
+pub fn assert_discriminator(stored: &[u8; 8], expected: &[u8; 8]) -> Result<(), ProgramError> {
+    if stored != expected {
+        return Err(ZupyTokenError::InvalidAccountDiscriminator.into());
+    }
+    Ok(())
+}
+
+/// Assert that `ata` is the associated token account of (`wallet`, `mint`)
+/// under `token_program`: seeds `[wallet, token_program, mint]` on the ATA
+/// program. A client-provided `bump` is checked with `create_program_address`;
+/// without one the address is derived with `find_program_address` (~1.5K CU
+/// per bump tried). Returns `InvalidAssociatedTokenAccount` on mismatch.
+pub fn assert_associated_token_address(
+    ata: &AccountView,
+    wallet: &Address,
+    mint: &Address,
+    token_program: &Address,
+    bump: Option<u8>,
+) -> Result<(), ProgramError> {
+    let ata_program = Address::from(ATA_PROGRAM_ID);
+    let seeds: [&[u8]; 3] = [wallet.as_ref(), token_program.as_ref(), mint.as_ref()];
+    let expected = match bump {
+        Some(bump) => Address::create_program_address(
+            &[seeds[0], seeds[1], seeds[2], &[bump]],
+            &ata_program,
+        )
+        .map_err(|_| ZupyTokenError::InvalidAssociatedTokenAccount)?,
+        None => Address::find_program_address(&seeds, &ata_program).0,
+    };
+    if ata.address() != &expected {
+        return Err(ZupyTokenError::InvalidAssociatedTokenAccount.into());
+    }
+    Ok(())
+}
+
 /// Assert that the account is owned by the given program ID.
 /// Semantic alias for assert_owner with program-centric naming.
 pub fn assert_program_id(account: &AccountView, program_id: &Address) -> Result<(), ProgramError> {
     assert_owner(account, program_id)
 }
 
+/// Destructures an instruction's fixed account layout in one place.
+///
+/// Binds each slot by position (`_` skips one), fails with
+/// `NotEnoughAccountKeys` if the slice is shorter than the list, and ignores
+/// any extra trailing accounts. A slot can carry `signer(Err)` or
+/// `writable(Err)` to fail with that `ZupyTokenError` (logged as
+/// `zupy_err:{Err}:{slot}`) when the flag is missing. One slice-pattern length
+/// check replaces the per-index bounds checks of `&accounts[i]`.
+///
+/// ```ignore
+/// expect_accounts!(accounts, [
+///     transfer_authority,
+///     token_state_account,
+///     fee_payer: signer(FeePayerNotSigner),
+/// ]);
+/// ```
+macro_rules! expect_accounts {
+    (@signer $name:ident, $err:ident) => {
+        if !$name.is_signer() {
+            $crate::helpers::log::sol_log(concat!("zupy_err:", stringify!($err), ":", stringify!($name)));
+            return Err(pinocchio::error::ProgramError::from($crate::error::ZupyTokenError::$err));
+        }
+    };
+    (@writable $name:ident, $err:ident) => {
+        if !$name.is_writable() {
+            $crate::helpers::log::sol_log(concat!("zupy_err:", stringify!($err), ":", stringify!($name)));
+            return Err(pinocchio::error::ProgramError::from($crate::error::ZupyTokenError::$err));
+        }
+    };
+    ($accounts:expr, [$($name:tt $(: $flag:ident($err:ident))?),+ $(,)?]) => {
+        let [$($name,)+ ..] = $accounts else {
+            return Err(pinocchio::error::ProgramError::NotEnoughAccountKeys);
+        };
+        $($(
+            $crate::helpers::account_checks::expect_accounts!(@$flag $name, $err);
+        )?)+
+    };
+}
+pub(crate) use expect_accounts;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -157,6 +237,58 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    // ── assert_discriminator tests ──────────────────────────────────────
+
+    #[test]
+    fn test_assert_discriminator_ok() {
+        assert!(assert_discriminator(&[7u8; 8], &[7u8; 8]).is_ok());
+    }
+
+    #[test]
+    fn test_assert_discriminator_fails_when_mismatch() {
+        let mut stored = [7u8; 8];
+        stored[0] ^= 0xFF;
+        assert_eq!(
+            assert_discriminator(&stored, &[7u8; 8]).unwrap_err(),
+            ProgramError::Custom(ZupyTokenError::InvalidAccountDiscriminator as u32)
+        );
+    }
+
+    // ── assert_associated_token_address tests ───────────────────────────
+
+    fn ata_of(wallet: &Address, mint: &Address, token_program: &Address) -> (Address, u8) {
+        Address::find_program_address(
+            &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+            &Address::from(ATA_PROGRAM_ID),
+        )
+    }
+
+    #[test]
+    fn test_assert_associated_token_address_ok() {
+        let (wallet, mint, token_program) =
+            (Address::from([1u8; 32]), Address::from([2u8; 32]), Address::from([3u8; 32]));
+        let (ata, bump) = ata_of(&wallet, &mint, &token_program);
+        let mut buf = make_account_buf(ata.as_ref().try_into().unwrap(), [3u8; 32], false);
+        let account = view_from_buf(&mut buf);
+        assert!(assert_associated_token_address(&account, &wallet, &mint, &token_program, Some(bump)).is_ok());
+        assert!(assert_associated_token_address(&account, &wallet, &mint, &token_program, None).is_ok());
+    }
+
+    #[test]
+    fn test_assert_associated_token_address_fails_when_not_ata() {
+        let (wallet, mint, token_program) =
+            (Address::from([1u8; 32]), Address::from([2u8; 32]), Address::from([3u8; 32]));
+        let (_, bump) = ata_of(&wallet, &mint, &token_program);
+        let mut buf = make_account_buf([55u8; 32], [3u8; 32], false);
+        let account = view_from_buf(&mut buf);
+        for b in [Some(bump), None] {
+            assert_eq!(
+                assert_associated_token_address(&account, &wallet, &mint, &token_program, b).unwrap_err(),
+                ProgramError::Custom(ZupyTokenError::InvalidAssociatedTokenAccount as u32)
+            );
+        }
+    }
+
     #[test]
     fn test_assert_program_id_fails_when_wrong_program() {
         let mut buf = make_account_buf([1u8; 32], [77u8; 32], false);
@@ -165,4 +297,65 @@ mod tests {
         let result = assert_program_id(&account, &wrong_pid);
         assert!(result.is_err());
     }
+
+    // ── expect_accounts! tests ──────────────────────────────────────────
+
+    /// Layout: authority (signer) + skipped slot + target (writable).
+    fn extract(accounts: &[AccountView]) -> Result<[u8; 32], ProgramError> {
+        expect_accounts!(accounts, [
+            authority: signer(AuthorityNotSigner),
+            _,
+            target: writable(InvalidPoolAccount),
+        ]);
+        assert!(authority.is_signer());
+        Ok(target.address().as_ref().try_into().unwrap())
+    }
+
+    #[test]
+    fn test_expect_accounts_binds_by_position() {
+        let mut bufs = [
+            make_account_buf([1u8; 32], [0u8; 32], true),
+            make_account_buf([2u8; 32], [0u8; 32], false),
+            make_account_buf([3u8; 32], [0u8; 32], false),
+            make_account_buf([4u8; 32], [0u8; 32], false),
+        ];
+        let views: Vec<AccountView> = bufs.iter_mut().map(view_from_buf).collect();
+        // Trailing extra accounts are ignored
+        assert_eq!(extract(&views), Ok([3u8; 32]));
+        assert_eq!(extract(&views[..3]), Ok([3u8; 32]));
+    }
+
+    #[test]
+    fn test_expect_accounts_too_few() {
+        let mut bufs = [
+            make_account_buf([1u8; 32], [0u8; 32], true),
+            make_account_buf([2u8; 32], [0u8; 32], false),
+        ];
+        let views: Vec<AccountView> = bufs.iter_mut().map(view_from_buf).collect();
+        assert_eq!(extract(&views), Err(ProgramError::NotEnoughAccountKeys));
+        assert_eq!(extract(&[]), Err(ProgramError::NotEnoughAccountKeys));
+    }
+
+    #[test]
+    fn test_expect_accounts_flag_errors() {
+        let mut bufs = [
+            make_account_buf([1u8; 32], [0u8; 32], false),
+            make_account_buf([2u8; 32], [0u8; 32], false),
+            make_account_buf([3u8; 32], [0u8; 32], false),
+        ];
+        let views: Vec<AccountView> = bufs.iter_mut().map(view_from_buf).collect();
+        assert_eq!(
+            extract(&views),
+            Err(ProgramError::Custom(ZupyTokenError::AuthorityNotSigner as u32))
+        );
+
+        bufs[0] = make_account_buf([1u8; 32], [0u8; 32], true);
+        // SAFETY: buf was laid out as a RuntimeAccount by make_account_buf
+        unsafe { (*(bufs[2].as_mut_ptr() as *mut RuntimeAccount)).is_writable = 0 };
+        let views: Vec<AccountView> = bufs.iter_mut().map(view_from_buf).collect();
+        assert_eq!(
+            extract(&views),
+            Err(ProgramError::Custom(ZupyTokenError::InvalidPoolAccount as u32))
+        );
+    }
 }
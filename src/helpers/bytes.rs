@@ -0,0 +1,49 @@
+//! Fixed-size views into byte slices without `try_into().unwrap()`.
+//!
+//! `<[u8; N]>::try_from(slice).unwrap()` keeps `TryFromSliceError`'s `Debug`
+//! impl and `Result::unwrap`'s formatting path in the SBF binary, once per
+//! call site. These helpers take the bounds-checked window and reinterpret it,
+//! so the only failure left is the slice bounds check (same as before: the
+//! offsets are fixed by validated account or instruction layouts).
+
+use pinocchio::Address;
+
+/// `&data[offset..offset + N]` as an array reference.
+#[inline(always)]
+pub fn read_array<const N: usize>(data: &[u8], offset: usize) -> &[u8; N] {
+    let window = &data[offset..offset + N];
+    // SAFETY: `window` is exactly `N` bytes and `[u8; N]` has alignment 1.
+    unsafe { &*(window.as_ptr() as *const [u8; N]) }
+}
+
+/// The 32 key bytes of `address`.
+#[inline(always)]
+pub fn address_bytes(address: &Address) -> &[u8; 32] {
+    read_array(address.as_ref(), 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_array_window() {
+        let data: Vec<u8> = (0..16).collect();
+        assert_eq!(read_array::<4>(&data, 0), &[0, 1, 2, 3]);
+        assert_eq!(read_array::<8>(&data, 8), &[8, 9, 10, 11, 12, 13, 14, 15]);
+        assert_eq!(u16::from_le_bytes(*read_array(&data, 2)), 0x0302);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_read_array_out_of_bounds_panics() {
+        let data = [0u8; 8];
+        read_array::<4>(&data, 5);
+    }
+
+    #[test]
+    fn test_address_bytes() {
+        let address = Address::from([7u8; 32]);
+        assert_eq!(address_bytes(&address), &[7u8; 32]);
+    }
+}
@@ -0,0 +1,131 @@
+//! Optional CompanyState balance counters.
+//!
+//! `transfer_company_to_user`, `transfer_user_to_company`, `return_to_pool` and
+//! `burn_from_company_pda` accept the company's CompanyState PDA
+//! `[COMPANY_STATE_SEED, company_id]` (created by `initialize_company`) as a
+//! trailing optional writable account, before any UserState or SPL Memo
+//! program. It is recognised by owner, size and discriminator and stripped
+//! before the remaining accounts are forwarded. The counters are a sanity
+//! check, not an authority: updates saturate, and a read-only account or
+//! another company's state is skipped, so they never fail a transfer.
+
+use pinocchio::{AccountView, Address};
+
+use crate::state::company_state::{
+    CompanyState, CompanyStateMut, COMPANY_STATE_DISCRIMINATOR, COMPANY_STATE_SIZE,
+};
+
+/// Splits a trailing CompanyState account off `accounts`.
+pub fn split_company_state<'a>(
+    program_id: &Address,
+    accounts: &'a [AccountView],
+) -> (&'a [AccountView], Option<&'a AccountView>) {
+    match accounts.split_last() {
+        Some((last, rest)) if is_company_state(program_id, last) => (rest, Some(last)),
+        _ => (accounts, None),
+    }
+}
+
+#[inline(always)]
+fn is_company_state(program_id: &Address, account: &AccountView) -> bool {
+    account.owned_by(program_id)
+        && account.data_len() >= COMPANY_STATE_SIZE
+        && CompanyState::from_slice(unsafe { account.borrow_unchecked() }).discriminator()
+            == &COMPANY_STATE_DISCRIMINATOR
+}
+
+/// Writable counters of `company_id`, or `None` when `company_state` (from
+/// `split_company_state`) is absent, read-only or belongs to another company.
+/// CompanyStates only exist at the PDA of the id they store, so the stored id
+/// identifies the account.
+pub fn company_counters(
+    company_state: Option<&AccountView>,
+    company_id: u64,
+) -> Option<CompanyStateMut<'_>> {
+    let company_state = company_state.filter(|account| account.is_writable())?;
+    let state = CompanyState::from_slice(unsafe { company_state.borrow_unchecked() });
+    if state.company_id() != company_id {
+        return None;
+    }
+    Some(CompanyStateMut::from_slice(unsafe { company_state.borrow_unchecked_mut() }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::mem::size_of;
+    use pinocchio::account::{RuntimeAccount, NOT_BORROWED};
+
+    const PROGRAM_ID: [u8; 32] = [1u8; 32];
+    const COMPANY_ID: u64 = 42;
+
+    /// RuntimeAccount buffer holding a CompanyState for `company_id`.
+    fn make_company_state(owner: [u8; 32], company_id: u64, is_writable: bool) -> Vec<u64> {
+        let header_size = size_of::<RuntimeAccount>();
+        let mut buf = vec![0u64; (header_size + COMPANY_STATE_SIZE).div_ceil(8)];
+        let raw = buf.as_mut_ptr() as *mut RuntimeAccount;
+        unsafe {
+            (*raw).borrow_state = NOT_BORROWED;
+            (*raw).is_writable = is_writable as u8;
+            (*raw).address = Address::from([9u8; 32]);
+            (*raw).owner = Address::from(owner);
+            (*raw).data_len = COMPANY_STATE_SIZE as u64;
+        }
+        let data = unsafe {
+            let base = buf.as_mut_ptr() as *mut u8;
+            core::slice::from_raw_parts_mut(base.add(header_size), COMPANY_STATE_SIZE)
+        };
+        let mut state = CompanyStateMut::from_slice(data);
+        state.set_discriminator(&COMPANY_STATE_DISCRIMINATOR);
+        state.set_company_id(company_id);
+        buf
+    }
+
+    fn view(buf: &mut [u64]) -> AccountView {
+        unsafe { AccountView::new_unchecked(buf.as_mut_ptr() as *mut RuntimeAccount) }
+    }
+
+    #[test]
+    fn test_split_company_state() {
+        let program_id = Address::from(PROGRAM_ID);
+        let mut other = make_company_state([2u8; 32], COMPANY_ID, true);
+        let mut state = make_company_state(PROGRAM_ID, COMPANY_ID, true);
+
+        let accounts = [view(&mut other), view(&mut state)];
+        let (rest, company_state) = split_company_state(&program_id, &accounts);
+        assert_eq!(rest.len(), 1);
+        assert!(company_state.is_some());
+
+        // Foreign-owned account with the same layout is not stripped
+        let (rest, company_state) = split_company_state(&program_id, &accounts[..1]);
+        assert_eq!(rest.len(), 1);
+        assert!(company_state.is_none());
+    }
+
+    #[test]
+    fn test_counters_after_operation_sequence() {
+        let mut buf = make_company_state(PROGRAM_ID, COMPANY_ID, true);
+        let account = view(&mut buf);
+
+        company_counters(Some(&account), COMPANY_ID).unwrap().record_received(1_000);
+        company_counters(Some(&account), COMPANY_ID).unwrap().record_sent(250);
+        company_counters(Some(&account), COMPANY_ID).unwrap().record_burned(100);
+        company_counters(Some(&account), COMPANY_ID).unwrap().record_sent(50);
+
+        let state = CompanyState::from_slice(unsafe { account.borrow_unchecked() });
+        assert_eq!(state.total_received(), 1_000);
+        assert_eq!(state.total_sent(), 300);
+        assert_eq!(state.total_burned(), 100);
+    }
+
+    #[test]
+    fn test_skipped_without_usable_account() {
+        assert!(company_counters(None, COMPANY_ID).is_none());
+
+        let mut read_only = make_company_state(PROGRAM_ID, COMPANY_ID, false);
+        assert!(company_counters(Some(&view(&mut read_only)), COMPANY_ID).is_none());
+
+        let mut other_company = make_company_state(PROGRAM_ID, COMPANY_ID + 1, true);
+        assert!(company_counters(Some(&view(&mut other_company)), COMPANY_ID).is_none());
+    }
+}
@@ -9,6 +9,8 @@
 //!   cu=2064). 13 fixed accounts + Merkle tree output queue as remaining accounts.
 //! - [`cpi_decompress_to_spl`]: Compressed leaf → SPL ATA.
 //!   Uses Transfer2 (disc=101). Dispatches, accounts accepted, reaches business logic.
+//! - [`cpi_merge_decompress_to_spl`]: Up to [`MAX_MERGE_INPUTS`] compressed leaves
+//!   ([`MergeInputs`]) → SPL ATA, with the change re-compressed to the authority.
 //!
 //! ## Path B — Compressed → Compressed (ZK proof required)
 //!
@@ -57,7 +59,11 @@ use pinocchio::cpi::Signer;
 use pinocchio::error::ProgramError;
 use pinocchio::instruction::{InstructionAccount, InstructionView};
 
-use crate::constants::{LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, TOKEN_DECIMALS};
+use crate::constants::LIGHT_COMPRESSED_TOKEN_PROGRAM_ID;
+use crate::error::{zupy_err, ZupyTokenError};
+use crate::helpers::bytes::read_array;
+use crate::helpers::instruction_data::{parse_u32, parse_u64, parse_u8, Reader};
+use crate::helpers::math::checked_add_amount;
 
 // ── Discriminators ────────────────────────────────────────────────────────────
 /// Anchor 8-byte discriminator for `compress_spl_token_account` (Path A compress).
@@ -108,6 +114,310 @@ pub(crate) fn validate_v1_transfer_disc(cpi_data: &[u8]) -> Result<(), ProgramEr
     Ok(())
 }
 
+/// Locates `compress_or_decompress_amount` inside a V1 TRANSFER payload.
+///
+/// Walks the Borsh `CompressedTokenInstructionDataTransfer` (wrapped in the Anchor
+/// `inputs: Vec<u8>` argument) and returns `(offset, available)`, where `offset`
+/// points at the `Option<u64>` tag and `available` is the summed input leaf amounts
+/// minus the summed output (change) amounts. Input amounts are part of the leaf
+/// hashes, so Light rejects the CPI if they do not match the spent leaves.
+///
+/// Returns `InvalidInstructionData` on truncated data, a compress-mode payload,
+/// or outputs exceeding inputs.
+pub(crate) fn locate_v1_decompress_amount(cpi_data: &[u8]) -> Result<(usize, u64), ProgramError> {
+    let (mut o, total_in) = skip_v1_inputs(cpi_data)?;
+    // output_compressed_accounts: Vec<PackedTokenTransferOutputData>
+    let output_count = parse_u32(cpi_data, o)?;
+    o += 4;
+    let mut total_out: u64 = 0;
+    for _ in 0..output_count {
+        let amount = parse_u64(cpi_data, o + 32)?;
+        total_out = total_out.checked_add(amount).ok_or(ProgramError::InvalidInstructionData)?;
+        o = skip_v1_output(cpi_data, o)?;
+    }
+    // is_compress: bool — only decompress payloads are resolvable
+    if parse_u8(cpi_data, o)? != 0 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    o += 1;
+    let available = total_in.checked_sub(total_out).ok_or(ProgramError::InvalidInstructionData)?;
+    Ok((o, available))
+}
+
+/// Amount a V1 compressed→compressed TRANSFER moves away from `source_owner`:
+/// the summed output amounts whose owner is not `source_owner` (change outputs
+/// back to the source are excluded).
+///
+/// Returns `InvalidInstructionData` on truncated data.
+pub(crate) fn v1_transfer_amount(cpi_data: &[u8], source_owner: &[u8; 32]) -> Result<u64, ProgramError> {
+    let (mut o, _) = skip_v1_inputs(cpi_data)?;
+    let output_count = parse_u32(cpi_data, o)?;
+    o += 4;
+    let mut moved: u64 = 0;
+    for _ in 0..output_count {
+        let owner = cpi_data.get(o..o + 32).ok_or(ProgramError::InvalidInstructionData)?;
+        let amount = parse_u64(cpi_data, o + 32)?;
+        if owner != source_owner {
+            moved = moved.checked_add(amount).ok_or(ProgramError::InvalidInstructionData)?;
+        }
+        o = skip_v1_output(cpi_data, o)?;
+    }
+    Ok(moved)
+}
+
+/// Walks a V1 TRANSFER payload up to `output_compressed_accounts`, returning
+/// `(offset, total_in)` with `total_in` the summed input leaf amounts.
+fn skip_v1_inputs(cpi_data: &[u8]) -> Result<(usize, u64), ProgramError> {
+    // [0..8] disc, [8..12] inputs Vec<u8> length, struct follows
+    let mut o = 12;
+    // proof: Option<CompressedProof> (a: 32, b: 64, c: 32)
+    if parse_u8(cpi_data, o)? == 1 {
+        o += 128;
+    }
+    o += 1;
+    // mint: Pubkey
+    o += 32;
+    // delegated_transfer: Option<{ owner: Pubkey, delegate_change_account_index: Option<u8> }>
+    if parse_u8(cpi_data, o)? == 1 {
+        o += 1 + 32;
+        o = skip_option(cpi_data, o, 1)?;
+    } else {
+        o += 1;
+    }
+    // input_token_data_with_context: Vec<InputTokenDataWithContext>
+    let input_count = parse_u32(cpi_data, o)?;
+    o += 4;
+    let mut total_in: u64 = 0;
+    for _ in 0..input_count {
+        let amount = parse_u64(cpi_data, o)?;
+        total_in = total_in.checked_add(amount).ok_or(ProgramError::InvalidInstructionData)?;
+        o = skip_option(cpi_data, o + 8, 1)?;   // delegate_index: Option<u8>
+        o += 7 + 2;                             // merkle_context (u8, u8, u32, bool) + root_index
+        o = skip_option(cpi_data, o, 8)?;       // lamports: Option<u64>
+        o = skip_option_bytes(cpi_data, o)?;    // tlv: Option<Vec<u8>>
+    }
+    Ok((o, total_in))
+}
+
+/// Skips one `PackedTokenTransferOutputData` (owner, amount, lamports, tree index, tlv).
+#[inline(always)]
+fn skip_v1_output(cpi_data: &[u8], offset: usize) -> Result<usize, ProgramError> {
+    let o = skip_option(cpi_data, offset + 40, 8)?;  // lamports: Option<u64>
+    skip_option_bytes(cpi_data, o + 1)                // merkle_tree_index, tlv: Option<Vec<u8>>
+}
+
+/// Skips a Borsh `Option<T>` with a fixed-size `T` of `size` bytes.
+#[inline(always)]
+fn skip_option(data: &[u8], offset: usize, size: usize) -> Result<usize, ProgramError> {
+    match parse_u8(data, offset)? {
+        0 => Ok(offset + 1),
+        _ => Ok(offset + 1 + size),
+    }
+}
+
+/// Skips a Borsh `Option<Vec<u8>>`.
+#[inline(always)]
+fn skip_option_bytes(data: &[u8], offset: usize) -> Result<usize, ProgramError> {
+    match parse_u8(data, offset)? {
+        0 => Ok(offset + 1),
+        _ => Ok(offset + 5 + parse_u32(data, offset + 1)? as usize),
+    }
+}
+
+// ── V1 Proof Structure ────────────────────────────────────────────────────────
+
+/// Root history length of a V1 state Merkle tree; `root_index` must fall inside it.
+const V1_STATE_TREE_ROOT_HISTORY: u16 = 2400;
+
+/// Borrowed Groth16 `CompressedProof` (a: G1, b: G2, c: G1, all compressed).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CompressedProof<'a> {
+    pub a: &'a [u8; 32],
+    pub b: &'a [u8; 64],
+    pub c: &'a [u8; 32],
+}
+
+/// Structural summary of a V1 TRANSFER payload, borrowed from the CPI data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct V1ProofLayout<'a> {
+    pub proof: Option<CompressedProof<'a>>,
+    pub input_count: u32,
+    pub output_count: u32,
+}
+
+/// Parses and structurally validates a V1 TRANSFER payload before it is
+/// forwarded to cToken, so malformed proofs fail here instead of deep inside
+/// Light after a full CPI.
+///
+/// Zero-copy and allocation-free. Checks:
+/// - `proof` tag is 0 or 1, and a Some proof carries a (32), b (64), c (32)
+/// - at least one input leaf
+/// - every input's Merkle tree / queue index and every output's tree index is
+///   below `account_count`
+/// - every input `root_index` is inside the V1 root history
+/// - the walked inputs and outputs fit inside the declared `inputs` length
+///
+/// Packed indices are relative to the accounts cToken receives after its fixed
+/// accounts. The client-assembled list is not split here, so they are bounded by
+/// the full forwarded `account_count`: a necessary condition that does not depend
+/// on the V1 fixed-account layout.
+///
+/// Returns `InvalidCompressedProof` on any violation, including truncation.
+pub fn parse_v1_proof_layout(
+    cpi_data: &[u8],
+    account_count: usize,
+) -> Result<V1ProofLayout<'_>, ProgramError> {
+    walk_v1_proof_layout(cpi_data, account_count)
+        .ok_or_else(|| zupy_err!(InvalidCompressedProof, "v1_proof"))
+}
+
+fn walk_v1_proof_layout(d: &[u8], account_count: usize) -> Option<V1ProofLayout<'_>> {
+    // [0..8] disc, [8..12] inputs Vec<u8> length, struct follows
+    let declared_end = 12usize.checked_add(u32::from_le_bytes(d.get(8..12)?.try_into().ok()?) as usize)?;
+    let mut o = 12;
+    let proof = match *d.get(o)? {
+        0 => {
+            o += 1;
+            None
+        }
+        1 => {
+            let proof = CompressedProof {
+                a: d.get(o + 1..o + 33)?.try_into().ok()?,
+                b: d.get(o + 33..o + 97)?.try_into().ok()?,
+                c: d.get(o + 97..o + 129)?.try_into().ok()?,
+            };
+            o += 129;
+            Some(proof)
+        }
+        _ => return None,
+    };
+    // mint: Pubkey
+    o += 32;
+    // delegated_transfer: Option<{ owner: Pubkey, delegate_change_account_index: Option<u8> }>
+    o = match *d.get(o)? {
+        0 => o + 1,
+        1 => skip_option(d, o + 33, 1).ok()?,
+        _ => return None,
+    };
+    let input_count = parse_u32(d, o).ok()?;
+    o += 4;
+    if input_count == 0 {
+        return None;
+    }
+    for _ in 0..input_count {
+        o = skip_option(d, o + 8, 1).ok()?;     // amount, delegate_index: Option<u8>
+        // merkle_context (tree idx, queue idx, leaf_index u32, bool) + root_index
+        let ctx = d.get(o..o + 9)?;
+        if ctx[0] as usize >= account_count || ctx[1] as usize >= account_count {
+            return None;
+        }
+        if u16::from_le_bytes([ctx[7], ctx[8]]) >= V1_STATE_TREE_ROOT_HISTORY {
+            return None;
+        }
+        o = skip_option(d, o + 9, 8).ok()?;     // lamports: Option<u64>
+        o = skip_option_bytes(d, o).ok()?;      // tlv: Option<Vec<u8>>
+    }
+    let output_count = parse_u32(d, o).ok()?;
+    o += 4;
+    for _ in 0..output_count {
+        let tree_index = skip_option(d, o + 40, 8).ok()?;  // owner, amount, lamports
+        if *d.get(tree_index)? as usize >= account_count {
+            return None;
+        }
+        o = skip_option_bytes(d, tree_index + 1).ok()?;
+    }
+    if o > declared_end || declared_end > d.len() {
+        return None;
+    }
+    Some(V1ProofLayout { proof, input_count, output_count })
+}
+
+// ── Multi-Input Merge ─────────────────────────────────────────────────────────
+
+/// Most compressed input leaves a merged return / withdraw may spend (the largest
+/// Light inclusion-proof batch).
+pub const MAX_MERGE_INPUTS: usize = 8;
+
+/// Serialized [`MergeInputs`] entry: amount (u64) + merkle_tree_index (u8) +
+/// queue_index (u8) + leaf_index (u32) + prove_by_index (bool) + root_index (u16).
+pub const MERGE_INPUT_LEN: usize = 17;
+
+/// Packed accounts ahead of the Light tree accounts in a Transfer2 decompress:
+/// mint, destination, authority, spl_interface_pda, token program, system program.
+const DECOMPRESS_PACKED_FIXED: u8 = 6;
+
+/// Packed index of the authority (source PDA) in a Transfer2 decompress.
+const DECOMPRESS_AUTHORITY_INDEX: u8 = 2;
+
+/// Token data hashing version of the leaves being spent (`TokenDataVersion::ShaFlat`).
+const TOKEN_DATA_VERSION: u8 = 3;
+
+/// Compressed input leaves for a merged decompress, borrowed from instruction data.
+///
+/// Wire format: `count (u8) + output_queue_index (u8) + count × entry +
+/// proof: Option<[u8; 128]>`. Tree, queue and output queue indices are relative
+/// to the Light accounts forwarded after the instruction's fixed accounts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MergeInputs<'a> {
+    entries: &'a [u8],
+    pub output_queue_index: u8,
+    pub proof: Option<&'a [u8; 128]>,
+}
+
+impl<'a> MergeInputs<'a> {
+    /// Reads the merge section. A zero count or more than [`MAX_MERGE_INPUTS`]
+    /// fails with `InvalidInputCount`; truncation with `InvalidInstructionData`.
+    pub fn parse(reader: &mut Reader<'a>) -> Result<Self, ProgramError> {
+        let count = reader.read_u8()? as usize;
+        if count == 0 || count > MAX_MERGE_INPUTS {
+            return Err(ZupyTokenError::InvalidInputCount.into());
+        }
+        let output_queue_index = reader.read_u8()?;
+        let entries = reader.read_slice(count * MERGE_INPUT_LEN)?;
+        let proof = match reader.read_u8()? {
+            0 => None,
+            1 => Some(reader.read_bytes::<128>()?),
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+        Ok(Self { entries, output_queue_index, proof })
+    }
+
+    /// Number of input leaves.
+    pub fn count(&self) -> usize {
+        self.entries.len() / MERGE_INPUT_LEN
+    }
+
+    #[inline(always)]
+    fn entry(&self, i: usize) -> &'a [u8] {
+        &self.entries[i * MERGE_INPUT_LEN..(i + 1) * MERGE_INPUT_LEN]
+    }
+
+    /// Summed input amounts; `ArithmeticOverflow` on overflow.
+    pub fn total(&self) -> Result<u64, ProgramError> {
+        let mut total: u64 = 0;
+        for i in 0..self.count() {
+            let amount = u64::from_le_bytes(*read_array::<8>(self.entry(i), 0));
+            total = checked_add_amount(total, amount)?;
+        }
+        Ok(total)
+    }
+
+    /// Every input's tree / queue index and the output queue must address one of
+    /// the `account_count` forwarded Light accounts; `NotEnoughAccountKeys` otherwise.
+    pub fn validate_accounts(&self, account_count: usize) -> Result<(), ProgramError> {
+        if self.output_queue_index as usize >= account_count {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        for i in 0..self.count() {
+            let e = self.entry(i);
+            if e[8] as usize >= account_count || e[9] as usize >= account_count {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            }
+        }
+        Ok(())
+    }
+}
+
 // ── Pure Data Builders (unit-testable) ────────────────────────────────────────
 
 /// Builds the 42-byte Borsh-encoded `compress_spl_token_account` instruction data
@@ -186,7 +496,7 @@ pub(crate) fn build_compress_with_remaining_data(owner: &[u8; 32], remaining: u6
 /// [8]     cpi_context = None (0)
 /// [9]     compressions = Some (1)
 /// [10..13] vec len = 2 (u32 LE)
-/// [14..29] Compression 0: decompress_spl(amount, mint=0, recipient=1, pool=3, idx=0, bump, dec=6)
+/// [14..29] Compression 0: decompress_spl(amount, mint=0, recipient=1, pool=3, idx=0, bump, decimals)
 /// [30..45] Compression 1: compress(amount, mint=0, source=2, auth=2)
 /// [46]    proof = None (0)
 /// [47..50] in_token_data len = 0 (u32 LE)
@@ -194,7 +504,7 @@ pub(crate) fn build_compress_with_remaining_data(owner: &[u8; 32], remaining: u6
 /// [55..58] in/out lamports/tlv = None (all zeros)
 /// ```
 #[inline]
-pub(crate) fn build_decompress_to_spl_data(amount: u64, spl_bump: u8) -> [u8; 59] {
+pub(crate) fn build_decompress_to_spl_data(amount: u64, spl_bump: u8, decimals: u8) -> [u8; 59] {
     let mut d = [0u8; 59];
     let ab = amount.to_le_bytes();
 
@@ -221,7 +531,7 @@ pub(crate) fn build_decompress_to_spl_data(amount: u64, spl_bump: u8) -> [u8; 59
     d[26] = 3; // pool_account_index = 3 (packed[3] = spl_interface_pda)
     // d[27] = pool_index = 0
     d[28] = spl_bump; // spl_interface_pda bump
-    d[29] = TOKEN_DECIMALS; // decimals = 6
+    d[29] = decimals; // mint decimals (token_state.decimals())
     // ── Compression 1: compress (spends the authority's compressed account) ─────
     d[30] = 0; // mode: CompressionMode::Compress = 0
     d[31..39].copy_from_slice(&ab); // amount (u64 LE)
@@ -244,6 +554,83 @@ pub(crate) fn build_decompress_to_spl_data(amount: u64, spl_bump: u8) -> [u8; 59
     d
 }
 
+/// Builds the Transfer2 data for a **merged decompress**: spends every leaf in
+/// `inputs` (owned by the authority), releases `amount` SPL to the destination,
+/// and re-compresses `change` (when non-zero) to the authority.
+///
+/// Same packed layout as [`build_decompress_to_spl_data`]; the Light tree accounts
+/// follow at packed[6+], so input indices are offset by 6.
+///
+/// ```text
+/// [0]      disc = 101
+/// [1..5]   header flags = 0
+/// [5]      output_queue = 6 + inputs.output_queue_index
+/// [6..8]   max_top_up = u16::MAX
+/// [8]      cpi_context = None
+/// [9..30]  compressions = Some([decompress_spl(amount, mint=0, recipient=1, pool=3, bump, decimals)])
+/// [30..]   proof: Option<CompressedProof>
+///          in_token_data: Vec<MultiInputTokenDataWithContext> (22 bytes each)
+///          out_token_data: Vec<MultiTokenTransferOutputData> (0 or 1 × 13 bytes)
+///          in/out lamports/tlv = None
+/// ```
+pub(crate) fn build_merge_decompress_to_spl_data(
+    inputs: &MergeInputs,
+    amount: u64,
+    change: u64,
+    spl_bump: u8,
+    decimals: u8,
+) -> Vec<u8> {
+    let proof_len = if inputs.proof.is_some() { 129 } else { 1 };
+    let outputs = usize::from(change > 0);
+    let mut d = Vec::with_capacity(30 + proof_len + 4 + inputs.count() * 22 + 4 + outputs * 13 + 4);
+
+    // ── Header ───────────────────────────────────────────────────────────────
+    d.push(TRANSFER2_DISC);
+    d.extend_from_slice(&[0, 0, 0, 0]);
+    d.push(DECOMPRESS_PACKED_FIXED + inputs.output_queue_index);
+    d.extend_from_slice(&u16::MAX.to_le_bytes());
+    d.push(0); // cpi_context: None
+    // ── compressions: Some(vec![decompress_spl]) ─────────────────────────────
+    d.push(1);
+    d.extend_from_slice(&1u32.to_le_bytes());
+    d.push(1); // mode: CompressionMode::Decompress
+    d.extend_from_slice(&amount.to_le_bytes());
+    d.extend_from_slice(&[0, 1, 0, 3, 0]); // mint, recipient, authority (unused), pool, pool_index
+    d.push(spl_bump);
+    d.push(decimals);
+    // ── proof ────────────────────────────────────────────────────────────────
+    match inputs.proof {
+        Some(proof) => {
+            d.push(1);
+            d.extend_from_slice(proof);
+        }
+        None => d.push(0),
+    }
+    // ── in_token_data ────────────────────────────────────────────────────────
+    d.extend_from_slice(&(inputs.count() as u32).to_le_bytes());
+    for i in 0..inputs.count() {
+        let e = inputs.entry(i);
+        d.push(DECOMPRESS_AUTHORITY_INDEX); // owner
+        d.extend_from_slice(&e[0..8]);      // amount
+        d.extend_from_slice(&[0, 0, 0]);    // has_delegate, delegate, mint
+        d.push(TOKEN_DATA_VERSION);
+        d.push(DECOMPRESS_PACKED_FIXED + e[8]); // merkle_tree_pubkey_index
+        d.push(DECOMPRESS_PACKED_FIXED + e[9]); // queue_pubkey_index
+        d.extend_from_slice(&e[10..17]);    // leaf_index, prove_by_index, root_index
+    }
+    // ── out_token_data: change back to the authority ─────────────────────────
+    d.extend_from_slice(&(outputs as u32).to_le_bytes());
+    if change > 0 {
+        d.push(DECOMPRESS_AUTHORITY_INDEX);
+        d.extend_from_slice(&change.to_le_bytes());
+        d.extend_from_slice(&[0, 0, 0]);
+        d.push(TOKEN_DATA_VERSION);
+    }
+    // ── in/out lamports/tlv: None ────────────────────────────────────────────
+    d.extend_from_slice(&[0, 0, 0, 0]);
+    d
+}
+
 // ── Path A: cpi_compress_from_spl ─────────────────────────────────────────────
 
 /// CPI: Compress tokens from a source SPL ATA into a compressed Merkle-tree leaf.
@@ -402,11 +789,88 @@ pub fn cpi_decompress_to_spl<'a>(
     system_program: &'a AccountView,
     amount: u64,
     spl_interface_pda_bump: u8,
+    decimals: u8,
     remaining_accounts: &'a [AccountView],
     signers: &[Signer],
 ) -> Result<(), ProgramError> {
-    let data = build_decompress_to_spl_data(amount, spl_interface_pda_bump);
+    let data = build_decompress_to_spl_data(amount, spl_interface_pda_bump, decimals);
+    invoke_decompress_to_spl(
+        &data,
+        compressed_token_authority,
+        payer,
+        mint,
+        destination_spl,
+        authority,
+        spl_interface_pda,
+        spl_token_program,
+        system_program,
+        remaining_accounts,
+        signers,
+    )
+}
 
+/// CPI: Merged decompress of several compressed leaves into a destination SPL ATA.
+///
+/// Spends every leaf in `inputs` (owned by `authority`), releases `amount` SPL from
+/// `spl_interface_pda` to `destination_spl`, and re-compresses `total - amount` to
+/// `authority` in the output queue. Same accounts as [`cpi_decompress_to_spl`];
+/// `remaining_accounts` are the Light trees / queues the input indices address.
+///
+/// Callers check `amount <= inputs.total()` and `inputs.validate_accounts` first.
+#[inline(always)]
+#[allow(clippy::too_many_arguments)]
+pub fn cpi_merge_decompress_to_spl<'a>(
+    compressed_token_authority: &'a AccountView,
+    payer: &'a AccountView,
+    mint: &'a AccountView,
+    destination_spl: &'a AccountView,
+    authority: &'a AccountView,
+    spl_interface_pda: &'a AccountView,
+    spl_token_program: &'a AccountView,
+    system_program: &'a AccountView,
+    inputs: &MergeInputs,
+    amount: u64,
+    spl_interface_pda_bump: u8,
+    decimals: u8,
+    remaining_accounts: &'a [AccountView],
+    signers: &[Signer],
+) -> Result<(), ProgramError> {
+    let change = inputs
+        .total()?
+        .checked_sub(amount)
+        .ok_or(ZupyTokenError::InsufficientBalance)?;
+    let data = build_merge_decompress_to_spl_data(inputs, amount, change, spl_interface_pda_bump, decimals);
+    invoke_decompress_to_spl(
+        &data,
+        compressed_token_authority,
+        payer,
+        mint,
+        destination_spl,
+        authority,
+        spl_interface_pda,
+        spl_token_program,
+        system_program,
+        remaining_accounts,
+        signers,
+    )
+}
+
+/// Invokes Transfer2 with the decompress account layout of [`cpi_decompress_to_spl`].
+#[inline(always)]
+#[allow(clippy::too_many_arguments)]
+fn invoke_decompress_to_spl<'a>(
+    data: &[u8],
+    compressed_token_authority: &'a AccountView,
+    payer: &'a AccountView,
+    mint: &'a AccountView,
+    destination_spl: &'a AccountView,
+    authority: &'a AccountView,
+    spl_interface_pda: &'a AccountView,
+    spl_token_program: &'a AccountView,
+    system_program: &'a AccountView,
+    remaining_accounts: &'a [AccountView],
+    signers: &[Signer],
+) -> Result<(), ProgramError> {
     let prog_id: Address = LIGHT_COMPRESSED_TOKEN_PROGRAM_ID.into();
 
     // Build fixed account metas; append remaining Light system accounts (Merkle tree, etc.)
@@ -432,7 +896,7 @@ pub fn cpi_decompress_to_spl<'a>(
     let instruction = InstructionView {
         program_id: &prog_id,
         accounts: &account_metas,
-        data: &data,
+        data,
     };
 
     // Build account view slice matching instruction.accounts 1:1 (no program account).
@@ -648,12 +1112,44 @@ pub fn derive_spl_interface_pda(mint_key: &[u8; 32]) -> (Address, u8) {
     Address::find_program_address(&[b"pool", mint_key.as_ref()], &light_ctoken)
 }
 
+/// `spl_interface_bump` value meaning "not sent": the bump is derived on-chain.
+pub const SPL_INTERFACE_BUMP_DERIVE: u8 = 0xFF;
+
+/// Validate `spl_interface_pda` against `[b"pool", mint_key, bump]` on the Light
+/// cToken program and return the bump for the CPI.
+///
+/// A client-sent `bump` costs one `create_program_address`; a wrong one yields a
+/// different (or no) address and fails. [`SPL_INTERFACE_BUMP_DERIVE`] falls back
+/// to [`derive_spl_interface_pda`] (~1.5K CU per bump tried). `InvalidPDA` on mismatch.
+pub fn validate_spl_interface_pda(
+    spl_interface_pda: &Address,
+    mint_key: &[u8; 32],
+    bump: u8,
+) -> Result<u8, ProgramError> {
+    if bump == SPL_INTERFACE_BUMP_DERIVE {
+        let (expected, bump) = derive_spl_interface_pda(mint_key);
+        if spl_interface_pda != &expected {
+            return Err(ZupyTokenError::InvalidPDA.into());
+        }
+        return Ok(bump);
+    }
+    let light_ctoken: Address = LIGHT_COMPRESSED_TOKEN_PROGRAM_ID.into();
+    let expected =
+        Address::create_program_address(&[b"pool", mint_key.as_ref(), &[bump]], &light_ctoken)
+            .map_err(|_| ZupyTokenError::InvalidPDA)?;
+    if spl_interface_pda != &expected {
+        return Err(ZupyTokenError::InvalidPDA.into());
+    }
+    Ok(bump)
+}
+
 
 // ── Unit Tests ─────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::constants::TOKEN_DECIMALS;
 
     // ── build_compress_all_data ──────────────────────────────────────────────
 
@@ -796,7 +1292,6 @@ mod tests {
         let mint_key = [0x42u8; 32]; // arbitrary
         let (_, bump) = derive_spl_interface_pda(&mint_key);
         assert!(bump >= 1, "PDA bump must be >= 1 (canonical bump always < 255)");
-        assert!(bump <= 255, "PDA bump must fit in u8");
     }
 
     #[test]
@@ -825,6 +1320,46 @@ mod tests {
         assert_ne!(pda_bytes, &[0u8; 32], "spl_interface PDA must not be all-zeros");
     }
 
+    // ── validate_spl_interface_pda ────────────────────────────────────────
+
+    fn invalid_pda() -> ProgramError {
+        ZupyTokenError::InvalidPDA.into()
+    }
+
+    #[test]
+    fn test_validate_spl_interface_pda_with_client_bump() {
+        let mint_key = [0x42u8; 32];
+        let (pda, bump) = derive_spl_interface_pda(&mint_key);
+        assert_eq!(validate_spl_interface_pda(&pda, &mint_key, bump), Ok(bump));
+    }
+
+    #[test]
+    fn test_validate_spl_interface_pda_derive_sentinel() {
+        let mint_key = [0x42u8; 32];
+        let (pda, bump) = derive_spl_interface_pda(&mint_key);
+        assert_eq!(validate_spl_interface_pda(&pda, &mint_key, SPL_INTERFACE_BUMP_DERIVE), Ok(bump));
+        assert_eq!(
+            validate_spl_interface_pda(&Address::from([0xCC; 32]), &mint_key, SPL_INTERFACE_BUMP_DERIVE),
+            Err(invalid_pda())
+        );
+    }
+
+    #[test]
+    fn test_validate_spl_interface_pda_wrong_bump_rejected() {
+        let mint_key = [0x42u8; 32];
+        let (pda, bump) = derive_spl_interface_pda(&mint_key);
+        // Every other bump either is on-curve or derives a different address
+        for wrong in (0..SPL_INTERFACE_BUMP_DERIVE).filter(|b| *b != bump) {
+            assert_eq!(validate_spl_interface_pda(&pda, &mint_key, wrong), Err(invalid_pda()));
+        }
+    }
+
+    #[test]
+    fn test_validate_spl_interface_pda_wrong_mint_rejected() {
+        let (pda, bump) = derive_spl_interface_pda(&[0x42u8; 32]);
+        assert_eq!(validate_spl_interface_pda(&pda, &[0x43u8; 32], bump), Err(invalid_pda()));
+    }
+
     // ── build_compressed_transfer_data ───────────────────────────────────
 
     #[test]
@@ -953,48 +1488,48 @@ mod tests {
     /// Discriminator confirmed correct from light-token-pinocchio 0.22.0 SDK source
     /// (transfer_to_spl.rs: `TRANSFER2_DISCRIMINATOR: u8 = 101`).
     fn test_build_decompress_to_spl_data_discriminator_is_101() {
-        let data = build_decompress_to_spl_data(1_000_000, 255);
+        let data = build_decompress_to_spl_data(1_000_000, 255, TOKEN_DECIMALS);
         assert_eq!(data[0], TRANSFER2_DISC, "first byte must be Transfer2 discriminator (101)");
         assert_eq!(data[0], 101);
     }
 
     #[test]
     fn test_build_decompress_to_spl_data_total_length_is_59() {
-        let data = build_decompress_to_spl_data(0, 0);
+        let data = build_decompress_to_spl_data(0, 0, TOKEN_DECIMALS);
         assert_eq!(data.len(), 59, "instruction data must be exactly 59 bytes");
     }
 
     #[test]
     fn test_build_decompress_to_spl_data_max_top_up_is_u16_max() {
-        let data = build_decompress_to_spl_data(0, 0);
+        let data = build_decompress_to_spl_data(0, 0, TOKEN_DECIMALS);
         assert_eq!(data[6], 0xFF, "max_top_up low byte");
         assert_eq!(data[7], 0xFF, "max_top_up high byte (u16::MAX)");
     }
 
     #[test]
     fn test_build_decompress_to_spl_data_compressions_is_some_with_two_entries() {
-        let data = build_decompress_to_spl_data(0, 0);
+        let data = build_decompress_to_spl_data(0, 0, TOKEN_DECIMALS);
         assert_eq!(data[9], 1, "compressions: Some = 1");
         assert_eq!(&data[10..14], &[2, 0, 0, 0], "vec len = 2 (u32 LE)");
     }
 
     #[test]
     fn test_build_decompress_to_spl_data_compression0_mode_is_decompress() {
-        let data = build_decompress_to_spl_data(0, 0);
+        let data = build_decompress_to_spl_data(0, 0, TOKEN_DECIMALS);
         assert_eq!(data[14], 1, "Compression 0 mode: Decompress = 1");
     }
 
     #[test]
     fn test_build_decompress_to_spl_data_compression0_amount_encoded_correctly() {
         let amount = 42_000_000u64; // 42 ZUPY
-        let data = build_decompress_to_spl_data(amount, 0);
+        let data = build_decompress_to_spl_data(amount, 0, TOKEN_DECIMALS);
         let encoded = u64::from_le_bytes(data[15..23].try_into().unwrap());
         assert_eq!(encoded, amount, "Compression 0 amount (u64 LE) at [15..23]");
     }
 
     #[test]
     fn test_build_decompress_to_spl_data_compression0_account_indices() {
-        let data = build_decompress_to_spl_data(0, 0);
+        let data = build_decompress_to_spl_data(0, 0, TOKEN_DECIMALS);
         assert_eq!(data[23], 0, "mint index = packed[0]");
         assert_eq!(data[24], 1, "source_or_recipient = packed[1] (pool_ata, SPL destination)");
         assert_eq!(data[25], 0, "authority = 0 (UNUSED for decompress_spl)");
@@ -1005,33 +1540,35 @@ mod tests {
     #[test]
     fn test_build_decompress_to_spl_data_compression0_bump_stored_correctly() {
         let bump: u8 = 251;
-        let data = build_decompress_to_spl_data(0, bump);
+        let data = build_decompress_to_spl_data(0, bump, TOKEN_DECIMALS);
         assert_eq!(data[28], bump, "spl_interface_pda bump stored at [28]");
     }
 
     #[test]
     fn test_build_decompress_to_spl_data_compression0_decimals_is_token_decimals() {
-        let data = build_decompress_to_spl_data(0, 0);
+        let data = build_decompress_to_spl_data(0, 0, TOKEN_DECIMALS);
         assert_eq!(data[29], TOKEN_DECIMALS, "Compression 0 decimals = TOKEN_DECIMALS (6)");
+        let data = build_decompress_to_spl_data(0, 0, 9);
+        assert_eq!(data[29], 9, "Compression 0 decimals follow the stored value");
     }
 
     #[test]
     fn test_build_decompress_to_spl_data_compression1_mode_is_compress() {
-        let data = build_decompress_to_spl_data(0, 0);
+        let data = build_decompress_to_spl_data(0, 0, TOKEN_DECIMALS);
         assert_eq!(data[30], 0, "Compression 1 mode: Compress = 0");
     }
 
     #[test]
     fn test_build_decompress_to_spl_data_compression1_amount_equals_compression0_amount() {
         let amount = 5_000_000_000u64; // 5,000 ZUPY
-        let data = build_decompress_to_spl_data(amount, 0);
+        let data = build_decompress_to_spl_data(amount, 0, TOKEN_DECIMALS);
         let c1_amount = u64::from_le_bytes(data[31..39].try_into().unwrap());
         assert_eq!(c1_amount, amount, "Compression 1 amount must equal Compression 0 amount");
     }
 
     #[test]
     fn test_build_decompress_to_spl_data_compression1_account_indices() {
-        let data = build_decompress_to_spl_data(0, 0);
+        let data = build_decompress_to_spl_data(0, 0, TOKEN_DECIMALS);
         assert_eq!(data[39], 0, "Compression 1 mint index = packed[0]");
         assert_eq!(data[40], 2, "source_or_recipient = packed[2] (source PDA, compressed source)");
         assert_eq!(data[41], 2, "authority = packed[2] (source PDA, signer)");
@@ -1043,7 +1580,7 @@ mod tests {
 
     #[test]
     fn test_build_decompress_to_spl_data_trailing_fields_are_zero_or_none() {
-        let data = build_decompress_to_spl_data(0, 0);
+        let data = build_decompress_to_spl_data(0, 0, TOKEN_DECIMALS);
         assert_eq!(data[46], 0, "proof: None");
         assert_eq!(&data[47..51], &[0, 0, 0, 0], "in_token_data: empty vec");
         assert_eq!(&data[51..55], &[0, 0, 0, 0], "out_token_data: empty vec");
@@ -1052,7 +1589,7 @@ mod tests {
 
     #[test]
     fn test_build_decompress_to_spl_data_zero_amount_has_zero_amount_fields() {
-        let data = build_decompress_to_spl_data(0, 0);
+        let data = build_decompress_to_spl_data(0, 0, TOKEN_DECIMALS);
         assert_eq!(&data[15..23], &[0u8; 8], "amount=0 in Compression 0");
         assert_eq!(&data[31..39], &[0u8; 8], "amount=0 in Compression 1");
     }
@@ -1060,7 +1597,7 @@ mod tests {
     #[test]
     fn test_build_decompress_to_spl_data_max_amount_u64() {
         let amount = u64::MAX;
-        let data = build_decompress_to_spl_data(amount, 0);
+        let data = build_decompress_to_spl_data(amount, 0, TOKEN_DECIMALS);
         let c0 = u64::from_le_bytes(data[15..23].try_into().unwrap());
         let c1 = u64::from_le_bytes(data[31..39].try_into().unwrap());
         assert_eq!(c0, u64::MAX, "Compression 0 handles u64::MAX");
@@ -1069,7 +1606,7 @@ mod tests {
 
     #[test]
     fn test_build_decompress_to_spl_data_compression0_mode_is_decompress_compression1_is_compress() {
-        let data = build_decompress_to_spl_data(1_000, 0);
+        let data = build_decompress_to_spl_data(1_000, 0, TOKEN_DECIMALS);
         assert_eq!(data[14], 1, "C0 mode: Decompress = 1");
         assert_eq!(data[30], 0, "C1 mode: Compress = 0");
     }
@@ -1167,4 +1704,304 @@ mod tests {
             "Transfer2 single-byte disc must be rejected (V1 expects 8-byte Anchor disc)"
         );
     }
+
+    // ── locate_v1_decompress_amount ─────────────────────────────────────────
+
+    /// Builds a V1 decompress payload: proof, 2 inputs (one with tlv), 1 change output.
+    fn build_v1_decompress_payload(inputs: &[u64], change: Option<u64>, amount: u64) -> (Vec<u8>, usize) {
+        let mut body = Vec::new();
+        body.push(1);                                  // proof: Some
+        body.extend_from_slice(&[0u8; 128]);
+        body.extend_from_slice(&[7u8; 32]);            // mint
+        body.push(0);                                  // delegated_transfer: None
+        body.extend_from_slice(&(inputs.len() as u32).to_le_bytes());
+        for (i, amt) in inputs.iter().enumerate() {
+            body.extend_from_slice(&amt.to_le_bytes());
+            body.push(0);                              // delegate_index: None
+            body.extend_from_slice(&[0, 1, 5, 0, 0, 0, 0]); // merkle_context
+            body.extend_from_slice(&3u16.to_le_bytes()); // root_index
+            body.push(0);                              // lamports: None
+            if i == 0 {
+                body.push(1);                          // tlv: Some(vec![9, 9])
+                body.extend_from_slice(&2u32.to_le_bytes());
+                body.extend_from_slice(&[9, 9]);
+            } else {
+                body.push(0);
+            }
+        }
+        let outputs: Vec<u64> = change.into_iter().collect();
+        body.extend_from_slice(&(outputs.len() as u32).to_le_bytes());
+        for amt in &outputs {
+            body.extend_from_slice(&[1u8; 32]);        // owner
+            body.extend_from_slice(&amt.to_le_bytes());
+            body.push(1);                              // lamports: Some
+            body.extend_from_slice(&0u64.to_le_bytes());
+            body.push(0);                              // merkle_tree_index
+            body.push(0);                              // tlv: None
+        }
+        body.push(0);                                  // is_compress: false
+        let amount_offset = 12 + body.len();
+        body.push(1);                                  // compress_or_decompress_amount: Some
+        body.extend_from_slice(&amount.to_le_bytes());
+        body.push(0);                                  // cpi_context: None
+        body.push(0);                                  // lamports_change_account_merkle_tree_index: None
+
+        let mut data = Vec::from(TRANSFER_V1_DISC.as_slice());
+        data.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        data.extend_from_slice(&body);
+        (data, amount_offset)
+    }
+
+    #[test]
+    fn test_locate_v1_decompress_amount_sums_inputs() {
+        let (data, offset) = build_v1_decompress_payload(&[300, 700], None, u64::MAX);
+        assert_eq!(locate_v1_decompress_amount(&data), Ok((offset, 1_000)));
+    }
+
+    #[test]
+    fn test_locate_v1_decompress_amount_subtracts_change() {
+        let (data, offset) = build_v1_decompress_payload(&[300, 700], Some(250), 750);
+        assert_eq!(locate_v1_decompress_amount(&data), Ok((offset, 750)));
+    }
+
+    #[test]
+    fn test_locate_v1_decompress_amount_rejects_compress_mode() {
+        let (mut data, offset) = build_v1_decompress_payload(&[100], None, 100);
+        data[offset - 1] = 1; // is_compress = true
+        assert_eq!(locate_v1_decompress_amount(&data), Err(ProgramError::InvalidInstructionData));
+    }
+
+    #[test]
+    fn test_locate_v1_decompress_amount_truncated() {
+        let (data, _) = build_v1_decompress_payload(&[100, 200], None, 300);
+        assert_eq!(locate_v1_decompress_amount(&data[..60]), Err(ProgramError::InvalidInstructionData));
+    }
+
+    #[test]
+    fn test_v1_transfer_amount_excludes_change_to_source() {
+        // The single output (250) is owned by [1; 32]
+        let (data, _) = build_v1_decompress_payload(&[300, 700], Some(250), 750);
+        assert_eq!(v1_transfer_amount(&data, &[1u8; 32]), Ok(0));
+        assert_eq!(v1_transfer_amount(&data, &[2u8; 32]), Ok(250));
+    }
+
+    #[test]
+    fn test_v1_transfer_amount_truncated() {
+        let (data, _) = build_v1_decompress_payload(&[100], Some(50), 50);
+        assert_eq!(v1_transfer_amount(&data[..data.len() - 40], &[2u8; 32]), Err(ProgramError::InvalidInstructionData));
+    }
+
+    // ── parse_v1_proof_layout ───────────────────────────────────────────────
+
+    fn invalid_proof() -> Result<V1ProofLayout<'static>, ProgramError> {
+        Err(crate::error::ZupyTokenError::InvalidCompressedProof.into())
+    }
+
+    /// Decompress payload shaped like a devnet `return_user_to_pool_v1` call:
+    /// non-zero proof, inputs on tree 0 / queue 1, change output on tree 0.
+    fn build_v1_proof_fixture() -> Vec<u8> {
+        let (mut data, _) = build_v1_decompress_payload(&[300, 700], Some(250), 750);
+        data[13..45].copy_from_slice(&[0xa1; 32]);
+        data[45..109].copy_from_slice(&[0xb2; 64]);
+        data[109..141].copy_from_slice(&[0xc3; 32]);
+        data
+    }
+
+    #[test]
+    fn test_parse_v1_proof_layout_valid_fixture() {
+        let data = build_v1_proof_fixture();
+        let layout = parse_v1_proof_layout(&data, 2).unwrap();
+        let proof = layout.proof.expect("proof: Some");
+        assert_eq!(proof.a, &[0xa1; 32]);
+        assert_eq!(proof.b, &[0xb2; 64]);
+        assert_eq!(proof.c, &[0xc3; 32]);
+        assert_eq!((layout.input_count, layout.output_count), (2, 1));
+    }
+
+    #[test]
+    fn test_parse_v1_proof_layout_truncated_proof() {
+        let data = build_v1_proof_fixture();
+        for len in [12, 13, 44, 108, 140] {
+            assert_eq!(parse_v1_proof_layout(&data[..len], 2), invalid_proof(), "len {}", len);
+        }
+    }
+
+    #[test]
+    fn test_parse_v1_proof_layout_truncated_anywhere() {
+        let data = build_v1_proof_fixture();
+        for len in 0..data.len() {
+            assert!(parse_v1_proof_layout(&data[..len], 2).is_err(), "len {}", len);
+        }
+    }
+
+    #[test]
+    fn test_parse_v1_proof_layout_rejects_bad_proof_tag() {
+        let mut data = build_v1_proof_fixture();
+        data[12] = 2;
+        assert_eq!(parse_v1_proof_layout(&data, 2), invalid_proof());
+    }
+
+    #[test]
+    fn test_parse_v1_proof_layout_zero_accounts() {
+        assert_eq!(parse_v1_proof_layout(&build_v1_proof_fixture(), 0), invalid_proof());
+        // Queue index 1 needs two forwarded accounts
+        assert_eq!(parse_v1_proof_layout(&build_v1_proof_fixture(), 1), invalid_proof());
+    }
+
+    #[test]
+    fn test_parse_v1_proof_layout_zero_inputs() {
+        let (data, _) = build_v1_decompress_payload(&[], None, 0);
+        assert_eq!(parse_v1_proof_layout(&data, 2), invalid_proof());
+    }
+
+    #[test]
+    fn test_parse_v1_proof_layout_root_index_out_of_range() {
+        let mut data = build_v1_proof_fixture();
+        // First input: disc/len (12) + proof (129) + mint (32) + delegated (1)
+        // + count (4) + amount (8) + delegate_index (1) + merkle_context (7)
+        let root_index = 12 + 129 + 32 + 1 + 4 + 8 + 1 + 7;
+        assert_eq!(u16::from_le_bytes([data[root_index], data[root_index + 1]]), 3);
+        data[root_index..root_index + 2].copy_from_slice(&V1_STATE_TREE_ROOT_HISTORY.to_le_bytes());
+        assert_eq!(parse_v1_proof_layout(&data, 2), invalid_proof());
+    }
+
+    #[test]
+    fn test_parse_v1_proof_layout_declared_length_too_short() {
+        let mut data = build_v1_proof_fixture();
+        data[8..12].copy_from_slice(&150u32.to_le_bytes());
+        assert_eq!(parse_v1_proof_layout(&data, 2), invalid_proof());
+    }
+
+    // ── MergeInputs / build_merge_decompress_to_spl_data ─────────────────────
+
+    /// Merge section: `amounts.len()` leaves on tree 0 / queue 1 (leaf = position,
+    /// proved by index, root 0), output queue 1, then `proof`.
+    fn build_merge_section(count: u8, amounts: &[u64], proof: Option<&[u8; 128]>) -> Vec<u8> {
+        let mut d = vec![count, 1];
+        for (i, amount) in amounts.iter().enumerate() {
+            d.extend_from_slice(&amount.to_le_bytes());
+            d.extend_from_slice(&[0, 1]);
+            d.extend_from_slice(&(i as u32).to_le_bytes());
+            d.push(1);
+            d.extend_from_slice(&0u16.to_le_bytes());
+        }
+        match proof {
+            Some(p) => {
+                d.push(1);
+                d.extend_from_slice(p);
+            }
+            None => d.push(0),
+        }
+        d
+    }
+
+    fn parse_merge(data: &[u8]) -> Result<MergeInputs<'_>, ProgramError> {
+        let mut reader = Reader::new(data);
+        let inputs = MergeInputs::parse(&mut reader)?;
+        reader.finish()?;
+        Ok(inputs)
+    }
+
+    #[test]
+    fn test_merge_inputs_parse_and_total() {
+        let data = build_merge_section(3, &[100, 200, 300], None);
+        let inputs = parse_merge(&data).unwrap();
+        assert_eq!(inputs.count(), 3);
+        assert_eq!(inputs.output_queue_index, 1);
+        assert_eq!(inputs.proof, None);
+        assert_eq!(inputs.total(), Ok(600));
+    }
+
+    #[test]
+    fn test_merge_inputs_parse_with_proof() {
+        let proof = [0xabu8; 128];
+        let data = build_merge_section(1, &[5], Some(&proof));
+        assert_eq!(parse_merge(&data).unwrap().proof, Some(&proof));
+    }
+
+    #[test]
+    fn test_merge_inputs_count_bounds() {
+        let err = Err(ZupyTokenError::InvalidInputCount.into());
+        assert_eq!(parse_merge(&build_merge_section(0, &[], None)), err);
+        let amounts = [1u64; MAX_MERGE_INPUTS + 1];
+        assert_eq!(parse_merge(&build_merge_section(amounts.len() as u8, &amounts, None)), err);
+        let amounts = [1u64; MAX_MERGE_INPUTS];
+        let data = build_merge_section(MAX_MERGE_INPUTS as u8, &amounts, None);
+        let inputs = parse_merge(&data).unwrap();
+        assert_eq!(inputs.count(), MAX_MERGE_INPUTS);
+    }
+
+    #[test]
+    fn test_merge_inputs_truncated_or_bad_proof_tag() {
+        let data = build_merge_section(2, &[1, 2], None);
+        for len in 1..data.len() {
+            assert_eq!(parse_merge(&data[..len]), Err(ProgramError::InvalidInstructionData), "len {}", len);
+        }
+        let mut data = data;
+        *data.last_mut().unwrap() = 2;
+        assert_eq!(parse_merge(&data), Err(ProgramError::InvalidInstructionData));
+    }
+
+    #[test]
+    fn test_merge_inputs_total_overflow() {
+        let data = build_merge_section(2, &[u64::MAX, 1], None);
+        assert_eq!(parse_merge(&data).unwrap().total(), Err(ZupyTokenError::ArithmeticOverflow.into()));
+    }
+
+    #[test]
+    fn test_merge_inputs_validate_accounts() {
+        let data = build_merge_section(2, &[1, 2], None);
+        let inputs = parse_merge(&data).unwrap();
+        assert_eq!(inputs.validate_accounts(2), Ok(()));
+        assert_eq!(inputs.validate_accounts(1), Err(ProgramError::NotEnoughAccountKeys));
+        assert_eq!(inputs.validate_accounts(0), Err(ProgramError::NotEnoughAccountKeys));
+    }
+
+    #[test]
+    fn test_build_merge_decompress_header_and_compression() {
+        let data = build_merge_section(2, &[400, 600], None);
+        let inputs = parse_merge(&data).unwrap();
+        let d = build_merge_decompress_to_spl_data(&inputs, 700, 300, 254, TOKEN_DECIMALS);
+        assert_eq!(d[0], TRANSFER2_DISC);
+        assert_eq!(d[5], DECOMPRESS_PACKED_FIXED + 1); // output queue at packed[7]
+        assert_eq!(&d[6..8], &[0xFF, 0xFF]);
+        assert_eq!(&d[8..14], &[0, 1, 1, 0, 0, 0]); // cpi_context None, compressions Some(len 1)
+        assert_eq!(d[14], 1); // Decompress
+        assert_eq!(u64::from_le_bytes(d[15..23].try_into().unwrap()), 700);
+        assert_eq!(&d[23..30], &[0, 1, 0, 3, 0, 254, TOKEN_DECIMALS]);
+        assert_eq!(d[30], 0); // proof: None
+    }
+
+    #[test]
+    fn test_build_merge_decompress_inputs_and_change() {
+        let data = build_merge_section(2, &[400, 600], None);
+        let inputs = parse_merge(&data).unwrap();
+        let d = build_merge_decompress_to_spl_data(&inputs, 700, 300, 254, TOKEN_DECIMALS);
+        assert_eq!(d.len(), 31 + 4 + 2 * 22 + 4 + 13 + 4);
+        assert_eq!(u32::from_le_bytes(d[31..35].try_into().unwrap()), 2);
+        let second = &d[35 + 22..35 + 44];
+        assert_eq!(second[0], DECOMPRESS_AUTHORITY_INDEX);
+        assert_eq!(u64::from_le_bytes(second[1..9].try_into().unwrap()), 600);
+        assert_eq!(&second[9..13], &[0, 0, 0, TOKEN_DATA_VERSION]);
+        assert_eq!(&second[13..15], &[DECOMPRESS_PACKED_FIXED, DECOMPRESS_PACKED_FIXED + 1]);
+        assert_eq!(u32::from_le_bytes(second[15..19].try_into().unwrap()), 1); // leaf_index
+        assert_eq!(&second[19..22], &[1, 0, 0]); // prove_by_index, root_index
+        let out = &d[79..];
+        assert_eq!(u32::from_le_bytes(out[0..4].try_into().unwrap()), 1);
+        assert_eq!(out[4], DECOMPRESS_AUTHORITY_INDEX);
+        assert_eq!(u64::from_le_bytes(out[5..13].try_into().unwrap()), 300);
+        assert_eq!(&out[13..], &[0, 0, 0, TOKEN_DATA_VERSION, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_build_merge_decompress_no_change_and_proof() {
+        let proof = [0x5au8; 128];
+        let data = build_merge_section(1, &[1_000], Some(&proof));
+        let inputs = parse_merge(&data).unwrap();
+        let d = build_merge_decompress_to_spl_data(&inputs, 1_000, 0, 254, TOKEN_DECIMALS);
+        assert_eq!(d.len(), 30 + 129 + 4 + 22 + 4 + 4);
+        assert_eq!(d[30], 1);
+        assert_eq!(&d[31..159], &proof);
+        assert_eq!(&d[d.len() - 8..], &[0, 0, 0, 0, 0, 0, 0, 0]); // no outputs, None tail
+    }
 }
@@ -0,0 +1,289 @@
+//! Coupon state PDAs backing on-chain coupon redemption, and the verified
+//! collection check and royalty parsing used by the coupon cNFT mints.
+//!
+//! `create_coupon_nft` creates the CouponState PDA `[COUPON_STATE_SEED,
+//! coupon_ksuid]` next to the coupon mint when the client passes it.
+//! `redeem_coupon_nft` and `transfer_coupon_nft` create it on first use for
+//! coupons minted without one, so every redeemed or gifted coupon ends up with
+//! a state account.
+
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address};
+
+use crate::constants::{
+    BPS_DENOMINATOR, COUPON_STATE_SEED, MAX_CNFT_CREATORS, MPL_TOKEN_METADATA_PROGRAM_ID,
+};
+use crate::error::ZupyTokenError;
+use crate::helpers::cpi::{cpi_create_account, CnftCollection, CnftRoyalties};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::pda::{derive_coupon_state_pda, validate_pda};
+use crate::state::coupon_state::{
+    CouponStateMut, COUPON_STATE_DISCRIMINATOR, COUPON_STATE_SIZE,
+};
+use crate::state::token_state::TokenState;
+
+/// Terms recorded in a CouponState when it is created.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CouponTerms {
+    /// Unix timestamp after which redemption fails; 0 = no expiry.
+    pub expires_at: i64,
+    /// ZUPY paid to the bearer on redemption; 0 = no token value.
+    pub value_amount: u64,
+    /// Company whose compressed PDA pays `value_amount`.
+    pub issuer_company_id: u64,
+}
+
+impl CouponTerms {
+    /// True when the terms need a CouponState to be enforced.
+    pub fn is_restricted(&self) -> bool {
+        self.expires_at != 0 || self.value_amount != 0
+    }
+}
+
+/// Reads the optional `create_coupon_nft` tail left in `reader`:
+/// `expires_at (i64)` then `value_amount (u64) + issuer_company_id (u64)`.
+/// Absent fields default to 0; a partial value pair is `InvalidInstructionData`.
+/// The tail ends the payload, so bytes past it fail too (see `Reader::finish`).
+pub fn parse_coupon_terms(reader: &mut Reader<'_>) -> Result<CouponTerms, ProgramError> {
+    let mut terms = CouponTerms::default();
+    if reader.is_empty() {
+        return Ok(terms);
+    }
+    terms.expires_at = reader.read_i64()?;
+    if !reader.is_empty() {
+        terms.value_amount = reader.read_u64()?;
+        terms.issuer_company_id = reader.read_u64()?;
+    }
+    reader.finish()?;
+    Ok(terms)
+}
+
+/// Reads the optional `mint_coupon_cnft` royalty tail left in `reader`:
+/// `seller_fee_basis_points (u16) + creator_count (u8)` then `creator_count ×
+/// (address ([u8; 32]) + share (u8))`. Absent = `CnftRoyalties::default()`.
+///
+/// More than `MAX_CNFT_CREATORS` creators, or shares not summing to 100, fail
+/// with `InvalidCreators`; bps above `BPS_DENOMINATOR` with `InvalidRoyaltyBps`.
+/// The tail ends the payload, so bytes past the last creator fail.
+pub fn parse_cnft_royalties(reader: &mut Reader<'_>) -> Result<CnftRoyalties, ProgramError> {
+    let mut royalties = CnftRoyalties::default();
+    if reader.is_empty() {
+        return Ok(royalties);
+    }
+    royalties.seller_fee_basis_points = reader.read_u16()?;
+    if royalties.seller_fee_basis_points > BPS_DENOMINATOR {
+        return Err(ZupyTokenError::InvalidRoyaltyBps.into());
+    }
+    let count = reader.read_u8()?;
+    if count as usize > MAX_CNFT_CREATORS {
+        return Err(ZupyTokenError::InvalidCreators.into());
+    }
+
+    let mut share_sum = 0u16;
+    for creator in royalties.creators.iter_mut().take(count as usize) {
+        let address = reader.read_bytes::<32>()?;
+        let share = reader.read_u8()?;
+        *creator = (*address, share);
+        share_sum += share as u16;
+    }
+    if count > 0 && share_sum != 100 {
+        return Err(ZupyTokenError::InvalidCreators.into());
+    }
+    reader.finish()?;
+    royalties.creator_count = count;
+    Ok(royalties)
+}
+
+/// Creates the unredeemed CouponState PDA for `coupon_ksuid`, funded by `payer`,
+/// recording `terms` and `holder` (the user_pda holding the coupon).
+///
+/// Checks the address against `[COUPON_STATE_SEED, coupon_ksuid]`; an account
+/// that already holds data is left untouched for the caller to validate.
+pub fn create_coupon_state_if_needed(
+    program_id: &Address,
+    coupon_state: &AccountView,
+    payer: &AccountView,
+    coupon_ksuid: &[u8; 27],
+    coupon_mint: &Address,
+    holder: &Address,
+    terms: &CouponTerms,
+) -> Result<(), ProgramError> {
+    let (expected_pda, bump) = derive_coupon_state_pda(program_id, coupon_ksuid);
+    validate_pda(coupon_state.address(), &expected_pda)?;
+    if coupon_state.data_len() > 0 {
+        return Ok(());
+    }
+
+    let bump_bytes = [bump];
+    let signer_seeds: [Seed; 3] = [
+        Seed::from(COUPON_STATE_SEED),
+        Seed::from(coupon_ksuid.as_ref()),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    let signer = Signer::from(&signer_seeds);
+
+    cpi_create_account(
+        payer,
+        coupon_state,
+        COUPON_STATE_SIZE as u64,
+        program_id,
+        &[signer],
+    )?;
+
+    let mint_key: &[u8; 32] = coupon_mint.as_ref().try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    let holder_key: &[u8; 32] = holder.as_ref().try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    let mut state = CouponStateMut::from_slice(unsafe { coupon_state.borrow_unchecked_mut() });
+    state.set_discriminator(&COUPON_STATE_DISCRIMINATOR);
+    state.set_coupon_ksuid(coupon_ksuid);
+    state.set_mint(mint_key);
+    state.set_expires_at(terms.expires_at);
+    state.set_value_amount(terms.value_amount);
+    state.set_issuer_company_id(terms.issuer_company_id);
+    state.set_holder(holder_key);
+    state.set_bump(bump);
+
+    Ok(())
+}
+
+/// Checks the collection accounts of a coupon cNFT mint against the
+/// already-validated `token_state_account` and its `state`: the collection mint
+/// must be `token_state.coupon_collection()` (unset or different →
+/// `InvalidCollection`) and the metadata program Metaplex Token Metadata.
+/// Returns the token_state bump for the collection authority signer.
+pub fn validate_coupon_collection(
+    token_state_account: &AccountView,
+    state: &TokenState,
+    collection: &CnftCollection,
+) -> Result<u8, ProgramError> {
+    match state.coupon_collection() {
+        Some(expected) if expected == collection.mint.address().as_ref() => {}
+        _ => return Err(ZupyTokenError::InvalidCollection.into()),
+    }
+    if collection.authority.address() != token_state_account.address() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+
+    let expected_metadata_program = Address::from(MPL_TOKEN_METADATA_PROGRAM_ID);
+    if collection.token_metadata_program.address() != &expected_metadata_program {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    Ok(state.bump())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tail(fields: &[u64]) -> Vec<u8> {
+        fields.iter().flat_map(|f| f.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_parse_coupon_terms_absent() {
+        assert_eq!(parse_coupon_terms(&mut Reader::new(&[])), Ok(CouponTerms::default()));
+        assert!(!CouponTerms::default().is_restricted());
+    }
+
+    #[test]
+    fn test_parse_coupon_terms_expiry_only() {
+        let terms = parse_coupon_terms(&mut Reader::new(&tail(&[1_900_000_000]))).unwrap();
+        assert_eq!(terms.expires_at, 1_900_000_000);
+        assert_eq!(terms.value_amount, 0);
+        assert!(terms.is_restricted());
+    }
+
+    #[test]
+    fn test_parse_coupon_terms_with_value() {
+        let terms = parse_coupon_terms(&mut Reader::new(&tail(&[0, 5_000_000, 77]))).unwrap();
+        assert_eq!(
+            terms,
+            CouponTerms { expires_at: 0, value_amount: 5_000_000, issuer_company_id: 77 }
+        );
+        assert!(terms.is_restricted());
+    }
+
+    #[test]
+    fn test_parse_coupon_terms_partial_value_rejected() {
+        let data = tail(&[0, 5_000_000]);
+        assert_eq!(parse_coupon_terms(&mut Reader::new(&data)), Err(ProgramError::InvalidInstructionData));
+    }
+
+    #[cfg(not(feature = "lenient-ix-data"))]
+    #[test]
+    fn test_parse_coupon_terms_trailing_byte_rejected() {
+        let mut data = tail(&[0, 5_000_000, 77]);
+        data.push(0);
+        assert_eq!(parse_coupon_terms(&mut Reader::new(&data)), Err(ProgramError::InvalidInstructionData));
+    }
+
+    fn royalty_tail(bps: u16, creators: &[([u8; 32], u8)]) -> Vec<u8> {
+        let mut data = bps.to_le_bytes().to_vec();
+        data.push(creators.len() as u8);
+        for (address, share) in creators {
+            data.extend_from_slice(address);
+            data.push(*share);
+        }
+        data
+    }
+
+    #[test]
+    fn test_parse_cnft_royalties_absent() {
+        assert_eq!(parse_cnft_royalties(&mut Reader::new(&[])), Ok(CnftRoyalties::default()));
+        assert!(CnftRoyalties::default().creators().is_empty());
+    }
+
+    #[test]
+    fn test_parse_cnft_royalties_two_creators() {
+        let data = royalty_tail(500, &[([1; 32], 60), ([2; 32], 40)]);
+        let royalties = parse_cnft_royalties(&mut Reader::new(&data)).unwrap();
+        assert_eq!(royalties.seller_fee_basis_points, 500);
+        assert_eq!(royalties.creators(), &[([1; 32], 60), ([2; 32], 40)]);
+    }
+
+    #[test]
+    fn test_parse_cnft_royalties_bps_only() {
+        let royalties = parse_cnft_royalties(&mut Reader::new(&royalty_tail(10_000, &[]))).unwrap();
+        assert_eq!(royalties.seller_fee_basis_points, 10_000);
+        assert!(royalties.creators().is_empty());
+    }
+
+    #[test]
+    fn test_parse_cnft_royalties_bad_share_sum() {
+        let data = royalty_tail(500, &[([1; 32], 60), ([2; 32], 30)]);
+        assert_eq!(parse_cnft_royalties(&mut Reader::new(&data)), Err(ZupyTokenError::InvalidCreators.into()));
+        let data = royalty_tail(500, &[([1; 32], 99)]);
+        assert_eq!(parse_cnft_royalties(&mut Reader::new(&data)), Err(ZupyTokenError::InvalidCreators.into()));
+    }
+
+    #[test]
+    fn test_parse_cnft_royalties_too_many_creators() {
+        let data = royalty_tail(500, &[([1; 32], 40), ([2; 32], 30), ([3; 32], 30)]);
+        assert_eq!(parse_cnft_royalties(&mut Reader::new(&data)), Err(ZupyTokenError::InvalidCreators.into()));
+    }
+
+    #[test]
+    fn test_parse_cnft_royalties_bps_above_100_percent() {
+        let data = royalty_tail(10_001, &[([1; 32], 100)]);
+        assert_eq!(parse_cnft_royalties(&mut Reader::new(&data)), Err(ZupyTokenError::InvalidRoyaltyBps.into()));
+    }
+
+    #[test]
+    fn test_parse_cnft_royalties_truncated_creator() {
+        let mut data = royalty_tail(500, &[([1; 32], 100)]);
+        data.pop();
+        assert_eq!(parse_cnft_royalties(&mut Reader::new(&data)), Err(ProgramError::InvalidInstructionData));
+    }
+
+    #[cfg(not(feature = "lenient-ix-data"))]
+    #[test]
+    fn test_parse_cnft_royalties_trailing_byte_rejected() {
+        let mut data = royalty_tail(500, &[([1; 32], 100)]);
+        data.push(0);
+        assert_eq!(parse_cnft_royalties(&mut Reader::new(&data)), Err(ProgramError::InvalidInstructionData));
+    }
+}
@@ -6,10 +6,15 @@ use pinocchio::instruction::{InstructionAccount, InstructionView};
 use pinocchio::sysvars::Sysvar;
 use pinocchio::sysvars::rent::Rent;
 
-use pinocchio_associated_token_account::instructions::Create;
-use pinocchio_system::instructions::CreateAccount;
+use pinocchio_associated_token_account::instructions::{Create, CreateIdempotent};
+use pinocchio_system::instructions::{CreateAccount, Transfer as SystemTransfer};
 use pinocchio_token_2022::instructions::{Burn, CloseAccount, InitializeMint2, MintTo, Transfer, TransferChecked};
 
+use crate::constants::{MAX_CNFT_CREATORS, SPL_MEMO_PROGRAM_ID};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::transfer_fee::{read_transfer_fee_config, transfer_checked_with_fee_data};
+use crate::state::token_state::TokenStateMut;
+
 /// CPI: Token-2022 Transfer (discriminator `0x03`).
 /// Transfers `amount` tokens from `source` to `destination` using PDA signer seeds.
 #[inline(always)]
@@ -35,6 +40,7 @@ pub fn cpi_transfer<'a>(
 /// CPI: Token-2022 TransferChecked.
 /// Transfers `amount` tokens with decimal validation from `source` to `destination`.
 #[inline(always)]
+#[allow(clippy::too_many_arguments)]
 pub fn cpi_transfer_checked<'a>(
     source: &'a AccountView,
     destination: &'a AccountView,
@@ -58,9 +64,81 @@ pub fn cpi_transfer_checked<'a>(
     Ok(())
 }
 
+/// CPI: Token-2022 TransferCheckedWithFee (`[26, 1]`).
+/// Manual CPI — pinocchio-token-2022 has no builder for the fee extension.
+/// `fee` must equal the fee Token-2022 computes for the current epoch.
+#[inline(always)]
+#[allow(clippy::too_many_arguments)]
+pub fn cpi_transfer_checked_with_fee<'a>(
+    source: &'a AccountView,
+    destination: &'a AccountView,
+    authority: &'a AccountView,
+    mint: &'a AccountView,
+    amount: u64,
+    decimals: u8,
+    fee: u64,
+    token_program: &Address,
+    signers: &[Signer],
+) -> Result<(), ProgramError> {
+    let data = transfer_checked_with_fee_data(amount, decimals, fee);
+    let accounts = [
+        InstructionAccount::writable(source.address()),
+        InstructionAccount::readonly(mint.address()),
+        InstructionAccount::writable(destination.address()),
+        InstructionAccount::readonly_signer(authority.address()),
+    ];
+    let instruction = InstructionView {
+        program_id: token_program,
+        accounts: &accounts,
+        data: &data,
+    };
+    pinocchio::cpi::invoke_signed(&instruction, &[source, mint, destination, authority], signers)?;
+    Ok(())
+}
+
+/// CPI: TransferChecked, or TransferCheckedWithFee with the current epoch's
+/// fee when `mint` carries the TransferFeeConfig extension (see
+/// `helpers::transfer_fee`). The Clock is only read for fee-bearing mints.
+#[inline(always)]
+#[allow(clippy::too_many_arguments)]
+pub fn cpi_transfer_checked_for_mint<'a>(
+    source: &'a AccountView,
+    destination: &'a AccountView,
+    authority: &'a AccountView,
+    mint: &'a AccountView,
+    amount: u64,
+    decimals: u8,
+    token_program: &Address,
+    signers: &[Signer],
+) -> Result<(), ProgramError> {
+    match read_transfer_fee_config(unsafe { mint.borrow_unchecked() }) {
+        None => cpi_transfer_checked(
+            source, destination, authority, mint, amount, decimals, token_program, signers,
+        ),
+        Some(config) => {
+            let epoch = pinocchio::sysvars::clock::Clock::get()?.epoch;
+            cpi_transfer_checked_with_fee(
+                source,
+                destination,
+                authority,
+                mint,
+                amount,
+                decimals,
+                config.fee(epoch, amount),
+                token_program,
+                signers,
+            )
+        }
+    }
+}
+
 /// CPI: Create Associated Token Account if it doesn't exist.
 /// An uninitialized ATA has `data_len() == 0` and is system-owned.
 /// If the ATA already exists (data_len > 0), this is a no-op.
+///
+/// Uses the ATA program's plain `Create` (discriminant 0), which fails hard
+/// if the account turns out to exist at the ATA-program level. Destination
+/// accounts should use [`cpi_create_ata_idempotent`] instead.
 #[inline(always)]
 pub fn cpi_create_ata_if_needed<'a>(
     ata_account: &'a AccountView,
@@ -87,6 +165,38 @@ pub fn cpi_create_ata_if_needed<'a>(
     Ok(())
 }
 
+/// CPI: Create Associated Token Account via `CreateIdempotent` (discriminant 1).
+/// An existing ATA with the right wallet and mint is a no-op at the ATA-program
+/// level; one with the wrong wallet or mint still fails there. Callers keep
+/// their own pre-validation (`validate_destination_ata_if_exists`) so a
+/// wrong-mint account surfaces as `InvalidMint` rather than an ATA-program error.
+/// Skips the CPI entirely when the account already has data.
+#[inline(always)]
+pub fn cpi_create_ata_idempotent<'a>(
+    ata_account: &'a AccountView,
+    payer: &'a AccountView,
+    owner: &'a AccountView,
+    mint: &'a AccountView,
+    token_program: &'a AccountView,
+    system_program: &'a AccountView,
+) -> Result<(), ProgramError> {
+    // Already initialized — nothing to do
+    if ata_account.data_len() > 0 {
+        return Ok(());
+    }
+
+    CreateIdempotent {
+        funding_account: payer,
+        account: ata_account,
+        wallet: owner,
+        mint,
+        system_program,
+        token_program,
+    }
+    .invoke()?;
+    Ok(())
+}
+
 /// CPI: Token-2022 Burn (discriminator `0x08`).
 /// Burns `amount` tokens from `token_account` using PDA signer seeds (invoke_signed).
 #[inline(always)]
@@ -197,6 +307,59 @@ pub fn cpi_create_account<'a>(
     Ok(())
 }
 
+/// Grows program-owned `account` to `new_len` bytes, first topping it up to the
+/// rent-exempt minimum from `payer` (System Transfer; `payer` must sign).
+#[inline(always)]
+pub fn cpi_grow_account<'a>(
+    payer: &'a AccountView,
+    account: &'a AccountView,
+    new_len: usize,
+) -> Result<(), ProgramError> {
+    cpi_fund_rent(payer, account, new_len)?;
+    account.resize(new_len)?;
+    Ok(())
+}
+
+/// Grows the TokenState account to `new_len` bytes (see `cpi_grow_account`)
+/// and defaults the new tail via `TokenStateMut::init_grown_tail`.
+/// `metadata_initialized` is only written when the flags are new to the account.
+#[inline(always)]
+pub fn cpi_grow_token_state<'a>(
+    payer: &'a AccountView,
+    token_state: &'a AccountView,
+    new_len: usize,
+    metadata_initialized: bool,
+) -> Result<(), ProgramError> {
+    let old_len = token_state.data_len();
+    cpi_grow_account(payer, token_state, new_len)?;
+    TokenStateMut::from_slice(unsafe { token_state.borrow_unchecked_mut() })
+        .init_grown_tail(old_len, metadata_initialized);
+    Ok(())
+}
+
+/// Tops `account` up to the rent-exempt minimum for `len` bytes from `payer`
+/// (System Transfer; `payer` must sign). Used before a CPI that reallocs an
+/// account it owns, e.g. Token-2022 TokenMetadata Initialize.
+#[inline(always)]
+pub fn cpi_fund_rent<'a>(
+    payer: &'a AccountView,
+    account: &'a AccountView,
+    len: usize,
+) -> Result<(), ProgramError> {
+    let rent = Rent::get()?;
+    let required = rent.try_minimum_balance(len)?;
+    let shortfall = required.saturating_sub(account.lamports());
+    if shortfall > 0 {
+        SystemTransfer {
+            from: payer,
+            to: account,
+            lamports: shortfall,
+        }
+        .invoke()?;
+    }
+    Ok(())
+}
+
 /// CPI: Token-2022 InitializeMint2.
 /// Initializes an already-created account as a Token-2022 mint.
 /// Must be called AFTER `cpi_create_account`.
@@ -280,10 +443,16 @@ pub fn cpi_initialize_metadata<'a>(
     Ok(())
 }
 
+/// spl-token-metadata-interface UpdateField discriminator.
+const UPDATE_FIELD_DISC: [u8; 8] = [221, 233, 49, 45, 181, 202, 220, 200];
+
+/// Borsh variant index of `Field::Key(String)` (custom additional_metadata key).
+pub const METADATA_FIELD_KEY: u8 = 3;
+
 /// CPI: spl-token-metadata-interface UpdateField.
 /// Manual CPI — no pinocchio crate exists for metadata interface.
 /// Discriminator: [221, 233, 49, 45, 181, 202, 220, 200]
-/// Field enum: 0=Name, 1=Symbol, 2=Uri (custom key not supported)
+/// Field enum: 0=Name, 1=Symbol, 2=Uri (custom keys: `cpi_update_metadata_key`)
 ///
 /// NOTE: Uses Vec for dynamic data serialization. Acceptable for cold-path
 /// (metadata updates are rare admin operations).
@@ -296,7 +465,6 @@ pub fn cpi_update_metadata_field<'a>(
     value: &str,
     signers: &[Signer],
 ) -> Result<(), ProgramError> {
-    let disc: [u8; 8] = [221, 233, 49, 45, 181, 202, 220, 200];
     let value_bytes = value.as_bytes();
 
     // Field is serialized as the spl_token_metadata_interface::state::Field enum.
@@ -308,11 +476,81 @@ pub fn cpi_update_metadata_field<'a>(
 
     let data_len = 8 + 1 + 4 + value_bytes.len();
     let mut data = Vec::with_capacity(data_len);
-    data.extend_from_slice(&disc);
+    data.extend_from_slice(&UPDATE_FIELD_DISC);
     data.push(field);  // 1-byte Borsh enum discriminant (NOT u32)
     data.extend_from_slice(&(value_bytes.len() as u32).to_le_bytes());
     data.extend_from_slice(value_bytes);
 
+    invoke_update_field(mint, authority, token_program, &data, signers)
+}
+
+/// CPI: spl-token-metadata-interface UpdateField with `Field::Key(key)`,
+/// inserting or overwriting an `additional_metadata` entry.
+#[inline(always)]
+pub fn cpi_update_metadata_key<'a>(
+    mint: &'a AccountView,
+    authority: &'a AccountView,
+    token_program: &'a AccountView,
+    key: &str,
+    value: &str,
+    signers: &[Signer],
+) -> Result<(), ProgramError> {
+    let data = build_update_metadata_key_data(key, value);
+    invoke_update_field(mint, authority, token_program, &data, signers)
+}
+
+/// UpdateField data for `Field::Key(key)`:
+/// disc (8) + variant 3 (u8) + key (u32 len + bytes) + value (u32 len + bytes).
+pub fn build_update_metadata_key_data(key: &str, value: &str) -> Vec<u8> {
+    let mut data = Vec::with_capacity(8 + 1 + 4 + key.len() + 4 + value.len());
+    data.extend_from_slice(&UPDATE_FIELD_DISC);
+    data.push(METADATA_FIELD_KEY);
+    data.extend_from_slice(&(key.len() as u32).to_le_bytes());
+    data.extend_from_slice(key.as_bytes());
+    data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    data.extend_from_slice(value.as_bytes());
+    data
+}
+
+#[inline(always)]
+fn invoke_update_field<'a>(
+    mint: &'a AccountView,
+    authority: &'a AccountView,
+    token_program: &'a AccountView,
+    data: &[u8],
+    signers: &[Signer],
+) -> Result<(), ProgramError> {
+    let accounts = [
+        InstructionAccount::writable(mint.address()),
+        InstructionAccount::readonly_signer(authority.address()),
+    ];
+
+    let instruction = InstructionView {
+        program_id: token_program.address(),
+        accounts: &accounts,
+        data,
+    };
+
+    pinocchio::cpi::invoke_signed(&instruction, &[mint, authority, token_program], signers)?;
+    Ok(())
+}
+
+/// spl-token-metadata-interface UpdateAuthority discriminator.
+const UPDATE_AUTHORITY_DISC: [u8; 8] = [215, 228, 166, 228, 84, 100, 86, 123];
+
+/// CPI: spl-token-metadata-interface UpdateAuthority with `new_authority = None`
+/// (32 zero bytes, `OptionalNonZeroPubkey`). Irreversible: no account can update
+/// the metadata afterwards.
+#[inline(always)]
+pub fn cpi_clear_metadata_update_authority<'a>(
+    mint: &'a AccountView,
+    authority: &'a AccountView,
+    token_program: &'a AccountView,
+    signers: &[Signer],
+) -> Result<(), ProgramError> {
+    let data = build_clear_update_authority_data();
+
+    // 0. metadata [writable] (= mint), 1. current update_authority [signer]
     let accounts = [
         InstructionAccount::writable(mint.address()),
         InstructionAccount::readonly_signer(authority.address()),
@@ -328,15 +566,86 @@ pub fn cpi_update_metadata_field<'a>(
     Ok(())
 }
 
-/// CPI: Bubblegum MintV1 — manual CPI (no mpl-bubblegum dependency).
-/// Discriminator: SHA256("global:mint_v1")[0..8]
-/// MetadataArgs serialized manually via borsh.
+/// UpdateAuthority data: disc (8) + new_authority (32, all zero = None).
+pub fn build_clear_update_authority_data() -> [u8; 40] {
+    let mut data = [0u8; 40];
+    data[..8].copy_from_slice(&UPDATE_AUTHORITY_DISC);
+    data
+}
+
+/// Splits an optional trailing SPL Memo program account off `accounts`.
+///
+/// Returns the accounts without it plus the memo program when the last account
+/// is `SPL_MEMO_PROGRAM_ID`; otherwise `accounts` unchanged and `None`, so
+/// clients that do not pass it see no difference.
+#[inline(always)]
+pub fn split_memo_program(accounts: &[AccountView]) -> (&[AccountView], Option<&AccountView>) {
+    let memo_program_id = Address::from(SPL_MEMO_PROGRAM_ID);
+    match accounts.split_last() {
+        Some((last, rest)) if last.address() == &memo_program_id => (rest, Some(last)),
+        _ => (accounts, None),
+    }
+}
+
+/// CPI: SPL Memo — logs `memo` as a standard memo instruction (no signers), so
+/// explorers and exchanges index it. Call only with an already-validated memo.
+#[inline(always)]
+pub fn cpi_spl_memo(memo_program: &AccountView, memo: &str) -> Result<(), ProgramError> {
+    let memo_program_id = Address::from(SPL_MEMO_PROGRAM_ID);
+    if memo_program.address() != &memo_program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let instruction = InstructionView {
+        program_id: memo_program.address(),
+        accounts: &[],
+        data: memo.as_bytes(),
+    };
+
+    pinocchio::cpi::invoke::<1>(&instruction, &[memo_program])?;
+    Ok(())
+}
+
+/// Verified-collection accounts for `cpi_bubblegum_mint_to_collection_v1`.
+pub struct CnftCollection<'a> {
+    /// Collection update authority — the token_state PDA, signs via `signers`.
+    pub authority: &'a AccountView,
+    pub mint: &'a AccountView,
+    /// Collection metadata (writable: Bubblegum bumps the collection size).
+    pub metadata: &'a AccountView,
+    pub edition: &'a AccountView,
+    /// Bubblegum PDA `[b"collection_cpi"]` that signs the Token Metadata CPI.
+    pub bubblegum_signer: &'a AccountView,
+    pub token_metadata_program: &'a AccountView,
+}
+
+/// Royalty terms for `cpi_bubblegum_mint_to_collection_v1`. The default (0 bps,
+/// no creators) is the metadata every coupon cNFT carried before royalties.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CnftRoyalties {
+    pub seller_fee_basis_points: u16,
+    pub creator_count: u8,
+    /// (address, share); only the first `creator_count` entries are used.
+    pub creators: [([u8; 32], u8); MAX_CNFT_CREATORS],
+}
+
+impl CnftRoyalties {
+    pub fn creators(&self) -> &[([u8; 32], u8)] {
+        &self.creators[..self.creator_count as usize]
+    }
+}
+
+/// CPI: Bubblegum MintToCollectionV1 — manual CPI (no mpl-bubblegum dependency).
+/// Discriminator: SHA256("global:mint_to_collection_v1")[0..8]
+/// MetadataArgs serialized manually via borsh, with `collection.key` set to the
+/// collection mint; Bubblegum marks it verified. Creators are unverified
+/// (they do not sign the mint).
 ///
 /// NOTE: Uses Vec for dynamic MetadataArgs serialization. Acceptable for cold-path
 /// (cNFT minting is an infrequent operation, not a hot-path transfer).
 #[inline(always)]
 #[allow(clippy::too_many_arguments)]
-pub fn cpi_bubblegum_mint_v1<'a>(
+pub fn cpi_bubblegum_mint_to_collection_v1<'a>(
     tree_config: &'a AccountView,
     leaf_owner: &'a AccountView,
     merkle_tree: &'a AccountView,
@@ -346,13 +655,16 @@ pub fn cpi_bubblegum_mint_v1<'a>(
     compression_program: &'a AccountView,
     system_program: &'a AccountView,
     bubblegum_program: &'a AccountView,
+    collection: &CnftCollection<'a>,
     name: &str,
     symbol: &str,
     uri: &str,
+    royalties: &CnftRoyalties,
+    signers: &[Signer],
 ) -> Result<(), ProgramError> {
-    // Discriminator: SHA256("global:mint_v1")[0..8]
-    // Pre-computed: [145, 98, 192, 118, 184, 147, 118, 104]
-    let disc: [u8; 8] = [145, 98, 192, 118, 184, 147, 118, 104];
+    // Discriminator: SHA256("global:mint_to_collection_v1")[0..8]
+    // Pre-computed: [153, 18, 178, 47, 197, 158, 86, 15]
+    let disc: [u8; 8] = [153, 18, 178, 47, 197, 158, 86, 15];
 
     let name_bytes = name.as_bytes();
     let symbol_bytes = symbol.as_bytes();
@@ -367,10 +679,10 @@ pub fn cpi_bubblegum_mint_v1<'a>(
         + 1                                   // is_mutable: bool
         + 1                                   // edition_nonce: Option<u8> (None)
         + 2                                   // token_standard: Option<TokenStandard> (Some(NonFungible))
-        + 1                                   // collection: Option<Collection> (None)
+        + 34                                  // collection: Option<Collection> (Some)
         + 1                                   // uses: Option<Uses> (None)
         + 1                                   // token_program_version: TokenProgramVersion (Original)
-        + 4;                                  // creators: Vec<Creator> (empty)
+        + 4 + 34 * royalties.creators().len(); // creators: Vec<Creator>
 
     let data_len = 8 + metadata_len;
     let mut data = Vec::with_capacity(data_len);
@@ -385,8 +697,8 @@ pub fn cpi_bubblegum_mint_v1<'a>(
     // uri
     data.extend_from_slice(&(uri_bytes.len() as u32).to_le_bytes());
     data.extend_from_slice(uri_bytes);
-    // seller_fee_basis_points: 0
-    data.extend_from_slice(&0u16.to_le_bytes());
+    // seller_fee_basis_points
+    data.extend_from_slice(&royalties.seller_fee_basis_points.to_le_bytes());
     // primary_sale_happened: true
     data.push(1);
     // is_mutable: false
@@ -396,19 +708,28 @@ pub fn cpi_bubblegum_mint_v1<'a>(
     // token_standard: Some(NonFungible) = [1 (Some), 0 (NonFungible variant index)]
     data.push(1);
     data.push(0);
-    // collection: None
+    // collection: Some(Collection { verified: false, key: collection mint })
+    data.push(1);
     data.push(0);
+    data.extend_from_slice(collection.mint.address().as_ref());
     // uses: None
     data.push(0);
     // token_program_version: Original = 0
     data.push(0);
-    // creators: empty Vec = [0, 0, 0, 0] (u32 length = 0)
-    data.extend_from_slice(&0u32.to_le_bytes());
+    // creators: Vec<Creator { address, verified: false, share }>
+    data.extend_from_slice(&(royalties.creators().len() as u32).to_le_bytes());
+    for (address, share) in royalties.creators() {
+        data.extend_from_slice(address);
+        data.push(0);
+        data.push(*share);
+    }
 
-    // Account order for Bubblegum MintV1:
+    // Account order for Bubblegum MintToCollectionV1:
     // [tree_config (w), leaf_owner, leaf_delegate, merkle_tree (w),
-    //  payer (w,s), tree_creator_or_delegate (s), log_wrapper,
-    //  compression_program, system_program]
+    //  payer (w,s), tree_creator_or_delegate (s), collection_authority (s),
+    //  collection_authority_record_pda, collection_mint, collection_metadata (w),
+    //  collection_edition, bubblegum_signer, log_wrapper, compression_program,
+    //  token_metadata_program, system_program]
     let accounts = [
         InstructionAccount::writable(tree_config.address()),
         InstructionAccount::readonly(leaf_owner.address()),
@@ -416,8 +737,16 @@ pub fn cpi_bubblegum_mint_v1<'a>(
         InstructionAccount::writable(merkle_tree.address()),
         InstructionAccount::writable_signer(payer.address()),
         InstructionAccount::readonly_signer(tree_authority.address()),
+        InstructionAccount::readonly_signer(collection.authority.address()),
+        // No authority record: Bubblegum reads its own program ID as None
+        InstructionAccount::readonly(bubblegum_program.address()),
+        InstructionAccount::readonly(collection.mint.address()),
+        InstructionAccount::writable(collection.metadata.address()),
+        InstructionAccount::readonly(collection.edition.address()),
+        InstructionAccount::readonly(collection.bubblegum_signer.address()),
         InstructionAccount::readonly(log_wrapper.address()),
         InstructionAccount::readonly(compression_program.address()),
+        InstructionAccount::readonly(collection.token_metadata_program.address()),
         InstructionAccount::readonly(system_program.address()),
     ];
 
@@ -427,7 +756,7 @@ pub fn cpi_bubblegum_mint_v1<'a>(
         data: &data,
     };
 
-    pinocchio::cpi::invoke::<9>(
+    pinocchio::cpi::invoke_signed::<16>(
         &instruction,
         &[
             tree_config,
@@ -436,14 +765,189 @@ pub fn cpi_bubblegum_mint_v1<'a>(
             merkle_tree,
             payer,
             tree_authority,
+            collection.authority,
+            bubblegum_program, // authority record = None
+            collection.mint,
+            collection.metadata,
+            collection.edition,
+            collection.bubblegum_signer,
             log_wrapper,
             compression_program,
+            collection.token_metadata_program,
             system_program,
         ],
+        signers,
     )?;
     Ok(())
 }
 
+/// Leaf passed to `cpi_bubblegum_burn` / `cpi_bubblegum_transfer`, as read
+/// from the DAS API.
+pub struct CnftLeaf<'a> {
+    pub root: &'a [u8; 32],
+    pub data_hash: &'a [u8; 32],
+    pub creator_hash: &'a [u8; 32],
+    pub nonce: u64,
+    pub index: u32,
+}
+
+impl<'a> CnftLeaf<'a> {
+    /// Reads root + data_hash + creator_hash + nonce (u64) + index (u32).
+    pub fn parse(reader: &mut Reader<'a>) -> Result<Self, ProgramError> {
+        let root = reader.read_bytes::<32>()?;
+        let data_hash = reader.read_bytes::<32>()?;
+        let creator_hash = reader.read_bytes::<32>()?;
+        let nonce = reader.read_u64()?;
+        let index = reader.read_u32()?;
+        Ok(Self { root, data_hash, creator_hash, nonce, index })
+    }
+
+    /// Bubblegum instruction data: `discriminator` + the borsh-encoded leaf args.
+    fn instruction_data(&self, discriminator: [u8; 8]) -> [u8; 116] {
+        let mut data = [0u8; 116];
+        data[0..8].copy_from_slice(&discriminator);
+        data[8..40].copy_from_slice(self.root);
+        data[40..72].copy_from_slice(self.data_hash);
+        data[72..104].copy_from_slice(self.creator_hash);
+        data[104..112].copy_from_slice(&self.nonce.to_le_bytes());
+        data[112..116].copy_from_slice(&self.index.to_le_bytes());
+        data
+    }
+}
+
+/// leaf_owner / leaf_delegate meta keeping the signer flag it arrived with.
+#[inline(always)]
+fn leaf_authority_meta(account: &AccountView) -> InstructionAccount<'_> {
+    if account.is_signer() {
+        InstructionAccount::readonly_signer(account.address())
+    } else {
+        InstructionAccount::readonly(account.address())
+    }
+}
+
+/// CPI: Bubblegum Burn — manual CPI (no mpl-bubblegum dependency).
+/// Discriminator: SHA256("global:burn")[0..8]
+/// leaf_owner / leaf_delegate keep the signer flag they arrived with: Bubblegum
+/// requires one of them to have signed the outer transaction. `proof` is the
+/// merkle proof (minus the canopy), forwarded read-only after the fixed accounts.
+///
+/// NOTE: Uses Vec for the variable-length proof. Acceptable for cold-path
+/// (cNFT burning is an infrequent operation, not a hot-path transfer).
+#[inline(always)]
+#[allow(clippy::too_many_arguments)]
+pub fn cpi_bubblegum_burn<'a>(
+    tree_config: &'a AccountView,
+    leaf_owner: &'a AccountView,
+    leaf_delegate: &'a AccountView,
+    merkle_tree: &'a AccountView,
+    log_wrapper: &'a AccountView,
+    compression_program: &'a AccountView,
+    system_program: &'a AccountView,
+    bubblegum_program: &'a AccountView,
+    proof: &'a [AccountView],
+    leaf: &CnftLeaf,
+) -> Result<(), ProgramError> {
+    // Discriminator: SHA256("global:burn")[0..8]
+    // Pre-computed: [116, 110, 29, 56, 107, 219, 42, 93]
+    let data = leaf.instruction_data([116, 110, 29, 56, 107, 219, 42, 93]);
+
+    // Account order for Bubblegum Burn:
+    // [tree_config, leaf_owner, leaf_delegate, merkle_tree (w), log_wrapper,
+    //  compression_program, system_program, ...proof]
+    let mut account_metas = Vec::with_capacity(7 + proof.len());
+    account_metas.push(InstructionAccount::readonly(tree_config.address()));
+    account_metas.push(leaf_authority_meta(leaf_owner));
+    account_metas.push(leaf_authority_meta(leaf_delegate));
+    account_metas.push(InstructionAccount::writable(merkle_tree.address()));
+    account_metas.push(InstructionAccount::readonly(log_wrapper.address()));
+    account_metas.push(InstructionAccount::readonly(compression_program.address()));
+    account_metas.push(InstructionAccount::readonly(system_program.address()));
+    for node in proof {
+        account_metas.push(InstructionAccount::readonly(node.address()));
+    }
+
+    let instruction = InstructionView {
+        program_id: bubblegum_program.address(),
+        accounts: &account_metas,
+        data: &data,
+    };
+
+    let mut account_views: Vec<&AccountView> = Vec::with_capacity(8 + proof.len());
+    account_views.push(tree_config);
+    account_views.push(leaf_owner);
+    account_views.push(leaf_delegate);
+    account_views.push(merkle_tree);
+    account_views.push(log_wrapper);
+    account_views.push(compression_program);
+    account_views.push(system_program);
+    account_views.extend(proof.iter());
+    account_views.push(bubblegum_program);
+
+    pinocchio::cpi::invoke_signed_with_slice(&instruction, &account_views, &[])?;
+    Ok(())
+}
+
+/// CPI: Bubblegum Transfer — manual CPI (no mpl-bubblegum dependency).
+/// Discriminator: SHA256("global:transfer")[0..8]
+/// Same signer and proof handling as `cpi_bubblegum_burn`; the leaf is
+/// re-hashed under `new_leaf_owner` with the delegate reset to it.
+#[inline(always)]
+#[allow(clippy::too_many_arguments)]
+pub fn cpi_bubblegum_transfer<'a>(
+    tree_config: &'a AccountView,
+    leaf_owner: &'a AccountView,
+    leaf_delegate: &'a AccountView,
+    new_leaf_owner: &'a AccountView,
+    merkle_tree: &'a AccountView,
+    log_wrapper: &'a AccountView,
+    compression_program: &'a AccountView,
+    system_program: &'a AccountView,
+    bubblegum_program: &'a AccountView,
+    proof: &'a [AccountView],
+    leaf: &CnftLeaf,
+) -> Result<(), ProgramError> {
+    // Discriminator: SHA256("global:transfer")[0..8]
+    // Pre-computed: [163, 52, 200, 231, 140, 3, 69, 186]
+    let data = leaf.instruction_data([163, 52, 200, 231, 140, 3, 69, 186]);
+
+    // Account order for Bubblegum Transfer:
+    // [tree_config, leaf_owner, leaf_delegate, new_leaf_owner, merkle_tree (w),
+    //  log_wrapper, compression_program, system_program, ...proof]
+    let mut account_metas = Vec::with_capacity(8 + proof.len());
+    account_metas.push(InstructionAccount::readonly(tree_config.address()));
+    account_metas.push(leaf_authority_meta(leaf_owner));
+    account_metas.push(leaf_authority_meta(leaf_delegate));
+    account_metas.push(InstructionAccount::readonly(new_leaf_owner.address()));
+    account_metas.push(InstructionAccount::writable(merkle_tree.address()));
+    account_metas.push(InstructionAccount::readonly(log_wrapper.address()));
+    account_metas.push(InstructionAccount::readonly(compression_program.address()));
+    account_metas.push(InstructionAccount::readonly(system_program.address()));
+    for node in proof {
+        account_metas.push(InstructionAccount::readonly(node.address()));
+    }
+
+    let instruction = InstructionView {
+        program_id: bubblegum_program.address(),
+        accounts: &account_metas,
+        data: &data,
+    };
+
+    let mut account_views: Vec<&AccountView> = Vec::with_capacity(9 + proof.len());
+    account_views.push(tree_config);
+    account_views.push(leaf_owner);
+    account_views.push(leaf_delegate);
+    account_views.push(new_leaf_owner);
+    account_views.push(merkle_tree);
+    account_views.push(log_wrapper);
+    account_views.push(compression_program);
+    account_views.push(system_program);
+    account_views.extend(proof.iter());
+    account_views.push(bubblegum_program);
+
+    pinocchio::cpi::invoke_signed_with_slice(&instruction, &account_views, &[])?;
+    Ok(())
+}
+
 /// CPI: Token-2022 MetadataPointer initialization.
 /// Must be called BEFORE InitializeMint2.
 /// Sets metadata_address = mint itself (self-referential).
@@ -482,6 +986,54 @@ pub fn cpi_initialize_metadata_pointer<'a>(
     Ok(())
 }
 
+/// CPI: Token-2022 InitializeNonTransferableMint (instruction index 32).
+/// Must be called BEFORE InitializeMint2. Token accounts for the mint get
+/// NonTransferableAccount + ImmutableOwner from the ATA program, and every
+/// Transfer / TransferChecked from them fails inside Token-2022.
+#[inline(always)]
+pub fn cpi_initialize_non_transferable_mint<'a>(
+    mint: &'a AccountView,
+    token_program: &'a AccountView,
+) -> Result<(), ProgramError> {
+    let data = [32u8];
+    let accounts = [InstructionAccount::writable(mint.address())];
+
+    let instruction = InstructionView {
+        program_id: token_program.address(),
+        accounts: &accounts,
+        data: &data,
+    };
+
+    pinocchio::cpi::invoke::<2>(&instruction, &[mint, token_program])?;
+    Ok(())
+}
+
+/// CPI: Token-2022 InitializeMintCloseAuthority (instruction index 25).
+/// Must be called BEFORE InitializeMint2. `close_authority` may later close
+/// the mint with CloseAccount once its supply is 0.
+#[inline(always)]
+pub fn cpi_initialize_mint_close_authority<'a>(
+    mint: &'a AccountView,
+    close_authority: &Address,
+    token_program: &'a AccountView,
+) -> Result<(), ProgramError> {
+    // Data: [25u8, 1u8 (COption::Some), close_authority (32 bytes)]
+    let mut data = [0u8; 34];
+    data[0] = 25;
+    data[1] = 1;
+    data[2..34].copy_from_slice(close_authority.as_ref());
+    let accounts = [InstructionAccount::writable(mint.address())];
+
+    let instruction = InstructionView {
+        program_id: token_program.address(),
+        accounts: &accounts,
+        data: &data,
+    };
+
+    pinocchio::cpi::invoke::<2>(&instruction, &[mint, token_program])?;
+    Ok(())
+}
+
 // NOTE: CPI helpers cannot be unit-tested without the Solana runtime.
 // They are integration-tested via Mollusk in tests/test_transfers.rs,
 // tests/test_split_burns.rs, and tests/test_cold_path.rs. The function
@@ -490,22 +1042,58 @@ pub fn cpi_initialize_metadata_pointer<'a>(
 
 #[cfg(test)]
 mod tests {
-    /// Verify the hardcoded Bubblegum MintV1 discriminator matches
-    /// SHA256("global:mint_v1")[0..8] — same convention as our 19 instructions.
+    /// Verify the hardcoded Bubblegum MintToCollectionV1 discriminator matches
+    /// SHA256("global:mint_to_collection_v1")[0..8] — same convention as our 19 instructions.
     #[test]
-    fn test_bubblegum_mint_v1_discriminator_matches_sha256() {
+    fn test_bubblegum_mint_to_collection_v1_discriminator_matches_sha256() {
         use sha2::{Sha256, Digest};
 
-        let hash = Sha256::digest(b"global:mint_v1");
+        let hash = Sha256::digest(b"global:mint_to_collection_v1");
         let expected: [u8; 8] = hash[0..8].try_into().unwrap();
-        let hardcoded: [u8; 8] = [145, 98, 192, 118, 184, 147, 118, 104];
+        let hardcoded: [u8; 8] = [153, 18, 178, 47, 197, 158, 86, 15];
         assert_eq!(
             hardcoded, expected,
-            "Bubblegum MintV1 discriminator mismatch: hardcoded={:?}, SHA256={:?}",
+            "Bubblegum MintToCollectionV1 discriminator mismatch: hardcoded={:?}, SHA256={:?}",
             hardcoded, expected
         );
     }
 
+    /// Verify the hardcoded Bubblegum Burn discriminator matches SHA256("global:burn")[0..8].
+    #[test]
+    fn test_bubblegum_burn_discriminator_matches_sha256() {
+        use sha2::{Sha256, Digest};
+
+        let hash = Sha256::digest(b"global:burn");
+        let expected: [u8; 8] = hash[0..8].try_into().unwrap();
+        assert_eq!([116, 110, 29, 56, 107, 219, 42, 93], expected);
+    }
+
+    /// Verify the hardcoded Bubblegum Transfer discriminator matches SHA256("global:transfer")[0..8].
+    #[test]
+    fn test_bubblegum_transfer_discriminator_matches_sha256() {
+        use sha2::{Sha256, Digest};
+
+        let hash = Sha256::digest(b"global:transfer");
+        let expected: [u8; 8] = hash[0..8].try_into().unwrap();
+        assert_eq!([163, 52, 200, 231, 140, 3, 69, 186], expected);
+    }
+
+    #[test]
+    fn test_cnft_leaf_parse_and_instruction_data() {
+        let mut data = [1u8; 32].to_vec();
+        data.extend_from_slice(&[2u8; 32]);
+        data.extend_from_slice(&[3u8; 32]);
+        data.extend_from_slice(&9u64.to_le_bytes());
+        data.extend_from_slice(&5u32.to_le_bytes());
+
+        let leaf = super::CnftLeaf::parse(&mut super::Reader::new(&data)).unwrap();
+        assert_eq!((leaf.nonce, leaf.index), (9, 5));
+        let ix_data = leaf.instruction_data([8u8; 8]);
+        assert_eq!(&ix_data[0..8], &[8u8; 8]);
+        assert_eq!(&ix_data[8..], &data[..]);
+        assert!(super::CnftLeaf::parse(&mut super::Reader::new(&data[..data.len() - 1])).is_err());
+    }
+
     /// Verify the metadata Initialize discriminator.
     #[test]
     fn test_metadata_initialize_discriminator() {
@@ -519,6 +1107,29 @@ mod tests {
         assert_eq!(disc[7], 141);
     }
 
+    /// `Field::Key` UpdateField payload: variant 3 followed by key and value strings.
+    #[test]
+    fn test_update_metadata_key_data_layout() {
+        let data = super::build_update_metadata_key_data("site", "zupy.com");
+        assert_eq!(&data[0..8], &super::UPDATE_FIELD_DISC);
+        assert_eq!(data[8], super::METADATA_FIELD_KEY);
+        assert_eq!(&data[9..13], &4u32.to_le_bytes());
+        assert_eq!(&data[13..17], b"site");
+        assert_eq!(&data[17..21], &8u32.to_le_bytes());
+        assert_eq!(&data[21..], b"zupy.com");
+    }
+
+    /// UpdateAuthority discriminator is spl_discriminator's
+    /// SHA256("spl_token_metadata_interface:update_the_authority")[0..8].
+    #[test]
+    fn test_clear_update_authority_data() {
+        use sha2::{Digest, Sha256};
+        let hash = Sha256::digest(b"spl_token_metadata_interface:update_the_authority");
+        let data = super::build_clear_update_authority_data();
+        assert_eq!(&data[0..8], &hash[0..8]);
+        assert!(data[8..].iter().all(|b| *b == 0), "new_authority = None");
+    }
+
     /// Verify the metadata UpdateField discriminator.
     #[test]
     fn test_metadata_update_field_discriminator() {
@@ -0,0 +1,83 @@
+//! Shared logic for the escrow instruction family
+//! (`create_escrow`, `claim_escrow`, `cancel_escrow`).
+//!
+//! The escrow PDA `[ESCROW_SEED, escrow_id]` holds both the `EscrowState` data and,
+//! as compressed owner, the escrowed tokens. Releases are signed with the escrow
+//! PDA seeds using the stored bump.
+
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address};
+
+use crate::constants::ESCROW_SEED;
+use crate::error::ZupyTokenError;
+use crate::helpers::account_checks::assert_discriminator;
+use crate::helpers::compressed_accounts::cpi_compressed_transfer;
+use crate::helpers::pda::validate_pda_with_seeds;
+use crate::state::escrow_state::{
+    EscrowState, ESCROW_STATE_DISCRIMINATOR, ESCROW_STATE_SIZE, ESCROW_STATUS_PENDING,
+};
+
+/// Validates an existing escrow account and that it is still pending.
+///
+/// Checks (in order): owned by our program, size, discriminator, stored
+/// `escrow_id` matches `escrow_id`, PDA `[ESCROW_SEED, escrow_id, bump]` via the
+/// stored bump, then status == Pending → `EscrowAlreadySettled`.
+pub fn validate_pending_escrow(
+    program_id: &Address,
+    escrow_account: &AccountView,
+    escrow_id: u64,
+) -> Result<(), ProgramError> {
+    if !escrow_account.owned_by(program_id) || escrow_account.data_len() < ESCROW_STATE_SIZE {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let escrow = EscrowState::from_slice(unsafe { escrow_account.borrow_unchecked() });
+    assert_discriminator(escrow.discriminator(), &ESCROW_STATE_DISCRIMINATOR)?;
+    if escrow.escrow_id() != escrow_id {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let escrow_id_bytes = escrow_id.to_le_bytes();
+    validate_pda_with_seeds(
+        escrow_account.address(),
+        &[ESCROW_SEED, &escrow_id_bytes, &[escrow.bump()]],
+        program_id,
+    )?;
+    if escrow.status() != ESCROW_STATUS_PENDING {
+        return Err(ZupyTokenError::EscrowAlreadySettled.into());
+    }
+    Ok(())
+}
+
+/// CPI: moves the escrowed compressed balance to `destination` (Path B),
+/// signed by the escrow PDA.
+#[allow(clippy::too_many_arguments)]
+pub fn release_escrow_compressed(
+    compressed_token_program: &AccountView,
+    fee_payer: &AccountView,
+    escrow_account: &AccountView,
+    destination: &AccountView,
+    system_program: &AccountView,
+    escrow_id: u64,
+    escrow_bump: u8,
+    amount: u64,
+) -> Result<(), ProgramError> {
+    let escrow_id_bytes = escrow_id.to_le_bytes();
+    let bump_bytes = [escrow_bump];
+    let signer_seeds: [Seed; 3] = [
+        Seed::from(ESCROW_SEED),
+        Seed::from(escrow_id_bytes.as_ref()),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    let signer = Signer::from(&signer_seeds);
+
+    cpi_compressed_transfer(
+        compressed_token_program,
+        fee_payer,
+        escrow_account, // source
+        destination,
+        escrow_account, // authority (escrow PDA signs)
+        system_program,
+        amount,
+        &[signer],
+    )
+}
@@ -0,0 +1,437 @@
+//! Structured supply-change logs for the indexer.
+//!
+//! Each supply-affecting instruction logs one line
+//! `zupy_evt:{kind}:{amount}:{memo_tail}` right before its token CPI, after
+//! every validation has passed, where `memo_tail` is the memo's `source_id`
+//! segment (`"zupy:v1:mint:batch"` → `batch`). The indexer matches on this
+//! line instead of decoding inner Token-2022 / Light instructions.
+//!
+//! Card tier changes and coupon redemptions use the same shape:
+//! `zupy_evt:card_tier:{tier}:{user_ksuid}` and
+//! `zupy_evt:coupon_redeem:{company_id}:{coupon_ksuid}`.
+//!
+//! Formatting uses a fixed stack buffer: no heap allocation on this path.
+//! A tail that does not fit is truncated on a char boundary.
+//!
+//! ## Anchor events
+//!
+//! The Anchor-era indexer reads `Program data: <base64>` lines carrying an
+//! 8-byte `sha256("event:<Name>")[..8]` discriminator followed by the Borsh
+//! struct ([`TransferEvent`], [`MintEvent`], [`BurnEvent`], [`WithdrawEvent`]).
+//! [`emit_anchor_event`] serializes into a stack buffer and hands it to
+//! `sol_log_data`, exactly like Anchor's `emit!`: the runtime base64-encodes it
+//! under the `Program data:` prefix (a `sol_log` line would read
+//! `Program log: Program data: ...`, which the indexer does not match).
+//! Compiled out without the `anchor-events` feature (on by default).
+
+use crate::helpers::bytes::read_array;
+
+/// Prefix of every event line.
+pub const EVENT_PREFIX: &str = "zupy_evt";
+
+/// Upper bound of one event line in bytes.
+pub const MAX_EVENT_LEN: usize = 96;
+
+/// Kind of supply change; the second segment of the event line.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SupplyEvent {
+    /// MintTo via `mint_tokens` / `mint_to_pool`.
+    Mint,
+    /// Treasury → pool transfer via `treasury_restock_pool`.
+    Restock,
+    /// Token-2022 or compressed burn.
+    Burn,
+}
+
+impl SupplyEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SupplyEvent::Mint => "mint",
+            SupplyEvent::Restock => "restock",
+            SupplyEvent::Burn => "burn",
+        }
+    }
+}
+
+/// Kind segment of the card tier event.
+pub const CARD_TIER_EVENT: &str = "card_tier";
+
+/// Kind segment of the coupon redemption event.
+pub const COUPON_REDEEM_EVENT: &str = "coupon_redeem";
+
+/// Logs `zupy_evt:{kind}:{amount}:{memo_tail}`.
+#[inline(always)]
+pub fn emit_supply_event(kind: SupplyEvent, amount: u64, memo: &str) {
+    let mut buf = [0u8; MAX_EVENT_LEN];
+    let len = format_supply_event(&mut buf, kind, amount, memo);
+    log_event(&buf[..len]);
+}
+
+/// Logs `zupy_evt:card_tier:{tier}:{user_ksuid}`.
+#[inline(always)]
+pub fn emit_card_tier_event(tier: u8, user_ksuid: &[u8; 27]) {
+    let mut buf = [0u8; MAX_EVENT_LEN];
+    let ksuid = core::str::from_utf8(user_ksuid).unwrap_or("");
+    let len = format_event(&mut buf, CARD_TIER_EVENT, tier as u64, ksuid);
+    log_event(&buf[..len]);
+}
+
+/// Logs `zupy_evt:coupon_redeem:{company_id}:{coupon_ksuid}`.
+#[inline(always)]
+pub fn emit_coupon_redeem_event(company_id: u64, coupon_ksuid: &[u8; 27]) {
+    let mut buf = [0u8; MAX_EVENT_LEN];
+    let ksuid = core::str::from_utf8(coupon_ksuid).unwrap_or("");
+    let len = format_event(&mut buf, COUPON_REDEEM_EVENT, company_id, ksuid);
+    log_event(&buf[..len]);
+}
+
+#[inline(always)]
+fn log_event(line: &[u8]) {
+    // Only whole `&str` pieces and ASCII digits are written
+    if let Ok(line) = core::str::from_utf8(line) {
+        crate::helpers::log::sol_log(line);
+    }
+}
+
+/// Writes the supply event line into `buf`, returning its length.
+pub fn format_supply_event(
+    buf: &mut [u8; MAX_EVENT_LEN],
+    kind: SupplyEvent,
+    amount: u64,
+    memo: &str,
+) -> usize {
+    let tail = memo.splitn(4, ':').nth(3).unwrap_or("");
+    format_event(buf, kind.as_str(), amount, tail)
+}
+
+/// Writes `zupy_evt:{kind}:{value}:{tail}` into `buf`, returning its length.
+pub fn format_event(buf: &mut [u8; MAX_EVENT_LEN], kind: &str, value: u64, tail: &str) -> usize {
+    let mut len = 0;
+    len = push(buf, len, EVENT_PREFIX);
+    len = push(buf, len, ":");
+    len = push(buf, len, kind);
+    len = push(buf, len, ":");
+
+    // value as decimal (u64::MAX has 20 digits)
+    let mut digits = [0u8; 20];
+    let mut n = value;
+    let mut i = digits.len();
+    loop {
+        i -= 1;
+        digits[i] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    buf[len..len + digits.len() - i].copy_from_slice(&digits[i..]);
+    len += digits.len() - i;
+
+    len = push(buf, len, ":");
+    let mut end = tail.len().min(MAX_EVENT_LEN - len);
+    while !tail.is_char_boundary(end) {
+        end -= 1;
+    }
+    push(buf, len, &tail[..end])
+}
+
+#[inline(always)]
+fn push(buf: &mut [u8; MAX_EVENT_LEN], len: usize, s: &str) -> usize {
+    buf[len..len + s.len()].copy_from_slice(s.as_bytes());
+    len + s.len()
+}
+
+// ── Anchor events ──────────────────────────────────────────────────────────
+
+/// Largest serialized Anchor event (discriminator + [`WithdrawEvent`]).
+pub const MAX_ANCHOR_EVENT_LEN: usize = 8 + WithdrawEvent::LEN;
+
+/// An Anchor `#[event]` struct: fixed-size Borsh body behind an 8-byte discriminator.
+pub trait AnchorEvent: Sized {
+    /// `sha256("event:<Name>")[..8]`.
+    const DISCRIMINATOR: [u8; 8];
+    /// Borsh body length in bytes.
+    const LEN: usize;
+    /// Writes the Borsh body into `out` (`Self::LEN` bytes).
+    fn write(&self, out: &mut [u8]);
+    /// Reads the Borsh body back; `None` unless `data` is exactly `Self::LEN` bytes.
+    fn read(data: &[u8]) -> Option<Self>;
+}
+
+/// Token movement between two accounts (`transfer_from_pool`, `return_to_pool`,
+/// `transfer_company_to_user`, `transfer_user_to_company`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransferEvent {
+    pub from: [u8; 32],
+    pub to: [u8; 32],
+    pub amount: u64,
+}
+
+/// Supply increase (`mint_tokens`, `mint_to_pool`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MintEvent {
+    pub destination: [u8; 32],
+    pub amount: u64,
+}
+
+/// Supply decrease (`burn_tokens`, `burn_from_company_pda`, `burn_pool_tokens`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BurnEvent {
+    pub source: [u8; 32],
+    pub amount: u64,
+}
+
+/// Compressed balance paid out to an external wallet: `amount` reaches
+/// `destination`, `fee` goes to the incentive pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WithdrawEvent {
+    pub source: [u8; 32],
+    pub destination: [u8; 32],
+    pub amount: u64,
+    pub fee: u64,
+}
+
+#[inline(always)]
+fn read_key(data: &[u8], offset: usize) -> [u8; 32] {
+    *read_array::<32>(data, offset)
+}
+
+#[inline(always)]
+fn read_amount(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(*read_array::<8>(data, offset))
+}
+
+impl AnchorEvent for TransferEvent {
+    const DISCRIMINATOR: [u8; 8] = [100, 10, 46, 113, 8, 28, 179, 125];
+    const LEN: usize = 72;
+
+    fn write(&self, out: &mut [u8]) {
+        out[0..32].copy_from_slice(&self.from);
+        out[32..64].copy_from_slice(&self.to);
+        out[64..72].copy_from_slice(&self.amount.to_le_bytes());
+    }
+
+    fn read(data: &[u8]) -> Option<Self> {
+        (data.len() == Self::LEN).then(|| Self {
+            from: read_key(data, 0),
+            to: read_key(data, 32),
+            amount: read_amount(data, 64),
+        })
+    }
+}
+
+impl AnchorEvent for MintEvent {
+    const DISCRIMINATOR: [u8; 8] = [197, 144, 146, 149, 66, 164, 95, 16];
+    const LEN: usize = 40;
+
+    fn write(&self, out: &mut [u8]) {
+        out[0..32].copy_from_slice(&self.destination);
+        out[32..40].copy_from_slice(&self.amount.to_le_bytes());
+    }
+
+    fn read(data: &[u8]) -> Option<Self> {
+        (data.len() == Self::LEN).then(|| Self {
+            destination: read_key(data, 0),
+            amount: read_amount(data, 32),
+        })
+    }
+}
+
+impl AnchorEvent for BurnEvent {
+    const DISCRIMINATOR: [u8; 8] = [33, 89, 47, 117, 82, 124, 238, 250];
+    const LEN: usize = 40;
+
+    fn write(&self, out: &mut [u8]) {
+        out[0..32].copy_from_slice(&self.source);
+        out[32..40].copy_from_slice(&self.amount.to_le_bytes());
+    }
+
+    fn read(data: &[u8]) -> Option<Self> {
+        (data.len() == Self::LEN).then(|| Self {
+            source: read_key(data, 0),
+            amount: read_amount(data, 32),
+        })
+    }
+}
+
+impl AnchorEvent for WithdrawEvent {
+    const DISCRIMINATOR: [u8; 8] = [22, 9, 133, 26, 160, 44, 71, 192];
+    const LEN: usize = 80;
+
+    fn write(&self, out: &mut [u8]) {
+        out[0..32].copy_from_slice(&self.source);
+        out[32..64].copy_from_slice(&self.destination);
+        out[64..72].copy_from_slice(&self.amount.to_le_bytes());
+        out[72..80].copy_from_slice(&self.fee.to_le_bytes());
+    }
+
+    fn read(data: &[u8]) -> Option<Self> {
+        (data.len() == Self::LEN).then(|| Self {
+            source: read_key(data, 0),
+            destination: read_key(data, 32),
+            amount: read_amount(data, 64),
+            fee: read_amount(data, 72),
+        })
+    }
+}
+
+/// Writes `discriminator + body` into `buf`, returning its length.
+pub fn encode_anchor_event<E: AnchorEvent>(event: &E, buf: &mut [u8; MAX_ANCHOR_EVENT_LEN]) -> usize {
+    buf[..8].copy_from_slice(&E::DISCRIMINATOR);
+    event.write(&mut buf[8..8 + E::LEN]);
+    8 + E::LEN
+}
+
+/// Decodes `discriminator + body` (the bytes of one `Program data:` line).
+pub fn decode_anchor_event<E: AnchorEvent>(data: &[u8]) -> Option<E> {
+    if data.len() < 8 || data[..8] != E::DISCRIMINATOR {
+        return None;
+    }
+    E::read(&data[8..])
+}
+
+/// Logs `event` as an Anchor `Program data:` line (no-op without `anchor-events`).
+#[inline(always)]
+pub fn emit_anchor_event<E: AnchorEvent>(event: &E) {
+    #[cfg(feature = "anchor-events")]
+    {
+        let mut buf = [0u8; MAX_ANCHOR_EVENT_LEN];
+        let len = encode_anchor_event(event, &mut buf);
+        crate::helpers::log::sol_log_data(&[&buf[..len]]);
+    }
+    #[cfg(not(feature = "anchor-events"))]
+    let _ = event;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(kind: SupplyEvent, amount: u64, memo: &str) -> String {
+        let mut buf = [0u8; MAX_EVENT_LEN];
+        let len = format_supply_event(&mut buf, kind, amount, memo);
+        String::from_utf8(buf[..len].to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_event_line_format() {
+        assert_eq!(format(SupplyEvent::Mint, 1_000_000, "zupy:v1:mint:batch"), "zupy_evt:mint:1000000:batch");
+        assert_eq!(format(SupplyEvent::Restock, 5, "zupy:v1:restock:manual"), "zupy_evt:restock:5:manual");
+        assert_eq!(format(SupplyEvent::Burn, 0, "zupy:v1:burn:42"), "zupy_evt:burn:0:42");
+    }
+
+    #[test]
+    fn test_amount_extremes() {
+        assert_eq!(
+            format(SupplyEvent::Mint, u64::MAX, "zupy:v1:mint:x"),
+            "zupy_evt:mint:18446744073709551615:x"
+        );
+    }
+
+    #[test]
+    fn test_tail_keeps_inner_colons() {
+        assert_eq!(format(SupplyEvent::Burn, 7, "zupy:v1:refund:burn:9"), "zupy_evt:burn:7:burn:9");
+        assert_eq!(format(SupplyEvent::Burn, 7, "no-segments"), "zupy_evt:burn:7:");
+    }
+
+    #[test]
+    fn test_card_tier_event_format() {
+        let mut buf = [0u8; MAX_EVENT_LEN];
+        let len = format_event(&mut buf, CARD_TIER_EVENT, 2, "2NRjKcGrXHKtGVjMXV7qptaXY2A");
+        assert_eq!(&buf[..len], b"zupy_evt:card_tier:2:2NRjKcGrXHKtGVjMXV7qptaXY2A");
+    }
+
+    #[test]
+    fn test_coupon_redeem_event_fits() {
+        // Longest case: u64::MAX company_id + 27-byte ksuid
+        let mut buf = [0u8; MAX_EVENT_LEN];
+        let len = format_event(&mut buf, COUPON_REDEEM_EVENT, u64::MAX, "1ujsszwN8NRY24YaXiTIE2VWDTS");
+        assert_eq!(
+            &buf[..len],
+            b"zupy_evt:coupon_redeem:18446744073709551615:1ujsszwN8NRY24YaXiTIE2VWDTS"
+        );
+    }
+
+    #[test]
+    fn test_long_tail_truncated_on_char_boundary() {
+        let memo = format!("zupy:v1:mint:{}", "é".repeat(100));
+        let line = format(SupplyEvent::Mint, u64::MAX, &memo);
+        assert!(line.len() <= MAX_EVENT_LEN);
+        assert!(line.starts_with("zupy_evt:mint:18446744073709551615:é"));
+        assert!(line.ends_with('é'));
+    }
+
+    // ── Anchor events ───────────────────────────────────────────────────
+
+    /// Standard base64 (what the runtime writes after `Program data: `).
+    fn base64_decode(line: &str) -> Vec<u8> {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut out = Vec::new();
+        let (mut acc, mut bits) = (0u32, 0);
+        for c in line.bytes().filter(|&c| c != b'=') {
+            acc = (acc << 6) | ALPHABET.iter().position(|&a| a == c).unwrap() as u32;
+            bits += 6;
+            if bits >= 8 {
+                bits -= 8;
+                out.push((acc >> bits) as u8);
+            }
+        }
+        out
+    }
+
+    fn decode_line<E: AnchorEvent>(line: &str) -> Option<E> {
+        decode_anchor_event(&base64_decode(line.strip_prefix("Program data: ")?))
+    }
+
+    fn anchor_disc(name: &str) -> [u8; 8] {
+        use sha2::{Digest, Sha256};
+        Sha256::digest(format!("event:{}", name).as_bytes())[..8].try_into().unwrap()
+    }
+
+    #[test]
+    fn test_anchor_event_discriminators() {
+        assert_eq!(TransferEvent::DISCRIMINATOR, anchor_disc("TransferEvent"));
+        assert_eq!(MintEvent::DISCRIMINATOR, anchor_disc("MintEvent"));
+        assert_eq!(BurnEvent::DISCRIMINATOR, anchor_disc("BurnEvent"));
+        assert_eq!(WithdrawEvent::DISCRIMINATOR, anchor_disc("WithdrawEvent"));
+    }
+
+    #[test]
+    fn test_decode_program_data_log() {
+        // Lines as an Anchor client would see them (generated off-chain with Borsh)
+        let transfer: TransferEvent = decode_line(
+            "Program data: ZAoucQgcs30BAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICQEIPAAAAAAA=",
+        )
+        .unwrap();
+        assert_eq!(transfer, TransferEvent { from: [1; 32], to: [2; 32], amount: 1_000_000 });
+
+        let withdraw: WithdrawEvent = decode_line(
+            "Program data: FgmFGqAsR8ADAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE3gMAAAAAAAAKAAAAAAAAAA==",
+        )
+        .unwrap();
+        assert_eq!(withdraw, WithdrawEvent { source: [3; 32], destination: [4; 32], amount: 990, fee: 10 });
+    }
+
+    fn round_trip<E: AnchorEvent + PartialEq + core::fmt::Debug>(event: E) {
+        let mut buf = [0u8; MAX_ANCHOR_EVENT_LEN];
+        let len = encode_anchor_event(&event, &mut buf);
+        assert_eq!(len, 8 + E::LEN);
+        assert_eq!(decode_anchor_event::<E>(&buf[..len]), Some(event));
+        assert_eq!(decode_anchor_event::<E>(&buf[..len - 1]), None);
+    }
+
+    #[test]
+    fn test_anchor_event_round_trip() {
+        round_trip(TransferEvent { from: [7; 32], to: [8; 32], amount: u64::MAX });
+        round_trip(MintEvent { destination: [9; 32], amount: 42 });
+        round_trip(BurnEvent { source: [10; 32], amount: 1 });
+        round_trip(WithdrawEvent { source: [11; 32], destination: [12; 32], amount: 5, fee: u64::MAX });
+    }
+
+    #[test]
+    fn test_decode_rejects_other_event() {
+        let mut buf = [0u8; MAX_ANCHOR_EVENT_LEN];
+        let len = encode_anchor_event(&MintEvent { destination: [1; 32], amount: 1 }, &mut buf);
+        assert_eq!(decode_anchor_event::<BurnEvent>(&buf[..len]), None);
+    }
+}
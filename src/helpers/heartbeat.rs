@@ -0,0 +1,88 @@
+//! Last-operation heartbeats in TokenState.
+//!
+//! `mint_tokens` / `mint_to_pool`, the transfer family and the withdraw-to-
+//! external instructions stamp `Clock::unix_timestamp` into the matching v2
+//! TokenState field (`last_mint_ts`, `last_transfer_ts`, `last_withdraw_ts`)
+//! once their checks pass. Most of them take token_state read-only; the stamp
+//! is then skipped rather than requiring a writable account, as it is on v1
+//! accounts, which have no room for it. The heartbeat never fails an operation.
+
+use pinocchio::sysvars::Sysvar as _;
+use pinocchio::{AccountView, ProgramResult};
+
+use crate::state::token_state::{LastOp, TokenStateMut, TOKEN_STATE_V2_SIZE};
+
+/// Stamps `op`'s last-operation timestamp into `token_state_account` (already
+/// validated by the caller). The Clock is only read when the stamp happens.
+pub fn stamp_last_op(token_state_account: &AccountView, op: LastOp) -> ProgramResult {
+    if let Some(mut state) = heartbeat_target(token_state_account) {
+        let clock = pinocchio::sysvars::clock::Clock::get()?;
+        state.set_last_op_ts(op, clock.unix_timestamp);
+    }
+    Ok(())
+}
+
+/// Writable v2 TokenState, or `None` when the heartbeat is skipped.
+#[inline(always)]
+fn heartbeat_target(token_state_account: &AccountView) -> Option<TokenStateMut<'_>> {
+    if !token_state_account.is_writable() || token_state_account.data_len() < TOKEN_STATE_V2_SIZE {
+        return None;
+    }
+    Some(TokenStateMut::from_slice(unsafe { token_state_account.borrow_unchecked_mut() }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::mem::size_of;
+    use pinocchio::account::{RuntimeAccount, NOT_BORROWED};
+    use pinocchio::Address;
+
+    use crate::state::token_state::{TokenState, TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION};
+
+    /// RuntimeAccount buffer holding a zeroed TokenState of `data_len` bytes.
+    fn make_token_state(data_len: usize, is_writable: bool) -> Vec<u64> {
+        let header_size = size_of::<RuntimeAccount>();
+        let mut buf = vec![0u64; (header_size + data_len).div_ceil(8)];
+        let raw = buf.as_mut_ptr() as *mut RuntimeAccount;
+        unsafe {
+            (*raw).borrow_state = NOT_BORROWED;
+            (*raw).is_writable = is_writable as u8;
+            (*raw).address = Address::from([9u8; 32]);
+            (*raw).data_len = data_len as u64;
+        }
+        buf
+    }
+
+    fn view(buf: &mut [u64]) -> AccountView {
+        unsafe { AccountView::new_unchecked(buf.as_mut_ptr() as *mut RuntimeAccount) }
+    }
+
+    #[test]
+    fn test_writable_v2_is_stamped() {
+        let mut buf = make_token_state(TOKEN_STATE_V2_SIZE, true);
+        let account = view(&mut buf);
+        heartbeat_target(&account).unwrap().set_last_op_ts(LastOp::Transfer, 1_700_000_000);
+
+        let state = TokenState::from_slice(unsafe { account.borrow_unchecked() });
+        assert_eq!(state.last_transfer_ts(), 1_700_000_000);
+        assert_eq!(state.last_mint_ts(), 0);
+        assert_eq!(state.last_withdraw_ts(), 0);
+    }
+
+    #[test]
+    fn test_skipped_when_read_only_or_v1() {
+        let mut read_only = make_token_state(TOKEN_STATE_V2_SIZE, false);
+        assert!(heartbeat_target(&view(&mut read_only)).is_none());
+
+        let mut v1 = make_token_state(TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION, true);
+        assert!(heartbeat_target(&view(&mut v1)).is_none());
+    }
+
+    #[test]
+    fn test_stamp_skipped_without_clock_read() {
+        // Off-chain there is no Clock sysvar: a skipped stamp must not touch it
+        let mut read_only = make_token_state(TOKEN_STATE_V2_SIZE, false);
+        assert_eq!(stamp_last_op(&view(&mut read_only), LastOp::Withdraw), Ok(()));
+    }
+}
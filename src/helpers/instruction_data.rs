@@ -1,5 +1,8 @@
 use pinocchio::error::ProgramError;
 
+use crate::error::ZupyTokenError;
+use crate::helpers::bytes::read_array;
+
 /// Parse a u64 (8-byte little-endian) from instruction data at the given offset.
 /// Returns `InvalidInstructionData` if not enough bytes remain.
 #[inline(always)]
@@ -9,7 +12,33 @@ pub fn parse_u64(data: &[u8], offset: usize) -> Result<u64, ProgramError> {
         return Err(ProgramError::InvalidInstructionData);
     }
     Ok(u64::from_le_bytes(
-        data[offset..offset + 8].try_into().unwrap(),
+        *read_array::<8>(data, offset),
+    ))
+}
+
+/// Parse a u16 (2-byte little-endian) from instruction data at the given offset.
+/// Returns `InvalidInstructionData` if not enough bytes remain.
+#[inline(always)]
+pub fn parse_u16(data: &[u8], offset: usize) -> Result<u16, ProgramError> {
+    let end = offset.checked_add(2).ok_or(ProgramError::InvalidInstructionData)?;
+    if data.len() < end {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    Ok(u16::from_le_bytes(
+        *read_array::<2>(data, offset),
+    ))
+}
+
+/// Parse a u32 (4-byte little-endian) from instruction data at the given offset.
+/// Returns `InvalidInstructionData` if not enough bytes remain.
+#[inline(always)]
+pub fn parse_u32(data: &[u8], offset: usize) -> Result<u32, ProgramError> {
+    let end = offset.checked_add(4).ok_or(ProgramError::InvalidInstructionData)?;
+    if data.len() < end {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    Ok(u32::from_le_bytes(
+        *read_array::<4>(data, offset),
     ))
 }
 
@@ -44,7 +73,7 @@ pub fn parse_pubkey(data: &[u8], offset: usize) -> Result<(&[u8; 32], usize), Pr
     if data.len() < end {
         return Err(ProgramError::InvalidInstructionData);
     }
-    let pubkey: &[u8; 32] = data[offset..end].try_into().unwrap();
+    let pubkey = read_array::<32>(data, offset);
     Ok((pubkey, end))
 }
 
@@ -57,7 +86,7 @@ pub fn parse_bytes<const N: usize>(data: &[u8], offset: usize) -> Result<(&[u8;
     if data.len() < end {
         return Err(ProgramError::InvalidInstructionData);
     }
-    let bytes: &[u8; N] = data[offset..end].try_into().unwrap();
+    let bytes = read_array::<N>(data, offset);
     Ok((bytes, end))
 }
 
@@ -71,7 +100,7 @@ pub fn parse_string(data: &[u8], offset: usize) -> Result<(&str, usize), Program
     if data.len() < len_end {
         return Err(ProgramError::InvalidInstructionData);
     }
-    let len = u32::from_le_bytes(data[offset..len_end].try_into().unwrap()) as usize;
+    let len = u32::from_le_bytes(*read_array::<4>(data, offset)) as usize;
     let str_start = len_end;
     let str_end = str_start.checked_add(len).ok_or(ProgramError::InvalidInstructionData)?;
     if data.len() < str_end {
@@ -82,10 +111,250 @@ pub fn parse_string(data: &[u8], offset: usize) -> Result<(&str, usize), Program
     Ok((s, str_end))
 }
 
+/// Strict-parse mode: once a handler has consumed its declared fields up to
+/// `end`, any remaining bytes fail with `InvalidInstructionData`, so every
+/// operation has exactly one encoding. Builds with the `lenient-ix-data`
+/// feature skip the check, for legacy clients that pad their payloads.
+#[inline(always)]
+pub fn expect_end(data: &[u8], end: usize) -> Result<(), ProgramError> {
+    if !cfg!(feature = "lenient-ix-data") && data.len() > end {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    Ok(())
+}
+
+/// Bounds-checked cursor over instruction data.
+///
+/// Each `read_*` consumes one field and advances past it. A short payload fails
+/// with `InvalidInstructionData` instead of panicking, which on SBF would
+/// surface as an opaque "program failed to complete". `finish` applies
+/// [`expect_end`] at the cursor once every declared field has been read.
+/// Copying a Reader saves its position, for payloads parsed twice.
+#[derive(Clone, Copy)]
+pub struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    #[inline(always)]
+    pub const fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    /// Bytes consumed so far.
+    #[inline(always)]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Bytes left after the cursor.
+    #[inline(always)]
+    pub const fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.offset)
+    }
+
+    /// True once every byte has been consumed (optional trailing fields absent).
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// The unconsumed tail, without advancing.
+    #[inline(always)]
+    pub fn rest(&self) -> &'a [u8] {
+        self.data.get(self.offset..).unwrap_or(&[])
+    }
+
+    /// Next byte, without advancing; `None` at the end of the data.
+    #[inline(always)]
+    pub fn peek_u8(&self) -> Option<u8> {
+        self.data.get(self.offset).copied()
+    }
+
+    /// Next `len` raw bytes (e.g. a run of fixed-size entries), advancing past them.
+    #[inline(always)]
+    pub fn read_slice(&mut self, len: usize) -> Result<&'a [u8], ProgramError> {
+        let end = self.offset.checked_add(len).ok_or(ProgramError::InvalidInstructionData)?;
+        let bytes = self.data.get(self.offset..end).ok_or(ProgramError::InvalidInstructionData)?;
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    #[inline(always)]
+    pub fn read_bytes<const N: usize>(&mut self) -> Result<&'a [u8; N], ProgramError> {
+        self.read_slice(N)?.try_into().map_err(|_| ProgramError::InvalidInstructionData)
+    }
+
+    #[inline(always)]
+    pub fn read_u8(&mut self) -> Result<u8, ProgramError> {
+        Ok(self.read_bytes::<1>()?[0])
+    }
+
+    /// 1 byte: 0 = false, non-zero = true.
+    #[inline(always)]
+    pub fn read_bool(&mut self) -> Result<bool, ProgramError> {
+        Ok(self.read_u8()? != 0)
+    }
+
+    #[inline(always)]
+    pub fn read_u16(&mut self) -> Result<u16, ProgramError> {
+        Ok(u16::from_le_bytes(*self.read_bytes()?))
+    }
+
+    #[inline(always)]
+    pub fn read_u32(&mut self) -> Result<u32, ProgramError> {
+        Ok(u32::from_le_bytes(*self.read_bytes()?))
+    }
+
+    #[inline(always)]
+    pub fn read_u64(&mut self) -> Result<u64, ProgramError> {
+        Ok(u64::from_le_bytes(*self.read_bytes()?))
+    }
+
+    #[inline(always)]
+    pub fn read_i64(&mut self) -> Result<i64, ProgramError> {
+        Ok(i64::from_le_bytes(*self.read_bytes()?))
+    }
+
+    #[inline(always)]
+    pub fn read_pubkey(&mut self) -> Result<&'a [u8; 32], ProgramError> {
+        self.read_bytes::<32>()
+    }
+
+    /// Borsh `String`: u32 LE length + UTF-8 bytes. The length is checked
+    /// against the remaining data before anything is sliced.
+    #[inline(always)]
+    pub fn read_string(&mut self) -> Result<&'a str, ProgramError> {
+        let len = self.read_u32()? as usize;
+        if len > self.remaining() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        core::str::from_utf8(self.read_slice(len)?).map_err(|_| ProgramError::InvalidInstructionData)
+    }
+
+    /// Strict end of payload: no bytes may follow the fields read so far.
+    #[inline(always)]
+    pub fn finish(&self) -> Result<(), ProgramError> {
+        expect_end(self.data, self.offset)
+    }
+}
+
+/// Validate a 27-byte KSUID: every byte base62 ASCII (`0-9`, `A-Z`, `a-z`) and
+/// not the nil KSUID (all `'0'`). Returns `InvalidKsuid` otherwise, so corrupted
+/// ids never derive (and orphan) a PDA.
+#[inline(always)]
+pub fn validate_ksuid(ksuid: &[u8; 27]) -> Result<(), ProgramError> {
+    if !ksuid.iter().all(u8::is_ascii_alphanumeric) || ksuid.iter().all(|b| *b == b'0') {
+        return Err(ZupyTokenError::InvalidKsuid.into());
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // ── expect_end tests ────────────────────────────────────────────────
+
+    #[test]
+    fn test_expect_end_exact() {
+        assert_eq!(expect_end(&[0u8; 9], 9), Ok(()));
+        assert_eq!(expect_end(&[], 0), Ok(()));
+    }
+
+    #[cfg(not(feature = "lenient-ix-data"))]
+    #[test]
+    fn test_expect_end_rejects_trailing_bytes() {
+        assert_eq!(expect_end(&[0u8; 10], 9), Err(ProgramError::InvalidInstructionData));
+        assert_eq!(expect_end(&[0u8], 0), Err(ProgramError::InvalidInstructionData));
+    }
+
+    #[cfg(feature = "lenient-ix-data")]
+    #[test]
+    fn test_expect_end_lenient_ignores_trailing_bytes() {
+        assert_eq!(expect_end(&[0u8; 10], 9), Ok(()));
+    }
+
+    // ── Reader tests ────────────────────────────────────────────────────
+
+    fn reader_payload() -> Vec<u8> {
+        let mut data = vec![7u8, 1];
+        data.extend_from_slice(&500u16.to_le_bytes());
+        data.extend_from_slice(&70_000u32.to_le_bytes());
+        data.extend_from_slice(&u64::MAX.to_le_bytes());
+        data.extend_from_slice(&(-5i64).to_le_bytes());
+        data.extend_from_slice(&[9u8; 32]);
+        data.extend_from_slice(&4u32.to_le_bytes());
+        data.extend_from_slice(b"zupy");
+        data
+    }
+
+    fn read_all(data: &[u8]) -> Result<(), ProgramError> {
+        let mut r = Reader::new(data);
+        assert_eq!(r.read_u8()?, 7);
+        assert!(r.read_bool()?);
+        assert_eq!(r.read_u16()?, 500);
+        assert_eq!(r.read_u32()?, 70_000);
+        assert_eq!(r.read_u64()?, u64::MAX);
+        assert_eq!(r.read_i64()?, -5);
+        assert_eq!(r.read_pubkey()?, &[9u8; 32]);
+        assert_eq!(r.read_string()?, "zupy");
+        r.finish()
+    }
+
+    #[test]
+    fn test_reader_reads_every_field() {
+        let data = reader_payload();
+        assert_eq!(read_all(&data), Ok(()));
+        let mut r = Reader::new(&data);
+        r.read_u8().unwrap();
+        assert_eq!(r.offset(), 1);
+        assert_eq!(r.remaining(), data.len() - 1);
+        assert_eq!(r.peek_u8(), Some(1));
+        assert_eq!(r.rest(), &data[1..]);
+    }
+
+    #[test]
+    fn test_reader_truncated_at_every_boundary() {
+        let data = reader_payload();
+        for len in 0..data.len() {
+            assert_eq!(read_all(&data[..len]), Err(ProgramError::InvalidInstructionData), "len {len}");
+        }
+    }
+
+    #[test]
+    fn test_reader_string_length_past_end() {
+        let mut data = u32::MAX.to_le_bytes().to_vec();
+        data.extend_from_slice(b"zupy");
+        assert_eq!(Reader::new(&data).read_string(), Err(ProgramError::InvalidInstructionData));
+    }
+
+    #[test]
+    fn test_reader_string_invalid_utf8() {
+        let mut data = 2u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&[0xFF, 0xFE]);
+        assert_eq!(Reader::new(&data).read_string(), Err(ProgramError::InvalidInstructionData));
+    }
+
+    #[test]
+    fn test_reader_empty() {
+        let mut r = Reader::new(&[]);
+        assert!(r.is_empty());
+        assert_eq!(r.peek_u8(), None);
+        assert_eq!(r.rest(), &[] as &[u8]);
+        assert_eq!(r.read_u8(), Err(ProgramError::InvalidInstructionData));
+        assert_eq!(r.finish(), Ok(()));
+    }
+
+    #[cfg(not(feature = "lenient-ix-data"))]
+    #[test]
+    fn test_reader_finish_rejects_trailing_bytes() {
+        let mut r = Reader::new(&[1, 2]);
+        r.read_u8().unwrap();
+        assert_eq!(r.finish(), Err(ProgramError::InvalidInstructionData));
+    }
+
     // ── parse_u64 tests ─────────────────────────────────────────────────
 
     #[test]
@@ -145,6 +414,42 @@ mod tests {
         assert_eq!(result.unwrap_err(), ProgramError::InvalidInstructionData);
     }
 
+    // ── parse_u16 tests ─────────────────────────────────────────────────
+
+    #[test]
+    fn test_parse_u16_valid() {
+        let mut data = vec![0xFFu8; 1]; // padding
+        data.extend_from_slice(&10_000u16.to_le_bytes());
+        assert_eq!(parse_u16(&data, 1).unwrap(), 10_000);
+    }
+
+    #[test]
+    fn test_parse_u16_truncated() {
+        let data = [0u8; 1];
+        assert_eq!(parse_u16(&data, 0), Err(ProgramError::InvalidInstructionData));
+    }
+
+    // ── parse_u32 tests ─────────────────────────────────────────────────
+
+    #[test]
+    fn test_parse_u32_valid() {
+        let mut data = vec![0xFFu8; 2]; // padding
+        data.extend_from_slice(&70_000u32.to_le_bytes());
+        assert_eq!(parse_u32(&data, 2).unwrap(), 70_000);
+    }
+
+    #[test]
+    fn test_parse_u32_truncated() {
+        let data = [0u8; 3];
+        assert_eq!(parse_u32(&data, 0), Err(ProgramError::InvalidInstructionData));
+    }
+
+    #[test]
+    fn test_parse_u32_offset_overflow() {
+        let data = [0u8; 4];
+        assert_eq!(parse_u32(&data, usize::MAX), Err(ProgramError::InvalidInstructionData));
+    }
+
     // ── parse_bool tests ──────────────────────────────────────────────
 
     #[test]
@@ -367,4 +672,36 @@ mod tests {
         assert_eq!(parsed_memo, memo);
         assert_eq!(end, data.len());
     }
+
+    // ── validate_ksuid tests ────────────────────────────────────────────
+
+    #[test]
+    fn test_validate_ksuid_valid() {
+        assert_eq!(validate_ksuid(b"2NRjKcGrXHKtGVjMXV7qptaXY2A"), Ok(()));
+        assert_eq!(validate_ksuid(b"aWgEPTl1tmebfsQzFP4bxwgy80V"), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_ksuid_digits_and_both_cases() {
+        assert_eq!(validate_ksuid(b"0123456789ABCDEFGHIJKLMNOPQ"), Ok(()));
+        assert_eq!(validate_ksuid(b"RSTUVWXYZabcdefghijklmnopqr"), Ok(()));
+        assert_eq!(validate_ksuid(b"stuvwxyz0000000000000000001"), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_ksuid_rejects_non_base62_bytes() {
+        let err: ProgramError = ZupyTokenError::InvalidKsuid.into();
+        for bad in [0x00u8, 0xFF, b'-', b'_', b' ', b'+', b'/'] {
+            let mut ksuid = *b"2NRjKcGrXHKtGVjMXV7qptaXY2A";
+            ksuid[13] = bad;
+            assert_eq!(validate_ksuid(&ksuid), Err(err.clone()), "byte {bad:#04x}");
+        }
+    }
+
+    #[test]
+    fn test_validate_ksuid_rejects_all_zero() {
+        let err: ProgramError = ZupyTokenError::InvalidKsuid.into();
+        assert_eq!(validate_ksuid(&[0u8; 27]), Err(err.clone()));
+        assert_eq!(validate_ksuid(&[b'0'; 27]), Err(err));
+    }
 }
@@ -0,0 +1,52 @@
+//! Debug logging that compiles out of release builds.
+//!
+//! `zupy_log!` lines are step traces for devnet debugging: they cost a
+//! `sol_log` syscall (≥100 CU) each, so they only exist under the
+//! `verbose-logs` feature. Without it the macro expands to nothing and the
+//! string is never placed in the binary.
+//!
+//! Error identification (`zupy_err!`) and indexer events (`helpers::events`)
+//! are not debug output and always log; the transfer, burn and withdraw
+//! handlers have no other logging today, so the default build loses nothing.
+//!
+//! [`sol_log`] / [`sol_log_data`] wrap the raw syscalls (pinocchio 0.10 has no
+//! log module). Off-chain (host unit tests) they are no-ops.
+
+/// Write `message` to the program log (`Program log: ...`).
+#[inline(always)]
+pub fn sol_log(message: &str) {
+    #[cfg(target_os = "solana")]
+    // SAFETY: pointer and length describe the `message` bytes.
+    unsafe {
+        pinocchio::syscalls::sol_log_(message.as_ptr(), message.len() as u64)
+    };
+    #[cfg(not(target_os = "solana"))]
+    let _ = message;
+}
+
+/// Write `data` base64-encoded to the program log (`Program data: ...`).
+#[inline(always)]
+pub fn sol_log_data(data: &[&[u8]]) {
+    #[cfg(target_os = "solana")]
+    // SAFETY: the syscall reads `data.len()` fat slice pointers from `data`.
+    unsafe {
+        pinocchio::syscalls::sol_log_data(data.as_ptr() as *const u8, data.len() as u64)
+    };
+    #[cfg(not(target_os = "solana"))]
+    let _ = data;
+}
+
+/// Logs `zupy_log:{context}` under `verbose-logs`; a no-op otherwise.
+///
+/// `zupy_log!("burn_tokens:cpi");`
+///
+/// No handler traces through it yet; add lines while debugging on devnet.
+#[allow(unused_macros)]
+macro_rules! zupy_log {
+    ($ctx:literal) => {{
+        #[cfg(feature = "verbose-logs")]
+        $crate::helpers::log::sol_log(concat!("zupy_log:", $ctx));
+    }};
+}
+#[allow(unused_imports)]
+pub(crate) use zupy_log;
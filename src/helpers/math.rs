@@ -0,0 +1,82 @@
+//! Checked token-amount arithmetic.
+//!
+//! Every amount computation (split legs, fees, pool remainders, input sums)
+//! goes through these helpers so an overflow or underflow surfaces as
+//! `ArithmeticOverflow` instead of wrapping or panicking.
+//!
+//! Saturating accounting counters (`company_counters`, `stats`, TokenState
+//! totals) are deliberately not routed here: they must never fail an operation.
+
+use pinocchio::error::ProgramError;
+
+use crate::constants::BPS_DENOMINATOR;
+use crate::error::ZupyTokenError;
+
+/// `a + b`; `ArithmeticOverflow` above `u64::MAX`.
+#[inline(always)]
+pub fn checked_add_amount(a: u64, b: u64) -> Result<u64, ProgramError> {
+    a.checked_add(b).ok_or_else(|| ZupyTokenError::ArithmeticOverflow.into())
+}
+
+/// `a - b`; `ArithmeticOverflow` below zero.
+#[inline(always)]
+pub fn checked_sub_amount(a: u64, b: u64) -> Result<u64, ProgramError> {
+    a.checked_sub(b).ok_or_else(|| ZupyTokenError::ArithmeticOverflow.into())
+}
+
+/// `floor(amount * bps / 10_000)` with a u128 intermediate, so `amount * bps`
+/// never overflows. `ArithmeticOverflow` if the result does not fit a u64
+/// (only possible for `bps > BPS_DENOMINATOR`).
+#[inline(always)]
+pub fn mul_bps(amount: u64, bps: u16) -> Result<u64, ProgramError> {
+    let scaled = (amount as u128) * (bps as u128) / (BPS_DENOMINATOR as u128);
+    u64::try_from(scaled).map_err(|_| ZupyTokenError::ArithmeticOverflow.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overflow() -> ProgramError {
+        ZupyTokenError::ArithmeticOverflow.into()
+    }
+
+    #[test]
+    fn test_checked_add_amount_at_max() {
+        assert_eq!(checked_add_amount(u64::MAX - 1, 1), Ok(u64::MAX));
+        assert_eq!(checked_add_amount(u64::MAX, 0), Ok(u64::MAX));
+        assert_eq!(checked_add_amount(u64::MAX, 1), Err(overflow()));
+        assert_eq!(checked_add_amount(u64::MAX, u64::MAX), Err(overflow()));
+    }
+
+    #[test]
+    fn test_checked_sub_amount_at_zero() {
+        assert_eq!(checked_sub_amount(u64::MAX, u64::MAX), Ok(0));
+        assert_eq!(checked_sub_amount(1, 1), Ok(0));
+        assert_eq!(checked_sub_amount(0, 1), Err(overflow()));
+        assert_eq!(checked_sub_amount(u64::MAX - 1, u64::MAX), Err(overflow()));
+    }
+
+    #[test]
+    fn test_mul_bps_rounds_down() {
+        assert_eq!(mul_bps(1_000_000, 50), Ok(5_000));
+        assert_eq!(mul_bps(199, 50), Ok(0));
+        assert_eq!(mul_bps(10_000, 1), Ok(1));
+        assert_eq!(mul_bps(0, BPS_DENOMINATOR), Ok(0));
+    }
+
+    #[test]
+    fn test_mul_bps_at_u64_max() {
+        // u64::MAX * 10_000 overflows u64 but not the u128 intermediate
+        assert_eq!(mul_bps(u64::MAX, BPS_DENOMINATOR), Ok(u64::MAX));
+        assert_eq!(mul_bps(u64::MAX, 5_000), Ok(u64::MAX / 2));
+        assert_eq!(mul_bps(u64::MAX, 0), Ok(0));
+    }
+
+    #[test]
+    fn test_mul_bps_above_denominator_overflows() {
+        assert_eq!(mul_bps(u64::MAX, BPS_DENOMINATOR + 1), Err(overflow()));
+        assert_eq!(mul_bps(u64::MAX, u16::MAX), Err(overflow()));
+        assert_eq!(mul_bps(1_000, 20_000), Ok(2_000));
+    }
+}
@@ -1,16 +1,215 @@
 use pinocchio::error::ProgramError;
 
-use crate::constants::{MEMO_PREFIX, MEMO_VERSION};
+use crate::constants::{
+    MEMO_MAX_LEN, MEMO_MAX_SEGMENTS, MEMO_OP_ID_MAX_LEN, MEMO_OP_ID_MIN_LEN, MEMO_PREFIX,
+    MEMO_VERSION, MEMO_VERSION_V2,
+};
 use crate::error::ZupyTokenError;
 
-/// Validate memo format: `"zupy:v1:{source}:{source_id}"`
+/// Memo schema version (segment 1).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MemoVersion {
+    /// `"zupy:v1:{source}:{source_id}"`
+    V1,
+    /// `"zupy:v2:{source}:{source_id}:{op_id}"` — `op_id` is a client-generated
+    /// operation id reconciliation uses to dedupe retries.
+    V2,
+}
+
+/// Operation tag (memo segment 2). Each instruction accepts only its own tag,
+/// see [`validate_memo_op`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MemoOp {
+    /// `pool_transfer` — transfer_from_pool, batch_transfer_from_pool
+    PoolTransfer,
+    /// `return` — return_to_pool, return_user_to_pool, batch_return_to_pool
+    Return,
+    /// `c2u` — transfer_company_to_user_v1
+    CompanyToUser,
+    /// `u2c` — transfer_user_to_company
+    UserToCompany,
+    /// `u2u` — transfer_user_to_user
+    UserToUser,
+    /// `c2c` — transfer_company_to_company
+    CompanyToCompany,
+    /// `withdraw` — withdraw_to_external, approve_withdrawal
+    Withdraw,
+    /// `withdraw_company` — withdraw_company_to_external
+    WithdrawCompany,
+    /// `deposit` — deposit_from_external
+    Deposit,
+    /// `deposit_company` — deposit_to_company
+    DepositCompany,
+    /// `mint` — mint_tokens, mint_to_pool
+    Mint,
+    /// `restock` — treasury_restock_pool
+    Restock,
+    /// `burn` — burn_tokens, burn_pool_tokens, burn_from_company_pda
+    Burn,
+    /// `burn_company` — burn_from_company_pda (alongside `burn`)
+    BurnCompany,
+    /// `coupon` — redeem_coupon_nft
+    Coupon,
+    /// `refund` — refund_company_to_user, refund_user_to_company
+    Refund,
+    /// `rebalance` — rebalance_pools
+    Rebalance,
+    /// `pool_to_company` — transfer_pool_to_company
+    PoolToCompany,
+    /// `incentive` — distribute_incentive
+    Incentive,
+    /// `escrow` — create_escrow
+    Escrow,
+    /// `escrow_claim` — claim_escrow
+    EscrowClaim,
+    /// `escrow_cancel` — cancel_escrow
+    EscrowCancel,
+    /// `vesting` — claim_vested
+    Vesting,
+}
+
+impl MemoOp {
+    /// Memo tag of this operation.
+    pub const fn tag(self) -> &'static str {
+        match self {
+            MemoOp::PoolTransfer => "pool_transfer",
+            MemoOp::Return => "return",
+            MemoOp::CompanyToUser => "c2u",
+            MemoOp::UserToCompany => "u2c",
+            MemoOp::UserToUser => "u2u",
+            MemoOp::CompanyToCompany => "c2c",
+            MemoOp::Withdraw => "withdraw",
+            MemoOp::WithdrawCompany => "withdraw_company",
+            MemoOp::Deposit => "deposit",
+            MemoOp::DepositCompany => "deposit_company",
+            MemoOp::Mint => "mint",
+            MemoOp::Restock => "restock",
+            MemoOp::Burn => "burn",
+            MemoOp::BurnCompany => "burn_company",
+            MemoOp::Coupon => "coupon",
+            MemoOp::Refund => "refund",
+            MemoOp::Rebalance => "rebalance",
+            MemoOp::PoolToCompany => "pool_to_company",
+            MemoOp::Incentive => "incentive",
+            MemoOp::Escrow => "escrow",
+            MemoOp::EscrowClaim => "escrow_claim",
+            MemoOp::EscrowCancel => "escrow_cancel",
+            MemoOp::Vesting => "vesting",
+        }
+    }
+
+    /// Operation for a memo tag, `None` when unknown.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        Some(match tag {
+            "pool_transfer" => MemoOp::PoolTransfer,
+            "return" => MemoOp::Return,
+            "c2u" => MemoOp::CompanyToUser,
+            "u2c" => MemoOp::UserToCompany,
+            "u2u" => MemoOp::UserToUser,
+            "c2c" => MemoOp::CompanyToCompany,
+            "withdraw" => MemoOp::Withdraw,
+            "withdraw_company" => MemoOp::WithdrawCompany,
+            "deposit" => MemoOp::Deposit,
+            "deposit_company" => MemoOp::DepositCompany,
+            "mint" => MemoOp::Mint,
+            "restock" => MemoOp::Restock,
+            "burn" => MemoOp::Burn,
+            "burn_company" => MemoOp::BurnCompany,
+            "coupon" => MemoOp::Coupon,
+            "refund" => MemoOp::Refund,
+            "rebalance" => MemoOp::Rebalance,
+            "pool_to_company" => MemoOp::PoolToCompany,
+            "incentive" => MemoOp::Incentive,
+            "escrow" => MemoOp::Escrow,
+            "escrow_claim" => MemoOp::EscrowClaim,
+            "escrow_cancel" => MemoOp::EscrowCancel,
+            "vesting" => MemoOp::Vesting,
+            _ => return None,
+        })
+    }
+}
+
+/// A parsed memo.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Memo<'a> {
+    pub version: MemoVersion,
+    pub op: MemoOp,
+    /// The id segment(s) after the tag, e.g. `"10:11"` in `"zupy:v1:c2c:10:11"`
+    /// (without the v2 op_id).
+    pub ids: &'a str,
+}
+
+/// A format-checked memo split into its segments.
+struct MemoParts<'a> {
+    version: MemoVersion,
+    source: &'a str,
+    /// Everything between `source` and the v2 `op_id`; may contain colons.
+    source_id: &'a str,
+}
+
+/// Validate memo format: `"zupy:v1:{source}:{source_id}"` or
+/// `"zupy:v2:{source}:{source_id}:{op_id}"`
 ///
 /// Rules:
-/// - Must have exactly 4 colon-separated segments
 /// - Segment 0 must be `MEMO_PREFIX` ("zupy")
-/// - Segment 1 must be `MEMO_VERSION` ("v1")
+/// - Segment 1 must be `MEMO_VERSION` ("v1") or `MEMO_VERSION_V2` ("v2")
 /// - Segments 2 and 3 (source and source_id) must be non-empty
+/// - v2 only: the last segment is the op_id, 16–27 base62 characters
+/// - At most `MEMO_MAX_LEN` (128) bytes of printable ASCII (`0x20..=0x7E`)
+/// - The source_id may contain colons, but no segment may be empty and there
+///   are at most `MEMO_MAX_SEGMENTS` (8) segments in total
 pub fn validate_memo_format(memo: &str) -> Result<(), ProgramError> {
+    split_memo(memo).map(|_| ())
+}
+
+/// Validate memo format and return its schema version, e.g. to decide whether
+/// the memo carries a v2 op_id.
+pub fn parse_memo_version(memo: &str) -> Result<MemoVersion, ProgramError> {
+    split_memo(memo).map(|parts| parts.version)
+}
+
+/// Validate memo format and parse its operation tag. An unknown tag fails with
+/// `InvalidMemoFormat`.
+pub fn parse_memo(memo: &str) -> Result<Memo<'_>, ProgramError> {
+    let parts = split_memo(memo)?;
+    let op = MemoOp::from_tag(parts.source)
+        .ok_or(ProgramError::from(ZupyTokenError::InvalidMemoFormat))?;
+    Ok(Memo { version: parts.version, op, ids: parts.source_id })
+}
+
+/// Validate the memo of an instruction performing `op`: the memo must parse
+/// and carry `op`'s tag, otherwise `InvalidMemoFormat` — a `pool_transfer`
+/// memo on a withdraw is rejected. Builds with the `permissive-memo` feature
+/// (staging) only check the format.
+pub fn validate_memo_op(memo: &str, op: MemoOp) -> Result<(), ProgramError> {
+    validate_memo_op_in(memo, &[op])
+}
+
+/// [`validate_memo_op`] for an instruction that accepts any of `ops`, e.g.
+/// burn_from_company_pda takes both `burn` and `burn_company`.
+pub fn validate_memo_op_in(memo: &str, ops: &[MemoOp]) -> Result<(), ProgramError> {
+    if cfg!(feature = "permissive-memo") {
+        return validate_memo_format(memo);
+    }
+    if !ops.contains(&parse_memo(memo)?.op) {
+        return Err(ZupyTokenError::InvalidMemoFormat.into());
+    }
+    Ok(())
+}
+
+fn split_memo(memo: &str) -> Result<MemoParts<'_>, ProgramError> {
+    if memo.len() > MEMO_MAX_LEN || !memo.bytes().all(|b| (0x20..=0x7E).contains(&b)) {
+        return Err(ZupyTokenError::InvalidMemoFormat.into());
+    }
+    let mut segments = 0;
+    for segment in memo.split(':') {
+        segments += 1;
+        if segment.is_empty() || segments > MEMO_MAX_SEGMENTS {
+            return Err(ZupyTokenError::InvalidMemoFormat.into());
+        }
+    }
+
+    // Segments are non-empty from here on
     let mut parts = memo.splitn(4, ':');
 
     let prefix = parts.next().ok_or(ProgramError::from(ZupyTokenError::InvalidMemoFormat))?;
@@ -18,24 +217,79 @@ pub fn validate_memo_format(memo: &str) -> Result<(), ProgramError> {
         return Err(ZupyTokenError::InvalidMemoFormat.into());
     }
 
-    let version = parts.next().ok_or(ProgramError::from(ZupyTokenError::InvalidMemoFormat))?;
-    if version != MEMO_VERSION {
-        return Err(ZupyTokenError::InvalidMemoFormat.into());
-    }
+    let version = match parts.next() {
+        Some(MEMO_VERSION) => MemoVersion::V1,
+        Some(MEMO_VERSION_V2) => MemoVersion::V2,
+        _ => return Err(ZupyTokenError::InvalidMemoFormat.into()),
+    };
 
     let source = parts.next().ok_or(ProgramError::from(ZupyTokenError::InvalidMemoFormat))?;
-    if source.is_empty() {
+    let rest = parts.next().ok_or(ProgramError::from(ZupyTokenError::InvalidMemoFormat))?;
+
+    let source_id = match version {
+        MemoVersion::V1 => rest,
+        MemoVersion::V2 => {
+            let (source_id, op_id) = rest
+                .rsplit_once(':')
+                .ok_or(ProgramError::from(ZupyTokenError::InvalidMemoFormat))?;
+            if !is_memo_op_id(op_id) {
+                return Err(ZupyTokenError::InvalidMemoFormat.into());
+            }
+            source_id
+        }
+    };
+
+    Ok(MemoParts { version, source, source_id })
+}
+
+/// 16–27 base62 (`[0-9A-Za-z]`) characters, e.g. a KSUID.
+fn is_memo_op_id(op_id: &str) -> bool {
+    (MEMO_OP_ID_MIN_LEN..=MEMO_OP_ID_MAX_LEN).contains(&op_id.len())
+        && op_id.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+/// Format-checked memo whose source is `expected_source`.
+fn memo_with_source<'a>(memo: &'a str, expected_source: &str) -> Result<MemoParts<'a>, ProgramError> {
+    let parts = split_memo(memo)?;
+    if parts.source != expected_source {
         return Err(ZupyTokenError::InvalidMemoFormat.into());
     }
+    Ok(parts)
+}
 
-    let source_id = parts.next().ok_or(ProgramError::from(ZupyTokenError::InvalidMemoFormat))?;
-    if source_id.is_empty() {
+/// [`validate_memo_op`] that also binds the id segment to `expected_id`,
+/// e.g. `validate_memo_op_id(memo, MemoOp::Deposit, 42)` accepts only
+/// `"zupy:v1:deposit:42"` (or its v2 form).
+pub fn validate_memo_op_id(memo: &str, op: MemoOp, expected_id: u64) -> Result<(), ProgramError> {
+    validate_memo_op(memo, op)?;
+    if split_memo(memo)?.source_id.parse::<u64>() != Ok(expected_id) {
         return Err(ZupyTokenError::InvalidMemoFormat.into());
     }
-
     Ok(())
 }
 
+/// Validate a refund memo `"zupy:v1:refund:{original_source}:{original_id}"` —
+/// the `refund` source followed by the original memo's suffix — and require
+/// that the referenced operation is `refunded_source`.
+///
+/// Returns the original memo suffix (`"{original_source}:{original_id}"`,
+/// without a v2 op_id).
+/// A well-formed memo referencing another operation type fails with
+/// `InvalidOperationType` (refund direction does not match the original).
+pub fn validate_refund_memo<'a>(memo: &'a str, refunded_source: &str) -> Result<&'a str, ProgramError> {
+    let suffix = memo_with_source(memo, "refund")?.source_id;
+    let (original_source, original_id) = suffix
+        .split_once(':')
+        .ok_or(ProgramError::from(ZupyTokenError::InvalidMemoFormat))?;
+    if original_source.is_empty() || original_id.is_empty() {
+        return Err(ZupyTokenError::InvalidMemoFormat.into());
+    }
+    if original_source != refunded_source {
+        return Err(ZupyTokenError::InvalidOperationType.into());
+    }
+    Ok(suffix)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,4 +370,232 @@ mod tests {
     fn test_invalid_memo_no_colons() {
         assert!(validate_memo_format("random_string").is_err());
     }
+
+    // ── Size and charset limits ─────────────────────────────────────────
+
+    #[test]
+    fn test_memo_at_max_len() {
+        let memo = format!("zupy:v1:transfer:{}", "9".repeat(MEMO_MAX_LEN - 17));
+        assert_eq!(memo.len(), MEMO_MAX_LEN);
+        assert!(validate_memo_format(&memo).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_memo_oversized() {
+        let memo = format!("zupy:v1:transfer:{}", "9".repeat(900));
+        assert_eq!(
+            validate_memo_format(&memo).unwrap_err(),
+            ProgramError::Custom(ZupyTokenError::InvalidMemoFormat as u32)
+        );
+    }
+
+    #[test]
+    fn test_invalid_memo_newline() {
+        assert_eq!(
+            validate_memo_format("zupy:v1:transfer:12\n345").unwrap_err(),
+            ProgramError::Custom(ZupyTokenError::InvalidMemoFormat as u32)
+        );
+    }
+
+    #[test]
+    fn test_invalid_memo_non_ascii() {
+        assert!(validate_memo_format("zupy:v1:transfer:café").is_err());
+        assert!(validate_memo_format("zupy:v1:transfer:12\t3").is_err());
+        assert!(validate_memo_format("zupy:v1:transfer:12\x7f").is_err());
+    }
+
+    #[test]
+    fn test_invalid_memo_empty_middle_segment() {
+        assert_eq!(
+            validate_memo_format("zupy:v1:split:a::c").unwrap_err(),
+            ProgramError::Custom(ZupyTokenError::InvalidMemoFormat as u32)
+        );
+    }
+
+    #[test]
+    fn test_memo_segment_limit() {
+        assert!(validate_memo_format("zupy:v1:split:a:b:c:d:e").is_ok());
+        assert!(validate_memo_format("zupy:v1:split:a:b:c:d:e:f").is_err());
+    }
+
+    // ── Schema v2 (op_id segment) ───────────────────────────────────────
+
+    const OP_ID: &str = "2NRjKcGrXHKtGVjMXV7qptaXY2A";
+
+    #[test]
+    fn test_valid_memo_v2() {
+        assert!(validate_memo_format(&format!("zupy:v2:transfer:12345:{}", OP_ID)).is_ok());
+        assert!(validate_memo_format("zupy:v2:transfer:12345:0123456789abcdef").is_ok());
+        assert!(validate_memo_format(&format!("zupy:v2:c2c:1:2:{}", OP_ID)).is_ok());
+    }
+
+    #[test]
+    fn test_parse_memo_version() {
+        assert_eq!(parse_memo_version("zupy:v1:transfer:12345"), Ok(MemoVersion::V1));
+        assert_eq!(parse_memo_version(&format!("zupy:v2:transfer:12345:{}", OP_ID)), Ok(MemoVersion::V2));
+        assert!(parse_memo_version("zupy:v1:transfer:").is_err());
+    }
+
+    #[test]
+    fn test_invalid_memo_v2_op_id() {
+        let err = ProgramError::Custom(ZupyTokenError::InvalidMemoFormat as u32);
+        // Missing op_id: "12345" is too short to be one
+        assert_eq!(validate_memo_format("zupy:v2:transfer:12345").unwrap_err(), err);
+        // op_id without a source_id
+        assert_eq!(validate_memo_format(&format!("zupy:v2:transfer:{}", OP_ID)).unwrap_err(), err);
+        // 15 and 28 characters
+        assert_eq!(validate_memo_format("zupy:v2:transfer:1:0123456789abcde").unwrap_err(), err);
+        assert_eq!(validate_memo_format(&format!("zupy:v2:transfer:1:{}X", OP_ID)).unwrap_err(), err);
+        // Not base62
+        assert_eq!(validate_memo_format("zupy:v2:transfer:1:0123456789abcde-").unwrap_err(), err);
+        assert_eq!(validate_memo_format("zupy:v2:transfer:1:0123456789abcde_").unwrap_err(), err);
+    }
+
+    #[test]
+    fn test_invalid_memo_v3() {
+        assert_eq!(
+            validate_memo_format("zupy:v3:transfer:12345").unwrap_err(),
+            ProgramError::Custom(ZupyTokenError::InvalidMemoFormat as u32)
+        );
+        assert!(validate_memo_format(&format!("zupy:v3:transfer:12345:{}", OP_ID)).is_err());
+    }
+
+    #[test]
+    fn test_memo_v2_source_and_id() {
+        let memo = format!("zupy:v2:deposit:42:{}", OP_ID);
+        assert!(validate_memo_op(&memo, MemoOp::Deposit).is_ok());
+        assert!(validate_memo_op_id(&memo, MemoOp::Deposit, 42).is_ok());
+        assert!(validate_memo_op_id(&memo, MemoOp::Deposit, 43).is_err());
+        assert_eq!(
+            validate_refund_memo(&format!("zupy:v2:refund:company_to_user:42:{}", OP_ID), "company_to_user"),
+            Ok("company_to_user:42")
+        );
+    }
+
+    // ── parse_memo / validate_memo_op tests ─────────────────────────────
+
+    #[test]
+    fn test_parse_memo() {
+        assert_eq!(
+            parse_memo("zupy:v1:c2c:10:11"),
+            Ok(Memo { version: MemoVersion::V1, op: MemoOp::CompanyToCompany, ids: "10:11" })
+        );
+        assert_eq!(
+            parse_memo(&format!("zupy:v2:withdraw:42:{}", OP_ID)),
+            Ok(Memo { version: MemoVersion::V2, op: MemoOp::Withdraw, ids: "42" })
+        );
+    }
+
+    #[test]
+    fn test_memo_op_tags_round_trip() {
+        for op in [
+            MemoOp::PoolTransfer, MemoOp::Return, MemoOp::CompanyToUser, MemoOp::UserToCompany,
+            MemoOp::UserToUser, MemoOp::CompanyToCompany, MemoOp::Withdraw, MemoOp::WithdrawCompany,
+            MemoOp::Deposit, MemoOp::DepositCompany, MemoOp::Mint, MemoOp::Restock, MemoOp::Burn,
+            MemoOp::BurnCompany, MemoOp::Coupon, MemoOp::Refund, MemoOp::Rebalance,
+            MemoOp::PoolToCompany, MemoOp::Incentive, MemoOp::Escrow, MemoOp::EscrowClaim,
+            MemoOp::EscrowCancel, MemoOp::Vesting,
+        ] {
+            assert_eq!(MemoOp::from_tag(op.tag()), Some(op));
+        }
+    }
+
+    #[test]
+    fn test_parse_memo_unknown_tag() {
+        assert_eq!(
+            parse_memo("zupy:v1:transfer:12345").unwrap_err(),
+            ProgramError::Custom(ZupyTokenError::InvalidMemoFormat as u32)
+        );
+    }
+
+    #[test]
+    fn test_memo_op_matches_instruction() {
+        assert!(validate_memo_op("zupy:v1:pool_transfer:1", MemoOp::PoolTransfer).is_ok());
+        assert!(validate_memo_op("zupy:v1:withdraw:42", MemoOp::Withdraw).is_ok());
+        assert!(validate_memo_op("zupy:v1:u2c:1:2", MemoOp::UserToCompany).is_ok());
+        assert!(validate_memo_op("zupy:v1:burn_company:42", MemoOp::BurnCompany).is_ok());
+    }
+
+    #[test]
+    fn test_memo_op_in_accepts_any_listed_op() {
+        let burn_company = [MemoOp::Burn, MemoOp::BurnCompany];
+        assert!(validate_memo_op_in("zupy:v1:burn:42", &burn_company).is_ok());
+        assert!(validate_memo_op_in("zupy:v1:burn_company:42", &burn_company).is_ok());
+    }
+
+    #[cfg(not(feature = "permissive-memo"))]
+    #[test]
+    fn test_memo_op_swapped_between_instructions() {
+        let err = ProgramError::Custom(ZupyTokenError::InvalidMemoFormat as u32);
+        // transfer_from_pool memo on a withdraw, and the other way round
+        assert_eq!(validate_memo_op("zupy:v1:pool_transfer:1", MemoOp::Withdraw).unwrap_err(), err);
+        assert_eq!(validate_memo_op("zupy:v1:withdraw:42", MemoOp::PoolTransfer).unwrap_err(), err);
+        assert_eq!(validate_memo_op("zupy:v1:burn:42", MemoOp::BurnCompany).unwrap_err(), err);
+        assert_eq!(
+            validate_memo_op_in("zupy:v1:withdraw:42", &[MemoOp::Burn, MemoOp::BurnCompany]).unwrap_err(),
+            err
+        );
+        assert_eq!(validate_memo_op("zupy:v1:transfer:1", MemoOp::PoolTransfer).unwrap_err(), err);
+        assert_eq!(validate_memo_op("zupy:v1:escrow:7", MemoOp::EscrowClaim).unwrap_err(), err);
+        assert_eq!(validate_memo_op("zupy:v1:withdraw:42", MemoOp::WithdrawCompany).unwrap_err(), err);
+    }
+
+    #[cfg(feature = "permissive-memo")]
+    #[test]
+    fn test_memo_op_permissive_checks_format_only() {
+        assert!(validate_memo_op("zupy:v1:pool_transfer:1", MemoOp::Withdraw).is_ok());
+        assert!(validate_memo_op("zupy:v1:transfer:1", MemoOp::PoolTransfer).is_ok());
+        assert!(validate_memo_op("zupy:v1:pool_transfer:", MemoOp::PoolTransfer).is_err());
+    }
+
+    // ── validate_memo_op_id tests ───────────────────────────────────────
+
+    #[test]
+    fn test_memo_op_id_matches() {
+        assert!(validate_memo_op_id("zupy:v1:deposit:42", MemoOp::Deposit, 42).is_ok());
+    }
+
+    #[test]
+    fn test_memo_op_id_mismatch() {
+        let err = ProgramError::Custom(ZupyTokenError::InvalidMemoFormat as u32);
+        assert_eq!(validate_memo_op_id("zupy:v1:deposit:43", MemoOp::Deposit, 42).unwrap_err(), err);
+        assert_eq!(validate_memo_op_id("zupy:v1:deposit:abc", MemoOp::Deposit, 42).unwrap_err(), err);
+        assert_eq!(validate_memo_op_id("zupy:v1:deposit:", MemoOp::Deposit, 42).unwrap_err(), err);
+    }
+
+    #[cfg(not(feature = "permissive-memo"))]
+    #[test]
+    fn test_memo_op_id_wrong_op() {
+        assert_eq!(
+            validate_memo_op_id("zupy:v1:withdraw:42", MemoOp::Deposit, 42).unwrap_err(),
+            ProgramError::Custom(ZupyTokenError::InvalidMemoFormat as u32)
+        );
+    }
+
+    // ── validate_refund_memo tests ──────────────────────────────────────
+
+    #[test]
+    fn test_refund_memo_returns_original_suffix() {
+        assert_eq!(
+            validate_refund_memo("zupy:v1:refund:company_to_user:42", "company_to_user"),
+            Ok("company_to_user:42")
+        );
+    }
+
+    #[test]
+    fn test_refund_memo_wrong_direction() {
+        assert_eq!(
+            validate_refund_memo("zupy:v1:refund:user_to_company:42", "company_to_user").unwrap_err(),
+            ProgramError::Custom(ZupyTokenError::InvalidOperationType as u32)
+        );
+    }
+
+    #[test]
+    fn test_refund_memo_malformed_suffix() {
+        let err = ProgramError::Custom(ZupyTokenError::InvalidMemoFormat as u32);
+        assert_eq!(validate_refund_memo("zupy:v1:refund:42", "company_to_user").unwrap_err(), err);
+        assert_eq!(validate_refund_memo("zupy:v1:refund::42", "company_to_user").unwrap_err(), err);
+        assert_eq!(validate_refund_memo("zupy:v1:refund:company_to_user:", "company_to_user").unwrap_err(), err);
+        assert_eq!(validate_refund_memo("zupy:v1:transfer:company_to_user:42", "company_to_user").unwrap_err(), err);
+    }
 }
@@ -0,0 +1,190 @@
+//! Token-2022 metadata value validation, shared by `initialize_metadata`,
+//! `update_metadata_field`, `update_metadata_fields` and `create_zupy_card`.
+//!
+//! Limits follow Metaplex conventions (name ≤ 32, symbol ≤ 10, uri ≤ 200 bytes)
+//! so an oversized value can never grow the mint account. Empty or over-long
+//! values keep their field-specific errors (`InvalidMetadataName` /
+//! `InvalidMetadataSymbol` / `InvalidMetadataUri`); a URI that is not ASCII or
+//! does not start with `https://` / `ipfs://` fails with `InvalidMetadataValue`.
+//! All checks run before any metadata CPI.
+
+use pinocchio::error::ProgramError;
+
+use crate::error::ZupyTokenError;
+use crate::state::token_state::TokenState;
+
+pub const MAX_METADATA_NAME_LEN: usize = 32;
+pub const MAX_METADATA_SYMBOL_LEN: usize = 10;
+pub const MAX_METADATA_URI_LEN: usize = 200;
+
+/// Accepted URI schemes.
+pub const METADATA_URI_SCHEMES: [&str; 2] = ["https://", "ipfs://"];
+
+/// Max length of a custom `additional_metadata` key.
+pub const MAX_METADATA_KEY_LEN: usize = 32;
+/// Max length of a custom `additional_metadata` value.
+pub const MAX_METADATA_VALUE_LEN: usize = 256;
+
+/// Bytes the TokenMetadata extension adds to a mint on Initialize: 4-byte TLV
+/// header + update_authority (32) + mint (32) + name / symbol / uri (4-byte
+/// length prefix each) + empty additional_metadata (4).
+pub fn token_metadata_space(name: &str, symbol: &str, uri: &str) -> usize {
+    4 + 32 + 32 + (4 + name.len()) + (4 + symbol.len()) + (4 + uri.len()) + 4
+}
+
+pub fn validate_metadata_name(name: &str) -> Result<(), ProgramError> {
+    if name.is_empty() || name.len() > MAX_METADATA_NAME_LEN {
+        return Err(ZupyTokenError::InvalidMetadataName.into());
+    }
+    Ok(())
+}
+
+pub fn validate_metadata_symbol(symbol: &str) -> Result<(), ProgramError> {
+    if symbol.is_empty() || symbol.len() > MAX_METADATA_SYMBOL_LEN {
+        return Err(ZupyTokenError::InvalidMetadataSymbol.into());
+    }
+    Ok(())
+}
+
+/// URI: 1..=200 bytes, ASCII, `https://` or `ipfs://`.
+pub fn validate_metadata_uri(uri: &str) -> Result<(), ProgramError> {
+    if uri.is_empty() || uri.len() > MAX_METADATA_URI_LEN {
+        return Err(ZupyTokenError::InvalidMetadataUri.into());
+    }
+    if !uri.is_ascii() || !METADATA_URI_SCHEMES.iter().any(|s| uri.starts_with(s)) {
+        return Err(ZupyTokenError::InvalidMetadataValue.into());
+    }
+    Ok(())
+}
+
+/// Validate metadata field value by type (0=Name, 1=Symbol, 2=Uri).
+#[inline(always)]
+pub fn validate_metadata_field(field: u8, value: &str) -> Result<(), ProgramError> {
+    match field {
+        0 => validate_metadata_name(value),
+        1 => validate_metadata_symbol(value),
+        2 => validate_metadata_uri(value),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+/// Validate a custom `additional_metadata` pair: non-empty printable ASCII,
+/// key ≤ 32 bytes, value ≤ 256 bytes. Empty values are rejected — keys are
+/// never removed through `update_metadata_field`.
+#[inline(always)]
+pub fn validate_additional_metadata(key: &str, value: &str) -> Result<(), ProgramError> {
+    let printable = |s: &str| s.bytes().all(|b| b.is_ascii_graphic() || b == b' ');
+    if key.is_empty() || key.len() > MAX_METADATA_KEY_LEN || !printable(key) {
+        return Err(ZupyTokenError::InvalidMetadataKey.into());
+    }
+    if value.is_empty() || value.len() > MAX_METADATA_VALUE_LEN || !printable(value) {
+        return Err(ZupyTokenError::InvalidMetadataValue.into());
+    }
+    Ok(())
+}
+
+/// Updates (and `freeze_metadata`) require metadata that is initialized
+/// (`MetadataNotInitialized`) and not frozen (`MetadataFrozen`).
+/// TokenState accounts too short to carry the flags are not checked.
+/// `state` comes from the caller's token_state validation.
+pub fn require_metadata_updatable(state: &TokenState) -> Result<(), ProgramError> {
+    if state.metadata_frozen() == Some(true) {
+        return Err(ZupyTokenError::MetadataFrozen.into());
+    }
+    if state.metadata_initialized() == Some(false) {
+        return Err(ZupyTokenError::MetadataNotInitialized.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::METADATA_URI;
+    use crate::helpers::cpi::METADATA_FIELD_KEY;
+
+    #[test]
+    fn test_token_metadata_space() {
+        // TLV header + two pubkeys + three empty strings + empty additional_metadata
+        assert_eq!(token_metadata_space("", "", ""), 4 + 64 + 12 + 4);
+        assert_eq!(token_metadata_space("Zuper Card", "ZCARD", "https://x"), 84 + 10 + 5 + 9);
+    }
+
+    #[test]
+    fn test_name_boundaries() {
+        assert!(validate_metadata_name(&"n".repeat(MAX_METADATA_NAME_LEN)).is_ok());
+        let err: ProgramError = ZupyTokenError::InvalidMetadataName.into();
+        assert_eq!(validate_metadata_name(&"n".repeat(MAX_METADATA_NAME_LEN + 1)), Err(err.clone()));
+        assert_eq!(validate_metadata_name(""), Err(err));
+    }
+
+    #[test]
+    fn test_symbol_boundaries() {
+        assert!(validate_metadata_symbol(&"S".repeat(MAX_METADATA_SYMBOL_LEN)).is_ok());
+        let err: ProgramError = ZupyTokenError::InvalidMetadataSymbol.into();
+        assert_eq!(validate_metadata_symbol(&"S".repeat(MAX_METADATA_SYMBOL_LEN + 1)), Err(err.clone()));
+        assert_eq!(validate_metadata_symbol(""), Err(err));
+    }
+
+    #[test]
+    fn test_uri_length_boundaries() {
+        let at_limit = format!("https://{}", "a".repeat(MAX_METADATA_URI_LEN - 8));
+        assert_eq!(at_limit.len(), MAX_METADATA_URI_LEN);
+        assert!(validate_metadata_uri(&at_limit).is_ok());
+        let err: ProgramError = ZupyTokenError::InvalidMetadataUri.into();
+        assert_eq!(validate_metadata_uri(&format!("{}a", at_limit)), Err(err.clone()));
+        assert_eq!(validate_metadata_uri(&"a".repeat(3_000)), Err(err.clone()));
+        assert_eq!(validate_metadata_uri(""), Err(err));
+    }
+
+    #[test]
+    fn test_uri_scheme() {
+        assert!(validate_metadata_uri("https://zupy.com/meta.json").is_ok());
+        assert!(validate_metadata_uri("ipfs://bafkrei").is_ok());
+        assert!(validate_metadata_uri(METADATA_URI).is_ok(), "configured URI must pass");
+        let err: ProgramError = ZupyTokenError::InvalidMetadataValue.into();
+        assert_eq!(validate_metadata_uri("http://zupy.com"), Err(err.clone()));
+        assert_eq!(validate_metadata_uri("ar://abc"), Err(err.clone()));
+        assert_eq!(validate_metadata_uri("HTTPS://zupy.com"), Err(err.clone()));
+        assert_eq!(validate_metadata_uri("https:/"), Err(err));
+    }
+
+    #[test]
+    fn test_unicode_uri_rejected() {
+        assert_eq!(
+            validate_metadata_uri("https://zupy.com/ménu.json"),
+            Err(ZupyTokenError::InvalidMetadataValue.into())
+        );
+    }
+
+    #[test]
+    fn test_metadata_field_dispatch() {
+        assert!(validate_metadata_field(0, "Zupy").is_ok());
+        assert!(validate_metadata_field(1, "ZUPY").is_ok());
+        assert!(validate_metadata_field(2, "ipfs://x").is_ok());
+        assert_eq!(validate_metadata_field(2, "x"), Err(ZupyTokenError::InvalidMetadataValue.into()));
+        assert_eq!(validate_metadata_field(METADATA_FIELD_KEY, "x"), Err(ProgramError::InvalidInstructionData));
+        assert_eq!(validate_metadata_field(4, "x"), Err(ProgramError::InvalidInstructionData));
+    }
+
+    #[test]
+    fn test_additional_metadata_key_limits() {
+        let value = "v";
+        assert!(validate_additional_metadata(&"k".repeat(MAX_METADATA_KEY_LEN), value).is_ok());
+        let err: ProgramError = ZupyTokenError::InvalidMetadataKey.into();
+        assert_eq!(validate_additional_metadata(&"k".repeat(MAX_METADATA_KEY_LEN + 1), value), Err(err.clone()));
+        assert_eq!(validate_additional_metadata("", value), Err(err.clone()));
+        assert_eq!(validate_additional_metadata("clé", value), Err(err.clone()));
+        assert_eq!(validate_additional_metadata("a\nb", value), Err(err));
+    }
+
+    #[test]
+    fn test_additional_metadata_value_limits() {
+        assert!(validate_additional_metadata("site", &"v".repeat(MAX_METADATA_VALUE_LEN)).is_ok());
+        assert!(validate_additional_metadata("site", "https://zupy.com/a b").is_ok());
+        let err: ProgramError = ZupyTokenError::InvalidMetadataValue.into();
+        assert_eq!(validate_additional_metadata("site", &"v".repeat(MAX_METADATA_VALUE_LEN + 1)), Err(err.clone()));
+        assert_eq!(validate_additional_metadata("site", ""), Err(err.clone()), "no removal via empty value");
+        assert_eq!(validate_additional_metadata("site", "zupy™"), Err(err));
+    }
+}
@@ -0,0 +1,180 @@
+//! Shared logic for rate-limited mint instructions.
+//!
+//! [`mint_with_limits`] is used by `mint_tokens` (to `treasury_ata`) and
+//! `mint_to_pool` (straight into `pool_ata`, skipping the treasury restock).
+//! Both are signed by `mint_authority`, honour the paused flag, and count
+//! against the same `per_tx_auto_limit` / `daily_auto_limit` budget and the
+//! `max_supply` cap; only the destination check differs.
+
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::{TOKEN_2022_PROGRAM_ID, TOKEN_STATE_SEED};
+use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::cpi::cpi_mint_to;
+use crate::helpers::events::{emit_anchor_event, emit_supply_event, MintEvent, SupplyEvent};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::memo::{validate_memo_op, MemoOp};
+use crate::helpers::transfer_validation::{read_mint_supply, validate_token_state_base};
+use crate::state::token_state::{LastOp, TokenStateMut};
+
+/// Where a rate-limited mint lands.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MintTarget {
+    /// `token_state.treasury_ata` → `InvalidTreasuryAccount` on mismatch.
+    TreasuryAta,
+    /// `token_state.pool_ata` → `InvalidPoolAccount` on mismatch.
+    PoolAta,
+}
+
+/// Rate-limited Token-2022 MintTo into the `target` account.
+/// PDA signer: token_state [TOKEN_STATE_SEED, &[bump]].
+///
+/// Accounts (5):
+///   0. mint_authority (writable, signer) — must match token_state.mint_authority()
+///   1. token_state (writable) — PDA [TOKEN_STATE_SEED], rate limit updates
+///   2. mint (writable) — Token-2022 mint
+///   3. destination (writable) — treasury_ata or pool_ata, per `target`
+///   4. token_program (read) — Token-2022
+///
+/// Optional: treasury (signer) appended after 4 to mint above `daily_auto_limit`.
+///
+/// On a v2 TokenState the mint also stamps `last_mint_ts` (see `helpers::heartbeat`).
+///
+/// Data: amount (u64) + memo (String)
+pub fn mint_with_limits(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+    target: MintTarget,
+) -> ProgramResult {
+    // ── Account extraction (5 accounts) ─────────────────────────────────
+    if accounts.len() < 5 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let mint_authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let mint = &accounts[2];
+    let destination = &accounts[3];
+    let token_program = &accounts[4];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let amount = reader.read_u64()?;
+    let memo = reader.read_string()?;
+    reader.finish()?;
+
+    // ── Input validation ────────────────────────────────────────────────
+    if amount == 0 {
+        return Err(ZupyTokenError::ZeroAmount.into());
+    }
+    validate_memo_op(memo, MemoOp::Mint)?;
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+
+    // ── Paused check ────────────────────────────────────────────────────
+    if state.paused() {
+        return Err(ZupyTokenError::SystemPaused.into());
+    }
+
+    // ── Signer + mint_authority check ───────────────────────────────────
+    if !mint_authority.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    let mint_auth_key: &[u8; 32] = address_bytes(mint_authority.address());
+    if !state.is_mint_authority(mint_auth_key) {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+
+    // ── Mint validation ─────────────────────────────────────────────────
+    let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
+    if !mint.owned_by(&token_2022_addr) {
+        return Err(ZupyTokenError::InvalidMint.into());
+    }
+    if state.mint() != mint.address().as_ref() {
+        return Err(ZupyTokenError::InvalidMint.into());
+    }
+
+    // ── Hard supply cap (0 = uncapped; no treasury override) ───────────
+    if !state.within_max_supply(read_mint_supply(mint), amount) {
+        return Err(ZupyTokenError::MaxSupplyExceeded.into());
+    }
+
+    // ── Destination validation ──────────────────────────────────────────
+    match target {
+        MintTarget::TreasuryAta => {
+            if state.treasury_ata() != destination.address().as_ref() {
+                return Err(ZupyTokenError::InvalidTreasuryAccount.into());
+            }
+        }
+        MintTarget::PoolAta => {
+            if state.pool_ata() != destination.address().as_ref() {
+                return Err(ZupyTokenError::InvalidPoolAccount.into());
+            }
+        }
+    }
+
+    // ── Token program check ─────────────────────────────────────────────
+    if token_program.address() != &token_2022_addr {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    // ── Rate limits ─────────────────────────────────────────────────────
+    use pinocchio::sysvars::Sysvar as _;
+    let clock = pinocchio::sysvars::clock::Clock::get()?;
+
+    // Per-transaction limit
+    if !state.within_tx_limit(amount) {
+        return Err(ZupyTokenError::ExceedsTransactionLimit.into());
+    }
+
+    // Daily limit over a rolling 24h window (simulate reset for pre-CPI check — CEI pattern).
+    // A treasury co-signer (any account after the 5 above) overrides the cap.
+    let daily_total = state
+        .effective_daily_minted(clock.unix_timestamp)
+        .checked_add(amount)
+        .ok_or(ZupyTokenError::ExceedsDailyLimit)?;
+    if daily_total > state.daily_auto_limit() {
+        let treasury_override = accounts[5..]
+            .iter()
+            .any(|a| a.is_signer() && a.address().as_ref() == state.treasury());
+        if !treasury_override {
+            return Err(ZupyTokenError::ExceedsDailyLimit.into());
+        }
+    }
+
+    let bump = state.bump();
+
+    // ── Event log (all checks passed) ───────────────────────────────────
+    emit_supply_event(SupplyEvent::Mint, amount, memo);
+    emit_anchor_event(&MintEvent { destination: *address_bytes(destination.address()), amount });
+
+    // ── CPI: Token-2022 MintTo ──────────────────────────────────────────
+    let bump_bytes = [bump];
+    let signer_seeds: [Seed; 2] = [
+        Seed::from(TOKEN_STATE_SEED),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    let signer = Signer::from(&signer_seeds);
+
+    cpi_mint_to(
+        mint,
+        destination,
+        token_state_account,
+        amount,
+        token_program.address(),
+        &[signer],
+    )?;
+
+    // ── Record mint AFTER successful CPI ──────────────────────────────
+    let mut state_mut =
+        TokenStateMut::from_slice(unsafe { token_state_account.borrow_unchecked_mut() });
+    state_mut.maybe_reset_daily(clock.unix_timestamp);
+    state_mut.record_mint(amount);
+    state_mut.set_last_op_ts(LastOp::Mint, clock.unix_timestamp);
+
+    Ok(())
+}
@@ -1,8 +1,27 @@
 pub mod account_checks;
+pub mod bytes;
+pub mod company_counters;
 pub mod compressed_accounts;
+pub mod coupon;
 pub mod cpi;
+pub mod escrow_common;
+pub mod events;
+pub mod heartbeat;
 pub mod instruction_data;
+pub mod log;
+pub mod math;
 pub mod memo;
+pub mod metadata;
+pub mod mint_common;
+pub mod nft_rate_limit;
+pub mod nonce;
 pub mod pda;
+pub mod receipt;
+pub mod refund_common;
 pub mod return_to_pool_common;
+pub mod stats;
+pub mod transfer_fee;
 pub mod transfer_validation;
+pub mod user_registry;
+pub mod withdraw_common;
+pub mod withdrawal_request;
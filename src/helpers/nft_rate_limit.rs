@@ -0,0 +1,140 @@
+//! Optional per-mint_authority NFT mint rate limit.
+//!
+//! `create_coupon_nft`, `mint_coupon_cnft` and `mint_coupon_cnft_batch` accept
+//! the mint_authority's RateLimitState PDA `[RATE_LIMIT_SEED, mint_authority]`
+//! as a trailing optional account. When it is passed, at most
+//! `MAX_NFT_MINTS_PER_WINDOW` NFTs are minted per `NFT_MINT_WINDOW_SECS`; the
+//! window restarts on the first mint after it ends. Without it the instructions
+//! behave as before. Only the NFT window fields are touched, so the token
+//! counters (`current_day` / `minted_today`) are unaffected.
+
+use pinocchio::error::ProgramError;
+use pinocchio::sysvars::Sysvar as _;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::{MAX_NFT_MINTS_PER_WINDOW, NFT_MINT_WINDOW_SECS, RATE_LIMIT_SEED};
+use crate::error::ZupyTokenError;
+use crate::helpers::account_checks::assert_discriminator;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::cpi::cpi_grow_account;
+use crate::helpers::pda::validate_pda_with_seeds;
+use crate::state::rate_limit_state::{
+    RateLimitState, RateLimitStateMut, RATE_LIMIT_STATE_DISCRIMINATOR, RATE_LIMIT_STATE_SIZE,
+    RATE_LIMIT_STATE_SIZE_WITH_NFT_WINDOW,
+};
+
+/// Counts `count` NFT mints against `mint_authority`'s RateLimitState.
+///
+/// Fails with `RateLimitNotInitialized` for an empty account, `InvalidPDA` /
+/// `InvalidAuthority` for another authority's state and `RateLimitExceeded`
+/// when the window is full. 57-byte accounts are grown first; mint_authority
+/// (writable, signer) pays the extra rent.
+pub fn consume_nft_mints(
+    program_id: &Address,
+    rate_limit: &AccountView,
+    mint_authority: &AccountView,
+    count: u64,
+) -> ProgramResult {
+    if rate_limit.data_len() == 0 {
+        return Err(ZupyTokenError::RateLimitNotInitialized.into());
+    }
+    if !rate_limit.owned_by(program_id) || rate_limit.data_len() < RATE_LIMIT_STATE_SIZE {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let state = RateLimitState::from_slice(unsafe { rate_limit.borrow_unchecked() });
+    assert_discriminator(state.discriminator(), &RATE_LIMIT_STATE_DISCRIMINATOR)?;
+    let authority_key: &[u8; 32] = address_bytes(mint_authority.address());
+    if state.authority() != authority_key {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    validate_pda_with_seeds(
+        rate_limit.address(),
+        &[RATE_LIMIT_SEED, authority_key, &[state.bump()]],
+        program_id,
+    )?;
+
+    let clock = pinocchio::sysvars::clock::Clock::get()?;
+    let (window_start, mints) = next_nft_window(
+        state.nft_window_start(),
+        state.nft_mints(),
+        clock.unix_timestamp,
+        count,
+    )?;
+
+    if rate_limit.data_len() < RATE_LIMIT_STATE_SIZE_WITH_NFT_WINDOW {
+        cpi_grow_account(mint_authority, rate_limit, RATE_LIMIT_STATE_SIZE_WITH_NFT_WINDOW)?;
+    }
+    let mut state = RateLimitStateMut::from_slice(unsafe { rate_limit.borrow_unchecked_mut() });
+    state.set_nft_window_start(window_start);
+    state.set_nft_mints(mints);
+    Ok(())
+}
+
+/// `(window_start, mints)` after minting `count` NFTs at `now`: a window older
+/// than `NFT_MINT_WINDOW_SECS` restarts at `now`, then `count` is added unless
+/// that exceeds `MAX_NFT_MINTS_PER_WINDOW`.
+#[inline(always)]
+fn next_nft_window(
+    window_start: i64,
+    mints: u64,
+    now: i64,
+    count: u64,
+) -> Result<(i64, u64), ProgramError> {
+    let (window_start, mints) = if now.saturating_sub(window_start) >= NFT_MINT_WINDOW_SECS {
+        (now, 0)
+    } else {
+        (window_start, mints)
+    };
+    let mints = mints
+        .checked_add(count)
+        .filter(|mints| *mints <= MAX_NFT_MINTS_PER_WINDOW)
+        .ok_or(ZupyTokenError::RateLimitExceeded)?;
+    Ok((window_start, mints))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: i64 = 1_700_000_000;
+
+    fn exceeded() -> Result<(i64, u64), ProgramError> {
+        Err(ZupyTokenError::RateLimitExceeded.into())
+    }
+
+    #[test]
+    fn test_counts_within_window() {
+        assert_eq!(next_nft_window(START, 0, START + 10, 1), Ok((START, 1)));
+        assert_eq!(next_nft_window(START, 7, START + 10, 3), Ok((START, 10)));
+    }
+
+    #[test]
+    fn test_last_mint_of_window_allowed() {
+        let max = MAX_NFT_MINTS_PER_WINDOW;
+        assert_eq!(next_nft_window(START, max - 1, START + 1, 1), Ok((START, max)));
+        assert_eq!(next_nft_window(START, max, START + 1, 1), exceeded());
+        assert_eq!(next_nft_window(START, max - 1, START + 1, 2), exceeded());
+    }
+
+    #[test]
+    fn test_window_boundary_resets() {
+        let max = MAX_NFT_MINTS_PER_WINDOW;
+        let end = START + NFT_MINT_WINDOW_SECS;
+        assert_eq!(next_nft_window(START, max, end - 1, 1), exceeded());
+        assert_eq!(next_nft_window(START, max, end, 1), Ok((end, 1)));
+    }
+
+    #[test]
+    fn test_fresh_window_still_capped() {
+        let max = MAX_NFT_MINTS_PER_WINDOW;
+        // Legacy accounts read window_start 0 → restart at now
+        assert_eq!(next_nft_window(0, 0, START, max), Ok((START, max)));
+        assert_eq!(next_nft_window(0, 0, START, max + 1), exceeded());
+    }
+
+    #[test]
+    fn test_counter_overflow_is_exceeded() {
+        assert_eq!(next_nft_window(START, 1, START, u64::MAX), exceeded());
+    }
+}
@@ -0,0 +1,108 @@
+//! Optional replay-protection nonce for `transfer_from_pool`.
+//!
+//! The `transfer_authority` signature alone authorizes identical transfers, so a
+//! captured transaction could be re-submitted within the blockhash window. The
+//! client may append a 9-byte trailer `[flag u8 = 1][nonce u64 LE]` after the
+//! existing payload (after the `op_id`, when one is sent). The handler rejects
+//! any nonce `<= TokenState.last_transfer_nonce` with `StaleNonce` and stores the
+//! new value, so `token_state` must be passed writable in this mode. Without the
+//! trailer (or with a lone flag byte 0) the instruction behaves exactly as before.
+//!
+//! Nonces never wrap: once `u64::MAX` is accepted every later nonced transfer
+//! is stale. Gaps are allowed, so the backend only needs a monotonic counter.
+
+use pinocchio::error::ProgramError;
+use pinocchio::AccountView;
+
+use crate::error::ZupyTokenError;
+use crate::helpers::instruction_data::Reader;
+use crate::state::token_state::TokenStateMut;
+
+/// Flag byte that enables the nonce check; 0 keeps it off.
+pub const NONCE_FLAG: u8 = 1;
+
+/// Length of the nonce trailer: flag (u8) + nonce (u64).
+pub const NONCE_TRAILER_LEN: usize = 9;
+
+/// Reads the optional nonce trailer left in `reader`.
+/// Returns `None` when no bytes follow or the flag is 0, which must be the last
+/// byte; a nonce after flag 0, or any other flag value, is `InvalidInstructionData`.
+pub fn parse_nonce(reader: &mut Reader<'_>) -> Result<Option<u64>, ProgramError> {
+    if reader.is_empty() {
+        return Ok(None);
+    }
+    match reader.read_u8()? {
+        0 if reader.is_empty() => Ok(None),
+        NONCE_FLAG => reader.read_u64().map(Some),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+/// Nonce must be strictly greater than the last accepted one → `StaleNonce`.
+pub fn check_nonce(last_nonce: u64, nonce: u64) -> Result<(), ProgramError> {
+    if nonce <= last_nonce {
+        return Err(ZupyTokenError::StaleNonce.into());
+    }
+    Ok(())
+}
+
+/// Checks `nonce` against `token_state` and persists it.
+/// `token_state_account` must already be validated as our TokenState PDA.
+pub fn consume_nonce(token_state_account: &AccountView, nonce: u64) -> Result<(), ProgramError> {
+    let mut state =
+        TokenStateMut::from_slice(unsafe { token_state_account.borrow_unchecked_mut() });
+    check_nonce(state.last_transfer_nonce(), nonce)?;
+    state.set_last_transfer_nonce(nonce);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nonce_absent_or_disabled() {
+        assert_eq!(parse_nonce(&mut Reader::new(&[])), Ok(None));
+        assert_eq!(parse_nonce(&mut Reader::new(&[0])), Ok(None));
+    }
+
+    #[test]
+    fn test_parse_nonce_flag_zero_with_nonce_rejected() {
+        assert_eq!(
+            parse_nonce(&mut Reader::new(&[0, 5, 0, 0, 0, 0, 0, 0, 0])),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+
+    #[test]
+    fn test_parse_nonce_enabled() {
+        let mut data = vec![NONCE_FLAG];
+        data.extend_from_slice(&42u64.to_le_bytes());
+        assert_eq!(data.len(), NONCE_TRAILER_LEN);
+        assert_eq!(parse_nonce(&mut Reader::new(&data)), Ok(Some(42)));
+        // Truncated trailer
+        assert_eq!(
+            parse_nonce(&mut Reader::new(&data[..data.len() - 1])),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+
+    #[test]
+    fn test_parse_nonce_unknown_flag() {
+        assert_eq!(parse_nonce(&mut Reader::new(&[2])), Err(ProgramError::InvalidInstructionData));
+    }
+
+    #[test]
+    fn test_check_nonce_ordering() {
+        let stale: ProgramError = ZupyTokenError::StaleNonce.into();
+        assert_eq!(check_nonce(0, 1), Ok(()));
+        assert_eq!(check_nonce(0, 0), Err(stale.clone()));
+        assert_eq!(check_nonce(10, 10), Err(stale.clone()));
+        assert_eq!(check_nonce(10, 9), Err(stale.clone()));
+        assert_eq!(check_nonce(10, 1_000), Ok(()));
+        // No wraparound: u64::MAX is terminal
+        assert_eq!(check_nonce(u64::MAX - 1, u64::MAX), Ok(()));
+        assert_eq!(check_nonce(u64::MAX, 0), Err(stale.clone()));
+        assert_eq!(check_nonce(u64::MAX, u64::MAX), Err(stale));
+    }
+}
@@ -2,9 +2,10 @@ use pinocchio::Address;
 use pinocchio::error::ProgramError;
 
 use crate::constants::{
-    COMPANY_SEED, COUPON_SEED, DISTRIBUTION_POOL_SEED, INCENTIVE_POOL_SEED,
-    RATE_LIMIT_SEED, TOKEN_STATE_SEED, USER_PDA_SEED, USER_SEED, ZUPY_CARD_MINT_SEED,
-    ZUPY_CARD_SEED,
+    BUBBLEGUM_PROGRAM_ID, COMPANY_SEED, COMPANY_STATE_SEED, COUPON_SEED, COUPON_STATE_SEED,
+    DISTRIBUTION_POOL_SEED, ESCROW_SEED, INCENTIVE_POOL_SEED, RATE_LIMIT_SEED, RECEIPT_SEED,
+    STATS_SEED, TOKEN_STATE_SEED, USER_PDA_SEED, USER_SEED, USER_STATE_SEED, VESTING_SEED,
+    WITHDRAW_LIMIT_SEED, WITHDRAWAL_REQUEST_SEED, ZUPY_CARD_MINT_SEED, ZUPY_CARD_SEED,
 };
 use crate::error::ZupyTokenError;
 
@@ -21,17 +22,34 @@ pub fn derive_company_pda(program_id: &Address, company_id: u64) -> (Address, u8
     Address::find_program_address(&[COMPANY_SEED, &bytes], program_id)
 }
 
+/// Derive company registry PDA. Seeds: `[b"company_state", &company_id.to_le_bytes()]`
+pub fn derive_company_state_pda(program_id: &Address, company_id: u64) -> (Address, u8) {
+    let bytes = company_id.to_le_bytes();
+    Address::find_program_address(&[COMPANY_STATE_SEED, &bytes], program_id)
+}
+
 /// Derive user PDA. Seeds: `[b"user", &user_id.to_le_bytes()]`
 pub fn derive_user_pda(program_id: &Address, user_id: u64) -> (Address, u8) {
     let bytes = user_id.to_le_bytes();
     Address::find_program_address(&[USER_SEED, &bytes], program_id)
 }
 
+/// Derive user registry PDA. Seeds: `[b"user_state", &user_id.to_le_bytes()]`
+pub fn derive_user_state_pda(program_id: &Address, user_id: u64) -> (Address, u8) {
+    let bytes = user_id.to_le_bytes();
+    Address::find_program_address(&[USER_STATE_SEED, &bytes], program_id)
+}
+
 /// Derive incentive_pool PDA. Seeds: `[b"incentive_pool"]`
 pub fn derive_incentive_pool_pda(program_id: &Address) -> (Address, u8) {
     Address::find_program_address(&[INCENTIVE_POOL_SEED], program_id)
 }
 
+/// Derive global stats PDA. Seeds: `[b"stats"]`
+pub fn derive_stats_pda(program_id: &Address) -> (Address, u8) {
+    Address::find_program_address(&[STATS_SEED], program_id)
+}
+
 /// Derive distribution_pool PDA. Seeds: `[b"distribution_pool"]`
 pub fn derive_distribution_pool_pda(program_id: &Address) -> (Address, u8) {
     Address::find_program_address(&[DISTRIBUTION_POOL_SEED], program_id)
@@ -52,6 +70,11 @@ pub fn derive_coupon_mint_pda(program_id: &Address, coupon_ksuid: &[u8]) -> (Add
     Address::find_program_address(&[COUPON_SEED, coupon_ksuid], program_id)
 }
 
+/// Derive coupon state PDA. Seeds: `[b"coupon_state", &coupon_ksuid]`
+pub fn derive_coupon_state_pda(program_id: &Address, coupon_ksuid: &[u8]) -> (Address, u8) {
+    Address::find_program_address(&[COUPON_STATE_SEED, coupon_ksuid], program_id)
+}
+
 /// Derive user_nft PDA. Seeds: `[b"user_pda", &user_ksuid]`
 pub fn derive_user_nft_pda(program_id: &Address, user_ksuid: &[u8]) -> (Address, u8) {
     Address::find_program_address(&[USER_PDA_SEED, user_ksuid], program_id)
@@ -62,6 +85,40 @@ pub fn derive_rate_limit_pda(program_id: &Address, authority: &[u8; 32]) -> (Add
     Address::find_program_address(&[RATE_LIMIT_SEED, authority], program_id)
 }
 
+/// Derive escrow PDA. Seeds: `[b"escrow", &escrow_id.to_le_bytes()]`
+pub fn derive_escrow_pda(program_id: &Address, escrow_id: u64) -> (Address, u8) {
+    let bytes = escrow_id.to_le_bytes();
+    Address::find_program_address(&[ESCROW_SEED, &bytes], program_id)
+}
+
+/// Derive vesting PDA. Seeds: `[b"vesting", &user_id.to_le_bytes()]`
+pub fn derive_vesting_pda(program_id: &Address, user_id: u64) -> (Address, u8) {
+    let bytes = user_id.to_le_bytes();
+    Address::find_program_address(&[VESTING_SEED, &bytes], program_id)
+}
+
+/// Derive idempotency receipt PDA. Seeds: `[b"receipt", op_id]`
+pub fn derive_receipt_pda(program_id: &Address, op_id: &[u8; 16]) -> (Address, u8) {
+    Address::find_program_address(&[RECEIPT_SEED, op_id], program_id)
+}
+
+/// Derive per-user withdraw limit PDA. Seeds: `[b"withdraw_limit", &user_id.to_le_bytes()]`
+pub fn derive_withdraw_limit_pda(program_id: &Address, user_id: u64) -> (Address, u8) {
+    let bytes = user_id.to_le_bytes();
+    Address::find_program_address(&[WITHDRAW_LIMIT_SEED, &bytes], program_id)
+}
+
+/// Derive withdrawal request PDA. Seeds: `[b"withdrawal_request", &request_id.to_le_bytes()]`
+pub fn derive_withdrawal_request_pda(program_id: &Address, request_id: u64) -> (Address, u8) {
+    let bytes = request_id.to_le_bytes();
+    Address::find_program_address(&[WITHDRAWAL_REQUEST_SEED, &bytes], program_id)
+}
+
+/// Derive Bubblegum tree_config PDA. Seeds: `[merkle_tree]` on `BUBBLEGUM_PROGRAM_ID`
+pub fn derive_tree_config_pda(merkle_tree: &Address) -> (Address, u8) {
+    Address::find_program_address(&[merkle_tree.as_ref()], &Address::from(BUBBLEGUM_PROGRAM_ID))
+}
+
 // ── Validation ──────────────────────────────────────────────────────────
 
 /// Validate that an account key matches the expected PDA.
@@ -100,6 +157,42 @@ pub fn validate_pda_with_seeds(
     Ok(())
 }
 
+/// Seeds per PDA, bump included (runtime limit).
+const MAX_PDA_SEEDS: usize = 16;
+
+/// Like [`validate_pda_with_seeds`], but `seeds` excludes the bump, which is
+/// passed separately. Returns the bump to sign with.
+///
+/// The client's `bump` is tried first. With the `pda-bump-fallback` feature, a
+/// mismatch falls back to `find_program_address` and accepts the account if it
+/// is the canonical PDA, returning the canonical bump; that path costs ~1.5K CU
+/// per bump tried, so it stays opt-in. Without the feature a wrong bump is
+/// `InvalidPDA`, as before.
+pub fn validate_pda_or_find(
+    account_key: &Address,
+    seeds: &[&[u8]],
+    bump: u8,
+    program_id: &Address,
+) -> Result<u8, ProgramError> {
+    if seeds.len() >= MAX_PDA_SEEDS {
+        return Err(ZupyTokenError::InvalidPDA.into());
+    }
+    let bump_seed = [bump];
+    let mut with_bump: [&[u8]; MAX_PDA_SEEDS] = [&[]; MAX_PDA_SEEDS];
+    with_bump[..seeds.len()].copy_from_slice(seeds);
+    with_bump[seeds.len()] = &bump_seed;
+    if validate_pda_with_seeds(account_key, &with_bump[..=seeds.len()], program_id).is_ok() {
+        return Ok(bump);
+    }
+
+    if !cfg!(feature = "pda-bump-fallback") {
+        return Err(ZupyTokenError::InvalidPDA.into());
+    }
+    let (expected, canonical_bump) = Address::find_program_address(seeds, program_id);
+    validate_pda(account_key, &expected)?;
+    Ok(canonical_bump)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -137,6 +230,24 @@ mod tests {
         assert_ne!(addr1, addr2);
     }
 
+    #[test]
+    fn test_company_state_pda_distinct_from_company_pda() {
+        let pid = test_program_id();
+        let (state, _) = derive_company_state_pda(&pid, 42);
+        assert_eq!(state, derive_company_state_pda(&pid, 42).0);
+        assert_ne!(state, derive_company_pda(&pid, 42).0);
+        assert_ne!(state, derive_company_state_pda(&pid, 43).0);
+    }
+
+    #[test]
+    fn test_user_state_pda_distinct_from_user_pda() {
+        let pid = test_program_id();
+        let (state, _) = derive_user_state_pda(&pid, 42);
+        assert_eq!(state, derive_user_state_pda(&pid, 42).0);
+        assert_ne!(state, derive_user_pda(&pid, 42).0);
+        assert_ne!(state, derive_user_state_pda(&pid, 43).0);
+    }
+
     #[test]
     fn test_user_pda_deterministic() {
         let pid = test_program_id();
@@ -219,6 +330,15 @@ mod tests {
         assert_eq!(bump1, bump2);
     }
 
+    #[test]
+    fn test_coupon_state_and_coupon_mint_are_different() {
+        let pid = test_program_id();
+        let ksuid = b"1ujsszwN8NRY24YaXiTIE2VWDTS";
+        let (state_addr, _) = derive_coupon_state_pda(&pid, ksuid);
+        let (mint_addr, _) = derive_coupon_mint_pda(&pid, ksuid);
+        assert_ne!(state_addr, mint_addr);
+    }
+
     #[test]
     fn test_user_nft_pda_deterministic() {
         let pid = test_program_id();
@@ -247,6 +367,16 @@ mod tests {
         assert_ne!(addr1, addr2);
     }
 
+    #[test]
+    fn test_escrow_pda_distinct_from_company_and_user() {
+        let pid = test_program_id();
+        let (escrow, _) = derive_escrow_pda(&pid, 42);
+        let (company, _) = derive_company_pda(&pid, 42);
+        let (user, _) = derive_user_pda(&pid, 42);
+        assert_ne!(escrow, company);
+        assert_ne!(escrow, user);
+    }
+
     #[test]
     fn test_all_single_seed_pdas_are_distinct() {
         let pid = test_program_id();
@@ -271,6 +401,20 @@ mod tests {
 
     // ── AC7: validate_pda tests ─────────────────────────────────────────
 
+    #[test]
+    fn test_tree_config_pda_depends_on_merkle_tree() {
+        let tree_a = Address::from([7u8; 32]);
+        let tree_b = Address::from([8u8; 32]);
+        let (a1, bump1) = derive_tree_config_pda(&tree_a);
+        let (a2, bump2) = derive_tree_config_pda(&tree_a);
+        assert_eq!(a1, a2);
+        assert_eq!(bump1, bump2);
+        assert_ne!(a1, derive_tree_config_pda(&tree_b).0);
+        // Bubblegum-owned: differs from the same seeds on this program
+        let (own, _) = Address::find_program_address(&[tree_a.as_ref()], &test_program_id());
+        assert_ne!(a1, own);
+    }
+
     #[test]
     fn test_validate_pda_matching() {
         let pid = test_program_id();
@@ -358,4 +502,51 @@ mod tests {
             &pid,
         ).is_ok());
     }
+
+    // ── validate_pda_or_find ────────────────────────────────────────────
+
+    #[test]
+    fn test_validate_pda_or_find_correct_bump_fast_path() {
+        let pid = test_program_id();
+        let (pda, bump) = derive_company_pda(&pid, 42);
+        let id = 42u64.to_le_bytes();
+        assert_eq!(validate_pda_or_find(&pda, &[COMPANY_SEED, &id], bump, &pid), Ok(bump));
+    }
+
+    #[cfg(feature = "pda-bump-fallback")]
+    #[test]
+    fn test_validate_pda_or_find_wrong_bump_falls_back() {
+        let pid = test_program_id();
+        let (pda, bump) = derive_company_pda(&pid, 42);
+        let id = 42u64.to_le_bytes();
+        let wrong_bump = bump.wrapping_sub(1);
+        assert_eq!(validate_pda_or_find(&pda, &[COMPANY_SEED, &id], wrong_bump, &pid), Ok(bump));
+    }
+
+    #[cfg(not(feature = "pda-bump-fallback"))]
+    #[test]
+    fn test_validate_pda_or_find_wrong_bump_rejected_without_fallback() {
+        let pid = test_program_id();
+        let (pda, bump) = derive_company_pda(&pid, 42);
+        let id = 42u64.to_le_bytes();
+        let wrong_bump = bump.wrapping_sub(1);
+        assert_eq!(
+            validate_pda_or_find(&pda, &[COMPANY_SEED, &id], wrong_bump, &pid),
+            Err(ProgramError::Custom(ZupyTokenError::InvalidPDA as u32))
+        );
+    }
+
+    #[test]
+    fn test_validate_pda_or_find_wrong_address() {
+        let pid = test_program_id();
+        let (_, bump) = derive_company_pda(&pid, 42);
+        let (other, _) = derive_company_pda(&pid, 43);
+        let id = 42u64.to_le_bytes();
+        for b in [bump, bump.wrapping_sub(1)] {
+            assert_eq!(
+                validate_pda_or_find(&other, &[COMPANY_SEED, &id], b, &pid),
+                Err(ProgramError::Custom(ZupyTokenError::InvalidPDA as u32))
+            );
+        }
+    }
 }
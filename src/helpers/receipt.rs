@@ -0,0 +1,111 @@
+//! Optional idempotency receipts for transfer instructions
+//! (`transfer_from_pool`, `transfer_company_to_user`, `withdraw_to_external`).
+//!
+//! The client appends a 16-byte `op_id` to the instruction data and inserts the
+//! receipt PDA `[RECEIPT_SEED, op_id]` into the account list. The handler creates
+//! the PDA in the same transaction as the transfer, so a retry with the same
+//! `op_id` fails with `DuplicateOperation` before any tokens move. Without an
+//! `op_id` the instruction behaves exactly as before.
+
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::sysvars::Sysvar as _;
+use pinocchio::{AccountView, Address};
+
+use crate::constants::RECEIPT_SEED;
+use crate::error::ZupyTokenError;
+use crate::helpers::cpi::cpi_create_account;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::pda::{derive_receipt_pda, validate_pda};
+use crate::state::receipt_state::{
+    ReceiptStateMut, RECEIPT_STATE_DISCRIMINATOR, RECEIPT_STATE_SIZE,
+};
+
+/// Length of the client-supplied operation id.
+pub const OP_ID_LEN: usize = 16;
+
+/// Reads the optional `op_id` at the end of the existing payload.
+/// Returns `None`, consuming nothing, when fewer than 16 bytes follow,
+/// preserving today's layout.
+pub fn parse_op_id(reader: &mut Reader<'_>) -> Option<[u8; OP_ID_LEN]> {
+    if reader.remaining() < OP_ID_LEN {
+        return None;
+    }
+    reader.read_bytes::<OP_ID_LEN>().ok().copied()
+}
+
+/// Creates the receipt PDA for `op_id`, funded by `payer`.
+///
+/// Checks (in order): `payer` is signer, receipt address matches
+/// `[RECEIPT_SEED, op_id]`, receipt not yet created → `DuplicateOperation`.
+/// Must run after all transfer validation so a rejected transfer never burns
+/// an `op_id`; the enclosing transaction reverts the receipt if the CPI fails.
+pub fn create_receipt(
+    program_id: &Address,
+    receipt_account: &AccountView,
+    payer: &AccountView,
+    op_id: &[u8; OP_ID_LEN],
+) -> Result<(), ProgramError> {
+    if !payer.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+
+    let (expected_pda, bump) = derive_receipt_pda(program_id, op_id);
+    validate_pda(receipt_account.address(), &expected_pda)?;
+    if receipt_account.data_len() > 0 {
+        return Err(ZupyTokenError::DuplicateOperation.into());
+    }
+
+    let bump_bytes = [bump];
+    let signer_seeds: [Seed; 3] = [
+        Seed::from(RECEIPT_SEED),
+        Seed::from(op_id.as_ref()),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    let signer = Signer::from(&signer_seeds);
+
+    cpi_create_account(
+        payer,
+        receipt_account,
+        RECEIPT_STATE_SIZE as u64,
+        program_id,
+        &[signer],
+    )?;
+
+    let clock = pinocchio::sysvars::clock::Clock::get()?;
+    let payer_key: &[u8; 32] = payer.address().as_ref().try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    let mut receipt =
+        ReceiptStateMut::from_slice(unsafe { receipt_account.borrow_unchecked_mut() });
+    receipt.set_discriminator(&RECEIPT_STATE_DISCRIMINATOR);
+    receipt.set_op_id(op_id);
+    receipt.set_payer(payer_key);
+    receipt.set_created_at(clock.unix_timestamp);
+    receipt.set_bump(bump);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_op_id_absent_when_payload_ends() {
+        assert_eq!(parse_op_id(&mut Reader::new(&[])), None);
+        let mut reader = Reader::new(&[1u8; 15]);
+        assert_eq!(parse_op_id(&mut reader), None);
+        assert_eq!(reader.remaining(), 15);
+    }
+
+    #[test]
+    fn test_parse_op_id_reads_exact_bytes() {
+        let op_id: [u8; 16] = core::array::from_fn(|i| i as u8);
+        let mut data = op_id.to_vec();
+        data.push(9);
+        let mut reader = Reader::new(&data);
+        assert_eq!(parse_op_id(&mut reader), Some(op_id));
+        assert_eq!(reader.rest(), &[9]);
+    }
+}
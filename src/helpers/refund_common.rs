@@ -0,0 +1,191 @@
+//! Shared logic for refund instructions
+//! (`refund_company_to_user`, `refund_user_to_company`).
+//!
+//! A refund is the reverse compressed transfer (Path B) of an earlier payment.
+//! Its memo `zupy:v1:refund:{original_source}:{original_id}` embeds the original
+//! memo suffix, and the referenced operation must be the opposite direction of
+//! the refund — e.g. a user→company refund can only reference `company_to_user`.
+
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::{COMPANY_SEED, LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, MEMO_MAX_LEN, USER_SEED};
+use crate::error::ZupyTokenError;
+use crate::helpers::compressed_accounts::cpi_compressed_transfer;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::memo::validate_refund_memo;
+use crate::helpers::pda::validate_pda_with_seeds;
+use crate::helpers::transfer_validation::validate_transfer_common_compressed;
+
+/// The original operation a refund reverses.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RefundOf {
+    /// Original company→user payment; refund moves user → company.
+    CompanyToUser,
+    /// Original user→company payment; refund moves company → user.
+    UserToCompany,
+}
+
+impl RefundOf {
+    /// Memo `source` segment of the original operation.
+    pub fn original_source(self) -> &'static str {
+        match self {
+            RefundOf::CompanyToUser => "company_to_user",
+            RefundOf::UserToCompany => "user_to_company",
+        }
+    }
+}
+
+/// Reverse compressed transfer between a company PDA and a user PDA.
+///
+/// Accounts (8):
+///   0. transfer_authority (signer)
+///   1. token_state (read)
+///   2. mint (read)
+///   3. company_pda (read)              — destination for `CompanyToUser`, source otherwise
+///   4. user_pda (read)                 — source for `CompanyToUser`, destination otherwise
+///   5. fee_payer (writable, signer)    — pays Light state tree fees
+///   6. system_program (read)
+///   7. compressed_token_program (read) — cTokenmWW8bLPjZEBAUgYy3zKxQZW6VKi7bqNFEVv3m
+///
+/// Data: company_id (0-7) + user_id (8-15) + amount (16-23) + original_amount (24-31)
+///       + company_bump (32) + user_bump (33)
+///       + memo (34+, `zupy:v1:refund:{original_source}:{original_id}`)
+///
+/// Validates (in order): zero amount / zero original_amount → `ZeroAmount`,
+/// amount > original_amount → `InvalidAmount`, refund memo (format and direction),
+/// checks 1–8 (incl. paused), compressed_token_program, both PDAs.
+/// Logs the refund memo and the original memo before the CPI.
+pub fn refund_compressed(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+    refund_of: RefundOf,
+) -> ProgramResult {
+    // ── Account extraction (8 accounts minimum) ─────────────────────────
+    if accounts.len() < 8 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let transfer_authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let mint = &accounts[2];
+    let company_pda = &accounts[3];
+    let user_pda = &accounts[4];
+    let fee_payer = &accounts[5];
+    let system_program = &accounts[6];
+    let compressed_token_program = &accounts[7];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let company_id_u64 = reader.read_u64()?;
+    let user_id_u64 = reader.read_u64()?;
+    let amount = reader.read_u64()?;
+    let original_amount = reader.read_u64()?;
+    let company_bump = reader.read_u8()?;
+    let user_bump = reader.read_u8()?;
+    let memo = reader.read_string()?;
+    reader.finish()?;
+
+    // ── Input validation ────────────────────────────────────────────────
+    if amount == 0 || original_amount == 0 {
+        return Err(ZupyTokenError::ZeroAmount.into());
+    }
+    if amount > original_amount {
+        return Err(ZupyTokenError::InvalidAmount.into());
+    }
+    let original_suffix = validate_refund_memo(memo, refund_of.original_source())?;
+
+    // ── Common transfer validation (checks 1–8) ─────────────────────────
+    validate_transfer_common_compressed(
+        program_id,
+        token_state_account,
+        transfer_authority,
+        mint,
+    )?;
+
+    // ── Check 9: compressed_token_program is Light cToken program ───────
+    let light_ctoken_addr = Address::from(LIGHT_COMPRESSED_TOKEN_PROGRAM_ID);
+    if compressed_token_program.address() != &light_ctoken_addr {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    // ── PDA validation: company_pda + user_pda ──────────────────────────
+    let company_id_bytes = company_id_u64.to_le_bytes();
+    validate_pda_with_seeds(
+        company_pda.address(),
+        &[COMPANY_SEED, &company_id_bytes, &[company_bump]],
+        program_id,
+    )?;
+    let user_id_bytes = user_id_u64.to_le_bytes();
+    validate_pda_with_seeds(
+        user_pda.address(),
+        &[USER_SEED, &user_id_bytes, &[user_bump]],
+        program_id,
+    )?;
+
+    // ── Audit trail: refund memo + original memo ────────────────────────
+    crate::helpers::log::sol_log(memo);
+    let (line, len) = original_memo_line(original_suffix);
+    if let Ok(line) = core::str::from_utf8(&line[..len]) {
+        crate::helpers::log::sol_log(line);
+    }
+
+    // ── CPI: reverse compressed transfer (source PDA signs) ─────────────
+    let (source, destination) = match refund_of {
+        RefundOf::CompanyToUser => (user_pda, company_pda),
+        RefundOf::UserToCompany => (company_pda, user_pda),
+    };
+    let (seed, id_bytes, bump) = match refund_of {
+        RefundOf::CompanyToUser => (USER_SEED, &user_id_bytes, user_bump),
+        RefundOf::UserToCompany => (COMPANY_SEED, &company_id_bytes, company_bump),
+    };
+    let bump_bytes = [bump];
+    let signer_seeds: [Seed; 3] = [
+        Seed::from(seed),
+        Seed::from(id_bytes.as_ref()),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    let signer = Signer::from(&signer_seeds);
+
+    cpi_compressed_transfer(
+        compressed_token_program,
+        fee_payer,
+        source,
+        destination,
+        source, // authority (source PDA signs)
+        system_program,
+        amount,
+        &[signer],
+    )
+}
+
+const ORIGINAL_MEMO_PREFIX: &[u8] = b"zupy:v1:";
+
+/// `zupy:v1:{suffix}` built on the stack (no `format!`); the suffix comes from
+/// a validated memo, so it is at most `MEMO_MAX_LEN` bytes of ASCII.
+fn original_memo_line(suffix: &str) -> ([u8; ORIGINAL_MEMO_PREFIX.len() + MEMO_MAX_LEN], usize) {
+    let mut line = [0u8; ORIGINAL_MEMO_PREFIX.len() + MEMO_MAX_LEN];
+    let suffix = &suffix.as_bytes()[..suffix.len().min(MEMO_MAX_LEN)];
+    let len = ORIGINAL_MEMO_PREFIX.len() + suffix.len();
+    line[..ORIGINAL_MEMO_PREFIX.len()].copy_from_slice(ORIGINAL_MEMO_PREFIX);
+    line[ORIGINAL_MEMO_PREFIX.len()..len].copy_from_slice(suffix);
+    (line, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_original_memo_line() {
+        let (line, len) = original_memo_line("company_to_user:42");
+        assert_eq!(&line[..len], b"zupy:v1:company_to_user:42");
+    }
+
+    #[test]
+    fn test_refund_directions_reference_opposite_operations() {
+        assert_eq!(RefundOf::CompanyToUser.original_source(), "company_to_user");
+        assert_eq!(RefundOf::UserToCompany.original_source(), "user_to_company");
+    }
+}
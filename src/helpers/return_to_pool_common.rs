@@ -7,6 +7,11 @@
 //!
 //! - [`v1_passthrough_to_pool`]: V1 path — forwards pre-built V1 TRANSFER CPI to mainnet cToken.
 //!   Used by `return_to_pool_v1` (company) and `return_user_to_pool_v1` (user).
+//!
+//! Both accept [`RETURN_ALL_AMOUNT`] (`u64::MAX`) as "return the full available
+//! balance", resolved at execution time so the backend does not race a stale query:
+//! the V2 path sums the compressed input leaves it spends, the V1 path the leaves
+//! in the forwarded payload.
 
 use pinocchio::cpi::{Seed, Signer};
 use pinocchio::error::ProgramError;
@@ -14,17 +19,26 @@ use pinocchio::instruction::{InstructionAccount, InstructionView};
 use pinocchio::{AccountView, Address, ProgramResult};
 
 use crate::constants::{
-    LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, LIGHT_TOKEN_CPI_AUTHORITY, TOKEN_2022_PROGRAM_ID,
+    COMPANY_SEED, LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, LIGHT_TOKEN_CPI_AUTHORITY,
+    RETURN_ALL_AMOUNT, TOKEN_2022_PROGRAM_ID, USER_SEED,
 };
 use crate::error::ZupyTokenError;
+use crate::helpers::account_checks::expect_accounts;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::company_counters::{company_counters, split_company_state};
 use crate::helpers::compressed_accounts::{
-    cpi_decompress_to_spl, derive_spl_interface_pda, validate_v1_transfer_disc,
+    cpi_decompress_to_spl, cpi_merge_decompress_to_spl, derive_spl_interface_pda,
+    locate_v1_decompress_amount, parse_v1_proof_layout, validate_v1_transfer_disc, MergeInputs,
+    TRANSFER_V1_DISC,
 };
-use crate::helpers::instruction_data::{parse_string, parse_u64, parse_u8};
-use crate::helpers::memo::validate_memo_format;
+use crate::helpers::cpi::{cpi_spl_memo, split_memo_program};
+use crate::helpers::events::{emit_anchor_event, TransferEvent};
+use crate::helpers::instruction_data::{parse_u64, parse_u8, Reader};
+use crate::helpers::memo::{validate_memo_op, MemoOp};
 use crate::helpers::pda::{validate_pda, validate_pda_with_seeds};
-use crate::helpers::transfer_validation::validate_transfer_common;
-use crate::state::token_state::TokenState;
+use crate::helpers::stats::{split_stats_state, stats_counters};
+use crate::helpers::transfer_validation::{enforce_v1_sunset, validate_transfer_common};
+use crate::state::token_state::TokenStateMut;
 
 /// V2 decompress path: compressed PDA balance → pool ATA via Light Transfer2.
 ///
@@ -41,54 +55,104 @@ use crate::state::token_state::TokenState;
 ///   9. compressed_token_authority (read)
 ///   10. spl_interface_pda        (writable)
 ///   11+ Light system accounts
+///   last: spl_memo_program (read, optional) — when present the validated memo is also
+///         emitted via CPI to SPL Memo; stripped before the Light accounts are forwarded
+///   company_state (writable, optional, company only) — CompanyState PDA before any memo
+///         program; `total_sent`, also stripped (see `helpers::company_counters`)
+///   stats (writable, optional) — Stats PDA before any CompanyState / memo program;
+///         `total_pool_inflow`, also stripped (see `helpers::stats`)
 ///
 /// Data: entity_id (0-7) + amount (8-15) + entity_bump (16) + memo (17+)
+///
+/// `RETURN_ALL_AMOUNT` without `merged`: the memo is followed by a [`MergeInputs`]
+/// section naming the entity's compressed leaves, and the return goes through
+/// the merged path below with the amount resolved to their sum.
+///
+/// `merged` (the `*_merged` instructions): the memo is followed by a [`MergeInputs`]
+/// section instead — 1 to `MAX_MERGE_INPUTS` compressed leaves with their Merkle
+/// contexts (`InvalidInputCount` otherwise). Their amounts are summed, `amount`
+/// (or all of it, for `RETURN_ALL_AMOUNT`) is decompressed in one CPI and the
+/// change is re-compressed to the entity. `amount` above the sum fails with
+/// `InsufficientBalance`; tree / queue indices must address the Light accounts
+/// at 11+ (`NotEnoughAccountKeys`).
 pub fn decompress_to_pool(
     program_id: &Address,
     accounts: &[AccountView],
     data: &[u8],
     pda_seed: &[u8],
+    merged: bool,
 ) -> ProgramResult {
+    // ── Optional trailing SPL Memo program ──────────────────────────────
+    let (accounts, memo_program) = split_memo_program(accounts);
+
+    // ── Optional trailing CompanyState (company returns only) ───────────
+    let (accounts, company_state) = if pda_seed == COMPANY_SEED {
+        split_company_state(program_id, accounts)
+    } else {
+        (accounts, None)
+    };
+
+    // ── Optional trailing Stats (global volume counters) ────────────────
+    let (accounts, stats) = split_stats_state(program_id, accounts);
+
     // ── Account extraction (11 accounts minimum) ─────────────────────────
-    if accounts.len() < 11 {
-        return Err(ProgramError::NotEnoughAccountKeys);
-    }
-    let transfer_authority = &accounts[0];
-    let token_state_account = &accounts[1];
-    let mint = &accounts[2];
-    let entity_pda = &accounts[3];
-    let pool_ata = &accounts[4];
-    let fee_payer = &accounts[5];
-    let token_program = &accounts[6];
-    let system_program = &accounts[7];
-    let compressed_token_prog = &accounts[8];
-    let compressed_token_auth = &accounts[9];
-    let spl_interface_pda = &accounts[10];
+    expect_accounts!(accounts, [
+        transfer_authority,
+        token_state_account,
+        mint,
+        entity_pda,
+        pool_ata,
+        fee_payer,
+        token_program,
+        system_program,
+        compressed_token_prog,
+        compressed_token_auth,
+        spl_interface_pda,
+    ]);
 
     // ── Parse instruction data ──────────────────────────────────────────
-    let entity_id_u64 = parse_u64(data, 0)?;
-    let amount = parse_u64(data, 8)?;
-    let entity_bump = parse_u8(data, 16)?;
-    let (memo, _) = parse_string(data, 17)?;
+    let mut reader = Reader::new(data);
+    let entity_id_u64 = reader.read_u64()?;
+    let amount = reader.read_u64()?;
+    let entity_bump = reader.read_u8()?;
+    let memo = reader.read_string()?;
+    let return_all = amount == RETURN_ALL_AMOUNT;
+    let inputs = if merged || return_all { Some(MergeInputs::parse(&mut reader)?) } else { None };
+
+    // ── Resolve "return all" sentinel from the compressed input leaves ───
+    let amount = match &inputs {
+        Some(inputs) if return_all => inputs.total()?,
+        _ => amount,
+    };
+    reader.finish()?;
 
     // ── Input validation ────────────────────────────────────────────────
     if amount == 0 {
         return Err(ZupyTokenError::ZeroAmount.into());
     }
-    validate_memo_format(memo)?;
+    validate_memo_op(memo, MemoOp::Return)?;
+
+    // ── Merged inputs: cover the amount, address forwarded Light accounts ─
+    if let Some(inputs) = &inputs {
+        if amount > inputs.total()? {
+            return Err(ZupyTokenError::InsufficientBalance.into());
+        }
+        inputs.validate_accounts(accounts.len() - 11)?;
+    }
 
     // ── Common transfer validation (9 checks, Spec §7.1-§7.8) ───────────
-    validate_transfer_common(
+    let state = validate_transfer_common(
         program_id,
         token_state_account,
         transfer_authority,
         mint,
         token_program,
-    )?;
+    )?
+    .state;
 
     // ── Additional signer check: fee_payer ──────────────────────────────
     if !fee_payer.is_signer() {
-        return Err(ZupyTokenError::InvalidAuthority.into());
+        return Err(ZupyTokenError::FeePayerNotSigner.into());
     }
 
     // ── Verify compressed_token_program is the Light cToken program ──────
@@ -112,7 +176,6 @@ pub fn decompress_to_pool(
     )?;
 
     // ── Pool ATA validation ─────────────────────────────────────────────
-    let state = TokenState::from_slice(unsafe { token_state_account.borrow_unchecked() });
     if pool_ata.address().as_ref() != state.pool_ata() {
         return Err(ZupyTokenError::InvalidPoolAccount.into());
     }
@@ -130,6 +193,18 @@ pub fn decompress_to_pool(
     let (expected_spl_pda, spl_bump) = derive_spl_interface_pda(&mint_key);
     validate_pda(spl_interface_pda.address(), &expected_spl_pda)?;
 
+    // ── Standard memo (optional) ────────────────────────────────────────
+    if let Some(memo_program) = memo_program {
+        cpi_spl_memo(memo_program, memo)?;
+    }
+
+    // ── Event log (all checks passed) ───────────────────────────────────
+    emit_anchor_event(&TransferEvent {
+        from: *address_bytes(entity_pda.address()),
+        to: *address_bytes(pool_ata.address()),
+        amount,
+    });
+
     // ── CPI: Decompress entity compressed balance → pool ATA ────────────
     let bump_bytes = [entity_bump];
     let signer_seeds: [Seed; 3] = [
@@ -139,21 +214,49 @@ pub fn decompress_to_pool(
     ];
     let signer = Signer::from(&signer_seeds);
 
-    cpi_decompress_to_spl(
-        compressed_token_prog,
-        compressed_token_auth,
-        fee_payer,
-        mint,
-        pool_ata,
-        entity_pda,
-        spl_interface_pda,
-        token_program,
-        system_program,
-        amount,
-        spl_bump,
-        &accounts[11..],
-        &[signer],
-    )?;
+    match &inputs {
+        Some(inputs) => cpi_merge_decompress_to_spl(
+            compressed_token_auth,
+            fee_payer,
+            mint,
+            pool_ata,
+            entity_pda,
+            spl_interface_pda,
+            token_program,
+            system_program,
+            inputs,
+            amount,
+            spl_bump,
+            state.decimals(),
+            &accounts[11..],
+            &[signer],
+        )?,
+        None => cpi_decompress_to_spl(
+            compressed_token_prog,
+            compressed_token_auth,
+            fee_payer,
+            mint,
+            pool_ata,
+            entity_pda,
+            spl_interface_pda,
+            token_program,
+            system_program,
+            amount,
+            spl_bump,
+            state.decimals(),
+            &accounts[11..],
+            &[signer],
+        )?,
+    }
+
+    // ── Company counters (optional, saturating) ─────────────────────────
+    if let Some(mut counters) = company_counters(company_state, entity_id_u64) {
+        counters.record_sent(amount);
+    }
+    // ── Global stats (optional, saturating) ─────────────────────────────
+    if let Some(mut counters) = stats_counters(stats) {
+        counters.record_pool_inflow(amount);
+    }
 
     Ok(())
 }
@@ -162,14 +265,32 @@ pub fn decompress_to_pool(
 ///
 /// Accounts (minimum 6 + CPI accounts):
 ///   0. transfer_authority        (signer)
-///   1. token_state               (read)
+///   1. token_state               (read)           — writable: V1 return counters
 ///   2. mint                      (read)
 ///   3. entity_pda                (read)           — company or user PDA
 ///   4. pool_ata                  (read)
 ///   5. token_program             (read)
 ///   6+ V1 CPI accounts           (client-assembled)
 ///
-/// Data: entity_id (0-7) + entity_bump (8) + raw V1 CPI data (9+)
+/// Data: entity_id (0-7) + entity_bump (8) + [memo (String)] + raw V1 CPI data
+///
+/// The optional memo is told apart from the V1 data by whether byte 9 starts the
+/// V1 TRANSFER disc (whose first 4 bytes are no valid memo length).
+///
+/// Validation mirrors [`decompress_to_pool`] with the same error codes: the V1
+/// payload's `compress_or_decompress_amount` must be non-zero (`ZeroAmount`), the
+/// memo (when sent) must be a `return` memo (`validate_memo_op`), then common
+/// transfer validation (authority, paused), the V1 sunset (`V1Sunset` once
+/// `token_state.v1_sunset_ts` has passed) and the entity PDA. A payload the V1
+/// walker cannot parse is rejected with `InvalidInstructionData`.
+///
+/// If the V1 payload's `compress_or_decompress_amount` is `Some(RETURN_ALL_AMOUNT)`,
+/// it is rewritten to the inputs' total minus change outputs before forwarding.
+///
+/// After the CPI succeeds, the (resolved) amount is added to
+/// `token_state.v1_user_returned` or `v1_company_returned` by `pda_seed`, so
+/// finance can track how much is still left on V1. The counters only move when
+/// token_state is passed writable; existing read-only clients are unaffected.
 pub fn v1_passthrough_to_pool(
     program_id: &Address,
     accounts: &[AccountView],
@@ -188,26 +309,65 @@ pub fn v1_passthrough_to_pool(
     let token_program = &accounts[5];
 
     // ── Parse instruction data ────────────────────────────────────────────
-    let entity_id_u64 = parse_u64(data, 0)?;
-    let entity_bump = parse_u8(data, 8)?;
+    let mut reader = Reader::new(data);
+    let entity_id_u64 = reader.read_u64()?;
+    let entity_bump = reader.read_u8()?;
 
-    // Raw V1 CPI instruction data starts at offset 9
-    if data.len() <= 9 {
+    // Raw V1 CPI instruction data starts at offset 9, or after the optional memo
+    if reader.is_empty() {
         return Err(ProgramError::InvalidInstructionData);
     }
-    let v1_cpi_data = &data[9..];
+    let memo = if reader.rest().starts_with(&TRANSFER_V1_DISC) {
+        None
+    } else {
+        Some(reader.read_string()?)
+    };
+    let v1_cpi_data = reader.rest();
 
     // ── Validate V1 TRANSFER discriminator prefix ──────────────────────────
     validate_v1_transfer_disc(v1_cpi_data)?;
 
+    // ── Decompress amount (resolving the "return all" sentinel) ────────────
+    let (amount_offset, available) = locate_v1_decompress_amount(v1_cpi_data)?;
+    let requested = match parse_u8(v1_cpi_data, amount_offset)? {
+        0 => 0,
+        _ => parse_u64(v1_cpi_data, amount_offset + 1)?,
+    };
+    let return_all = requested == RETURN_ALL_AMOUNT;
+    let amount = if return_all { available } else { requested };
+
+    // ── Input validation (same checks and codes as decompress_to_pool) ────
+    if amount == 0 {
+        return Err(ZupyTokenError::ZeroAmount.into());
+    }
+    if let Some(memo) = memo {
+        validate_memo_op(memo, MemoOp::Return)?;
+    }
+
+    let mut resolved_cpi_data = Vec::new();
+    if return_all {
+        resolved_cpi_data.extend_from_slice(v1_cpi_data);
+        resolved_cpi_data[amount_offset + 1..amount_offset + 9]
+            .copy_from_slice(&amount.to_le_bytes());
+    }
+    let v1_cpi_data = if resolved_cpi_data.is_empty() {
+        v1_cpi_data
+    } else {
+        &resolved_cpi_data
+    };
+
     // ── Common transfer validation (9 checks, Spec §7.1-§7.8) ─────────────
-    validate_transfer_common(
+    let state = validate_transfer_common(
         program_id,
         token_state_account,
         transfer_authority,
         mint,
         token_program,
-    )?;
+    )?
+    .state;
+
+    // ── V1 sunset (0 = no cutoff) ──────────────────────────────────────────
+    enforce_v1_sunset(&state)?;
 
     // ── PDA validation (via client-provided bump) ────────────────────────
     let entity_id_bytes = entity_id_u64.to_le_bytes();
@@ -218,7 +378,6 @@ pub fn v1_passthrough_to_pool(
     )?;
 
     // ── Pool ATA validation ───────────────────────────────────────────────
-    let state = TokenState::from_slice(unsafe { token_state_account.borrow_unchecked() });
     if pool_ata.address().as_ref() != state.pool_ata() {
         return Err(ZupyTokenError::InvalidPoolAccount.into());
     }
@@ -230,6 +389,7 @@ pub fn v1_passthrough_to_pool(
     // ── Build CPI instruction for cToken V1 ───────────────────────────────
     let prog_id: Address = LIGHT_COMPRESSED_TOKEN_PROGRAM_ID.into();
     let cpi_accounts = &accounts[6..];
+    parse_v1_proof_layout(v1_cpi_data, cpi_accounts.len())?;
 
     // Build account metas, forcing entity_pda to be signer for invoke_signed.
     let mut account_metas = Vec::with_capacity(cpi_accounts.len());
@@ -263,5 +423,14 @@ pub fn v1_passthrough_to_pool(
 
     pinocchio::cpi::invoke_signed_with_slice(&instruction, &account_views, &[signer])?;
 
+    // ── Migration accounting AFTER successful CPI (saturating; skipped if read-only)
+    if let Some(mut state_mut) = TokenStateMut::if_writable(token_state_account) {
+        if pda_seed == USER_SEED {
+            state_mut.record_v1_user_returned(amount);
+        } else {
+            state_mut.record_v1_company_returned(amount);
+        }
+    }
+
     Ok(())
 }
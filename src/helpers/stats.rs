@@ -0,0 +1,117 @@
+//! Optional global Stats counters.
+//!
+//! `transfer_from_pool`, `transfer_user_to_company`, `transfer_company_to_user`,
+//! `return_to_pool` / `return_user_to_pool` and the withdraw-to-external
+//! instructions accept the Stats PDA `[STATS_SEED]` (created by
+//! `initialize_stats`) as a trailing optional writable account, before any
+//! CompanyState, UserState or SPL Memo program. It is recognised by owner,
+//! size and discriminator and stripped before the remaining accounts are
+//! forwarded. Like the CompanyState counters, updates saturate and a
+//! read-only account is skipped, so the stats never fail an operation.
+
+use pinocchio::{AccountView, Address};
+
+use crate::state::stats_state::{
+    StatsState, StatsStateMut, STATS_STATE_DISCRIMINATOR, STATS_STATE_SIZE,
+};
+
+/// Splits a trailing Stats account off `accounts`.
+pub fn split_stats_state<'a>(
+    program_id: &Address,
+    accounts: &'a [AccountView],
+) -> (&'a [AccountView], Option<&'a AccountView>) {
+    match accounts.split_last() {
+        Some((last, rest)) if is_stats_state(program_id, last) => (rest, Some(last)),
+        _ => (accounts, None),
+    }
+}
+
+#[inline(always)]
+fn is_stats_state(program_id: &Address, account: &AccountView) -> bool {
+    account.owned_by(program_id)
+        && account.data_len() >= STATS_STATE_SIZE
+        && StatsState::from_slice(unsafe { account.borrow_unchecked() }).discriminator()
+            == &STATS_STATE_DISCRIMINATOR
+}
+
+/// Writable Stats counters, or `None` when `stats` (from `split_stats_state`)
+/// is absent or read-only. Only `initialize_stats` writes the discriminator,
+/// at the `[STATS_SEED]` PDA, so the discriminator identifies the account.
+pub fn stats_counters(stats: Option<&AccountView>) -> Option<StatsStateMut<'_>> {
+    let stats = stats.filter(|account| account.is_writable())?;
+    Some(StatsStateMut::from_slice(unsafe { stats.borrow_unchecked_mut() }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::mem::size_of;
+    use pinocchio::account::{RuntimeAccount, NOT_BORROWED};
+
+    const PROGRAM_ID: [u8; 32] = [1u8; 32];
+
+    /// RuntimeAccount buffer holding an empty StatsState.
+    fn make_stats(owner: [u8; 32], is_writable: bool) -> Vec<u64> {
+        let header_size = size_of::<RuntimeAccount>();
+        let mut buf = vec![0u64; (header_size + STATS_STATE_SIZE).div_ceil(8)];
+        let raw = buf.as_mut_ptr() as *mut RuntimeAccount;
+        unsafe {
+            (*raw).borrow_state = NOT_BORROWED;
+            (*raw).is_writable = is_writable as u8;
+            (*raw).address = Address::from([9u8; 32]);
+            (*raw).owner = Address::from(owner);
+            (*raw).data_len = STATS_STATE_SIZE as u64;
+        }
+        let data = unsafe {
+            let base = buf.as_mut_ptr() as *mut u8;
+            core::slice::from_raw_parts_mut(base.add(header_size), STATS_STATE_SIZE)
+        };
+        StatsStateMut::from_slice(data).set_discriminator(&STATS_STATE_DISCRIMINATOR);
+        buf
+    }
+
+    fn view(buf: &mut [u64]) -> AccountView {
+        unsafe { AccountView::new_unchecked(buf.as_mut_ptr() as *mut RuntimeAccount) }
+    }
+
+    #[test]
+    fn test_split_stats_state() {
+        let program_id = Address::from(PROGRAM_ID);
+        let mut other = make_stats([2u8; 32], true);
+        let mut stats = make_stats(PROGRAM_ID, true);
+
+        let accounts = [view(&mut other), view(&mut stats)];
+        let (rest, found) = split_stats_state(&program_id, &accounts);
+        assert_eq!(rest.len(), 1);
+        assert!(found.is_some());
+
+        // Foreign-owned account with the same layout is not stripped
+        let (rest, found) = split_stats_state(&program_id, &accounts[..1]);
+        assert_eq!(rest.len(), 1);
+        assert!(found.is_none());
+    }
+
+    #[test]
+    fn test_counters_after_operation_sequence() {
+        let mut buf = make_stats(PROGRAM_ID, true);
+        let account = view(&mut buf);
+
+        stats_counters(Some(&account)).unwrap().record_pool_outflow(1_000);
+        stats_counters(Some(&account)).unwrap().record_company_to_user(400);
+        stats_counters(Some(&account)).unwrap().record_pool_outflow(500);
+        stats_counters(Some(&account)).unwrap().record_withdrawn(300);
+
+        let state = StatsState::from_slice(unsafe { account.borrow_unchecked() });
+        assert_eq!(state.total_pool_outflow(), 1_500);
+        assert_eq!(state.total_company_to_user(), 400);
+        assert_eq!(state.total_withdrawn(), 300);
+    }
+
+    #[test]
+    fn test_skipped_without_usable_account() {
+        assert!(stats_counters(None).is_none());
+
+        let mut read_only = make_stats(PROGRAM_ID, false);
+        assert!(stats_counters(Some(&view(&mut read_only))).is_none());
+    }
+}
@@ -0,0 +1,209 @@
+//! Token-2022 TransferFeeConfig support.
+//!
+//! Token-2022 rejects `Transfer` / `TransferChecked` on mints carrying the
+//! TransferFeeConfig extension. SPL-path instructions read the mint's fee
+//! config here and switch to `TransferCheckedWithFee` with the exact fee
+//! Token-2022 will withhold; mints without the extension keep the plain CPI.
+//! Compressed (Light) transfers never touch the mint and are unaffected.
+
+use crate::helpers::bytes::read_array;
+
+/// AccountType byte of an extended mint: the base mint is padded to the
+/// token account length (165) before it.
+const ACCOUNT_TYPE_OFFSET: usize = 165;
+/// `AccountType::Mint`.
+const ACCOUNT_TYPE_MINT: u8 = 1;
+/// First TLV entry: type (u16 LE) + length (u16 LE) + value.
+const TLV_START: usize = ACCOUNT_TYPE_OFFSET + 1;
+
+/// `ExtensionType::TransferFeeConfig`.
+pub const TRANSFER_FEE_CONFIG_EXTENSION: u16 = 1;
+/// Serialized TransferFeeConfig: config + withdraw authorities (32 each),
+/// withheld_amount (u64), older and newer TransferFee (18 each).
+pub const TRANSFER_FEE_CONFIG_LEN: usize = 108;
+const OLDER_FEE_OFFSET: usize = 72;
+const NEWER_FEE_OFFSET: usize = 90;
+
+/// `TokenInstruction::TransferFeeExtension` + `TransferFeeInstruction::TransferCheckedWithFee`.
+pub const TRANSFER_CHECKED_WITH_FEE_DISC: [u8; 2] = [26, 1];
+/// Disc (2) + amount (u64) + decimals (u8) + fee (u64).
+pub const TRANSFER_CHECKED_WITH_FEE_DATA_LEN: usize = 19;
+
+const MAX_FEE_BASIS_POINTS: u128 = 10_000;
+
+/// One epoch's fee schedule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransferFee {
+    pub epoch: u64,
+    pub maximum_fee: u64,
+    pub basis_points: u16,
+}
+
+impl TransferFee {
+    fn from_bytes(b: &[u8]) -> Self {
+        Self {
+            epoch: u64::from_le_bytes(*read_array::<8>(b, 0)),
+            maximum_fee: u64::from_le_bytes(*read_array::<8>(b, 8)),
+            basis_points: u16::from_le_bytes(*read_array::<2>(b, 16)),
+        }
+    }
+
+    /// Fee withheld on `amount`, as Token-2022 computes it:
+    /// `ceil(amount * basis_points / 10_000)`, capped at `maximum_fee`.
+    pub fn calculate(&self, amount: u64) -> u64 {
+        if self.basis_points == 0 || amount == 0 {
+            return 0;
+        }
+        let numerator = amount as u128 * self.basis_points as u128;
+        let fee = numerator.div_ceil(MAX_FEE_BASIS_POINTS);
+        (fee as u64).min(self.maximum_fee)
+    }
+}
+
+/// The older / newer fee pair stored in the mint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransferFeeConfig {
+    pub older: TransferFee,
+    pub newer: TransferFee,
+}
+
+impl TransferFeeConfig {
+    /// Schedule in force at `epoch`: `newer` once its epoch is reached.
+    pub fn epoch_fee(&self, epoch: u64) -> &TransferFee {
+        if epoch >= self.newer.epoch {
+            &self.newer
+        } else {
+            &self.older
+        }
+    }
+
+    /// Fee withheld on a transfer of `amount` at `epoch`.
+    pub fn fee(&self, epoch: u64, amount: u64) -> u64 {
+        self.epoch_fee(epoch).calculate(amount)
+    }
+}
+
+/// TransferFeeConfig of a Token-2022 mint, or `None` for a base mint or an
+/// extended mint without (or with a truncated) fee config.
+pub fn read_transfer_fee_config(mint_data: &[u8]) -> Option<TransferFeeConfig> {
+    if mint_data.get(ACCOUNT_TYPE_OFFSET) != Some(&ACCOUNT_TYPE_MINT) {
+        return None;
+    }
+    let mut offset = TLV_START;
+    while let Some(header) = mint_data.get(offset..offset + 4) {
+        let ext_type = u16::from_le_bytes([header[0], header[1]]);
+        let len = u16::from_le_bytes([header[2], header[3]]) as usize;
+        let value = mint_data.get(offset + 4..offset + 4 + len)?;
+        if ext_type == TRANSFER_FEE_CONFIG_EXTENSION {
+            if len < TRANSFER_FEE_CONFIG_LEN {
+                return None;
+            }
+            return Some(TransferFeeConfig {
+                older: TransferFee::from_bytes(&value[OLDER_FEE_OFFSET..]),
+                newer: TransferFee::from_bytes(&value[NEWER_FEE_OFFSET..]),
+            });
+        }
+        offset += 4 + len;
+    }
+    None
+}
+
+/// `TransferCheckedWithFee` instruction data.
+pub fn transfer_checked_with_fee_data(
+    amount: u64,
+    decimals: u8,
+    fee: u64,
+) -> [u8; TRANSFER_CHECKED_WITH_FEE_DATA_LEN] {
+    let mut data = [0u8; TRANSFER_CHECKED_WITH_FEE_DATA_LEN];
+    data[0..2].copy_from_slice(&TRANSFER_CHECKED_WITH_FEE_DISC);
+    data[2..10].copy_from_slice(&amount.to_le_bytes());
+    data[10] = decimals;
+    data[11..19].copy_from_slice(&fee.to_le_bytes());
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fee_bytes(fee: &TransferFee) -> Vec<u8> {
+        let mut b = Vec::new();
+        b.extend_from_slice(&fee.epoch.to_le_bytes());
+        b.extend_from_slice(&fee.maximum_fee.to_le_bytes());
+        b.extend_from_slice(&fee.basis_points.to_le_bytes());
+        b
+    }
+
+    fn push_tlv(data: &mut Vec<u8>, ext_type: u16, value: &[u8]) {
+        data.extend_from_slice(&ext_type.to_le_bytes());
+        data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+        data.extend_from_slice(value);
+    }
+
+    /// Extended mint: padded base + AccountType::Mint + a MintCloseAuthority
+    /// TLV (type 3) + the TransferFeeConfig TLV when `config` is given.
+    fn make_mint(config: Option<&TransferFeeConfig>) -> Vec<u8> {
+        let mut data = vec![0u8; ACCOUNT_TYPE_OFFSET];
+        data[44] = 6; // decimals
+        data[45] = 1; // is_initialized
+        data.push(ACCOUNT_TYPE_MINT);
+        push_tlv(&mut data, 3, &[7u8; 32]);
+        if let Some(config) = config {
+            let mut value = vec![0u8; 72];
+            value.extend_from_slice(&fee_bytes(&config.older));
+            value.extend_from_slice(&fee_bytes(&config.newer));
+            push_tlv(&mut data, TRANSFER_FEE_CONFIG_EXTENSION, &value);
+        }
+        data
+    }
+
+    fn config() -> TransferFeeConfig {
+        TransferFeeConfig {
+            older: TransferFee { epoch: 0, maximum_fee: 5_000, basis_points: 50 },
+            newer: TransferFee { epoch: 100, maximum_fee: 1_000_000, basis_points: 125 },
+        }
+    }
+
+    #[test]
+    fn test_reads_fee_config_after_other_extensions() {
+        assert_eq!(read_transfer_fee_config(&make_mint(Some(&config()))), Some(config()));
+    }
+
+    #[test]
+    fn test_no_fee_config() {
+        assert_eq!(read_transfer_fee_config(&[0u8; 82]), None);
+        assert_eq!(read_transfer_fee_config(&make_mint(None)), None);
+
+        // Truncated TLV value
+        let mut data = make_mint(Some(&config()));
+        data.truncate(data.len() - 1);
+        assert_eq!(read_transfer_fee_config(&data), None);
+    }
+
+    #[test]
+    fn test_fee_rounds_up_and_is_capped() {
+        let fee = TransferFee { epoch: 0, maximum_fee: 5_000, basis_points: 50 };
+        assert_eq!(fee.calculate(0), 0);
+        assert_eq!(fee.calculate(1), 1); // 0.005 rounds up
+        assert_eq!(fee.calculate(200_000), 1_000);
+        assert_eq!(fee.calculate(u64::MAX), 5_000);
+        assert_eq!(TransferFee { basis_points: 0, ..fee }.calculate(1_000), 0);
+    }
+
+    #[test]
+    fn test_newer_fee_applies_from_its_epoch() {
+        let config = config();
+        assert_eq!(config.fee(99, 1_000_000), 5_000);
+        assert_eq!(config.fee(100, 1_000_000), 12_500);
+    }
+
+    #[test]
+    fn test_transfer_checked_with_fee_data_layout() {
+        let config = read_transfer_fee_config(&make_mint(Some(&config()))).unwrap();
+        let data = transfer_checked_with_fee_data(1_000_000, 6, config.fee(100, 1_000_000));
+        assert_eq!(&data[0..2], &[26, 1]);
+        assert_eq!(u64::from_le_bytes(data[2..10].try_into().unwrap()), 1_000_000);
+        assert_eq!(data[10], 6);
+        assert_eq!(u64::from_le_bytes(data[11..19].try_into().unwrap()), 12_500);
+    }
+}
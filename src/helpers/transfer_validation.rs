@@ -4,60 +4,57 @@ use pinocchio::ProgramResult;
 use pinocchio::cpi::{Seed, Signer};
 use pinocchio::error::ProgramError;
 
-use crate::constants::{TOKEN_2022_PROGRAM_ID, TOKEN_DECIMALS, TOKEN_STATE_SEED};
-use crate::error::ZupyTokenError;
-use crate::helpers::cpi::{cpi_create_ata_if_needed, cpi_transfer_checked};
-use crate::helpers::pda::validate_pda_with_seeds;
-use crate::state::token_state::{TokenState, TOKEN_STATE_SIZE};
-
-/// Result of common transfer validation: returns the TokenState bump for PDA signing.
+use crate::constants::{
+    COMPANY_SEED, LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, LIGHT_TOKEN_CPI_AUTHORITY, TOKEN_2022_PROGRAM_ID,
+    USER_SEED,
+};
+use crate::error::{zupy_err, ZupyTokenError};
+use crate::helpers::account_checks::assert_associated_token_address;
+use crate::helpers::bytes::{address_bytes, read_array};
+use crate::helpers::compressed_accounts::{cpi_compress_from_spl, derive_spl_interface_pda};
+use crate::helpers::cpi::{cpi_create_ata_idempotent, cpi_transfer_checked_for_mint};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::math::checked_sub_amount;
+use crate::helpers::memo::{validate_memo_op, validate_memo_op_id, MemoOp};
+use crate::helpers::pda::{validate_pda, validate_pda_with_seeds};
+use crate::helpers::withdraw_common::WithdrawParams;
+use crate::state::token_state::TokenState;
+
+/// Result of common transfer validation: the validated TokenState and its bump
+/// for PDA signing.
+///
+/// `state` is the instruction's only parse of token_state: a zero-copy view
+/// over the account data checked once by `TokenState::try_from_account`.
+/// Handlers and `execute_pda_transfer` read later fields (pool ATA, limits,
+/// decimals) from it instead of validating the account again; only writes
+/// (nonce, heartbeat, counters) borrow the data mutably.
 #[derive(Debug)]
-pub struct TransferValidationResult {
+pub struct TransferValidationResult<'a> {
     pub bump: u8,
+    pub state: TokenState<'a>,
 }
 
 /// Base token_state validation shared by ALL instructions that read token_state.
 ///
 /// Validates (in order):
-/// 1. token_state owned by our program (Spec §7.1)
-/// 2. token_state data length >= TOKEN_STATE_SIZE (Spec §7.7)
-/// 3. token_state PDA matches `[TOKEN_STATE_SEED, &[bump]]` via stored bump (Spec §7.2)
-/// 4. token_state.initialized == true → NotInitialized (6010)
+/// 1–4. `TokenState::try_from_account`: owner (Spec §7.1), data length
+///      (Spec §7.7), discriminator, PDA via stored bump (Spec §7.2)
+/// 5. token_state.initialized == true → NotInitialized (6010)
 ///
 /// Does NOT check paused or authority — those are instruction-specific.
-/// Returns the stored bump for use in PDA signing.
-pub fn validate_token_state_base(
+/// Returns the validated TokenState; `bump()` gives the PDA signing bump.
+pub fn validate_token_state_base<'a>(
     program_id: &Address,
-    token_state_account: &AccountView,
-) -> Result<u8, ProgramError> {
-    // §7.1 — token_state owned by our program
-    if !token_state_account.owned_by(program_id) {
-        return Err(ZupyTokenError::InvalidAuthority.into());
-    }
-
-    // §7.7 — data length check
-    if token_state_account.data_len() < TOKEN_STATE_SIZE {
-        return Err(ProgramError::InvalidAccountData);
-    }
-
-    // Zero-copy read (safe: single-threaded Solana runtime)
-    let state = TokenState::from_slice(unsafe { token_state_account.borrow_unchecked() });
-
-    // §7.2 — PDA validation via stored bump
-    let bump = state.bump();
-    let expected_pda =
-        Address::create_program_address(&[TOKEN_STATE_SEED, &[bump]], program_id)
-            .map_err(|_| ZupyTokenError::InvalidPDA)?;
-    if token_state_account.address() != &expected_pda {
-        return Err(ZupyTokenError::InvalidPDA.into());
-    }
+    token_state_account: &'a AccountView,
+) -> Result<TokenState<'a>, ProgramError> {
+    let state = TokenState::try_from_account(token_state_account, program_id)?;
 
     // §7.4 — initialized check
     if !state.initialized() {
-        return Err(ZupyTokenError::NotInitialized.into());
+        return Err(zupy_err!(NotInitialized, "token_state"));
     }
 
-    Ok(bump)
+    Ok(state)
 }
 
 /// Common transfer validation applied to ALL 4 hot-path transfer instructions.
@@ -65,55 +62,34 @@ pub fn validate_token_state_base(
 /// Validates (in order):
 /// 1–4. Base token_state checks via `validate_token_state_base`
 /// 5. token_state.paused == false → SystemPaused (6018)
-/// 6. token_state.transfer_authority == transfer_authority → InvalidAuthority (6000)
+/// 6. transfer_authority signs → AuthorityNotSigner (6068), and
+///    token_state.transfer_authority == transfer_authority → AuthorityMismatch (6069)
 /// 7. mint owned by Token-2022 (Spec §7.1)
 /// 8. token_state.mint == mint.address() → InvalidMint (6011)
 /// 9. token_program is Token-2022 program ID (Spec §7.8)
 ///
-/// Returns the token_state bump for use in PDA signing.
-pub fn validate_transfer_common(
+/// Returns the validated TokenState and its bump for use in PDA signing.
+pub fn validate_transfer_common<'a>(
     program_id: &Address,
-    token_state_account: &AccountView,
+    token_state_account: &'a AccountView,
     transfer_authority: &AccountView,
     mint: &AccountView,
     token_program: &AccountView,
-) -> Result<TransferValidationResult, ProgramError> {
-    // 1–4. Base token_state validation
-    let bump = validate_token_state_base(program_id, token_state_account)?;
-
-    // Read state for remaining checks
-    let state = TokenState::from_slice(unsafe { token_state_account.borrow_unchecked() });
-
-    // 5. not paused
-    if state.paused() {
-        return Err(ZupyTokenError::SystemPaused.into());
-    }
-
-    // 6. transfer_authority matches
-    if !transfer_authority.is_signer() {
-        return Err(ZupyTokenError::InvalidAuthority.into());
-    }
-    if state.transfer_authority() != transfer_authority.address().as_ref() {
-        return Err(ZupyTokenError::InvalidAuthority.into());
-    }
-
-    // 7. mint owned by Token-2022
-    let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
-    if !mint.owned_by(&token_2022_addr) {
-        return Err(ZupyTokenError::InvalidMint.into());
-    }
-
-    // 8. token_state.mint matches
-    if state.mint() != mint.address().as_ref() {
-        return Err(ZupyTokenError::InvalidMint.into());
-    }
+) -> Result<TransferValidationResult<'a>, ProgramError> {
+    // 1–8. Base token_state, paused, authority and mint checks
+    let validation = validate_transfer_common_compressed(
+        program_id,
+        token_state_account,
+        transfer_authority,
+        mint,
+    )?;
 
     // 9. token_program is Token-2022
-    if token_program.address() != &token_2022_addr {
-        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    if token_program.address() != &Address::from(TOKEN_2022_PROGRAM_ID) {
+        return Err(zupy_err!(InvalidTokenProgram, "token_program"));
     }
 
-    Ok(TransferValidationResult { bump })
+    Ok(validation)
 }
 
 /// Common validation for compressed-token transfer instructions.
@@ -126,47 +102,46 @@ pub fn validate_transfer_common(
 /// Validates (in order):
 /// 1–4. Base token_state checks via `validate_token_state_base`
 /// 5. token_state.paused == false → SystemPaused
-/// 6. transfer_authority is signer + matches token_state.transfer_authority
+/// 6. transfer_authority is signer (AuthorityNotSigner) + matches
+///    token_state.transfer_authority (AuthorityMismatch)
 /// 7. mint owned by Token-2022 (mint account is still the Token-2022 mint)
 /// 8. token_state.mint == mint.address()
 ///
-/// Returns the token_state bump for PDA signing.
-pub fn validate_transfer_common_compressed(
+/// Returns the validated TokenState and its bump for PDA signing.
+pub fn validate_transfer_common_compressed<'a>(
     program_id: &Address,
-    token_state_account: &AccountView,
+    token_state_account: &'a AccountView,
     transfer_authority: &AccountView,
     mint: &AccountView,
-) -> Result<TransferValidationResult, ProgramError> {
+) -> Result<TransferValidationResult<'a>, ProgramError> {
     // 1–4. Base token_state validation
-    let bump = validate_token_state_base(program_id, token_state_account)?;
-
-    let state = TokenState::from_slice(unsafe { token_state_account.borrow_unchecked() });
+    let state = validate_token_state_base(program_id, token_state_account)?;
 
     // 5. not paused
     if state.paused() {
-        return Err(ZupyTokenError::SystemPaused.into());
+        return Err(zupy_err!(SystemPaused, "paused"));
     }
 
     // 6. transfer_authority matches
     if !transfer_authority.is_signer() {
-        return Err(ZupyTokenError::InvalidAuthority.into());
+        return Err(zupy_err!(AuthorityNotSigner, "transfer_authority"));
     }
     if state.transfer_authority() != transfer_authority.address().as_ref() {
-        return Err(ZupyTokenError::InvalidAuthority.into());
+        return Err(zupy_err!(AuthorityMismatch, "transfer_authority"));
     }
 
     // 7. mint owned by Token-2022
     let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
     if !mint.owned_by(&token_2022_addr) {
-        return Err(ZupyTokenError::InvalidMint.into());
+        return Err(zupy_err!(InvalidMint, "mint_owner"));
     }
 
     // 8. token_state.mint matches
     if state.mint() != mint.address().as_ref() {
-        return Err(ZupyTokenError::InvalidMint.into());
+        return Err(zupy_err!(InvalidMint, "mint"));
     }
 
-    Ok(TransferValidationResult { bump })
+    Ok(TransferValidationResult { bump: state.bump(), state })
 }
 
 /// Validate common metadata instruction accounts.
@@ -178,24 +153,22 @@ pub fn validate_transfer_common_compressed(
 /// 3. Mint owned by Token-2022 + mint address matches token_state
 /// 4. token_program is Token-2022
 ///
-/// Returns the token_state bump for PDA signing.
-pub fn validate_metadata_accounts(
+/// Returns the validated TokenState; `bump()` gives the PDA signing bump.
+pub fn validate_metadata_accounts<'a>(
     program_id: &Address,
     authority: &AccountView,
-    token_state_account: &AccountView,
+    token_state_account: &'a AccountView,
     mint: &AccountView,
     token_program: &AccountView,
-) -> Result<u8, ProgramError> {
+) -> Result<TokenState<'a>, ProgramError> {
     // Base token_state validation
-    validate_token_state_base(program_id, token_state_account)?;
-
-    let state = TokenState::from_slice(unsafe { token_state_account.borrow_unchecked() });
+    let state = validate_token_state_base(program_id, token_state_account)?;
 
     // Treasury authorization
     if !authority.is_signer() {
         return Err(ZupyTokenError::InvalidAuthority.into());
     }
-    let authority_key: &[u8; 32] = authority.address().as_ref().try_into().unwrap();
+    let authority_key: &[u8; 32] = address_bytes(authority.address());
     if !state.is_treasury(authority_key) {
         return Err(ZupyTokenError::UnauthorizedTreasury.into());
     }
@@ -214,7 +187,7 @@ pub fn validate_metadata_accounts(
         return Err(ZupyTokenError::InvalidTokenProgram.into());
     }
 
-    Ok(state.bump())
+    Ok(state)
 }
 
 /// Validate NFT minting payer authorization.
@@ -224,24 +197,24 @@ pub fn validate_metadata_accounts(
 /// 1. Payer is signer
 /// 2. Base token_state (ownership, PDA, initialized)
 /// 3. Payer matches token_state.mint_authority (AUDIT 12.1)
-pub fn validate_nft_payer(
+///
+/// Returns the validated TokenState.
+pub fn validate_nft_payer<'a>(
     program_id: &Address,
     payer: &AccountView,
-    token_state_account: &AccountView,
-) -> Result<(), ProgramError> {
+    token_state_account: &'a AccountView,
+) -> Result<TokenState<'a>, ProgramError> {
     if !payer.is_signer() {
         return Err(ZupyTokenError::InvalidAuthority.into());
     }
 
-    validate_token_state_base(program_id, token_state_account)?;
-
-    let state = TokenState::from_slice(unsafe { token_state_account.borrow_unchecked() });
-    let payer_key: &[u8; 32] = payer.address().as_ref().try_into().unwrap();
+    let state = validate_token_state_base(program_id, token_state_account)?;
+    let payer_key: &[u8; 32] = address_bytes(payer.address());
     if !state.is_mint_authority(payer_key) {
         return Err(ZupyTokenError::InvalidAuthority.into());
     }
 
-    Ok(())
+    Ok(state)
 }
 
 /// Read token balance from a Token account (zero-copy, offset 64, u64 LE).
@@ -254,7 +227,7 @@ pub fn validate_nft_payer(
 #[inline(always)]
 pub fn read_token_balance(account: &AccountView) -> u64 {
     let data = unsafe { account.borrow_unchecked() };
-    u64::from_le_bytes(data[64..72].try_into().unwrap())
+    u64::from_le_bytes(*read_array::<8>(data, 64))
 }
 
 /// Read token account owner pubkey (bytes 32..64).
@@ -281,7 +254,134 @@ pub fn read_token_mint(account: &AccountView) -> &[u8] {
     unsafe { &account.borrow_unchecked()[0..32] }
 }
 
+/// Read a mint's current supply (u64 LE, bytes 36..44).
+///
+/// # Safety contract
+/// Caller MUST verify the account is owned by Token-2022 and is our mint.
+/// Token-2022 mints are guaranteed >= 82 bytes (SPL Mint layout), so indexing
+/// bytes 36..44 is safe.
+#[inline(always)]
+pub fn read_mint_supply(mint: &AccountView) -> u64 {
+    let data = unsafe { mint.borrow_unchecked() };
+    u64::from_le_bytes(*read_array::<8>(data, 36))
+}
+
+/// Enforce `token_state.per_tx_auto_limit` on a hot-path transfer amount.
+///
+/// A limit of 0 means "no limit" (deployments where the field was never set).
+/// Above the limit the transfer needs a treasury override: any account in
+/// `accounts` that signs and matches `token_state.treasury()` (conventionally
+/// appended last). Otherwise → `ExceedsPerTxLimit`.
+///
+/// `state` comes from the caller's token_state validation.
+pub fn enforce_per_tx_limit(
+    state: &TokenState,
+    amount: u64,
+    accounts: &[AccountView],
+) -> Result<(), ProgramError> {
+    let limit = state.per_tx_auto_limit();
+    if limit == 0 || amount <= limit {
+        return Ok(());
+    }
+    let treasury_override = accounts
+        .iter()
+        .any(|a| a.is_signer() && a.address().as_ref() == state.treasury());
+    if !treasury_override {
+        return Err(zupy_err!(ExceedsPerTxLimit, "per_tx"));
+    }
+    Ok(())
+}
+
+/// Reject V1 passthroughs once `token_state.v1_sunset_ts` has passed → `V1Sunset`.
+///
+/// A sunset of 0 means the V1 path is open; the Clock is only read when one is set.
+///
+/// `state` comes from the caller's token_state validation.
+pub fn enforce_v1_sunset(state: &TokenState) -> Result<(), ProgramError> {
+    if state.v1_sunset_ts() == 0 {
+        return Ok(());
+    }
+    use pinocchio::sysvars::Sysvar as _;
+    let clock = pinocchio::sysvars::clock::Clock::get()?;
+    if state.v1_sunset_reached(clock.unix_timestamp) {
+        return Err(ZupyTokenError::V1Sunset.into());
+    }
+    Ok(())
+}
+
+/// Validate a withdraw-to-external request up to (not including) any CPI.
+///
+/// Shared by the native path (`withdraw_common::execute_withdraw`) and the V1
+/// passthrough (`withdraw_to_external_v1`) so both reject with the same codes.
+/// `accounts[0..4]` must be transfer_authority, token_state, mint and entity_pda;
+/// the whole slice is scanned for a treasury co-signer. Checks, in order:
+/// 1. amount != 0 → `ZeroAmount`
+/// 2. memo: a `withdraw` memo, or `withdraw_company` for `COMPANY_SEED`
+///    (`validate_memo_op`)
+/// 3. Common transfer validation (9 checks via `validate_transfer_common`)
+/// 4. Unless `treasury_approved`: `per_tx_auto_limit`, then user withdrawals at or
+///    above `withdraw_approval_threshold` (0 = off) → `RequiresApproval`
+/// 5. Entity PDA derivation with the client-provided bump
+///
+/// Returns the validated TokenState.
+pub fn validate_withdraw_request<'a>(
+    program_id: &Address,
+    accounts: &'a [AccountView],
+    token_program: &AccountView,
+    params: &WithdrawParams,
+    pda_seed: &[u8],
+    treasury_approved: bool,
+) -> Result<TokenState<'a>, ProgramError> {
+    let transfer_authority = &accounts[0];
+    let token_state        = &accounts[1];
+    let mint               = &accounts[2];
+    let entity_pda         = &accounts[3];
+
+    // 1. Zero amount
+    if params.amount == 0 {
+        return Err(zupy_err!(ZeroAmount, "amount"));
+    }
+
+    // 2. Memo (company entities withdraw under their own tag)
+    let memo_op = if pda_seed == COMPANY_SEED { MemoOp::WithdrawCompany } else { MemoOp::Withdraw };
+    validate_memo_op(params.memo, memo_op)?;
+
+    // 3. Common transfer validation — 9 standard security checks
+    let state = validate_transfer_common(
+        program_id,
+        token_state,
+        transfer_authority,
+        mint,
+        token_program,
+    )?
+    .state;
+
+    if !treasury_approved {
+        // 4a. Per-transaction limit (treasury override above it)
+        enforce_per_tx_limit(&state, params.amount, accounts)?;
+
+        // 4b. Large user withdrawals go through request_withdrawal + approve_withdrawal
+        let threshold = state.withdraw_approval_threshold();
+        if pda_seed == USER_SEED && threshold > 0 && params.amount >= threshold {
+            return Err(zupy_err!(RequiresApproval, "threshold"));
+        }
+    }
+
+    // 5. Entity PDA with client-provided bump
+    let entity_id_bytes = params.entity_id.to_le_bytes();
+    validate_pda_with_seeds(
+        entity_pda.address(),
+        &[pda_seed, &entity_id_bytes, &[params.entity_bump]],
+        program_id,
+    )?;
+
+    Ok(state)
+}
+
 /// Validate that a source ATA's mint matches the expected mint and owner matches expected PDA.
+///
+/// Not owned by Token-2022 → `AccountNotTokenOwned`, mint mismatch →
+/// `InvalidMint`, token owner mismatch → `TokenOwnerMismatch`.
 pub fn validate_source_ata(
     ata: &AccountView,
     expected_mint: &Address,
@@ -290,24 +390,24 @@ pub fn validate_source_ata(
     // ATA must be owned by Token-2022
     let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
     if !ata.owned_by(&token_2022_addr) {
-        return Err(ZupyTokenError::InvalidAuthority.into());
+        return Err(zupy_err!(AccountNotTokenOwned, "source_ata_owner"));
     }
 
     // mint check
     if read_token_mint(ata) != expected_mint.as_ref() {
-        return Err(ZupyTokenError::InvalidMint.into());
+        return Err(zupy_err!(InvalidMint, "source_ata_mint"));
     }
 
     // owner check
     if read_token_owner(ata) != expected_owner.as_ref() {
-        return Err(ZupyTokenError::InvalidAuthority.into());
+        return Err(zupy_err!(TokenOwnerMismatch, "source_ata_owner"));
     }
 
     Ok(())
 }
 
 /// Validate a destination ATA if it already exists (data_len > 0).
-/// Checks: Token-2022 ownership + mint match.
+/// Checks: Token-2022 ownership (`AccountNotTokenOwned`) + mint match (`InvalidMint`).
 /// Skips validation if the account has no data (will be created via CPI).
 pub fn validate_destination_ata_if_exists(
     ata: &AccountView,
@@ -316,10 +416,10 @@ pub fn validate_destination_ata_if_exists(
     if ata.data_len() > 0 {
         let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
         if !ata.owned_by(&token_2022_addr) {
-            return Err(ZupyTokenError::InvalidAuthority.into());
+            return Err(zupy_err!(AccountNotTokenOwned, "dest_ata_owner"));
         }
         if read_token_mint(ata) != expected_mint.as_ref() {
-            return Err(ZupyTokenError::InvalidMint.into());
+            return Err(zupy_err!(InvalidMint, "dest_ata_mint"));
         }
     }
     Ok(())
@@ -328,19 +428,20 @@ pub fn validate_destination_ata_if_exists(
 /// Execute a validated PDA-to-PDA token transfer.
 ///
 /// Shared by `transfer_company_to_user` and `transfer_user_to_company`.
-/// Handles the full flow after account extraction and data parsing:
-/// 1. Input validation (amount != 0, memo format)
-/// 2. Common transfer validation (9 checks via `validate_transfer_common`)
-/// 3. Source + destination PDA validation (client-provided bumps)
-/// 4. Source ATA ownership + mint + balance check
-/// 5. Destination ATA validation (if exists)
-/// 6. Create destination ATA if needed (CPI)
-/// 7. TransferChecked CPI with source PDA as signer
+/// The caller has already checked its inputs (amount != 0, memo format) and run
+/// `validate_transfer_common`; `validation` is that result, so token_state is
+/// not read again here. Handles the rest of the flow:
+/// 1. Source + destination PDA validation (client-provided bumps)
+/// 2. Source ATA ownership + mint + balance check
+/// 3. Destination ATA validation (mint if it exists; canonical ATA address)
+/// 4. Create destination ATA if needed (CPI)
+/// 5. TransferChecked CPI (WithFee on fee-bearing mints) with source PDA as signer
 #[inline(always)]
+#[allow(clippy::too_many_arguments)]
 pub fn execute_pda_transfer(
     program_id: &Address,
+    validation: &TransferValidationResult<'_>,
     transfer_authority: &AccountView,
-    token_state_account: &AccountView,
     mint: &AccountView,
     token_program: &AccountView,
     system_program: &AccountView,
@@ -355,23 +456,7 @@ pub fn execute_pda_transfer(
     dest_id_bytes: &[u8],
     dest_bump: u8,
     amount: u64,
-    memo: &str,
 ) -> ProgramResult {
-    // ── Input validation ──────────────────────────────────────────────
-    if amount == 0 {
-        return Err(ZupyTokenError::ZeroAmount.into());
-    }
-    crate::helpers::memo::validate_memo_format(memo)?;
-
-    // ── Common transfer validation (9 checks) ─────────────────────────
-    validate_transfer_common(
-        program_id,
-        token_state_account,
-        transfer_authority,
-        mint,
-        token_program,
-    )?;
-
     // ── PDA validation: source ────────────────────────────────────────
     validate_pda_with_seeds(
         source_pda.address(),
@@ -392,14 +477,21 @@ pub fn execute_pda_transfer(
     // ── Balance check ─────────────────────────────────────────────────
     let balance = read_token_balance(source_ata);
     if balance < amount {
-        return Err(ZupyTokenError::InsufficientBalance.into());
+        return Err(zupy_err!(InsufficientBalance, "source_balance"));
     }
 
     // ── Destination ATA validation (if already exists) ────────────────
     validate_destination_ata_if_exists(dest_ata, mint.address())?;
+    assert_associated_token_address(
+        dest_ata,
+        dest_pda.address(),
+        mint.address(),
+        token_program.address(),
+        None,
+    )?;
 
     // ── CPI: Create destination ATA if needed ─────────────────────────
-    cpi_create_ata_if_needed(
+    cpi_create_ata_idempotent(
         dest_ata,
         transfer_authority,
         dest_pda,
@@ -409,6 +501,7 @@ pub fn execute_pda_transfer(
     )?;
 
     // ── CPI: TransferChecked (source PDA signs) ───────────────────────
+    let decimals = validation.state.decimals();
     let bump_bytes = [source_bump];
     let signer_seeds: [Seed; 3] = [
         Seed::from(source_seed),
@@ -417,13 +510,13 @@ pub fn execute_pda_transfer(
     ];
     let signer = Signer::from(&signer_seeds);
 
-    cpi_transfer_checked(
+    cpi_transfer_checked_for_mint(
         source_ata,
         dest_ata,
         source_pda,
         mint,
         amount,
-        TOKEN_DECIMALS,
+        decimals,
         token_program.address(),
         &[signer],
     )?;
@@ -431,13 +524,160 @@ pub fn execute_pda_transfer(
     Ok(())
 }
 
+/// Compress tokens from an external wallet's ATA into a PDA's compressed balance.
+///
+/// Shared by `deposit_from_external` (`USER_SEED`, memo `deposit`) and
+/// `deposit_to_company` (`COMPANY_SEED`, memo `deposit_company`). Path A compress
+/// (same CPI as `transfer_from_pool`) with the external wallet — an outer-tx
+/// signer — as compress authority, so no program PDA signs.
+///
+/// Accounts (16 minimum, plus ≥1 remaining Merkle tree accounts):
+///   0.  external_wallet            (signer)           — owner of source_ata
+///   1.  token_state                (read)             — our program's token_state PDA
+///   2.  mint                       (read)             — ZUPY Token-2022 mint
+///   3.  source_ata                 (writable)         — external wallet's ATA (debited)
+///   4.  entity_pda                 (read)             — PDA [pda_seed, entity_id], compressed owner
+///   5.  fee_payer                  (writable, signer) — pays Light Protocol rent/fees
+///   6.  token_program              (read)             — Token-2022 program
+///   7.  system_program             (read)             — System program
+///   8.  compressed_token_program   (read)             — Light cToken program
+///   9.  cpi_authority_pda          (read)             — LIGHT_TOKEN_CPI_AUTHORITY
+///   10. light_system_program       (read)             — LIGHT_SYSTEM_PROGRAM_ID
+///   11. registered_program_pda     (read)             — REGISTERED_PROGRAM_PDA
+///   12. noop_program               (read)             — SPL_NOOP_ID
+///   13. account_compression_authority (read)          — ACCOUNT_COMPRESSION_AUTHORITY
+///   14. account_compression_program  (read)           — ACCOUNT_COMPRESSION_PROGRAM_ID
+///   15. spl_interface_pda          (writable)         — Light SPL pool PDA (seeds=[b"pool", mint])
+///   16+ Merkle tree output queue   (writable)         — injected by JS client
+///
+/// Data: amount (0-7) + entity_id (8-15) + entity_bump (16)
+///       + memo (17+, `zupy:v1:{memo_op tag}:{entity_id}`)
+pub fn execute_external_deposit(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+    pda_seed: &[u8],
+    memo_op: MemoOp,
+) -> ProgramResult {
+    // ── Account extraction (16 accounts minimum) ─────────────────────────
+    if accounts.len() < 16 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let external_wallet              = &accounts[0];
+    let token_state_account          = &accounts[1];
+    let mint                         = &accounts[2];
+    let source_ata                   = &accounts[3];
+    let entity_pda                   = &accounts[4];
+    let fee_payer                    = &accounts[5];
+    let token_program                = &accounts[6];
+    let system_program               = &accounts[7];
+    let compressed_token_prog        = &accounts[8];
+    let cpi_authority_pda            = &accounts[9];
+    let light_system_program         = &accounts[10];
+    let registered_program_pda       = &accounts[11];
+    let noop_program                 = &accounts[12];
+    let account_compression_authority = &accounts[13];
+    let account_compression_program  = &accounts[14];
+    let spl_interface_pda            = &accounts[15];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let amount = reader.read_u64()?;
+    let entity_id = reader.read_u64()?;
+    let entity_bump = reader.read_u8()?;
+    let memo = reader.read_string()?;
+    reader.finish()?;
+
+    // ── Input validation ────────────────────────────────────────────────
+    if amount == 0 {
+        return Err(zupy_err!(ZeroAmount, "amount"));
+    }
+    validate_memo_op_id(memo, memo_op, entity_id)?;
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) + paused ──
+    let state = validate_token_state_base(program_id, token_state_account)?;
+    if state.paused() {
+        return Err(zupy_err!(SystemPaused, "paused"));
+    }
+
+    // ── Signers: external wallet (compress authority) + fee_payer ───────
+    if !external_wallet.is_signer() {
+        return Err(zupy_err!(AuthorityNotSigner, "external_wallet"));
+    }
+    if !fee_payer.is_signer() {
+        return Err(zupy_err!(FeePayerNotSigner, "fee_payer"));
+    }
+
+    // ── Mint + token program validation ─────────────────────────────────
+    let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
+    if !mint.owned_by(&token_2022_addr) || state.mint() != mint.address().as_ref() {
+        return Err(zupy_err!(InvalidMint, "mint"));
+    }
+    if token_program.address() != &token_2022_addr {
+        return Err(zupy_err!(InvalidTokenProgram, "token_program"));
+    }
+
+    // ── Source ATA: Token-2022, ZUPY mint, owned by the external wallet ──
+    validate_source_ata(source_ata, mint.address(), external_wallet.address())?;
+    let source_balance = read_token_balance(source_ata);
+    if source_balance < amount {
+        return Err(zupy_err!(InsufficientBalance, "source_balance"));
+    }
+
+    // ── PDA validation: entity_pda (client-provided bump) ───────────────
+    let entity_id_bytes = entity_id.to_le_bytes();
+    validate_pda_with_seeds(
+        entity_pda.address(),
+        &[pda_seed, &entity_id_bytes, &[entity_bump]],
+        program_id,
+    )?;
+
+    // ── Light program accounts ──────────────────────────────────────────
+    let expected_ctoken: Address = LIGHT_COMPRESSED_TOKEN_PROGRAM_ID.into();
+    if compressed_token_prog.address() != &expected_ctoken {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let expected_ctoken_auth = Address::from(LIGHT_TOKEN_CPI_AUTHORITY);
+    if cpi_authority_pda.address() != &expected_ctoken_auth {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mint_key: [u8; 32] = mint.address().as_ref().try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    let (expected_spl_pda, _) = derive_spl_interface_pda(&mint_key);
+    validate_pda(spl_interface_pda.address(), &expected_spl_pda)?;
+
+    // ── CPI: Compress from source_ata → compressed leaf for entity_pda ──
+    let owner: &[u8; 32] = entity_pda.address().as_ref().try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    cpi_compress_from_spl(
+        compressed_token_prog,
+        cpi_authority_pda,
+        light_system_program,
+        registered_program_pda,
+        noop_program,
+        account_compression_authority,
+        account_compression_program,
+        fee_payer,
+        external_wallet,            // authority: external wallet (outer-tx signer)
+        spl_interface_pda,          // token_pool_pda
+        source_ata,                 // source_ata
+        token_program,
+        system_program,
+        owner,
+        Some(checked_sub_amount(source_balance, amount)?),
+        &accounts[16..],            // remaining: Merkle tree output queue
+        &[],
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use pinocchio::account::{RuntimeAccount, NOT_BORROWED};
     use core::mem::size_of;
-    use crate::state::token_state::{TokenStateMut, TOKEN_STATE_DISCRIMINATOR};
-    use crate::constants::{COMPANY_SEED, PROGRAM_ID, USER_SEED};
+    use crate::state::token_state::{TokenStateMut, TOKEN_STATE_DISCRIMINATOR, TOKEN_STATE_SIZE};
+    use crate::constants::{COMPANY_SEED, PROGRAM_ID, TOKEN_STATE_SEED, USER_SEED};
 
     // ── Test helpers ────────────────────────────────────────────────────
 
@@ -489,7 +729,7 @@ mod tests {
         // We need RuntimeAccount header + TOKEN_STATE_SIZE data bytes
         let header_size = size_of::<RuntimeAccount>();
         let total_bytes = header_size + TOKEN_STATE_SIZE;
-        let words = (total_bytes + 7) / 8;
+        let words = total_bytes.div_ceil(8);
         let mut buf = vec![0u64; words];
 
         // Derive the PDA address
@@ -589,7 +829,7 @@ mod tests {
         let header_size = size_of::<RuntimeAccount>();
         let short_data_len = TOKEN_STATE_SIZE - 1;
         let total_bytes = header_size + short_data_len;
-        let words = (total_bytes + 7) / 8;
+        let words = total_bytes.div_ceil(8);
         let mut ts_buf = vec![0u64; words];
 
         let raw = ts_buf.as_mut_ptr() as *mut RuntimeAccount;
@@ -701,7 +941,7 @@ mod tests {
         let tp_view = view_from_buf(&mut tp_buf);
 
         let result = validate_transfer_common(&pid, &token_state_view, &auth_view, &mint_view, &tp_view);
-        assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::InvalidAuthority as u32));
+        assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::AuthorityMismatch as u32));
     }
 
     #[test]
@@ -728,7 +968,7 @@ mod tests {
         let tp_view = view_from_buf(&mut tp_buf);
 
         let result = validate_transfer_common(&pid, &token_state_view, &auth_view, &mint_view, &tp_view);
-        assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::InvalidAuthority as u32));
+        assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::AuthorityNotSigner as u32));
     }
 
     #[test]
@@ -886,7 +1126,7 @@ mod tests {
         let header_size = size_of::<RuntimeAccount>();
         let data_len = 165; // SPL Token account minimum size
         let total_bytes = header_size + data_len;
-        let words = (total_bytes + 7) / 8;
+        let words = total_bytes.div_ceil(8);
         let mut buf = vec![0u64; words];
 
         let raw = buf.as_mut_ptr() as *mut RuntimeAccount;
@@ -984,7 +1224,7 @@ mod tests {
         let mint_addr = Address::from(mint);
         let owner_addr = Address::from(pda_owner);
         let result = validate_source_ata(&view, &mint_addr, &owner_addr);
-        assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::InvalidAuthority as u32));
+        assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::AccountNotTokenOwned as u32));
     }
 
     #[test]
@@ -1008,7 +1248,7 @@ mod tests {
         let mint_addr = Address::from(mint);
         let wrong_owner = Address::from([77u8; 32]);
         let result = validate_source_ata(&view, &mint_addr, &wrong_owner);
-        assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::InvalidAuthority as u32));
+        assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::TokenOwnerMismatch as u32));
     }
 
     // ── validate_token_state_base tests ──────────────────────────────────
@@ -1026,7 +1266,7 @@ mod tests {
 
         let result = validate_token_state_base(&pid, &token_state_view);
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), bump);
+        assert_eq!(result.unwrap().bump(), bump);
     }
 
     #[test]
@@ -1054,7 +1294,7 @@ mod tests {
         let header_size = size_of::<RuntimeAccount>();
         let short_data_len = TOKEN_STATE_SIZE - 1;
         let total_bytes = header_size + short_data_len;
-        let words = (total_bytes + 7) / 8;
+        let words = total_bytes.div_ceil(8);
         let mut ts_buf = vec![0u64; words];
 
         let raw = ts_buf.as_mut_ptr() as *mut RuntimeAccount;
@@ -1136,7 +1376,7 @@ mod tests {
         let view = view_from_buf(&mut buf);
         let mint_addr = Address::from(mint);
         let result = validate_destination_ata_if_exists(&view, &mint_addr);
-        assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::InvalidAuthority as u32));
+        assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::AccountNotTokenOwned as u32));
     }
 
     #[test]
@@ -1163,7 +1403,7 @@ mod tests {
     ) -> Vec<u64> {
         let header_size = size_of::<RuntimeAccount>();
         let total_bytes = header_size + TOKEN_STATE_SIZE;
-        let words = (total_bytes + 7) / 8;
+        let words = total_bytes.div_ceil(8);
         let mut buf = vec![0u64; words];
 
         let pid = Address::from(program_id_bytes);
@@ -1229,7 +1469,7 @@ mod tests {
 
         let result = validate_metadata_accounts(&pid, &auth_view, &ts_view, &mint_view, &tp_view);
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), bump);
+        assert_eq!(result.unwrap().bump(), bump);
     }
 
     #[test]
@@ -1477,100 +1717,6 @@ mod tests {
 
     // ── execute_pda_transfer tests ──────────────────────────────────────
 
-    #[test]
-    fn test_execute_pda_transfer_zero_amount() {
-        let pid = Address::from(PROGRAM_ID);
-        let d = [1u8; 32];
-        let mut b0 = make_account_buf(d, d, true, false, 0).0;
-        let mut b1 = make_account_buf(d, d, false, false, 0).0;
-        let mut b2 = make_account_buf(d, d, false, false, 0).0;
-        let mut b3 = make_account_buf(d, d, false, false, 0).0;
-        let mut b4 = make_account_buf(d, d, false, false, 0).0;
-        let mut b5 = make_account_buf(d, d, false, false, 0).0;
-        let mut b6 = make_account_buf(d, d, false, false, 0).0;
-        let mut b7 = make_account_buf(d, d, false, false, 0).0;
-        let mut b8 = make_account_buf(d, d, false, false, 0).0;
-
-        let result = execute_pda_transfer(
-            &pid,
-            &view_from_buf(&mut b0), &view_from_buf(&mut b1),
-            &view_from_buf(&mut b2), &view_from_buf(&mut b3),
-            &view_from_buf(&mut b4), &view_from_buf(&mut b5),
-            &view_from_buf(&mut b6),
-            USER_SEED, &1u64.to_le_bytes(), 255,
-            &view_from_buf(&mut b7), &view_from_buf(&mut b8),
-            COMPANY_SEED, &2u64.to_le_bytes(), 255,
-            0, "zupy:v1:transfer:123",
-        );
-        assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::ZeroAmount as u32));
-    }
-
-    #[test]
-    fn test_execute_pda_transfer_invalid_memo() {
-        let pid = Address::from(PROGRAM_ID);
-        let d = [1u8; 32];
-        let mut b0 = make_account_buf(d, d, true, false, 0).0;
-        let mut b1 = make_account_buf(d, d, false, false, 0).0;
-        let mut b2 = make_account_buf(d, d, false, false, 0).0;
-        let mut b3 = make_account_buf(d, d, false, false, 0).0;
-        let mut b4 = make_account_buf(d, d, false, false, 0).0;
-        let mut b5 = make_account_buf(d, d, false, false, 0).0;
-        let mut b6 = make_account_buf(d, d, false, false, 0).0;
-        let mut b7 = make_account_buf(d, d, false, false, 0).0;
-        let mut b8 = make_account_buf(d, d, false, false, 0).0;
-
-        let result = execute_pda_transfer(
-            &pid,
-            &view_from_buf(&mut b0), &view_from_buf(&mut b1),
-            &view_from_buf(&mut b2), &view_from_buf(&mut b3),
-            &view_from_buf(&mut b4), &view_from_buf(&mut b5),
-            &view_from_buf(&mut b6),
-            USER_SEED, &1u64.to_le_bytes(), 255,
-            &view_from_buf(&mut b7), &view_from_buf(&mut b8),
-            COMPANY_SEED, &2u64.to_le_bytes(), 255,
-            1_000_000, "bad-memo",
-        );
-        assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::InvalidMemoFormat as u32));
-    }
-
-    #[test]
-    fn test_execute_pda_transfer_common_validation_fails() {
-        let pid_bytes = PROGRAM_ID;
-        let pid = Address::from(pid_bytes);
-        let bump = find_token_state_bump(pid_bytes);
-
-        // token_state with WRONG owner → fails validate_transfer_common
-        let mut ts_buf = make_token_state_account(
-            pid_bytes, [3u8; 32], [8u8; 32], [4u8; 32], bump, true, false,
-        );
-        let raw = ts_buf.as_mut_ptr() as *mut RuntimeAccount;
-        unsafe { (*raw).owner = Address::from([99u8; 32]); }
-        let ts_view = view_from_buf(&mut ts_buf);
-
-        let d = [1u8; 32];
-        let mut auth_buf = make_account_buf([3u8; 32], [0u8; 32], true, false, 0).0;
-        let mut mint_buf = make_account_buf([8u8; 32], TOKEN_2022_PROGRAM_ID, false, false, 0).0;
-        let mut tp_buf = make_account_buf(TOKEN_2022_PROGRAM_ID, [0u8; 32], false, false, 0).0;
-        let mut sys_buf = make_account_buf(d, d, false, false, 0).0;
-        let mut b5 = make_account_buf(d, d, false, false, 0).0;
-        let mut b6 = make_account_buf(d, d, false, false, 0).0;
-        let mut b7 = make_account_buf(d, d, false, false, 0).0;
-        let mut b8 = make_account_buf(d, d, false, false, 0).0;
-
-        let result = execute_pda_transfer(
-            &pid,
-            &view_from_buf(&mut auth_buf), &ts_view,
-            &view_from_buf(&mut mint_buf), &view_from_buf(&mut tp_buf),
-            &view_from_buf(&mut sys_buf), &view_from_buf(&mut b5),
-            &view_from_buf(&mut b6),
-            USER_SEED, &1u64.to_le_bytes(), 255,
-            &view_from_buf(&mut b7), &view_from_buf(&mut b8),
-            COMPANY_SEED, &2u64.to_le_bytes(), 255,
-            1_000_000, "zupy:v1:transfer:123",
-        );
-        assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::InvalidAuthority as u32));
-    }
-
     #[test]
     fn test_execute_pda_transfer_bad_source_pda() {
         let pid_bytes = PROGRAM_ID;
@@ -1604,14 +1750,16 @@ mod tests {
         let mut dst_pda_buf = make_account_buf(d, d, false, false, 0).0;
         let mut dst_ata_buf = make_account_buf(d, d, false, false, 0).0;
 
+        let validation =
+            validate_transfer_common(&pid, &ts_view, &auth_view, &mint_view, &tp_view).unwrap();
         let result = execute_pda_transfer(
             &pid,
-            &auth_view, &ts_view, &mint_view, &tp_view, &sys_view,
+            &validation, &auth_view, &mint_view, &tp_view, &sys_view,
             &src_pda_view, &view_from_buf(&mut src_ata_buf),
             USER_SEED, &source_id_bytes, source_bump,
             &view_from_buf(&mut dst_pda_buf), &view_from_buf(&mut dst_ata_buf),
             COMPANY_SEED, &2u64.to_le_bytes(), 255,
-            1_000_000, "zupy:v1:transfer:123",
+            1_000_000,
         );
         assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::InvalidPDA as u32));
     }
@@ -1656,14 +1804,16 @@ mod tests {
         let mut src_ata_buf = make_account_buf(d, d, false, false, 0).0;
         let mut dst_ata_buf = make_account_buf(d, d, false, false, 0).0;
 
+        let validation =
+            validate_transfer_common(&pid, &ts_view, &auth_view, &mint_view, &tp_view).unwrap();
         let result = execute_pda_transfer(
             &pid,
-            &auth_view, &ts_view, &mint_view, &tp_view, &sys_view,
+            &validation, &auth_view, &mint_view, &tp_view, &sys_view,
             &src_pda_view, &view_from_buf(&mut src_ata_buf),
             USER_SEED, &source_id_bytes, source_bump,
             &dst_pda_view, &view_from_buf(&mut dst_ata_buf),
             COMPANY_SEED, &dest_id_bytes, dest_bump,
-            1_000_000, "zupy:v1:transfer:123",
+            1_000_000,
         );
         assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::InvalidPDA as u32));
     }
@@ -1713,14 +1863,16 @@ mod tests {
         let d = [1u8; 32];
         let mut dst_ata_buf = make_account_buf(d, d, false, false, 0).0;
 
+        let validation =
+            validate_transfer_common(&pid, &ts_view, &auth_view, &mint_view, &tp_view).unwrap();
         let result = execute_pda_transfer(
             &pid,
-            &auth_view, &ts_view, &mint_view, &tp_view, &sys_view,
+            &validation, &auth_view, &mint_view, &tp_view, &sys_view,
             &src_pda_view, &src_ata_view,
             USER_SEED, &source_id_bytes, source_bump,
             &dst_pda_view, &view_from_buf(&mut dst_ata_buf),
             COMPANY_SEED, &dest_id_bytes, dest_bump,
-            1_000_000, "zupy:v1:transfer:123",
+            1_000_000,
         );
         assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::InvalidMint as u32));
     }
@@ -1772,14 +1924,16 @@ mod tests {
         let d = [1u8; 32];
         let mut dst_ata_buf = make_account_buf(d, d, false, false, 0).0;
 
+        let validation =
+            validate_transfer_common(&pid, &ts_view, &auth_view, &mint_view, &tp_view).unwrap();
         let result = execute_pda_transfer(
             &pid,
-            &auth_view, &ts_view, &mint_view, &tp_view, &sys_view,
+            &validation, &auth_view, &mint_view, &tp_view, &sys_view,
             &src_pda_view, &src_ata_view,
             USER_SEED, &source_id_bytes, source_bump,
             &dst_pda_view, &view_from_buf(&mut dst_ata_buf),
             COMPANY_SEED, &dest_id_bytes, dest_bump,
-            1_000_000, "zupy:v1:transfer:123",
+            1_000_000,
         );
         assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::InsufficientBalance as u32));
     }
@@ -1834,14 +1988,16 @@ mod tests {
         );
         let dst_ata_view = view_from_buf(&mut dst_ata_buf);
 
+        let validation =
+            validate_transfer_common(&pid, &ts_view, &auth_view, &mint_view, &tp_view).unwrap();
         let result = execute_pda_transfer(
             &pid,
-            &auth_view, &ts_view, &mint_view, &tp_view, &sys_view,
+            &validation, &auth_view, &mint_view, &tp_view, &sys_view,
             &src_pda_view, &src_ata_view,
             USER_SEED, &source_id_bytes, source_bump,
             &dst_pda_view, &dst_ata_view,
             COMPANY_SEED, &dest_id_bytes, dest_bump,
-            1_000_000, "zupy:v1:transfer:123",
+            1_000_000,
         );
         assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::InvalidMint as u32));
     }
@@ -1915,7 +2071,7 @@ mod tests {
         let mint_view = view_from_buf(&mut mint_buf);
 
         let result = validate_transfer_common_compressed(&pid, &ts_view, &auth_view, &mint_view);
-        assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::InvalidAuthority as u32));
+        assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::AuthorityNotSigner as u32));
     }
 
     #[test]
@@ -1939,7 +2095,7 @@ mod tests {
         let mint_view = view_from_buf(&mut mint_buf);
 
         let result = validate_transfer_common_compressed(&pid, &ts_view, &auth_view, &mint_view);
-        assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::InvalidAuthority as u32));
+        assert_eq!(result.unwrap_err(), ProgramError::Custom(ZupyTokenError::AuthorityMismatch as u32));
     }
 
     #[test]
@@ -0,0 +1,68 @@
+//! Optional user registration check for pool and company transfers.
+//!
+//! `transfer_from_pool` and `transfer_company_to_user` accept the recipient's
+//! UserState PDA `[USER_STATE_SEED, user_id]` (created by `initialize_user`) as
+//! a trailing optional account, before any SPL Memo program. It is recognised
+//! by owner, size and discriminator and stripped before the remaining accounts
+//! are forwarded. With `TokenState.strict_registration` off it is ignored;
+//! with it on, transfers to a user PDA without an active UserState fail with
+//! `UserNotRegistered`.
+
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::USER_STATE_SEED;
+use crate::error::ZupyTokenError;
+use crate::helpers::pda::{derive_user_pda, validate_pda_with_seeds};
+use crate::state::token_state::TokenState;
+use crate::state::user_state::{UserState, USER_STATE_DISCRIMINATOR, USER_STATE_SIZE};
+
+/// Splits a trailing UserState account off `accounts`.
+pub fn split_user_state<'a>(
+    program_id: &Address,
+    accounts: &'a [AccountView],
+) -> (&'a [AccountView], Option<&'a AccountView>) {
+    match accounts.split_last() {
+        Some((last, rest)) if is_user_state(program_id, last) => (rest, Some(last)),
+        _ => (accounts, None),
+    }
+}
+
+#[inline(always)]
+fn is_user_state(program_id: &Address, account: &AccountView) -> bool {
+    account.owned_by(program_id)
+        && account.data_len() >= USER_STATE_SIZE
+        && UserState::from_slice(unsafe { account.borrow_unchecked() }).discriminator()
+            == &USER_STATE_DISCRIMINATOR
+}
+
+/// In strict registration mode, requires `user_state` to be the active
+/// UserState of the user owning `user_pda`; otherwise `UserNotRegistered`.
+/// A no-op while `strict_registration` is off. `state` comes from the caller's
+/// token_state validation and `user_state` from `split_user_state`.
+pub fn enforce_user_registration(
+    program_id: &Address,
+    state: &TokenState,
+    user_state: Option<&AccountView>,
+    user_pda: &Address,
+) -> ProgramResult {
+    if !state.strict_registration() {
+        return Ok(());
+    }
+
+    let user_state = user_state.ok_or(ZupyTokenError::UserNotRegistered)?;
+    let user = UserState::from_slice(unsafe { user_state.borrow_unchecked() });
+    if !user.is_active() {
+        return Err(ZupyTokenError::UserNotRegistered.into());
+    }
+    let user_id_bytes = user.user_id().to_le_bytes();
+    validate_pda_with_seeds(
+        user_state.address(),
+        &[USER_STATE_SEED, &user_id_bytes, &[user.bump()]],
+        program_id,
+    )
+    .map_err(|_| ZupyTokenError::UserNotRegistered)?;
+    if &derive_user_pda(program_id, user.user_id()).0 != user_pda {
+        return Err(ZupyTokenError::UserNotRegistered.into());
+    }
+    Ok(())
+}
@@ -0,0 +1,498 @@
+//! Shared logic for withdraw-to-external instructions.
+//!
+//! [`decompress_to_external`] is parametrized by PDA seed (`USER_SEED` or `COMPANY_SEED`)
+//! and used by `withdraw_to_external` (user) and `withdraw_company_to_external` (company).
+//! Both share the dest ATA creation and `spl_interface_pda` validation, and the
+//! optional withdrawal fee (`TokenState.withdraw_fee_bps`) routed to the incentive pool.
+//! User withdrawals additionally honour an optional per-user daily limit PDA, and
+//! at or above `TokenState.withdraw_approval_threshold` must go through
+//! `request_withdrawal` + `approve_withdrawal`, which runs [`execute_withdraw`]
+//! with the stored parameters.
+
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::{AccountView, Address, ProgramResult};
+use pinocchio::error::ProgramError;
+
+use crate::constants::{LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, USER_SEED, WITHDRAW_LIMIT_SEED};
+use crate::error::zupy_err;
+use crate::helpers::account_checks::{assert_associated_token_address, expect_accounts};
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::compressed_accounts::{
+    cpi_compressed_transfer, cpi_decompress_to_spl, cpi_merge_decompress_to_spl,
+    validate_spl_interface_pda, MergeInputs,
+};
+use crate::helpers::cpi::{cpi_create_ata_idempotent, cpi_spl_memo, split_memo_program};
+use crate::helpers::events::{emit_anchor_event, WithdrawEvent};
+use crate::helpers::heartbeat::stamp_last_op;
+use crate::helpers::math::{checked_sub_amount, mul_bps};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::pda::validate_pda_with_seeds;
+use crate::helpers::receipt::{create_receipt, parse_op_id, OP_ID_LEN};
+use crate::helpers::stats::{split_stats_state, stats_counters};
+use crate::helpers::transfer_validation::{
+    validate_destination_ata_if_exists, validate_withdraw_request,
+};
+use crate::state::token_state::LastOp;
+use crate::state::withdraw_limit_state::{
+    WithdrawLimitState, WithdrawLimitStateMut, WITHDRAW_LIMIT_STATE_DISCRIMINATOR,
+    WITHDRAW_LIMIT_STATE_SIZE,
+};
+
+/// Withdrawal parameters, parsed from instruction data or loaded from a
+/// `WithdrawalRequestState`.
+pub struct WithdrawParams<'a> {
+    pub amount: u64,
+    pub entity_id: u64,
+    pub entity_bump: u8,
+    pub memo: &'a str,
+    /// spl_interface_pda bump from the client; `SPL_INTERFACE_BUMP_DERIVE` derives it on-chain.
+    pub spl_interface_bump: u8,
+    /// Compressed input leaves to merge (`withdraw_to_external_merged` only).
+    pub inputs: Option<MergeInputs<'a>>,
+    pub op_id: Option<[u8; OP_ID_LEN]>,
+    /// dest_ata's ATA bump, if the client sent it; otherwise derived on-chain.
+    pub dest_ata_bump: Option<u8>,
+}
+
+/// Flag byte announcing a trailing dest_ata_bump.
+pub const DEST_ATA_BUMP_FLAG: u8 = 1;
+
+/// Reads the optional `[flag u8 = 1][dest_ata_bump u8]` trailer left in `reader`.
+/// Returns `None` when no bytes follow; any other flag value (e.g. a stray
+/// trailing byte) is `InvalidInstructionData`.
+fn parse_dest_ata_bump(reader: &mut Reader<'_>) -> Result<Option<u8>, ProgramError> {
+    if reader.is_empty() {
+        return Ok(None);
+    }
+    match reader.read_u8()? {
+        DEST_ATA_BUMP_FLAG => reader.read_u8().map(Some),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+/// Decompress an entity PDA's compressed ZUPY balance into an external wallet's ATA.
+///
+/// Parses [`WithdrawParams`] from `data` and runs [`execute_withdraw`] on the
+/// direct (not treasury-approved) path.
+///
+/// Accounts (13 minimum):
+///   0. transfer_authority       (signer)           — Backend authority (Vault Transit)
+///   1. token_state              (read)             — Program state PDA
+///   2. mint                     (read)             — ZUPY mint (Token-2022)
+///   3. entity_pda               (read)             — Source user/company PDA (signs decompress CPI)
+///   4. dest_wallet              (read)             — External wallet address (NOT a PDA)
+///   5. dest_ata                 (writable)         — Destination ATA (created if needed)
+///   6. fee_payer                (writable, signer) — Pays ATA rent + Light Protocol fees
+///   7. token_program            (read)             — Token-2022 Program
+///   8. associated_token_program (read)             — ATA Program (required for ATA creation)
+///   9. system_program           (read)             — System Program
+///   10. compressed_token_program (read)            — Light cToken Program
+///   11. compressed_token_authority (read)          — Light cToken authority PDA
+///   12. spl_interface_pda       (writable)         — Light SPL pool PDA (seeds=[b"pool", mint])
+///   13+ Light system accounts                      — Merkle tree, nullifier queue, noop (client-injected)
+///
+/// With an `op_id`, the receipt PDA `[RECEIPT_SEED, op_id]` is inserted at 13 and the
+/// Light system accounts move to 14+. While `withdraw_fee_bps > 0`, the incentive pool
+/// (writable, must match `token_state.incentive_pool()`) follows (13, or 14 with a receipt) and the Light
+/// system accounts shift by one more.
+///
+/// User withdrawals (`USER_SEED`) may pass the user's `[WITHDRAW_LIMIT_SEED, user_id]`
+/// PDA (writable) right after those, before the Light system accounts. When present the
+/// amount is counted against its rolling 24h `daily_limit` (`WithdrawLimitExceeded` past it);
+/// users without the PDA are uncapped.
+///
+/// Fee: `fee = amount * withdraw_fee_bps / 10_000` (rounded down, in the user's favour)
+/// moves to the incentive pool as a compressed transfer; `amount - fee` is decompressed
+/// to dest_ata. With `withdraw_fee_bps == 0` neither the account nor the CPI is involved.
+///
+/// Data: amount (0-7) + entity_id (8-15) + entity_bump (16) + memo (17+)
+///       + spl_interface_bump (u8, after memo) + optional op_id ([u8; 16])
+///       + optional dest_ata_bump trailer (flag u8 = 1 + bump u8, last)
+///
+/// dest_ata must be the associated token account of (dest_wallet, mint) under
+/// token_program (`InvalidAssociatedTokenAccount` otherwise). Sending
+/// `dest_ata_bump` saves the on-chain `find_program_address`. Likewise
+/// `spl_interface_bump` is checked with one `create_program_address` (`InvalidPDA`
+/// if wrong); `0xFF` (`SPL_INTERFACE_BUMP_DERIVE`) derives it on-chain instead.
+///
+/// Amounts above `per_tx_auto_limit` (0 = no limit) fail with `ExceedsPerTxLimit`
+/// unless the treasury co-signs as an extra account (conventionally last).
+///
+/// User withdrawals of `withdraw_approval_threshold` or more (0 = no threshold) fail
+/// with `RequiresApproval`.
+///
+/// Optional: the SPL Memo program as the very last account; the validated memo is
+/// then also emitted via CPI to it (see `split_memo_program`).
+///
+/// Optional: the Stats PDA `[STATS_SEED]` (writable) before any memo program; the
+/// amount (fee included) is added to its `total_withdrawn` (see `helpers::stats`).
+///
+/// Passing token_state writable stamps `last_withdraw_ts` on a v2 TokenState
+/// (see `helpers::heartbeat`).
+///
+/// `merged` (`withdraw_to_external_merged`): a [`MergeInputs`] section follows the
+/// spl_interface_bump, before any op_id — 1 to `MAX_MERGE_INPUTS` compressed leaves with their
+/// Merkle contexts (`InvalidInputCount` otherwise). The payout is decompressed from
+/// them in one CPI and the change re-compressed to the entity; a payout above their
+/// sum fails with `InsufficientBalance`, and tree / queue indices must address the
+/// Light system accounts (`NotEnoughAccountKeys`).
+pub fn decompress_to_external(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+    pda_seed: &[u8],
+    merged: bool,
+) -> ProgramResult {
+    // 1. Account count check (MUST be first)
+    if accounts.len() < 13 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    // 2. Parse instruction data
+    let mut reader = Reader::new(data);
+    let params = WithdrawParams {
+        amount: reader.read_u64()?,
+        entity_id: reader.read_u64()?,
+        entity_bump: reader.read_u8()?,
+        memo: reader.read_string()?,
+        spl_interface_bump: reader.read_u8()?,
+        inputs: if merged { Some(MergeInputs::parse(&mut reader)?) } else { None },
+        op_id: parse_op_id(&mut reader),
+        dest_ata_bump: parse_dest_ata_bump(&mut reader)?,
+    };
+    reader.finish()?;
+
+    execute_withdraw(program_id, accounts, &params, pda_seed, false)
+}
+
+/// Full withdraw flow over the [`decompress_to_external`] account layout.
+///
+/// `treasury_approved` is set by `approve_withdrawal` only: the treasury has
+/// signed off on this exact request, so the approval threshold and
+/// `per_tx_auto_limit` are not applied. The daily limit PDA still is.
+pub fn execute_withdraw(
+    program_id: &Address,
+    accounts: &[AccountView],
+    params: &WithdrawParams,
+    pda_seed: &[u8],
+    treasury_approved: bool,
+) -> ProgramResult {
+    // 0. Optional trailing SPL Memo program (stripped before any index math)
+    let (accounts, memo_program) = split_memo_program(accounts);
+
+    // 0b. Optional trailing Stats PDA (global volume counters)
+    let (accounts, stats) = split_stats_state(program_id, accounts);
+
+    // 1–2. Account count check (MUST be first) + unpack accounts and parameters
+    expect_accounts!(accounts, [
+        _,                     // transfer_authority — checked by validate_withdraw_request
+        _,                     // token_state — checked by validate_withdraw_request
+        mint,
+        entity_pda,
+        dest_wallet,
+        dest_ata,
+        fee_payer,
+        token_program,
+        _,                     // associated_token_program — must be in tx accounts list for
+                               // the ATA CPI at runtime; cpi_create_ata_idempotent uses hardcoded ID
+        system_program,
+        compressed_token_prog,
+        compressed_token_auth,
+        spl_interface_pda,
+    ]);
+
+    let amount      = params.amount;
+    let entity_id   = params.entity_id;
+    let entity_bump = params.entity_bump;
+    let memo        = params.memo;
+    let op_id       = params.op_id;
+
+    // 3. Account count with the optional receipt
+    let receipt_end = if op_id.is_some() { 14 } else { 13 };
+    if accounts.len() < receipt_end {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    // 4–7. Amount, memo, common checks, per-tx limit / approval threshold, entity PDA
+    let state = validate_withdraw_request(
+        program_id,
+        accounts,
+        token_program,
+        params,
+        pda_seed,
+        treasury_approved,
+    )?;
+    let entity_id_bytes = entity_id.to_le_bytes();
+
+    // 7b. Withdrawal fee split (incentive pool account only while a fee is configured)
+    let fee_bps = state.withdraw_fee_bps();
+    let remaining_start = if fee_bps > 0 { receipt_end + 1 } else { receipt_end };
+    if accounts.len() < remaining_start {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let fee = compute_withdraw_fee(amount, fee_bps)?;
+    let payout = checked_sub_amount(amount, fee)?;
+    // Stored by initialize_token as the [INCENTIVE_POOL_SEED] PDA; no bump search here
+    if fee_bps > 0 && accounts[receipt_end].address().as_ref() != state.incentive_pool() {
+        return Err(zupy_err!(InvalidIncentivePool, "incentive_pool"));
+    }
+
+    // 7c. Per-user daily limit (optional PDA, user withdrawals only)
+    let remaining_start = if pda_seed == USER_SEED
+        && consume_withdraw_limit(program_id, accounts.get(remaining_start), entity_id, amount)?
+    {
+        remaining_start + 1
+    } else {
+        remaining_start
+    };
+
+    // 7d. Merged inputs cover the payout and address forwarded Light accounts
+    if let Some(inputs) = &params.inputs {
+        if payout > inputs.total()? {
+            return Err(zupy_err!(InsufficientBalance, "merge_inputs"));
+        }
+        inputs.validate_accounts(accounts.len() - remaining_start)?;
+    }
+
+    // 8. Validate fee_payer is a signer (same pattern as other compressed instructions)
+    if !fee_payer.is_signer() {
+        return Err(zupy_err!(FeePayerNotSigner, "fee_payer"));
+    }
+
+    // 9. Validate compressed_token_program is the Light cToken program
+    let expected_ctoken: Address = LIGHT_COMPRESSED_TOKEN_PROGRAM_ID.into();
+    if compressed_token_prog.address() != &expected_ctoken {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // 10. Validate existing dest_ata if present (mint check) — no-op if account has no data (AC3)
+    validate_destination_ata_if_exists(dest_ata, mint.address())?;
+
+    // 10b. dest_ata is the canonical ATA of (dest_wallet, mint), existing or not
+    assert_associated_token_address(
+        dest_ata,
+        dest_wallet.address(),
+        mint.address(),
+        token_program.address(),
+        params.dest_ata_bump,
+    )?;
+
+    // 11. Idempotency receipt (optional) — before any ATA rent is spent on a retry
+    if let Some(op_id) = &op_id {
+        create_receipt(program_id, &accounts[13], fee_payer, op_id)?;
+    }
+
+    // 12. Create dest_ata for external wallet if it doesn't exist
+    // The external wallet is not a PDA and cannot hold compressed tokens directly.
+    cpi_create_ata_idempotent(
+        dest_ata,
+        fee_payer,    // pays ATA rent (~0.002 SOL) — NOT transfer_authority
+        dest_wallet,  // owner (external wallet — NOT a PDA)
+        mint,
+        token_program,
+        system_program,
+    )?;
+
+    // 13. Validate spl_interface_pda address (client bump or derived) for the CPI (AC1)
+    let mint_key: [u8; 32] = mint.address().as_ref().try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    let spl_bump =
+        validate_spl_interface_pda(spl_interface_pda.address(), &mint_key, params.spl_interface_bump)?;
+
+    // 13b. Standard memo (optional)
+    if let Some(memo_program) = memo_program {
+        cpi_spl_memo(memo_program, memo)?;
+    }
+
+    // 13c. Event log (all checks passed)
+    emit_anchor_event(&WithdrawEvent {
+        source: *address_bytes(entity_pda.address()),
+        destination: *address_bytes(dest_ata.address()),
+        amount: payout,
+        fee,
+    });
+
+    // 14. Fee leg: entity compressed balance → incentive pool (compressed transfer)
+    let bump_bytes = [entity_bump];
+    if fee > 0 {
+        let fee_signer_seeds: [Seed; 3] = [
+            Seed::from(pda_seed),
+            Seed::from(entity_id_bytes.as_ref()),
+            Seed::from(bump_bytes.as_ref()),
+        ];
+        let fee_signer = Signer::from(&fee_signer_seeds);
+
+        cpi_compressed_transfer(
+            compressed_token_prog,
+            fee_payer,
+            entity_pda,             // source
+            &accounts[receipt_end], // destination: incentive pool PDA
+            entity_pda,             // authority (source PDA signs)
+            system_program,
+            fee,
+            &[fee_signer],
+        )?;
+    }
+
+    // 15. Decompress: entity compressed balance → dest_ata (external wallet's ATA) (AC1)
+    // entity_pda signs with its 3-seed pattern
+    let signer_seeds: [Seed; 3] = [
+        Seed::from(pda_seed),
+        Seed::from(entity_id_bytes.as_ref()),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    let signer = Signer::from(&signer_seeds);
+
+    match &params.inputs {
+        Some(inputs) => cpi_merge_decompress_to_spl(
+            compressed_token_auth,
+            fee_payer,
+            mint,
+            dest_ata,
+            entity_pda,
+            spl_interface_pda,
+            token_program,
+            system_program,
+            inputs,
+            payout,
+            spl_bump,
+            state.decimals(),
+            &accounts[remaining_start..],
+            &[signer],
+        )?,
+        None => cpi_decompress_to_spl(
+            compressed_token_prog,
+            compressed_token_auth,
+            fee_payer,
+            mint,
+            dest_ata,          // destination SPL (external wallet's ATA)
+            entity_pda,        // authority (source owner, signs decompress)
+            spl_interface_pda,
+            token_program,
+            system_program,
+            payout,
+            spl_bump,
+            state.decimals(),
+            &accounts[remaining_start..], // remaining Light system accounts (Merkle tree, nullifier queue, noop)
+            &[signer],
+        )?,
+    }
+
+    // 16. Global stats (optional, saturating)
+    if let Some(mut counters) = stats_counters(stats) {
+        counters.record_withdrawn(amount);
+    }
+
+    // 17. Heartbeat: last_withdraw_ts (v2, writable token_state only)
+    stamp_last_op(&accounts[1], LastOp::Withdraw)?;
+
+    Ok(())
+}
+
+/// Charge `amount` against the user's withdraw limit PDA if `account` is one.
+///
+/// Returns `Ok(false)` when `account` is absent or not a WithdrawLimitState (the
+/// Light system accounts that follow are never owned by this program), leaving the
+/// user uncapped. A limit PDA for another user is rejected.
+pub fn consume_withdraw_limit(
+    program_id: &Address,
+    account: Option<&AccountView>,
+    user_id: u64,
+    amount: u64,
+) -> Result<bool, ProgramError> {
+    let Some(limit_account) = account else {
+        return Ok(false);
+    };
+    if !limit_account.owned_by(program_id) || limit_account.data_len() < WITHDRAW_LIMIT_STATE_SIZE {
+        return Ok(false);
+    }
+    let limit = WithdrawLimitState::from_slice(unsafe { limit_account.borrow_unchecked() });
+    if limit.discriminator() != &WITHDRAW_LIMIT_STATE_DISCRIMINATOR {
+        return Ok(false);
+    }
+    if limit.user_id() != user_id {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let user_id_bytes = user_id.to_le_bytes();
+    validate_pda_with_seeds(
+        limit_account.address(),
+        &[WITHDRAW_LIMIT_SEED, &user_id_bytes, &[limit.bump()]],
+        program_id,
+    )?;
+
+    use pinocchio::sysvars::Sysvar as _;
+    let clock = pinocchio::sysvars::clock::Clock::get()?;
+    let mut limit = WithdrawLimitStateMut::from_slice(unsafe { limit_account.borrow_unchecked_mut() });
+    if !limit.try_consume(amount, clock.unix_timestamp) {
+        return Err(zupy_err!(WithdrawLimitExceeded, "withdraw_limit"));
+    }
+    Ok(true)
+}
+
+/// Withdrawal fee for `amount` at `fee_bps`, rounded down so the user never
+/// pays more than the configured rate ([`mul_bps`]); `fee <= amount`
+/// whenever `fee_bps <= BPS_DENOMINATOR`.
+pub fn compute_withdraw_fee(amount: u64, fee_bps: u16) -> Result<u64, ProgramError> {
+    mul_bps(amount, fee_bps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::MAX_WITHDRAW_FEE_BPS;
+
+    fn reconcile(amount: u64, fee_bps: u16) -> (u64, u64) {
+        let fee = compute_withdraw_fee(amount, fee_bps).unwrap();
+        let payout = amount.checked_sub(fee).unwrap();
+        assert_eq!(fee + payout, amount, "fee + payout must equal amount ({amount} @ {fee_bps} bps)");
+        (fee, payout)
+    }
+
+    #[test]
+    fn test_zero_bps_is_no_fee() {
+        for amount in [1, 999, 1_000_000, u64::MAX] {
+            assert_eq!(reconcile(amount, 0), (0, amount));
+        }
+    }
+
+    #[test]
+    fn test_one_raw_unit_pays_no_fee() {
+        assert_eq!(reconcile(1, MAX_WITHDRAW_FEE_BPS), (0, 1));
+        assert_eq!(reconcile(1, 1), (0, 1));
+    }
+
+    #[test]
+    fn test_fee_rounds_down() {
+        // 1.5% of 333 = 4.995 → 4
+        assert_eq!(reconcile(333, 150), (4, 329));
+        // 0.01% of 9_999 = 0.9999 → 0
+        assert_eq!(reconcile(9_999, 1), (0, 9_999));
+        assert_eq!(reconcile(10_000, 1), (1, 9_999));
+    }
+
+    #[test]
+    fn test_fee_reconciles_across_amounts() {
+        for amount in [1, 2, 19, 20, 21, 123_456, 1_000_000, 999_999_999_999, u64::MAX] {
+            for bps in [1, 50, 250, MAX_WITHDRAW_FEE_BPS] {
+                let (fee, _) = reconcile(amount, bps);
+                assert!(fee as u128 * 10_000 <= amount as u128 * bps as u128);
+            }
+        }
+        assert_eq!(reconcile(u64::MAX, MAX_WITHDRAW_FEE_BPS).0, u64::MAX / 20);
+    }
+
+    #[test]
+    fn test_parse_dest_ata_bump() {
+        assert_eq!(parse_dest_ata_bump(&mut Reader::new(&[])), Ok(None));
+        assert_eq!(parse_dest_ata_bump(&mut Reader::new(&[DEST_ATA_BUMP_FLAG, 254])), Ok(Some(254)));
+        // A bare trailing byte is not a bump
+        assert_eq!(
+            parse_dest_ata_bump(&mut Reader::new(&[254])),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            parse_dest_ata_bump(&mut Reader::new(&[DEST_ATA_BUMP_FLAG])),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
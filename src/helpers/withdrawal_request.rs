@@ -0,0 +1,61 @@
+//! Shared logic for the two-phase withdrawal family
+//! (`request_withdrawal`, `approve_withdrawal`, `cancel_withdrawal`).
+//!
+//! The request PDA `[WITHDRAWAL_REQUEST_SEED, request_id]` only records the
+//! withdrawal parameters; no tokens move until approval. Approval and
+//! cancellation both close the PDA and refund its rent to the stored payer.
+
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::WITHDRAWAL_REQUEST_SEED;
+use crate::error::ZupyTokenError;
+use crate::helpers::account_checks::assert_discriminator;
+use crate::helpers::pda::validate_pda_with_seeds;
+use crate::state::withdrawal_request_state::{
+    WithdrawalRequestState, WITHDRAWAL_REQUEST_STATE_DISCRIMINATOR, WITHDRAWAL_REQUEST_STATE_SIZE,
+};
+
+/// Validates an open withdrawal request and its rent recipient.
+///
+/// Checks (in order): owned by our program, size, discriminator, stored
+/// `request_id` matches, PDA `[WITHDRAWAL_REQUEST_SEED, request_id, bump]` via
+/// the stored bump, then `payer` is the stored payer.
+pub fn validate_withdrawal_request(
+    program_id: &Address,
+    request_account: &AccountView,
+    request_id: u64,
+    payer: &AccountView,
+) -> Result<(), ProgramError> {
+    if !request_account.owned_by(program_id)
+        || request_account.data_len() < WITHDRAWAL_REQUEST_STATE_SIZE
+    {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let request = WithdrawalRequestState::from_slice(unsafe { request_account.borrow_unchecked() });
+    assert_discriminator(request.discriminator(), &WITHDRAWAL_REQUEST_STATE_DISCRIMINATOR)?;
+    if request.request_id() != request_id {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let request_id_bytes = request_id.to_le_bytes();
+    validate_pda_with_seeds(
+        request_account.address(),
+        &[WITHDRAWAL_REQUEST_SEED, &request_id_bytes, &[request.bump()]],
+        program_id,
+    )?;
+    if payer.address().as_ref() != request.payer() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// Closes the request: moves its rent to `payer`, then releases the account.
+pub fn close_withdrawal_request(request_account: &AccountView, payer: &AccountView) -> ProgramResult {
+    let refund = payer
+        .lamports()
+        .checked_add(request_account.lamports())
+        .ok_or(ZupyTokenError::InvalidAmount)?;
+    payer.set_lamports(refund);
+    request_account.set_lamports(0);
+    request_account.close()
+}
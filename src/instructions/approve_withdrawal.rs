@@ -0,0 +1,101 @@
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::USER_SEED;
+use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::compressed_accounts::SPL_INTERFACE_BUMP_DERIVE;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::transfer_validation::validate_token_state_base;
+use crate::helpers::withdraw_common::{execute_withdraw, WithdrawParams};
+use crate::helpers::withdrawal_request::{close_withdrawal_request, validate_withdrawal_request};
+use crate::state::withdrawal_request_state::WithdrawalRequestState;
+
+/// Process `approve_withdrawal` instruction.
+///
+/// Second phase of a two-phase withdrawal: the treasury co-signs and the stored
+/// request (user_id, amount, destination) runs through the `withdraw_to_external`
+/// flow, then the WithdrawalRequestState PDA is closed and its rent refunded.
+/// `withdraw_approval_threshold` and `per_tx_auto_limit` do not apply; the
+/// optional withdrawal fee and daily limit PDA do.
+///
+/// Accounts (16 minimum):
+///   0. treasury (signer)             — must be token_state.treasury()
+///   1. withdrawal_request (writable) — PDA [WITHDRAWAL_REQUEST_SEED, request_id]
+///   2. payer (writable)              — must match request.payer(); receives the rent
+///   3+ `withdraw_to_external` accounts (13 minimum, same order and optional accounts,
+///      no receipt); dest_wallet (7) must match request.destination()
+///
+/// Data: request_id (u64) + user_bump (u8) + memo (String)
+/// Discriminator: `[75, 48, 146, 122, 201, 158, 210, 123]` (SHA256("global:approve_withdrawal"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (16 accounts minimum) ────────────────────────
+    if accounts.len() < 16 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let treasury = &accounts[0];
+    let request_account = &accounts[1];
+    let payer = &accounts[2];
+    let withdraw_accounts = &accounts[3..];
+    let token_state_account = &withdraw_accounts[1];
+    let dest_wallet = &withdraw_accounts[4];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let request_id = reader.read_u64()?;
+    let user_bump = reader.read_u8()?;
+    let memo = reader.read_string()?;
+    reader.finish()?;
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+
+    // ── Treasury authorization ──────────────────────────────────────────
+    if !treasury.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    let treasury_key: &[u8; 32] = address_bytes(treasury.address());
+    if !state.is_treasury(treasury_key) {
+        return Err(ZupyTokenError::UnauthorizedTreasury.into());
+    }
+
+    // ── Request validation: owner, size, discriminator, PDA, payer ──────
+    validate_withdrawal_request(program_id, request_account, request_id, payer)?;
+    let request = WithdrawalRequestState::from_slice(unsafe { request_account.borrow_unchecked() });
+    if dest_wallet.address().as_ref() != request.destination() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // ── Withdraw with the stored parameters ─────────────────────────────
+    let params = WithdrawParams {
+        amount: request.amount(),
+        entity_id: request.user_id(),
+        entity_bump: user_bump,
+        memo,
+        spl_interface_bump: SPL_INTERFACE_BUMP_DERIVE,
+        inputs: None,
+        op_id: None,
+        dest_ata_bump: None,
+    };
+    execute_withdraw(program_id, withdraw_accounts, &params, USER_SEED, true)?;
+
+    // ── Close: move rent to payer, then release the account ─────────────
+    close_withdrawal_request(request_account, payer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_approve_withdrawal_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 13];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
@@ -0,0 +1,229 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::{AccountView, Address, ProgramResult};
+use pinocchio::error::ProgramError;
+
+use crate::constants::{
+    COMPANY_SEED, LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, LIGHT_TOKEN_CPI_AUTHORITY,
+    MAX_BATCH_RETURN_COMPANIES, RETURN_ALL_AMOUNT, TOKEN_2022_PROGRAM_ID,
+};
+use crate::error::ZupyTokenError;
+use crate::helpers::compressed_accounts::{cpi_decompress_to_spl, derive_spl_interface_pda};
+use crate::helpers::instruction_data::{parse_u64, parse_u8, Reader};
+use crate::helpers::math::checked_add_amount;
+use crate::helpers::memo::{validate_memo_op, MemoOp};
+use crate::helpers::pda::{validate_pda, validate_pda_with_seeds};
+use crate::helpers::transfer_validation::validate_transfer_common;
+
+/// Size of one batch entry: company_id (u64) + amount (u64) + company_bump (u8).
+const BATCH_ENTRY_SIZE: usize = 17;
+
+/// Index of the first company PDA; entry `i` uses `accounts[COMPANIES_START + i]`.
+const COMPANIES_START: usize = 10;
+
+/// Process `batch_return_to_pool` instruction (compressed version).
+///
+/// End-of-day settlement: returns unused balances from up to
+/// `MAX_BATCH_RETURN_COMPANIES` companies to the pool ATA in one transaction.
+/// token_state, mint, pool and Light accounts are validated once; every entry
+/// (non-zero amount, no duplicate company_id, company PDA) is validated before
+/// any CPI. Each company PDA must sign for its own compressed leaves, so one
+/// Path A reverse decompress CPI is issued per entry; any failure reverts the
+/// whole batch. The `RETURN_ALL_AMOUNT` sentinel is not supported here.
+///
+/// Accounts (10 + count minimum, plus Light system accounts):
+///   0. transfer_authority         (signer)
+///   1. token_state                (read)
+///   2. mint                       (read)
+///   3. pool_ata                   (writable)
+///   4. fee_payer                  (writable, signer)
+///   5. token_program              (read)             — Token-2022
+///   6. system_program             (read)
+///   7. compressed_token_program   (read)             — Light cToken program
+///   8. compressed_token_authority (read)             — LIGHT_TOKEN_CPI_AUTHORITY
+///   9. spl_interface_pda          (writable)         — seeds=[b"pool", mint]
+///   10..10+count company PDAs     (read)             — one per entry, in entry order
+///   10+count.. Light system accounts                 — shared by every entry's CPI
+///
+/// Data: memo (String, bytes 0+) + entries (Vec<(u64 company_id, u64 amount, u8 company_bump)>,
+///       u32 LE count followed by 17-byte entries)
+/// Discriminator: `[222, 46, 174, 2, 156, 238, 219, 34]` (SHA256("global:batch_return_to_pool"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (10 accounts minimum) ─────────────────────────
+    if accounts.len() < COMPANIES_START {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let transfer_authority    = &accounts[0];
+    let token_state_account   = &accounts[1];
+    let mint                  = &accounts[2];
+    let pool_ata              = &accounts[3];
+    let fee_payer             = &accounts[4];
+    let token_program         = &accounts[5];
+    let system_program        = &accounts[6];
+    let compressed_token_prog = &accounts[7];
+    let compressed_token_auth = &accounts[8];
+    let spl_interface_pda     = &accounts[9];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let memo = reader.read_string()?;
+    let count = reader.read_u32()? as usize;
+    if count == 0 || count > MAX_BATCH_RETURN_COMPANIES {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let entries = reader.read_slice(count * BATCH_ENTRY_SIZE)?;
+    reader.finish()?;
+    if accounts.len() < COMPANIES_START + count {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let companies = &accounts[COMPANIES_START..COMPANIES_START + count];
+    let light_accounts = &accounts[COMPANIES_START + count..];
+
+    // ── Input validation (every entry, before any CPI) ──────────────────
+    let mut total: u64 = 0;
+    for i in 0..count {
+        let (company_id, amount, _) = parse_entry(entries, i)?;
+        if amount == 0 {
+            return Err(ZupyTokenError::ZeroAmount.into());
+        }
+        if amount == RETURN_ALL_AMOUNT {
+            return Err(ZupyTokenError::InvalidAmount.into());
+        }
+        for j in 0..i {
+            if parse_entry(entries, j)?.0 == company_id {
+                return Err(ZupyTokenError::DuplicateBatchEntry.into());
+            }
+        }
+        total = checked_add_amount(total, amount)?;
+    }
+    validate_memo_op(memo, MemoOp::Return)?;
+
+    // ── Common transfer validation (9 checks, Spec §7.1-§7.8) ───────────
+    let validation = validate_transfer_common(
+        program_id,
+        token_state_account,
+        transfer_authority,
+        mint,
+        token_program,
+    )?;
+
+    // ── Additional signer check: fee_payer ──────────────────────────────
+    if !fee_payer.is_signer() {
+        return Err(ZupyTokenError::FeePayerNotSigner.into());
+    }
+
+    // ── Verify compressed_token_program is the Light cToken program ──────
+    let expected_ctoken: Address = LIGHT_COMPRESSED_TOKEN_PROGRAM_ID.into();
+    if compressed_token_prog.address() != &expected_ctoken {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // ── Verify compressed_token_authority is the canonical cToken CPI PDA ─
+    let expected_ctoken_auth = Address::from(LIGHT_TOKEN_CPI_AUTHORITY);
+    if compressed_token_auth.address() != &expected_ctoken_auth {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // ── PDA validation: every company (via client-provided bumps) ───────
+    for (i, company_pda) in companies.iter().enumerate() {
+        let (company_id, _, company_bump) = parse_entry(entries, i)?;
+        validate_pda_with_seeds(
+            company_pda.address(),
+            &[COMPANY_SEED, &company_id.to_le_bytes(), &[company_bump]],
+            program_id,
+        )?;
+    }
+
+    // ── Pool ATA validation ─────────────────────────────────────────────
+    let state = validation.state;
+    if pool_ata.address().as_ref() != state.pool_ata() {
+        return Err(ZupyTokenError::InvalidPoolAccount.into());
+    }
+    let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
+    if !pool_ata.owned_by(&token_2022_addr) {
+        return Err(ZupyTokenError::InvalidPoolAccount.into());
+    }
+
+    // ── Validate spl_interface_pda address and derive bump ───────────────
+    let mint_key: [u8; 32] = mint.address().as_ref().try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    let (expected_spl_pda, spl_bump) = derive_spl_interface_pda(&mint_key);
+    validate_pda(spl_interface_pda.address(), &expected_spl_pda)?;
+
+    // ── CPI loop: one decompress per company, signed by its PDA ─────────
+    for (i, company_pda) in companies.iter().enumerate() {
+        let (company_id, amount, company_bump) = parse_entry(entries, i)?;
+        let company_id_bytes = company_id.to_le_bytes();
+        let bump_bytes = [company_bump];
+        let signer_seeds: [Seed; 3] = [
+            Seed::from(COMPANY_SEED),
+            Seed::from(company_id_bytes.as_ref()),
+            Seed::from(bump_bytes.as_ref()),
+        ];
+        let signer = Signer::from(&signer_seeds);
+
+        cpi_decompress_to_spl(
+            compressed_token_prog,
+            compressed_token_auth,
+            fee_payer,
+            mint,
+            pool_ata,
+            company_pda,
+            spl_interface_pda,
+            token_program,
+            system_program,
+            amount,
+            spl_bump,
+            state.decimals(),
+            light_accounts,
+            &[signer],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Parse the `i`-th batch entry: (company_id, amount, company_bump).
+#[inline(always)]
+fn parse_entry(entries: &[u8], i: usize) -> Result<(u64, u64, u8), ProgramError> {
+    let offset = i * BATCH_ENTRY_SIZE;
+    let company_id = parse_u64(entries, offset)?;
+    let amount = parse_u64(entries, offset + 8)?;
+    let company_bump = parse_u8(entries, offset + 16)?;
+    Ok((company_id, amount, company_bump))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_return_to_pool_not_enough_account_keys() {
+        let program_id = Address::default();
+        let data = [0u8; 16];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+
+    #[test]
+    fn test_parse_entry_reads_company_amount_and_bump() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&42u64.to_le_bytes());
+        data.extend_from_slice(&500u64.to_le_bytes());
+        data.push(254);
+        data.extend_from_slice(&7u64.to_le_bytes());
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+        data.push(251);
+        assert_eq!(parse_entry(&data, 0).unwrap(), (42, 500, 254));
+        assert_eq!(parse_entry(&data, 1).unwrap(), (7, 1_000, 251));
+    }
+
+    #[test]
+    fn test_parse_entry_truncated() {
+        let data = [0u8; BATCH_ENTRY_SIZE - 1];
+        assert_eq!(parse_entry(&data, 0), Err(ProgramError::InvalidInstructionData));
+    }
+}
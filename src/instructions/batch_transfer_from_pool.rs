@@ -0,0 +1,238 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::{AccountView, Address, ProgramResult};
+use pinocchio::error::ProgramError;
+
+use crate::constants::{
+    LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, LIGHT_TOKEN_CPI_AUTHORITY, MAX_BATCH_RECIPIENTS,
+    TOKEN_2022_PROGRAM_ID, TOKEN_STATE_SEED,
+};
+use crate::error::zupy_err;
+use crate::helpers::compressed_accounts::{cpi_compress_from_spl, derive_spl_interface_pda};
+use crate::helpers::heartbeat::stamp_last_op;
+use crate::helpers::instruction_data::{parse_u64, parse_u8, Reader};
+use crate::helpers::math::{checked_add_amount, checked_sub_amount};
+use crate::helpers::memo::{validate_memo_op, MemoOp};
+use crate::helpers::pda::validate_pda;
+use crate::helpers::transfer_validation::{read_token_balance, validate_transfer_common};
+use crate::state::token_state::LastOp;
+
+/// Size of one batch entry: recipient_index (u8) + amount (u64).
+const BATCH_ENTRY_SIZE: usize = 9;
+
+/// Index of the first recipient account; entries index into `accounts[16..]`.
+const RECIPIENTS_START: usize = 16;
+
+/// Process `batch_transfer_from_pool` instruction (compressed token version).
+///
+/// Same flow as `transfer_from_pool`, but compresses from the pool ATA to up to
+/// `MAX_BATCH_RECIPIENTS` recipients in a single transaction (promotional
+/// airdrops). The pool is validated once, the summed amount is checked against
+/// the pool balance up front, and one Path A compress CPI is issued per entry.
+/// Any entry with a zero amount fails the whole batch.
+///
+/// Accounts (16 minimum, plus one recipient account per referenced index):
+///   0.  transfer_authority         (signer)           — must match TRANSFER_AUTHORITY_PUBKEY
+///   1.  token_state                (read)             — our program's token_state PDA
+///   2.  mint                       (read)             — ZUPY Token-2022 mint
+///   3.  pool_ata                   (writable)         — distribution pool ATA (source)
+///   4.  fee_payer                  (writable, signer) — pays Light Protocol rent/fees
+///   5.  token_program              (read)             — Token-2022 program
+///   6.  system_program             (read)             — System program
+///   7.  compressed_token_program   (read)             — Light cToken program
+///   8.  cpi_authority_pda          (read)             — LIGHT_TOKEN_CPI_AUTHORITY
+///   9.  light_system_program       (read)             — LIGHT_SYSTEM_PROGRAM_ID
+///   10. registered_program_pda     (read)             — REGISTERED_PROGRAM_PDA
+///   11. noop_program               (read)             — SPL_NOOP_ID
+///   12. account_compression_authority (read)          — ACCOUNT_COMPRESSION_AUTHORITY
+///   13. account_compression_program  (read)           — ACCOUNT_COMPRESSION_PROGRAM_ID
+///   14. spl_interface_pda          (writable)         — Light SPL pool PDA (seeds=[b"pool", mint])
+///   15. Merkle tree output queue   (writable)         — injected by JS client
+///   16+ recipients                 (read)             — referenced by entry index
+///
+/// Data: memo (String, bytes 0+) + entries (Vec<(u8 recipient_index, u64 amount)>,
+///       u32 LE count followed by 9-byte entries)
+/// Discriminator: `[11, 232, 149, 72, 195, 180, 19, 106]` (SHA256("global:batch_transfer_from_pool"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (16 accounts minimum) ─────────────────────────
+    if accounts.len() < RECIPIENTS_START {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let transfer_authority           = &accounts[0];
+    let token_state_account          = &accounts[1];
+    let mint                         = &accounts[2];
+    let pool_ata                     = &accounts[3];
+    let fee_payer                    = &accounts[4];
+    let token_program                = &accounts[5];
+    let system_program               = &accounts[6];
+    let compressed_token_prog        = &accounts[7];
+    let cpi_authority_pda            = &accounts[8];
+    let light_system_program         = &accounts[9];
+    let registered_program_pda       = &accounts[10];
+    let noop_program                 = &accounts[11];
+    let account_compression_authority = &accounts[12];
+    let account_compression_program  = &accounts[13];
+    let spl_interface_pda            = &accounts[14];
+    let output_queue                 = &accounts[15..RECIPIENTS_START];
+    let recipients                   = &accounts[RECIPIENTS_START..];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let memo = reader.read_string()?;
+    let count = reader.read_u32()? as usize;
+    if count == 0 || count > MAX_BATCH_RECIPIENTS {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let entries = reader.read_slice(count * BATCH_ENTRY_SIZE)?;
+    reader.finish()?;
+
+    // ── Input validation (every entry, before any CPI) ──────────────────
+    let mut total: u64 = 0;
+    for i in 0..count {
+        let (recipient_index, amount) = parse_entry(entries, i)?;
+        if amount == 0 {
+            return Err(zupy_err!(ZeroAmount, "entry_amount"));
+        }
+        if recipient_index >= recipients.len() {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        total = checked_add_amount(total, amount)?;
+    }
+    validate_memo_op(memo, MemoOp::PoolTransfer)?;
+
+    // ── Common transfer validation (9 checks, Spec §7.1-§7.8) ───────────
+    let validation = validate_transfer_common(
+        program_id,
+        token_state_account,
+        transfer_authority,
+        mint,
+        token_program,
+    )?;
+
+    // ── Additional signer check: fee_payer ──────────────────────────────
+    if !fee_payer.is_signer() {
+        return Err(zupy_err!(FeePayerNotSigner, "fee_payer"));
+    }
+
+    // ── Verify compressed_token_program is the Light cToken program ──────
+    let expected_ctoken: Address = LIGHT_COMPRESSED_TOKEN_PROGRAM_ID.into();
+    if compressed_token_prog.address() != &expected_ctoken {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // ── Verify cpi_authority_pda is the canonical cToken CPI PDA ─────────
+    let expected_ctoken_auth = Address::from(LIGHT_TOKEN_CPI_AUTHORITY);
+    if cpi_authority_pda.address() != &expected_ctoken_auth {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // ── Pool ATA validation ─────────────────────────────────────────────
+    let state = validation.state;
+    if pool_ata.address().as_ref() != state.pool_ata() {
+        return Err(zupy_err!(InvalidPoolAccount, "pool_ata"));
+    }
+    let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
+    if !pool_ata.owned_by(&token_2022_addr) {
+        return Err(zupy_err!(InvalidPoolAccount, "pool_ata_owner"));
+    }
+
+    // ── Balance check (summed amount, up front) ─────────────────────────
+    let pool_balance = read_token_balance(pool_ata);
+    if pool_balance < total {
+        return Err(zupy_err!(InsufficientPoolBalance, "pool_balance"));
+    }
+
+    // ── Validate spl_interface_pda address ──────────────────────────────
+    let mint_key: [u8; 32] = mint.address().as_ref().try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    let (expected_spl_pda, _) = derive_spl_interface_pda(&mint_key);
+    validate_pda(spl_interface_pda.address(), &expected_spl_pda)?;
+
+    // ── CPI loop: one compress per entry ────────────────────────────────
+    // token_state PDA signs with [TOKEN_STATE_SEED, &[bump]]
+    let bump_bytes = [validation.bump];
+    let mut remaining_amount = pool_balance;
+    for i in 0..count {
+        let (recipient_index, amount) = parse_entry(entries, i)?;
+        let owner: &[u8; 32] = recipients[recipient_index].address().as_ref().try_into()
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        // Cannot underflow: total <= pool_balance was checked above
+        remaining_amount = checked_sub_amount(remaining_amount, amount)?;
+
+        let signer_seeds: [Seed; 2] = [
+            Seed::from(TOKEN_STATE_SEED),
+            Seed::from(bump_bytes.as_ref()),
+        ];
+        let signer = Signer::from(&signer_seeds);
+
+        cpi_compress_from_spl(
+            compressed_token_prog,
+            cpi_authority_pda,
+            light_system_program,
+            registered_program_pda,
+            noop_program,
+            account_compression_authority,
+            account_compression_program,
+            fee_payer,
+            token_state_account,        // authority: token_state PDA that owns pool_ata
+            spl_interface_pda,          // token_pool_pda
+            pool_ata,                   // source_ata
+            token_program,
+            system_program,
+            owner,
+            Some(remaining_amount),
+            output_queue,               // remaining: Merkle tree output queue
+            &[signer],
+        )?;
+    }
+
+    // ── Heartbeat: last_transfer_ts (v2, writable token_state only) ─────
+    stamp_last_op(token_state_account, LastOp::Transfer)?;
+
+    Ok(())
+}
+
+/// Parse the `i`-th batch entry: (recipient_index, amount).
+#[inline(always)]
+fn parse_entry(entries: &[u8], i: usize) -> Result<(usize, u64), ProgramError> {
+    let offset = i * BATCH_ENTRY_SIZE;
+    let recipient_index = parse_u8(entries, offset)? as usize;
+    let amount = parse_u64(entries, offset + 1)?;
+    Ok((recipient_index, amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify account count check: at least 16 accounts required.
+    #[test]
+    fn test_process_returns_not_enough_account_keys() {
+        let program_id = Address::default();
+        let data = [0u8; 16];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+
+    #[test]
+    fn test_parse_entry_reads_index_and_amount() {
+        let mut data = Vec::new();
+        data.push(3);
+        data.extend_from_slice(&500u64.to_le_bytes());
+        data.push(7);
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+        assert_eq!(parse_entry(&data, 0).unwrap(), (3, 500));
+        assert_eq!(parse_entry(&data, 1).unwrap(), (7, 1_000));
+    }
+
+    #[test]
+    fn test_parse_entry_truncated() {
+        let mut data = vec![2u8];
+        data.extend_from_slice(&[0u8; 7]); // one byte short of a full entry
+        assert_eq!(parse_entry(&data, 0), Err(ProgramError::InvalidInstructionData));
+    }
+}
@@ -0,0 +1,109 @@
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::{BUBBLEGUM_PROGRAM_ID, SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use crate::error::ZupyTokenError;
+use crate::helpers::cpi::{cpi_bubblegum_burn, CnftLeaf};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::transfer_validation::validate_nft_payer;
+
+/// Process `burn_coupon_cnft` instruction.
+///
+/// Burns a compressed coupon NFT (e.g. once redeemed) via manual Bubblegum
+/// Burn CPI. The leaf fields and proof come from the DAS API; Bubblegum
+/// verifies them against the tree. leaf_owner or leaf_delegate must sign the
+/// transaction — their signer flags are forwarded as-is, this program signs
+/// nothing. mint_authority must sign and the system must not be paused.
+///
+/// Accounts (10 + proof):
+///   0. mint_authority (signer) — must match token_state.mint_authority()
+///   1. tree_config (read)
+///   2. leaf_owner (read, signer unless leaf_delegate signs)
+///   3. leaf_delegate (read, signer unless leaf_owner signs)
+///   4. merkle_tree (writable)
+///   5. bubblegum_program (read)
+///   6. compression_program (read)
+///   7. log_wrapper (read)
+///   8. system_program (read)
+///   9. token_state (read) — PDA [TOKEN_STATE_SEED], Audit 12.1
+///  10.. proof nodes (read) — merkle proof minus the canopy
+///
+/// Data: root ([u8; 32]) + data_hash ([u8; 32]) + creator_hash ([u8; 32])
+///       + nonce (u64) + index (u32)
+/// Discriminator: [202, 73, 131, 140, 182, 100, 104, 77] (SHA256("global:burn_coupon_cnft"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (10 accounts + proof) ────────────────────────
+    if accounts.len() < 10 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let mint_authority = &accounts[0];
+    let tree_config = &accounts[1];
+    let leaf_owner = &accounts[2];
+    let leaf_delegate = &accounts[3];
+    let merkle_tree = &accounts[4];
+    let bubblegum_program = &accounts[5];
+    let compression_program = &accounts[6];
+    let log_wrapper = &accounts[7];
+    let system_program = &accounts[8];
+    let token_state_account = &accounts[9];
+    let proof = &accounts[10..];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let leaf = CnftLeaf::parse(&mut reader)?;
+    reader.finish()?;
+
+    // ── Authority (signer + token_state + mint_authority), not paused ───
+    let state = validate_nft_payer(program_id, mint_authority, token_state_account)?;
+    if state.paused() {
+        return Err(ZupyTokenError::SystemPaused.into());
+    }
+
+    // ── Hardcoded program ID checks ─────────────────────────────────────
+    let expected_bubblegum = Address::from(BUBBLEGUM_PROGRAM_ID);
+    if bubblegum_program.address() != &expected_bubblegum {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    let expected_compression = Address::from(SPL_ACCOUNT_COMPRESSION_ID);
+    if compression_program.address() != &expected_compression {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    let expected_noop = Address::from(SPL_NOOP_ID);
+    if log_wrapper.address() != &expected_noop {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    // ── CPI: Bubblegum Burn (leaf owner / delegate signed the tx) ───────
+    cpi_bubblegum_burn(
+        tree_config,
+        leaf_owner,
+        leaf_delegate,
+        merkle_tree,
+        log_wrapper,
+        compression_program,
+        system_program,
+        bubblegum_program,
+        proof,
+        &leaf,
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burn_coupon_cnft_not_enough_account_keys() {
+        let program_id = Address::default();
+        let result = process(&program_id, &[], &[0u8; 108]);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+}
@@ -3,12 +3,16 @@ use pinocchio::{AccountView, Address, ProgramResult};
 use pinocchio::error::ProgramError;
 
 use crate::constants::{COMPANY_SEED, LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, SYSTEM_PROGRAM_ID};
-use crate::error::ZupyTokenError;
+use crate::error::zupy_err;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::company_counters::{company_counters, split_company_state};
 use crate::helpers::compressed_accounts::cpi_compressed_burn;
-use crate::helpers::instruction_data::{parse_string, parse_u64};
-use crate::helpers::memo::validate_memo_format;
+use crate::helpers::events::{emit_anchor_event, emit_supply_event, BurnEvent, SupplyEvent};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::memo::{validate_memo_op_in, MemoOp};
 use crate::helpers::pda::{derive_company_pda, validate_pda};
 use crate::helpers::transfer_validation::validate_transfer_common_compressed;
+use crate::state::token_state::TokenStateMut;
 
 /// Process `burn_from_company_pda` instruction (compressed version).
 ///
@@ -17,13 +21,15 @@ use crate::helpers::transfer_validation::validate_transfer_common_compressed;
 ///
 /// Accounts (7 minimum):
 ///   0. transfer_authority        (signer)           — must match TRANSFER_AUTHORITY_PUBKEY
-///   1. token_state               (read)             — our program's token_state PDA
+///   1. token_state               (read)             — our program's token_state PDA (writable: `total_burned`)
 ///   2. mint                      (writable)         — ZUPY Token-2022 mint (supply decrement)
 ///   3. company_pda               (read)             — compressed source + CPI authority
 ///   4. fee_payer                 (writable, signer) — pays Light Protocol rent/fees
 ///   5. system_program            (read)             — System program
 ///   6. compressed_token_program  (read)             — Light cToken program
 ///   7+ Light system accounts                        — Merkle tree, nullifier queue, noop (client-injected)
+///   last: company_state          (writable, optional) — CompanyState PDA; `total_burned`,
+///         stripped before the Light accounts are forwarded (see `helpers::company_counters`)
 ///
 /// Data: company_id_u64 (0-7) + amount (8-15) + memo (16+)
 pub fn process(
@@ -31,6 +37,9 @@ pub fn process(
     accounts: &[AccountView],
     data: &[u8],
 ) -> ProgramResult {
+    // ── Optional trailing CompanyState (balance counters) ───────────────
+    let (accounts, company_state) = split_company_state(program_id, accounts);
+
     // ── Account extraction (7 accounts minimum) ─────────────────────────
     if accounts.len() < 7 {
         return Err(ProgramError::NotEnoughAccountKeys);
@@ -44,15 +53,17 @@ pub fn process(
     let compressed_token_prog = &accounts[6];
 
     // ── Parse instruction data ──────────────────────────────────────────
-    let company_id_u64 = parse_u64(data, 0)?;
-    let amount = parse_u64(data, 8)?;
-    let (memo, _) = parse_string(data, 16)?;
+    let mut reader = Reader::new(data);
+    let company_id_u64 = reader.read_u64()?;
+    let amount = reader.read_u64()?;
+    let memo = reader.read_string()?;
+    reader.finish()?;
 
     // ── Input validation ────────────────────────────────────────────────
     if amount == 0 {
-        return Err(ZupyTokenError::ZeroAmount.into());
+        return Err(zupy_err!(ZeroAmount, "amount"));
     }
-    validate_memo_format(memo)?;
+    validate_memo_op_in(memo, &[MemoOp::Burn, MemoOp::BurnCompany])?;
 
     // ── Common transfer validation (8 checks — compressed variant, no token_program) ─
     // Checks 1–8 from validate_transfer_common; check 9 (token_program == Token-2022)
@@ -66,7 +77,7 @@ pub fn process(
 
     // ── Additional signer check: fee_payer ──────────────────────────────
     if !fee_payer.is_signer() {
-        return Err(ZupyTokenError::InvalidAuthority.into());
+        return Err(zupy_err!(FeePayerNotSigner, "fee_payer"));
     }
 
     // ── Verify system_program is the System Program ──────────────────────
@@ -86,6 +97,10 @@ pub fn process(
     let (expected_company_pda, company_bump) = derive_company_pda(program_id, company_id_u64);
     validate_pda(company_pda.address(), &expected_company_pda)?;
 
+    // ── Event log (all checks passed) ───────────────────────────────────
+    emit_supply_event(SupplyEvent::Burn, amount, memo);
+    emit_anchor_event(&BurnEvent { source: *address_bytes(company_pda.address()), amount });
+
     // ── CPI: Burn company compressed balance via Light Protocol ──────────
     // company_pda signs with 3-seed pattern (COMPANY_SEED + company_id + bump)
     let bump_bytes = [company_bump];
@@ -107,6 +122,14 @@ pub fn process(
         &[signer],
     )?;
 
+    // ── Record burn AFTER successful CPI (saturating; skipped if read-only)
+    if let Some(mut state_mut) = TokenStateMut::if_writable(token_state_account) {
+        state_mut.record_burn(amount);
+    }
+    if let Some(mut counters) = company_counters(company_state, company_id_u64) {
+        counters.record_burned(amount);
+    }
+
     Ok(())
 }
 
@@ -0,0 +1,140 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::{TOKEN_2022_PROGRAM_ID, TOKEN_STATE_SEED};
+use crate::error::zupy_err;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::cpi::cpi_burn;
+use crate::helpers::events::{emit_anchor_event, emit_supply_event, BurnEvent, SupplyEvent};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::memo::{validate_memo_op, MemoOp};
+use crate::helpers::transfer_validation::{
+    read_token_balance, validate_source_ata, validate_token_state_base,
+};
+use crate::state::token_state::TokenStateMut;
+
+/// Process `burn_pool_tokens` instruction.
+///
+/// Burns unsold inventory straight out of the SPL-side pool_ata, signed by the
+/// token_state PDA (pool_ata's token authority), instead of routing it back
+/// through treasury_ata first. Counts towards `total_burned`.
+///
+/// Unlike `burn_tokens`, this respects the paused flag.
+///
+/// Accounts (5):
+///   0. treasury (signer)          — must be token_state.treasury()
+///   1. token_state (writable)     — PDA [TOKEN_STATE_SEED], burn authority + `total_burned`
+///   2. mint (writable)            — ZUPY Token-2022 mint (supply decrement)
+///   3. pool_ata (writable)        — must match token_state.pool_ata()
+///   4. token_program (read)       — Token-2022
+///
+/// Data: amount (u64) + memo (String)
+/// Discriminator: `[189, 143, 81, 31, 173, 28, 110, 37]` (SHA256("global:burn_pool_tokens"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (5 accounts) ─────────────────────────────────
+    if accounts.len() < 5 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let treasury = &accounts[0];
+    let token_state_account = &accounts[1];
+    let mint = &accounts[2];
+    let pool_ata = &accounts[3];
+    let token_program = &accounts[4];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let amount = reader.read_u64()?;
+    let memo = reader.read_string()?;
+    reader.finish()?;
+
+    // ── Input validation ────────────────────────────────────────────────
+    if amount == 0 {
+        return Err(zupy_err!(ZeroAmount, "amount"));
+    }
+    validate_memo_op(memo, MemoOp::Burn)?;
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+    let bump = state.bump();
+
+    // ── Paused check ────────────────────────────────────────────────────
+    if state.paused() {
+        return Err(zupy_err!(SystemPaused, "paused"));
+    }
+
+    // ── Treasury authorization ──────────────────────────────────────────
+    if !treasury.is_signer() {
+        return Err(zupy_err!(InvalidAuthority, "treasury_signer"));
+    }
+    let treasury_key: &[u8; 32] = address_bytes(treasury.address());
+    if !state.is_treasury(treasury_key) {
+        return Err(zupy_err!(UnauthorizedTreasury, "treasury"));
+    }
+
+    // ── Mint validation ─────────────────────────────────────────────────
+    let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
+    if !mint.owned_by(&token_2022_addr) || state.mint() != mint.address().as_ref() {
+        return Err(zupy_err!(InvalidMint, "mint"));
+    }
+
+    // ── Pool ATA validation ─────────────────────────────────────────────
+    if state.pool_ata() != pool_ata.address().as_ref() {
+        return Err(zupy_err!(InvalidPoolAccount, "pool_ata"));
+    }
+    validate_source_ata(pool_ata, mint.address(), token_state_account.address())?;
+
+    // ── Token program check ─────────────────────────────────────────────
+    if token_program.address() != &token_2022_addr {
+        return Err(zupy_err!(InvalidTokenProgram, "token_program"));
+    }
+
+    // ── Balance check ───────────────────────────────────────────────────
+    if read_token_balance(pool_ata) < amount {
+        return Err(zupy_err!(InsufficientBalance, "pool_balance"));
+    }
+
+    // ── Event log (all checks passed) ───────────────────────────────────
+    emit_supply_event(SupplyEvent::Burn, amount, memo);
+    emit_anchor_event(&BurnEvent { source: *address_bytes(pool_ata.address()), amount });
+
+    // ── CPI: Token-2022 Burn (token_state PDA signs) ────────────────────
+    let bump_bytes = [bump];
+    let signer_seeds: [Seed; 2] = [
+        Seed::from(TOKEN_STATE_SEED),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    let signer = Signer::from(&signer_seeds);
+
+    cpi_burn(
+        pool_ata,
+        mint,
+        token_state_account,
+        amount,
+        token_program.address(),
+        &[signer],
+    )?;
+
+    // ── Record burn AFTER successful CPI (saturating) ──────────────────
+    TokenStateMut::from_slice(unsafe { token_state_account.borrow_unchecked_mut() })
+        .record_burn(amount);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burn_pool_tokens_not_enough_account_keys() {
+        let program_id = Address::default();
+        let data = [0u8; 32];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+}
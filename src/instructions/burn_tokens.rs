@@ -2,14 +2,16 @@ use pinocchio::{AccountView, Address, ProgramResult};
 use pinocchio::error::ProgramError;
 
 use crate::constants::TOKEN_2022_PROGRAM_ID;
-use crate::error::ZupyTokenError;
+use crate::error::zupy_err;
+use crate::helpers::bytes::address_bytes;
 use crate::helpers::cpi::cpi_burn_invoke;
-use crate::helpers::instruction_data::{parse_string, parse_u64};
-use crate::helpers::memo::validate_memo_format;
+use crate::helpers::events::{emit_anchor_event, emit_supply_event, BurnEvent, SupplyEvent};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::memo::{validate_memo_op, MemoOp};
 use crate::helpers::transfer_validation::{
     read_token_balance, read_token_mint, validate_token_state_base,
 };
-use crate::state::token_state::TokenState;
+use crate::state::token_state::TokenStateMut;
 
 /// Process `burn_tokens` instruction.
 ///
@@ -20,7 +22,7 @@ use crate::state::token_state::TokenState;
 ///
 /// Accounts (6):
 ///   0. authority (signer) — must be treasury
-///   1. token_state (read)
+///   1. token_state (read; writable to advance `total_burned`)
 ///   2. mint (writable)
 ///   3. token_account (writable)
 ///   4. token_account_owner (signer)
@@ -44,65 +46,67 @@ pub fn process(
     let token_program = &accounts[5];
 
     // ── Parse instruction data ──────────────────────────────────────────
-    let amount = parse_u64(data, 0)?;
-    let (memo, _) = parse_string(data, 8)?;
+    let mut reader = Reader::new(data);
+    let amount = reader.read_u64()?;
+    let memo = reader.read_string()?;
+    reader.finish()?;
 
     // ── Input validation ────────────────────────────────────────────────
     if amount == 0 {
-        return Err(ZupyTokenError::ZeroAmount.into());
+        return Err(zupy_err!(ZeroAmount, "amount"));
     }
-    validate_memo_format(memo)?;
+    validate_memo_op(memo, MemoOp::Burn)?;
 
     // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
-    validate_token_state_base(program_id, token_state_account)?;
-    // NOTE: burn_tokens does NOT check paused (intentional)
-
-    // Zero-copy read for authority checks
-    let state = TokenState::from_slice(unsafe { token_state_account.borrow_unchecked() });
+    let state = validate_token_state_base(program_id, token_state_account)?;
 
     // ── Signer checks (Spec §7.3) ───────────────────────────────────────
     if !authority.is_signer() {
-        return Err(ZupyTokenError::InvalidAuthority.into());
+        return Err(zupy_err!(InvalidAuthority, "authority_signer"));
     }
     // authority must be treasury
     if state.treasury() != authority.address().as_ref() {
-        return Err(ZupyTokenError::InvalidAuthority.into());
+        return Err(zupy_err!(InvalidAuthority, "treasury"));
     }
     // token_account_owner must be signer (holder authorization)
     if !token_account_owner.is_signer() {
-        return Err(ZupyTokenError::InvalidAuthority.into());
+        return Err(zupy_err!(InvalidAuthority, "holder_signer"));
     }
 
     // ── Mint ownership (Spec §7.1) + match ──────────────────────────────
     let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
     if !mint.owned_by(&token_2022_addr) {
-        return Err(ZupyTokenError::InvalidMint.into());
+        return Err(zupy_err!(InvalidMint, "mint_owner"));
     }
     if state.mint() != mint.address().as_ref() {
-        return Err(ZupyTokenError::InvalidMint.into());
+        return Err(zupy_err!(InvalidMint, "mint"));
     }
 
     // ── token_account ownership (Spec §7.1) ─────────────────────────────
     if !token_account.owned_by(&token_2022_addr) {
-        return Err(ZupyTokenError::InvalidAuthority.into());
+        return Err(zupy_err!(AccountNotTokenOwned, "token_account_owner"));
     }
 
     // ── token_account mint check ────────────────────────────────────────
     if read_token_mint(token_account) != mint.address().as_ref() {
-        return Err(ZupyTokenError::InvalidMint.into());
+        return Err(zupy_err!(InvalidMint, "token_account_mint"));
     }
 
     // ── CPI safety: token_program is Token-2022 (Spec §7.8) ────────────
     if token_program.address() != &token_2022_addr {
-        return Err(ZupyTokenError::InvalidTokenProgram.into());
+        return Err(zupy_err!(InvalidTokenProgram, "token_program"));
     }
 
     // ── Balance check ───────────────────────────────────────────────────
     let balance = read_token_balance(token_account);
     if balance < amount {
-        return Err(ZupyTokenError::InsufficientBalance.into());
+        return Err(zupy_err!(InsufficientBalance, "balance"));
     }
 
+    // ── Event log (all checks passed) ───────────────────────────────────
+    emit_supply_event(SupplyEvent::Burn, amount, memo);
+    emit_anchor_event(&BurnEvent { source: *address_bytes(token_account.address()), amount });
+
     // ── CPI: Token-2022 Burn via regular invoke ─────────────────────────
     cpi_burn_invoke(
         token_account,
@@ -112,5 +116,10 @@ pub fn process(
         token_program.address(),
     )?;
 
+    // ── Record burn AFTER successful CPI (saturating; skipped if read-only)
+    if let Some(mut state_mut) = TokenStateMut::if_writable(token_state_account) {
+        state_mut.record_burn(amount);
+    }
+
     Ok(())
 }
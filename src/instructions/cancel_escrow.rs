@@ -0,0 +1,212 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::{AccountView, Address, ProgramResult};
+use pinocchio::error::ProgramError;
+
+use crate::constants::{
+    COMPANY_SEED, ESCROW_SEED, LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, LIGHT_TOKEN_CPI_AUTHORITY,
+    TOKEN_2022_PROGRAM_ID,
+};
+use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::compressed_accounts::{cpi_decompress_to_spl, derive_spl_interface_pda};
+use crate::helpers::escrow_common::{release_escrow_compressed, validate_pending_escrow};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::memo::{validate_memo_op, MemoOp};
+use crate::helpers::pda::{validate_pda, validate_pda_with_seeds};
+use crate::helpers::transfer_validation::validate_token_state_base;
+use crate::state::escrow_state::{
+    EscrowState, EscrowStateMut, ESCROW_SOURCE_POOL, ESCROW_STATUS_CANCELLED,
+};
+
+/// Process `cancel_escrow` instruction.
+///
+/// Returns a pending escrow to its source: the transfer authority may cancel
+/// once `expiry` has passed (`EscrowNotExpired` before), the treasury at any time.
+///
+/// - Company source: Path B compressed transfer back to the company PDA.
+/// - Pool source: Path A reverse decompress into pool_ata.
+/// Both are signed by the escrow PDA.
+///
+/// Accounts (8 for company source; 11 minimum plus Light system accounts for pool source):
+///   0.  authority                  (signer)           — transfer_authority or treasury
+///   1.  token_state                (read)
+///   2.  mint                       (read)
+///   3.  escrow                     (writable)         — PDA [ESCROW_SEED, escrow_id]
+///   4.  destination                (writable)         — company PDA, or pool_ata for pool source
+///   5.  fee_payer                  (writable, signer)
+///   6.  system_program             (read)
+///   7.  compressed_token_program   (read)             — Light cToken program
+///   Pool source only:
+///   8.  token_program              (read)             — Token-2022
+///   9.  compressed_token_authority (read)             — LIGHT_TOKEN_CPI_AUTHORITY
+///   10. spl_interface_pda          (writable)         — seeds=[b"pool", mint]
+///   11+ Light system accounts
+///
+/// Data: escrow_id (u64) + company_bump (u8, ignored for pool source)
+///       + memo (String, `zupy:v1:escrow_cancel:{id}`)
+/// Discriminator: `[156, 203, 54, 179, 38, 72, 33, 21]` (SHA256("global:cancel_escrow"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (8 accounts minimum) ─────────────────────────
+    if accounts.len() < 8 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let mint = &accounts[2];
+    let escrow_account = &accounts[3];
+    let destination = &accounts[4];
+    let fee_payer = &accounts[5];
+    let system_program = &accounts[6];
+    let compressed_token_prog = &accounts[7];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let escrow_id = reader.read_u64()?;
+    let company_bump = reader.read_u8()?;
+    let memo = reader.read_string()?;
+    reader.finish()?;
+
+    // ── Input validation ────────────────────────────────────────────────
+    validate_memo_op(memo, MemoOp::EscrowCancel)?;
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+    if state.paused() {
+        return Err(ZupyTokenError::SystemPaused.into());
+    }
+
+    // ── Mint validation ─────────────────────────────────────────────────
+    let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
+    if !mint.owned_by(&token_2022_addr) || state.mint() != mint.address().as_ref() {
+        return Err(ZupyTokenError::InvalidMint.into());
+    }
+
+    // ── Verify compressed_token_program is the Light cToken program ──────
+    let expected_ctoken: Address = LIGHT_COMPRESSED_TOKEN_PROGRAM_ID.into();
+    if compressed_token_prog.address() != &expected_ctoken {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // ── Escrow validation: PDA, pending ─────────────────────────────────
+    validate_pending_escrow(program_id, escrow_account, escrow_id)?;
+    let escrow = EscrowState::from_slice(unsafe { escrow_account.borrow_unchecked() });
+
+    // ── Authorization: treasury anytime, transfer_authority after expiry ─
+    if !authority.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    if !fee_payer.is_signer() {
+        return Err(ZupyTokenError::FeePayerNotSigner.into());
+    }
+    let authority_key: &[u8; 32] = address_bytes(authority.address());
+    if !state.is_treasury(authority_key) {
+        if state.transfer_authority() != authority.address().as_ref() {
+            return Err(ZupyTokenError::InvalidAuthority.into());
+        }
+        use pinocchio::sysvars::Sysvar as _;
+        let clock = pinocchio::sysvars::clock::Clock::get()?;
+        if clock.unix_timestamp < escrow.expiry() {
+            return Err(ZupyTokenError::EscrowNotExpired.into());
+        }
+    }
+
+    let amount = escrow.amount();
+    let escrow_bump = escrow.bump();
+    let source_kind = escrow.source_kind();
+    let source_id = escrow.source_id();
+
+    // ── Mark cancelled, then return to source (CPI failure reverts) ─────
+    EscrowStateMut::from_slice(unsafe { escrow_account.borrow_unchecked_mut() })
+        .set_status(ESCROW_STATUS_CANCELLED);
+
+    if source_kind == ESCROW_SOURCE_POOL {
+        if accounts.len() < 11 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let token_program = &accounts[8];
+        let compressed_token_auth = &accounts[9];
+        let spl_interface_pda = &accounts[10];
+
+        if token_program.address() != &token_2022_addr {
+            return Err(ZupyTokenError::InvalidTokenProgram.into());
+        }
+        let expected_ctoken_auth = Address::from(LIGHT_TOKEN_CPI_AUTHORITY);
+        if compressed_token_auth.address() != &expected_ctoken_auth {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // Pool ATA validation
+        if destination.address().as_ref() != state.pool_ata()
+            || !destination.owned_by(&token_2022_addr)
+        {
+            return Err(ZupyTokenError::InvalidPoolAccount.into());
+        }
+
+        let mint_key: [u8; 32] = mint.address().as_ref().try_into()
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        let (expected_spl_pda, spl_bump) = derive_spl_interface_pda(&mint_key);
+        validate_pda(spl_interface_pda.address(), &expected_spl_pda)?;
+
+        let escrow_id_bytes = escrow_id.to_le_bytes();
+        let bump_bytes = [escrow_bump];
+        let signer_seeds: [Seed; 3] = [
+            Seed::from(ESCROW_SEED),
+            Seed::from(escrow_id_bytes.as_ref()),
+            Seed::from(bump_bytes.as_ref()),
+        ];
+        let signer = Signer::from(&signer_seeds);
+
+        cpi_decompress_to_spl(
+            compressed_token_prog,
+            compressed_token_auth,
+            fee_payer,
+            mint,
+            destination,    // pool_ata
+            escrow_account, // authority: escrow PDA
+            spl_interface_pda,
+            token_program,
+            system_program,
+            amount,
+            spl_bump,
+            state.decimals(),
+            &accounts[11..],
+            &[signer],
+        )
+    } else {
+        // Company PDA validation (stored source_id, client-provided bump)
+        let source_id_bytes = source_id.to_le_bytes();
+        validate_pda_with_seeds(
+            destination.address(),
+            &[COMPANY_SEED, &source_id_bytes, &[company_bump]],
+            program_id,
+        )?;
+
+        release_escrow_compressed(
+            compressed_token_prog,
+            fee_payer,
+            escrow_account,
+            destination,
+            system_program,
+            escrow_id,
+            escrow_bump,
+            amount,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_escrow_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 13];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
@@ -0,0 +1,74 @@
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::transfer_validation::validate_token_state_base;
+use crate::helpers::withdrawal_request::{close_withdrawal_request, validate_withdrawal_request};
+
+/// Process `cancel_withdrawal` instruction.
+///
+/// Drops a pending two-phase withdrawal: closes the WithdrawalRequestState PDA
+/// and refunds its rent to the original payer. No tokens move, so this also
+/// works while the system is paused. Either the transfer authority or the
+/// treasury can cancel.
+///
+/// Accounts (4):
+///   0. authority (signer)            — transfer_authority or treasury
+///   1. token_state (read)            — PDA [TOKEN_STATE_SEED]
+///   2. withdrawal_request (writable) — PDA [WITHDRAWAL_REQUEST_SEED, request_id]
+///   3. payer (writable)              — must match request.payer(); receives the rent
+///
+/// Data: request_id (u64)
+/// Discriminator: `[183, 104, 181, 250, 28, 128, 210, 70]` (SHA256("global:cancel_withdrawal"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (4 accounts) ─────────────────────────────────
+    if accounts.len() < 4 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let request_account = &accounts[2];
+    let payer = &accounts[3];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let request_id = reader.read_u64()?;
+    reader.finish()?;
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+
+    // ── Authorization: transfer_authority or treasury ───────────────────
+    if !authority.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    let authority_key: &[u8; 32] = address_bytes(authority.address());
+    if !state.is_treasury(authority_key) && state.transfer_authority() != authority_key {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+
+    // ── Request validation: owner, size, discriminator, PDA, payer ──────
+    validate_withdrawal_request(program_id, request_account, request_id, payer)?;
+
+    // ── Close: move rent to payer, then release the account ─────────────
+    close_withdrawal_request(request_account, payer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_withdrawal_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 8];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
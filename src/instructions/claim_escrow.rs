@@ -0,0 +1,121 @@
+use pinocchio::{AccountView, Address, ProgramResult};
+use pinocchio::error::ProgramError;
+
+use crate::constants::{LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, USER_SEED};
+use crate::error::ZupyTokenError;
+use crate::helpers::escrow_common::{release_escrow_compressed, validate_pending_escrow};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::memo::{validate_memo_op, MemoOp};
+use crate::helpers::pda::validate_pda_with_seeds;
+use crate::helpers::transfer_validation::validate_transfer_common_compressed;
+use crate::state::escrow_state::{EscrowState, EscrowStateMut, ESCROW_STATUS_CLAIMED};
+
+/// Process `claim_escrow` instruction.
+///
+/// Releases a pending escrow to its recipient's user PDA (Path B compressed
+/// transfer, signed by the escrow PDA). Fails with `EscrowExpired` at or after
+/// `expiry` and with `EscrowAlreadySettled` if already claimed or cancelled.
+///
+/// Accounts (8):
+///   0. transfer_authority (signer)
+///   1. token_state (read)
+///   2. mint (read)
+///   3. escrow (writable)               — PDA [ESCROW_SEED, escrow_id]
+///   4. user_pda (read)                 — PDA [USER_SEED, escrow.recipient_user_id]
+///   5. fee_payer (writable, signer)    — pays Light state tree fees
+///   6. system_program (read)
+///   7. compressed_token_program (read) — cTokenmWW8bLPjZEBAUgYy3zKxQZW6VKi7bqNFEVv3m
+///
+/// Data: escrow_id (u64) + user_bump (u8) + memo (String, `zupy:v1:escrow_claim:{id}`)
+/// Discriminator: `[200, 80, 182, 159, 61, 75, 9, 205]` (SHA256("global:claim_escrow"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (8 accounts) ─────────────────────────────────
+    if accounts.len() < 8 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let transfer_authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let mint = &accounts[2];
+    let escrow_account = &accounts[3];
+    let user_pda = &accounts[4];
+    let fee_payer = &accounts[5];
+    let system_program = &accounts[6];
+    let compressed_token_program = &accounts[7];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let escrow_id = reader.read_u64()?;
+    let user_bump = reader.read_u8()?;
+    let memo = reader.read_string()?;
+    reader.finish()?;
+
+    // ── Input validation ────────────────────────────────────────────────
+    validate_memo_op(memo, MemoOp::EscrowClaim)?;
+
+    // ── Common transfer validation (checks 1–8) ─────────────────────────
+    validate_transfer_common_compressed(
+        program_id,
+        token_state_account,
+        transfer_authority,
+        mint,
+    )?;
+
+    // ── Check 9: compressed_token_program is Light cToken program ───────
+    let light_ctoken_addr = Address::from(LIGHT_COMPRESSED_TOKEN_PROGRAM_ID);
+    if compressed_token_program.address() != &light_ctoken_addr {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    // ── Escrow validation: PDA, pending, not expired ────────────────────
+    validate_pending_escrow(program_id, escrow_account, escrow_id)?;
+    let escrow = EscrowState::from_slice(unsafe { escrow_account.borrow_unchecked() });
+
+    use pinocchio::sysvars::Sysvar as _;
+    let clock = pinocchio::sysvars::clock::Clock::get()?;
+    if clock.unix_timestamp >= escrow.expiry() {
+        return Err(ZupyTokenError::EscrowExpired.into());
+    }
+
+    // ── PDA validation: recipient user_pda ──────────────────────────────
+    let user_id_bytes = escrow.recipient_user_id().to_le_bytes();
+    validate_pda_with_seeds(
+        user_pda.address(),
+        &[USER_SEED, &user_id_bytes, &[user_bump]],
+        program_id,
+    )?;
+
+    let amount = escrow.amount();
+    let escrow_bump = escrow.bump();
+
+    // ── Mark claimed, then release (CPI failure reverts the status) ─────
+    EscrowStateMut::from_slice(unsafe { escrow_account.borrow_unchecked_mut() })
+        .set_status(ESCROW_STATUS_CLAIMED);
+
+    release_escrow_compressed(
+        compressed_token_program,
+        fee_payer,
+        escrow_account,
+        user_pda,
+        system_program,
+        escrow_id,
+        escrow_bump,
+        amount,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claim_escrow_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 13];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
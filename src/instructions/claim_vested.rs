@@ -0,0 +1,273 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::{AccountView, Address, ProgramResult};
+use pinocchio::error::ProgramError;
+
+use crate::constants::{
+    LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, LIGHT_TOKEN_CPI_AUTHORITY, TOKEN_2022_PROGRAM_ID,
+    TOKEN_STATE_SEED, USER_SEED, VESTING_SEED,
+};
+use crate::error::ZupyTokenError;
+use crate::helpers::account_checks::assert_discriminator;
+use crate::helpers::compressed_accounts::{cpi_compress_from_spl, derive_spl_interface_pda};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::math::checked_sub_amount;
+use crate::helpers::memo::{validate_memo_op, MemoOp};
+use crate::helpers::pda::{validate_pda, validate_pda_with_seeds};
+use crate::helpers::transfer_validation::{read_token_balance, validate_transfer_common};
+use crate::state::vesting_state::{
+    VestingState, VestingStateMut, VESTING_STATE_DISCRIMINATOR, VESTING_STATE_SIZE,
+};
+
+/// Process `claim_vested` instruction (compressed token version).
+///
+/// Releases the unlocked-but-unclaimed part of a vesting grant from the pool
+/// ATA to the beneficiary's user PDA (Path A compress, same as
+/// `transfer_from_pool`), then advances `claimed`. The unlocked amount is
+/// linear in `Clock::unix_timestamp` between `start_ts` and `start_ts + duration`.
+///
+/// Accounts (17 minimum, plus ≥1 remaining Merkle tree accounts):
+///   0.  transfer_authority         (signer)           — must match TRANSFER_AUTHORITY_PUBKEY
+///   1.  token_state                (read)             — our program's token_state PDA
+///   2.  mint                       (read)             — ZUPY Token-2022 mint
+///   3.  pool_ata                   (writable)         — distribution pool ATA (source)
+///   4.  user_pda                   (read)             — PDA [USER_SEED, user_id], compressed owner
+///   5.  fee_payer                  (writable, signer) — pays Light Protocol rent/fees
+///   6.  token_program              (read)             — Token-2022 program
+///   7.  system_program             (read)             — System program
+///   8.  compressed_token_program   (read)             — Light cToken program
+///   9.  cpi_authority_pda          (read)             — LIGHT_TOKEN_CPI_AUTHORITY
+///   10. light_system_program       (read)             — LIGHT_SYSTEM_PROGRAM_ID
+///   11. registered_program_pda     (read)             — REGISTERED_PROGRAM_PDA
+///   12. noop_program               (read)             — SPL_NOOP_ID
+///   13. account_compression_authority (read)          — ACCOUNT_COMPRESSION_AUTHORITY
+///   14. account_compression_program  (read)           — ACCOUNT_COMPRESSION_PROGRAM_ID
+///   15. spl_interface_pda          (writable)         — Light SPL pool PDA (seeds=[b"pool", mint])
+///   16. vesting                    (writable)         — PDA [VESTING_SEED, user_id]
+///   17+ Merkle tree output queue   (writable)         — injected by JS client
+///
+/// Data: user_id (u64) + user_bump (u8) + memo (String, `zupy:v1:vesting:{id}`)
+/// Discriminator: `[208, 190, 166, 114, 203, 225, 140, 208]` (SHA256("global:claim_vested"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (17 accounts minimum) ─────────────────────────
+    if accounts.len() < 17 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let transfer_authority           = &accounts[0];
+    let token_state_account          = &accounts[1];
+    let mint                         = &accounts[2];
+    let pool_ata                     = &accounts[3];
+    let user_pda                     = &accounts[4];
+    let fee_payer                    = &accounts[5];
+    let token_program                = &accounts[6];
+    let system_program               = &accounts[7];
+    let compressed_token_prog        = &accounts[8];
+    let cpi_authority_pda            = &accounts[9];
+    let light_system_program         = &accounts[10];
+    let registered_program_pda       = &accounts[11];
+    let noop_program                 = &accounts[12];
+    let account_compression_authority = &accounts[13];
+    let account_compression_program  = &accounts[14];
+    let spl_interface_pda            = &accounts[15];
+    let vesting_account              = &accounts[16];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let user_id_u64 = reader.read_u64()?;
+    let user_bump = reader.read_u8()?;
+    let memo = reader.read_string()?;
+    reader.finish()?;
+
+    // ── Input validation ────────────────────────────────────────────────
+    validate_memo_op(memo, MemoOp::Vesting)?;
+
+    // ── Common transfer validation (9 checks, Spec §7.1-§7.8) ───────────
+    let validation = validate_transfer_common(
+        program_id,
+        token_state_account,
+        transfer_authority,
+        mint,
+        token_program,
+    )?;
+
+    // ── Additional signer check: fee_payer ──────────────────────────────
+    if !fee_payer.is_signer() {
+        return Err(ZupyTokenError::FeePayerNotSigner.into());
+    }
+
+    // ── Verify compressed_token_program is the Light cToken program ──────
+    let expected_ctoken: Address = LIGHT_COMPRESSED_TOKEN_PROGRAM_ID.into();
+    if compressed_token_prog.address() != &expected_ctoken {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // ── Verify cpi_authority_pda is the canonical cToken CPI PDA ─────────
+    let expected_ctoken_auth = Address::from(LIGHT_TOKEN_CPI_AUTHORITY);
+    if cpi_authority_pda.address() != &expected_ctoken_auth {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // ── Vesting account validation ──────────────────────────────────────
+    if !vesting_account.owned_by(program_id) || vesting_account.data_len() < VESTING_STATE_SIZE {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let vesting = VestingState::from_slice(unsafe { vesting_account.borrow_unchecked() });
+    assert_discriminator(vesting.discriminator(), &VESTING_STATE_DISCRIMINATOR)?;
+    if vesting.beneficiary_user_id() != user_id_u64 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let user_id_bytes = user_id_u64.to_le_bytes();
+    validate_pda_with_seeds(
+        vesting_account.address(),
+        &[VESTING_SEED, &user_id_bytes, &[vesting.bump()]],
+        program_id,
+    )?;
+
+    // ── PDA validation: user_pda (compressed owner) ─────────────────────
+    validate_pda_with_seeds(
+        user_pda.address(),
+        &[USER_SEED, &user_id_bytes, &[user_bump]],
+        program_id,
+    )?;
+
+    // ── Claimable = unlocked(now) - claimed ─────────────────────────────
+    use pinocchio::sysvars::Sysvar as _;
+    let clock = pinocchio::sysvars::clock::Clock::get()?;
+    let unlocked = unlocked_amount(
+        vesting.total_amount(),
+        vesting.start_ts(),
+        vesting.duration(),
+        clock.unix_timestamp,
+    )?;
+    let claimed = vesting.claimed();
+    let amount = checked_sub_amount(unlocked, claimed)?;
+    if amount == 0 {
+        return Err(ZupyTokenError::NothingToClaim.into());
+    }
+
+    // ── Pool ATA validation ─────────────────────────────────────────────
+    let state = validation.state;
+    if pool_ata.address().as_ref() != state.pool_ata() {
+        return Err(ZupyTokenError::InvalidPoolAccount.into());
+    }
+    let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
+    if !pool_ata.owned_by(&token_2022_addr) {
+        return Err(ZupyTokenError::InvalidPoolAccount.into());
+    }
+
+    // ── Balance check ───────────────────────────────────────────────────
+    let pool_balance = read_token_balance(pool_ata);
+    if pool_balance < amount {
+        return Err(ZupyTokenError::InsufficientPoolBalance.into());
+    }
+
+    // ── Validate spl_interface_pda address ──────────────────────────────
+    let mint_key: [u8; 32] = mint.address().as_ref().try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    let (expected_spl_pda, _) = derive_spl_interface_pda(&mint_key);
+    validate_pda(spl_interface_pda.address(), &expected_spl_pda)?;
+
+    // ── Advance claimed (unlocked <= total, so no overflow) ─────────────
+    VestingStateMut::from_slice(unsafe { vesting_account.borrow_unchecked_mut() })
+        .set_claimed(unlocked);
+
+    // ── CPI: Compress from pool_ata → compressed leaf for user ──────────
+    let owner: &[u8; 32] = user_pda.address().as_ref().try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    let bump_bytes = [validation.bump];
+    let signer_seeds: [Seed; 2] = [
+        Seed::from(TOKEN_STATE_SEED),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    let signer = Signer::from(&signer_seeds);
+
+    cpi_compress_from_spl(
+        compressed_token_prog,
+        cpi_authority_pda,
+        light_system_program,
+        registered_program_pda,
+        noop_program,
+        account_compression_authority,
+        account_compression_program,
+        fee_payer,
+        token_state_account,        // authority: token_state PDA that owns pool_ata
+        spl_interface_pda,          // token_pool_pda
+        pool_ata,                   // source_ata
+        token_program,
+        system_program,
+        owner,
+        Some(checked_sub_amount(pool_balance, amount)?),
+        &accounts[17..],            // remaining: Merkle tree output queue
+        &[signer],
+    )?;
+
+    Ok(())
+}
+
+/// Linearly unlocked amount at `now`: 0 before `start_ts`, `total` from
+/// `start_ts + duration` on, `total * elapsed / duration` (u128) in between.
+pub fn unlocked_amount(total: u64, start_ts: i64, duration: u64, now: i64) -> Result<u64, ProgramError> {
+    if now <= start_ts {
+        return Ok(0);
+    }
+    let elapsed = (now as i128 - start_ts as i128) as u128;
+    if elapsed >= duration as u128 {
+        return Ok(total);
+    }
+    let unlocked = (total as u128)
+        .checked_mul(elapsed)
+        .and_then(|v| v.checked_div(duration as u128))
+        .ok_or(ZupyTokenError::ArithmeticOverflow)?;
+    // elapsed < duration ⇒ unlocked < total, always fits in u64
+    Ok(unlocked as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: i64 = 1_700_000_000;
+    const YEAR: u64 = 31_536_000;
+
+    #[test]
+    fn test_claim_vested_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 13];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+
+    #[test]
+    fn test_unlocked_before_and_at_start_is_zero() {
+        assert_eq!(unlocked_amount(1_000, START, YEAR, START - 1), Ok(0));
+        assert_eq!(unlocked_amount(1_000, START, YEAR, START), Ok(0));
+    }
+
+    #[test]
+    fn test_unlocked_is_linear() {
+        let half = START + (YEAR / 2) as i64;
+        assert_eq!(unlocked_amount(1_000_000, START, YEAR, half), Ok(500_000));
+    }
+
+    #[test]
+    fn test_unlocked_after_full_vesting_is_exact_total() {
+        // Rounding down mid-way never strands dust: the end releases the remainder.
+        let total = 1_000_000_000_007;
+        let end = START + YEAR as i64;
+        assert_eq!(unlocked_amount(total, START, YEAR, end), Ok(total));
+        assert_eq!(unlocked_amount(total, START, YEAR, i64::MAX), Ok(total));
+    }
+
+    #[test]
+    fn test_unlocked_max_values_do_not_overflow() {
+        let almost_end = START + YEAR as i64 - 1;
+        let unlocked = unlocked_amount(u64::MAX, START, YEAR, almost_end).unwrap();
+        assert!(unlocked < u64::MAX);
+        assert_eq!(unlocked_amount(u64::MAX, i64::MIN, u64::MAX, i64::MAX - 1), Ok(u64::MAX - 1));
+        assert_eq!(unlocked_amount(u64::MAX, i64::MIN, u64::MAX, i64::MAX), Ok(u64::MAX));
+    }
+}
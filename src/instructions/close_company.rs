@@ -0,0 +1,107 @@
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::error::ZupyTokenError;
+use crate::helpers::account_checks::assert_discriminator;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::pda::{derive_company_state_pda, validate_pda};
+use crate::helpers::transfer_validation::validate_token_state_base;
+use crate::state::company_state::{
+    CompanyState, COMPANY_STATE_DISCRIMINATOR, COMPANY_STATE_SIZE,
+};
+
+/// Process `close_company` instruction.
+///
+/// Deregisters a churned company: closes its CompanyState PDA and sends the
+/// rent to the treasury. CompanyState is the only account the registry keeps
+/// per company; the company PDA itself is data-less.
+///
+/// The company's compressed balance lives in Merkle-tree leaves this program
+/// cannot read, so the treasury must attest it is zero (`balance_attested`).
+/// Without the attestation, or when the recorded counters still show a
+/// balance (`total_received - total_sent - total_burned > 0`), the close fails
+/// with `CompanyNotEmpty`. Only the treasury wallet can close a company.
+///
+/// Accounts (3):
+///   0. authority (writable, signer) — must be token_state.treasury(); receives the rent
+///   1. token_state (read) — PDA [TOKEN_STATE_SEED]
+///   2. company_state (writable) — PDA [COMPANY_STATE_SEED, &company_id.to_le_bytes()], closed
+///
+/// Data: company_id (u64) + balance_attested (bool)
+/// Discriminator: `[199, 184, 167, 41, 63, 245, 165, 93]` (SHA256("global:close_company"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (3 accounts) ─────────────────────────────────
+    if accounts.len() < 3 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let company_state = &accounts[2];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let company_id = reader.read_u64()?;
+    let balance_attested = reader.read_bool()?;
+    reader.finish()?;
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+
+    // ── Treasury authorization ──────────────────────────────────────────
+    if !authority.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    let authority_key: &[u8; 32] = address_bytes(authority.address());
+    if !state.is_treasury(authority_key) {
+        return Err(ZupyTokenError::UnauthorizedTreasury.into());
+    }
+
+    // ── PDA validation ──────────────────────────────────────────────────
+    let (expected_pda, _) = derive_company_state_pda(program_id, company_id);
+    validate_pda(company_state.address(), &expected_pda)?;
+
+    // ── Company state: registered ───────────────────────────────────────
+    if company_state.data_len() == 0 {
+        return Err(ZupyTokenError::NotInitialized.into());
+    }
+    if !company_state.owned_by(program_id) || company_state.data_len() < COMPANY_STATE_SIZE {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let company = CompanyState::from_slice(unsafe { company_state.borrow_unchecked() });
+    assert_discriminator(company.discriminator(), &COMPANY_STATE_DISCRIMINATOR)?;
+
+    // ── Zero-balance guard: attestation + recorded counters ─────────────
+    if !balance_attested || company.recorded_balance() > 0 {
+        return Err(ZupyTokenError::CompanyNotEmpty.into());
+    }
+
+    // ── Close CompanyState: zero it, move rent to treasury, release ─────
+    unsafe { company_state.borrow_unchecked_mut() }.fill(0);
+    let refund = authority
+        .lamports()
+        .checked_add(company_state.lamports())
+        .ok_or(ZupyTokenError::InvalidAmount)?;
+    authority.set_lamports(refund);
+    company_state.set_lamports(0);
+    company_state.close()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_close_company_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 9];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
@@ -0,0 +1,171 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::{BASIC_MINT_SIZE, TOKEN_2022_PROGRAM_ID, TOKEN_STATE_SEED, USER_PDA_SEED};
+use crate::error::ZupyTokenError;
+use crate::helpers::account_checks::assert_discriminator;
+use crate::helpers::cpi::{cpi_burn, cpi_close_account};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::pda::{
+    derive_coupon_mint_pda, derive_coupon_state_pda, derive_user_nft_pda, validate_pda,
+};
+use crate::helpers::transfer_validation::{
+    read_token_balance, validate_nft_payer, validate_source_ata,
+};
+use crate::state::coupon_state::{CouponState, COUPON_STATE_DISCRIMINATOR, COUPON_STATE_SIZE};
+
+/// Process `close_coupon` instruction.
+///
+/// Reclaims the rent of a finished coupon: burns the coupon token if an
+/// expired coupon still holds it, closes the holder's coupon ATA (skipped if
+/// `redeem_coupon_nft` already closed it), the coupon mint and the CouponState
+/// PDA, sending their lamports to `destination`.
+///
+/// Only coupons marked redeemed or past a non-zero `expires_at` (Clock sysvar)
+/// can be closed; anything else fails with `CouponStillActive`. The mint is
+/// closed with the token_state PDA as its MintCloseAuthority; mints created
+/// before `create_coupon_nft` set that extension are left in place with supply 0.
+///
+/// Accounts (8):
+///   0. mint_authority (signer) — must match token_state.mint_authority()
+///   1. token_state (read) — PDA [TOKEN_STATE_SEED], mint close authority
+///   2. user_pda (read) — PDA [b"user_pda", &user_ksuid], CouponState holder
+///   3. coupon_mint (writable) — PDA [b"coupon", &coupon_ksuid], closed
+///   4. coupon_ata (writable) — coupon ATA for user_pda, closed
+///   5. coupon_state (writable) — PDA [b"coupon_state", &coupon_ksuid], closed
+///   6. destination (writable) — receives the rent of all closed accounts
+///   7. token_program (read) — Token-2022
+///
+/// Data: coupon_ksuid ([u8; 27]) + user_ksuid ([u8; 27])
+/// Discriminator: [145, 129, 128, 62, 140, 121, 104, 203] (SHA256("global:close_coupon"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (8 accounts) ─────────────────────────────────
+    if accounts.len() < 8 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let mint_authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let user_pda = &accounts[2];
+    let coupon_mint = &accounts[3];
+    let coupon_ata = &accounts[4];
+    let coupon_state = &accounts[5];
+    let destination = &accounts[6];
+    let token_program = &accounts[7];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let coupon_ksuid = reader.read_bytes::<27>()?;
+    let user_ksuid = reader.read_bytes::<27>()?;
+    reader.finish()?;
+
+    // ── Authority (signer + token_state + mint_authority) ───────────────
+    let token_state_bump =
+        validate_nft_payer(program_id, mint_authority, token_state_account)?.bump();
+
+    // ── Token program check ─────────────────────────────────────────────
+    let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
+    if token_program.address() != &token_2022_addr {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    // ── PDA validations ─────────────────────────────────────────────────
+    let (expected_user_pda, user_bump) = derive_user_nft_pda(program_id, user_ksuid);
+    validate_pda(user_pda.address(), &expected_user_pda)?;
+
+    let (expected_coupon_mint, _) = derive_coupon_mint_pda(program_id, coupon_ksuid);
+    validate_pda(coupon_mint.address(), &expected_coupon_mint)?;
+
+    let (expected_state_pda, _) = derive_coupon_state_pda(program_id, coupon_ksuid);
+    validate_pda(coupon_state.address(), &expected_state_pda)?;
+
+    // ── Coupon state: must be redeemed or expired ───────────────────────
+    if !coupon_state.owned_by(program_id) || coupon_state.data_len() < COUPON_STATE_SIZE {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let coupon = CouponState::from_slice(unsafe { coupon_state.borrow_unchecked() });
+    assert_discriminator(coupon.discriminator(), &COUPON_STATE_DISCRIMINATOR)?;
+    use pinocchio::sysvars::Sysvar as _;
+    let clock = pinocchio::sysvars::clock::Clock::get()?;
+    if !coupon.redeemed() && !coupon.is_expired(clock.unix_timestamp) {
+        return Err(ZupyTokenError::CouponStillActive.into());
+    }
+    if coupon.holder() != expected_user_pda.as_ref() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+
+    // ── CPIs: Burn a leftover coupon, close its ATA (user_pda signs) ────
+    if coupon_ata.data_len() > 0 {
+        validate_source_ata(coupon_ata, &expected_coupon_mint, &expected_user_pda)?;
+
+        let user_bump_bytes = [user_bump];
+        let user_signer_seeds: [Seed; 3] = [
+            Seed::from(USER_PDA_SEED),
+            Seed::from(user_ksuid.as_ref()),
+            Seed::from(user_bump_bytes.as_ref()),
+        ];
+
+        let balance = read_token_balance(coupon_ata);
+        if balance > 0 {
+            cpi_burn(
+                coupon_ata,
+                coupon_mint,
+                user_pda,
+                balance,
+                &token_2022_addr,
+                &[Signer::from(&user_signer_seeds)],
+            )?;
+        }
+
+        cpi_close_account(
+            coupon_ata,
+            destination,
+            user_pda,
+            &token_2022_addr,
+            &[Signer::from(&user_signer_seeds)],
+        )?;
+    }
+
+    // ── CPI: Close the coupon mint (token_state PDA signs) ──────────────
+    if coupon_mint.data_len() > BASIC_MINT_SIZE as usize {
+        let bump_bytes = [token_state_bump];
+        let token_state_seeds: [Seed; 2] = [
+            Seed::from(TOKEN_STATE_SEED),
+            Seed::from(bump_bytes.as_ref()),
+        ];
+        cpi_close_account(
+            coupon_mint,
+            destination,
+            token_state_account,
+            &token_2022_addr,
+            &[Signer::from(&token_state_seeds)],
+        )?;
+    }
+
+    // ── Close CouponState: move rent to destination, then release ───────
+    let refund = destination
+        .lamports()
+        .checked_add(coupon_state.lamports())
+        .ok_or(ZupyTokenError::InvalidAmount)?;
+    destination.set_lamports(refund);
+    coupon_state.set_lamports(0);
+    coupon_state.close()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_close_coupon_not_enough_account_keys() {
+        let program_id = Address::default();
+        let result = process(&program_id, &[], &[0u8; 54]);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+}
@@ -0,0 +1,105 @@
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::{RECEIPT_RETENTION_SECS, RECEIPT_SEED};
+use crate::error::ZupyTokenError;
+use crate::helpers::account_checks::assert_discriminator;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::pda::validate_pda_with_seeds;
+use crate::helpers::transfer_validation::validate_token_state_base;
+use crate::state::receipt_state::{
+    ReceiptState, RECEIPT_STATE_DISCRIMINATOR, RECEIPT_STATE_SIZE,
+};
+
+/// Process `close_receipt` instruction.
+///
+/// Closes an idempotency receipt once it is older than `RECEIPT_RETENTION_SECS`
+/// (`ReceiptRetentionActive` before) and returns its rent to the original payer.
+/// After closing, the `op_id` is no longer protected against replays.
+///
+/// Accounts (4):
+///   0. transfer_authority (signer) — must match token_state.transfer_authority()
+///   1. token_state (read)          — PDA [TOKEN_STATE_SEED]
+///   2. receipt (writable)          — PDA [RECEIPT_SEED, op_id]
+///   3. payer (writable)            — must match receipt.payer(); receives the rent
+///
+/// Data: op_id ([u8; 16])
+/// Discriminator: `[126, 254, 244, 203, 124, 164, 134, 89]` (SHA256("global:close_receipt"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (4 accounts) ─────────────────────────────────
+    if accounts.len() < 4 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let transfer_authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let receipt_account = &accounts[2];
+    let payer = &accounts[3];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let op_id = reader.read_bytes::<16>()?;
+    reader.finish()?;
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+
+    // ── Authority validation ────────────────────────────────────────────
+    if !transfer_authority.is_signer()
+        || state.transfer_authority() != transfer_authority.address().as_ref()
+    {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+
+    // ── Receipt validation: owner, size, discriminator, PDA ─────────────
+    if !receipt_account.owned_by(program_id) || receipt_account.data_len() < RECEIPT_STATE_SIZE {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let receipt = ReceiptState::from_slice(unsafe { receipt_account.borrow_unchecked() });
+    assert_discriminator(receipt.discriminator(), &RECEIPT_STATE_DISCRIMINATOR)?;
+    if receipt.op_id() != op_id {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    validate_pda_with_seeds(
+        receipt_account.address(),
+        &[RECEIPT_SEED, op_id, &[receipt.bump()]],
+        program_id,
+    )?;
+    if payer.address().as_ref() != receipt.payer() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // ── Retention period ────────────────────────────────────────────────
+    use pinocchio::sysvars::Sysvar as _;
+    let clock = pinocchio::sysvars::clock::Clock::get()?;
+    if clock.unix_timestamp.saturating_sub(receipt.created_at()) < RECEIPT_RETENTION_SECS {
+        return Err(ZupyTokenError::ReceiptRetentionActive.into());
+    }
+
+    // ── Close: move rent to payer, then release the account ─────────────
+    let refund = payer
+        .lamports()
+        .checked_add(receipt_account.lamports())
+        .ok_or(ZupyTokenError::InvalidAmount)?;
+    payer.set_lamports(refund);
+    receipt_account.set_lamports(0);
+    receipt_account.close()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_close_receipt_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 16];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
@@ -2,21 +2,32 @@ use pinocchio::cpi::{Seed, Signer};
 use pinocchio::error::ProgramError;
 use pinocchio::{AccountView, Address, ProgramResult};
 
-use crate::constants::{BASIC_MINT_SIZE, COUPON_SEED, TOKEN_2022_PROGRAM_ID};
+use crate::constants::{CLOSABLE_MINT_SIZE, COUPON_SEED, TOKEN_2022_PROGRAM_ID};
 use crate::error::ZupyTokenError;
+use crate::helpers::coupon::{create_coupon_state_if_needed, parse_coupon_terms};
 use crate::helpers::cpi::{
-    cpi_create_account, cpi_create_ata_if_needed, cpi_initialize_mint, cpi_mint_to,
+    cpi_create_account, cpi_create_ata_if_needed, cpi_initialize_mint,
+    cpi_initialize_mint_close_authority, cpi_mint_to,
 };
-use crate::helpers::instruction_data::{parse_bytes, parse_string};
+use crate::helpers::instruction_data::{validate_ksuid, Reader};
+use crate::helpers::nft_rate_limit::consume_nft_mints;
 use crate::helpers::pda::{derive_coupon_mint_pda, derive_user_nft_pda, validate_pda};
 use crate::helpers::transfer_validation::validate_nft_payer;
 
 /// Process `create_coupon_nft` instruction.
 ///
 /// Creates a transferable coupon NFT: mint PDA + ATA + mint 1.
-/// Coupon mint is self-authority (authority = coupon_mint PDA itself).
+/// Coupon mint is self-authority (authority = coupon_mint PDA itself), with the
+/// token_state PDA as MintCloseAuthority so `close_coupon` can reclaim its rent.
+/// When `coupon_state` is passed, its CouponState PDA is created too (skipped
+/// if it already exists) and records `expires_at` and the optional token value
+/// (`value_amount` owed by `issuer_company_id`); `redeem_coupon_nft` creates it
+/// otherwise, without expiry or value. Non-zero terms require `coupon_state`.
+/// Passing payer's RateLimitState counts the mint against its NFT window
+/// (`RateLimitExceeded` when full); it sits after coupon_state, so both go
+/// together.
 ///
-/// Accounts (8):
+/// Accounts (8, +2 optional):
 ///   0. user_pda (read) — PDA [b"user_pda", &user_ksuid]
 ///   1. coupon_mint (writable) — PDA [b"coupon", &coupon_ksuid], init mint
 ///   2. coupon_ata (writable) — ATA for user_pda
@@ -25,8 +36,12 @@ use crate::helpers::transfer_validation::validate_nft_payer;
 ///   5. token_program (read) — Token-2022
 ///   6. associated_token_program (read)
 ///   7. system_program (read)
+///   8. coupon_state (writable, optional) — PDA [b"coupon_state", &coupon_ksuid]
+///   9. rate_limit (writable, optional) — PDA [RATE_LIMIT_SEED, payer]
 ///
 /// Data: user_ksuid ([u8; 27]) + coupon_ksuid ([u8; 27]) + metadata_uri (String)
+///       + expires_at (i64, optional, 0 = no expiry)
+///       + value_amount (u64) + issuer_company_id (u64) (optional pair, 0 = no value)
 pub fn process(
     program_id: &Address,
     accounts: &[AccountView],
@@ -44,15 +59,29 @@ pub fn process(
     let token_program = &accounts[5];
     let _associated_token_program = &accounts[6];
     let system_program = &accounts[7];
+    let coupon_state = accounts.get(8);
+    let rate_limit = accounts.get(9);
 
     // ── Parse instruction data ──────────────────────────────────────────
-    let (user_ksuid, offset) = parse_bytes::<27>(data, 0)?;
-    let (coupon_ksuid, offset) = parse_bytes::<27>(data, offset)?;
-    let (_metadata_uri, _) = parse_string(data, offset)?;
+    let mut reader = Reader::new(data);
+    let user_ksuid = reader.read_bytes::<27>()?;
+    let coupon_ksuid = reader.read_bytes::<27>()?;
+    validate_ksuid(user_ksuid)?;
+    validate_ksuid(coupon_ksuid)?;
+    let _metadata_uri = reader.read_string()?;
+    let terms = parse_coupon_terms(&mut reader)?;
+    if terms.is_restricted() && coupon_state.is_none() {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
 
     // ── NFT payer validation (signer + token_state + mint_authority) ─────
     validate_nft_payer(program_id, payer, token_state_account)?;
 
+    // ── NFT mint rate limit (optional) ──────────────────────────────────
+    if let Some(rate_limit) = rate_limit {
+        consume_nft_mints(program_id, rate_limit, payer, 1)?;
+    }
+
     // ── Token program check ─────────────────────────────────────────────
     let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
     if token_program.address() != &token_2022_addr {
@@ -66,7 +95,7 @@ pub fn process(
     let (expected_coupon_mint, coupon_bump) = derive_coupon_mint_pda(program_id, coupon_ksuid);
     validate_pda(coupon_mint.address(), &expected_coupon_mint)?;
 
-    // ── CPI 1: Create coupon mint PDA (82 bytes, owned by Token-2022) ───
+    // ── CPI 1: Create coupon mint PDA (202 bytes, owned by Token-2022) ──
     let coupon_bump_bytes = [coupon_bump];
     let mint_signer_seeds: [Seed; 3] = [
         Seed::from(COUPON_SEED),
@@ -78,11 +107,18 @@ pub fn process(
     cpi_create_account(
         payer,
         coupon_mint,
-        BASIC_MINT_SIZE,
+        CLOSABLE_MINT_SIZE,
         &token_2022_addr,
         &[mint_signer],
     )?;
 
+    // ── CPI 2a: MintCloseAuthority extension (BEFORE InitializeMint) ───
+    cpi_initialize_mint_close_authority(
+        coupon_mint,
+        token_state_account.address(),
+        token_program,
+    )?;
+
     // ── CPI 2: Initialize mint (decimals=0, authority=coupon_mint PDA) ──
     cpi_initialize_mint(
         coupon_mint,
@@ -119,5 +155,18 @@ pub fn process(
         &[mint_signer2],
     )?;
 
+    // ── Coupon state (optional, redeemed = false) ───────────────────────
+    if let Some(coupon_state) = coupon_state {
+        create_coupon_state_if_needed(
+            program_id,
+            coupon_state,
+            payer,
+            coupon_ksuid,
+            &expected_coupon_mint,
+            &expected_user_pda,
+            &terms,
+        )?;
+    }
+
     Ok(())
 }
@@ -0,0 +1,263 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::{AccountView, Address, ProgramResult};
+use pinocchio::error::ProgramError;
+
+use crate::constants::{
+    COMPANY_SEED, ESCROW_SEED, LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, LIGHT_TOKEN_CPI_AUTHORITY,
+    TOKEN_2022_PROGRAM_ID, TOKEN_STATE_SEED,
+};
+use crate::error::ZupyTokenError;
+use crate::helpers::compressed_accounts::{
+    cpi_compress_from_spl, cpi_compressed_transfer, derive_spl_interface_pda,
+};
+use crate::helpers::cpi::cpi_create_account;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::math::checked_sub_amount;
+use crate::helpers::memo::{validate_memo_op, MemoOp};
+use crate::helpers::pda::{derive_escrow_pda, validate_pda, validate_pda_with_seeds};
+use crate::helpers::transfer_validation::{read_token_balance, validate_transfer_common};
+use crate::state::escrow_state::{
+    EscrowStateMut, ESCROW_SOURCE_COMPANY, ESCROW_SOURCE_POOL, ESCROW_STATE_DISCRIMINATOR,
+    ESCROW_STATE_SIZE, ESCROW_STATUS_PENDING,
+};
+
+/// Process `create_escrow` instruction.
+///
+/// Creates an Escrow PDA `[ESCROW_SEED, escrow_id]` and moves `amount` into its
+/// compressed balance, to be claimed by `recipient_user_id` before `expiry`
+/// (`claim_escrow`) or returned to the source (`cancel_escrow`).
+///
+/// - Pool source: Path A compress from pool_ata, signed by the token_state PDA.
+/// - Company source: Path B compressed transfer, signed by the company PDA.
+///
+/// Accounts (9 for company source; 16 minimum plus Merkle output queue for pool source):
+///   0.  transfer_authority         (signer)
+///   1.  token_state                (read)
+///   2.  mint                       (read)
+///   3.  escrow                     (writable)         — PDA [ESCROW_SEED, escrow_id], created here
+///   4.  source                     (writable)         — company PDA, or pool_ata for pool source
+///   5.  fee_payer                  (writable, signer) — pays escrow rent + Light fees
+///   6.  system_program             (read)
+///   7.  compressed_token_program   (read)             — Light cToken program
+///   8.  token_program              (read)             — Token-2022
+///   Pool source only:
+///   9.  cpi_authority_pda          (read)             — LIGHT_TOKEN_CPI_AUTHORITY
+///   10. light_system_program       (read)
+///   11. registered_program_pda     (read)
+///   12. noop_program               (read)
+///   13. account_compression_authority (read)
+///   14. account_compression_program  (read)
+///   15. spl_interface_pda          (writable)         — seeds=[b"pool", mint]
+///   16+ Merkle tree output queue   (writable)
+///
+/// Data: escrow_id (u64) + source_kind (u8: 0 = pool, 1 = company) + source_id (u64)
+///       + source_bump (u8) + recipient_user_id (u64) + amount (u64) + expiry (i64)
+///       + memo (String, `zupy:v1:escrow:{id}`)
+/// Discriminator: `[253, 215, 165, 116, 36, 108, 68, 80]` (SHA256("global:create_escrow"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (9 accounts minimum) ─────────────────────────
+    if accounts.len() < 9 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let transfer_authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let mint = &accounts[2];
+    let escrow_account = &accounts[3];
+    let source = &accounts[4];
+    let fee_payer = &accounts[5];
+    let system_program = &accounts[6];
+    let compressed_token_prog = &accounts[7];
+    let token_program = &accounts[8];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let escrow_id = reader.read_u64()?;
+    let source_kind = reader.read_u8()?;
+    let source_id = reader.read_u64()?;
+    let source_bump = reader.read_u8()?;
+    let recipient_user_id = reader.read_u64()?;
+    let amount = reader.read_u64()?;
+    let expiry = reader.read_i64()?;
+    let memo = reader.read_string()?;
+    reader.finish()?;
+
+    // ── Input validation ────────────────────────────────────────────────
+    if source_kind != ESCROW_SOURCE_POOL && source_kind != ESCROW_SOURCE_COMPANY {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    if amount == 0 {
+        return Err(ZupyTokenError::ZeroAmount.into());
+    }
+    validate_memo_op(memo, MemoOp::Escrow)?;
+
+    use pinocchio::sysvars::Sysvar as _;
+    let clock = pinocchio::sysvars::clock::Clock::get()?;
+    if expiry <= clock.unix_timestamp {
+        return Err(ZupyTokenError::EscrowExpired.into());
+    }
+
+    // ── Common transfer validation (9 checks, Spec §7.1-§7.8) ───────────
+    let validation = validate_transfer_common(
+        program_id,
+        token_state_account,
+        transfer_authority,
+        mint,
+        token_program,
+    )?;
+
+    // ── Additional signer check: fee_payer ──────────────────────────────
+    if !fee_payer.is_signer() {
+        return Err(ZupyTokenError::FeePayerNotSigner.into());
+    }
+
+    // ── Verify compressed_token_program is the Light cToken program ──────
+    let expected_ctoken: Address = LIGHT_COMPRESSED_TOKEN_PROGRAM_ID.into();
+    if compressed_token_prog.address() != &expected_ctoken {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // ── Escrow PDA validation + init guard ──────────────────────────────
+    let (expected_escrow, escrow_bump) = derive_escrow_pda(program_id, escrow_id);
+    validate_pda(escrow_account.address(), &expected_escrow)?;
+    if escrow_account.data_len() > 0 {
+        return Err(ZupyTokenError::AlreadyInitialized.into());
+    }
+
+    // ── CPI: Create escrow account (51 bytes) ───────────────────────────
+    let escrow_id_bytes = escrow_id.to_le_bytes();
+    let escrow_bump_bytes = [escrow_bump];
+    let escrow_seeds: [Seed; 3] = [
+        Seed::from(ESCROW_SEED),
+        Seed::from(escrow_id_bytes.as_ref()),
+        Seed::from(escrow_bump_bytes.as_ref()),
+    ];
+    cpi_create_account(
+        fee_payer,
+        escrow_account,
+        ESCROW_STATE_SIZE as u64,
+        program_id,
+        &[Signer::from(&escrow_seeds)],
+    )?;
+
+    // ── Fund the escrow's compressed balance ────────────────────────────
+    let owner: &[u8; 32] = escrow_account.address().as_ref().try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    if source_kind == ESCROW_SOURCE_POOL {
+        if accounts.len() < 16 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let cpi_authority_pda = &accounts[9];
+        let spl_interface_pda = &accounts[15];
+
+        let expected_ctoken_auth = Address::from(LIGHT_TOKEN_CPI_AUTHORITY);
+        if cpi_authority_pda.address() != &expected_ctoken_auth {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // Pool ATA validation
+        let state = validation.state;
+        if source.address().as_ref() != state.pool_ata() {
+            return Err(ZupyTokenError::InvalidPoolAccount.into());
+        }
+        let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
+        if !source.owned_by(&token_2022_addr) {
+            return Err(ZupyTokenError::InvalidPoolAccount.into());
+        }
+        let pool_balance = read_token_balance(source);
+        if pool_balance < amount {
+            return Err(ZupyTokenError::InsufficientPoolBalance.into());
+        }
+
+        let mint_key: [u8; 32] = mint.address().as_ref().try_into()
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        let (expected_spl_pda, _) = derive_spl_interface_pda(&mint_key);
+        validate_pda(spl_interface_pda.address(), &expected_spl_pda)?;
+
+        // token_state PDA signs with [TOKEN_STATE_SEED, &[bump]]
+        let bump_bytes = [validation.bump];
+        let signer_seeds: [Seed; 2] = [
+            Seed::from(TOKEN_STATE_SEED),
+            Seed::from(bump_bytes.as_ref()),
+        ];
+        let signer = Signer::from(&signer_seeds);
+
+        cpi_compress_from_spl(
+            compressed_token_prog,
+            cpi_authority_pda,
+            &accounts[10],              // light_system_program
+            &accounts[11],              // registered_program_pda
+            &accounts[12],              // noop_program
+            &accounts[13],              // account_compression_authority
+            &accounts[14],              // account_compression_program
+            fee_payer,
+            token_state_account,        // authority: token_state PDA that owns pool_ata
+            spl_interface_pda,
+            source,                     // source_ata: pool_ata
+            token_program,
+            system_program,
+            owner,
+            Some(checked_sub_amount(pool_balance, amount)?),
+            &accounts[16..],            // remaining: Merkle tree output queue
+            &[signer],
+        )?;
+    } else {
+        // Company PDA validation (via client-provided bump)
+        let source_id_bytes = source_id.to_le_bytes();
+        validate_pda_with_seeds(
+            source.address(),
+            &[COMPANY_SEED, &source_id_bytes, &[source_bump]],
+            program_id,
+        )?;
+
+        let source_bump_bytes = [source_bump];
+        let signer_seeds: [Seed; 3] = [
+            Seed::from(COMPANY_SEED),
+            Seed::from(source_id_bytes.as_ref()),
+            Seed::from(source_bump_bytes.as_ref()),
+        ];
+        let signer = Signer::from(&signer_seeds);
+
+        cpi_compressed_transfer(
+            compressed_token_prog,
+            fee_payer,
+            source,         // source: company PDA
+            escrow_account, // destination: escrow PDA
+            source,         // authority (company PDA signs)
+            system_program,
+            amount,
+            &[signer],
+        )?;
+    }
+
+    // ── Initialize escrow state ─────────────────────────────────────────
+    let mut escrow = EscrowStateMut::from_slice(unsafe { escrow_account.borrow_unchecked_mut() });
+    escrow.set_discriminator(&ESCROW_STATE_DISCRIMINATOR);
+    escrow.set_escrow_id(escrow_id);
+    escrow.set_source_kind(source_kind);
+    escrow.set_source_id(if source_kind == ESCROW_SOURCE_COMPANY { source_id } else { 0 });
+    escrow.set_recipient_user_id(recipient_user_id);
+    escrow.set_amount(amount);
+    escrow.set_expiry(expiry);
+    escrow.set_status(ESCROW_STATUS_PENDING);
+    escrow.set_bump(escrow_bump);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_escrow_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 46];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
@@ -0,0 +1,122 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::VESTING_SEED;
+use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::cpi::cpi_create_account;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::pda::{derive_vesting_pda, validate_pda};
+use crate::helpers::transfer_validation::validate_token_state_base;
+use crate::state::vesting_state::{
+    VestingStateMut, VESTING_STATE_DISCRIMINATOR, VESTING_STATE_SIZE,
+};
+
+/// Process `create_vesting` instruction.
+///
+/// Creates a VestingState PDA granting `total_amount` to a user, released
+/// linearly from `start_ts` over `duration` seconds via `claim_vested`.
+/// Tokens stay in the pool until claimed. Only the treasury wallet can grant.
+///
+/// Accounts (4):
+///   0. treasury (writable, signer) — must be token_state.treasury(); pays rent
+///   1. token_state (read)          — PDA [TOKEN_STATE_SEED]
+///   2. vesting (writable)          — PDA [VESTING_SEED, user_id], created here
+///   3. system_program (read)
+///
+/// Data: user_id (u64) + total_amount (u64) + start_ts (i64) + duration (u64, seconds)
+/// Discriminator: `[135, 184, 171, 156, 197, 162, 246, 44]` (SHA256("global:create_vesting"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (4 accounts) ─────────────────────────────────
+    if accounts.len() < 4 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let treasury = &accounts[0];
+    let token_state_account = &accounts[1];
+    let vesting_account = &accounts[2];
+    let _system_program = &accounts[3];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let user_id = reader.read_u64()?;
+    let total_amount = reader.read_u64()?;
+    let start_ts = reader.read_i64()?;
+    let duration = reader.read_u64()?;
+    reader.finish()?;
+
+    // ── Input validation ────────────────────────────────────────────────
+    if total_amount == 0 {
+        return Err(ZupyTokenError::ZeroAmount.into());
+    }
+    if duration == 0 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+
+    // ── Treasury authorization ──────────────────────────────────────────
+    if !treasury.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    let treasury_key: &[u8; 32] = address_bytes(treasury.address());
+    if !state.is_treasury(treasury_key) {
+        return Err(ZupyTokenError::UnauthorizedTreasury.into());
+    }
+
+    // ── PDA validation + init guard ─────────────────────────────────────
+    let (expected_pda, bump) = derive_vesting_pda(program_id, user_id);
+    validate_pda(vesting_account.address(), &expected_pda)?;
+    if vesting_account.data_len() > 0 {
+        return Err(ZupyTokenError::AlreadyInitialized.into());
+    }
+
+    // ── CPI: Create account (49 bytes) ──────────────────────────────────
+    let user_id_bytes = user_id.to_le_bytes();
+    let bump_bytes = [bump];
+    let signer_seeds: [Seed; 3] = [
+        Seed::from(VESTING_SEED),
+        Seed::from(user_id_bytes.as_ref()),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    let signer = Signer::from(&signer_seeds);
+
+    cpi_create_account(
+        treasury,
+        vesting_account,
+        VESTING_STATE_SIZE as u64,
+        program_id,
+        &[signer],
+    )?;
+
+    // ── Initialize state fields ─────────────────────────────────────────
+    let mut vesting =
+        VestingStateMut::from_slice(unsafe { vesting_account.borrow_unchecked_mut() });
+    vesting.set_discriminator(&VESTING_STATE_DISCRIMINATOR);
+    vesting.set_beneficiary_user_id(user_id);
+    vesting.set_total_amount(total_amount);
+    vesting.set_start_ts(start_ts);
+    vesting.set_duration(duration);
+    vesting.set_claimed(0);
+    vesting.set_bump(bump);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_vesting_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 32];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
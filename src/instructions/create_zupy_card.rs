@@ -2,27 +2,54 @@ use pinocchio::cpi::{Seed, Signer};
 use pinocchio::error::ProgramError;
 use pinocchio::{AccountView, Address, ProgramResult};
 
-use crate::constants::{BASIC_MINT_SIZE, TOKEN_2022_PROGRAM_ID, ZUPY_CARD_MINT_SEED, ZUPY_CARD_SEED};
+use crate::constants::{
+    TOKEN_2022_PROGRAM_ID, ZUPY_CARD_MINT_SEED, ZUPY_CARD_MINT_SIZE, ZUPY_CARD_NAME,
+    ZUPY_CARD_SEED, ZUPY_CARD_SYMBOL,
+};
 use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
 use crate::helpers::cpi::{
-    cpi_create_account, cpi_create_ata_if_needed, cpi_initialize_mint, cpi_mint_to,
+    cpi_create_account, cpi_create_ata_if_needed, cpi_fund_rent, cpi_initialize_metadata,
+    cpi_initialize_metadata_pointer, cpi_initialize_mint, cpi_initialize_non_transferable_mint,
+    cpi_mint_to,
 };
-use crate::helpers::instruction_data::{parse_bytes, parse_string};
+use crate::helpers::instruction_data::{validate_ksuid, Reader};
+use crate::helpers::metadata::{token_metadata_space, validate_metadata_uri};
 use crate::helpers::pda::{
     derive_user_nft_pda, derive_zupy_card_mint_pda, derive_zupy_card_pda,
     validate_pda,
 };
 use crate::helpers::transfer_validation::validate_nft_payer;
-use crate::state::zupy_card::{ZupyCardMut, ZUPY_CARD_DISCRIMINATOR, ZUPY_CARD_SIZE};
+use crate::state::zupy_card::{
+    ZupyCardMut, CARD_STATUS_ACTIVE, ZUPY_CARD_DISCRIMINATOR, ZUPY_CARD_SIZE_WITH_ISSUED_AT,
+};
 
 /// Process `create_zupy_card` instruction.
 ///
 /// Creates a soulbound Zuper Card NFT: ZupyCard PDA + mint PDA + ATA + mint 1.
+/// The mint carries the Token-2022 NonTransferable extension, so the card can
+/// never leave the ATA (whose ImmutableOwner the ATA program adds). Cards
+/// created before this have plain 82-byte mints and stay transferable.
+///
+/// The mint also carries a MetadataPointer to itself and TokenMetadata with
+/// `ZUPY_CARD_NAME` / `ZUPY_CARD_SYMBOL` / `metadata_uri`, so wallets resolve
+/// the card art on-chain. The zupy_card PDA (mint authority) is the pointer and
+/// metadata update authority and signs the TokenMetadata Initialize; the mint
+/// is funded up front for the bytes that CPI appends.
+///
+/// A ksuid gets one card PDA for life: a zupy_card account holding any data
+/// (active or revoked card) fails with `CardAlreadyExists` before any CPI, and
+/// user_pda must be the ksuid's own PDA; lost wallets go through
+/// `reissue_zupy_card` instead.
+///
+/// The card starts active with `created_at` = `issued_at` = the Clock sysvar's
+/// unix_timestamp.
 ///
 /// Accounts (9):
 ///   0. user_pda (read) — PDA [b"user_pda", &user_ksuid]
-///   1. zupy_card (writable) — PDA [b"zupy_card", &user_ksuid], init 108 bytes
-///   2. mint (writable) — PDA [b"zupy_card_mint", &user_ksuid], init mint
+///   1. zupy_card (writable) — PDA [b"zupy_card", &user_ksuid], init 130 bytes
+///   2. mint (writable) — PDA [b"zupy_card_mint", &user_ksuid], init 238-byte mint
+///      + TokenMetadata
 ///   3. token_account (writable) — ATA for user_pda
 ///   4. token_state (read) — PDA [TOKEN_STATE_SEED], Audit 12.1
 ///   5. payer (writable, signer) — must match token_state.mint_authority()
@@ -30,7 +57,7 @@ use crate::state::zupy_card::{ZupyCardMut, ZUPY_CARD_DISCRIMINATOR, ZUPY_CARD_SI
 ///   7. associated_token_program (read)
 ///   8. system_program (read)
 ///
-/// Data: user_ksuid ([u8; 27]) + metadata_uri (String)
+/// Data: user_ksuid ([u8; 27]) + metadata_uri (String, `https://` or `ipfs://`)
 pub fn process(
     program_id: &Address,
     accounts: &[AccountView],
@@ -51,8 +78,12 @@ pub fn process(
     let system_program = &accounts[8];
 
     // ── Parse instruction data ──────────────────────────────────────────
-    let (user_ksuid, offset) = parse_bytes::<27>(data, 0)?;
-    let (_metadata_uri, _) = parse_string(data, offset)?;
+    let mut reader = Reader::new(data);
+    let user_ksuid = reader.read_bytes::<27>()?;
+    validate_ksuid(user_ksuid)?;
+    let metadata_uri = reader.read_string()?;
+    reader.finish()?;
+    validate_metadata_uri(metadata_uri)?;
 
     // ── NFT payer validation (signer + token_state + mint_authority) ─────
     validate_nft_payer(program_id, payer, token_state_account)?;
@@ -73,12 +104,12 @@ pub fn process(
     let (expected_mint_pda, mint_bump) = derive_zupy_card_mint_pda(program_id, user_ksuid);
     validate_pda(mint.address(), &expected_mint_pda)?;
 
-    // ── Init guard: zupy_card must not already exist ─────────────────────
+    // ── Init guard: one card per ksuid ──────────────────────────────────
     if zupy_card.data_len() > 0 {
-        return Err(ZupyTokenError::AlreadyInitialized.into());
+        return Err(ZupyTokenError::CardAlreadyExists.into());
     }
 
-    // ── CPI 1: Create ZupyCard PDA (108 bytes) ──────────────────────────
+    // ── CPI 1: Create ZupyCard PDA (130 bytes) ──────────────────────────
     let card_bump_bytes = [card_bump];
     let card_signer_seeds: [Seed; 3] = [
         Seed::from(ZUPY_CARD_SEED),
@@ -90,12 +121,12 @@ pub fn process(
     cpi_create_account(
         payer,
         zupy_card,
-        ZUPY_CARD_SIZE as u64,
+        ZUPY_CARD_SIZE_WITH_ISSUED_AT as u64,
         program_id,
         &[card_signer],
     )?;
 
-    // ── CPI 2: Create mint PDA (82 bytes, owned by Token-2022) ──────────
+    // ── CPI 2: Create mint PDA (238 bytes, owned by Token-2022) ─────────
     let mint_bump_bytes = [mint_bump];
     let mint_signer_seeds: [Seed; 3] = [
         Seed::from(ZUPY_CARD_MINT_SEED),
@@ -107,11 +138,17 @@ pub fn process(
     cpi_create_account(
         payer,
         mint,
-        BASIC_MINT_SIZE,
+        ZUPY_CARD_MINT_SIZE,
         &token_2022_addr,
         &[mint_signer],
     )?;
 
+    // ── CPI 3a: NonTransferable extension (BEFORE InitializeMint) ───────
+    cpi_initialize_non_transferable_mint(mint, token_program)?;
+
+    // ── CPI 3b: MetadataPointer → mint itself (BEFORE InitializeMint) ───
+    cpi_initialize_metadata_pointer(mint, &expected_card_pda, token_program)?;
+
     // ── CPI 3: Initialize mint (decimals=0, authority=zupy_card PDA) ────
     cpi_initialize_mint(
         mint,
@@ -121,6 +158,26 @@ pub fn process(
         &token_2022_addr,
     )?;
 
+    // ── CPI 3c: TokenMetadata (rent for the appended TLV, card PDA signs)
+    let metadata_len = ZUPY_CARD_MINT_SIZE as usize
+        + token_metadata_space(ZUPY_CARD_NAME, ZUPY_CARD_SYMBOL, metadata_uri);
+    cpi_fund_rent(payer, mint, metadata_len)?;
+
+    let card_signer_seeds_md: [Seed; 3] = [
+        Seed::from(ZUPY_CARD_SEED),
+        Seed::from(user_ksuid.as_ref()),
+        Seed::from(card_bump_bytes.as_ref()),
+    ];
+    cpi_initialize_metadata(
+        mint,
+        zupy_card,
+        token_program,
+        ZUPY_CARD_NAME,
+        ZUPY_CARD_SYMBOL,
+        metadata_uri,
+        &[Signer::from(&card_signer_seeds_md)],
+    )?;
+
     // ── CPI 4: Create ATA for user_pda ──────────────────────────────────
     cpi_create_ata_if_needed(
         token_account,
@@ -155,13 +212,18 @@ pub fn process(
     let mut card_state =
         ZupyCardMut::from_slice(unsafe { zupy_card.borrow_unchecked_mut() });
     card_state.set_discriminator(&ZUPY_CARD_DISCRIMINATOR);
-    let owner_key: &[u8; 32] = user_pda.address().as_ref().try_into().unwrap();
+    let owner_key: &[u8; 32] = address_bytes(user_pda.address());
     card_state.set_owner(owner_key);
-    let mint_key: &[u8; 32] = mint.address().as_ref().try_into().unwrap();
+    let mint_key: &[u8; 32] = address_bytes(mint.address());
     card_state.set_mint(mint_key);
     card_state.set_user_ksuid(user_ksuid);
     card_state.set_created_at(clock.unix_timestamp);
     card_state.set_bump(card_bump);
+    card_state.set_tier(0);
+    card_state.set_updated_at(clock.unix_timestamp);
+    card_state.set_status(CARD_STATUS_ACTIVE);
+    card_state.set_reissue_count(0);
+    card_state.set_issued_at(clock.unix_timestamp);
 
     Ok(())
 }
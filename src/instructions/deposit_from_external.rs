@@ -0,0 +1,38 @@
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::USER_SEED;
+use crate::helpers::memo::MemoOp;
+use crate::helpers::transfer_validation::execute_external_deposit;
+
+/// Process `deposit_from_external` instruction.
+///
+/// Inverse of `withdraw_to_external`: compresses ZUPY from an external wallet's
+/// ATA straight into a compressed balance owned by the user PDA.
+///
+/// Delegates to [`execute_external_deposit`] with `USER_SEED` and memo
+/// `zupy:v1:deposit:{user_id}`. See that function for the 16-account layout,
+/// data format, and validations.
+///
+/// Data: amount (u64) + user_id (u64) + user_bump (u8) + memo (String)
+/// Discriminator: `[139, 158, 73, 234, 109, 218, 37, 182]` (SHA256("global:deposit_from_external"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    execute_external_deposit(program_id, accounts, data, USER_SEED, MemoOp::Deposit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pinocchio::error::ProgramError;
+
+    #[test]
+    fn test_deposit_from_external_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 17];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
@@ -0,0 +1,38 @@
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::COMPANY_SEED;
+use crate::helpers::memo::MemoOp;
+use crate::helpers::transfer_validation::execute_external_deposit;
+
+/// Process `deposit_to_company` instruction.
+///
+/// Company top-up: compresses ZUPY from a corporate wallet's ATA into the
+/// compressed balance owned by the company PDA. Mirrors `deposit_from_external`.
+///
+/// Delegates to [`execute_external_deposit`] with `COMPANY_SEED` and memo
+/// `zupy:v1:deposit_company:{company_id}`. See that function for the 16-account
+/// layout, data format, and validations.
+///
+/// Data: amount (u64) + company_id (u64) + company_bump (u8) + memo (String)
+/// Discriminator: `[105, 18, 104, 231, 120, 142, 27, 161]` (SHA256("global:deposit_to_company"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    execute_external_deposit(program_id, accounts, data, COMPANY_SEED, MemoOp::DepositCompany)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pinocchio::error::ProgramError;
+
+    #[test]
+    fn test_deposit_to_company_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 17];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
@@ -0,0 +1,133 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::{AccountView, Address, ProgramResult};
+use pinocchio::error::ProgramError;
+
+use crate::constants::{INCENTIVE_POOL_SEED, LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, USER_SEED};
+use crate::error::ZupyTokenError;
+use crate::helpers::compressed_accounts::cpi_compressed_transfer;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::memo::{validate_memo_op, MemoOp};
+use crate::helpers::pda::validate_pda_with_seeds;
+use crate::helpers::transfer_validation::validate_transfer_common_compressed;
+
+/// Process `distribute_incentive` instruction (compressed token version).
+///
+/// Pays out from the incentive pool's compressed balance (funded by
+/// `execute_split_transfer` and the withdraw fee) to a user's compressed
+/// balance via Light cToken `Transfer` CPI (Path B: compressed → compressed).
+/// The incentive pool PDA `[INCENTIVE_POOL_SEED]` is the compressed owner and
+/// CPI signer; it must match `token_state.incentive_pool()`. The cToken program
+/// rejects amounts above the pool's compressed balance.
+///
+/// Accounts (8):
+///   0. transfer_authority (signer)      — must match TRANSFER_AUTHORITY_PUBKEY
+///   1. token_state (read)               — our program's token_state PDA
+///   2. mint (read)                      — ZUPY Token-2022 mint
+///   3. incentive_pool (read)            — PDA [INCENTIVE_POOL_SEED], source owner / PDA signer
+///   4. user_pda (read)                  — PDA [USER_SEED, user_id], destination owner
+///   5. fee_payer (writable, signer)     — pays Light state tree fees
+///   6. system_program (read)
+///   7. compressed_token_program (read)  — cTokenmWW8bLPjZEBAUgYy3zKxQZW6VKi7bqNFEVv3m
+///
+/// Data: user_id (u64) + amount (u64) + user_bump (u8) + incentive_bump (u8)
+///       + memo (String, `zupy:v1:incentive:{id}`)
+/// Discriminator: `[172, 211, 121, 130, 226, 29, 17, 199]` (SHA256("global:distribute_incentive"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (8 accounts minimum) ─────────────────────────
+    if accounts.len() < 8 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let transfer_authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let mint = &accounts[2];
+    let incentive_pool = &accounts[3]; // source owner / PDA signer
+    let user_pda = &accounts[4];       // destination owner
+    let fee_payer = &accounts[5];
+    let system_program = &accounts[6];
+    let compressed_token_program = &accounts[7];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let user_id_u64 = reader.read_u64()?;
+    let amount = reader.read_u64()?;
+    let user_bump = reader.read_u8()?;
+    let incentive_bump = reader.read_u8()?;
+    let memo = reader.read_string()?;
+    reader.finish()?;
+
+    // ── Input validation ────────────────────────────────────────────────
+    if amount == 0 {
+        return Err(ZupyTokenError::ZeroAmount.into());
+    }
+    validate_memo_op(memo, MemoOp::Incentive)?;
+
+    // ── Common transfer validation (checks 1–8) ─────────────────────────
+    let validation = validate_transfer_common_compressed(
+        program_id,
+        token_state_account,
+        transfer_authority,
+        mint,
+    )?;
+
+    // ── Check 9: compressed_token_program is Light cToken program ───────
+    let light_ctoken_addr = Address::from(LIGHT_COMPRESSED_TOKEN_PROGRAM_ID);
+    if compressed_token_program.address() != &light_ctoken_addr {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    // ── Incentive pool validation (source) ──────────────────────────────
+    if incentive_pool.address().as_ref() != validation.state.incentive_pool() {
+        return Err(ZupyTokenError::InvalidIncentivePool.into());
+    }
+    validate_pda_with_seeds(
+        incentive_pool.address(),
+        &[INCENTIVE_POOL_SEED, &[incentive_bump]],
+        program_id,
+    )?;
+
+    // ── PDA validation: user_pda (destination) ──────────────────────────
+    let user_id_bytes = user_id_u64.to_le_bytes();
+    validate_pda_with_seeds(
+        user_pda.address(),
+        &[USER_SEED, &user_id_bytes, &[user_bump]],
+        program_id,
+    )?;
+
+    // ── CPI: compressed transfer (incentive pool PDA signs) ─────────────
+    let bump_bytes = [incentive_bump];
+    let signer_seeds: [Seed; 2] = [
+        Seed::from(INCENTIVE_POOL_SEED),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    let signer = Signer::from(&signer_seeds);
+
+    cpi_compressed_transfer(
+        compressed_token_program,
+        fee_payer,
+        incentive_pool, // source
+        user_pda,       // destination
+        incentive_pool, // authority (source PDA signs)
+        system_program,
+        amount,
+        &[signer],
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process_returns_not_enough_account_keys() {
+        let program_id = Address::default();
+        let data = [0u8; 17];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+}
@@ -3,12 +3,14 @@ use pinocchio::{AccountView, Address, ProgramResult};
 use pinocchio::error::ProgramError;
 
 use crate::constants::{COMPANY_SEED, INCENTIVE_POOL_SEED, LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, USER_SEED};
-use crate::error::ZupyTokenError;
+use crate::error::zupy_err;
 use crate::helpers::compressed_accounts::{cpi_compressed_burn, cpi_compressed_transfer};
-use crate::helpers::instruction_data::{parse_string, parse_u64, parse_u8};
+use crate::helpers::heartbeat::stamp_last_op;
+use crate::helpers::instruction_data::Reader;
 use crate::helpers::pda::validate_pda_with_seeds;
 use crate::helpers::transfer_validation::validate_transfer_common_compressed;
-use crate::instructions::split_math::calculate_split;
+use crate::instructions::split_math::{calculate_split, calculate_split_bps};
+use crate::state::token_state::LastOp;
 
 /// Process `execute_split_transfer` instruction.
 ///
@@ -16,6 +18,11 @@ use crate::instructions::split_math::calculate_split;
 ///   User (compressed) → Company (83.3%) + Incentive Pool (8.3%) + Burn (8.3%)
 /// Three Light cToken CPIs: 2× Transfer + 1× Burn, all signed by user_pda.
 ///
+/// If the optional trailing `split_bps` is present, the incentive leg is
+/// `split_bps` basis points of `z_total` instead, bounded by the treasury-set
+/// `[min_split_bps, max_split_bps]` range in TokenState; the company leg takes
+/// the remainder (including rounding dust). The burn leg is unchanged.
+///
 /// Accounts (9+):
 ///   0. transfer_authority (signer)
 ///   1. token_state (read)
@@ -30,7 +37,7 @@ use crate::instructions::split_math::calculate_split;
 ///
 /// Data: user_id_u64 (u64) + company_id_u64 (u64) + z_total (u64)
 ///       + user_bump (u8) + company_bump (u8) + incentive_bump (u8)
-///       + operation_type (String)
+///       + operation_type (String) + split_bps (u16, optional)
 ///
 /// Discriminator: [51, 254, 61, 214, 234, 138, 101, 214] — UNCHANGED (AC6)
 pub fn process(
@@ -54,24 +61,27 @@ pub fn process(
     // accounts[9..] = Light system accounts (merkle tree, nullifier queue, etc.)
 
     // ── Parse instruction data ──────────────────────────────────────────
-    let user_id_u64 = parse_u64(data, 0)?;
-    let company_id_u64 = parse_u64(data, 8)?;
-    let z_total = parse_u64(data, 16)?;
-    let user_bump = parse_u8(data, 24)?;
-    let company_bump = parse_u8(data, 25)?;
-    let incentive_bump = parse_u8(data, 26)?;
-    let (operation_type, _) = parse_string(data, 27)?;
+    let mut reader = Reader::new(data);
+    let user_id_u64 = reader.read_u64()?;
+    let company_id_u64 = reader.read_u64()?;
+    let z_total = reader.read_u64()?;
+    let user_bump = reader.read_u8()?;
+    let company_bump = reader.read_u8()?;
+    let incentive_bump = reader.read_u8()?;
+    let operation_type = reader.read_string()?;
+    let split_bps = if reader.is_empty() { None } else { Some(reader.read_u16()?) };
+    reader.finish()?;
 
     // ── Input validation ────────────────────────────────────────────────
     if z_total == 0 {
-        return Err(ZupyTokenError::ZeroAmount.into());
+        return Err(zupy_err!(ZeroAmount, "z_total"));
     }
     if operation_type != "mixed_payment" && operation_type != "z_direct" {
-        return Err(ZupyTokenError::InvalidOperationType.into());
+        return Err(zupy_err!(InvalidOperationType, "operation_type"));
     }
 
     // ── Common transfer validation (compressed variant: checks 1–8) ──────
-    validate_transfer_common_compressed(
+    let validation = validate_transfer_common_compressed(
         program_id,
         token_state_account,
         transfer_authority,
@@ -81,7 +91,7 @@ pub fn process(
     // ── Check 9: compressed_token_program is Light cToken program ────────
     let light_ctoken_addr = Address::from(LIGHT_COMPRESSED_TOKEN_PROGRAM_ID);
     if compressed_token_program.address() != &light_ctoken_addr {
-        return Err(ZupyTokenError::InvalidTokenProgram.into());
+        return Err(zupy_err!(InvalidTokenProgram, "ctoken_program"));
     }
 
     // ── PDA validation: user_pda (source) ───────────────────────────────
@@ -108,7 +118,15 @@ pub fn process(
     )?;
 
     // ── Split calculation (AC1–3, AC4 reused unchanged) ─────────────────
-    let split = calculate_split(z_total)?;
+    let split = match split_bps {
+        Some(bps) => {
+            if !validation.state.within_split_range(bps) {
+                return Err(zupy_err!(InvalidSplitRatio, "split_bps"));
+            }
+            calculate_split_bps(z_total, bps)?
+        }
+        None => calculate_split(z_total)?,
+    };
 
     // ── CPI signer seeds: user_pda signs all 3 CPIs ──────────────────────
     let user_bump_bytes = [user_bump];
@@ -171,6 +189,9 @@ pub fn process(
         &[signer3],
     )?;
 
+    // ── Heartbeat: last_transfer_ts (v2, writable token_state only) ─────
+    stamp_last_op(token_state_account, LastOp::Transfer)?;
+
     Ok(())
 }
 
@@ -194,7 +215,7 @@ mod tests {
         let program_id = Address::from([1u8; 32]);
         let data = [0u8; 27];
         fn make_buf(addr: [u8; 32]) -> Vec<u64> {
-            let words = (core::mem::size_of::<RuntimeAccount>() + 7) / 8 + 1;
+            let words = core::mem::size_of::<RuntimeAccount>().div_ceil(8) + 1;
             let mut buf = vec![0u64; words];
             let raw = buf.as_mut_ptr() as *mut RuntimeAccount;
             unsafe {
@@ -0,0 +1,84 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::TOKEN_STATE_SEED;
+use crate::helpers::cpi::cpi_clear_metadata_update_authority;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::metadata::require_metadata_updatable;
+use crate::helpers::transfer_validation::validate_metadata_accounts;
+use crate::state::token_state::TokenStateMut;
+
+/// Process `freeze_metadata` instruction.
+///
+/// Makes name/symbol/uri permanently immutable: sets the Token-2022 metadata
+/// update authority to None via spl-token-metadata-interface UpdateAuthority
+/// CPI (token_state PDA signs) and records `metadata_frozen` in TokenState.
+/// Later `update_metadata_field(s)` calls fail with `MetadataFrozen` before
+/// any CPI; freezing twice fails the same way.
+///
+/// Legacy TokenState accounts without the metadata flags still freeze on the
+/// Token-2022 side, but nothing is recorded.
+///
+/// Accounts (4):
+///   0. authority (writable, signer) — must be token_state.treasury()
+///   1. token_state (writable) — PDA [TOKEN_STATE_SEED]
+///   2. mint (writable) — Token-2022 mint with MetadataPointer
+///   3. token_program (read) — Token-2022
+///
+/// Data: none
+/// Discriminator: [8, 61, 146, 255, 94, 108, 158, 83] (SHA256("global:freeze_metadata"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (4 accounts) ─────────────────────────────────
+    if accounts.len() < 4 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let mint = &accounts[2];
+    let token_program = &accounts[3];
+
+    // ── Parse instruction data (none) ───────────────────────────────────
+    Reader::new(data).finish()?;
+
+    // ── Metadata account validation (treasury + mint + token_program) ────
+    let state = validate_metadata_accounts(
+        program_id, authority, token_state_account, mint, token_program,
+    )?;
+    let bump = state.bump();
+    require_metadata_updatable(&state)?;
+
+    // ── CPI: Clear update authority (token_state PDA signs) ─────────────
+    let bump_bytes = [bump];
+    let signer_seeds: [Seed; 2] = [
+        Seed::from(TOKEN_STATE_SEED),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    let signer = Signer::from(&signer_seeds);
+
+    cpi_clear_metadata_update_authority(mint, token_state_account, token_program, &[signer])?;
+
+    // ── Mark metadata frozen ────────────────────────────────────────────
+    if state.metadata_frozen().is_some() {
+        TokenStateMut::from_slice(unsafe { token_state_account.borrow_unchecked_mut() })
+            .set_metadata_frozen(true);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_freeze_metadata_not_enough_account_keys() {
+        let program_id = Address::default();
+        let result = process(&program_id, &[], &[]);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+}
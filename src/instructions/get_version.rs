@@ -0,0 +1,143 @@
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::helpers::instruction_data::Reader;
+use crate::state::token_state::TOKEN_STATE_VERSION_2;
+
+/// Build identity returned by `get_version`: 12 bytes in declaration order,
+/// `semver [u8; 3]` + `git_hash_prefix [u8; 8]` + `layout_version u8`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProgramVersion {
+    /// Crate `major.minor.patch` (`CARGO_PKG_VERSION_*`).
+    pub semver: [u8; 3],
+    /// First 8 ASCII chars of `ZUPY_GIT_HASH` at build time; zeros when unset.
+    pub git_hash_prefix: [u8; 8],
+    /// Current TokenState layout version.
+    pub layout_version: u8,
+}
+
+impl ProgramVersion {
+    pub const LEN: usize = 12;
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut out = [0u8; Self::LEN];
+        out[0..3].copy_from_slice(&self.semver);
+        out[3..11].copy_from_slice(&self.git_hash_prefix);
+        out[11] = self.layout_version;
+        out
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        let data: &[u8; Self::LEN] = data.try_into().ok()?;
+        let mut semver = [0u8; 3];
+        semver.copy_from_slice(&data[0..3]);
+        let mut git_hash_prefix = [0u8; 8];
+        git_hash_prefix.copy_from_slice(&data[3..11]);
+        Some(Self { semver, git_hash_prefix, layout_version: data[11] })
+    }
+}
+
+/// This build's version, fixed at compile time. The deploy pipeline sets
+/// `ZUPY_GIT_HASH=$(git rev-parse HEAD)` for `cargo build-sbf`.
+pub const PROGRAM_VERSION: ProgramVersion = ProgramVersion {
+    semver: [
+        parse_u8(env!("CARGO_PKG_VERSION_MAJOR")),
+        parse_u8(env!("CARGO_PKG_VERSION_MINOR")),
+        parse_u8(env!("CARGO_PKG_VERSION_PATCH")),
+    ],
+    git_hash_prefix: hash_prefix(option_env!("ZUPY_GIT_HASH")),
+    layout_version: TOKEN_STATE_VERSION_2,
+};
+
+/// Decimal `u8` from a version component; panics at compile time otherwise.
+const fn parse_u8(s: &str) -> u8 {
+    let bytes = s.as_bytes();
+    assert!(!bytes.is_empty() && bytes.len() <= 3);
+    let mut value: u16 = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        assert!(bytes[i].is_ascii_digit());
+        value = value * 10 + (bytes[i] - b'0') as u16;
+        i += 1;
+    }
+    assert!(value <= u8::MAX as u16);
+    value as u8
+}
+
+/// First 8 bytes of `hash`, zero-padded; all zeros when unset.
+const fn hash_prefix(hash: Option<&str>) -> [u8; 8] {
+    let mut out = [0u8; 8];
+    if let Some(hash) = hash {
+        let bytes = hash.as_bytes();
+        let mut i = 0;
+        while i < out.len() && i < bytes.len() {
+            out[i] = bytes[i];
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Process `get_version` instruction.
+///
+/// Sets the program return data to [`PROGRAM_VERSION`] (12 bytes, see
+/// [`ProgramVersion`]) so a deploy pipeline can check which build is live
+/// with a simulated call. Read-only and permissionless.
+///
+/// Accounts: none (any passed are ignored)
+///
+/// Data: none
+/// Discriminator: `[168, 85, 244, 45, 81, 56, 130, 50]` (SHA256("global:get_version"))
+pub fn process(
+    _program_id: &Address,
+    _accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    Reader::new(data).finish()?;
+    pinocchio::cpi::set_return_data(&PROGRAM_VERSION.to_bytes());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_program_version_encoding_is_stable() {
+        let version = ProgramVersion {
+            semver: [1, 2, 3],
+            git_hash_prefix: *b"8f6f5f4a",
+            layout_version: 2,
+        };
+        assert_eq!(
+            version.to_bytes(),
+            [1, 2, 3, b'8', b'f', b'6', b'f', b'5', b'f', b'4', b'a', 2]
+        );
+        assert_eq!(ProgramVersion::from_bytes(&version.to_bytes()), Some(version));
+        assert_eq!(ProgramVersion::from_bytes(&[0u8; 11]), None);
+        assert_eq!(ProgramVersion::from_bytes(&[0u8; 13]), None);
+    }
+
+    #[test]
+    fn test_program_version_matches_crate() {
+        let expected: Vec<u8> = env!("CARGO_PKG_VERSION")
+            .split('.')
+            .map(|part| part.parse().unwrap())
+            .collect();
+        assert_eq!(PROGRAM_VERSION.semver.to_vec(), expected);
+        assert_eq!(PROGRAM_VERSION.layout_version, TOKEN_STATE_VERSION_2);
+    }
+
+    #[test]
+    fn test_hash_prefix() {
+        assert_eq!(hash_prefix(None), [0u8; 8]);
+        assert_eq!(hash_prefix(Some("abc")), *b"abc\0\0\0\0\0");
+        assert_eq!(hash_prefix(Some("8f6f5f4a1e591e1")), *b"8f6f5f4a");
+    }
+
+    #[test]
+    fn test_parse_u8() {
+        assert_eq!(parse_u8("0"), 0);
+        assert_eq!(parse_u8("12"), 12);
+        assert_eq!(parse_u8("255"), 255);
+    }
+}
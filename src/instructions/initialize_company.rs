@@ -0,0 +1,117 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::COMPANY_STATE_SEED;
+use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::cpi::cpi_create_account;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::pda::{derive_company_state_pda, validate_pda};
+use crate::helpers::transfer_validation::validate_token_state_base;
+use crate::state::company_state::{
+    CompanyStateMut, COMPANY_STATE_DISCRIMINATOR, COMPANY_STATE_SIZE,
+};
+
+/// Process `initialize_company` instruction.
+///
+/// Registers `company_id` on-chain: creates its CompanyState PDA (50 bytes)
+/// with the id, registration time, `flags`, bump and zeroed balance counters.
+/// The company PDA `[COMPANY_SEED, company_id]` owns compressed tokens and
+/// stays data-less, so the record lives at a companion seed. A company can be
+/// registered once; re-initialization fails with `AlreadyInitialized`. Company
+/// transfers work whether or not the company is registered and only update the
+/// counters when the CompanyState is passed.
+///
+/// Accounts (4):
+///   0. authority (writable, signer) — treasury or transfer_authority; payer
+///   1. token_state (read) — PDA [TOKEN_STATE_SEED]
+///   2. company_state (writable) — PDA [COMPANY_STATE_SEED, &company_id.to_le_bytes()]
+///   3. system_program (read)
+///
+/// Data: company_id (u64) + flags (u8)
+/// Discriminator: `[75, 156, 55, 94, 184, 64, 58, 30]` (SHA256("global:initialize_company"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (4 accounts) ─────────────────────────────────
+    if accounts.len() < 4 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let company_state = &accounts[2];
+    let _system_program = &accounts[3];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let company_id = reader.read_u64()?;
+    let flags = reader.read_u8()?;
+    reader.finish()?;
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+
+    // ── Authorization: treasury or transfer_authority ───────────────────
+    if !authority.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    let authority_key: &[u8; 32] = address_bytes(authority.address());
+    if !state.is_treasury(authority_key) && !state.is_transfer_authority(authority_key) {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+
+    // ── PDA validation ──────────────────────────────────────────────────
+    let (expected_pda, bump) = derive_company_state_pda(program_id, company_id);
+    validate_pda(company_state.address(), &expected_pda)?;
+
+    // ── Init guard: company must not already be registered ──────────────
+    if company_state.data_len() > 0 {
+        return Err(ZupyTokenError::AlreadyInitialized.into());
+    }
+
+    // ── CPI: Create account (50 bytes) ──────────────────────────────────
+    let company_id_bytes = company_id.to_le_bytes();
+    let bump_bytes = [bump];
+    let signer_seeds: [Seed; 3] = [
+        Seed::from(COMPANY_STATE_SEED),
+        Seed::from(company_id_bytes.as_ref()),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    cpi_create_account(
+        authority,
+        company_state,
+        COMPANY_STATE_SIZE as u64,
+        program_id,
+        &[Signer::from(&signer_seeds)],
+    )?;
+
+    // ── Initialize state fields ─────────────────────────────────────────
+    use pinocchio::sysvars::Sysvar as _;
+    let clock = pinocchio::sysvars::clock::Clock::get()?;
+
+    let mut company =
+        CompanyStateMut::from_slice(unsafe { company_state.borrow_unchecked_mut() });
+    company.set_discriminator(&COMPANY_STATE_DISCRIMINATOR);
+    company.set_company_id(company_id);
+    company.set_created_at(clock.unix_timestamp);
+    company.set_flags(flags);
+    company.set_bump(bump);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initialize_company_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 9];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
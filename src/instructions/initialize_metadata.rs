@@ -5,21 +5,29 @@ use pinocchio::{AccountView, Address, ProgramResult};
 use crate::constants::TOKEN_STATE_SEED;
 use crate::error::ZupyTokenError;
 use crate::helpers::cpi::cpi_initialize_metadata;
-use crate::helpers::instruction_data::parse_string;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::metadata::{
+    validate_metadata_name, validate_metadata_symbol, validate_metadata_uri,
+};
 use crate::helpers::transfer_validation::validate_metadata_accounts;
+use crate::state::token_state::TokenStateMut;
 
 /// Process `initialize_metadata` instruction.
 ///
 /// Initializes Token-2022 metadata via spl-token-metadata-interface CPI.
 /// token_state PDA signs via invoke_signed.
 ///
+/// Runs once: a repeat call fails with `MetadataAlreadyInitialized` before the
+/// CPI. Legacy TokenState accounts without the `metadata_initialized` byte skip
+/// the check and rely on Token-2022 rejecting the second initialization.
+///
 /// Accounts (4):
 ///   0. authority (writable, signer) — must be token_state.treasury()
-///   1. token_state (read) — PDA [TOKEN_STATE_SEED]
+///   1. token_state (writable) — PDA [TOKEN_STATE_SEED]
 ///   2. mint (writable) — Token-2022 mint with MetadataPointer
 ///   3. token_program (read) — Token-2022
 ///
-/// Data: name (String) + symbol (String) + uri (String)
+/// Data: name (String) + symbol (String) + uri (String, `https://` or `ipfs://`)
 pub fn process(
     program_id: &Address,
     accounts: &[AccountView],
@@ -35,25 +43,29 @@ pub fn process(
     let token_program = &accounts[3];
 
     // ── Parse instruction data ──────────────────────────────────────────
-    let (name, offset) = parse_string(data, 0)?;
-    let (symbol, offset) = parse_string(data, offset)?;
-    let (uri, _) = parse_string(data, offset)?;
+    let mut reader = Reader::new(data);
+    let name = reader.read_string()?;
+    let symbol = reader.read_string()?;
+    let uri = reader.read_string()?;
+    reader.finish()?;
 
-    // ── String validation ───────────────────────────────────────────────
-    if name.is_empty() || name.len() > 32 {
-        return Err(ZupyTokenError::InvalidMetadataName.into());
-    }
-    if symbol.is_empty() || symbol.len() > 10 {
-        return Err(ZupyTokenError::InvalidMetadataSymbol.into());
-    }
-    if uri.is_empty() || uri.len() > 200 {
-        return Err(ZupyTokenError::InvalidMetadataUri.into());
-    }
+    // ── String validation (lengths, URI scheme) ─────────────────────────
+    validate_metadata_name(name)?;
+    validate_metadata_symbol(symbol)?;
+    validate_metadata_uri(uri)?;
 
     // ── Metadata account validation (treasury + mint + token_program) ────
-    let bump = validate_metadata_accounts(
+    let state = validate_metadata_accounts(
         program_id, authority, token_state_account, mint, token_program,
     )?;
+    let bump = state.bump();
+
+    // ── Idempotency guard ───────────────────────────────────────────────
+    let tracks_flag = match state.metadata_initialized() {
+        Some(true) => return Err(ZupyTokenError::MetadataAlreadyInitialized.into()),
+        Some(false) => true,
+        None => false,
+    };
 
     // ── CPI: Initialize metadata (token_state PDA signs) ────────────────
     let bump_bytes = [bump];
@@ -73,5 +85,11 @@ pub fn process(
         &[signer],
     )?;
 
+    // ── Mark metadata initialized ───────────────────────────────────────
+    if tracks_flag {
+        TokenStateMut::from_slice(unsafe { token_state_account.borrow_unchecked_mut() })
+            .set_metadata_initialized(true);
+    }
+
     Ok(())
 }
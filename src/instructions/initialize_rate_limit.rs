@@ -2,17 +2,24 @@ use pinocchio::cpi::{Seed, Signer};
 use pinocchio::error::ProgramError;
 use pinocchio::{AccountView, Address, ProgramResult};
 
-use crate::constants::{RATE_LIMIT_SEED, SECONDS_PER_DAY};
+use crate::constants::{
+    DEFAULT_RATE_LIMIT_MAX_OPERATIONS, DEFAULT_RATE_LIMIT_WINDOW_SECS, RATE_LIMIT_SEED,
+    SECONDS_PER_DAY,
+};
 use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
 use crate::helpers::cpi::cpi_create_account;
+use crate::helpers::instruction_data::Reader;
 use crate::helpers::pda::{derive_rate_limit_pda, validate_pda};
 use crate::state::rate_limit_state::{
-    RateLimitStateMut, RATE_LIMIT_STATE_DISCRIMINATOR, RATE_LIMIT_STATE_SIZE,
+    RateLimitStateMut, RATE_LIMIT_STATE_DISCRIMINATOR, RATE_LIMIT_STATE_SIZE_WITH_OPS_WINDOW,
 };
 
 /// Process `initialize_rate_limit` instruction.
 ///
-/// Creates a per-authority RateLimitState PDA account (57 bytes).
+/// Creates a per-authority RateLimitState PDA account (105 bytes, NFT mint
+/// and operation windows included). The operation window starts now with
+/// `DEFAULT_RATE_LIMIT_WINDOW_SECS` / `DEFAULT_RATE_LIMIT_MAX_OPERATIONS`.
 /// No instruction data beyond discriminator.
 ///
 /// Accounts (3):
@@ -22,7 +29,7 @@ use crate::state::rate_limit_state::{
 pub fn process(
     program_id: &Address,
     accounts: &[AccountView],
-    _data: &[u8],
+    data: &[u8],
 ) -> ProgramResult {
     // ── Account extraction (3 accounts) ─────────────────────────────────
     if accounts.len() < 3 {
@@ -32,13 +39,16 @@ pub fn process(
     let rate_limit_state = &accounts[1];
     let _system_program = &accounts[2];
 
+    // ── Parse instruction data (none) ───────────────────────────────────
+    Reader::new(data).finish()?;
+
     // ── Signer check ────────────────────────────────────────────────────
     if !authority.is_signer() {
         return Err(ZupyTokenError::InvalidAuthority.into());
     }
 
     // ── PDA validation ──────────────────────────────────────────────────
-    let auth_key: &[u8; 32] = authority.address().as_ref().try_into().unwrap();
+    let auth_key: &[u8; 32] = address_bytes(authority.address());
     let (expected_pda, bump) = derive_rate_limit_pda(program_id, auth_key);
     validate_pda(rate_limit_state.address(), &expected_pda)?;
 
@@ -47,7 +57,7 @@ pub fn process(
         return Err(ZupyTokenError::AlreadyInitialized.into());
     }
 
-    // ── CPI: Create account (57 bytes) ──────────────────────────────────
+    // ── CPI: Create account (105 bytes) ─────────────────────────────────
     let bump_bytes = [bump];
     let signer_seeds: [Seed; 3] = [
         Seed::from(RATE_LIMIT_SEED),
@@ -59,7 +69,7 @@ pub fn process(
     cpi_create_account(
         authority,
         rate_limit_state,
-        RATE_LIMIT_STATE_SIZE as u64,
+        RATE_LIMIT_STATE_SIZE_WITH_OPS_WINDOW as u64,
         program_id,
         &[signer],
     )?;
@@ -77,6 +87,12 @@ pub fn process(
     state.set_current_day(current_day);
     state.set_minted_today(0);
     state.set_bump(bump);
+    state.set_nft_window_start(clock.unix_timestamp);
+    state.set_nft_mints(0);
+    state.set_window_seconds(DEFAULT_RATE_LIMIT_WINDOW_SECS);
+    state.set_max_operations(DEFAULT_RATE_LIMIT_MAX_OPERATIONS);
+    state.set_current_count(0);
+    state.set_window_start(clock.unix_timestamp);
 
     Ok(())
 }
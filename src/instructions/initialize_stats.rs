@@ -0,0 +1,99 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::STATS_SEED;
+use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::cpi::cpi_create_account;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::pda::{derive_stats_pda, validate_pda};
+use crate::helpers::transfer_validation::validate_token_state_base;
+use crate::state::stats_state::{StatsStateMut, STATS_STATE_DISCRIMINATOR, STATS_STATE_SIZE};
+
+/// Process `initialize_stats` instruction.
+///
+/// Creates the global Stats PDA (49 bytes) with zeroed volume counters. Once
+/// it exists, clients may append it to pool, company and withdraw instructions
+/// to keep the counters (see `helpers::stats`). Created once; a second call
+/// fails with `AlreadyInitialized`. Only the treasury wallet can create it.
+///
+/// Accounts (4):
+///   0. authority (writable, signer) — must be token_state.treasury(); payer
+///   1. token_state (read) — PDA [TOKEN_STATE_SEED]
+///   2. stats (writable) — PDA [STATS_SEED]
+///   3. system_program (read)
+///
+/// No instruction data beyond discriminator.
+/// Discriminator: `[144, 201, 117, 76, 127, 118, 176, 16]` (SHA256("global:initialize_stats"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (4 accounts) ─────────────────────────────────
+    if accounts.len() < 4 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let stats = &accounts[2];
+    let _system_program = &accounts[3];
+
+    // ── Parse instruction data (none) ───────────────────────────────────
+    Reader::new(data).finish()?;
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+
+    // ── Treasury authorization ──────────────────────────────────────────
+    if !authority.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    let authority_key: &[u8; 32] = address_bytes(authority.address());
+    if !state.is_treasury(authority_key) {
+        return Err(ZupyTokenError::UnauthorizedTreasury.into());
+    }
+
+    // ── PDA validation ──────────────────────────────────────────────────
+    let (expected_pda, bump) = derive_stats_pda(program_id);
+    validate_pda(stats.address(), &expected_pda)?;
+
+    // ── Init guard: account must not already exist ──────────────────────
+    if stats.data_len() > 0 {
+        return Err(ZupyTokenError::AlreadyInitialized.into());
+    }
+
+    // ── CPI: Create account (49 bytes) ──────────────────────────────────
+    let bump_bytes = [bump];
+    let signer_seeds: [Seed; 2] = [
+        Seed::from(STATS_SEED),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    cpi_create_account(
+        authority,
+        stats,
+        STATS_STATE_SIZE as u64,
+        program_id,
+        &[Signer::from(&signer_seeds)],
+    )?;
+
+    // ── Initialize state fields (counters start at zero) ────────────────
+    let mut stats_state = StatsStateMut::from_slice(unsafe { stats.borrow_unchecked_mut() });
+    stats_state.set_discriminator(&STATS_STATE_DISCRIMINATOR);
+    stats_state.set_bump(bump);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initialize_stats_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let result = process(&program_id, &[], &[]);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
@@ -3,15 +3,19 @@ use pinocchio::error::ProgramError;
 use pinocchio::{AccountView, Address, ProgramResult};
 
 use crate::constants::{
-    DAILY_AUTO_LIMIT, PER_TX_AUTO_LIMIT, TOKEN_2022_PROGRAM_ID, TOKEN_DECIMALS, TOKEN_STATE_SEED,
+    DAILY_AUTO_LIMIT, MAX_TOKEN_DECIMALS, PER_TX_AUTO_LIMIT, TOKEN_2022_PROGRAM_ID,
+    TOKEN_DECIMALS, TOKEN_STATE_SEED,
 };
 use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
 use crate::helpers::cpi::{cpi_create_account, cpi_initialize_metadata_pointer, cpi_initialize_mint};
-use crate::helpers::instruction_data::parse_pubkey;
+use crate::helpers::instruction_data::Reader;
 use crate::helpers::pda::{
     derive_distribution_pool_pda, derive_incentive_pool_pda, derive_token_state_pda, validate_pda,
 };
-use crate::state::token_state::{TokenStateMut, TOKEN_STATE_DISCRIMINATOR, TOKEN_STATE_SIZE};
+use crate::state::token_state::{
+    TokenStateMut, TOKEN_STATE_DISCRIMINATOR, TOKEN_STATE_V2_SIZE, TOKEN_STATE_VERSION_2,
+};
 
 /// Mint size for Token-2022 with MetadataPointer extension.
 /// Token-2022 pads base mint to BASE_ACCOUNT_LENGTH (165) before TLV extensions:
@@ -24,7 +28,7 @@ const MINT_WITH_METADATA_POINTER_SIZE: u64 = 234;
 ///
 /// Accounts (8):
 ///   0. authority (writable, signer) — payer
-///   1. token_state (writable) — PDA [TOKEN_STATE_SEED], init 363 bytes
+///   1. token_state (writable) — PDA [TOKEN_STATE_SEED], init 512 bytes (layout v2)
 ///   2. mint (writable, signer) — fresh keypair, Token-2022 mint
 ///   3. pool_ata (writable) — stored in state
 ///   4. treasury_ata (writable) — stored in state
@@ -33,6 +37,11 @@ const MINT_WITH_METADATA_POINTER_SIZE: u64 = 234;
 ///   7. associated_token_program (read)
 ///
 /// Data: treasury (pubkey) + mint_authority (pubkey) + transfer_authority (pubkey)
+///       + optional max_supply (u64; omitted or 0 = uncapped)
+///       + optional decimals (u8; omitted = TOKEN_DECIMALS, requires max_supply)
+///
+/// `max_supply` and `decimals` cannot be changed afterwards: no instruction
+/// writes them again. Decimals above `MAX_TOKEN_DECIMALS` fail with `InvalidDecimals`.
 pub fn process(
     program_id: &Address,
     accounts: &[AccountView],
@@ -52,9 +61,16 @@ pub fn process(
     let _associated_token_program = &accounts[7];
 
     // ── Parse instruction data: 3 pubkeys ───────────────────────────────
-    let (treasury_pubkey, offset) = parse_pubkey(data, 0)?;
-    let (mint_authority_pubkey, offset) = parse_pubkey(data, offset)?;
-    let (transfer_authority_pubkey, _) = parse_pubkey(data, offset)?;
+    let mut reader = Reader::new(data);
+    let treasury_pubkey = reader.read_pubkey()?;
+    let mint_authority_pubkey = reader.read_pubkey()?;
+    let transfer_authority_pubkey = reader.read_pubkey()?;
+    let max_supply = if reader.is_empty() { 0 } else { reader.read_u64()? };
+    let decimals = if reader.is_empty() { TOKEN_DECIMALS } else { reader.read_u8()? };
+    reader.finish()?;
+    if decimals > MAX_TOKEN_DECIMALS {
+        return Err(ZupyTokenError::InvalidDecimals.into());
+    }
 
     // ── Signer checks ──────────────────────────────────────────────────
     if !authority.is_signer() {
@@ -83,7 +99,7 @@ pub fn process(
     let (distribution_pool_pda, _) = derive_distribution_pool_pda(program_id);
     let (incentive_pool_pda, _) = derive_incentive_pool_pda(program_id);
 
-    // ── CPI 1: Create TokenState PDA account (363 bytes) ────────────────
+    // ── CPI 1: Create TokenState PDA account (512 bytes) ────────────────
     let bump_bytes = [bump];
     let signer_seeds: [Seed; 2] = [
         Seed::from(TOKEN_STATE_SEED),
@@ -94,7 +110,7 @@ pub fn process(
     cpi_create_account(
         authority,
         token_state_account,
-        TOKEN_STATE_SIZE as u64,
+        TOKEN_STATE_V2_SIZE as u64,
         program_id,
         &[signer],
     )?;
@@ -115,12 +131,12 @@ pub fn process(
         token_program,
     )?;
 
-    // ── CPI 4: Initialize Mint (decimals=6, authority=token_state PDA) ──
+    // ── CPI 4: Initialize Mint (decimals, authority=token_state PDA) ────
     cpi_initialize_mint(
         mint,
         &expected_pda,         // mint_authority = token_state PDA
         Some(&expected_pda),   // freeze_authority = token_state PDA
-        TOKEN_DECIMALS,
+        decimals,
         &token_2022_addr,
     )?;
 
@@ -134,19 +150,19 @@ pub fn process(
     state.set_transfer_authority(transfer_authority_pubkey);
 
     // Pool/treasury ATAs stored unchecked per spec — validated at use-time by hot-path instructions
-    let pool_ata_key: &[u8; 32] = pool_ata.address().as_ref().try_into().unwrap();
+    let pool_ata_key: &[u8; 32] = address_bytes(pool_ata.address());
     state.set_pool_ata(pool_ata_key);
 
-    let dist_key: &[u8; 32] = distribution_pool_pda.as_ref().try_into().unwrap();
+    let dist_key: &[u8; 32] = address_bytes(&distribution_pool_pda);
     state.set_distribution_pool(dist_key);
 
-    let incentive_key: &[u8; 32] = incentive_pool_pda.as_ref().try_into().unwrap();
+    let incentive_key: &[u8; 32] = address_bytes(&incentive_pool_pda);
     state.set_incentive_pool(incentive_key);
 
-    let treasury_ata_key: &[u8; 32] = treasury_ata.address().as_ref().try_into().unwrap();
+    let treasury_ata_key: &[u8; 32] = address_bytes(treasury_ata.address());
     state.set_treasury_ata(treasury_ata_key);
 
-    let mint_key: &[u8; 32] = mint.address().as_ref().try_into().unwrap();
+    let mint_key: &[u8; 32] = address_bytes(mint.address());
     state.set_mint(mint_key);
 
     state.set_initialized(true);
@@ -156,6 +172,9 @@ pub fn process(
     state.set_daily_minted(0);
     state.set_last_reset_timestamp(0);
     state.set_paused(false);
+    state.set_max_supply(max_supply);
+    state.set_decimals(decimals);
+    state.set_version(TOKEN_STATE_VERSION_2);
 
     Ok(())
 }
@@ -0,0 +1,115 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::USER_STATE_SEED;
+use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::cpi::cpi_create_account;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::pda::{derive_user_state_pda, validate_pda};
+use crate::helpers::transfer_validation::validate_token_state_base;
+use crate::state::user_state::{
+    UserStateMut, USER_STATE_DISCRIMINATOR, USER_STATE_SIZE, USER_STATUS_ACTIVE,
+};
+
+/// Process `initialize_user` instruction.
+///
+/// Registers `user_id` on-chain: creates its UserState PDA (26 bytes) with the
+/// id, registration time, an active status and bump. The user PDA
+/// `[USER_SEED, user_id]` owns compressed tokens and stays data-less, so the
+/// record lives at a companion seed. A user can be registered once;
+/// re-initialization fails with `AlreadyInitialized`. Pool and company
+/// transfers to the user require the record only while `strict_registration`
+/// is on.
+///
+/// Accounts (4):
+///   0. authority (writable, signer) — treasury or transfer_authority; payer
+///   1. token_state (read) — PDA [TOKEN_STATE_SEED]
+///   2. user_state (writable) — PDA [USER_STATE_SEED, &user_id.to_le_bytes()]
+///   3. system_program (read)
+///
+/// Data: user_id (u64)
+/// Discriminator: `[111, 17, 185, 250, 60, 122, 38, 254]` (SHA256("global:initialize_user"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (4 accounts) ─────────────────────────────────
+    if accounts.len() < 4 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let user_state = &accounts[2];
+    let _system_program = &accounts[3];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let user_id = reader.read_u64()?;
+    reader.finish()?;
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+
+    // ── Authorization: treasury or transfer_authority ───────────────────
+    if !authority.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    let authority_key: &[u8; 32] = address_bytes(authority.address());
+    if !state.is_treasury(authority_key) && !state.is_transfer_authority(authority_key) {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+
+    // ── PDA validation ──────────────────────────────────────────────────
+    let (expected_pda, bump) = derive_user_state_pda(program_id, user_id);
+    validate_pda(user_state.address(), &expected_pda)?;
+
+    // ── Init guard: user must not already be registered ─────────────────
+    if user_state.data_len() > 0 {
+        return Err(ZupyTokenError::AlreadyInitialized.into());
+    }
+
+    // ── CPI: Create account (26 bytes) ──────────────────────────────────
+    let user_id_bytes = user_id.to_le_bytes();
+    let bump_bytes = [bump];
+    let signer_seeds: [Seed; 3] = [
+        Seed::from(USER_STATE_SEED),
+        Seed::from(user_id_bytes.as_ref()),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    cpi_create_account(
+        authority,
+        user_state,
+        USER_STATE_SIZE as u64,
+        program_id,
+        &[Signer::from(&signer_seeds)],
+    )?;
+
+    // ── Initialize state fields ─────────────────────────────────────────
+    use pinocchio::sysvars::Sysvar as _;
+    let clock = pinocchio::sysvars::clock::Clock::get()?;
+
+    let mut user = UserStateMut::from_slice(unsafe { user_state.borrow_unchecked_mut() });
+    user.set_discriminator(&USER_STATE_DISCRIMINATOR);
+    user.set_user_id(user_id);
+    user.set_created_at(clock.unix_timestamp);
+    user.set_status(USER_STATUS_ACTIVE);
+    user.set_bump(bump);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initialize_user_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 8];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
@@ -0,0 +1,86 @@
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::cpi::cpi_grow_token_state;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::transfer_validation::validate_token_state_base;
+use crate::state::token_state::{TOKEN_STATE_V2_SIZE, TOKEN_STATE_VERSION_2};
+
+/// Process `migrate_token_state` instruction.
+///
+/// Upgrades a v1 TokenState (any size below `TOKEN_STATE_V2_SIZE`) to layout
+/// v2: grows the account to `TOKEN_STATE_V2_SIZE`, zeroes every byte past the
+/// old length so missing tails read their defaults, and sets `version` to 2.
+/// Existing bytes are left untouched. Accounts created before the metadata
+/// flags existed take `metadata_initialized` from the instruction data, since
+/// the chain cannot tell whether their metadata was set up. Migrating a v2
+/// account fails with `AlreadyInitialized`. Only the treasury wallet can
+/// migrate; fee_payer covers the extra rent.
+///
+/// Accounts (4):
+///   0. authority (signer) — must be token_state.treasury()
+///   1. token_state (writable) — PDA [TOKEN_STATE_SEED]
+///   2. fee_payer (writable, signer) — pays the rent for the grown account
+///   3. system_program (read)
+///
+/// Data: metadata_initialized (bool; ignored when the flags already exist)
+/// Discriminator: `[191, 239, 37, 200, 20, 173, 31, 65]` (SHA256("global:migrate_token_state"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (4 accounts) ─────────────────────────────────
+    if accounts.len() < 4 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let fee_payer = &accounts[2];
+    let _system_program = &accounts[3];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let metadata_initialized = reader.read_bool()?;
+    reader.finish()?;
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+
+    // ── Treasury authorization ──────────────────────────────────────────
+    if !authority.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    let authority_key: &[u8; 32] = address_bytes(authority.address());
+    if !state.is_treasury(authority_key) {
+        return Err(ZupyTokenError::UnauthorizedTreasury.into());
+    }
+    if !fee_payer.is_signer() {
+        return Err(ZupyTokenError::FeePayerNotSigner.into());
+    }
+
+    // ── Version guard: only v1 accounts migrate ─────────────────────────
+    if state.version() >= TOKEN_STATE_VERSION_2 {
+        return Err(ZupyTokenError::AlreadyInitialized.into());
+    }
+
+    // ── Grow to the v2 size, defaulting the new tail ────────────────────
+    cpi_grow_token_state(fee_payer, token_state_account, TOKEN_STATE_V2_SIZE, metadata_initialized)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_token_state_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
@@ -1,37 +1,62 @@
+use pinocchio::cpi::{Seed, Signer};
 use pinocchio::error::ProgramError;
 use pinocchio::{AccountView, Address, ProgramResult};
 
-use crate::constants::{BUBBLEGUM_PROGRAM_ID, SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use crate::constants::{
+    BUBBLEGUM_PROGRAM_ID, SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID, TOKEN_STATE_SEED,
+};
 use crate::error::ZupyTokenError;
-use crate::helpers::cpi::cpi_bubblegum_mint_v1;
-use crate::helpers::instruction_data::parse_string;
+use crate::helpers::coupon::{parse_cnft_royalties, validate_coupon_collection};
+use crate::helpers::cpi::{cpi_bubblegum_mint_to_collection_v1, CnftCollection};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::nft_rate_limit::consume_nft_mints;
+use crate::helpers::pda::{derive_tree_config_pda, validate_pda};
 use crate::helpers::transfer_validation::validate_nft_payer;
 
 /// Process `mint_coupon_cnft` instruction.
 ///
-/// Mints a compressed NFT via manual Bubblegum MintV1 CPI (no mpl-bubblegum dependency).
-/// tree_authority signs directly (regular invoke).
+/// Mints a compressed NFT into the verified coupon collection via manual
+/// Bubblegum MintToCollectionV1 CPI (no mpl-bubblegum dependency), so wallets
+/// group coupons under `token_state.coupon_collection()`. tree_authority signs
+/// directly; the token_state PDA signs as collection authority. A collection
+/// mint other than the configured one (or none configured) fails with
+/// `InvalidCollection`.
 ///
-/// Accounts (10):
+/// An optional royalty tail sets seller_fee_basis_points and up to
+/// `MAX_CNFT_CREATORS` unverified creators (shares summing to 100) so partner
+/// brands show as creators; without it the metadata keeps 0 bps, no creators.
+///
+/// Passing payer's RateLimitState counts the mint against its NFT window
+/// (`RateLimitExceeded` when full).
+///
+/// Accounts (15, +1 optional):
 ///   0. tree_authority (writable, signer)
 ///   1. leaf_owner (read) — NFT recipient
 ///   2. merkle_tree (writable)
-///   3. tree_config (writable)
+///   3. tree_config (writable) — Bubblegum PDA [merkle_tree]
 ///   4. payer (writable, signer)
 ///   5. bubblegum_program (read)
 ///   6. compression_program (read)
 ///   7. log_wrapper (read)
 ///   8. system_program (read)
-///   9. token_state (read) — PDA [TOKEN_STATE_SEED], Audit 12.1
+///   9. token_state (read) — PDA [TOKEN_STATE_SEED], Audit 12.1; collection authority
+///  10. collection_mint (read) — must be token_state.coupon_collection()
+///  11. collection_metadata (writable) — Token Metadata PDA of collection_mint
+///  12. collection_edition (read) — master edition PDA of collection_mint
+///  13. bubblegum_signer (read) — Bubblegum PDA [b"collection_cpi"]
+///  14. token_metadata_program (read) — Metaplex Token Metadata
+///  15. rate_limit (writable, optional) — PDA [RATE_LIMIT_SEED, payer]
 ///
 /// Data: name (String) + symbol (String) + uri (String)
+///       [+ seller_fee_basis_points (u16) + creator_count (u8)
+///        + creator_count × (address ([u8; 32]) + share (u8))]
 pub fn process(
     program_id: &Address,
     accounts: &[AccountView],
     data: &[u8],
 ) -> ProgramResult {
-    // ── Account extraction (10 accounts) ────────────────────────────────
-    if accounts.len() < 10 {
+    // ── Account extraction (15 accounts) ────────────────────────────────
+    if accounts.len() < 15 {
         return Err(ProgramError::NotEnoughAccountKeys);
     }
     let tree_authority = &accounts[0];
@@ -44,11 +69,22 @@ pub fn process(
     let log_wrapper = &accounts[7];
     let system_program = &accounts[8];
     let token_state_account = &accounts[9];
+    let collection = CnftCollection {
+        authority: token_state_account,
+        mint: &accounts[10],
+        metadata: &accounts[11],
+        edition: &accounts[12],
+        bubblegum_signer: &accounts[13],
+        token_metadata_program: &accounts[14],
+    };
+    let rate_limit = accounts.get(15);
 
     // ── Parse instruction data ──────────────────────────────────────────
-    let (name, offset) = parse_string(data, 0)?;
-    let (symbol, offset) = parse_string(data, offset)?;
-    let (uri, _) = parse_string(data, offset)?;
+    let mut reader = Reader::new(data);
+    let name = reader.read_string()?;
+    let symbol = reader.read_string()?;
+    let uri = reader.read_string()?;
+    let royalties = parse_cnft_royalties(&mut reader)?;
 
     // ── Signer check: tree_authority ─────────────────────────────────────
     if !tree_authority.is_signer() {
@@ -56,7 +92,12 @@ pub fn process(
     }
 
     // ── NFT payer validation (signer + token_state + mint_authority) ─────
-    validate_nft_payer(program_id, payer, token_state_account)?;
+    let state = validate_nft_payer(program_id, payer, token_state_account)?;
+
+    // ── NFT mint rate limit (optional) ──────────────────────────────────
+    if let Some(rate_limit) = rate_limit {
+        consume_nft_mints(program_id, rate_limit, payer, 1)?;
+    }
 
     // ── Hardcoded program ID checks ─────────────────────────────────────
     let expected_bubblegum = Address::from(BUBBLEGUM_PROGRAM_ID);
@@ -74,8 +115,20 @@ pub fn process(
         return Err(ZupyTokenError::InvalidTokenProgram.into());
     }
 
-    // ── CPI: Bubblegum MintV1 (regular invoke) ──────────────────────────
-    cpi_bubblegum_mint_v1(
+    // ── tree_config must be Bubblegum's PDA for merkle_tree ─────────────
+    let (expected_tree_config, _) = derive_tree_config_pda(merkle_tree.address());
+    validate_pda(tree_config.address(), &expected_tree_config)?;
+
+    // ── Collection: configured coupon collection + Token Metadata ───────
+    let token_state_bump = validate_coupon_collection(token_state_account, &state, &collection)?;
+
+    // ── CPI: Bubblegum MintToCollectionV1 (token_state PDA signs) ───────
+    let bump_bytes = [token_state_bump];
+    let token_state_seeds: [Seed; 2] = [
+        Seed::from(TOKEN_STATE_SEED),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    cpi_bubblegum_mint_to_collection_v1(
         tree_config,
         leaf_owner,
         merkle_tree,
@@ -85,9 +138,12 @@ pub fn process(
         compression_program,
         system_program,
         bubblegum_program,
+        &collection,
         name,
         symbol,
         uri,
+        &royalties,
+        &[Signer::from(&token_state_seeds)],
     )?;
 
     Ok(())
@@ -0,0 +1,216 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::{
+    BUBBLEGUM_PROGRAM_ID, MAX_CNFT_BATCH, SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID,
+    TOKEN_STATE_SEED,
+};
+use crate::error::ZupyTokenError;
+use crate::helpers::coupon::validate_coupon_collection;
+use crate::helpers::cpi::{cpi_bubblegum_mint_to_collection_v1, CnftCollection, CnftRoyalties};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::nft_rate_limit::consume_nft_mints;
+use crate::helpers::transfer_validation::validate_nft_payer;
+
+/// Entry layout flag: every entry carries its own name + symbol + uri.
+const LAYOUT_PER_ENTRY: u8 = 0;
+/// Entry layout flag: one name + symbol precede the entries, which carry a uri each.
+const LAYOUT_SHARED: u8 = 1;
+
+/// Process `mint_coupon_cnft_batch` instruction.
+///
+/// Batched `mint_coupon_cnft` for campaign drops: mints up to `MAX_CNFT_BATCH`
+/// compressed NFTs to `leaf_owner` with one Bubblegum MintToCollectionV1 CPI
+/// per entry. Signers, token_state, collection and program IDs are validated
+/// once and every entry is parsed before the first CPI; any failure reverts the
+/// whole batch. Batch mints carry no royalties or creators. With payer's
+/// RateLimitState passed, the whole batch counts against its NFT window.
+///
+/// Accounts (15, +1 optional): same as `mint_coupon_cnft`
+///   0. tree_authority (writable, signer)
+///   1. leaf_owner (read) — NFT recipient
+///   2. merkle_tree (writable)
+///   3. tree_config (writable)
+///   4. payer (writable, signer)
+///   5. bubblegum_program (read)
+///   6. compression_program (read)
+///   7. log_wrapper (read)
+///   8. system_program (read)
+///   9. token_state (read) — PDA [TOKEN_STATE_SEED], Audit 12.1; collection authority
+///  10. collection_mint (read) — must be token_state.coupon_collection()
+///  11. collection_metadata (writable)
+///  12. collection_edition (read)
+///  13. bubblegum_signer (read)
+///  14. token_metadata_program (read)
+///  15. rate_limit (writable, optional) — PDA [RATE_LIMIT_SEED, payer]
+///
+/// Data: count (u32 LE, 1..=MAX_CNFT_BATCH) + layout (u8)
+///       layout 0: count × (name (String) + symbol (String) + uri (String))
+///       layout 1: name (String) + symbol (String) + count × uri (String)
+/// Discriminator: [109, 102, 110, 70, 92, 118, 58, 9] (SHA256("global:mint_coupon_cnft_batch"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (15 accounts) ────────────────────────────────
+    if accounts.len() < 15 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let tree_authority = &accounts[0];
+    let leaf_owner = &accounts[1];
+    let merkle_tree = &accounts[2];
+    let tree_config = &accounts[3];
+    let payer = &accounts[4];
+    let bubblegum_program = &accounts[5];
+    let compression_program = &accounts[6];
+    let log_wrapper = &accounts[7];
+    let system_program = &accounts[8];
+    let token_state_account = &accounts[9];
+    let collection = CnftCollection {
+        authority: token_state_account,
+        mint: &accounts[10],
+        metadata: &accounts[11],
+        edition: &accounts[12],
+        bubblegum_signer: &accounts[13],
+        token_metadata_program: &accounts[14],
+    };
+    let rate_limit = accounts.get(15);
+
+    // ── Parse instruction data (every entry, before any CPI) ────────────
+    let mut reader = Reader::new(data);
+    let count = reader.read_u32()? as usize;
+    if count == 0 || count > MAX_CNFT_BATCH {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let shared = match reader.read_u8()? {
+        LAYOUT_PER_ENTRY => None,
+        LAYOUT_SHARED => {
+            let name = reader.read_string()?;
+            let symbol = reader.read_string()?;
+            Some((name, symbol))
+        }
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+    let entries = reader;
+    for _ in 0..count {
+        parse_entry(&mut reader, shared)?;
+    }
+    reader.finish()?;
+
+    // ── Signer check: tree_authority ─────────────────────────────────────
+    if !tree_authority.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+
+    // ── NFT payer validation (signer + token_state + mint_authority) ─────
+    let state = validate_nft_payer(program_id, payer, token_state_account)?;
+
+    // ── NFT mint rate limit (optional, whole batch) ─────────────────────
+    if let Some(rate_limit) = rate_limit {
+        consume_nft_mints(program_id, rate_limit, payer, count as u64)?;
+    }
+
+    // ── Hardcoded program ID checks ─────────────────────────────────────
+    let expected_bubblegum = Address::from(BUBBLEGUM_PROGRAM_ID);
+    if bubblegum_program.address() != &expected_bubblegum {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    let expected_compression = Address::from(SPL_ACCOUNT_COMPRESSION_ID);
+    if compression_program.address() != &expected_compression {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    let expected_noop = Address::from(SPL_NOOP_ID);
+    if log_wrapper.address() != &expected_noop {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    // ── Collection: configured coupon collection + Token Metadata ───────
+    let token_state_bump = validate_coupon_collection(token_state_account, &state, &collection)?;
+    let bump_bytes = [token_state_bump];
+    let token_state_seeds: [Seed; 2] = [
+        Seed::from(TOKEN_STATE_SEED),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+
+    // ── CPIs: one MintToCollectionV1 per entry (token_state PDA signs) ──
+    let mut reader = entries;
+    for _ in 0..count {
+        let (name, symbol, uri) = parse_entry(&mut reader, shared)?;
+        cpi_bubblegum_mint_to_collection_v1(
+            tree_config,
+            leaf_owner,
+            merkle_tree,
+            payer,
+            tree_authority,
+            log_wrapper,
+            compression_program,
+            system_program,
+            bubblegum_program,
+            &collection,
+            name,
+            symbol,
+            uri,
+            &CnftRoyalties::default(),
+            &[Signer::from(&token_state_seeds)],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Reads the next entry as `(name, symbol, uri)`; with a `shared` name +
+/// symbol the entry holds only its uri.
+#[inline(always)]
+fn parse_entry<'a>(
+    reader: &mut Reader<'a>,
+    shared: Option<(&'a str, &'a str)>,
+) -> Result<(&'a str, &'a str, &'a str), ProgramError> {
+    let (name, symbol) = match shared {
+        Some(shared) => shared,
+        None => (reader.read_string()?, reader.read_string()?),
+    };
+    let uri = reader.read_string()?;
+    Ok((name, symbol, uri))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(s: &str) -> Vec<u8> {
+        let mut out = (s.len() as u32).to_le_bytes().to_vec();
+        out.extend_from_slice(s.as_bytes());
+        out
+    }
+
+    #[test]
+    fn test_parse_entry_per_entry_layout() {
+        let mut data = string("Coupon #1");
+        data.extend_from_slice(&string("ZCPN"));
+        data.extend_from_slice(&string("https://zupy.com/cnft/1.json"));
+        let mut reader = Reader::new(&data);
+        let (name, symbol, uri) = parse_entry(&mut reader, None).unwrap();
+        assert_eq!((name, symbol, uri), ("Coupon #1", "ZCPN", "https://zupy.com/cnft/1.json"));
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_parse_entry_shared_layout() {
+        let data = string("https://zupy.com/cnft/2.json");
+        let mut reader = Reader::new(&data);
+        let (name, symbol, uri) = parse_entry(&mut reader, Some(("Zupy Coupon", "ZCPN"))).unwrap();
+        assert_eq!((name, symbol, uri), ("Zupy Coupon", "ZCPN", "https://zupy.com/cnft/2.json"));
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_mint_coupon_cnft_batch_not_enough_account_keys() {
+        let program_id = Address::default();
+        let result = process(&program_id, &[], &[0u8; 5]);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+}
@@ -0,0 +1,45 @@
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::helpers::mint_common::{mint_with_limits, MintTarget};
+
+/// Process `mint_to_pool` instruction.
+///
+/// Rate-limited mint straight into the pool ATA: one MintTo CPI instead of
+/// `mint_tokens` followed by `treasury_restock_pool`. Counts against the same
+/// `per_tx_auto_limit` / `daily_auto_limit` budget as `mint_tokens`.
+/// PDA signer: token_state [TOKEN_STATE_SEED, &[bump]].
+///
+/// Delegates to [`mint_with_limits`] with [`MintTarget::PoolAta`].
+///
+/// Accounts (5):
+///   0. mint_authority (writable, signer) — must match token_state.mint_authority()
+///   1. token_state (writable) — PDA [TOKEN_STATE_SEED], rate limit updates
+///   2. mint (writable) — Token-2022 mint
+///   3. pool_ata (writable) — MintTo destination, must equal token_state.pool_ata()
+///   4. token_program (read) — Token-2022
+///
+/// Optional: treasury (signer) appended after 4 to mint above `daily_auto_limit`.
+///
+/// Data: amount (u64) + memo (String)
+/// Discriminator: [12, 29, 169, 194, 157, 181, 118, 124] (SHA256("global:mint_to_pool"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    mint_with_limits(program_id, accounts, data, MintTarget::PoolAta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pinocchio::error::ProgramError;
+
+    #[test]
+    fn test_mint_to_pool_not_enough_account_keys() {
+        let program_id = Address::default();
+        let data = [0u8; 32];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+}
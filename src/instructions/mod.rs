@@ -1,22 +1,74 @@
 pub mod initialize_token;
 pub mod initialize_metadata;
 pub mod update_metadata_field;
+pub mod update_metadata_fields;
+pub mod freeze_metadata;
 pub mod mint_tokens;
+pub mod mint_to_pool;
 pub mod treasury_restock_pool;
+pub mod rebalance_pools;
+pub mod sweep_dust;
 pub mod transfer_from_pool;
+pub mod transfer_pool_to_company;
+pub mod create_escrow;
+pub mod claim_escrow;
+pub mod cancel_escrow;
+pub mod create_vesting;
+pub mod claim_vested;
+pub mod close_receipt;
+pub mod batch_transfer_from_pool;
 pub mod return_to_pool;
+pub mod batch_return_to_pool;
 pub mod return_to_pool_v1;
 pub mod return_user_to_pool;
 pub mod return_user_to_pool_v1;
+pub mod return_user_to_pool_merged;
 pub mod transfer_company_to_user;
+pub mod transfer_company_to_user_v1;
+pub mod transfer_company_to_company;
 pub mod transfer_user_to_company;
+pub mod transfer_user_to_user;
+pub mod refund_company_to_user;
+pub mod refund_user_to_company;
 pub mod execute_split_transfer;
 pub mod split_math;
+pub mod distribute_incentive;
 pub mod burn_tokens;
 pub mod burn_from_company_pda;
+pub mod burn_pool_tokens;
 pub mod initialize_rate_limit;
+pub mod initialize_company;
+pub mod close_company;
+pub mod initialize_user;
+pub mod migrate_token_state;
+pub mod initialize_stats;
 pub mod set_paused;
+pub mod set_strict_registration;
+pub mod set_split_range;
+pub mod set_user_withdraw_limit;
+pub mod set_v1_sunset;
+pub mod set_withdraw_approval_threshold;
+pub mod set_withdraw_fee;
 pub mod create_zupy_card;
+pub mod revoke_zupy_card;
+pub mod update_card_tier;
+pub mod reissue_zupy_card;
 pub mod create_coupon_nft;
+pub mod redeem_coupon_nft;
+pub mod transfer_coupon_nft;
+pub mod close_coupon;
 pub mod mint_coupon_cnft;
+pub mod mint_coupon_cnft_batch;
+pub mod set_coupon_collection;
+pub mod burn_coupon_cnft;
+pub mod transfer_coupon_cnft;
 pub mod withdraw_to_external;
+pub mod withdraw_to_external_v1;
+pub mod withdraw_to_external_merged;
+pub mod withdraw_company_to_external;
+pub mod request_withdrawal;
+pub mod approve_withdrawal;
+pub mod cancel_withdrawal;
+pub mod deposit_from_external;
+pub mod deposit_to_company;
+pub mod get_version;
@@ -0,0 +1,228 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::{
+    DISTRIBUTION_POOL_SEED, LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, LIGHT_TOKEN_CPI_AUTHORITY,
+    TOKEN_2022_PROGRAM_ID, TOKEN_STATE_SEED,
+};
+use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::compressed_accounts::{
+    cpi_compress_from_spl, cpi_decompress_to_spl, derive_spl_interface_pda,
+};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::math::checked_sub_amount;
+use crate::helpers::memo::{validate_memo_op, MemoOp};
+use crate::helpers::pda::{validate_pda, validate_pda_with_seeds};
+use crate::helpers::transfer_validation::{
+    read_token_balance, validate_source_ata, validate_token_state_base,
+};
+
+/// Direction: pool_ata → distribution_pool.
+pub const REBALANCE_TO_DISTRIBUTION: u8 = 0;
+/// Direction: distribution_pool → pool_ata.
+pub const REBALANCE_TO_POOL: u8 = 1;
+
+/// Process `rebalance_pools` instruction.
+///
+/// Moves tokens between the two program-controlled pools recorded in
+/// TokenState: the SPL `pool_ata` and the compressed balance of the
+/// `distribution_pool` PDA `[DISTRIBUTION_POOL_SEED]`. Pool → distribution
+/// compresses from `pool_ata` signed by the token_state PDA (Path A, as
+/// `transfer_pool_to_company`); distribution → pool decompresses signed by the
+/// distribution pool PDA (Path A reverse, as `return_to_pool`), and the cToken
+/// program rejects amounts above its compressed balance. Treasury-signed; the
+/// memo must be `zupy:v1:rebalance:{id}`.
+///
+/// Accounts (11 minimum):
+///   0.  treasury (signer)                  — must be token_state.treasury()
+///   1.  token_state (read)                 — PDA [TOKEN_STATE_SEED], pool_ata authority
+///   2.  mint (read)                        — ZUPY Token-2022 mint
+///   3.  pool_ata (writable)                — must match token_state.pool_ata()
+///   4.  distribution_pool (read)           — must match token_state.distribution_pool()
+///   5.  fee_payer (writable, signer)       — pays Light Protocol rent/fees
+///   6.  token_program (read)               — Token-2022
+///   7.  system_program (read)
+///   8.  compressed_token_program (read)    — Light cToken program
+///   9.  cpi_authority_pda (read)           — LIGHT_TOKEN_CPI_AUTHORITY
+///   10. spl_interface_pda (writable)       — Light SPL pool PDA (seeds=[b"pool", mint])
+///   11+ Light accounts:
+///       pool → distribution: light_system_program, registered_program_pda,
+///       noop_program, account_compression_authority, account_compression_program
+///       (11–15), then the Merkle tree output queue (16+)
+///       distribution → pool: Light system accounts forwarded to the decompress
+///
+/// Data: direction (u8: 0 = pool→distribution, 1 = distribution→pool)
+///       + amount (u64) + distribution_bump (u8) + memo (String)
+/// Discriminator: `[85, 169, 193, 206, 239, 180, 209, 253]` (SHA256("global:rebalance_pools"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (11 accounts minimum) ─────────────────────────
+    if accounts.len() < 11 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let treasury                 = &accounts[0];
+    let token_state_account      = &accounts[1];
+    let mint                     = &accounts[2];
+    let pool_ata                 = &accounts[3];
+    let distribution_pool        = &accounts[4];
+    let fee_payer                = &accounts[5];
+    let token_program            = &accounts[6];
+    let system_program           = &accounts[7];
+    let compressed_token_prog    = &accounts[8];
+    let cpi_authority_pda        = &accounts[9];
+    let spl_interface_pda        = &accounts[10];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let direction = reader.read_u8()?;
+    let amount = reader.read_u64()?;
+    let distribution_bump = reader.read_u8()?;
+    let memo = reader.read_string()?;
+    reader.finish()?;
+
+    // ── Input validation ────────────────────────────────────────────────
+    if direction != REBALANCE_TO_DISTRIBUTION && direction != REBALANCE_TO_POOL {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    if direction == REBALANCE_TO_DISTRIBUTION && accounts.len() < 16 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    if amount == 0 {
+        return Err(ZupyTokenError::ZeroAmount.into());
+    }
+    validate_memo_op(memo, MemoOp::Rebalance)?;
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+
+    // ── Treasury authorization ──────────────────────────────────────────
+    if !treasury.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    let treasury_key: &[u8; 32] = address_bytes(treasury.address());
+    if !state.is_treasury(treasury_key) {
+        return Err(ZupyTokenError::UnauthorizedTreasury.into());
+    }
+    if !fee_payer.is_signer() {
+        return Err(ZupyTokenError::FeePayerNotSigner.into());
+    }
+
+    // ── Mint validation ─────────────────────────────────────────────────
+    let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
+    if !mint.owned_by(&token_2022_addr) || state.mint() != mint.address().as_ref() {
+        return Err(ZupyTokenError::InvalidMint.into());
+    }
+
+    // ── Pool validation (stored addresses, pool_ata mint + authority) ───
+    if state.pool_ata() != pool_ata.address().as_ref()
+        || state.distribution_pool() != distribution_pool.address().as_ref()
+    {
+        return Err(ZupyTokenError::InvalidPoolAccount.into());
+    }
+    validate_source_ata(pool_ata, mint.address(), token_state_account.address())?;
+    validate_pda_with_seeds(
+        distribution_pool.address(),
+        &[DISTRIBUTION_POOL_SEED, &[distribution_bump]],
+        program_id,
+    )?;
+
+    // ── Program checks ──────────────────────────────────────────────────
+    if token_program.address() != &token_2022_addr {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+    let expected_ctoken: Address = LIGHT_COMPRESSED_TOKEN_PROGRAM_ID.into();
+    if compressed_token_prog.address() != &expected_ctoken {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let expected_ctoken_auth = Address::from(LIGHT_TOKEN_CPI_AUTHORITY);
+    if cpi_authority_pda.address() != &expected_ctoken_auth {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // ── Validate spl_interface_pda address and derive bump ───────────────
+    let mint_key: &[u8; 32] = address_bytes(mint.address());
+    let (expected_spl_pda, spl_bump) = derive_spl_interface_pda(mint_key);
+    validate_pda(spl_interface_pda.address(), &expected_spl_pda)?;
+
+    if direction == REBALANCE_TO_DISTRIBUTION {
+        // ── Balance check ───────────────────────────────────────────────
+        let pool_balance = read_token_balance(pool_ata);
+        if pool_balance < amount {
+            return Err(ZupyTokenError::InsufficientPoolBalance.into());
+        }
+        let remaining_amount = checked_sub_amount(pool_balance, amount)?;
+
+        // ── CPI: Compress pool_ata → distribution pool (token_state signs)
+        let bump_bytes = [state.bump()];
+        let signer_seeds: [Seed; 2] = [
+            Seed::from(TOKEN_STATE_SEED),
+            Seed::from(bump_bytes.as_ref()),
+        ];
+        let signer = Signer::from(&signer_seeds);
+
+        cpi_compress_from_spl(
+            compressed_token_prog,
+            cpi_authority_pda,
+            &accounts[11],              // light_system_program
+            &accounts[12],              // registered_program_pda
+            &accounts[13],              // noop_program
+            &accounts[14],              // account_compression_authority
+            &accounts[15],              // account_compression_program
+            fee_payer,
+            token_state_account,        // authority: token_state PDA that owns pool_ata
+            spl_interface_pda,          // token_pool_pda
+            pool_ata,                   // source_ata
+            token_program,
+            system_program,
+            address_bytes(distribution_pool.address()),
+            Some(remaining_amount),
+            &accounts[16..],            // remaining: Merkle tree output queue
+            &[signer],
+        )?;
+    } else {
+        // ── CPI: Decompress distribution pool → pool_ata (pool PDA signs) ─
+        let bump_bytes = [distribution_bump];
+        let signer_seeds: [Seed; 2] = [
+            Seed::from(DISTRIBUTION_POOL_SEED),
+            Seed::from(bump_bytes.as_ref()),
+        ];
+        let signer = Signer::from(&signer_seeds);
+
+        cpi_decompress_to_spl(
+            compressed_token_prog,
+            cpi_authority_pda,
+            fee_payer,
+            mint,
+            pool_ata,
+            distribution_pool,
+            spl_interface_pda,
+            token_program,
+            system_program,
+            amount,
+            spl_bump,
+            state.decimals(),
+            &accounts[11..],
+            &[signer],
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rebalance_pools_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 14];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
@@ -0,0 +1,271 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::{
+    COMPANY_SEED, LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, USER_PDA_SEED,
+    USER_SEED,
+};
+use crate::error::ZupyTokenError;
+use crate::helpers::account_checks::assert_discriminator;
+use crate::helpers::compressed_accounts::cpi_compressed_transfer;
+use crate::helpers::coupon::{create_coupon_state_if_needed, CouponTerms};
+use crate::helpers::cpi::{cpi_burn, cpi_close_account};
+use crate::helpers::events::emit_coupon_redeem_event;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::memo::{validate_memo_op, MemoOp};
+use crate::helpers::pda::{
+    derive_coupon_mint_pda, derive_user_nft_pda, validate_pda, validate_pda_with_seeds,
+};
+use crate::helpers::transfer_validation::{
+    read_token_balance, validate_source_ata, validate_token_state_base,
+};
+use crate::state::coupon_state::{
+    CouponState, CouponStateMut, COUPON_STATE_DISCRIMINATOR, COUPON_STATE_SIZE,
+};
+
+/// Process `redeem_coupon_nft` instruction.
+///
+/// Consumes a coupon minted by `create_coupon_nft`: burns the coupon token
+/// from the user_pda ATA (user_pda signs), optionally closes that ATA, and sets
+/// `redeemed` in the CouponState PDA, creating it first for coupons minted
+/// without one. Logs `zupy_evt:coupon_redeem:{company_id}:{coupon_ksuid}`.
+///
+/// Coupons carrying a `value_amount` also pay it from the issuer company's
+/// compressed PDA to the bearer's compressed user PDA (Light cToken Transfer,
+/// company PDA signs) in the same transaction as the burn, so a failed
+/// transfer — e.g. an underfunded company — reverts the whole redemption.
+/// Zero-value coupons skip that leg and its accounts.
+///
+/// A second redemption fails with `CouponAlreadyRedeemed`, one after a
+/// non-zero `expires_at` (Clock sysvar) with `CouponExpired`, and a coupon_ksuid
+/// whose mint was never created with `InvalidPDA`. The ATA must hold exactly
+/// the one coupon token, else `InsufficientBalance`.
+///
+/// Accounts (8, +4 for value coupons, +1 optional):
+///   0. transfer_authority (writable, signer) — pays for a missing coupon_state
+///   1. token_state (read) — PDA [TOKEN_STATE_SEED]
+///   2. user_pda (read) — PDA [b"user_pda", &user_ksuid], coupon ATA owner
+///   3. coupon_mint (writable) — PDA [b"coupon", &coupon_ksuid]
+///   4. coupon_ata (writable) — coupon ATA for user_pda
+///   5. coupon_state (writable) — PDA [b"coupon_state", &coupon_ksuid]
+///   6. token_program (read) — Token-2022
+///   7. system_program (read)
+///   Value coupons only:
+///   8. company_pda (read) — PDA [b"company", issuer_company_id], compressed source
+///   9. user_compressed_pda (read) — PDA [b"user", user_id], compressed destination
+///  10. fee_payer (writable, signer) — pays Light state tree fees
+///  11. compressed_token_program (read) — Light cToken
+///   Last (8, or 12 for value coupons):
+///      destination (writable, optional) — closes coupon_ata, receives its rent
+///
+/// Data: user_ksuid ([u8; 27]) + coupon_ksuid ([u8; 27]) + company_id (u64)
+///       + memo (String)
+///       + user_id (u64) + company_bump (u8) + user_bump (u8) (value coupons only)
+/// Discriminator: [20, 168, 33, 154, 93, 33, 255, 212] (SHA256("global:redeem_coupon_nft"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (8 accounts; value leg + destination below) ───
+    if accounts.len() < 8 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let transfer_authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let user_pda = &accounts[2];
+    let coupon_mint = &accounts[3];
+    let coupon_ata = &accounts[4];
+    let coupon_state = &accounts[5];
+    let token_program = &accounts[6];
+    let system_program = &accounts[7];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let user_ksuid = reader.read_bytes::<27>()?;
+    let coupon_ksuid = reader.read_bytes::<27>()?;
+    let company_id = reader.read_u64()?;
+    let memo = reader.read_string()?;
+    // user_id + company_bump + user_bump, sent for value coupons only
+    let value_fields = if reader.is_empty() {
+        None
+    } else {
+        Some((reader.read_u64()?, reader.read_u8()?, reader.read_u8()?))
+    };
+    reader.finish()?;
+    validate_memo_op(memo, MemoOp::Coupon)?;
+
+    // ── Authority: transfer_authority, not paused ───────────────────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+    if state.paused() {
+        return Err(ZupyTokenError::SystemPaused.into());
+    }
+    if !transfer_authority.is_signer()
+        || state.transfer_authority() != transfer_authority.address().as_ref()
+    {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+
+    // ── Token program check ─────────────────────────────────────────────
+    let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
+    if token_program.address() != &token_2022_addr {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    // ── PDA validations: user_pda, coupon_mint (must be minted) ─────────
+    let (expected_user_pda, user_bump) = derive_user_nft_pda(program_id, user_ksuid);
+    validate_pda(user_pda.address(), &expected_user_pda)?;
+
+    let (expected_coupon_mint, _) = derive_coupon_mint_pda(program_id, coupon_ksuid);
+    validate_pda(coupon_mint.address(), &expected_coupon_mint)?;
+    if !coupon_mint.owned_by(&token_2022_addr) || coupon_mint.data_len() == 0 {
+        return Err(ZupyTokenError::InvalidPDA.into());
+    }
+
+    // ── Coupon state: create if missing, reject if redeemed or expired ──
+    create_coupon_state_if_needed(
+        program_id,
+        coupon_state,
+        transfer_authority,
+        coupon_ksuid,
+        &expected_coupon_mint,
+        &expected_user_pda,
+        &CouponTerms::default(),
+    )?;
+    if !coupon_state.owned_by(program_id) || coupon_state.data_len() < COUPON_STATE_SIZE {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let coupon = CouponState::from_slice(unsafe { coupon_state.borrow_unchecked() });
+    assert_discriminator(coupon.discriminator(), &COUPON_STATE_DISCRIMINATOR)?;
+    if coupon.redeemed() {
+        return Err(ZupyTokenError::CouponAlreadyRedeemed.into());
+    }
+    use pinocchio::sysvars::Sysvar as _;
+    let clock = pinocchio::sysvars::clock::Clock::get()?;
+    if coupon.is_expired(clock.unix_timestamp) {
+        return Err(ZupyTokenError::CouponExpired.into());
+    }
+    let value_amount = coupon.value_amount();
+    let issuer_company_id = coupon.issuer_company_id();
+
+    // ── Value leg accounts: issuer company → bearer user PDA ────────────
+    let value_leg = if value_amount > 0 {
+        if accounts.len() < 12 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let (user_id, company_bump, user_bump) =
+            value_fields.ok_or(ProgramError::InvalidInstructionData)?;
+        let leg = ValueLeg {
+            company_pda: &accounts[8],
+            user_compressed_pda: &accounts[9],
+            fee_payer: &accounts[10],
+            compressed_token_program: &accounts[11],
+            company_bump,
+        };
+
+        let light_ctoken_addr = Address::from(LIGHT_COMPRESSED_TOKEN_PROGRAM_ID);
+        if leg.compressed_token_program.address() != &light_ctoken_addr {
+            return Err(ZupyTokenError::InvalidTokenProgram.into());
+        }
+        validate_pda_with_seeds(
+            leg.company_pda.address(),
+            &[COMPANY_SEED, &issuer_company_id.to_le_bytes(), &[leg.company_bump]],
+            program_id,
+        )?;
+        validate_pda_with_seeds(
+            leg.user_compressed_pda.address(),
+            &[USER_SEED, &user_id.to_le_bytes(), &[user_bump]],
+            program_id,
+        )?;
+        Some(leg)
+    } else {
+        None
+    };
+    let destination = accounts.get(if value_leg.is_some() { 12 } else { 8 });
+
+    // ── Coupon ATA: mint + owner, holds the coupon ──────────────────────
+    validate_source_ata(coupon_ata, &expected_coupon_mint, &expected_user_pda)?;
+    if read_token_balance(coupon_ata) != 1 {
+        return Err(ZupyTokenError::InsufficientBalance.into());
+    }
+
+    // ── CPIs: Burn the coupon, optionally close its ATA (user_pda signs) ─
+    let user_bump_bytes = [user_bump];
+    let user_signer_seeds: [Seed; 3] = [
+        Seed::from(USER_PDA_SEED),
+        Seed::from(user_ksuid.as_ref()),
+        Seed::from(user_bump_bytes.as_ref()),
+    ];
+
+    cpi_burn(
+        coupon_ata,
+        coupon_mint,
+        user_pda,
+        1,
+        &token_2022_addr,
+        &[Signer::from(&user_signer_seeds)],
+    )?;
+
+    if let Some(destination) = destination {
+        cpi_close_account(
+            coupon_ata,
+            destination,
+            user_pda,
+            &token_2022_addr,
+            &[Signer::from(&user_signer_seeds)],
+        )?;
+    }
+
+    // ── CPI: Pay value_amount from the issuer company (company PDA signs) ─
+    if let Some(leg) = value_leg {
+        let company_id_bytes = issuer_company_id.to_le_bytes();
+        let company_bump_bytes = [leg.company_bump];
+        let company_signer_seeds: [Seed; 3] = [
+            Seed::from(COMPANY_SEED),
+            Seed::from(company_id_bytes.as_ref()),
+            Seed::from(company_bump_bytes.as_ref()),
+        ];
+        cpi_compressed_transfer(
+            leg.compressed_token_program,
+            leg.fee_payer,
+            leg.company_pda,         // source
+            leg.user_compressed_pda, // destination
+            leg.company_pda,         // authority (source PDA signs)
+            system_program,
+            value_amount,
+            &[Signer::from(&company_signer_seeds)],
+        )?;
+    }
+
+    // ── Mark redeemed ───────────────────────────────────────────────────
+    let mut coupon = CouponStateMut::from_slice(unsafe { coupon_state.borrow_unchecked_mut() });
+    coupon.set_redeemed(true);
+    coupon.set_redeemed_at(clock.unix_timestamp);
+    coupon.set_company_id(company_id);
+
+    emit_coupon_redeem_event(company_id, coupon_ksuid);
+
+    Ok(())
+}
+
+/// Accounts and signer bump for the compressed value transfer of a value coupon.
+struct ValueLeg<'a> {
+    company_pda: &'a AccountView,
+    user_compressed_pda: &'a AccountView,
+    fee_payer: &'a AccountView,
+    compressed_token_program: &'a AccountView,
+    company_bump: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redeem_coupon_nft_not_enough_account_keys() {
+        let program_id = Address::default();
+        let result = process(&program_id, &[], &[0u8; 62]);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+}
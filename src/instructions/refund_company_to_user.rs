@@ -0,0 +1,35 @@
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::helpers::refund_common::{refund_compressed, RefundOf};
+
+/// Process `refund_company_to_user` instruction.
+///
+/// Refunds (fully or partially) an earlier company→user payment: moves tokens from the
+/// user PDA back to the company PDA. The memo must reference the original operation
+/// (`zupy:v1:refund:company_to_user:{id}`).
+///
+/// Delegates to [`refund_compressed`] with [`RefundOf::CompanyToUser`].
+/// See that function for full account layout, data format, and validations.
+///
+/// Discriminator: `[52, 146, 11, 177, 127, 186, 168, 248]` (SHA256("global:refund_company_to_user"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    refund_compressed(program_id, accounts, data, RefundOf::CompanyToUser)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pinocchio::error::ProgramError;
+
+    #[test]
+    fn test_refund_company_to_user_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 34];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
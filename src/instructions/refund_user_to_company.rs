@@ -0,0 +1,35 @@
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::helpers::refund_common::{refund_compressed, RefundOf};
+
+/// Process `refund_user_to_company` instruction.
+///
+/// Refunds (fully or partially) an earlier user→company payment: moves tokens from the
+/// company PDA back to the user PDA. The memo must reference the original operation
+/// (`zupy:v1:refund:user_to_company:{id}`).
+///
+/// Delegates to [`refund_compressed`] with [`RefundOf::UserToCompany`].
+/// See that function for full account layout, data format, and validations.
+///
+/// Discriminator: `[211, 211, 223, 120, 221, 99, 252, 87]` (SHA256("global:refund_user_to_company"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    refund_compressed(program_id, accounts, data, RefundOf::UserToCompany)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pinocchio::error::ProgramError;
+
+    #[test]
+    fn test_refund_user_to_company_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 34];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
@@ -0,0 +1,209 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::{TOKEN_2022_PROGRAM_ID, USER_PDA_SEED, ZUPY_CARD_SEED};
+use crate::error::ZupyTokenError;
+use crate::helpers::account_checks::{assert_associated_token_address, assert_discriminator};
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::cpi::{cpi_burn, cpi_create_ata_idempotent, cpi_grow_account, cpi_mint_to};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::pda::{
+    derive_user_nft_pda, derive_zupy_card_mint_pda, derive_zupy_card_pda, validate_pda,
+};
+use crate::helpers::transfer_validation::{
+    read_token_balance, validate_destination_ata_if_exists, validate_nft_payer,
+    validate_source_ata,
+};
+use crate::state::zupy_card::{
+    ZupyCard, ZupyCardMut, CARD_STATUS_ACTIVE, ZUPY_CARD_DISCRIMINATOR, ZUPY_CARD_SIZE,
+    ZUPY_CARD_SIZE_WITH_ISSUED_AT,
+};
+
+/// Process `reissue_zupy_card` instruction.
+///
+/// Recovers a Zupy Card for a user who lost their wallet: mints a fresh card
+/// token (same mint, same PDAs) into `new_wallet`'s ATA and re-points the
+/// ZupyCard `owner` at it. The card's old token is invalidated — burned when
+/// `user_pda` + `old_token_account` are supplied, otherwise simply no longer
+/// the ZupyCard owner's. Bumps `reissue_count` and sets `updated_at` and
+/// `issued_at` to now.
+///
+/// Frozen cards fail with `CardFrozen`, revoked cards with `CardRevoked`. Cards
+/// shorter than `ZUPY_CARD_SIZE_WITH_ISSUED_AT` are grown first; the
+/// mint_authority pays the extra rent.
+///
+/// Accounts (9, +2 optional):
+///   0. mint_authority (writable, signer) — must match token_state.mint_authority()
+///   1. token_state (read) — PDA [TOKEN_STATE_SEED]
+///   2. zupy_card (writable) — PDA [b"zupy_card", &user_ksuid]
+///   3. mint (writable) — PDA [b"zupy_card_mint", &user_ksuid]
+///   4. new_wallet (read) — new card holder
+///   5. new_token_account (writable) — ATA for new_wallet, created if needed
+///   6. token_program (read) — Token-2022
+///   7. associated_token_program (read)
+///   8. system_program (read)
+///   9. user_pda (read, optional) — PDA [b"user_pda", &user_ksuid], signs the burn
+///  10. old_token_account (writable, optional) — old card ATA for user_pda, burned
+///
+/// Data: user_ksuid ([u8; 27])
+/// Discriminator: [197, 233, 16, 159, 160, 154, 0, 138] (SHA256("global:reissue_zupy_card"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (9 accounts + optional old card) ─────────────
+    if accounts.len() < 9 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let mint_authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let zupy_card = &accounts[2];
+    let mint = &accounts[3];
+    let new_wallet = &accounts[4];
+    let new_token_account = &accounts[5];
+    let token_program = &accounts[6];
+    let _associated_token_program = &accounts[7];
+    let system_program = &accounts[8];
+    let old_card = match accounts.len() {
+        9 => None,
+        10 => return Err(ProgramError::NotEnoughAccountKeys),
+        _ => Some((&accounts[9], &accounts[10])),
+    };
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let user_ksuid = reader.read_bytes::<27>()?;
+    reader.finish()?;
+
+    // ── Authority (signer + token_state + mint_authority) ───────────────
+    validate_nft_payer(program_id, mint_authority, token_state_account)?;
+
+    // ── Token program check ─────────────────────────────────────────────
+    let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
+    if token_program.address() != &token_2022_addr {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    // ── PDA validations ─────────────────────────────────────────────────
+    let (expected_card_pda, _) = derive_zupy_card_pda(program_id, user_ksuid);
+    validate_pda(zupy_card.address(), &expected_card_pda)?;
+
+    let (expected_mint_pda, _) = derive_zupy_card_mint_pda(program_id, user_ksuid);
+    validate_pda(mint.address(), &expected_mint_pda)?;
+
+    // ── new_token_account is new_wallet's ATA for the card mint ─────────
+    validate_destination_ata_if_exists(new_token_account, mint.address())?;
+    assert_associated_token_address(
+        new_token_account,
+        new_wallet.address(),
+        mint.address(),
+        token_program.address(),
+        None,
+    )?;
+
+    // ── ZupyCard validation: owner, size, discriminator, mint, status ───
+    if !zupy_card.owned_by(program_id) || zupy_card.data_len() < ZUPY_CARD_SIZE {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let card = ZupyCard::from_slice(unsafe { zupy_card.borrow_unchecked() });
+    assert_discriminator(card.discriminator(), &ZUPY_CARD_DISCRIMINATOR)?;
+    if card.mint() != mint.address().as_ref() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if card.is_frozen() {
+        return Err(ZupyTokenError::CardFrozen.into());
+    }
+    if card.is_revoked() {
+        return Err(ZupyTokenError::CardRevoked.into());
+    }
+    let card_bump = card.bump();
+    let reissue_count = card.reissue_count().saturating_add(1);
+
+    // ── Grow older cards to carry status, reissue_count + issued_at ─────
+    if zupy_card.data_len() < ZUPY_CARD_SIZE_WITH_ISSUED_AT {
+        cpi_grow_account(mint_authority, zupy_card, ZUPY_CARD_SIZE_WITH_ISSUED_AT)?;
+    }
+
+    // ── CPI 1 (optional): Burn the old card (user_pda signs) ────────────
+    if let Some((user_pda, old_token_account)) = old_card {
+        let (expected_user_pda, user_bump) = derive_user_nft_pda(program_id, user_ksuid);
+        validate_pda(user_pda.address(), &expected_user_pda)?;
+        validate_source_ata(old_token_account, mint.address(), &expected_user_pda)?;
+
+        let balance = read_token_balance(old_token_account);
+        if balance > 1 {
+            return Err(ZupyTokenError::InvalidCardBalance.into());
+        }
+        if balance == 1 {
+            let user_bump_bytes = [user_bump];
+            let user_signer_seeds: [Seed; 3] = [
+                Seed::from(USER_PDA_SEED),
+                Seed::from(user_ksuid.as_ref()),
+                Seed::from(user_bump_bytes.as_ref()),
+            ];
+            cpi_burn(
+                old_token_account,
+                mint,
+                user_pda,
+                1,
+                &token_2022_addr,
+                &[Signer::from(&user_signer_seeds)],
+            )?;
+        }
+    }
+
+    // ── CPI 2: Create ATA for new_wallet ────────────────────────────────
+    cpi_create_ata_idempotent(
+        new_token_account,
+        mint_authority,
+        new_wallet,
+        mint,
+        token_program,
+        system_program,
+    )?;
+
+    // ── CPI 3: Mint 1 card to the new ATA (zupy_card PDA signs) ─────────
+    let card_bump_bytes = [card_bump];
+    let card_signer_seeds: [Seed; 3] = [
+        Seed::from(ZUPY_CARD_SEED),
+        Seed::from(user_ksuid.as_ref()),
+        Seed::from(card_bump_bytes.as_ref()),
+    ];
+
+    cpi_mint_to(
+        mint,
+        new_token_account,
+        zupy_card, // authority = zupy_card PDA
+        1,
+        &token_2022_addr,
+        &[Signer::from(&card_signer_seeds)],
+    )?;
+
+    // ── Re-point ZupyCard at the new wallet ─────────────────────────────
+    use pinocchio::sysvars::Sysvar as _;
+    let clock = pinocchio::sysvars::clock::Clock::get()?;
+
+    let mut card_state = ZupyCardMut::from_slice(unsafe { zupy_card.borrow_unchecked_mut() });
+    let owner_key: &[u8; 32] = address_bytes(new_wallet.address());
+    card_state.set_owner(owner_key);
+    card_state.set_status(CARD_STATUS_ACTIVE);
+    card_state.set_reissue_count(reissue_count);
+    card_state.set_updated_at(clock.unix_timestamp);
+    card_state.set_issued_at(clock.unix_timestamp);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reissue_zupy_card_not_enough_account_keys() {
+        let program_id = Address::default();
+        let result = process(&program_id, &[], &[0u8; 27]);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+}
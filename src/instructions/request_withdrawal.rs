@@ -0,0 +1,132 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::WITHDRAWAL_REQUEST_SEED;
+use crate::error::ZupyTokenError;
+use crate::helpers::cpi::cpi_create_account;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::pda::{derive_withdrawal_request_pda, validate_pda};
+use crate::helpers::transfer_validation::validate_token_state_base;
+use crate::state::withdrawal_request_state::{
+    WithdrawalRequestStateMut, WITHDRAWAL_REQUEST_STATE_DISCRIMINATOR,
+    WITHDRAWAL_REQUEST_STATE_SIZE,
+};
+
+/// Process `request_withdrawal` instruction.
+///
+/// First phase of a two-phase withdrawal: records a user withdrawal to an
+/// external wallet in a WithdrawalRequestState PDA for the treasury to approve
+/// (`approve_withdrawal`) or for either side to cancel (`cancel_withdrawal`).
+/// No tokens move here; the user's balance is checked at approval.
+///
+/// Accounts (5):
+///   0. transfer_authority (signer)    — must match token_state.transfer_authority()
+///   1. token_state (read)             — PDA [TOKEN_STATE_SEED]
+///   2. withdrawal_request (writable)  — PDA [WITHDRAWAL_REQUEST_SEED, request_id], created here
+///   3. fee_payer (writable, signer)   — pays rent, refunded when the request is closed
+///   4. system_program (read)
+///
+/// Data: request_id (u64) + user_id (u64) + amount (u64) + destination ([u8; 32], external wallet)
+/// Discriminator: `[251, 85, 121, 205, 56, 201, 12, 177]` (SHA256("global:request_withdrawal"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (5 accounts) ─────────────────────────────────
+    if accounts.len() < 5 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let transfer_authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let request_account = &accounts[2];
+    let fee_payer = &accounts[3];
+    let _system_program = &accounts[4];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let request_id = reader.read_u64()?;
+    let user_id = reader.read_u64()?;
+    let amount = reader.read_u64()?;
+    let destination = reader.read_pubkey()?;
+    reader.finish()?;
+
+    // ── Input validation ────────────────────────────────────────────────
+    if amount == 0 {
+        return Err(ZupyTokenError::ZeroAmount.into());
+    }
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+    if state.paused() {
+        return Err(ZupyTokenError::SystemPaused.into());
+    }
+
+    // ── Authority validation ────────────────────────────────────────────
+    if !transfer_authority.is_signer()
+        || state.transfer_authority() != transfer_authority.address().as_ref()
+    {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    if !fee_payer.is_signer() {
+        return Err(ZupyTokenError::FeePayerNotSigner.into());
+    }
+
+    // ── PDA validation + init guard ─────────────────────────────────────
+    let (expected_pda, bump) = derive_withdrawal_request_pda(program_id, request_id);
+    validate_pda(request_account.address(), &expected_pda)?;
+    if request_account.data_len() > 0 {
+        return Err(ZupyTokenError::AlreadyInitialized.into());
+    }
+
+    // ── CPI: Create account (105 bytes) ─────────────────────────────────
+    let request_id_bytes = request_id.to_le_bytes();
+    let bump_bytes = [bump];
+    let signer_seeds: [Seed; 3] = [
+        Seed::from(WITHDRAWAL_REQUEST_SEED),
+        Seed::from(request_id_bytes.as_ref()),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    let signer = Signer::from(&signer_seeds);
+
+    cpi_create_account(
+        fee_payer,
+        request_account,
+        WITHDRAWAL_REQUEST_STATE_SIZE as u64,
+        program_id,
+        &[signer],
+    )?;
+
+    // ── Initialize state fields ─────────────────────────────────────────
+    use pinocchio::sysvars::Sysvar as _;
+    let clock = pinocchio::sysvars::clock::Clock::get()?;
+    let payer_key: &[u8; 32] = fee_payer.address().as_ref().try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    let mut request =
+        WithdrawalRequestStateMut::from_slice(unsafe { request_account.borrow_unchecked_mut() });
+    request.set_discriminator(&WITHDRAWAL_REQUEST_STATE_DISCRIMINATOR);
+    request.set_request_id(request_id);
+    request.set_user_id(user_id);
+    request.set_amount(amount);
+    request.set_destination(destination);
+    request.set_payer(payer_key);
+    request.set_requested_at(clock.unix_timestamp);
+    request.set_bump(bump);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_withdrawal_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 56];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
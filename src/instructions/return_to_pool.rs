@@ -10,12 +10,15 @@ use crate::helpers::return_to_pool_common::decompress_to_pool;
 ///
 /// Delegates to [`decompress_to_pool`] with `COMPANY_SEED`.
 /// See that function for full account layout, data format, and security validations.
+/// The SPL Memo program may be appended as the last account to also emit the memo
+/// as a standard memo instruction; the company's CompanyState may precede it to
+/// add the amount to its `total_sent`.
 pub fn process(
     program_id: &Address,
     accounts: &[AccountView],
     data: &[u8],
 ) -> ProgramResult {
-    decompress_to_pool(program_id, accounts, data, COMPANY_SEED)
+    decompress_to_pool(program_id, accounts, data, COMPANY_SEED, false)
 }
 
 #[cfg(test)]
@@ -10,6 +10,9 @@ use crate::helpers::return_to_pool_common::v1_passthrough_to_pool;
 ///
 /// Delegates to [`v1_passthrough_to_pool`] with `COMPANY_SEED`.
 /// See that function for full account layout, data format, and security validations.
+/// A zero amount, malformed memo, paused system, wrong authority or wrong company
+/// PDA fail with the same error codes as `return_to_pool`.
+/// The returned amount is added to `token_state.v1_company_returned` (token_state writable).
 pub fn process(
     program_id: &Address,
     accounts: &[AccountView],
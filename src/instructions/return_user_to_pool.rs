@@ -15,7 +15,7 @@ pub fn process(
     accounts: &[AccountView],
     data: &[u8],
 ) -> ProgramResult {
-    decompress_to_pool(program_id, accounts, data, USER_SEED)
+    decompress_to_pool(program_id, accounts, data, USER_SEED, false)
 }
 
 #[cfg(test)]
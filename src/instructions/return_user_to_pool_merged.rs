@@ -0,0 +1,38 @@
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::USER_SEED;
+use crate::helpers::return_to_pool_common::decompress_to_pool;
+
+/// Process `return_user_to_pool_merged` instruction.
+///
+/// `return_user_to_pool` for a user whose balance is fragmented across several
+/// compressed accounts: the memo is followed by a `MergeInputs` section (count,
+/// output queue index, per-input amount + Merkle context, optional proof) and
+/// every listed leaf is spent in a single decompress, with the change
+/// re-compressed to the user PDA. `RETURN_ALL_AMOUNT` returns the summed inputs.
+///
+/// Delegates to [`decompress_to_pool`] with `USER_SEED` and `merged = true`.
+/// See that function for full account layout, data format, and security validations.
+///
+/// Discriminator: [187, 144, 44, 168, 103, 162, 5, 18] (SHA256("global:return_user_to_pool_merged"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    decompress_to_pool(program_id, accounts, data, USER_SEED, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pinocchio::error::ProgramError;
+
+    #[test]
+    fn test_return_user_to_pool_merged_not_enough_account_keys() {
+        let program_id = Address::default();
+        let data = [0u8; 32];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+}
@@ -0,0 +1,156 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::{TOKEN_2022_PROGRAM_ID, USER_PDA_SEED};
+use crate::error::ZupyTokenError;
+use crate::helpers::account_checks::assert_discriminator;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::cpi::{cpi_burn, cpi_close_account};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::pda::{
+    derive_user_nft_pda, derive_zupy_card_mint_pda, derive_zupy_card_pda, validate_pda,
+};
+use crate::helpers::transfer_validation::{
+    read_token_balance, validate_source_ata, validate_token_state_base,
+};
+use crate::state::zupy_card::{ZupyCard, ZUPY_CARD_DISCRIMINATOR, ZUPY_CARD_SIZE};
+
+/// Process `revoke_zupy_card` instruction.
+///
+/// Teardown for `create_zupy_card` when a user account is deleted: burns the
+/// card (if still held), closes the card ATA and the ZupyCard PDA, and sends
+/// their rent to `destination`. A card ATA holding anything but 0 or 1 fails
+/// with `InvalidCardBalance`.
+///
+/// The card mint is left in place with supply 0: it has no MintCloseAuthority
+/// extension, so Token-2022 refuses CloseAccount on it.
+///
+/// Accounts (8):
+///   0. authority (signer) — token_state.mint_authority() or token_state.treasury()
+///   1. token_state (read) — PDA [TOKEN_STATE_SEED]
+///   2. user_pda (read) — PDA [b"user_pda", &user_ksuid], card ATA owner, signs burn/close
+///   3. zupy_card (writable) — PDA [b"zupy_card", &user_ksuid], closed
+///   4. mint (writable) — PDA [b"zupy_card_mint", &user_ksuid]
+///   5. token_account (writable) — card ATA for user_pda, closed
+///   6. destination (writable) — receives the rent of both closed accounts
+///   7. token_program (read) — Token-2022
+///
+/// Data: user_ksuid ([u8; 27])
+/// Discriminator: [176, 218, 15, 77, 139, 66, 145, 34] (SHA256("global:revoke_zupy_card"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (8 accounts) ─────────────────────────────────
+    if accounts.len() < 8 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let user_pda = &accounts[2];
+    let zupy_card = &accounts[3];
+    let mint = &accounts[4];
+    let token_account = &accounts[5];
+    let destination = &accounts[6];
+    let token_program = &accounts[7];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let user_ksuid = reader.read_bytes::<27>()?;
+    reader.finish()?;
+
+    // ── Authority: mint_authority or treasury ───────────────────────────
+    if !authority.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    let state = validate_token_state_base(program_id, token_state_account)?;
+    let authority_key: &[u8; 32] = address_bytes(authority.address());
+    if !state.is_mint_authority(authority_key) && !state.is_treasury(authority_key) {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+
+    // ── Token program check ─────────────────────────────────────────────
+    let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
+    if token_program.address() != &token_2022_addr {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    // ── PDA validations ─────────────────────────────────────────────────
+    let (expected_user_pda, user_bump) = derive_user_nft_pda(program_id, user_ksuid);
+    validate_pda(user_pda.address(), &expected_user_pda)?;
+
+    let (expected_card_pda, _) = derive_zupy_card_pda(program_id, user_ksuid);
+    validate_pda(zupy_card.address(), &expected_card_pda)?;
+
+    let (expected_mint_pda, _) = derive_zupy_card_mint_pda(program_id, user_ksuid);
+    validate_pda(mint.address(), &expected_mint_pda)?;
+
+    // ── ZupyCard validation: owner, size, discriminator, mint ───────────
+    if !zupy_card.owned_by(program_id) || zupy_card.data_len() < ZUPY_CARD_SIZE {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let card = ZupyCard::from_slice(unsafe { zupy_card.borrow_unchecked() });
+    assert_discriminator(card.discriminator(), &ZUPY_CARD_DISCRIMINATOR)?;
+    if card.mint() != mint.address().as_ref() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // ── Card ATA: mint + owner, balance 0 or 1 ──────────────────────────
+    validate_source_ata(token_account, mint.address(), &expected_user_pda)?;
+    let balance = read_token_balance(token_account);
+    if balance > 1 {
+        return Err(ZupyTokenError::InvalidCardBalance.into());
+    }
+
+    // ── CPIs: Burn the card, close its ATA (user_pda signs) ─────────────
+    let user_bump_bytes = [user_bump];
+    let user_signer_seeds: [Seed; 3] = [
+        Seed::from(USER_PDA_SEED),
+        Seed::from(user_ksuid.as_ref()),
+        Seed::from(user_bump_bytes.as_ref()),
+    ];
+
+    if balance == 1 {
+        cpi_burn(
+            token_account,
+            mint,
+            user_pda,
+            1,
+            &token_2022_addr,
+            &[Signer::from(&user_signer_seeds)],
+        )?;
+    }
+
+    cpi_close_account(
+        token_account,
+        destination,
+        user_pda,
+        &token_2022_addr,
+        &[Signer::from(&user_signer_seeds)],
+    )?;
+
+    // ── Close ZupyCard: move rent to destination, then release ──────────
+    let refund = destination
+        .lamports()
+        .checked_add(zupy_card.lamports())
+        .ok_or(ZupyTokenError::InvalidAmount)?;
+    destination.set_lamports(refund);
+    zupy_card.set_lamports(0);
+    zupy_card.close()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_revoke_zupy_card_not_enough_account_keys() {
+        let program_id = Address::default();
+        let result = process(&program_id, &[], &[0u8; 27]);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+}
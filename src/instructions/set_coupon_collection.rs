@@ -0,0 +1,83 @@
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::cpi::cpi_grow_token_state;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::transfer_validation::validate_token_state_base;
+use crate::state::token_state::{TokenStateMut, TOKEN_STATE_SIZE_WITH_COUPON_COLLECTION};
+
+/// Process `set_coupon_collection` instruction.
+///
+/// Sets `coupon_collection`: the collection mint every coupon cNFT is minted
+/// into by `mint_coupon_cnft` / `mint_coupon_cnft_batch`. The collection's
+/// update authority must be the token_state PDA, which signs the Bubblegum
+/// verification. All zeroes unsets it, failing cNFT mints with
+/// `InvalidCollection`. Only the treasury wallet can change it.
+///
+/// TokenStates shorter than `TOKEN_STATE_SIZE_WITH_COUPON_COLLECTION` are grown
+/// first; the treasury pays the extra rent. Accounts gaining the metadata flags
+/// keep reading as metadata-initialized, as they did while too short to carry them.
+///
+/// Accounts (3):
+///   0. authority (writable, signer) — must be token_state.treasury()
+///   1. token_state (writable) — PDA [TOKEN_STATE_SEED]
+///   2. system_program (read)
+///
+/// Data: collection_mint ([u8; 32])
+/// Discriminator: `[131, 191, 144, 20, 32, 43, 205, 107]` (SHA256("global:set_coupon_collection"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (3 accounts) ─────────────────────────────────
+    if accounts.len() < 3 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let _system_program = &accounts[2];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let collection_mint = reader.read_bytes::<32>()?;
+    reader.finish()?;
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+
+    // ── Treasury authorization ──────────────────────────────────────────
+    if !authority.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    let authority_key: &[u8; 32] = address_bytes(authority.address());
+    if !state.is_treasury(authority_key) {
+        return Err(ZupyTokenError::UnauthorizedTreasury.into());
+    }
+
+    // ── Grow older TokenStates to carry coupon_collection ───────────────
+    if token_state_account.data_len() < TOKEN_STATE_SIZE_WITH_COUPON_COLLECTION {
+        cpi_grow_token_state(authority, token_state_account, TOKEN_STATE_SIZE_WITH_COUPON_COLLECTION, true)?;
+    }
+
+    // ── Update coupon collection ────────────────────────────────────────
+    TokenStateMut::from_slice(unsafe { token_state_account.borrow_unchecked_mut() })
+        .set_coupon_collection(collection_mint);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_coupon_collection_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 32];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
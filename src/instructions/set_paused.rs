@@ -2,9 +2,10 @@ use pinocchio::error::ProgramError;
 use pinocchio::{AccountView, Address, ProgramResult};
 
 use crate::error::ZupyTokenError;
-use crate::helpers::instruction_data::parse_bool;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::instruction_data::Reader;
 use crate::helpers::transfer_validation::validate_token_state_base;
-use crate::state::token_state::{TokenState, TokenStateMut};
+use crate::state::token_state::TokenStateMut;
 
 /// Process `set_paused` instruction.
 ///
@@ -29,19 +30,18 @@ pub fn process(
     let token_state_account = &accounts[1];
 
     // ── Parse instruction data ──────────────────────────────────────────
-    let paused = parse_bool(data, 0)?;
+    let mut reader = Reader::new(data);
+    let paused = reader.read_bool()?;
+    reader.finish()?;
 
     // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
-    validate_token_state_base(program_id, token_state_account)?;
-
-    // Zero-copy read for treasury authorization
-    let state = TokenState::from_slice(unsafe { token_state_account.borrow_unchecked() });
+    let state = validate_token_state_base(program_id, token_state_account)?;
 
     // ── Treasury authorization (AC6) ────────────────────────────────────
     if !authority.is_signer() {
         return Err(ZupyTokenError::InvalidAuthority.into());
     }
-    let authority_key: &[u8; 32] = authority.address().as_ref().try_into().unwrap();
+    let authority_key: &[u8; 32] = address_bytes(authority.address());
     if !state.is_treasury(authority_key) {
         return Err(ZupyTokenError::UnauthorizedTreasury.into());
     }
@@ -0,0 +1,77 @@
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::BPS_DENOMINATOR;
+use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::transfer_validation::validate_token_state_base;
+use crate::state::token_state::TokenStateMut;
+
+/// Process `set_split_range` instruction.
+///
+/// Sets the `[min_split_bps, max_split_bps]` range that bounds the
+/// client-specified incentive share in `execute_split_transfer`.
+/// Only the treasury wallet can change the range.
+///
+/// Accounts (2):
+///   0. authority (signer) — must be token_state.treasury()
+///   1. token_state (writable) — PDA [TOKEN_STATE_SEED]
+///
+/// Data: min_bps (u16) + max_bps (u16)
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (2 accounts) ─────────────────────────────────
+    if accounts.len() < 2 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let authority = &accounts[0];
+    let token_state_account = &accounts[1];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let min_bps = reader.read_u16()?;
+    let max_bps = reader.read_u16()?;
+    reader.finish()?;
+
+    // ── Input validation ────────────────────────────────────────────────
+    if min_bps > max_bps || max_bps > BPS_DENOMINATOR {
+        return Err(ZupyTokenError::InvalidSplitRatio.into());
+    }
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+
+    // ── Treasury authorization ──────────────────────────────────────────
+    if !authority.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    let authority_key: &[u8; 32] = address_bytes(authority.address());
+    if !state.is_treasury(authority_key) {
+        return Err(ZupyTokenError::UnauthorizedTreasury.into());
+    }
+
+    // ── Update split range ──────────────────────────────────────────────
+    let mut state_mut =
+        TokenStateMut::from_slice(unsafe { token_state_account.borrow_unchecked_mut() });
+    state_mut.set_min_split_bps(min_bps);
+    state_mut.set_max_split_bps(max_bps);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_split_range_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 4];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
@@ -0,0 +1,83 @@
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::cpi::cpi_grow_token_state;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::transfer_validation::validate_token_state_base;
+use crate::state::token_state::{TokenStateMut, TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION};
+
+/// Process `set_strict_registration` instruction.
+///
+/// Sets `strict_registration`. While on, `transfer_from_pool` and
+/// `transfer_company_to_user` require the recipient's UserState (created by
+/// `initialize_user`) and fail with `UserNotRegistered` without it. Only the
+/// treasury wallet can change it.
+///
+/// TokenStates shorter than `TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION` are
+/// grown first; the treasury pays the extra rent. Accounts gaining the metadata
+/// flags keep reading as metadata-initialized, as they did while too short to
+/// carry them.
+///
+/// Accounts (3):
+///   0. authority (writable, signer) — must be token_state.treasury()
+///   1. token_state (writable) — PDA [TOKEN_STATE_SEED]
+///   2. system_program (read)
+///
+/// Data: enabled (bool)
+/// Discriminator: `[92, 42, 138, 11, 227, 9, 178, 89]` (SHA256("global:set_strict_registration"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (3 accounts) ─────────────────────────────────
+    if accounts.len() < 3 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let _system_program = &accounts[2];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let enabled = reader.read_bool()?;
+    reader.finish()?;
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+
+    // ── Treasury authorization ──────────────────────────────────────────
+    if !authority.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    let authority_key: &[u8; 32] = address_bytes(authority.address());
+    if !state.is_treasury(authority_key) {
+        return Err(ZupyTokenError::UnauthorizedTreasury.into());
+    }
+
+    // ── Grow older TokenStates to carry strict_registration ─────────────
+    if token_state_account.data_len() < TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION {
+        cpi_grow_token_state(authority, token_state_account, TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION, true)?;
+    }
+
+    // ── Update registration mode ────────────────────────────────────────
+    TokenStateMut::from_slice(unsafe { token_state_account.borrow_unchecked_mut() })
+        .set_strict_registration(enabled);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_strict_registration_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [1u8];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
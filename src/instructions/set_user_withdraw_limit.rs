@@ -0,0 +1,125 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::WITHDRAW_LIMIT_SEED;
+use crate::error::ZupyTokenError;
+use crate::helpers::account_checks::assert_discriminator;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::cpi::cpi_create_account;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::pda::{derive_withdraw_limit_pda, validate_pda};
+use crate::helpers::transfer_validation::validate_token_state_base;
+use crate::state::withdraw_limit_state::{
+    WithdrawLimitState, WithdrawLimitStateMut, WITHDRAW_LIMIT_STATE_DISCRIMINATOR,
+    WITHDRAW_LIMIT_STATE_SIZE,
+};
+
+/// Process `set_user_withdraw_limit` instruction.
+///
+/// Creates or updates a user's WithdrawLimitState PDA, capping the amount
+/// `withdraw_to_external` may move per rolling 24h window. On creation the
+/// window is empty; on update only `daily_limit` changes, so amounts already
+/// withdrawn in the current window still count. Only the treasury wallet can set it.
+///
+/// Accounts (4):
+///   0. treasury (writable, signer) — must be token_state.treasury(); pays rent on creation
+///   1. token_state (read)          — PDA [TOKEN_STATE_SEED]
+///   2. withdraw_limit (writable)   — PDA [WITHDRAW_LIMIT_SEED, user_id], created if empty
+///   3. system_program (read)
+///
+/// Data: user_id (u64) + daily_limit (u64)
+/// Discriminator: `[66, 103, 224, 46, 107, 159, 198, 142]` (SHA256("global:set_user_withdraw_limit"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (4 accounts) ─────────────────────────────────
+    if accounts.len() < 4 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let treasury = &accounts[0];
+    let token_state_account = &accounts[1];
+    let limit_account = &accounts[2];
+    let _system_program = &accounts[3];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let user_id = reader.read_u64()?;
+    let daily_limit = reader.read_u64()?;
+    reader.finish()?;
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+
+    // ── Treasury authorization ──────────────────────────────────────────
+    if !treasury.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    let treasury_key: &[u8; 32] = address_bytes(treasury.address());
+    if !state.is_treasury(treasury_key) {
+        return Err(ZupyTokenError::UnauthorizedTreasury.into());
+    }
+
+    // ── PDA validation ──────────────────────────────────────────────────
+    let (expected_pda, bump) = derive_withdraw_limit_pda(program_id, user_id);
+    validate_pda(limit_account.address(), &expected_pda)?;
+
+    // ── Existing PDA: update the limit only ─────────────────────────────
+    if limit_account.data_len() > 0 {
+        if !limit_account.owned_by(program_id)
+            || limit_account.data_len() < WITHDRAW_LIMIT_STATE_SIZE
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let limit = WithdrawLimitState::from_slice(unsafe { limit_account.borrow_unchecked() });
+        assert_discriminator(limit.discriminator(), &WITHDRAW_LIMIT_STATE_DISCRIMINATOR)?;
+        WithdrawLimitStateMut::from_slice(unsafe { limit_account.borrow_unchecked_mut() })
+            .set_daily_limit(daily_limit);
+        return Ok(());
+    }
+
+    // ── CPI: Create account (41 bytes) ──────────────────────────────────
+    let user_id_bytes = user_id.to_le_bytes();
+    let bump_bytes = [bump];
+    let signer_seeds: [Seed; 3] = [
+        Seed::from(WITHDRAW_LIMIT_SEED),
+        Seed::from(user_id_bytes.as_ref()),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    let signer = Signer::from(&signer_seeds);
+
+    cpi_create_account(
+        treasury,
+        limit_account,
+        WITHDRAW_LIMIT_STATE_SIZE as u64,
+        program_id,
+        &[signer],
+    )?;
+
+    // ── Initialize state fields (window opens on the first withdrawal) ──
+    let mut limit =
+        WithdrawLimitStateMut::from_slice(unsafe { limit_account.borrow_unchecked_mut() });
+    limit.set_discriminator(&WITHDRAW_LIMIT_STATE_DISCRIMINATOR);
+    limit.set_user_id(user_id);
+    limit.set_daily_limit(daily_limit);
+    limit.set_consumed(0);
+    limit.set_window_start(0);
+    limit.set_bump(bump);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_user_withdraw_limit_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 16];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
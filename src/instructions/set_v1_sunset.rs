@@ -0,0 +1,70 @@
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::transfer_validation::validate_token_state_base;
+use crate::state::token_state::TokenStateMut;
+
+/// Process `set_v1_sunset` instruction.
+///
+/// Sets `v1_sunset_ts`: from this unix timestamp on, the `_v1` passthroughs
+/// (mainnet V1 cToken) fail with `V1Sunset`. 0 removes the sunset, so the V1
+/// path can be retired on a fixed date (or brought back) without a redeploy.
+/// Only the treasury wallet can change it.
+///
+/// Accounts (2):
+///   0. authority (signer) — must be token_state.treasury()
+///   1. token_state (writable) — PDA [TOKEN_STATE_SEED]
+///
+/// Data: sunset_ts (i64)
+/// Discriminator: `[1, 118, 100, 229, 250, 202, 232, 49]` (SHA256("global:set_v1_sunset"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (2 accounts) ─────────────────────────────────
+    if accounts.len() < 2 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let authority = &accounts[0];
+    let token_state_account = &accounts[1];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let sunset_ts = reader.read_i64()?;
+    reader.finish()?;
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+
+    // ── Treasury authorization ──────────────────────────────────────────
+    if !authority.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    let authority_key: &[u8; 32] = address_bytes(authority.address());
+    if !state.is_treasury(authority_key) {
+        return Err(ZupyTokenError::UnauthorizedTreasury.into());
+    }
+
+    // ── Update V1 sunset ────────────────────────────────────────────────
+    TokenStateMut::from_slice(unsafe { token_state_account.borrow_unchecked_mut() })
+        .set_v1_sunset_ts(sunset_ts);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_v1_sunset_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 8];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
@@ -0,0 +1,70 @@
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::transfer_validation::validate_token_state_base;
+use crate::state::token_state::TokenStateMut;
+
+/// Process `set_withdraw_approval_threshold` instruction.
+///
+/// Sets `withdraw_approval_threshold`: user withdrawals of this amount or more
+/// are rejected by `withdraw_to_external` with `RequiresApproval` and must go
+/// through `request_withdrawal` + `approve_withdrawal`. 0 disables the two-phase
+/// requirement. Only the treasury wallet can change it.
+///
+/// Accounts (2):
+///   0. authority (signer) — must be token_state.treasury()
+///   1. token_state (writable) — PDA [TOKEN_STATE_SEED]
+///
+/// Data: threshold (u64)
+/// Discriminator: `[42, 9, 51, 233, 87, 88, 53, 22]` (SHA256("global:set_withdraw_approval_threshold"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (2 accounts) ─────────────────────────────────
+    if accounts.len() < 2 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let authority = &accounts[0];
+    let token_state_account = &accounts[1];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let threshold = reader.read_u64()?;
+    reader.finish()?;
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+
+    // ── Treasury authorization ──────────────────────────────────────────
+    if !authority.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    let authority_key: &[u8; 32] = address_bytes(authority.address());
+    if !state.is_treasury(authority_key) {
+        return Err(ZupyTokenError::UnauthorizedTreasury.into());
+    }
+
+    // ── Update approval threshold ───────────────────────────────────────
+    TokenStateMut::from_slice(unsafe { token_state_account.borrow_unchecked_mut() })
+        .set_withdraw_approval_threshold(threshold);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_withdraw_approval_threshold_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 8];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
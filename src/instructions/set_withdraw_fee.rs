@@ -0,0 +1,75 @@
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::MAX_WITHDRAW_FEE_BPS;
+use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::transfer_validation::validate_token_state_base;
+use crate::state::token_state::TokenStateMut;
+
+/// Process `set_withdraw_fee` instruction.
+///
+/// Sets `withdraw_fee_bps`, the share of each external withdrawal routed to
+/// the incentive pool. Capped at `MAX_WITHDRAW_FEE_BPS`; 0 disables the fee.
+/// Only the treasury wallet can change it.
+///
+/// Accounts (2):
+///   0. authority (signer) — must be token_state.treasury()
+///   1. token_state (writable) — PDA [TOKEN_STATE_SEED]
+///
+/// Data: fee_bps (u16)
+/// Discriminator: `[33, 223, 102, 118, 225, 116, 8, 238]` (SHA256("global:set_withdraw_fee"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (2 accounts) ─────────────────────────────────
+    if accounts.len() < 2 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let authority = &accounts[0];
+    let token_state_account = &accounts[1];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let fee_bps = reader.read_u16()?;
+    reader.finish()?;
+
+    // ── Input validation ────────────────────────────────────────────────
+    if fee_bps > MAX_WITHDRAW_FEE_BPS {
+        return Err(ZupyTokenError::InvalidWithdrawFee.into());
+    }
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+
+    // ── Treasury authorization ──────────────────────────────────────────
+    if !authority.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    let authority_key: &[u8; 32] = address_bytes(authority.address());
+    if !state.is_treasury(authority_key) {
+        return Err(ZupyTokenError::UnauthorizedTreasury.into());
+    }
+
+    // ── Update withdraw fee ─────────────────────────────────────────────
+    TokenStateMut::from_slice(unsafe { token_state_account.borrow_unchecked_mut() })
+        .set_withdraw_fee_bps(fee_bps);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_withdraw_fee_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 2];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
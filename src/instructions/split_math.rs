@@ -1,6 +1,8 @@
 use pinocchio::error::ProgramError;
 
+use crate::constants::BPS_DENOMINATOR;
 use crate::error::ZupyTokenError;
+use crate::helpers::math::{checked_add_amount, checked_sub_amount, mul_bps};
 
 /// Split result: (company_amount, burn_amount, incentive_amount)
 #[derive(Debug)]
@@ -20,7 +22,8 @@ pub struct SplitResult {
 ///
 /// Uses u128 intermediate arithmetic to prevent overflow.
 /// All u128→u64 casts use `try_into()` (NOT `as u64`) per Audit 12.1.
-/// Sum verification via `checked_add` ensures no tokens are lost.
+/// Leg arithmetic goes through `helpers::math` (`ArithmeticOverflow` on
+/// over/underflow); the sum verification ensures no tokens are lost.
 pub fn calculate_split(z_total: u64) -> Result<SplitResult, ProgramError> {
     if z_total == 0 {
         return Err(ZupyTokenError::ZeroAmount.into());
@@ -33,25 +36,19 @@ pub fn calculate_split(z_total: u64) -> Result<SplitResult, ProgramError> {
     // AUDIT 12.1 CRITICAL: u128→u64 via try_into(), NOT `as u64`
     let z_base: u64 = z_base_128
         .try_into()
-        .map_err(|_| ZupyTokenError::SplitCalculationError)?;
+        .map_err(|_| ZupyTokenError::ArithmeticOverflow)?;
 
     // z_markup = z_total - z_base (safe: z_base <= z_total by construction)
-    let z_markup = z_total
-        .checked_sub(z_base)
-        .ok_or(ZupyTokenError::SplitCalculationError)?;
+    let z_markup = checked_sub_amount(z_total, z_base)?;
 
     let burn_amount = z_markup / 2; // floor division
-    let incentive_amount = z_markup
-        .checked_sub(burn_amount)
-        .ok_or(ZupyTokenError::SplitCalculationError)?; // gets dust (ceil)
+    let incentive_amount = checked_sub_amount(z_markup, burn_amount)?; // gets dust (ceil)
 
     let company_amount = z_base;
 
-    // AUDIT 12.1 CRITICAL: Sum verification with checked_add
-    let sum = company_amount
-        .checked_add(burn_amount)
-        .and_then(|s| s.checked_add(incentive_amount))
-        .ok_or(ZupyTokenError::SplitCalculationError)?;
+    // AUDIT 12.1 CRITICAL: Sum verification with checked adds
+    let sum = checked_add_amount(company_amount, burn_amount)
+        .and_then(|s| checked_add_amount(s, incentive_amount))?;
 
     if sum != z_total {
         return Err(ZupyTokenError::SplitCalculationError.into());
@@ -64,6 +61,42 @@ pub fn calculate_split(z_total: u64) -> Result<SplitResult, ProgramError> {
     })
 }
 
+/// Calculate a split with a client-specified incentive share.
+///
+/// The burn leg is unchanged from `calculate_split` (half of the 20% markup).
+/// The incentive leg is `floor(z_total * split_bps / 10_000)`, and the company
+/// leg receives the remainder — including all rounding dust — so the three
+/// legs always reconcile to `z_total`. A share that leaves less than the burn
+/// leg for the company fails with `ArithmeticOverflow`.
+///
+/// Range enforcement against TokenState's `[min_split_bps, max_split_bps]` is
+/// the caller's job; this only rejects ratios above 100%.
+pub fn calculate_split_bps(z_total: u64, split_bps: u16) -> Result<SplitResult, ProgramError> {
+    if split_bps > BPS_DENOMINATOR {
+        return Err(ZupyTokenError::InvalidSplitRatio.into());
+    }
+    let base = calculate_split(z_total)?;
+
+    // mul_bps: u128 intermediate, z_total * 10_000 cannot overflow
+    let incentive_amount = mul_bps(z_total, split_bps)?;
+
+    // Company gets the remainder (and therefore the rounding dust)
+    let company_amount = checked_sub_amount(z_total, base.burn_amount)
+        .and_then(|r| checked_sub_amount(r, incentive_amount))?;
+
+    let sum = checked_add_amount(company_amount, base.burn_amount)
+        .and_then(|s| checked_add_amount(s, incentive_amount))?;
+    if sum != z_total {
+        return Err(ZupyTokenError::SplitCalculationError.into());
+    }
+
+    Ok(SplitResult {
+        company_amount,
+        burn_amount: base.burn_amount,
+        incentive_amount,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,4 +239,74 @@ mod tests {
         assert_eq!(r.burn_amount + r.incentive_amount, 2);
         assert!(r.incentive_amount >= r.burn_amount);
     }
+
+    // ── calculate_split_bps ────────────────────────────────────────────
+
+    #[test]
+    fn test_split_bps_zero_gives_no_incentive() {
+        let r = calculate_split_bps(1_000_000, 0).unwrap();
+        assert_eq!(r.incentive_amount, 0);
+        assert_eq!(r.burn_amount, 83_333);
+        assert_eq!(r.company_amount, 916_667);
+    }
+
+    #[test]
+    fn test_split_bps_max_feasible() {
+        // 9_000 bps incentive + 8.33% burn leaves the company the remainder
+        let r = calculate_split_bps(1_000_000, 9_000).unwrap();
+        assert_eq!(r.incentive_amount, 900_000);
+        assert_eq!(r.burn_amount, 83_333);
+        assert_eq!(r.company_amount, 16_667);
+    }
+
+    #[test]
+    fn test_split_bps_above_100_percent_rejected() {
+        assert_eq!(
+            calculate_split_bps(1_000_000, 10_001).unwrap_err(),
+            ProgramError::Custom(ZupyTokenError::InvalidSplitRatio as u32)
+        );
+    }
+
+    #[test]
+    fn test_split_bps_exceeding_total_with_burn_fails() {
+        // 100% incentive leaves nothing for the burn leg
+        assert_eq!(
+            calculate_split_bps(1_000_000, 10_000).unwrap_err(),
+            ProgramError::Custom(ZupyTokenError::ArithmeticOverflow as u32)
+        );
+    }
+
+    #[test]
+    fn test_split_bps_near_max_fails_instead_of_wrapping() {
+        // Incentive = z_total; the burn leg would push the company leg below zero
+        assert_eq!(
+            calculate_split_bps(u64::MAX - 1, 10_000).unwrap_err(),
+            ProgramError::Custom(ZupyTokenError::ArithmeticOverflow as u32)
+        );
+        let r = calculate_split_bps(u64::MAX, 9_000).unwrap();
+        assert_eq!(r.company_amount + r.burn_amount + r.incentive_amount, u64::MAX);
+    }
+
+    #[test]
+    fn test_split_bps_one_lamport_rounding_goes_to_company() {
+        // 1 * 5000 / 10000 = 0.5 → floor 0; burn = 0; company keeps the lamport
+        let r = calculate_split_bps(1, 5_000).unwrap();
+        assert_eq!(r.incentive_amount, 0);
+        assert_eq!(r.burn_amount, 0);
+        assert_eq!(r.company_amount, 1);
+    }
+
+    #[test]
+    fn test_split_bps_sum_reconciles() {
+        for z_total in [1u64, 7, 119, 120, 1_000_001, u64::MAX] {
+            for bps in [0u16, 1, 333, 2_500, 9_000] {
+                let r = calculate_split_bps(z_total, bps).unwrap();
+                assert_eq!(
+                    r.company_amount as u128 + r.burn_amount as u128 + r.incentive_amount as u128,
+                    z_total as u128,
+                    "sum for z_total={} bps={}", z_total, bps
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,175 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::{TOKEN_2022_PROGRAM_ID, TOKEN_DECIMALS, TOKEN_STATE_SEED};
+use crate::error::ZupyTokenError;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::cpi::cpi_transfer_checked_for_mint;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::transfer_validation::{
+    read_token_balance, validate_source_ata, validate_token_state_base,
+};
+
+/// One whole token in raw units at the default `TOKEN_DECIMALS`.
+pub const WHOLE_TOKEN: u64 = whole_token(TOKEN_DECIMALS);
+
+/// One whole token in raw units (10^decimals).
+pub const fn whole_token(decimals: u8) -> u64 {
+    10u64.pow(decimals as u32)
+}
+
+/// Process `sweep_dust` instruction.
+///
+/// Moves the sub-token remainder ("dust") of the SPL-side pool_ata to
+/// treasury_ata with TransferChecked (TransferCheckedWithFee on a fee-bearing
+/// mint), signed by the token_state PDA.
+/// Dust is `balance % unit`, where `unit` is one whole token (at the stored
+/// `token_state.decimals()`) by default or an explicit threshold no larger than
+/// one whole token, so a sweep can never move more than the computed dust.
+/// Zero dust is a clean no-op.
+///
+/// Accounts (6):
+///   0. treasury (signer)          — must be token_state.treasury()
+///   1. token_state (read)         — PDA [TOKEN_STATE_SEED], token authority of pool_ata
+///   2. mint (read)                — ZUPY Token-2022 mint
+///   3. pool_ata (writable)        — must match token_state.pool_ata() (source)
+///   4. treasury_ata (writable)    — must match token_state.treasury_ata() (destination)
+///   5. token_program (read)       — Token-2022
+///
+/// Data: threshold (u64; 0 = one whole token, else 1..=10^decimals)
+/// Discriminator: `[9, 49, 242, 88, 156, 84, 109, 15]` (SHA256("global:sweep_dust"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (6 accounts) ─────────────────────────────────
+    if accounts.len() < 6 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let treasury = &accounts[0];
+    let token_state_account = &accounts[1];
+    let mint = &accounts[2];
+    let pool_ata = &accounts[3];
+    let treasury_ata = &accounts[4];
+    let token_program = &accounts[5];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let threshold = reader.read_u64()?;
+    reader.finish()?;
+
+    // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+    let bump = state.bump();
+
+    // ── Input validation: never sweep whole tokens ──────────────────────
+    let decimals = state.decimals();
+    let whole = whole_token(decimals);
+    let unit = match threshold {
+        0 => whole,
+        t if t <= whole => t,
+        _ => return Err(ZupyTokenError::InvalidAmount.into()),
+    };
+
+    // ── Treasury authorization ──────────────────────────────────────────
+    if !treasury.is_signer() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+    let treasury_key: &[u8; 32] = address_bytes(treasury.address());
+    if !state.is_treasury(treasury_key) {
+        return Err(ZupyTokenError::UnauthorizedTreasury.into());
+    }
+
+    // ── Mint validation ─────────────────────────────────────────────────
+    let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
+    if !mint.owned_by(&token_2022_addr) || state.mint() != mint.address().as_ref() {
+        return Err(ZupyTokenError::InvalidMint.into());
+    }
+
+    // ── Pool + treasury account validation ──────────────────────────────
+    if state.pool_ata() != pool_ata.address().as_ref() {
+        return Err(ZupyTokenError::InvalidPoolAccount.into());
+    }
+    validate_source_ata(pool_ata, mint.address(), token_state_account.address())?;
+    if state.treasury_ata() != treasury_ata.address().as_ref() {
+        return Err(ZupyTokenError::InvalidTreasuryAccount.into());
+    }
+
+    // ── Token program check ─────────────────────────────────────────────
+    if token_program.address() != &token_2022_addr {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    // ── Dust computation (no-op when zero) ──────────────────────────────
+    let dust = compute_dust(read_token_balance(pool_ata), unit);
+    if dust == 0 {
+        return Ok(());
+    }
+
+    // ── CPI: TransferChecked (token_state PDA signs) ────────────────────
+    let bump_bytes = [bump];
+    let signer_seeds: [Seed; 2] = [
+        Seed::from(TOKEN_STATE_SEED),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    let signer = Signer::from(&signer_seeds);
+
+    cpi_transfer_checked_for_mint(
+        pool_ata,
+        treasury_ata,
+        token_state_account,
+        mint,
+        dust,
+        decimals,
+        token_program.address(),
+        &[signer],
+    )?;
+
+    Ok(())
+}
+
+/// Dust in `balance` for a sweep unit: `balance % unit` (always `< unit`).
+pub fn compute_dust(balance: u64, unit: u64) -> u64 {
+    balance % unit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sweep_dust_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 8];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+
+    #[test]
+    fn test_whole_token_has_no_dust() {
+        assert_eq!(WHOLE_TOKEN, 1_000_000);
+        assert_eq!(compute_dust(WHOLE_TOKEN, WHOLE_TOKEN), 0);
+        assert_eq!(compute_dust(5 * WHOLE_TOKEN, WHOLE_TOKEN), 0);
+    }
+
+    #[test]
+    fn test_sub_token_balance_is_all_dust() {
+        assert_eq!(compute_dust(123_456, WHOLE_TOKEN), 123_456);
+        assert_eq!(compute_dust(7 * WHOLE_TOKEN + 123_456, WHOLE_TOKEN), 123_456);
+    }
+
+    #[test]
+    fn test_whole_token_follows_decimals() {
+        assert_eq!(whole_token(0), 1);
+        assert_eq!(whole_token(9), 1_000_000_000);
+        assert_eq!(compute_dust(1_500_000_123, whole_token(9)), 500_000_123);
+    }
+
+    #[test]
+    fn test_explicit_threshold() {
+        assert_eq!(compute_dust(123_456, 1_000), 456);
+        assert_eq!(compute_dust(123_456, 1), 0);
+    }
+}
@@ -0,0 +1,143 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::{AccountView, Address, ProgramResult};
+use pinocchio::error::ProgramError;
+
+use crate::constants::{LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, COMPANY_SEED};
+use crate::error::zupy_err;
+use crate::helpers::compressed_accounts::cpi_compressed_transfer;
+use crate::helpers::heartbeat::stamp_last_op;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::memo::{validate_memo_op, MemoOp};
+use crate::helpers::pda::validate_pda_or_find;
+use crate::helpers::transfer_validation::validate_transfer_common_compressed;
+use crate::state::token_state::LastOp;
+
+/// Process `transfer_company_to_company` instruction.
+///
+/// Moves ZUPY between two company compressed balances (e.g. franchise groups)
+/// via Light cToken `Transfer` CPI (Path B: compressed → compressed), avoiding
+/// a round-trip through the pool. The source company PDA is the compressed
+/// owner and CPI signer. Identical source and destination are rejected. Under
+/// `pda-bump-fallback` a wrong bump is tolerated for the canonical company PDAs.
+///
+/// Accounts (8):
+///   0. transfer_authority (signer)
+///   1. token_state (read)
+///   2. mint (read)
+///   3. from_company_pda (read)         — source owner / PDA signer
+///   4. to_company_pda (read)           — destination owner
+///   5. fee_payer (writable, signer)    — pays Light state tree fees
+///   6. system_program (read)
+///   7. compressed_token_program (read) — cTokenmWW8bLPjZEBAUgYy3zKxQZW6VKi7bqNFEVv3m
+///
+/// Data: from_company_id_u64 (u64) + to_company_id_u64 (u64) + amount (u64)
+///       + from_bump (u8) + to_bump (u8) + memo (String, e.g. `zupy:v1:c2c:<from>:<to>`)
+///
+/// Discriminator: [42, 241, 16, 155, 103, 235, 235, 117] (SHA256("global:transfer_company_to_company"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (8 accounts minimum) ─────────────────────────
+    if accounts.len() < 8 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let transfer_authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let mint = &accounts[2];
+    let from_company_pda = &accounts[3]; // source owner / PDA signer
+    let to_company_pda = &accounts[4];   // destination owner
+    let fee_payer = &accounts[5];
+    let system_program = &accounts[6];
+    let compressed_token_program = &accounts[7];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let from_company_id_u64 = reader.read_u64()?;
+    let to_company_id_u64 = reader.read_u64()?;
+    let amount = reader.read_u64()?;
+    let from_bump = reader.read_u8()?;
+    let to_bump = reader.read_u8()?;
+    let memo = reader.read_string()?;
+    reader.finish()?;
+
+    // ── Input validation ────────────────────────────────────────────────
+    if amount == 0 {
+        return Err(zupy_err!(ZeroAmount, "amount"));
+    }
+    if from_company_id_u64 == to_company_id_u64 || from_company_pda.address() == to_company_pda.address() {
+        return Err(zupy_err!(SelfTransfer, "same_company"));
+    }
+    validate_memo_op(memo, MemoOp::CompanyToCompany)?;
+
+    // ── Common transfer validation (checks 1–8) ─────────────────────────
+    validate_transfer_common_compressed(
+        program_id,
+        token_state_account,
+        transfer_authority,
+        mint,
+    )?;
+
+    // ── Check 9: compressed_token_program is Light cToken program ───────
+    let light_ctoken_addr = Address::from(LIGHT_COMPRESSED_TOKEN_PROGRAM_ID);
+    if compressed_token_program.address() != &light_ctoken_addr {
+        return Err(zupy_err!(InvalidTokenProgram, "ctoken_program"));
+    }
+
+    // ── PDA validation: from_company_pda (source) ───────────────────────
+    let from_id_bytes = from_company_id_u64.to_le_bytes();
+    let from_bump = validate_pda_or_find(
+        from_company_pda.address(),
+        &[COMPANY_SEED, &from_id_bytes],
+        from_bump,
+        program_id,
+    )?;
+
+    // ── PDA validation: to_company_pda (destination) ────────────────────
+    let to_id_bytes = to_company_id_u64.to_le_bytes();
+    validate_pda_or_find(
+        to_company_pda.address(),
+        &[COMPANY_SEED, &to_id_bytes],
+        to_bump,
+        program_id,
+    )?;
+
+    // ── CPI: compressed transfer (from_company_pda signs) ───────────────
+    let from_bump_bytes = [from_bump];
+    let signer_seeds: [Seed; 3] = [
+        Seed::from(COMPANY_SEED),
+        Seed::from(from_id_bytes.as_ref()),
+        Seed::from(from_bump_bytes.as_ref()),
+    ];
+    let signer = Signer::from(&signer_seeds);
+
+    cpi_compressed_transfer(
+        compressed_token_program,
+        fee_payer,
+        from_company_pda, // source
+        to_company_pda,   // destination
+        from_company_pda, // authority (source PDA signs)
+        system_program,
+        amount,
+        &[signer],
+    )?;
+
+    // ── Heartbeat: last_transfer_ts (v2, writable token_state only) ─────
+    stamp_last_op(token_state_account, LastOp::Transfer)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_company_to_company_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 26];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
@@ -4,10 +4,23 @@ use pinocchio::error::ProgramError;
 use pinocchio::instruction::{InstructionAccount, InstructionView};
 
 use crate::constants::{COMPANY_SEED, LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, USER_SEED};
-use crate::helpers::compressed_accounts::validate_v1_transfer_disc;
-use crate::helpers::instruction_data::{parse_u64, parse_u8};
+use crate::helpers::account_checks::expect_accounts;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::company_counters::{company_counters, split_company_state};
+use crate::helpers::compressed_accounts::{
+    parse_v1_proof_layout, v1_transfer_amount, validate_v1_transfer_disc, TRANSFER_V1_DISC,
+};
+use crate::helpers::events::{emit_anchor_event, TransferEvent};
+use crate::helpers::heartbeat::stamp_last_op;
+use crate::helpers::instruction_data::Reader;
 use crate::helpers::pda::validate_pda_with_seeds;
-use crate::helpers::transfer_validation::validate_transfer_common_compressed;
+use crate::helpers::receipt::{create_receipt, parse_op_id};
+use crate::helpers::transfer_validation::{
+    enforce_per_tx_limit, validate_transfer_common_compressed,
+};
+use crate::helpers::stats::{split_stats_state, stats_counters};
+use crate::helpers::user_registry::{enforce_user_registration, split_user_state};
+use crate::state::token_state::LastOp;
 
 /// Process `transfer_company_to_user` instruction (V1 CPI passthrough).
 ///
@@ -29,6 +42,13 @@ use crate::helpers::transfer_validation::validate_transfer_common_compressed;
 /// 4. User PDA derivation must match `user_id` (validates destination)
 /// 5. CPI data must start with V1 TRANSFER disc (prevents other cToken instructions)
 /// 6. CPI target hardcoded to `LIGHT_COMPRESSED_TOKEN_PROGRAM_ID`
+/// 7. Amount leaving the company PDA (V1 outputs not owned by it) within
+///    `per_tx_auto_limit` (0 = no limit), unless the treasury co-signs as an
+///    extra account → `ExceedsPerTxLimit`
+/// 8. With `strict_registration` on, the user's UserState must be passed →
+///    `UserNotRegistered`
+/// 9. V1 proof structure and packed indices (`parse_v1_proof_layout`) →
+///    `InvalidCompressedProof`
 ///
 /// ## Accounts (minimum 5 + CPI accounts)
 ///
@@ -39,6 +59,21 @@ use crate::helpers::transfer_validation::validate_transfer_common_compressed;
 ///   4. user_pda                  (read)             — compressed destination (validated)
 ///   5+ V1 CPI accounts           (client-assembled) — forwarded to cToken in V1 order
 ///
+/// With an `op_id`, the receipt PDA `[RECEIPT_SEED, op_id]` (writable, created here)
+/// is inserted at 5 and the V1 CPI accounts move to 6+. Receipt rent is paid by
+/// account 6, the V1 fee_payer (must sign).
+///
+/// Optional: the user's UserState PDA `[USER_STATE_SEED, user_id]` as the last
+/// account; it is stripped before the V1 CPI accounts are forwarded.
+///
+/// Optional: the company's CompanyState PDA (writable) before any UserState,
+/// also stripped; the amount is added to its `total_sent`. See
+/// `helpers::company_counters`.
+///
+/// Optional: the Stats PDA `[STATS_SEED]` (writable) before any CompanyState,
+/// also stripped; the amount is added to its `total_company_to_user`. See
+/// `helpers::stats`.
+///
 /// ## Data Layout (after 8-byte Anchor discriminator, stripped by lib.rs)
 ///
 /// ```text
@@ -49,45 +84,79 @@ use crate::helpers::transfer_validation::validate_transfer_common_compressed;
 /// [18..]   raw V1 CPI instruction data (starts with 8-byte V1 TRANSFER disc)
 /// ```
 ///
+/// With an idempotency receipt, a 16-byte `op_id` precedes the V1 data:
+///
+/// ```text
+/// [18..34] op_id ([u8; 16])
+/// [34..]   raw V1 CPI instruction data
+/// ```
+///
+/// The two layouts are told apart by whether byte 18 starts the V1 TRANSFER disc.
+///
 /// Discriminator: [8, 143, 213, 13, 143, 247, 145, 33] — UNCHANGED
 pub fn process(
     program_id: &Address,
     accounts: &[AccountView],
     data: &[u8],
 ) -> ProgramResult {
+    // ── Optional trailing UserState (registration check) ────────────────
+    let (accounts, user_state) = split_user_state(program_id, accounts);
+
+    // ── Optional trailing CompanyState (balance counters) ───────────────
+    let (accounts, company_state) = split_company_state(program_id, accounts);
+
+    // ── Optional trailing Stats (global volume counters) ────────────────
+    let (accounts, stats) = split_stats_state(program_id, accounts);
+
     // ── Account extraction (minimum 5 validation accounts) ──────────────
-    if accounts.len() < 5 {
-        return Err(ProgramError::NotEnoughAccountKeys);
-    }
-    let transfer_authority  = &accounts[0];
-    let token_state_account = &accounts[1];
-    let mint                = &accounts[2];
-    let company_pda         = &accounts[3]; // source PDA / CPI signer
-    let user_pda            = &accounts[4]; // destination PDA
+    expect_accounts!(accounts, [
+        transfer_authority,
+        token_state_account,
+        mint,
+        company_pda, // source PDA / CPI signer
+        user_pda,    // destination PDA
+    ]);
 
     // ── Parse instruction data ──────────────────────────────────────────
-    let company_id_u64 = parse_u64(data, 0)?;
-    let user_id_u64 = parse_u64(data, 8)?;
-    let company_bump = parse_u8(data, 16)?;
-    let user_bump = parse_u8(data, 17)?;
+    let mut reader = Reader::new(data);
+    let company_id_u64 = reader.read_u64()?;
+    let user_id_u64 = reader.read_u64()?;
+    let company_bump = reader.read_u8()?;
+    let user_bump = reader.read_u8()?;
 
-    // Raw V1 CPI instruction data starts at offset 18
-    if data.len() <= 18 {
+    // Raw V1 CPI instruction data follows at offset 18, or 34 after an op_id
+    if reader.is_empty() {
         return Err(ProgramError::InvalidInstructionData);
     }
-    let v1_cpi_data = &data[18..];
+    let op_id = if reader.rest().starts_with(&TRANSFER_V1_DISC) {
+        None
+    } else {
+        parse_op_id(&mut reader)
+    };
+    let v1_cpi_data = reader.rest();
+    let cpi_accounts_start = if op_id.is_some() { 6 } else { 5 };
+    // Receipt mode needs the receipt (5) and its rent payer (6)
+    if op_id.is_some() && accounts.len() < 7 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
 
     // ── Validate V1 TRANSFER discriminator prefix ───────────────────────
     validate_v1_transfer_disc(v1_cpi_data)?;
 
     // ── Common transfer validation (checks 1–8) ─────────────────────────
-    validate_transfer_common_compressed(
+    let validation = validate_transfer_common_compressed(
         program_id,
         token_state_account,
         transfer_authority,
         mint,
     )?;
 
+    // ── Per-transaction limit on the V1 outputs leaving company_pda ─────
+    let company_key: &[u8; 32] = company_pda.address().as_ref().try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    let amount = v1_transfer_amount(v1_cpi_data, company_key)?;
+    enforce_per_tx_limit(&validation.state, amount, accounts)?;
+
     // ── PDA validation: company_pda (source) ────────────────────────────
     let company_id_bytes = company_id_u64.to_le_bytes();
     validate_pda_with_seeds(
@@ -104,9 +173,18 @@ pub fn process(
         program_id,
     )?;
 
+    // ── Strict registration: user must be registered ────────────────────
+    enforce_user_registration(program_id, &validation.state, user_state, user_pda.address())?;
+
+    // ── Idempotency receipt (optional; duplicate op_id fails here) ──────
+    if let Some(op_id) = &op_id {
+        create_receipt(program_id, &accounts[5], &accounts[6], op_id)?;
+    }
+
     // ── Build CPI instruction for cToken V1 ─────────────────────────────
     let prog_id: Address = LIGHT_COMPRESSED_TOKEN_PROGRAM_ID.into();
-    let cpi_accounts = &accounts[5..];
+    let cpi_accounts = &accounts[cpi_accounts_start..];
+    parse_v1_proof_layout(v1_cpi_data, cpi_accounts.len())?;
 
     // Build account metas from CPI accounts, forcing company_pda to be signer.
     // The company_pda is not a signer on the outer transaction (only our program
@@ -133,6 +211,13 @@ pub fn process(
     // Build account view slice matching instruction.accounts 1:1.
     let account_views: Vec<&AccountView> = cpi_accounts.iter().collect();
 
+    // ── Event log (all checks passed) ───────────────────────────────────
+    emit_anchor_event(&TransferEvent {
+        from: *company_key,
+        to: *address_bytes(user_pda.address()),
+        amount,
+    });
+
     // ── CPI: Forward V1 TRANSFER to cToken, signing with company PDA ────
     let bump_bytes = [company_bump];
     let signer_seeds: [Seed; 3] = [
@@ -144,6 +229,18 @@ pub fn process(
 
     pinocchio::cpi::invoke_signed_with_slice(&instruction, &account_views, &[signer])?;
 
+    // ── Company counters (optional, saturating) ─────────────────────────
+    if let Some(mut counters) = company_counters(company_state, company_id_u64) {
+        counters.record_sent(amount);
+    }
+    // ── Global stats (optional, saturating) ─────────────────────────────
+    if let Some(mut counters) = stats_counters(stats) {
+        counters.record_company_to_user(amount);
+    }
+
+    // ── Heartbeat: last_transfer_ts (v2, writable token_state only) ─────
+    stamp_last_op(token_state_account, LastOp::Transfer)?;
+
     Ok(())
 }
 
@@ -165,7 +262,7 @@ mod tests {
         let program_id = Address::from([1u8; 32]);
         let data = [0u8; 32];
         fn make_buf(addr: [u8; 32]) -> Vec<u64> {
-            let words = (core::mem::size_of::<RuntimeAccount>() + 7) / 8 + 1;
+            let words = core::mem::size_of::<RuntimeAccount>().div_ceil(8) + 1;
             let mut buf = vec![0u64; words];
             let raw = buf.as_mut_ptr() as *mut RuntimeAccount;
             unsafe {
@@ -0,0 +1,186 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::{AccountView, Address, ProgramResult};
+use pinocchio::error::ProgramError;
+use pinocchio::instruction::{InstructionAccount, InstructionView};
+
+use crate::constants::{COMPANY_SEED, LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, USER_SEED};
+use crate::error::zupy_err;
+use crate::helpers::compressed_accounts::{
+    parse_v1_proof_layout, v1_transfer_amount, validate_v1_transfer_disc,
+};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::memo::{validate_memo_op, MemoOp};
+use crate::helpers::pda::validate_pda_with_seeds;
+use crate::helpers::transfer_validation::{
+    enforce_per_tx_limit, enforce_v1_sunset, validate_transfer_common_compressed,
+};
+
+/// Process `transfer_company_to_user_v1` instruction (V1 CPI passthrough, mainnet).
+///
+/// Moves legacy company balances held on the V1 mainnet cToken program to a user.
+/// Same passthrough as `return_user_to_pool_v1`: the backend builds the complete
+/// V1 TRANSFER data and V1-ordered account list, this instruction validates the
+/// shared preconditions and forwards both untouched, signing with the company PDA.
+/// Unlike `transfer_company_to_user` it carries a memo and takes the V1 program as
+/// an explicit account checked against `LIGHT_COMPRESSED_TOKEN_PROGRAM_ID`.
+///
+/// ## Security Validations
+///
+/// 1. CPI data must start with the V1 TRANSFER disc
+/// 2. Amount leaving the company PDA must be non-zero → `ZeroAmount`
+/// 3. Memo must be a `c2u` memo (`validate_memo_op`) → `InvalidMemoFormat`
+/// 4. `transfer_authority` signer, token_state, mint and paused flag
+///    (via `validate_transfer_common_compressed`)
+/// 5. `token_state.v1_sunset_ts` not yet reached → `V1Sunset`
+/// 6. `v1_program` must be `LIGHT_COMPRESSED_TOKEN_PROGRAM_ID` → `IncorrectProgramId`
+/// 7. Amount within `per_tx_auto_limit` unless the treasury co-signs → `ExceedsPerTxLimit`
+/// 8. Company and user PDA derivations must match their ids → `InvalidPDA`
+/// 9. V1 proof structure and packed indices (`parse_v1_proof_layout`)
+///    → `InvalidCompressedProof`
+///
+/// ## Accounts (minimum 6 + CPI accounts)
+///
+///   0. transfer_authority        (signer)           — must match TRANSFER_AUTHORITY_PUBKEY
+///   1. token_state               (read)             — our program's token_state PDA
+///   2. mint                      (read)             — ZUPY Token-2022 mint
+///   3. company_pda               (read)             — compressed source + CPI signer
+///   4. user_pda                  (read)             — compressed destination (validated)
+///   5. v1_program                (read)             — LIGHT_COMPRESSED_TOKEN_PROGRAM_ID
+///   6+ V1 CPI accounts           (client-assembled) — forwarded to cToken in V1 order
+///
+/// ## Data Layout (after 8-byte Anchor discriminator, stripped by lib.rs)
+///
+/// ```text
+/// [0..8]   company_id (u64 LE)
+/// [8..16]  user_id (u64 LE)
+/// [16]     company_bump (u8)
+/// [17]     user_bump (u8)
+/// [18..]   memo (String) followed by raw V1 CPI data (starts with V1 TRANSFER disc)
+/// ```
+///
+/// Discriminator: [120, 245, 74, 115, 49, 3, 57, 70] (SHA256("global:transfer_company_to_user_v1"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (minimum 6 validation accounts) ──────────────
+    if accounts.len() < 6 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let transfer_authority  = &accounts[0];
+    let token_state_account = &accounts[1];
+    let mint                = &accounts[2];
+    let company_pda         = &accounts[3]; // source PDA / CPI signer
+    let user_pda            = &accounts[4]; // destination PDA
+    let v1_program          = &accounts[5];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let company_id_u64 = reader.read_u64()?;
+    let user_id_u64 = reader.read_u64()?;
+    let company_bump = reader.read_u8()?;
+    let user_bump = reader.read_u8()?;
+    let memo = reader.read_string()?;
+    let v1_cpi_data = reader.rest();
+
+    // ── Validate V1 TRANSFER discriminator prefix ───────────────────────
+    validate_v1_transfer_disc(v1_cpi_data)?;
+
+    // ── Input validation ────────────────────────────────────────────────
+    let company_key: &[u8; 32] = company_pda.address().as_ref().try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    let amount = v1_transfer_amount(v1_cpi_data, company_key)?;
+    if amount == 0 {
+        return Err(zupy_err!(ZeroAmount, "amount"));
+    }
+    validate_memo_op(memo, MemoOp::CompanyToUser)?;
+
+    // ── Common transfer validation (checks 1–8) ─────────────────────────
+    let validation = validate_transfer_common_compressed(
+        program_id,
+        token_state_account,
+        transfer_authority,
+        mint,
+    )?;
+
+    // ── V1 sunset (0 = no cutoff) ───────────────────────────────────────
+    enforce_v1_sunset(&validation.state)?;
+
+    // ── Verify v1_program is the mainnet cToken program ─────────────────
+    let prog_id: Address = LIGHT_COMPRESSED_TOKEN_PROGRAM_ID.into();
+    if v1_program.address() != &prog_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // ── Per-transaction limit (treasury override above it) ──────────────
+    enforce_per_tx_limit(&validation.state, amount, accounts)?;
+
+    // ── PDA validation: company_pda (source) ────────────────────────────
+    let company_id_bytes = company_id_u64.to_le_bytes();
+    validate_pda_with_seeds(
+        company_pda.address(),
+        &[COMPANY_SEED, &company_id_bytes, &[company_bump]],
+        program_id,
+    )?;
+
+    // ── PDA validation: user_pda (destination) ──────────────────────────
+    let user_id_bytes = user_id_u64.to_le_bytes();
+    validate_pda_with_seeds(
+        user_pda.address(),
+        &[USER_SEED, &user_id_bytes, &[user_bump]],
+        program_id,
+    )?;
+
+    // ── Build CPI instruction for cToken V1 ─────────────────────────────
+    let cpi_accounts = &accounts[6..];
+    parse_v1_proof_layout(v1_cpi_data, cpi_accounts.len())?;
+
+    // Forward the V1 accounts as given, forcing company_pda to be signer
+    // (invoke_signed provides its signature via the company PDA seeds).
+    let mut account_metas = Vec::with_capacity(cpi_accounts.len());
+    for acct in cpi_accounts {
+        let is_company_pda = acct.address() == company_pda.address();
+        let meta = match (acct.is_writable(), acct.is_signer() || is_company_pda) {
+            (true, true)  => InstructionAccount::writable_signer(acct.address()),
+            (true, false) => InstructionAccount::writable(acct.address()),
+            (false, true) => InstructionAccount::readonly_signer(acct.address()),
+            _             => InstructionAccount::readonly(acct.address()),
+        };
+        account_metas.push(meta);
+    }
+
+    let instruction = InstructionView {
+        program_id: &prog_id,
+        accounts: &account_metas,
+        data: v1_cpi_data,
+    };
+
+    let account_views: Vec<&AccountView> = cpi_accounts.iter().collect();
+
+    // ── CPI: Forward V1 TRANSFER to cToken, signing with company PDA ────
+    let bump_bytes = [company_bump];
+    let signer_seeds: [Seed; 3] = [
+        Seed::from(COMPANY_SEED),
+        Seed::from(company_id_bytes.as_ref()),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    let signer = Signer::from(&signer_seeds);
+
+    pinocchio::cpi::invoke_signed_with_slice(&instruction, &account_views, &[signer])?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_company_to_user_v1_not_enough_account_keys() {
+        let program_id = Address::default();
+        let data = [0u8; 32];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+}
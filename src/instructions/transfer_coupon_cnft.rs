@@ -0,0 +1,117 @@
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::{BUBBLEGUM_PROGRAM_ID, SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use crate::error::ZupyTokenError;
+use crate::helpers::cpi::{cpi_bubblegum_transfer, CnftLeaf};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::transfer_validation::validate_nft_payer;
+
+/// Process `transfer_coupon_cnft` instruction.
+///
+/// Moves a compressed coupon NFT to another wallet via manual Bubblegum
+/// Transfer CPI. Leaf fields, proof and signing follow `burn_coupon_cnft`:
+/// leaf_owner or leaf_delegate must sign the transaction, this program signs
+/// nothing. mint_authority must sign, the system must not be paused, and a
+/// new_leaf_owner equal to leaf_owner fails with `SelfTransfer`.
+///
+/// Accounts (11 + proof):
+///   0. mint_authority (signer) — must match token_state.mint_authority()
+///   1. tree_config (read)
+///   2. leaf_owner (read, signer unless leaf_delegate signs)
+///   3. leaf_delegate (read, signer unless leaf_owner signs)
+///   4. new_leaf_owner (read) — recipient
+///   5. merkle_tree (writable)
+///   6. bubblegum_program (read)
+///   7. compression_program (read)
+///   8. log_wrapper (read)
+///   9. system_program (read)
+///  10. token_state (read) — PDA [TOKEN_STATE_SEED], Audit 12.1
+///  11.. proof nodes (read) — merkle proof minus the canopy
+///
+/// Data: root ([u8; 32]) + data_hash ([u8; 32]) + creator_hash ([u8; 32])
+///       + nonce (u64) + index (u32)
+/// Discriminator: [186, 174, 51, 18, 51, 212, 240, 93] (SHA256("global:transfer_coupon_cnft"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (11 accounts + proof) ────────────────────────
+    if accounts.len() < 11 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let mint_authority = &accounts[0];
+    let tree_config = &accounts[1];
+    let leaf_owner = &accounts[2];
+    let leaf_delegate = &accounts[3];
+    let new_leaf_owner = &accounts[4];
+    let merkle_tree = &accounts[5];
+    let bubblegum_program = &accounts[6];
+    let compression_program = &accounts[7];
+    let log_wrapper = &accounts[8];
+    let system_program = &accounts[9];
+    let token_state_account = &accounts[10];
+    let proof = &accounts[11..];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let leaf = CnftLeaf::parse(&mut reader)?;
+    reader.finish()?;
+
+    // ── Authority (signer + token_state + mint_authority), not paused ───
+    let state = validate_nft_payer(program_id, mint_authority, token_state_account)?;
+    if state.paused() {
+        return Err(ZupyTokenError::SystemPaused.into());
+    }
+
+    // ── Recipient must differ from the current owner ────────────────────
+    if new_leaf_owner.address() == leaf_owner.address() {
+        return Err(ZupyTokenError::SelfTransfer.into());
+    }
+
+    // ── Hardcoded program ID checks ─────────────────────────────────────
+    let expected_bubblegum = Address::from(BUBBLEGUM_PROGRAM_ID);
+    if bubblegum_program.address() != &expected_bubblegum {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    let expected_compression = Address::from(SPL_ACCOUNT_COMPRESSION_ID);
+    if compression_program.address() != &expected_compression {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    let expected_noop = Address::from(SPL_NOOP_ID);
+    if log_wrapper.address() != &expected_noop {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    // ── CPI: Bubblegum Transfer (leaf owner / delegate signed the tx) ───
+    cpi_bubblegum_transfer(
+        tree_config,
+        leaf_owner,
+        leaf_delegate,
+        new_leaf_owner,
+        merkle_tree,
+        log_wrapper,
+        compression_program,
+        system_program,
+        bubblegum_program,
+        proof,
+        &leaf,
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_coupon_cnft_not_enough_account_keys() {
+        let program_id = Address::default();
+        let result = process(&program_id, &[], &[0u8; 108]);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+}
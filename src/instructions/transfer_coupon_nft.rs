@@ -0,0 +1,199 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::{TOKEN_2022_PROGRAM_ID, USER_PDA_SEED};
+use crate::error::ZupyTokenError;
+use crate::helpers::account_checks::{assert_associated_token_address, assert_discriminator};
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::coupon::{create_coupon_state_if_needed, CouponTerms};
+use crate::helpers::cpi::{cpi_create_ata_idempotent, cpi_transfer_checked};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::pda::{derive_coupon_mint_pda, derive_user_nft_pda, validate_pda};
+use crate::helpers::transfer_validation::{
+    read_token_balance, read_token_owner, validate_destination_ata_if_exists,
+    validate_source_ata, validate_token_state_base,
+};
+use crate::state::coupon_state::{
+    CouponState, CouponStateMut, COUPON_STATE_DISCRIMINATOR, COUPON_STATE_SIZE,
+};
+
+/// Process `transfer_coupon_nft` instruction.
+///
+/// Gifts an unredeemed coupon between users: moves the coupon token from the
+/// from_user_pda ATA (from_user_pda signs) to the to_user_pda ATA, creating it
+/// if needed, and records to_user_pda as the CouponState `holder`. Coupons
+/// minted without a CouponState get one first, held by from_user_pda.
+///
+/// Redeemed coupons fail with `CouponAlreadyRedeemed`, expired ones with
+/// `CouponExpired`, and a from_user_pda other than the recorded holder with
+/// `InvalidAuthority`. The source ATA must hold exactly the one coupon token,
+/// else `InsufficientBalance`.
+///
+/// Accounts (11):
+///   0. transfer_authority (writable, signer) — pays for the ATA / missing coupon_state
+///   1. token_state (read) — PDA [TOKEN_STATE_SEED]
+///   2. from_user_pda (read) — PDA [b"user_pda", &from_user_ksuid], current holder
+///   3. to_user_pda (read) — PDA [b"user_pda", &to_user_ksuid], recipient
+///   4. coupon_mint (read) — PDA [b"coupon", &coupon_ksuid]
+///   5. from_ata (writable) — coupon ATA for from_user_pda
+///   6. to_ata (writable) — coupon ATA for to_user_pda, created if needed
+///   7. coupon_state (writable) — PDA [b"coupon_state", &coupon_ksuid]
+///   8. token_program (read) — Token-2022
+///   9. associated_token_program (read)
+///  10. system_program (read)
+///
+/// Data: coupon_ksuid ([u8; 27]) + from_user_ksuid ([u8; 27]) + to_user_ksuid ([u8; 27])
+/// Discriminator: [33, 2, 46, 248, 13, 49, 16, 109] (SHA256("global:transfer_coupon_nft"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (11 accounts) ────────────────────────────────
+    if accounts.len() < 11 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let transfer_authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let from_user_pda = &accounts[2];
+    let to_user_pda = &accounts[3];
+    let coupon_mint = &accounts[4];
+    let from_ata = &accounts[5];
+    let to_ata = &accounts[6];
+    let coupon_state = &accounts[7];
+    let token_program = &accounts[8];
+    let _associated_token_program = &accounts[9];
+    let system_program = &accounts[10];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let coupon_ksuid = reader.read_bytes::<27>()?;
+    let from_user_ksuid = reader.read_bytes::<27>()?;
+    let to_user_ksuid = reader.read_bytes::<27>()?;
+    reader.finish()?;
+
+    // ── Authority: transfer_authority, not paused ───────────────────────
+    let state = validate_token_state_base(program_id, token_state_account)?;
+    if state.paused() {
+        return Err(ZupyTokenError::SystemPaused.into());
+    }
+    if !transfer_authority.is_signer()
+        || state.transfer_authority() != transfer_authority.address().as_ref()
+    {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+
+    // ── Token program check ─────────────────────────────────────────────
+    let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
+    if token_program.address() != &token_2022_addr {
+        return Err(ZupyTokenError::InvalidTokenProgram.into());
+    }
+
+    // ── PDA validations: both user PDAs, coupon_mint (must be minted) ───
+    let (expected_from_pda, from_bump) = derive_user_nft_pda(program_id, from_user_ksuid);
+    validate_pda(from_user_pda.address(), &expected_from_pda)?;
+
+    let (expected_to_pda, _) = derive_user_nft_pda(program_id, to_user_ksuid);
+    validate_pda(to_user_pda.address(), &expected_to_pda)?;
+
+    let (expected_coupon_mint, _) = derive_coupon_mint_pda(program_id, coupon_ksuid);
+    validate_pda(coupon_mint.address(), &expected_coupon_mint)?;
+    if !coupon_mint.owned_by(&token_2022_addr) || coupon_mint.data_len() == 0 {
+        return Err(ZupyTokenError::InvalidPDA.into());
+    }
+
+    // ── Coupon state: create if missing, reject if redeemed or expired ──
+    create_coupon_state_if_needed(
+        program_id,
+        coupon_state,
+        transfer_authority,
+        coupon_ksuid,
+        &expected_coupon_mint,
+        &expected_from_pda,
+        &CouponTerms::default(),
+    )?;
+    if !coupon_state.owned_by(program_id) || coupon_state.data_len() < COUPON_STATE_SIZE {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let coupon = CouponState::from_slice(unsafe { coupon_state.borrow_unchecked() });
+    assert_discriminator(coupon.discriminator(), &COUPON_STATE_DISCRIMINATOR)?;
+    if coupon.redeemed() {
+        return Err(ZupyTokenError::CouponAlreadyRedeemed.into());
+    }
+    use pinocchio::sysvars::Sysvar as _;
+    let clock = pinocchio::sysvars::clock::Clock::get()?;
+    if coupon.is_expired(clock.unix_timestamp) {
+        return Err(ZupyTokenError::CouponExpired.into());
+    }
+    if coupon.holder() != expected_from_pda.as_ref() {
+        return Err(ZupyTokenError::InvalidAuthority.into());
+    }
+
+    // ── Source ATA: mint + owner, holds the coupon ──────────────────────
+    validate_source_ata(from_ata, &expected_coupon_mint, &expected_from_pda)?;
+    if read_token_balance(from_ata) != 1 {
+        return Err(ZupyTokenError::InsufficientBalance.into());
+    }
+
+    // ── Destination ATA (mint + owner when it already exists) ───────────
+    validate_destination_ata_if_exists(to_ata, &expected_coupon_mint)?;
+    if to_ata.data_len() > 0 && read_token_owner(to_ata) != expected_to_pda.as_ref() {
+        return Err(ZupyTokenError::TokenOwnerMismatch.into());
+    }
+    assert_associated_token_address(
+        to_ata,
+        &expected_to_pda,
+        &expected_coupon_mint,
+        token_program.address(),
+        None,
+    )?;
+
+    // ── CPI 1: Create ATA for to_user_pda ───────────────────────────────
+    cpi_create_ata_idempotent(
+        to_ata,
+        transfer_authority,
+        to_user_pda,
+        coupon_mint,
+        token_program,
+        system_program,
+    )?;
+
+    // ── CPI 2: TransferChecked the coupon (from_user_pda signs) ─────────
+    let from_bump_bytes = [from_bump];
+    let from_signer_seeds: [Seed; 3] = [
+        Seed::from(USER_PDA_SEED),
+        Seed::from(from_user_ksuid.as_ref()),
+        Seed::from(from_bump_bytes.as_ref()),
+    ];
+
+    cpi_transfer_checked(
+        from_ata,
+        to_ata,
+        from_user_pda,
+        coupon_mint,
+        1,
+        0,
+        &token_2022_addr,
+        &[Signer::from(&from_signer_seeds)],
+    )?;
+
+    // ── Record the new holder ───────────────────────────────────────────
+    let holder_key: &[u8; 32] = address_bytes(&expected_to_pda);
+    CouponStateMut::from_slice(unsafe { coupon_state.borrow_unchecked_mut() })
+        .set_holder(holder_key);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_coupon_nft_not_enough_account_keys() {
+        let program_id = Address::default();
+        let result = process(&program_id, &[], &[0u8; 81]);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+}
@@ -3,13 +3,24 @@ use pinocchio::{AccountView, Address, ProgramResult};
 use pinocchio::error::ProgramError;
 
 use crate::constants::{LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, LIGHT_TOKEN_CPI_AUTHORITY, TOKEN_2022_PROGRAM_ID, TOKEN_STATE_SEED};
-use crate::error::ZupyTokenError;
-use crate::helpers::compressed_accounts::{cpi_compress_from_spl, derive_spl_interface_pda};
-use crate::helpers::instruction_data::{parse_string, parse_u64};
-use crate::helpers::memo::validate_memo_format;
-use crate::helpers::pda::validate_pda;
-use crate::helpers::transfer_validation::{read_token_balance, validate_transfer_common};
-use crate::state::token_state::TokenState;
+use crate::error::zupy_err;
+use crate::helpers::account_checks::expect_accounts;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::compressed_accounts::{cpi_compress_from_spl, validate_spl_interface_pda};
+use crate::helpers::cpi::{cpi_spl_memo, split_memo_program};
+use crate::helpers::events::{emit_anchor_event, TransferEvent};
+use crate::helpers::heartbeat::stamp_last_op;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::math::checked_sub_amount;
+use crate::helpers::memo::{validate_memo_op, MemoOp};
+use crate::helpers::nonce::{consume_nonce, parse_nonce};
+use crate::helpers::receipt::{create_receipt, parse_op_id};
+use crate::helpers::stats::{split_stats_state, stats_counters};
+use crate::helpers::transfer_validation::{
+    enforce_per_tx_limit, read_token_balance, validate_transfer_common,
+};
+use crate::helpers::user_registry::{enforce_user_registration, split_user_state};
+use crate::state::token_state::LastOp;
 
 /// Process `transfer_from_pool` instruction (compressed token version).
 ///
@@ -42,43 +53,92 @@ use crate::state::token_state::TokenState;
 ///   15. spl_interface_pda          (writable)         — Light SPL pool PDA (seeds=[b"pool", mint])
 ///   16+ Merkle tree output queue   (writable)         — injected by JS client
 ///
-/// Data: amount (u64, bytes 0–7) + memo (String, bytes 8+)
+/// With an `op_id`, the receipt PDA `[RECEIPT_SEED, op_id]` (writable, created
+/// here, rent paid by fee_payer) is inserted at 16 and the Merkle queue moves to 17+.
+///
+/// Amounts above `per_tx_auto_limit` (0 = no limit) fail with `ExceedsPerTxLimit`
+/// unless the treasury co-signs as an extra account (conventionally last).
+///
+/// Optional: the SPL Memo program (`SPL_MEMO_PROGRAM_ID`) as the very last account
+/// (after any treasury co-signer). When present the validated memo is also emitted
+/// via CPI to it; it is stripped before the Light accounts are forwarded.
+///
+/// Optional: the recipient's UserState PDA `[USER_STATE_SEED, user_id]` last
+/// (before any memo program), also stripped. With `strict_registration` on it
+/// is required and the recipient must be that user's PDA, else
+/// `UserNotRegistered`. See `helpers::user_registry`.
+///
+/// Optional: the Stats PDA `[STATS_SEED]` (writable) before any UserState, also
+/// stripped; the amount is added to its `total_pool_outflow`. See `helpers::stats`.
+///
+/// Replay protection (opt-in): a `[flag u8 = 1][nonce u64]` trailer after the
+/// payload (and after any op_id) must carry a nonce above
+/// `TokenState.last_transfer_nonce`, else `StaleNonce`; the nonce is then stored,
+/// so `token_state` must be writable in this mode. See `helpers::nonce`.
+///
+/// Passing token_state writable also stamps `last_transfer_ts` on a v2
+/// TokenState; read-only it is skipped. See `helpers::heartbeat`.
+///
+/// Data: amount (u64, bytes 0–7) + memo (String, bytes 8+) + spl_interface_bump (u8, after memo)
+///       + optional op_id ([u8; 16]) + optional nonce trailer (flag u8 + u64)
+///
+/// `spl_interface_bump` is the canonical bump of spl_interface_pda, checked with one
+/// `create_program_address` (a wrong bump fails with `InvalidPDA`); `0xFF`
+/// (`SPL_INTERFACE_BUMP_DERIVE`) derives it on-chain instead, for clients that
+/// don't track it.
 /// Discriminator: `[136, 167, 45, 66, 74, 252, 0, 16]` (SHA256("global:transfer_from_pool"))
 pub fn process(
     program_id: &Address,
     accounts: &[AccountView],
     data: &[u8],
 ) -> ProgramResult {
+    // ── Optional trailing SPL Memo program ──────────────────────────────
+    let (accounts, memo_program) = split_memo_program(accounts);
+
+    // ── Optional trailing UserState (registration check) ────────────────
+    let (accounts, user_state) = split_user_state(program_id, accounts);
+
+    // ── Optional trailing Stats (global volume counters) ────────────────
+    let (accounts, stats) = split_stats_state(program_id, accounts);
+
     // ── Account extraction (16 accounts minimum) ─────────────────────────
-    if accounts.len() < 16 {
-        return Err(ProgramError::NotEnoughAccountKeys);
-    }
-    let transfer_authority           = &accounts[0];
-    let token_state_account          = &accounts[1];
-    let mint                         = &accounts[2];
-    let pool_ata                     = &accounts[3];
-    let recipient                    = &accounts[4];
-    let fee_payer                    = &accounts[5];
-    let token_program                = &accounts[6];
-    let system_program               = &accounts[7];
-    let compressed_token_prog        = &accounts[8];
-    let cpi_authority_pda            = &accounts[9];
-    let light_system_program         = &accounts[10];
-    let registered_program_pda       = &accounts[11];
-    let noop_program                 = &accounts[12];
-    let account_compression_authority = &accounts[13];
-    let account_compression_program  = &accounts[14];
-    let spl_interface_pda            = &accounts[15];
+    expect_accounts!(accounts, [
+        transfer_authority,
+        token_state_account,
+        mint,
+        pool_ata,
+        recipient,
+        fee_payer,
+        token_program,
+        system_program,
+        compressed_token_prog,
+        cpi_authority_pda,
+        light_system_program,
+        registered_program_pda,
+        noop_program,
+        account_compression_authority,
+        account_compression_program,
+        spl_interface_pda,
+    ]);
 
     // ── Parse instruction data ──────────────────────────────────────────
-    let amount = parse_u64(data, 0)?;
-    let (memo, _) = parse_string(data, 8)?;
+    let mut reader = Reader::new(data);
+    let amount = reader.read_u64()?;
+    let memo = reader.read_string()?;
+    let spl_interface_bump = reader.read_u8()?;
+    let op_id = parse_op_id(&mut reader);
+    let nonce = parse_nonce(&mut reader)?;
+    reader.finish()?;
+    let remaining_start = if op_id.is_some() { 17 } else { 16 };
+    if accounts.len() < remaining_start {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
 
     // ── Input validation ────────────────────────────────────────────────
     if amount == 0 {
-        return Err(ZupyTokenError::ZeroAmount.into());
+        return Err(zupy_err!(ZeroAmount, "amount"));
     }
-    validate_memo_format(memo)?;
+    validate_memo_op(memo, MemoOp::PoolTransfer)?;
 
     // ── Common transfer validation (9 checks, Spec §7.1-§7.8) ───────────
     let validation = validate_transfer_common(
@@ -89,9 +149,15 @@ pub fn process(
         token_program,
     )?;
 
+    // ── Per-transaction limit (treasury override above it) ──────────────
+    enforce_per_tx_limit(&validation.state, amount, accounts)?;
+
+    // ── Strict registration: recipient must be a registered user ───────
+    enforce_user_registration(program_id, &validation.state, user_state, recipient.address())?;
+
     // ── Additional signer check: fee_payer ──────────────────────────────
     if !fee_payer.is_signer() {
-        return Err(ZupyTokenError::InvalidAuthority.into());
+        return Err(zupy_err!(FeePayerNotSigner, "fee_payer"));
     }
 
     // ── Verify compressed_token_program is the Light cToken program ──────
@@ -106,37 +172,56 @@ pub fn process(
         return Err(ProgramError::IncorrectProgramId);
     }
 
-    // ── Read token_state for pool_ata validation ────────────────────────
-    let state = TokenState::from_slice(unsafe { token_state_account.borrow_unchecked() });
-
     // ── Pool ATA validation ─────────────────────────────────────────────
+    let state = validation.state;
     if pool_ata.address().as_ref() != state.pool_ata() {
-        return Err(ZupyTokenError::InvalidPoolAccount.into());
+        return Err(zupy_err!(InvalidPoolAccount, "pool_ata"));
     }
     // Pool ATA must be owned by Token-2022 (Spec §7.1)
     let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
     if !pool_ata.owned_by(&token_2022_addr) {
-        return Err(ZupyTokenError::InvalidPoolAccount.into());
+        return Err(zupy_err!(InvalidPoolAccount, "pool_ata_owner"));
     }
 
     // ── Balance check ───────────────────────────────────────────────────
     let pool_balance = read_token_balance(pool_ata);
     if pool_balance < amount {
-        return Err(ZupyTokenError::InsufficientPoolBalance.into());
+        return Err(zupy_err!(InsufficientPoolBalance, "pool_balance"));
     }
 
     // ── Validate spl_interface_pda address ──────────────────────────────
     let mint_key: [u8; 32] = mint.address().as_ref().try_into()
         .map_err(|_| ProgramError::InvalidAccountData)?;
-    let (expected_spl_pda, _) = derive_spl_interface_pda(&mint_key);
-    validate_pda(spl_interface_pda.address(), &expected_spl_pda)?;
+    validate_spl_interface_pda(spl_interface_pda.address(), &mint_key, spl_interface_bump)?;
 
     // ── Encode recipient owner ───────────────────────────────────────────
     let owner: &[u8; 32] = recipient.address().as_ref().try_into()
         .map_err(|_| ProgramError::InvalidAccountData)?;
 
     // remaining_amount = pool_balance - amount (SPL to keep in pool_ata)
-    let remaining_amount = pool_balance - amount;
+    let remaining_amount = checked_sub_amount(pool_balance, amount)?;
+
+    // ── Replay-protection nonce (optional; stale nonce fails here) ──────
+    if let Some(nonce) = nonce {
+        consume_nonce(token_state_account, nonce)?;
+    }
+
+    // ── Idempotency receipt (optional; duplicate op_id fails here) ──────
+    if let Some(op_id) = &op_id {
+        create_receipt(program_id, &accounts[16], fee_payer, op_id)?;
+    }
+
+    // ── Standard memo (optional) ────────────────────────────────────────
+    if let Some(memo_program) = memo_program {
+        cpi_spl_memo(memo_program, memo)?;
+    }
+
+    // ── Event log (all checks passed) ───────────────────────────────────
+    emit_anchor_event(&TransferEvent {
+        from: *address_bytes(pool_ata.address()),
+        to: *owner,
+        amount,
+    });
 
     // ── CPI: Compress from Pool ATA → compressed leaf for recipient ──────
     // token_state PDA signs with [TOKEN_STATE_SEED, &[bump]]
@@ -163,10 +248,18 @@ pub fn process(
         system_program,
         owner,
         Some(remaining_amount),
-        &accounts[16..],            // remaining: Merkle tree output queue
+        &accounts[remaining_start..], // remaining: Merkle tree output queue
         &[signer],
     )?;
 
+    // ── Global stats (optional, saturating) ─────────────────────────────
+    if let Some(mut counters) = stats_counters(stats) {
+        counters.record_pool_outflow(amount);
+    }
+
+    // ── Heartbeat: last_transfer_ts (v2, writable token_state only) ─────
+    stamp_last_op(token_state_account, LastOp::Transfer)?;
+
     Ok(())
 }
 
@@ -0,0 +1,196 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::{AccountView, Address, ProgramResult};
+use pinocchio::error::ProgramError;
+
+use crate::constants::{
+    COMPANY_SEED, LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, LIGHT_TOKEN_CPI_AUTHORITY,
+    TOKEN_2022_PROGRAM_ID, TOKEN_STATE_SEED,
+};
+use crate::error::zupy_err;
+use crate::helpers::compressed_accounts::{cpi_compress_from_spl, derive_spl_interface_pda};
+use crate::helpers::heartbeat::stamp_last_op;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::math::checked_sub_amount;
+use crate::helpers::memo::{validate_memo_op, MemoOp};
+use crate::helpers::pda::{validate_pda, validate_pda_with_seeds};
+use crate::helpers::transfer_validation::{read_token_balance, validate_transfer_common};
+use crate::state::token_state::LastOp;
+
+/// Process `transfer_pool_to_company` instruction (compressed token version).
+///
+/// Merchant onboarding: compresses tokens from the distribution pool ATA
+/// straight into a company PDA's compressed balance, so the backend does not
+/// need a second hop after `transfer_from_pool`. Same Path A compress CPI as
+/// `transfer_from_pool`, with the recipient validated as `[COMPANY_SEED, company_id]`.
+///
+/// Accounts (16 minimum, plus ≥1 remaining Merkle tree accounts):
+///   0.  transfer_authority         (signer)           — must match TRANSFER_AUTHORITY_PUBKEY
+///   1.  token_state                (read)             — our program's token_state PDA
+///   2.  mint                       (read)             — ZUPY Token-2022 mint
+///   3.  pool_ata                   (writable)         — distribution pool ATA (source)
+///   4.  company_pda                (read)             — PDA [COMPANY_SEED, company_id], compressed owner
+///   5.  fee_payer                  (writable, signer) — pays Light Protocol rent/fees
+///   6.  token_program              (read)             — Token-2022 program
+///   7.  system_program             (read)             — System program
+///   8.  compressed_token_program   (read)             — Light cToken program
+///   9.  cpi_authority_pda          (read)             — LIGHT_TOKEN_CPI_AUTHORITY
+///   10. light_system_program       (read)             — LIGHT_SYSTEM_PROGRAM_ID
+///   11. registered_program_pda     (read)             — REGISTERED_PROGRAM_PDA
+///   12. noop_program               (read)             — SPL_NOOP_ID
+///   13. account_compression_authority (read)          — ACCOUNT_COMPRESSION_AUTHORITY
+///   14. account_compression_program  (read)           — ACCOUNT_COMPRESSION_PROGRAM_ID
+///   15. spl_interface_pda          (writable)         — Light SPL pool PDA (seeds=[b"pool", mint])
+///   16+ Merkle tree output queue   (writable)         — injected by JS client
+///
+/// Data: company_id (u64) + amount (u64) + company_bump (u8)
+///       + memo (String, `zupy:v1:pool_to_company:{id}`)
+/// Discriminator: `[203, 31, 19, 31, 105, 47, 222, 80]` (SHA256("global:transfer_pool_to_company"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (16 accounts minimum) ─────────────────────────
+    if accounts.len() < 16 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let transfer_authority           = &accounts[0];
+    let token_state_account          = &accounts[1];
+    let mint                         = &accounts[2];
+    let pool_ata                     = &accounts[3];
+    let company_pda                  = &accounts[4];
+    let fee_payer                    = &accounts[5];
+    let token_program                = &accounts[6];
+    let system_program               = &accounts[7];
+    let compressed_token_prog        = &accounts[8];
+    let cpi_authority_pda            = &accounts[9];
+    let light_system_program         = &accounts[10];
+    let registered_program_pda       = &accounts[11];
+    let noop_program                 = &accounts[12];
+    let account_compression_authority = &accounts[13];
+    let account_compression_program  = &accounts[14];
+    let spl_interface_pda            = &accounts[15];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let company_id_u64 = reader.read_u64()?;
+    let amount = reader.read_u64()?;
+    let company_bump = reader.read_u8()?;
+    let memo = reader.read_string()?;
+    reader.finish()?;
+
+    // ── Input validation ────────────────────────────────────────────────
+    if amount == 0 {
+        return Err(zupy_err!(ZeroAmount, "amount"));
+    }
+    validate_memo_op(memo, MemoOp::PoolToCompany)?;
+
+    // ── Common transfer validation (9 checks, Spec §7.1-§7.8) ───────────
+    let validation = validate_transfer_common(
+        program_id,
+        token_state_account,
+        transfer_authority,
+        mint,
+        token_program,
+    )?;
+
+    // ── Additional signer check: fee_payer ──────────────────────────────
+    if !fee_payer.is_signer() {
+        return Err(zupy_err!(FeePayerNotSigner, "fee_payer"));
+    }
+
+    // ── Verify compressed_token_program is the Light cToken program ──────
+    let expected_ctoken: Address = LIGHT_COMPRESSED_TOKEN_PROGRAM_ID.into();
+    if compressed_token_prog.address() != &expected_ctoken {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // ── Verify cpi_authority_pda is the canonical cToken CPI PDA ─────────
+    let expected_ctoken_auth = Address::from(LIGHT_TOKEN_CPI_AUTHORITY);
+    if cpi_authority_pda.address() != &expected_ctoken_auth {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // ── Pool ATA validation ─────────────────────────────────────────────
+    let state = validation.state;
+    if pool_ata.address().as_ref() != state.pool_ata() {
+        return Err(zupy_err!(InvalidPoolAccount, "pool_ata"));
+    }
+    let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
+    if !pool_ata.owned_by(&token_2022_addr) {
+        return Err(zupy_err!(InvalidPoolAccount, "pool_ata_owner"));
+    }
+
+    // ── PDA validation: company_pda (compressed owner) ──────────────────
+    let company_id_bytes = company_id_u64.to_le_bytes();
+    validate_pda_with_seeds(
+        company_pda.address(),
+        &[COMPANY_SEED, &company_id_bytes, &[company_bump]],
+        program_id,
+    )?;
+
+    // ── Balance check ───────────────────────────────────────────────────
+    let pool_balance = read_token_balance(pool_ata);
+    if pool_balance < amount {
+        return Err(zupy_err!(InsufficientPoolBalance, "pool_balance"));
+    }
+
+    // ── Validate spl_interface_pda address ──────────────────────────────
+    let mint_key: [u8; 32] = mint.address().as_ref().try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    let (expected_spl_pda, _) = derive_spl_interface_pda(&mint_key);
+    validate_pda(spl_interface_pda.address(), &expected_spl_pda)?;
+
+    // ── Encode company PDA as compressed owner ───────────────────────────
+    let owner: &[u8; 32] = company_pda.address().as_ref().try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    let remaining_amount = checked_sub_amount(pool_balance, amount)?;
+
+    // ── CPI: Compress from pool_ata → compressed leaf for company ────────
+    // token_state PDA signs with [TOKEN_STATE_SEED, &[bump]]
+    let bump_bytes = [validation.bump];
+    let signer_seeds: [Seed; 2] = [
+        Seed::from(TOKEN_STATE_SEED),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    let signer = Signer::from(&signer_seeds);
+
+    cpi_compress_from_spl(
+        compressed_token_prog,
+        cpi_authority_pda,
+        light_system_program,
+        registered_program_pda,
+        noop_program,
+        account_compression_authority,
+        account_compression_program,
+        fee_payer,
+        token_state_account,        // authority: token_state PDA that owns pool_ata
+        spl_interface_pda,          // token_pool_pda
+        pool_ata,                   // source_ata
+        token_program,
+        system_program,
+        owner,
+        Some(remaining_amount),
+        &accounts[16..],            // remaining: Merkle tree output queue
+        &[signer],
+    )?;
+
+    // ── Heartbeat: last_transfer_ts (v2, writable token_state only) ─────
+    stamp_last_op(token_state_account, LastOp::Transfer)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process_returns_not_enough_account_keys() {
+        let program_id = Address::default();
+        let data = [0u8; 17];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+}
@@ -1,14 +1,22 @@
 use pinocchio::cpi::{Seed, Signer};
 use pinocchio::{AccountView, Address, ProgramResult};
-use pinocchio::error::ProgramError;
 
 use crate::constants::{COMPANY_SEED, LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, USER_SEED};
-use crate::error::ZupyTokenError;
+use crate::error::zupy_err;
+use crate::helpers::account_checks::expect_accounts;
+use crate::helpers::bytes::address_bytes;
+use crate::helpers::company_counters::{company_counters, split_company_state};
 use crate::helpers::compressed_accounts::cpi_compressed_transfer;
-use crate::helpers::instruction_data::{parse_string, parse_u64, parse_u8};
-use crate::helpers::memo::validate_memo_format;
+use crate::helpers::events::{emit_anchor_event, TransferEvent};
+use crate::helpers::heartbeat::stamp_last_op;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::memo::{validate_memo_op, MemoOp};
 use crate::helpers::pda::validate_pda_with_seeds;
-use crate::helpers::transfer_validation::validate_transfer_common_compressed;
+use crate::helpers::stats::{split_stats_state, stats_counters};
+use crate::helpers::transfer_validation::{
+    enforce_per_tx_limit, validate_transfer_common_compressed,
+};
+use crate::state::token_state::LastOp;
 
 /// Process `transfer_user_to_company` instruction.
 ///
@@ -25,6 +33,17 @@ use crate::helpers::transfer_validation::validate_transfer_common_compressed;
 ///   6. system_program (read)
 ///   7. compressed_token_program (read) — cTokenmWW8bLPjZEBAUgYy3zKxQZW6VKi7bqNFEVv3m
 ///
+/// Amounts above `per_tx_auto_limit` (0 = no limit) fail with `ExceedsPerTxLimit`
+/// unless the treasury co-signs as an extra account (conventionally last).
+///
+/// Optional: the company's CompanyState PDA (writable) as the very last
+/// account; the amount is added to its `total_received`. See
+/// `helpers::company_counters`.
+///
+/// Optional: the Stats PDA `[STATS_SEED]` (writable) before any CompanyState,
+/// also stripped; the amount is added to its `total_user_to_company`. See
+/// `helpers::stats`.
+///
 /// Data: user_id_u64 (u64) + company_id_u64 (u64) + amount (u64)
 ///       + user_bump (u8) + company_bump (u8) + memo (String)
 ///
@@ -34,47 +53,55 @@ pub fn process(
     accounts: &[AccountView],
     data: &[u8],
 ) -> ProgramResult {
+    // ── Optional trailing CompanyState (balance counters) ───────────────
+    let (accounts, company_state) = split_company_state(program_id, accounts);
+
+    // ── Optional trailing Stats (global volume counters) ────────────────
+    let (accounts, stats) = split_stats_state(program_id, accounts);
+
     // ── Account extraction (8 accounts minimum) ─────────────────────────
-    if accounts.len() < 8 {
-        return Err(ProgramError::NotEnoughAccountKeys);
-    }
-    let transfer_authority = &accounts[0];
-    let token_state_account = &accounts[1];
-    let mint = &accounts[2];
-    let user_pda = &accounts[3];    // source owner / PDA signer
-    let company_pda = &accounts[4]; // destination owner
-    let fee_payer = &accounts[5];
-    let system_program = &accounts[6];
-    let compressed_token_program = &accounts[7];
+    expect_accounts!(accounts, [
+        transfer_authority,
+        token_state_account,
+        mint,
+        user_pda,    // source owner / PDA signer
+        company_pda, // destination owner
+        fee_payer,
+        system_program,
+        compressed_token_program,
+    ]);
 
     // ── Parse instruction data ──────────────────────────────────────────
-    let user_id_u64 = parse_u64(data, 0)?;
-    let company_id_u64 = parse_u64(data, 8)?;
-    let amount = parse_u64(data, 16)?;
-    let user_bump = parse_u8(data, 24)?;
-    let company_bump = parse_u8(data, 25)?;
-    let (memo, _) = parse_string(data, 26)?;
-    // Remaining bytes (if any): ValidityProof + InputTokenDataWithContext from Photon
-    // — accepted in instruction data per AC3, not consumed by on-chain program.
+    let mut reader = Reader::new(data);
+    let user_id_u64 = reader.read_u64()?;
+    let company_id_u64 = reader.read_u64()?;
+    let amount = reader.read_u64()?;
+    let user_bump = reader.read_u8()?;
+    let company_bump = reader.read_u8()?;
+    let memo = reader.read_string()?;
+    reader.finish()?;
 
     // ── Input validation ────────────────────────────────────────────────
     if amount == 0 {
-        return Err(ZupyTokenError::ZeroAmount.into());
+        return Err(zupy_err!(ZeroAmount, "amount"));
     }
-    validate_memo_format(memo)?;
+    validate_memo_op(memo, MemoOp::UserToCompany)?;
 
     // ── Common transfer validation (checks 1–8) ─────────────────────────
-    validate_transfer_common_compressed(
+    let validation = validate_transfer_common_compressed(
         program_id,
         token_state_account,
         transfer_authority,
         mint,
     )?;
 
+    // ── Per-transaction limit (treasury override above it) ──────────────
+    enforce_per_tx_limit(&validation.state, amount, accounts)?;
+
     // ── Check 9: compressed_token_program is Light cToken program ───────
     let light_ctoken_addr = Address::from(LIGHT_COMPRESSED_TOKEN_PROGRAM_ID);
     if compressed_token_program.address() != &light_ctoken_addr {
-        return Err(ZupyTokenError::InvalidTokenProgram.into());
+        return Err(zupy_err!(InvalidTokenProgram, "ctoken_program"));
     }
 
     // ── PDA validation: user_pda (source) ───────────────────────────────
@@ -93,6 +120,13 @@ pub fn process(
         program_id,
     )?;
 
+    // ── Event log (all checks passed) ───────────────────────────────────
+    emit_anchor_event(&TransferEvent {
+        from: *address_bytes(user_pda.address()),
+        to: *address_bytes(company_pda.address()),
+        amount,
+    });
+
     // ── CPI: compressed transfer (user_pda signs) ───────────────────────
     let user_bump_bytes = [user_bump];
     let signer_seeds: [Seed; 3] = [
@@ -111,12 +145,27 @@ pub fn process(
         system_program,
         amount,
         &[signer],
-    )
+    )?;
+
+    // ── Company counters (optional, saturating) ─────────────────────────
+    if let Some(mut counters) = company_counters(company_state, company_id_u64) {
+        counters.record_received(amount);
+    }
+    // ── Global stats (optional, saturating) ─────────────────────────────
+    if let Some(mut counters) = stats_counters(stats) {
+        counters.record_user_to_company(amount);
+    }
+
+    // ── Heartbeat: last_transfer_ts (v2, writable token_state only) ─────
+    stamp_last_op(token_state_account, LastOp::Transfer)?;
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use pinocchio::error::ProgramError;
 
     #[test]
     fn test_transfer_user_to_company_not_enough_account_keys() {
@@ -132,7 +181,7 @@ mod tests {
         let program_id = Address::from([1u8; 32]);
         let data = [0u8; 26];
         fn make_buf(addr: [u8; 32]) -> Vec<u64> {
-            let words = (core::mem::size_of::<RuntimeAccount>() + 7) / 8 + 1;
+            let words = core::mem::size_of::<RuntimeAccount>().div_ceil(8) + 1;
             let mut buf = vec![0u64; words];
             let raw = buf.as_mut_ptr() as *mut RuntimeAccount;
             unsafe {
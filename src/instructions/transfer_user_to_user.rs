@@ -0,0 +1,143 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::{AccountView, Address, ProgramResult};
+use pinocchio::error::ProgramError;
+
+use crate::constants::{LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, USER_SEED};
+use crate::error::zupy_err;
+use crate::helpers::compressed_accounts::cpi_compressed_transfer;
+use crate::helpers::heartbeat::stamp_last_op;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::memo::{validate_memo_op, MemoOp};
+use crate::helpers::pda::validate_pda_or_find;
+use crate::helpers::transfer_validation::validate_transfer_common_compressed;
+use crate::state::token_state::LastOp;
+
+/// Process `transfer_user_to_user` instruction.
+///
+/// Peer-to-peer gift: transfers ZUPY from one user's compressed balance to
+/// another user's compressed balance via Light cToken `Transfer` CPI
+/// (Path B: compressed → compressed). Same layout as `transfer_user_to_company`
+/// with a user PDA as destination. Self-transfers are rejected. Under
+/// `pda-bump-fallback` a wrong bump is tolerated for the canonical user PDAs.
+///
+/// Accounts (8):
+///   0. transfer_authority (signer)
+///   1. token_state (read)
+///   2. mint (read)
+///   3. from_user_pda (read)            — source owner / PDA signer
+///   4. to_user_pda (read)              — destination owner
+///   5. fee_payer (writable, signer)    — pays Light state tree fees
+///   6. system_program (read)
+///   7. compressed_token_program (read) — cTokenmWW8bLPjZEBAUgYy3zKxQZW6VKi7bqNFEVv3m
+///
+/// Data: from_user_id_u64 (u64) + to_user_id_u64 (u64) + amount (u64)
+///       + from_bump (u8) + to_bump (u8) + memo (String, e.g. `zupy:v1:u2u:<from>:<to>`)
+///
+/// Discriminator: [180, 29, 108, 140, 251, 43, 31, 123] (SHA256("global:transfer_user_to_user"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (8 accounts minimum) ─────────────────────────
+    if accounts.len() < 8 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let transfer_authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let mint = &accounts[2];
+    let from_user_pda = &accounts[3]; // source owner / PDA signer
+    let to_user_pda = &accounts[4];   // destination owner
+    let fee_payer = &accounts[5];
+    let system_program = &accounts[6];
+    let compressed_token_program = &accounts[7];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let from_user_id_u64 = reader.read_u64()?;
+    let to_user_id_u64 = reader.read_u64()?;
+    let amount = reader.read_u64()?;
+    let from_bump = reader.read_u8()?;
+    let to_bump = reader.read_u8()?;
+    let memo = reader.read_string()?;
+    reader.finish()?;
+
+    // ── Input validation ────────────────────────────────────────────────
+    if amount == 0 {
+        return Err(zupy_err!(ZeroAmount, "amount"));
+    }
+    if from_user_id_u64 == to_user_id_u64 || from_user_pda.address() == to_user_pda.address() {
+        return Err(zupy_err!(SelfTransfer, "same_user"));
+    }
+    validate_memo_op(memo, MemoOp::UserToUser)?;
+
+    // ── Common transfer validation (checks 1–8) ─────────────────────────
+    validate_transfer_common_compressed(
+        program_id,
+        token_state_account,
+        transfer_authority,
+        mint,
+    )?;
+
+    // ── Check 9: compressed_token_program is Light cToken program ───────
+    let light_ctoken_addr = Address::from(LIGHT_COMPRESSED_TOKEN_PROGRAM_ID);
+    if compressed_token_program.address() != &light_ctoken_addr {
+        return Err(zupy_err!(InvalidTokenProgram, "ctoken_program"));
+    }
+
+    // ── PDA validation: from_user_pda (source) ──────────────────────────
+    let from_id_bytes = from_user_id_u64.to_le_bytes();
+    let from_bump = validate_pda_or_find(
+        from_user_pda.address(),
+        &[USER_SEED, &from_id_bytes],
+        from_bump,
+        program_id,
+    )?;
+
+    // ── PDA validation: to_user_pda (destination) ───────────────────────
+    let to_id_bytes = to_user_id_u64.to_le_bytes();
+    validate_pda_or_find(
+        to_user_pda.address(),
+        &[USER_SEED, &to_id_bytes],
+        to_bump,
+        program_id,
+    )?;
+
+    // ── CPI: compressed transfer (from_user_pda signs) ──────────────────
+    let from_bump_bytes = [from_bump];
+    let signer_seeds: [Seed; 3] = [
+        Seed::from(USER_SEED),
+        Seed::from(from_id_bytes.as_ref()),
+        Seed::from(from_bump_bytes.as_ref()),
+    ];
+    let signer = Signer::from(&signer_seeds);
+
+    cpi_compressed_transfer(
+        compressed_token_program,
+        fee_payer,
+        from_user_pda, // source
+        to_user_pda,   // destination
+        from_user_pda, // authority (source PDA signs)
+        system_program,
+        amount,
+        &[signer],
+    )?;
+
+    // ── Heartbeat: last_transfer_ts (v2, writable token_state only) ─────
+    stamp_last_op(token_state_account, LastOp::Transfer)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_user_to_user_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 26];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
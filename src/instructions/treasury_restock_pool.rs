@@ -3,18 +3,21 @@ use pinocchio::{AccountView, Address, ProgramResult};
 
 use crate::constants::{TOKEN_2022_PROGRAM_ID, TREASURY_WALLET_PUBKEY};
 use crate::error::ZupyTokenError;
-use crate::helpers::cpi::cpi_transfer;
-use crate::helpers::instruction_data::{parse_string, parse_u64};
-use crate::helpers::memo::validate_memo_format;
+use crate::helpers::cpi::{cpi_transfer, cpi_transfer_checked_with_fee};
+use crate::helpers::events::{emit_supply_event, SupplyEvent};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::memo::{validate_memo_op, MemoOp};
+use crate::helpers::transfer_fee::read_transfer_fee_config;
 use crate::helpers::transfer_validation::{
     read_token_balance, validate_source_ata, validate_token_state_base,
 };
-use crate::state::token_state::TokenState;
 
 /// Process `treasury_restock_pool` instruction.
 ///
 /// Transfers tokens from treasury_ata to pool_ata.
 /// treasury_wallet signs directly (regular invoke, no invoke_signed).
+/// On a mint with the TransferFeeConfig extension the plain Transfer is
+/// replaced by TransferCheckedWithFee; the pool then receives `amount - fee`.
 /// No rate limits — manual Trezor signing IS the security control.
 ///
 /// Accounts (6):
@@ -43,20 +46,19 @@ pub fn process(
     let token_program = &accounts[5];
 
     // ── Parse instruction data ──────────────────────────────────────────
-    let amount = parse_u64(data, 0)?;
-    let (memo, _) = parse_string(data, 8)?;
+    let mut reader = Reader::new(data);
+    let amount = reader.read_u64()?;
+    let memo = reader.read_string()?;
+    reader.finish()?;
 
     // ── Input validation ────────────────────────────────────────────────
     if amount == 0 {
         return Err(ZupyTokenError::ZeroAmount.into());
     }
-    validate_memo_format(memo)?;
+    validate_memo_op(memo, MemoOp::Restock)?;
 
     // ── Base token_state validation (§7.1, §7.7, §7.2, §7.4) ──────────
-    validate_token_state_base(program_id, token_state_account)?;
-
-    // Zero-copy read for remaining checks
-    let state = TokenState::from_slice(unsafe { token_state_account.borrow_unchecked() });
+    let state = validate_token_state_base(program_id, token_state_account)?;
 
     // ── Mint validation ─────────────────────────────────────────────────
     let token_2022_addr = Address::from(TOKEN_2022_PROGRAM_ID);
@@ -99,15 +101,36 @@ pub fn process(
         return Err(ZupyTokenError::InsufficientBalance.into());
     }
 
+    // ── Event log (all checks passed) ───────────────────────────────────
+    emit_supply_event(SupplyEvent::Restock, amount, memo);
+
     // ── CPI: Transfer (regular invoke — treasury_wallet is signer) ──────
-    cpi_transfer(
-        treasury_ata,
-        pool_ata,
-        treasury_wallet,
-        amount,
-        token_program.address(),
-        &[], // empty signers = regular invoke
-    )?;
+    // Fee-bearing mints reject plain Transfer: TransferCheckedWithFee instead
+    match read_transfer_fee_config(unsafe { mint.borrow_unchecked() }) {
+        None => cpi_transfer(
+            treasury_ata,
+            pool_ata,
+            treasury_wallet,
+            amount,
+            token_program.address(),
+            &[], // empty signers = regular invoke
+        )?,
+        Some(config) => {
+            use pinocchio::sysvars::Sysvar as _;
+            let clock = pinocchio::sysvars::clock::Clock::get()?;
+            cpi_transfer_checked_with_fee(
+                treasury_ata,
+                pool_ata,
+                treasury_wallet,
+                mint,
+                amount,
+                state.decimals(),
+                config.fee(clock.epoch, amount),
+                token_program.address(),
+                &[],
+            )?
+        }
+    }
 
     Ok(())
 }
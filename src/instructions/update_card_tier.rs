@@ -0,0 +1,103 @@
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::MAX_CARD_TIER;
+use crate::error::ZupyTokenError;
+use crate::helpers::account_checks::assert_discriminator;
+use crate::helpers::cpi::cpi_grow_account;
+use crate::helpers::events::emit_card_tier_event;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::pda::{derive_zupy_card_pda, validate_pda};
+use crate::helpers::transfer_validation::validate_nft_payer;
+use crate::state::zupy_card::{
+    ZupyCard, ZupyCardMut, ZUPY_CARD_DISCRIMINATOR, ZUPY_CARD_SIZE, ZUPY_CARD_SIZE_WITH_TIER,
+};
+
+/// Process `update_card_tier` instruction.
+///
+/// Sets a Zupy Card's tier (0 = none, 1 = bronze, 2 = silver, 3 = gold) and
+/// `updated_at`, then logs `zupy_evt:card_tier:{tier}:{user_ksuid}`. Tiers
+/// above `MAX_CARD_TIER` fail with `InvalidCardTier`; frozen cards fail with
+/// `CardFrozen` and revoked cards with `CardRevoked`.
+///
+/// Legacy 108-byte cards are grown to `ZUPY_CARD_SIZE_WITH_TIER` first; the
+/// mint_authority pays the extra rent.
+///
+/// Accounts (4):
+///   0. mint_authority (writable, signer) — must match token_state.mint_authority()
+///   1. token_state (read) — PDA [TOKEN_STATE_SEED]
+///   2. zupy_card (writable) — PDA [b"zupy_card", &user_ksuid]
+///   3. system_program (read) — rent top-up for legacy cards
+///
+/// Data: user_ksuid ([u8; 27]) + tier (u8)
+/// Discriminator: [179, 143, 196, 15, 127, 132, 0, 73] (SHA256("global:update_card_tier"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (4 accounts) ─────────────────────────────────
+    if accounts.len() < 4 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let mint_authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let zupy_card = &accounts[2];
+    let _system_program = &accounts[3];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let user_ksuid = reader.read_bytes::<27>()?;
+    let tier = reader.read_u8()?;
+    reader.finish()?;
+    if tier > MAX_CARD_TIER {
+        return Err(ZupyTokenError::InvalidCardTier.into());
+    }
+
+    // ── Authority (signer + token_state + mint_authority) ───────────────
+    validate_nft_payer(program_id, mint_authority, token_state_account)?;
+
+    // ── ZupyCard validation: PDA, owner, size, discriminator, status ────
+    let (expected_card_pda, _) = derive_zupy_card_pda(program_id, user_ksuid);
+    validate_pda(zupy_card.address(), &expected_card_pda)?;
+    if !zupy_card.owned_by(program_id) || zupy_card.data_len() < ZUPY_CARD_SIZE {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let card = ZupyCard::from_slice(unsafe { zupy_card.borrow_unchecked() });
+    assert_discriminator(card.discriminator(), &ZUPY_CARD_DISCRIMINATOR)?;
+    if card.is_frozen() {
+        return Err(ZupyTokenError::CardFrozen.into());
+    }
+    if card.is_revoked() {
+        return Err(ZupyTokenError::CardRevoked.into());
+    }
+
+    // ── Grow legacy cards to carry tier + updated_at ────────────────────
+    if zupy_card.data_len() < ZUPY_CARD_SIZE_WITH_TIER {
+        cpi_grow_account(mint_authority, zupy_card, ZUPY_CARD_SIZE_WITH_TIER)?;
+    }
+
+    // ── Write tier ──────────────────────────────────────────────────────
+    use pinocchio::sysvars::Sysvar as _;
+    let clock = pinocchio::sysvars::clock::Clock::get()?;
+
+    let mut card_state = ZupyCardMut::from_slice(unsafe { zupy_card.borrow_unchecked_mut() });
+    card_state.set_tier(tier);
+    card_state.set_updated_at(clock.unix_timestamp);
+
+    emit_card_tier_event(tier, user_ksuid);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_card_tier_not_enough_account_keys() {
+        let program_id = Address::default();
+        let result = process(&program_id, &[], &[0u8; 28]);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+}
@@ -3,27 +3,22 @@ use pinocchio::error::ProgramError;
 use pinocchio::{AccountView, Address, ProgramResult};
 
 use crate::constants::TOKEN_STATE_SEED;
-use crate::error::ZupyTokenError;
-use crate::helpers::cpi::cpi_update_metadata_field;
-use crate::helpers::instruction_data::{parse_string, parse_u8};
+use crate::helpers::cpi::{cpi_update_metadata_field, cpi_update_metadata_key, METADATA_FIELD_KEY};
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::metadata::{
+    require_metadata_updatable, validate_additional_metadata, validate_metadata_field,
+};
 use crate::helpers::transfer_validation::validate_metadata_accounts;
 
-/// Validate metadata field value by type (0=Name, 1=Symbol, 2=Uri).
-#[inline(always)]
-fn validate_metadata_field(field: u8, value: &str) -> Result<(), pinocchio::error::ProgramError> {
-    match field {
-        0 if value.is_empty() || value.len() > 32 => Err(ZupyTokenError::InvalidMetadataName.into()),
-        1 if value.is_empty() || value.len() > 10 => Err(ZupyTokenError::InvalidMetadataSymbol.into()),
-        2 if value.is_empty() || value.len() > 200 => Err(ZupyTokenError::InvalidMetadataUri.into()),
-        0..=2 => Ok(()),
-        _ => Err(ProgramError::InvalidInstructionData),
-    }
-}
-
 /// Process `update_metadata_field` instruction.
 ///
 /// Updates a metadata field via spl-token-metadata-interface UpdateField CPI.
 /// token_state PDA signs via invoke_signed.
+/// Fails with `MetadataNotInitialized` before `initialize_metadata` has run and
+/// with `MetadataFrozen` after `freeze_metadata`.
+///
+/// Field 3 sets a custom `additional_metadata` entry (`Field::Key(key)`),
+/// inserting or overwriting it; see `validate_additional_metadata` for limits.
 ///
 /// Accounts (4):
 ///   0. authority (writable, signer) — must be token_state.treasury()
@@ -32,6 +27,7 @@ fn validate_metadata_field(field: u8, value: &str) -> Result<(), pinocchio::erro
 ///   3. token_program (read) — Token-2022
 ///
 /// Data: field (u8: 0=Name, 1=Symbol, 2=Uri) + value (String)
+///       or field 3 + key (String) + value (String)
 pub fn process(
     program_id: &Address,
     accounts: &[AccountView],
@@ -47,16 +43,28 @@ pub fn process(
     let token_program = &accounts[3];
 
     // ── Parse instruction data ──────────────────────────────────────────
-    let field = parse_u8(data, 0)?;
-    let (value, _) = parse_string(data, 1)?;
+    let mut reader = Reader::new(data);
+    let field = reader.read_u8()?;
+    let value = reader.read_string()?;
 
-    // ── Field-specific validation ───────────────────────────────────────
-    validate_metadata_field(field, value)?;
+    // ── Field-specific validation (custom key: `value` is the key) ──────
+    let custom_value = if field == METADATA_FIELD_KEY {
+        let custom_value = reader.read_string()?;
+        reader.finish()?;
+        validate_additional_metadata(value, custom_value)?;
+        Some(custom_value)
+    } else {
+        reader.finish()?;
+        validate_metadata_field(field, value)?;
+        None
+    };
 
     // ── Metadata account validation (treasury + mint + token_program) ────
-    let bump = validate_metadata_accounts(
+    let state = validate_metadata_accounts(
         program_id, authority, token_state_account, mint, token_program,
     )?;
+    let bump = state.bump();
+    require_metadata_updatable(&state)?;
 
     // ── CPI: Update metadata field (token_state PDA signs) ──────────────
     let bump_bytes = [bump];
@@ -66,14 +74,25 @@ pub fn process(
     ];
     let signer = Signer::from(&signer_seeds);
 
-    cpi_update_metadata_field(
-        mint,
-        token_state_account,
-        token_program,
-        field,
-        value,
-        &[signer],
-    )?;
+    match custom_value {
+        Some(custom_value) => cpi_update_metadata_key(
+            mint,
+            token_state_account,
+            token_program,
+            value,
+            custom_value,
+            &[signer],
+        )?,
+        None => cpi_update_metadata_field(
+            mint,
+            token_state_account,
+            token_program,
+            field,
+            value,
+            &[signer],
+        )?,
+    }
 
     Ok(())
 }
+
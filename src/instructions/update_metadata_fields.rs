@@ -0,0 +1,174 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::TOKEN_STATE_SEED;
+use crate::helpers::cpi::cpi_update_metadata_field;
+use crate::helpers::instruction_data::Reader;
+use crate::helpers::metadata::{require_metadata_updatable, validate_metadata_field};
+use crate::helpers::transfer_validation::validate_metadata_accounts;
+
+/// Number of updatable metadata fields (0=Name, 1=Symbol, 2=Uri).
+const FIELD_COUNT: u8 = 3;
+
+/// Mask with every known field bit set (bit N = field N).
+const ALL_FIELDS_MASK: u8 = (1 << FIELD_COUNT) - 1;
+
+/// Process `update_metadata_fields` instruction.
+///
+/// Batched `update_metadata_field`: one transaction for any subset of
+/// name/symbol/uri. Every value is parsed and validated before the first
+/// UpdateField CPI, so a bad field leaves the metadata untouched.
+/// token_state PDA signs via invoke_signed.
+/// Fails with `MetadataNotInitialized` before `initialize_metadata` has run and
+/// with `MetadataFrozen` after `freeze_metadata`.
+///
+/// Accounts (4):
+///   0. authority (writable, signer) — must be token_state.treasury()
+///   1. token_state (read) — PDA [TOKEN_STATE_SEED]
+///   2. mint (writable) — Token-2022 mint with MetadataPointer
+///   3. token_program (read) — Token-2022
+///
+/// Data: mask (u8: bit 0=Name, bit 1=Symbol, bit 2=Uri) + one value (String)
+///       per set bit, in field order. Empty mask or unknown bits → `InvalidInstructionData`.
+/// Discriminator: [61, 69, 228, 80, 185, 192, 143, 124] (SHA256("global:update_metadata_fields"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (4 accounts) ─────────────────────────────────
+    if accounts.len() < 4 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let authority = &accounts[0];
+    let token_state_account = &accounts[1];
+    let mint = &accounts[2];
+    let token_program = &accounts[3];
+
+    // ── Parse + validate every field before any CPI ─────────────────────
+    let values = parse_metadata_fields(data)?;
+
+    // ── Metadata account validation (treasury + mint + token_program) ────
+    let state = validate_metadata_accounts(
+        program_id, authority, token_state_account, mint, token_program,
+    )?;
+    let bump = state.bump();
+    require_metadata_updatable(&state)?;
+
+    // ── CPIs: Update each present field (token_state PDA signs) ─────────
+    let bump_bytes = [bump];
+    let signer_seeds: [Seed; 2] = [
+        Seed::from(TOKEN_STATE_SEED),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+
+    for (field, value) in values.iter().enumerate() {
+        if let Some(value) = value {
+            cpi_update_metadata_field(
+                mint,
+                token_state_account,
+                token_program,
+                field as u8,
+                value,
+                &[Signer::from(&signer_seeds)],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses `mask + values`, validating each value with `validate_metadata_field`.
+/// Returns the value for each field index, `None` where the mask bit is clear.
+pub fn parse_metadata_fields(data: &[u8]) -> Result<[Option<&str>; 3], ProgramError> {
+    let mut reader = Reader::new(data);
+    let mask = reader.read_u8()?;
+    if mask == 0 || mask & !ALL_FIELDS_MASK != 0 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let mut values = [None; FIELD_COUNT as usize];
+    for field in 0..FIELD_COUNT {
+        if mask & (1 << field) == 0 {
+            continue;
+        }
+        let value = reader.read_string()?;
+        validate_metadata_field(field, value)?;
+        values[field as usize] = Some(value);
+    }
+    reader.finish()?;
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ZupyTokenError;
+
+    fn build(mask: u8, values: &[&str]) -> Vec<u8> {
+        let mut data = vec![mask];
+        for v in values {
+            data.extend_from_slice(&(v.len() as u32).to_le_bytes());
+            data.extend_from_slice(v.as_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_update_metadata_fields_not_enough_account_keys() {
+        let program_id = Address::default();
+        let result = process(&program_id, &[], &build(0b001, &["Zupy"]));
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+
+    #[test]
+    fn test_parse_all_three() {
+        let data = build(0b111, &["Zupy 2", "ZUPY2", "ipfs://new"]);
+        assert_eq!(
+            parse_metadata_fields(&data),
+            Ok([Some("Zupy 2"), Some("ZUPY2"), Some("ipfs://new")])
+        );
+    }
+
+    #[cfg(not(feature = "lenient-ix-data"))]
+    #[test]
+    fn test_parse_trailing_byte_rejected() {
+        let mut data = build(0b010, &["ZUPY2"]);
+        data.push(0);
+        assert_eq!(parse_metadata_fields(&data), Err(ProgramError::InvalidInstructionData));
+    }
+
+    #[test]
+    fn test_parse_single_field() {
+        assert_eq!(parse_metadata_fields(&build(0b010, &["ZUPY2"])), Ok([None, Some("ZUPY2"), None]));
+        // Values follow field order, skipping clear bits
+        assert_eq!(
+            parse_metadata_fields(&build(0b101, &["Zupy 2", "ipfs://new"])),
+            Ok([Some("Zupy 2"), None, Some("ipfs://new")])
+        );
+    }
+
+    #[test]
+    fn test_empty_mask_rejected() {
+        assert_eq!(parse_metadata_fields(&[0]), Err(ProgramError::InvalidInstructionData));
+        assert_eq!(parse_metadata_fields(&[]), Err(ProgramError::InvalidInstructionData));
+    }
+
+    #[test]
+    fn test_invalid_field_index_rejected() {
+        let data = build(0b1001, &["Zupy 2", "extra"]);
+        assert_eq!(parse_metadata_fields(&data), Err(ProgramError::InvalidInstructionData));
+    }
+
+    #[test]
+    fn test_any_invalid_value_fails_whole_batch() {
+        let long_uri = "u".repeat(201);
+        let data = build(0b111, &["Zupy 2", "ZUPY2", &long_uri]);
+        assert_eq!(parse_metadata_fields(&data), Err(ZupyTokenError::InvalidMetadataUri.into()));
+
+        // Missing value for a set bit
+        let data = build(0b011, &["Zupy 2"]);
+        assert_eq!(parse_metadata_fields(&data), Err(ProgramError::InvalidInstructionData));
+    }
+}
@@ -0,0 +1,39 @@
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::COMPANY_SEED;
+use crate::helpers::withdraw_common::decompress_to_external;
+
+/// Process `withdraw_company_to_external` instruction.
+///
+/// Decompresses a company's compressed ZUPY balance into an external wallet's ATA,
+/// so companies can cash out without routing through a user PDA.
+///
+/// Delegates to [`decompress_to_external`] with `COMPANY_SEED`; the memo must be a
+/// `withdraw_company` memo. Same 13-account layout as `withdraw_to_external`,
+/// with the company PDA at 3 (signs the decompress CPI).
+///
+/// Data: amount (u64) + company_id (u64) + company_bump (u8)
+///       + memo (String, `zupy:v1:withdraw_company:{id}`) + spl_interface_bump (u8, 0xFF = derive)
+///       + optional op_id ([u8; 16]) + optional dest_ata_bump trailer (flag u8 = 1 + bump u8)
+/// Discriminator: `[221, 137, 39, 239, 134, 153, 158, 5]` (SHA256("global:withdraw_company_to_external"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    decompress_to_external(program_id, accounts, data, COMPANY_SEED, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pinocchio::error::ProgramError;
+
+    #[test]
+    fn test_withdraw_company_to_external_not_enough_account_keys() {
+        let program_id = Address::from([1u8; 32]);
+        let data = [0u8; 17];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+    }
+}
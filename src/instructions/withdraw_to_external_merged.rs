@@ -0,0 +1,43 @@
+use pinocchio::{AccountView, Address, ProgramResult};
+
+use crate::constants::USER_SEED;
+use crate::helpers::withdraw_common::decompress_to_external;
+
+/// Process `withdraw_to_external_merged` instruction.
+///
+/// `withdraw_to_external` for a user whose balance is fragmented across several
+/// compressed accounts: the memo is followed by a `MergeInputs` section (count,
+/// output queue index, per-input amount + Merkle context, optional proof), and the
+/// payout is decompressed from all listed leaves in one CPI with the change
+/// re-compressed to the user PDA.
+///
+/// Same accounts, fee, limits and optional trailing fields (op_id, dest_ata_bump)
+/// as `withdraw_to_external`. Withdrawals at or above the approval threshold still
+/// fail with `RequiresApproval`; `approve_withdrawal` replays the single-input path.
+///
+/// Delegates to [`decompress_to_external`] with `USER_SEED` and `merged = true`.
+///
+/// Data: amount (u64) + user_id (u64) + user_bump (u8) + memo (String)
+///       + spl_interface_bump (u8, 0xFF = derive) + MergeInputs + optional op_id ([u8; 16]) + optional dest_ata_bump trailer (flag u8 = 1 + bump u8, last)
+///
+/// Discriminator: [156, 165, 248, 89, 75, 159, 87, 181] (SHA256("global:withdraw_to_external_merged"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    decompress_to_external(program_id, accounts, data, USER_SEED, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pinocchio::error::ProgramError;
+
+    #[test]
+    fn test_process_returns_not_enough_account_keys() {
+        let program_id = Address::default();
+        let result = process(&program_id, &[], &[0u8; 17]);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+}
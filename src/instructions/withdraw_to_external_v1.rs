@@ -0,0 +1,190 @@
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::{AccountView, Address, ProgramResult};
+use pinocchio::error::ProgramError;
+use pinocchio::instruction::{InstructionAccount, InstructionView};
+
+use crate::constants::{LIGHT_COMPRESSED_TOKEN_PROGRAM_ID, USER_SEED};
+use crate::error::zupy_err;
+use crate::helpers::account_checks::assert_associated_token_address;
+use crate::helpers::compressed_accounts::{
+    locate_v1_decompress_amount, parse_v1_proof_layout, validate_v1_transfer_disc,
+    SPL_INTERFACE_BUMP_DERIVE,
+};
+use crate::helpers::instruction_data::{parse_u64, parse_u8, Reader};
+use crate::helpers::transfer_validation::{
+    enforce_v1_sunset, read_token_owner, validate_destination_ata_if_exists,
+    validate_withdraw_request,
+};
+use crate::helpers::withdraw_common::{consume_withdraw_limit, WithdrawParams};
+
+/// Index of `compress_or_decompress_token_account` in the V1 TRANSFER account list.
+const V1_DECOMPRESS_ACCOUNT_INDEX: usize = 10;
+
+/// Process `withdraw_to_external_v1` instruction (V1 CPI passthrough, mainnet).
+///
+/// Withdraws a user's balance still held on the V1 mainnet cToken program to an
+/// external wallet's ATA without migrating it first. Runs the same request
+/// validation as `withdraw_to_external` (`validate_withdraw_request`), then
+/// forwards the pre-built V1 TRANSFER (decompress) to cToken like
+/// `return_user_to_pool_v1`, signing with the user PDA.
+///
+/// The dest_ata is not created here: the backend prepends an idempotent ATA
+/// creation. It must be dest_wallet's associated token account for the mint; if
+/// it exists it must be a Token-2022 account for our mint owned by dest_wallet,
+/// and it must be the V1 payload's decompress target. The withdrawal
+/// fee is not charged on this legacy path.
+///
+/// Accounts (8 minimum + CPI accounts):
+///   0. transfer_authority       (signer)           — Backend authority (Vault Transit)
+///   1. token_state              (read)             — Program state PDA
+///   2. mint                     (read)             — ZUPY mint (Token-2022)
+///   3. user_pda                 (read)             — Source user PDA (signs the V1 CPI)
+///   4. dest_wallet              (read)             — External wallet address (NOT a PDA)
+///   5. dest_ata                 (writable)         — Destination ATA (must equal V1 account 10)
+///   6. token_program            (read)             — Token-2022 Program
+///   7. v1_program               (read)             — LIGHT_COMPRESSED_TOKEN_PROGRAM_ID
+///   8+ V1 CPI accounts          (client-assembled) — forwarded to cToken in V1 order
+///
+/// Optional: the user's withdraw limit PDA `[WITHDRAW_LIMIT_SEED, user_id]` (writable)
+/// at 8, with the V1 CPI accounts moving to 9+; same rolling 24h cap as the native path.
+///
+/// Amounts above `per_tx_auto_limit` need a treasury co-signer (`ExceedsPerTxLimit`)
+/// and amounts of `withdraw_approval_threshold` or more fail with `RequiresApproval`.
+/// Once `token_state.v1_sunset_ts` has passed it fails with `V1Sunset`.
+///
+/// Data: amount (u64, bytes 0–7) + user_id (u64, bytes 8–15) + user_bump (u8, byte 16)
+///       + memo (String, bytes 17+) + raw V1 CPI data (starts with V1 TRANSFER disc);
+///       the V1 `compress_or_decompress_amount` must be `Some(amount)`
+/// Discriminator: [26, 223, 149, 246, 144, 169, 233, 46] (SHA256("global:withdraw_to_external_v1"))
+pub fn process(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    // ── Account extraction (minimum 8 validation accounts) ──────────────
+    if accounts.len() < 8 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    // accounts[0..2] = transfer_authority, token_state — checked by validate_withdraw_request
+    let mint          = &accounts[2];
+    let user_pda      = &accounts[3];
+    let dest_wallet   = &accounts[4];
+    let dest_ata      = &accounts[5];
+    let token_program = &accounts[6];
+    let v1_program    = &accounts[7];
+
+    // ── Parse instruction data ──────────────────────────────────────────
+    let mut reader = Reader::new(data);
+    let params = WithdrawParams {
+        amount: reader.read_u64()?,
+        entity_id: reader.read_u64()?,
+        entity_bump: reader.read_u8()?,
+        memo: reader.read_string()?,
+        spl_interface_bump: SPL_INTERFACE_BUMP_DERIVE,
+        inputs: None,
+        op_id: None,
+        dest_ata_bump: None,
+    };
+    let v1_cpi_data = reader.rest();
+
+    // ── V1 payload: TRANSFER disc, decompress of exactly `amount` ───────
+    validate_v1_transfer_disc(v1_cpi_data)?;
+    let (amount_offset, _) = locate_v1_decompress_amount(v1_cpi_data)?;
+    if parse_u8(v1_cpi_data, amount_offset)? != 1
+        || parse_u64(v1_cpi_data, amount_offset + 1)? != params.amount
+    {
+        return Err(zupy_err!(InvalidAmount, "v1_amount"));
+    }
+
+    // ── Shared withdraw validation (same checks as withdraw_to_external) ─
+    let state =
+        validate_withdraw_request(program_id, accounts, token_program, &params, USER_SEED, false)?;
+
+    // ── V1 sunset (0 = no cutoff) ───────────────────────────────────────
+    enforce_v1_sunset(&state)?;
+
+    // ── Verify v1_program is the mainnet cToken program ─────────────────
+    let prog_id: Address = LIGHT_COMPRESSED_TOKEN_PROGRAM_ID.into();
+    if v1_program.address() != &prog_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // ── Destination ATA (mint + owner when it already exists) ───────────
+    validate_destination_ata_if_exists(dest_ata, mint.address())?;
+    if dest_ata.data_len() > 0 && read_token_owner(dest_ata) != dest_wallet.address().as_ref() {
+        return Err(zupy_err!(TokenOwnerMismatch, "dest_ata_owner"));
+    }
+    assert_associated_token_address(
+        dest_ata,
+        dest_wallet.address(),
+        mint.address(),
+        token_program.address(),
+        None,
+    )?;
+
+    // ── Per-user daily limit (optional PDA at 8) ────────────────────────
+    let cpi_start = if consume_withdraw_limit(program_id, accounts.get(8), params.entity_id, params.amount)? {
+        9
+    } else {
+        8
+    };
+    let cpi_accounts = &accounts[cpi_start..];
+
+    // ── The V1 decompress must pay out to dest_ata ──────────────────────
+    match cpi_accounts.get(V1_DECOMPRESS_ACCOUNT_INDEX) {
+        Some(target) if target.address() == dest_ata.address() => {}
+        _ => return Err(ProgramError::InvalidAccountData),
+    }
+
+    // ── V1 proof structure (before spending a CPI on it) ────────────────
+    parse_v1_proof_layout(v1_cpi_data, cpi_accounts.len())?;
+
+    // ── Build CPI instruction for cToken V1 ─────────────────────────────
+    // Forward the V1 accounts as given, forcing user_pda to be signer.
+    let mut account_metas = Vec::with_capacity(cpi_accounts.len());
+    for acct in cpi_accounts {
+        let is_user_pda = acct.address() == user_pda.address();
+        let meta = match (acct.is_writable(), acct.is_signer() || is_user_pda) {
+            (true, true)  => InstructionAccount::writable_signer(acct.address()),
+            (true, false) => InstructionAccount::writable(acct.address()),
+            (false, true) => InstructionAccount::readonly_signer(acct.address()),
+            _             => InstructionAccount::readonly(acct.address()),
+        };
+        account_metas.push(meta);
+    }
+
+    let instruction = InstructionView {
+        program_id: &prog_id,
+        accounts: &account_metas,
+        data: v1_cpi_data,
+    };
+
+    let account_views: Vec<&AccountView> = cpi_accounts.iter().collect();
+
+    // ── CPI: Forward V1 TRANSFER to cToken, signing with user PDA ───────
+    let user_id_bytes = params.entity_id.to_le_bytes();
+    let bump_bytes = [params.entity_bump];
+    let signer_seeds: [Seed; 3] = [
+        Seed::from(USER_SEED),
+        Seed::from(user_id_bytes.as_ref()),
+        Seed::from(bump_bytes.as_ref()),
+    ];
+    let signer = Signer::from(&signer_seeds);
+
+    pinocchio::cpi::invoke_signed_with_slice(&instruction, &account_views, &[signer])?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_withdraw_to_external_v1_not_enough_account_keys() {
+        let program_id = Address::default();
+        let data = [0u8; 32];
+        let result = process(&program_id, &[], &data);
+        assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+    }
+}
@@ -23,6 +23,217 @@ security_txt! {
     logo: "https://cdn.zupy.com/static/images/token/zupy-coin-purple-512.png"
 }
 
+/// Signature shared by every `instructions::*::process`.
+pub type Handler = fn(&Address, &[AccountView], &[u8]) -> ProgramResult;
+
+/// Declares the instruction set once: each `name => [discriminator]` entry
+/// becomes a row of [`INSTRUCTIONS`] and an arm of the dispatcher `match`
+/// (routing to `instructions::name::process`), so the two cannot drift.
+/// Discriminators are Anchor's `SHA256("global:<name>")[0..8]`.
+///
+/// With `debug-dispatch` each arm first logs `zupy_ix:{name}`; without it no
+/// `zupy_ix:` line (nor the syscall) is compiled in.
+macro_rules! instruction_table {
+    ($($name:ident => [$($byte:literal),+],)+) => {
+        /// Every instruction as (Anchor name, discriminator, handler), in
+        /// declaration order.
+        pub const INSTRUCTIONS: &[(&str, [u8; 8], Handler)] = &[
+            $((stringify!($name), [$($byte),+], instructions::$name::process as Handler),)+
+        ];
+
+        #[inline(always)]
+        fn dispatch(
+            program_id: &Address,
+            accounts: &[AccountView],
+            instruction_data: &[u8],
+            disc: [u8; 8],
+            data: &[u8],
+        ) -> ProgramResult {
+            match disc {
+                $([$($byte),+] => {
+                    #[cfg(feature = "debug-dispatch")]
+                    $crate::helpers::log::sol_log(concat!("zupy_ix:", stringify!($name)));
+                    instructions::$name::process(program_id, accounts, data)
+                })+
+                _ => dispatch_unknown(program_id, accounts, instruction_data),
+            }
+        }
+    };
+}
+
+instruction_table! {
+    initialize_token => [38, 209, 150, 50, 190, 117, 16, 54],
+    initialize_metadata => [35, 215, 241, 156, 122, 208, 206, 212],
+    update_metadata_field => [103, 217, 144, 202, 46, 70, 233, 141],
+    mint_tokens => [59, 132, 24, 246, 122, 39, 8, 243],
+    treasury_restock_pool => [94, 62, 103, 106, 93, 87, 173, 24],
+    transfer_from_pool => [136, 167, 45, 66, 74, 252, 0, 16],
+    return_to_pool => [36, 85, 39, 183, 30, 172, 176, 72],
+    transfer_company_to_user => [8, 143, 213, 13, 143, 247, 145, 33],
+    transfer_user_to_company => [186, 233, 22, 40, 87, 223, 252, 131],
+    execute_split_transfer => [51, 254, 61, 214, 234, 138, 101, 214],
+    burn_tokens => [76, 15, 51, 254, 229, 215, 121, 66],
+    burn_from_company_pda => [43, 207, 204, 77, 74, 93, 165, 34],
+    initialize_rate_limit => [36, 132, 34, 217, 150, 48, 192, 165],
+    set_paused => [91, 60, 125, 192, 176, 225, 166, 218],
+    create_zupy_card => [92, 114, 17, 0, 219, 121, 112, 150],
+    create_coupon_nft => [5, 106, 153, 76, 114, 157, 63, 236],
+    mint_coupon_cnft => [75, 5, 206, 155, 96, 133, 98, 15],
+    withdraw_to_external => [114, 198, 185, 119, 169, 163, 29, 251],
+    return_user_to_pool => [151, 33, 221, 193, 7, 214, 10, 199],
+    // V1 CPI passthrough, mainnet
+    return_user_to_pool_v1 => [41, 120, 49, 208, 53, 163, 70, 32],
+    // Company→pool V1 CPI passthrough, mainnet
+    return_to_pool_v1 => [170, 95, 61, 209, 55, 75, 105, 211],
+    batch_transfer_from_pool => [11, 232, 149, 72, 195, 180, 19, 106],
+    transfer_user_to_user => [180, 29, 108, 140, 251, 43, 31, 123],
+    transfer_company_to_company => [42, 241, 16, 155, 103, 235, 235, 117],
+    set_split_range => [192, 112, 184, 242, 22, 8, 14, 63],
+    rebalance_pools => [85, 169, 193, 206, 239, 180, 209, 253],
+    distribute_incentive => [172, 211, 121, 130, 226, 29, 17, 199],
+    transfer_pool_to_company => [203, 31, 19, 31, 105, 47, 222, 80],
+    create_escrow => [253, 215, 165, 116, 36, 108, 68, 80],
+    claim_escrow => [200, 80, 182, 159, 61, 75, 9, 205],
+    cancel_escrow => [156, 203, 54, 179, 38, 72, 33, 21],
+    create_vesting => [135, 184, 171, 156, 197, 162, 246, 44],
+    claim_vested => [208, 190, 166, 114, 203, 225, 140, 208],
+    close_receipt => [126, 254, 244, 203, 124, 164, 134, 89],
+    refund_company_to_user => [52, 146, 11, 177, 127, 186, 168, 248],
+    refund_user_to_company => [211, 211, 223, 120, 221, 99, 252, 87],
+    sweep_dust => [9, 49, 242, 88, 156, 84, 109, 15],
+    deposit_from_external => [139, 158, 73, 234, 109, 218, 37, 182],
+    deposit_to_company => [105, 18, 104, 231, 120, 142, 27, 161],
+    withdraw_company_to_external => [221, 137, 39, 239, 134, 153, 158, 5],
+    set_withdraw_fee => [33, 223, 102, 118, 225, 116, 8, 238],
+    set_user_withdraw_limit => [66, 103, 224, 46, 107, 159, 198, 142],
+    request_withdrawal => [251, 85, 121, 205, 56, 201, 12, 177],
+    approve_withdrawal => [75, 48, 146, 122, 201, 158, 210, 123],
+    cancel_withdrawal => [183, 104, 181, 250, 28, 128, 210, 70],
+    set_withdraw_approval_threshold => [42, 9, 51, 233, 87, 88, 53, 22],
+    batch_return_to_pool => [222, 46, 174, 2, 156, 238, 219, 34],
+    // Company→user V1 CPI passthrough, mainnet
+    transfer_company_to_user_v1 => [120, 245, 74, 115, 49, 3, 57, 70],
+    // User→external wallet V1 CPI passthrough, mainnet
+    withdraw_to_external_v1 => [26, 223, 149, 246, 144, 169, 233, 46],
+    set_v1_sunset => [1, 118, 100, 229, 250, 202, 232, 49],
+    mint_to_pool => [12, 29, 169, 194, 157, 181, 118, 124],
+    burn_pool_tokens => [189, 143, 81, 31, 173, 28, 110, 37],
+    update_metadata_fields => [61, 69, 228, 80, 185, 192, 143, 124],
+    freeze_metadata => [8, 61, 146, 255, 94, 108, 158, 83],
+    revoke_zupy_card => [176, 218, 15, 77, 139, 66, 145, 34],
+    update_card_tier => [179, 143, 196, 15, 127, 132, 0, 73],
+    reissue_zupy_card => [197, 233, 16, 159, 160, 154, 0, 138],
+    redeem_coupon_nft => [20, 168, 33, 154, 93, 33, 255, 212],
+    transfer_coupon_nft => [33, 2, 46, 248, 13, 49, 16, 109],
+    close_coupon => [145, 129, 128, 62, 140, 121, 104, 203],
+    mint_coupon_cnft_batch => [109, 102, 110, 70, 92, 118, 58, 9],
+    set_coupon_collection => [131, 191, 144, 20, 32, 43, 205, 107],
+    burn_coupon_cnft => [202, 73, 131, 140, 182, 100, 104, 77],
+    transfer_coupon_cnft => [186, 174, 51, 18, 51, 212, 240, 93],
+    // Company registry
+    initialize_company => [75, 156, 55, 94, 184, 64, 58, 30],
+    // UserState registry
+    initialize_user => [111, 17, 185, 250, 60, 122, 38, 254],
+    // Treasury-only, grows TokenState
+    set_strict_registration => [92, 42, 138, 11, 227, 9, 178, 89],
+    // Treasury-only, reclaims CompanyState rent
+    close_company => [199, 184, 167, 41, 63, 245, 165, 93],
+    // Treasury-only, grows TokenState to v2
+    migrate_token_state => [191, 239, 37, 200, 20, 173, 31, 65],
+    // Treasury-only, creates the global Stats PDA
+    initialize_stats => [144, 201, 117, 76, 127, 118, 176, 16],
+    // Multi-input decompress
+    return_user_to_pool_merged => [187, 144, 44, 168, 103, 162, 5, 18],
+    // Multi-input decompress
+    withdraw_to_external_merged => [156, 165, 248, 89, 75, 159, 87, 181],
+    // Permissionless, zero accounts: build version via return data
+    get_version => [168, 85, 244, 45, 81, 56, 130, 50],
+}
+
+/// High bit of a compact opcode (`compact-discriminator` feature).
+pub const COMPACT_OPCODE_FLAG: u8 = 0x80;
+
+/// Compact 1-byte opcode of an instruction: `0x80 | index` into
+/// [`INSTRUCTIONS`]. Stable as long as the table is append-only.
+pub fn compact_opcode(name: &str) -> Option<u8> {
+    let index = INSTRUCTIONS.iter().position(|(n, _, _)| *n == name)?;
+    u8::try_from(index).ok().filter(|i| *i < COMPACT_OPCODE_FLAG).map(|i| COMPACT_OPCODE_FLAG | i)
+}
+
+/// Handler of a compact opcode; `None` without the high bit or past the table.
+#[inline(always)]
+pub fn compact_handler(opcode: u8) -> Option<Handler> {
+    if opcode & COMPACT_OPCODE_FLAG == 0 {
+        return None;
+    }
+    INSTRUCTIONS.get((opcode & !COMPACT_OPCODE_FLAG) as usize).map(|(_, _, handler)| *handler)
+}
+
+/// Data that is not a known 8-byte discriminator. The long form always wins,
+/// so with `compact-discriminator` a first byte with the high bit set is only
+/// read as a compact opcode (payload from byte 1) after the 8-byte match missed.
+///
+/// Otherwise 8+ bytes are an unknown opcode: the received discriminator is
+/// logged and `UnknownInstruction` returned. Shorter data is a malformed
+/// payload and stays `InvalidInstructionData`.
+#[inline(always)]
+fn dispatch_unknown(
+    program_id: &Address,
+    accounts: &[AccountView],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    #[cfg(feature = "compact-discriminator")]
+    if let Some((&opcode, data)) = instruction_data.split_first() {
+        if let Some(handler) = compact_handler(opcode) {
+            #[cfg(feature = "debug-dispatch")]
+            log_compact_dispatch(opcode);
+            return handler(program_id, accounts, data);
+        }
+    }
+    #[cfg(not(feature = "compact-discriminator"))]
+    let _ = (program_id, accounts);
+
+    let Some(disc) = instruction_data.first_chunk::<8>() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let line = unknown_discriminator_line(disc);
+    if let Ok(line) = core::str::from_utf8(&line) {
+        crate::helpers::log::sol_log(line);
+    }
+    Err(error::ZupyTokenError::UnknownInstruction.into())
+}
+
+/// `zupy_ix:{name}` for a compact opcode, matching the long-form arms' line.
+#[cfg(all(feature = "compact-discriminator", feature = "debug-dispatch"))]
+fn log_compact_dispatch(opcode: u8) {
+    const PREFIX: &[u8] = b"zupy_ix:";
+    let name = INSTRUCTIONS[(opcode & !COMPACT_OPCODE_FLAG) as usize].0.as_bytes();
+    let mut line = [0u8; 64];
+    let len = (PREFIX.len() + name.len()).min(line.len());
+    line[..PREFIX.len()].copy_from_slice(PREFIX);
+    line[PREFIX.len()..len].copy_from_slice(&name[..len - PREFIX.len()]);
+    if let Ok(line) = core::str::from_utf8(&line[..len]) {
+        crate::helpers::log::sol_log(line);
+    }
+}
+
+const UNKNOWN_DISC_PREFIX: &[u8] = b"zupy_err:UnknownInstruction:";
+const UNKNOWN_DISC_LINE_LEN: usize = UNKNOWN_DISC_PREFIX.len() + 16;
+
+/// `zupy_err:UnknownInstruction:{disc as 16 lowercase hex digits}`, built on
+/// the stack (no `format!`).
+fn unknown_discriminator_line(disc: &[u8; 8]) -> [u8; UNKNOWN_DISC_LINE_LEN] {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    let mut line = [0u8; UNKNOWN_DISC_LINE_LEN];
+    line[..UNKNOWN_DISC_PREFIX.len()].copy_from_slice(UNKNOWN_DISC_PREFIX);
+    for (i, byte) in disc.iter().enumerate() {
+        let at = UNKNOWN_DISC_PREFIX.len() + 2 * i;
+        line[at] = HEX[(byte >> 4) as usize];
+        line[at + 1] = HEX[(byte & 0x0F) as usize];
+    }
+    line
+}
+
 entrypoint!(process_instruction);
 
 pub fn process_instruction(
@@ -31,7 +242,7 @@ pub fn process_instruction(
     instruction_data: &[u8],
 ) -> ProgramResult {
     if instruction_data.len() < 8 {
-        return Err(ProgramError::InvalidInstructionData);
+        return dispatch_unknown(program_id, accounts, instruction_data);
     }
 
     let (disc_bytes, data) = instruction_data.split_at(8);
@@ -39,175 +250,45 @@ pub fn process_instruction(
         .try_into()
         .map_err(|_| ProgramError::InvalidInstructionData)?;
 
-    match disc {
-        // 1. initialize_token
-        [38, 209, 150, 50, 190, 117, 16, 54] => {
-            instructions::initialize_token::process(program_id, accounts, data)
-        }
-        // 2. initialize_metadata
-        [35, 215, 241, 156, 122, 208, 206, 212] => {
-            instructions::initialize_metadata::process(program_id, accounts, data)
-        }
-        // 3. update_metadata_field
-        [103, 217, 144, 202, 46, 70, 233, 141] => {
-            instructions::update_metadata_field::process(program_id, accounts, data)
-        }
-        // 4. mint_tokens
-        [59, 132, 24, 246, 122, 39, 8, 243] => {
-            instructions::mint_tokens::process(program_id, accounts, data)
-        }
-        // 5. treasury_restock_pool
-        [94, 62, 103, 106, 93, 87, 173, 24] => {
-            instructions::treasury_restock_pool::process(program_id, accounts, data)
-        }
-        // 6. transfer_from_pool
-        [136, 167, 45, 66, 74, 252, 0, 16] => {
-            instructions::transfer_from_pool::process(program_id, accounts, data)
-        }
-        // 7. return_to_pool
-        [36, 85, 39, 183, 30, 172, 176, 72] => {
-            instructions::return_to_pool::process(program_id, accounts, data)
-        }
-        // 8. transfer_company_to_user
-        [8, 143, 213, 13, 143, 247, 145, 33] => {
-            instructions::transfer_company_to_user::process(program_id, accounts, data)
-        }
-        // 9. transfer_user_to_company
-        [186, 233, 22, 40, 87, 223, 252, 131] => {
-            instructions::transfer_user_to_company::process(program_id, accounts, data)
-        }
-        // 10. execute_split_transfer
-        [51, 254, 61, 214, 234, 138, 101, 214] => {
-            instructions::execute_split_transfer::process(program_id, accounts, data)
-        }
-        // 11. burn_tokens
-        [76, 15, 51, 254, 229, 215, 121, 66] => {
-            instructions::burn_tokens::process(program_id, accounts, data)
-        }
-        // 12. burn_from_company_pda
-        [43, 207, 204, 77, 74, 93, 165, 34] => {
-            instructions::burn_from_company_pda::process(program_id, accounts, data)
-        }
-        // 13. initialize_rate_limit
-        [36, 132, 34, 217, 150, 48, 192, 165] => {
-            instructions::initialize_rate_limit::process(program_id, accounts, data)
-        }
-        // 14. set_paused
-        [91, 60, 125, 192, 176, 225, 166, 218] => {
-            instructions::set_paused::process(program_id, accounts, data)
-        }
-        // 15. create_zupy_card
-        [92, 114, 17, 0, 219, 121, 112, 150] => {
-            instructions::create_zupy_card::process(program_id, accounts, data)
-        }
-        // 16. create_coupon_nft
-        [5, 106, 153, 76, 114, 157, 63, 236] => {
-            instructions::create_coupon_nft::process(program_id, accounts, data)
-        }
-        // 17. mint_coupon_cnft
-        [75, 5, 206, 155, 96, 133, 98, 15] => {
-            instructions::mint_coupon_cnft::process(program_id, accounts, data)
-        }
-        // 18. withdraw_to_external
-        [114, 198, 185, 119, 169, 163, 29, 251] => {
-            instructions::withdraw_to_external::process(program_id, accounts, data)
-        }
-        // 19. return_user_to_pool
-        [151, 33, 221, 193, 7, 214, 10, 199] => {
-            instructions::return_user_to_pool::process(program_id, accounts, data)
-        }
-        // 20. return_user_to_pool_v1 (V1 CPI passthrough, mainnet)
-        [41, 120, 49, 208, 53, 163, 70, 32] => {
-            instructions::return_user_to_pool_v1::process(program_id, accounts, data)
-        }
-        // 21. return_to_pool_v1 (company→pool V1 CPI passthrough, mainnet)
-        [170, 95, 61, 209, 55, 75, 105, 211] => {
-            instructions::return_to_pool_v1::process(program_id, accounts, data)
-        }
-        _ => Err(ProgramError::InvalidInstructionData),
-    }
+    dispatch(program_id, accounts, instruction_data, disc, data)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    /// All 21 instruction names (must match Anchor exactly).
-    const INSTRUCTION_NAMES: [&str; 21] = [
-        "initialize_token",
-        "initialize_metadata",
-        "update_metadata_field",
-        "mint_tokens",
-        "treasury_restock_pool",
-        "transfer_from_pool",
-        "return_to_pool",
-        "transfer_company_to_user",
-        "transfer_user_to_company",
-        "execute_split_transfer",
-        "burn_tokens",
-        "burn_from_company_pda",
-        "initialize_rate_limit",
-        "set_paused",
-        "create_zupy_card",
-        "create_coupon_nft",
-        "mint_coupon_cnft",
-        "withdraw_to_external",
-        "return_user_to_pool",
-        "return_user_to_pool_v1",
-        "return_to_pool_v1",
-    ];
-
-    /// All 21 discriminators in the same order.
-    const DISCRIMINATORS: [[u8; 8]; 21] = [
-        [38, 209, 150, 50, 190, 117, 16, 54],   // initialize_token
-        [35, 215, 241, 156, 122, 208, 206, 212], // initialize_metadata
-        [103, 217, 144, 202, 46, 70, 233, 141],  // update_metadata_field
-        [59, 132, 24, 246, 122, 39, 8, 243],     // mint_tokens
-        [94, 62, 103, 106, 93, 87, 173, 24],     // treasury_restock_pool
-        [136, 167, 45, 66, 74, 252, 0, 16],      // transfer_from_pool
-        [36, 85, 39, 183, 30, 172, 176, 72],     // return_to_pool
-        [8, 143, 213, 13, 143, 247, 145, 33],    // transfer_company_to_user
-        [186, 233, 22, 40, 87, 223, 252, 131],   // transfer_user_to_company
-        [51, 254, 61, 214, 234, 138, 101, 214],  // execute_split_transfer
-        [76, 15, 51, 254, 229, 215, 121, 66],    // burn_tokens
-        [43, 207, 204, 77, 74, 93, 165, 34],     // burn_from_company_pda
-        [36, 132, 34, 217, 150, 48, 192, 165],   // initialize_rate_limit
-        [91, 60, 125, 192, 176, 225, 166, 218],  // set_paused
-        [92, 114, 17, 0, 219, 121, 112, 150],    // create_zupy_card
-        [5, 106, 153, 76, 114, 157, 63, 236],    // create_coupon_nft
-        [75, 5, 206, 155, 96, 133, 98, 15],      // mint_coupon_cnft
-        [114, 198, 185, 119, 169, 163, 29, 251], // withdraw_to_external
-        [151, 33, 221, 193, 7, 214, 10, 199],    // return_user_to_pool
-        [41, 120, 49, 208, 53, 163, 70, 32],     // return_user_to_pool_v1
-        [170, 95, 61, 209, 55, 75, 105, 211],    // return_to_pool_v1
-    ];
+    /// Modules under `instructions/` that are shared code, not instructions.
+    const NON_INSTRUCTION_MODULES: [&str; 1] = ["split_math"];
+
+    /// Instructions that take no accounts and succeed without any.
+    const ZERO_ACCOUNT_INSTRUCTIONS: [&str; 1] = ["get_version"];
 
     /// AC2: Verify each discriminator matches SHA256("global:<name>")[0..8]
     #[test]
-    fn test_all_21_discriminators_match_sha256() {
+    fn test_all_discriminators_match_sha256() {
         use sha2::{Sha256, Digest};
 
-        for (i, name) in INSTRUCTION_NAMES.iter().enumerate() {
+        for (i, (name, disc, _)) in INSTRUCTIONS.iter().enumerate() {
             let input = format!("global:{}", name);
             let hash = Sha256::digest(input.as_bytes());
             let expected: [u8; 8] = hash[0..8].try_into().unwrap();
             assert_eq!(
-                DISCRIMINATORS[i], expected,
+                *disc, expected,
                 "Discriminator mismatch for instruction '{}' at index {}",
                 name, i
             );
         }
     }
 
-    /// AC2: All 21 discriminators are unique
+    /// AC2: All discriminators are unique
     #[test]
     fn test_all_discriminators_unique() {
-        for i in 0..21 {
-            for j in (i + 1)..21 {
+        for (i, (name_i, disc_i, _)) in INSTRUCTIONS.iter().enumerate() {
+            for (name_j, disc_j, _) in &INSTRUCTIONS[i + 1..] {
                 assert_ne!(
-                    DISCRIMINATORS[i], DISCRIMINATORS[j],
+                    disc_i, disc_j,
                     "Duplicate discriminator between '{}' and '{}'",
-                    INSTRUCTION_NAMES[i], INSTRUCTION_NAMES[j]
+                    name_i, name_j
                 );
             }
         }
@@ -230,29 +311,53 @@ mod tests {
         assert_eq!(result.unwrap_err(), ProgramError::InvalidInstructionData);
     }
 
-    /// AC3: Unknown discriminator returns InvalidInstructionData
+    /// AC3: Unknown discriminator returns UnknownInstruction
     #[test]
     fn test_unknown_discriminator_returns_error() {
         let pid = Address::from(constants::PROGRAM_ID);
         let unknown = [255u8; 8];
         let result = process_instruction(&pid, &[], &unknown);
-        assert_eq!(result.unwrap_err(), ProgramError::InvalidInstructionData);
+        assert_eq!(
+            result.unwrap_err(),
+            ProgramError::Custom(error::ZupyTokenError::UnknownInstruction as u32)
+        );
+        // Trailing payload does not change the verdict
+        let result = process_instruction(&pid, &[], &[0u8; 12]);
+        assert_eq!(
+            result.unwrap_err(),
+            ProgramError::Custom(error::ZupyTokenError::UnknownInstruction as u32)
+        );
+    }
+
+    /// The unknown-discriminator log line carries the 8 received bytes in hex
+    #[test]
+    fn test_unknown_discriminator_line() {
+        let line = unknown_discriminator_line(&[0x00, 0x01, 0x7f, 0x80, 0xab, 0xcd, 0xef, 0xff]);
+        assert_eq!(
+            core::str::from_utf8(&line).unwrap(),
+            "zupy_err:UnknownInstruction:00017f80abcdefff"
+        );
     }
 
     /// AC3: Valid discriminator dispatches to the correct handler.
-    /// All 21 instructions are implemented and return NotEnoughAccountKeys
-    /// when called with no accounts (proves routing works).
+    /// Every instruction returns NotEnoughAccountKeys when called with no
+    /// accounts (proves routing works), same as its table handler.
     #[test]
-    fn test_valid_discriminator_dispatches_all_21() {
+    fn test_valid_discriminator_dispatches_all() {
         let pid = Address::from(constants::PROGRAM_ID);
-        for (i, disc) in DISCRIMINATORS.iter().enumerate() {
+        for (name, disc, handler) in INSTRUCTIONS {
+            if ZERO_ACCOUNT_INSTRUCTIONS.contains(name) {
+                assert_eq!(process_instruction(&pid, &[], disc), Ok(()), "{}", name);
+                continue;
+            }
             let result = process_instruction(&pid, &[], disc);
             assert_eq!(
                 result.unwrap_err(),
                 ProgramError::NotEnoughAccountKeys,
                 "Instruction '{}' should return NotEnoughAccountKeys with no accounts",
-                INSTRUCTION_NAMES[i],
+                name,
             );
+            assert_eq!(handler(&pid, &[], &[]).unwrap_err(), ProgramError::NotEnoughAccountKeys);
         }
     }
 
@@ -262,16 +367,100 @@ mod tests {
     #[test]
     fn test_discriminator_with_extra_data_dispatches_to_handler() {
         let pid = Address::from(constants::PROGRAM_ID);
-        let mut data = Vec::from(DISCRIMINATORS[0]); // initialize_token
+        let mut data = Vec::from(INSTRUCTIONS[0].1); // initialize_token
         data.extend_from_slice(&[1, 2, 3, 4]); // extra instruction data
         let result = process_instruction(&pid, &[], &data);
         assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
     }
 
-    /// AC2: Exactly 21 instructions are handled
+    /// AC2: One table row per instruction module, named after it
     #[test]
-    fn test_exactly_21_instructions() {
-        assert_eq!(INSTRUCTION_NAMES.len(), 21);
-        assert_eq!(DISCRIMINATORS.len(), 21);
+    fn test_instruction_table_covers_every_module() {
+        let modules: Vec<&str> = include_str!("instructions/mod.rs")
+            .lines()
+            .filter_map(|line| line.strip_prefix("pub mod ")?.strip_suffix(';'))
+            .filter(|module| !NON_INSTRUCTION_MODULES.contains(module))
+            .collect();
+        assert_eq!(INSTRUCTIONS.len(), modules.len());
+        for module in modules {
+            assert!(
+                INSTRUCTIONS.iter().any(|(name, _, _)| *name == module),
+                "instructions::{} has no table entry",
+                module,
+            );
+        }
+    }
+
+    /// Compact opcodes map one-to-one onto the long-form table: opcode i
+    /// reaches the same handler as instruction i's 8-byte discriminator.
+    #[test]
+    fn test_compact_opcodes_match_long_form() {
+        assert!(INSTRUCTIONS.len() <= COMPACT_OPCODE_FLAG as usize);
+        for (i, (name, _, handler)) in INSTRUCTIONS.iter().enumerate() {
+            let opcode = compact_opcode(name).unwrap();
+            assert_eq!(opcode, COMPACT_OPCODE_FLAG | i as u8, "{}", name);
+            assert_eq!(
+                compact_handler(opcode).map(|h| h as usize),
+                Some(*handler as usize),
+                "compact opcode {:#04x} routes away from '{}'",
+                opcode, name,
+            );
+        }
+        assert!(compact_handler(INSTRUCTIONS.len() as u8 | COMPACT_OPCODE_FLAG).is_none());
+        assert!(compact_handler(0x00).is_none());
+        assert!(compact_handler(0x7F).is_none());
+        assert_eq!(compact_opcode("not_an_instruction"), None);
+    }
+
+    /// Compact opcodes are wire format: pinned so reordering the table fails here.
+    #[test]
+    fn test_compact_opcodes_are_stable() {
+        assert_eq!(compact_opcode("initialize_token"), Some(0x80));
+        assert_eq!(compact_opcode("transfer_from_pool"), Some(0x85));
+        assert_eq!(compact_opcode("return_to_pool"), Some(0x86));
+        assert_eq!(compact_opcode("transfer_company_to_user"), Some(0x87));
+        assert_eq!(compact_opcode("transfer_user_to_company"), Some(0x88));
+        assert_eq!(compact_opcode("burn_tokens"), Some(0x8A));
+        assert_eq!(compact_opcode("withdraw_to_external"), Some(0x91));
+        assert_eq!(compact_opcode("withdraw_to_external_merged"), Some(0xC7));
+    }
+
+    /// With `compact-discriminator`, a bare opcode reaches its handler; the
+    /// long form still routes unchanged.
+    #[cfg(feature = "compact-discriminator")]
+    #[test]
+    fn test_compact_opcode_dispatches_all() {
+        let pid = Address::from(constants::PROGRAM_ID);
+        for (name, disc, _) in INSTRUCTIONS {
+            let opcode = compact_opcode(name).unwrap();
+            if ZERO_ACCOUNT_INSTRUCTIONS.contains(name) {
+                assert_eq!(process_instruction(&pid, &[], &[opcode]), Ok(()), "{}", name);
+                continue;
+            }
+            assert_eq!(
+                process_instruction(&pid, &[], &[opcode]).unwrap_err(),
+                ProgramError::NotEnoughAccountKeys,
+                "compact '{}' should reach its handler",
+                name,
+            );
+            assert_eq!(
+                process_instruction(&pid, &[], disc).unwrap_err(),
+                ProgramError::NotEnoughAccountKeys,
+            );
+        }
+    }
+
+    /// Compact opcodes past the table, and low first bytes, stay invalid.
+    #[cfg(feature = "compact-discriminator")]
+    #[test]
+    fn test_compact_opcode_unknown_returns_error() {
+        let pid = Address::from(constants::PROGRAM_ID);
+        let past_end = COMPACT_OPCODE_FLAG | INSTRUCTIONS.len() as u8;
+        for data in [vec![past_end], vec![0x7F], vec![0x00, 1, 2]] {
+            assert_eq!(
+                process_instruction(&pid, &[], &data).unwrap_err(),
+                ProgramError::InvalidInstructionData,
+            );
+        }
     }
 }
@@ -0,0 +1,220 @@
+use crate::helpers::bytes::read_array;
+
+/// Zero-copy CompanyState — 50 bytes total.
+/// Anchor account discriminator: SHA256("account:CompanyState")[0..8]
+///
+/// PDA `[COMPANY_STATE_SEED, &company_id.to_le_bytes()]`: the on-chain record
+/// that `company_id` was registered by `initialize_company`. It sits next to
+/// the company PDA `[COMPANY_SEED, company_id]`, which owns compressed tokens
+/// and stays data-less. Instructions that move company tokens do not require it;
+/// when it is passed they keep its running `total_received` / `total_sent` /
+/// `total_burned` counters (see `helpers::company_counters`).
+pub struct CompanyState<'a> {
+    data: &'a [u8],
+}
+
+pub struct CompanyStateMut<'a> {
+    data: &'a mut [u8],
+}
+
+pub const COMPANY_STATE_DISCRIMINATOR: [u8; 8] = [16, 209, 91, 71, 54, 50, 165, 120];
+pub const COMPANY_STATE_SIZE: usize = 50;
+
+const OFF_DISC: usize = 0;
+const OFF_COMPANY_ID: usize = 8;
+const OFF_CREATED_AT: usize = 16;
+const OFF_FLAGS: usize = 24;
+const OFF_BUMP: usize = 25;
+const OFF_TOTAL_RECEIVED: usize = 26;
+const OFF_TOTAL_SENT: usize = 34;
+const OFF_TOTAL_BURNED: usize = 42;
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(*read_array::<8>(data, offset))
+}
+
+impl<'a> CompanyState<'a> {
+    pub const SIZE: usize = COMPANY_STATE_SIZE;
+    pub const DISCRIMINATOR: [u8; 8] = COMPANY_STATE_DISCRIMINATOR;
+
+    pub fn from_slice(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn discriminator(&self) -> &[u8; 8] {
+        read_array::<8>(self.data, OFF_DISC)
+    }
+    pub fn company_id(&self) -> u64 {
+        u64::from_le_bytes(*read_array::<8>(self.data, OFF_COMPANY_ID))
+    }
+    /// Unix timestamp of the registration.
+    pub fn created_at(&self) -> i64 {
+        i64::from_le_bytes(*read_array::<8>(self.data, OFF_CREATED_AT))
+    }
+    /// Bit flags set at registration; no bits are defined yet.
+    pub fn flags(&self) -> u8 {
+        self.data[OFF_FLAGS]
+    }
+    pub fn bump(&self) -> u8 {
+        self.data[OFF_BUMP]
+    }
+    /// Tokens received by the company PDA (user → company transfers).
+    pub fn total_received(&self) -> u64 {
+        read_u64(self.data, OFF_TOTAL_RECEIVED)
+    }
+    /// Tokens sent from the company PDA (company → user, returns to pool).
+    pub fn total_sent(&self) -> u64 {
+        read_u64(self.data, OFF_TOTAL_SENT)
+    }
+    /// Tokens burned from the company PDA.
+    pub fn total_burned(&self) -> u64 {
+        read_u64(self.data, OFF_TOTAL_BURNED)
+    }
+    /// Balance implied by the counters: received − sent − burned, floored at 0.
+    pub fn recorded_balance(&self) -> u64 {
+        self.total_received()
+            .saturating_sub(self.total_sent())
+            .saturating_sub(self.total_burned())
+    }
+}
+
+impl<'a> CompanyStateMut<'a> {
+    pub fn from_slice(data: &'a mut [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn set_discriminator(&mut self, disc: &[u8; 8]) {
+        self.data[OFF_DISC..OFF_DISC + 8].copy_from_slice(disc);
+    }
+    pub fn set_company_id(&mut self, val: u64) {
+        self.data[OFF_COMPANY_ID..OFF_COMPANY_ID + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_created_at(&mut self, val: i64) {
+        self.data[OFF_CREATED_AT..OFF_CREATED_AT + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_flags(&mut self, val: u8) {
+        self.data[OFF_FLAGS] = val;
+    }
+    pub fn set_bump(&mut self, val: u8) {
+        self.data[OFF_BUMP] = val;
+    }
+    pub fn set_total_received(&mut self, val: u64) {
+        self.data[OFF_TOTAL_RECEIVED..OFF_TOTAL_RECEIVED + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_total_sent(&mut self, val: u64) {
+        self.data[OFF_TOTAL_SENT..OFF_TOTAL_SENT + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_total_burned(&mut self, val: u64) {
+        self.data[OFF_TOTAL_BURNED..OFF_TOTAL_BURNED + 8].copy_from_slice(&val.to_le_bytes());
+    }
+
+    /// Adds `amount` to `total_received`, saturating at `u64::MAX`.
+    pub fn record_received(&mut self, amount: u64) {
+        let total = read_u64(self.data, OFF_TOTAL_RECEIVED).saturating_add(amount);
+        self.set_total_received(total);
+    }
+    /// Adds `amount` to `total_sent`, saturating at `u64::MAX`.
+    pub fn record_sent(&mut self, amount: u64) {
+        let total = read_u64(self.data, OFF_TOTAL_SENT).saturating_add(amount);
+        self.set_total_sent(total);
+    }
+    /// Adds `amount` to `total_burned`, saturating at `u64::MAX`.
+    pub fn record_burned(&mut self, amount: u64) {
+        let total = read_u64(self.data, OFF_TOTAL_BURNED).saturating_add(amount);
+        self.set_total_burned(total);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_company_state_size() {
+        assert_eq!(COMPANY_STATE_SIZE, OFF_TOTAL_BURNED + 8);
+    }
+
+    #[test]
+    fn test_company_state_discriminator_matches_anchor() {
+        use sha2::{Sha256, Digest};
+        let hash = Sha256::digest(b"account:CompanyState");
+        let expected: [u8; 8] = hash[0..8].try_into().unwrap();
+        assert_eq!(COMPANY_STATE_DISCRIMINATOR, expected);
+    }
+
+    #[test]
+    fn test_read_write_round_trip() {
+        let mut buf = [0u8; COMPANY_STATE_SIZE];
+        let mut state = CompanyStateMut::from_slice(&mut buf);
+
+        state.set_discriminator(&COMPANY_STATE_DISCRIMINATOR);
+        state.set_company_id(u64::MAX);
+        state.set_created_at(1_700_000_000);
+        state.set_flags(0b1010_0101);
+        state.set_bump(251);
+        state.set_total_received(1);
+        state.set_total_sent(2);
+        state.set_total_burned(3);
+
+        let read = CompanyState::from_slice(&buf);
+        assert_eq!(read.discriminator(), &COMPANY_STATE_DISCRIMINATOR);
+        assert_eq!(read.company_id(), u64::MAX);
+        assert_eq!(read.created_at(), 1_700_000_000);
+        assert_eq!(read.flags(), 0b1010_0101);
+        assert_eq!(read.bump(), 251);
+        assert_eq!(read.total_received(), 1);
+        assert_eq!(read.total_sent(), 2);
+        assert_eq!(read.total_burned(), 3);
+    }
+
+    #[test]
+    fn test_counters_after_operation_sequence() {
+        let mut buf = [0u8; COMPANY_STATE_SIZE];
+        let mut state = CompanyStateMut::from_slice(&mut buf);
+        state.set_bump(7);
+
+        state.record_received(1_000);
+        state.record_sent(300);
+        state.record_received(500);
+        state.record_burned(200);
+        state.record_sent(100);
+
+        let read = CompanyState::from_slice(&buf);
+        assert_eq!(read.total_received(), 1_500);
+        assert_eq!(read.total_sent(), 400);
+        assert_eq!(read.total_burned(), 200);
+        assert_eq!(read.bump(), 7, "counters must not overlap the header");
+    }
+
+    #[test]
+    fn test_recorded_balance() {
+        let mut buf = [0u8; COMPANY_STATE_SIZE];
+        let mut state = CompanyStateMut::from_slice(&mut buf);
+        state.record_received(1_000);
+        state.record_sent(600);
+        state.record_burned(300);
+        assert_eq!(CompanyState::from_slice(&buf).recorded_balance(), 100);
+
+        CompanyStateMut::from_slice(&mut buf).record_sent(100);
+        assert_eq!(CompanyState::from_slice(&buf).recorded_balance(), 0);
+
+        // Untracked inflows (e.g. pool → company) can leave outflows ahead
+        CompanyStateMut::from_slice(&mut buf).record_burned(50);
+        assert_eq!(CompanyState::from_slice(&buf).recorded_balance(), 0);
+    }
+
+    #[test]
+    fn test_counters_saturate() {
+        let mut buf = [0u8; COMPANY_STATE_SIZE];
+        let mut state = CompanyStateMut::from_slice(&mut buf);
+        state.set_total_received(u64::MAX - 1);
+        state.record_received(5);
+        state.record_sent(u64::MAX);
+        state.record_sent(1);
+
+        let read = CompanyState::from_slice(&buf);
+        assert_eq!(read.total_received(), u64::MAX);
+        assert_eq!(read.total_sent(), u64::MAX);
+        assert_eq!(read.total_burned(), 0);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::helpers::bytes::read_array;
+
+/// Zero-copy CouponState — 141 bytes total.
+/// Anchor account discriminator: SHA256("account:CouponState")[0..8]
+///
+/// PDA `[COUPON_STATE_SEED, coupon_ksuid]`. Tracks whether the coupon NFT has
+/// been consumed by `redeem_coupon_nft`; a set `redeemed` flag rejects any
+/// further redemption with `CouponAlreadyRedeemed`, and a non-zero
+/// `expires_at` in the past rejects it with `CouponExpired`. A non-zero
+/// `value_amount` is paid from the issuer company's compressed PDA on redemption.
+/// `holder` is the user_pda whose ATA holds the coupon, moved by `transfer_coupon_nft`.
+pub struct CouponState<'a> {
+    data: &'a [u8],
+}
+
+pub struct CouponStateMut<'a> {
+    data: &'a mut [u8],
+}
+
+pub const COUPON_STATE_DISCRIMINATOR: [u8; 8] = [144, 129, 227, 81, 182, 182, 160, 153];
+pub const COUPON_STATE_SIZE: usize = 141;
+
+const OFF_DISC: usize = 0;
+const OFF_COUPON_KSUID: usize = 8;
+const OFF_MINT: usize = 35;
+const OFF_REDEEMED: usize = 67;
+const OFF_REDEEMED_AT: usize = 68;
+const OFF_COMPANY_ID: usize = 76;
+const OFF_EXPIRES_AT: usize = 84;
+const OFF_VALUE_AMOUNT: usize = 92;
+const OFF_ISSUER_COMPANY_ID: usize = 100;
+const OFF_HOLDER: usize = 108;
+const OFF_BUMP: usize = 140;
+
+impl<'a> CouponState<'a> {
+    pub const SIZE: usize = COUPON_STATE_SIZE;
+    pub const DISCRIMINATOR: [u8; 8] = COUPON_STATE_DISCRIMINATOR;
+
+    pub fn from_slice(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn discriminator(&self) -> &[u8; 8] {
+        read_array::<8>(self.data, OFF_DISC)
+    }
+    pub fn coupon_ksuid(&self) -> &[u8; 27] {
+        read_array::<27>(self.data, OFF_COUPON_KSUID)
+    }
+    pub fn mint(&self) -> &[u8; 32] {
+        read_array::<32>(self.data, OFF_MINT)
+    }
+    pub fn redeemed(&self) -> bool {
+        self.data[OFF_REDEEMED] != 0
+    }
+    /// Unix timestamp of the redemption; 0 while unredeemed.
+    pub fn redeemed_at(&self) -> i64 {
+        i64::from_le_bytes(*read_array::<8>(self.data, OFF_REDEEMED_AT))
+    }
+    /// Company the coupon was redeemed at; 0 while unredeemed.
+    pub fn company_id(&self) -> u64 {
+        u64::from_le_bytes(*read_array::<8>(self.data, OFF_COMPANY_ID))
+    }
+    /// Unix timestamp after which the coupon can no longer be redeemed; 0 = never.
+    pub fn expires_at(&self) -> i64 {
+        i64::from_le_bytes(*read_array::<8>(self.data, OFF_EXPIRES_AT))
+    }
+    /// True once `now` is past a non-zero `expires_at`.
+    pub fn is_expired(&self, now: i64) -> bool {
+        let expires_at = self.expires_at();
+        expires_at != 0 && now > expires_at
+    }
+    /// ZUPY the issuer company owes the bearer; 0 = no token value.
+    pub fn value_amount(&self) -> u64 {
+        u64::from_le_bytes(*read_array::<8>(self.data, OFF_VALUE_AMOUNT))
+    }
+    /// Company whose compressed PDA pays `value_amount`.
+    pub fn issuer_company_id(&self) -> u64 {
+        u64::from_le_bytes(
+            *read_array::<8>(self.data, OFF_ISSUER_COMPANY_ID),
+        )
+    }
+    /// user_pda currently holding the coupon.
+    pub fn holder(&self) -> &[u8; 32] {
+        read_array::<32>(self.data, OFF_HOLDER)
+    }
+    pub fn bump(&self) -> u8 {
+        self.data[OFF_BUMP]
+    }
+}
+
+impl<'a> CouponStateMut<'a> {
+    pub fn from_slice(data: &'a mut [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn set_discriminator(&mut self, disc: &[u8; 8]) {
+        self.data[OFF_DISC..OFF_DISC + 8].copy_from_slice(disc);
+    }
+    pub fn set_coupon_ksuid(&mut self, ksuid: &[u8; 27]) {
+        self.data[OFF_COUPON_KSUID..OFF_COUPON_KSUID + 27].copy_from_slice(ksuid);
+    }
+    pub fn set_mint(&mut self, mint: &[u8; 32]) {
+        self.data[OFF_MINT..OFF_MINT + 32].copy_from_slice(mint);
+    }
+    pub fn set_redeemed(&mut self, val: bool) {
+        self.data[OFF_REDEEMED] = val as u8;
+    }
+    pub fn set_redeemed_at(&mut self, val: i64) {
+        self.data[OFF_REDEEMED_AT..OFF_REDEEMED_AT + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_company_id(&mut self, val: u64) {
+        self.data[OFF_COMPANY_ID..OFF_COMPANY_ID + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_expires_at(&mut self, val: i64) {
+        self.data[OFF_EXPIRES_AT..OFF_EXPIRES_AT + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_value_amount(&mut self, val: u64) {
+        self.data[OFF_VALUE_AMOUNT..OFF_VALUE_AMOUNT + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_issuer_company_id(&mut self, val: u64) {
+        self.data[OFF_ISSUER_COMPANY_ID..OFF_ISSUER_COMPANY_ID + 8]
+            .copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_holder(&mut self, holder: &[u8; 32]) {
+        self.data[OFF_HOLDER..OFF_HOLDER + 32].copy_from_slice(holder);
+    }
+    pub fn set_bump(&mut self, val: u8) {
+        self.data[OFF_BUMP] = val;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coupon_state_size() {
+        assert_eq!(COUPON_STATE_SIZE, OFF_BUMP + 1);
+    }
+
+    #[test]
+    fn test_coupon_state_discriminator_matches_anchor() {
+        use sha2::{Sha256, Digest};
+        let hash = Sha256::digest(b"account:CouponState");
+        let expected: [u8; 8] = hash[0..8].try_into().unwrap();
+        assert_eq!(COUPON_STATE_DISCRIMINATOR, expected);
+    }
+
+    #[test]
+    fn test_read_write_round_trip() {
+        let mut buf = [0u8; COUPON_STATE_SIZE];
+        let mut state = CouponStateMut::from_slice(&mut buf);
+
+        state.set_discriminator(&COUPON_STATE_DISCRIMINATOR);
+        state.set_coupon_ksuid(b"1ujsszwN8NRY24YaXiTIE2VWDTS");
+        state.set_mint(&[9u8; 32]);
+        state.set_redeemed(true);
+        state.set_redeemed_at(1_700_000_000);
+        state.set_company_id(u64::MAX);
+        state.set_expires_at(1_800_000_000);
+        state.set_value_amount(5_000_000);
+        state.set_issuer_company_id(77);
+        state.set_holder(&[4u8; 32]);
+        state.set_bump(253);
+
+        let read = CouponState::from_slice(&buf);
+        assert_eq!(read.discriminator(), &COUPON_STATE_DISCRIMINATOR);
+        assert_eq!(read.coupon_ksuid(), b"1ujsszwN8NRY24YaXiTIE2VWDTS");
+        assert_eq!(read.mint(), &[9u8; 32]);
+        assert!(read.redeemed());
+        assert_eq!(read.redeemed_at(), 1_700_000_000);
+        assert_eq!(read.company_id(), u64::MAX);
+        assert_eq!(read.expires_at(), 1_800_000_000);
+        assert_eq!(read.value_amount(), 5_000_000);
+        assert_eq!(read.issuer_company_id(), 77);
+        assert_eq!(read.holder(), &[4u8; 32]);
+        assert_eq!(read.bump(), 253);
+    }
+
+    #[test]
+    fn test_is_expired() {
+        let mut buf = [0u8; COUPON_STATE_SIZE];
+        assert!(!CouponState::from_slice(&buf).is_expired(i64::MAX), "0 = no expiry");
+
+        CouponStateMut::from_slice(&mut buf).set_expires_at(1_000);
+        let state = CouponState::from_slice(&buf);
+        assert!(!state.is_expired(999));
+        assert!(!state.is_expired(1_000), "redeemable up to expires_at");
+        assert!(state.is_expired(1_001));
+    }
+}
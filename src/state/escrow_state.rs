@@ -0,0 +1,157 @@
+use crate::helpers::bytes::read_array;
+
+/// Zero-copy EscrowState — 51 bytes total.
+/// Anchor account discriminator: SHA256("account:EscrowState")[0..8]
+///
+/// The escrow PDA `[ESCROW_SEED, escrow_id]` is also the compressed owner of the
+/// escrowed tokens until they are claimed or cancelled.
+pub struct EscrowState<'a> {
+    data: &'a [u8],
+}
+
+pub struct EscrowStateMut<'a> {
+    data: &'a mut [u8],
+}
+
+pub const ESCROW_STATE_DISCRIMINATOR: [u8; 8] = [19, 90, 148, 111, 55, 130, 229, 108];
+pub const ESCROW_STATE_SIZE: usize = 51;
+
+/// Source kind: funded from the distribution pool ATA (Path A compress).
+pub const ESCROW_SOURCE_POOL: u8 = 0;
+/// Source kind: funded from a company PDA's compressed balance (Path B).
+pub const ESCROW_SOURCE_COMPANY: u8 = 1;
+
+pub const ESCROW_STATUS_PENDING: u8 = 0;
+pub const ESCROW_STATUS_CLAIMED: u8 = 1;
+pub const ESCROW_STATUS_CANCELLED: u8 = 2;
+
+const OFF_DISC: usize = 0;
+const OFF_ESCROW_ID: usize = 8;
+const OFF_SOURCE_KIND: usize = 16;
+const OFF_SOURCE_ID: usize = 17;
+const OFF_RECIPIENT_USER_ID: usize = 25;
+const OFF_AMOUNT: usize = 33;
+const OFF_EXPIRY: usize = 41;
+const OFF_STATUS: usize = 49;
+const OFF_BUMP: usize = 50;
+
+impl<'a> EscrowState<'a> {
+    pub const SIZE: usize = ESCROW_STATE_SIZE;
+    pub const DISCRIMINATOR: [u8; 8] = ESCROW_STATE_DISCRIMINATOR;
+
+    pub fn from_slice(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn discriminator(&self) -> &[u8; 8] {
+        read_array::<8>(self.data, OFF_DISC)
+    }
+    pub fn escrow_id(&self) -> u64 {
+        u64::from_le_bytes(*read_array::<8>(self.data, OFF_ESCROW_ID))
+    }
+    pub fn source_kind(&self) -> u8 {
+        self.data[OFF_SOURCE_KIND]
+    }
+    /// Company id for `ESCROW_SOURCE_COMPANY`; 0 for the pool.
+    pub fn source_id(&self) -> u64 {
+        u64::from_le_bytes(*read_array::<8>(self.data, OFF_SOURCE_ID))
+    }
+    pub fn recipient_user_id(&self) -> u64 {
+        u64::from_le_bytes(
+            *read_array::<8>(self.data, OFF_RECIPIENT_USER_ID),
+        )
+    }
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(*read_array::<8>(self.data, OFF_AMOUNT))
+    }
+    /// Unix timestamp after which the escrow can no longer be claimed.
+    pub fn expiry(&self) -> i64 {
+        i64::from_le_bytes(*read_array::<8>(self.data, OFF_EXPIRY))
+    }
+    pub fn status(&self) -> u8 {
+        self.data[OFF_STATUS]
+    }
+    pub fn bump(&self) -> u8 {
+        self.data[OFF_BUMP]
+    }
+}
+
+impl<'a> EscrowStateMut<'a> {
+    pub fn from_slice(data: &'a mut [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn set_discriminator(&mut self, disc: &[u8; 8]) {
+        self.data[OFF_DISC..OFF_DISC + 8].copy_from_slice(disc);
+    }
+    pub fn set_escrow_id(&mut self, val: u64) {
+        self.data[OFF_ESCROW_ID..OFF_ESCROW_ID + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_source_kind(&mut self, val: u8) {
+        self.data[OFF_SOURCE_KIND] = val;
+    }
+    pub fn set_source_id(&mut self, val: u64) {
+        self.data[OFF_SOURCE_ID..OFF_SOURCE_ID + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_recipient_user_id(&mut self, val: u64) {
+        self.data[OFF_RECIPIENT_USER_ID..OFF_RECIPIENT_USER_ID + 8]
+            .copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_amount(&mut self, val: u64) {
+        self.data[OFF_AMOUNT..OFF_AMOUNT + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_expiry(&mut self, val: i64) {
+        self.data[OFF_EXPIRY..OFF_EXPIRY + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_status(&mut self, val: u8) {
+        self.data[OFF_STATUS] = val;
+    }
+    pub fn set_bump(&mut self, val: u8) {
+        self.data[OFF_BUMP] = val;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escrow_state_size() {
+        assert_eq!(ESCROW_STATE_SIZE, OFF_BUMP + 1);
+    }
+
+    #[test]
+    fn test_escrow_state_discriminator_matches_anchor() {
+        use sha2::{Sha256, Digest};
+        let hash = Sha256::digest(b"account:EscrowState");
+        let expected: [u8; 8] = hash[0..8].try_into().unwrap();
+        assert_eq!(ESCROW_STATE_DISCRIMINATOR, expected);
+    }
+
+    #[test]
+    fn test_read_write_round_trip() {
+        let mut buf = [0u8; ESCROW_STATE_SIZE];
+        let mut state = EscrowStateMut::from_slice(&mut buf);
+
+        state.set_discriminator(&ESCROW_STATE_DISCRIMINATOR);
+        state.set_escrow_id(9_001);
+        state.set_source_kind(ESCROW_SOURCE_COMPANY);
+        state.set_source_id(42);
+        state.set_recipient_user_id(77);
+        state.set_amount(5_000_000);
+        state.set_expiry(1_700_604_800);
+        state.set_status(ESCROW_STATUS_CLAIMED);
+        state.set_bump(252);
+
+        let read = EscrowState::from_slice(&buf);
+        assert_eq!(read.discriminator(), &ESCROW_STATE_DISCRIMINATOR);
+        assert_eq!(read.escrow_id(), 9_001);
+        assert_eq!(read.source_kind(), ESCROW_SOURCE_COMPANY);
+        assert_eq!(read.source_id(), 42);
+        assert_eq!(read.recipient_user_id(), 77);
+        assert_eq!(read.amount(), 5_000_000);
+        assert_eq!(read.expiry(), 1_700_604_800);
+        assert_eq!(read.status(), ESCROW_STATUS_CLAIMED);
+        assert_eq!(read.bump(), 252);
+    }
+}
@@ -1,7 +1,25 @@
 pub mod token_state;
 pub mod rate_limit_state;
 pub mod zupy_card;
+pub mod escrow_state;
+pub mod vesting_state;
+pub mod receipt_state;
+pub mod withdraw_limit_state;
+pub mod withdrawal_request_state;
+pub mod coupon_state;
+pub mod company_state;
+pub mod user_state;
+pub mod stats_state;
 
 pub use token_state::TokenState;
 pub use rate_limit_state::RateLimitState;
 pub use zupy_card::ZupyCard;
+pub use escrow_state::EscrowState;
+pub use vesting_state::VestingState;
+pub use receipt_state::ReceiptState;
+pub use withdraw_limit_state::WithdrawLimitState;
+pub use withdrawal_request_state::WithdrawalRequestState;
+pub use coupon_state::CouponState;
+pub use company_state::CompanyState;
+pub use user_state::UserState;
+pub use stats_state::StatsState;
@@ -1,4 +1,9 @@
-/// Zero-copy RateLimitState — 57 bytes total.
+use crate::helpers::bytes::read_array;
+
+/// Zero-copy RateLimitState — 57 bytes total, plus the NFT mint window
+/// (`nft_window_start` / `nft_mints`, 73 bytes) and the operation window
+/// (`window_seconds` / `max_operations` / `current_count` / `window_start`,
+/// 105 bytes) on newer or grown accounts.
 /// Anchor account discriminator: SHA256("account:RateLimitState")[0..8]
 pub struct RateLimitState<'a> {
     data: &'a [u8],
@@ -10,12 +15,25 @@ pub struct RateLimitStateMut<'a> {
 
 pub const RATE_LIMIT_STATE_DISCRIMINATOR: [u8; 8] = [75, 173, 86, 207, 52, 170, 71, 97];
 pub const RATE_LIMIT_STATE_SIZE: usize = 57;
+/// Size of a RateLimitState carrying `nft_window_start` (57..65) and
+/// `nft_mints` (65..73). Shorter accounts read an empty window.
+pub const RATE_LIMIT_STATE_SIZE_WITH_NFT_WINDOW: usize = 73;
+/// Size of a RateLimitState carrying the operation window: `window_seconds`
+/// (73..81), `max_operations` (81..89), `current_count` (89..97) and
+/// `window_start` (97..105). Shorter accounts read zeroes.
+pub const RATE_LIMIT_STATE_SIZE_WITH_OPS_WINDOW: usize = 105;
 
 const OFF_DISC: usize = 0;
 const OFF_AUTHORITY: usize = 8;
 const OFF_CURRENT_DAY: usize = 40;
 const OFF_MINTED_TODAY: usize = 48;
 const OFF_BUMP: usize = 56;
+const OFF_NFT_WINDOW_START: usize = 57;
+const OFF_NFT_MINTS: usize = 65;
+const OFF_WINDOW_SECONDS: usize = 73;
+const OFF_MAX_OPERATIONS: usize = 81;
+const OFF_CURRENT_COUNT: usize = 89;
+const OFF_WINDOW_START: usize = 97;
 
 impl<'a> RateLimitState<'a> {
     pub const SIZE: usize = RATE_LIMIT_STATE_SIZE;
@@ -26,20 +44,70 @@ impl<'a> RateLimitState<'a> {
     }
 
     pub fn discriminator(&self) -> &[u8; 8] {
-        self.data[OFF_DISC..OFF_DISC + 8].try_into().unwrap()
+        read_array::<8>(self.data, OFF_DISC)
     }
     pub fn authority(&self) -> &[u8; 32] {
-        self.data[OFF_AUTHORITY..OFF_AUTHORITY + 32].try_into().unwrap()
+        read_array::<32>(self.data, OFF_AUTHORITY)
     }
     pub fn current_day(&self) -> u64 {
-        u64::from_le_bytes(self.data[OFF_CURRENT_DAY..OFF_CURRENT_DAY + 8].try_into().unwrap())
+        u64::from_le_bytes(*read_array::<8>(self.data, OFF_CURRENT_DAY))
     }
     pub fn minted_today(&self) -> u64 {
-        u64::from_le_bytes(self.data[OFF_MINTED_TODAY..OFF_MINTED_TODAY + 8].try_into().unwrap())
+        u64::from_le_bytes(*read_array::<8>(self.data, OFF_MINTED_TODAY))
     }
     pub fn bump(&self) -> u8 {
         self.data[OFF_BUMP]
     }
+    /// Start of the current NFT mint window (unix timestamp); 0 on 57-byte accounts.
+    pub fn nft_window_start(&self) -> i64 {
+        if self.data.len() < RATE_LIMIT_STATE_SIZE_WITH_NFT_WINDOW {
+            return 0;
+        }
+        i64::from_le_bytes(
+            *read_array::<8>(self.data, OFF_NFT_WINDOW_START),
+        )
+    }
+    /// NFTs minted in the current window; 0 on 57-byte accounts.
+    pub fn nft_mints(&self) -> u64 {
+        if self.data.len() < RATE_LIMIT_STATE_SIZE_WITH_NFT_WINDOW {
+            return 0;
+        }
+        u64::from_le_bytes(*read_array::<8>(self.data, OFF_NFT_MINTS))
+    }
+    /// Length of the operation window in seconds; 0 on accounts without it.
+    pub fn window_seconds(&self) -> i64 {
+        if self.data.len() < RATE_LIMIT_STATE_SIZE_WITH_OPS_WINDOW {
+            return 0;
+        }
+        i64::from_le_bytes(
+            *read_array::<8>(self.data, OFF_WINDOW_SECONDS),
+        )
+    }
+    /// Operations allowed per window; 0 on accounts without it.
+    pub fn max_operations(&self) -> u64 {
+        if self.data.len() < RATE_LIMIT_STATE_SIZE_WITH_OPS_WINDOW {
+            return 0;
+        }
+        u64::from_le_bytes(
+            *read_array::<8>(self.data, OFF_MAX_OPERATIONS),
+        )
+    }
+    /// Operations counted in the current window; 0 on accounts without it.
+    pub fn current_count(&self) -> u64 {
+        if self.data.len() < RATE_LIMIT_STATE_SIZE_WITH_OPS_WINDOW {
+            return 0;
+        }
+        u64::from_le_bytes(
+            *read_array::<8>(self.data, OFF_CURRENT_COUNT),
+        )
+    }
+    /// Start of the current operation window (unix timestamp); 0 on accounts without it.
+    pub fn window_start(&self) -> i64 {
+        if self.data.len() < RATE_LIMIT_STATE_SIZE_WITH_OPS_WINDOW {
+            return 0;
+        }
+        i64::from_le_bytes(*read_array::<8>(self.data, OFF_WINDOW_START))
+    }
 }
 
 impl<'a> RateLimitStateMut<'a> {
@@ -62,6 +130,25 @@ impl<'a> RateLimitStateMut<'a> {
     pub fn set_bump(&mut self, val: u8) {
         self.data[OFF_BUMP] = val;
     }
+    pub fn set_nft_window_start(&mut self, val: i64) {
+        self.data[OFF_NFT_WINDOW_START..OFF_NFT_WINDOW_START + 8]
+            .copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_nft_mints(&mut self, val: u64) {
+        self.data[OFF_NFT_MINTS..OFF_NFT_MINTS + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_window_seconds(&mut self, val: i64) {
+        self.data[OFF_WINDOW_SECONDS..OFF_WINDOW_SECONDS + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_max_operations(&mut self, val: u64) {
+        self.data[OFF_MAX_OPERATIONS..OFF_MAX_OPERATIONS + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_current_count(&mut self, val: u64) {
+        self.data[OFF_CURRENT_COUNT..OFF_CURRENT_COUNT + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_window_start(&mut self, val: i64) {
+        self.data[OFF_WINDOW_START..OFF_WINDOW_START + 8].copy_from_slice(&val.to_le_bytes());
+    }
 }
 
 #[cfg(test)]
@@ -100,4 +187,78 @@ mod tests {
         assert_eq!(read.minted_today(), 500_000_000_000);
         assert_eq!(read.bump(), 253);
     }
+
+    #[test]
+    fn test_legacy_account_reads_empty_nft_window() {
+        let buf = [0xFFu8; RATE_LIMIT_STATE_SIZE];
+        let read = RateLimitState::from_slice(&buf);
+        assert_eq!(read.nft_window_start(), 0);
+        assert_eq!(read.nft_mints(), 0);
+    }
+
+    #[test]
+    fn test_nft_window_does_not_touch_token_counters() {
+        let mut buf = [0u8; RATE_LIMIT_STATE_SIZE_WITH_NFT_WINDOW];
+        let mut state = RateLimitStateMut::from_slice(&mut buf);
+        state.set_current_day(19723);
+        state.set_minted_today(1_000);
+        state.set_bump(254);
+        state.set_nft_window_start(1_700_000_000);
+        state.set_nft_mints(u64::MAX);
+
+        let read = RateLimitState::from_slice(&buf);
+        assert_eq!(read.current_day(), 19723);
+        assert_eq!(read.minted_today(), 1_000);
+        assert_eq!(read.bump(), 254);
+        assert_eq!(read.nft_window_start(), 1_700_000_000);
+        assert_eq!(read.nft_mints(), u64::MAX);
+    }
+
+    #[test]
+    fn test_short_accounts_read_empty_ops_window() {
+        let buf = [0xFFu8; RATE_LIMIT_STATE_SIZE_WITH_NFT_WINDOW];
+        let read = RateLimitState::from_slice(&buf);
+        assert_eq!(read.window_seconds(), 0);
+        assert_eq!(read.max_operations(), 0);
+        assert_eq!(read.current_count(), 0);
+        assert_eq!(read.window_start(), 0);
+    }
+
+    #[test]
+    fn test_layout_golden() {
+        assert_eq!(RATE_LIMIT_STATE_SIZE_WITH_NFT_WINDOW, RATE_LIMIT_STATE_SIZE + 16);
+        assert_eq!(RATE_LIMIT_STATE_SIZE_WITH_OPS_WINDOW, RATE_LIMIT_STATE_SIZE_WITH_NFT_WINDOW + 32);
+
+        let mut buf = [0u8; RATE_LIMIT_STATE_SIZE_WITH_OPS_WINDOW];
+        let mut state = RateLimitStateMut::from_slice(&mut buf);
+        state.set_discriminator(&RATE_LIMIT_STATE_DISCRIMINATOR);
+        state.set_authority(&[0xAA; 32]);
+        state.set_current_day(0x0101_0101_0101_0101);
+        state.set_minted_today(0x0202_0202_0202_0202);
+        state.set_bump(0xBB);
+        state.set_nft_window_start(0x0303_0303_0303_0303);
+        state.set_nft_mints(0x0404_0404_0404_0404);
+        state.set_window_seconds(0x0505_0505_0505_0505);
+        state.set_max_operations(0x0606_0606_0606_0606);
+        state.set_current_count(0x0707_0707_0707_0707);
+        state.set_window_start(0x0808_0808_0808_0808);
+
+        assert_eq!(&buf[0..8], &RATE_LIMIT_STATE_DISCRIMINATOR);
+        assert_eq!(&buf[8..40], &[0xAA; 32]);
+        assert_eq!(&buf[40..48], &[0x01; 8]);
+        assert_eq!(&buf[48..56], &[0x02; 8]);
+        assert_eq!(buf[56], 0xBB);
+        assert_eq!(&buf[57..65], &[0x03; 8]);
+        assert_eq!(&buf[65..73], &[0x04; 8]);
+        assert_eq!(&buf[73..81], &[0x05; 8]);
+        assert_eq!(&buf[81..89], &[0x06; 8]);
+        assert_eq!(&buf[89..97], &[0x07; 8]);
+        assert_eq!(&buf[97..105], &[0x08; 8]);
+
+        let read = RateLimitState::from_slice(&buf);
+        assert_eq!(read.window_seconds(), 0x0505_0505_0505_0505);
+        assert_eq!(read.max_operations(), 0x0606_0606_0606_0606);
+        assert_eq!(read.current_count(), 0x0707_0707_0707_0707);
+        assert_eq!(read.window_start(), 0x0808_0808_0808_0808);
+    }
 }
@@ -0,0 +1,110 @@
+use crate::helpers::bytes::read_array;
+
+/// Zero-copy ReceiptState — 65 bytes total.
+/// Anchor account discriminator: SHA256("account:ReceiptState")[0..8]
+///
+/// PDA `[RECEIPT_SEED, op_id]`. Its existence marks `op_id` as executed; a retry
+/// with the same id fails at creation with `DuplicateOperation`. Rent is
+/// returned to `payer` by `close_receipt` after `RECEIPT_RETENTION_SECS`.
+pub struct ReceiptState<'a> {
+    data: &'a [u8],
+}
+
+pub struct ReceiptStateMut<'a> {
+    data: &'a mut [u8],
+}
+
+pub const RECEIPT_STATE_DISCRIMINATOR: [u8; 8] = [60, 100, 197, 85, 27, 171, 25, 247];
+pub const RECEIPT_STATE_SIZE: usize = 65;
+
+const OFF_DISC: usize = 0;
+const OFF_OP_ID: usize = 8;
+const OFF_PAYER: usize = 24;
+const OFF_CREATED_AT: usize = 56;
+const OFF_BUMP: usize = 64;
+
+impl<'a> ReceiptState<'a> {
+    pub const SIZE: usize = RECEIPT_STATE_SIZE;
+    pub const DISCRIMINATOR: [u8; 8] = RECEIPT_STATE_DISCRIMINATOR;
+
+    pub fn from_slice(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn discriminator(&self) -> &[u8; 8] {
+        read_array::<8>(self.data, OFF_DISC)
+    }
+    pub fn op_id(&self) -> &[u8; 16] {
+        read_array::<16>(self.data, OFF_OP_ID)
+    }
+    /// Account that paid the rent; the only valid `close_receipt` destination.
+    pub fn payer(&self) -> &[u8; 32] {
+        read_array::<32>(self.data, OFF_PAYER)
+    }
+    /// Unix timestamp of the transfer that created the receipt.
+    pub fn created_at(&self) -> i64 {
+        i64::from_le_bytes(*read_array::<8>(self.data, OFF_CREATED_AT))
+    }
+    pub fn bump(&self) -> u8 {
+        self.data[OFF_BUMP]
+    }
+}
+
+impl<'a> ReceiptStateMut<'a> {
+    pub fn from_slice(data: &'a mut [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn set_discriminator(&mut self, disc: &[u8; 8]) {
+        self.data[OFF_DISC..OFF_DISC + 8].copy_from_slice(disc);
+    }
+    pub fn set_op_id(&mut self, op_id: &[u8; 16]) {
+        self.data[OFF_OP_ID..OFF_OP_ID + 16].copy_from_slice(op_id);
+    }
+    pub fn set_payer(&mut self, payer: &[u8; 32]) {
+        self.data[OFF_PAYER..OFF_PAYER + 32].copy_from_slice(payer);
+    }
+    pub fn set_created_at(&mut self, val: i64) {
+        self.data[OFF_CREATED_AT..OFF_CREATED_AT + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_bump(&mut self, val: u8) {
+        self.data[OFF_BUMP] = val;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_receipt_state_size() {
+        assert_eq!(RECEIPT_STATE_SIZE, OFF_BUMP + 1);
+    }
+
+    #[test]
+    fn test_receipt_state_discriminator_matches_anchor() {
+        use sha2::{Sha256, Digest};
+        let hash = Sha256::digest(b"account:ReceiptState");
+        let expected: [u8; 8] = hash[0..8].try_into().unwrap();
+        assert_eq!(RECEIPT_STATE_DISCRIMINATOR, expected);
+    }
+
+    #[test]
+    fn test_read_write_round_trip() {
+        let mut buf = [0u8; RECEIPT_STATE_SIZE];
+        let mut state = ReceiptStateMut::from_slice(&mut buf);
+
+        state.set_discriminator(&RECEIPT_STATE_DISCRIMINATOR);
+        state.set_op_id(&[7u8; 16]);
+        state.set_payer(&[9u8; 32]);
+        state.set_created_at(1_700_000_000);
+        state.set_bump(253);
+
+        let read = ReceiptState::from_slice(&buf);
+        assert_eq!(read.discriminator(), &RECEIPT_STATE_DISCRIMINATOR);
+        assert_eq!(read.op_id(), &[7u8; 16]);
+        assert_eq!(read.payer(), &[9u8; 32]);
+        assert_eq!(read.created_at(), 1_700_000_000);
+        assert_eq!(read.bump(), 253);
+    }
+}
@@ -0,0 +1,163 @@
+use crate::helpers::bytes::read_array;
+
+/// Zero-copy StatsState — 49 bytes total.
+/// Anchor account discriminator: SHA256("account:StatsState")[0..8]
+///
+/// Global PDA `[STATS_SEED]` created by `initialize_stats`: running token
+/// volume counters for dashboards. Transfers and withdrawals do not require
+/// it; when it is passed they add their amount to the matching counter (see
+/// `helpers::stats`).
+pub struct StatsState<'a> {
+    data: &'a [u8],
+}
+
+pub struct StatsStateMut<'a> {
+    data: &'a mut [u8],
+}
+
+pub const STATS_STATE_DISCRIMINATOR: [u8; 8] = [64, 176, 156, 124, 97, 167, 184, 119];
+pub const STATS_STATE_SIZE: usize = 49;
+
+const OFF_DISC: usize = 0;
+const OFF_POOL_OUTFLOW: usize = 8;
+const OFF_POOL_INFLOW: usize = 16;
+const OFF_USER_TO_COMPANY: usize = 24;
+const OFF_COMPANY_TO_USER: usize = 32;
+const OFF_WITHDRAWN: usize = 40;
+const OFF_BUMP: usize = 48;
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(*read_array::<8>(data, offset))
+}
+
+impl<'a> StatsState<'a> {
+    pub const SIZE: usize = STATS_STATE_SIZE;
+    pub const DISCRIMINATOR: [u8; 8] = STATS_STATE_DISCRIMINATOR;
+
+    pub fn from_slice(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn discriminator(&self) -> &[u8; 8] {
+        read_array::<8>(self.data, OFF_DISC)
+    }
+    /// Tokens sent out of the distribution pool (`transfer_from_pool`).
+    pub fn total_pool_outflow(&self) -> u64 {
+        read_u64(self.data, OFF_POOL_OUTFLOW)
+    }
+    /// Tokens returned to the distribution pool (`return_to_pool`, `return_user_to_pool`).
+    pub fn total_pool_inflow(&self) -> u64 {
+        read_u64(self.data, OFF_POOL_INFLOW)
+    }
+    /// Tokens moved from users to companies (`transfer_user_to_company`).
+    pub fn total_user_to_company(&self) -> u64 {
+        read_u64(self.data, OFF_USER_TO_COMPANY)
+    }
+    /// Tokens moved from companies to users (`transfer_company_to_user`).
+    pub fn total_company_to_user(&self) -> u64 {
+        read_u64(self.data, OFF_COMPANY_TO_USER)
+    }
+    /// Tokens withdrawn to external wallets, fees included.
+    pub fn total_withdrawn(&self) -> u64 {
+        read_u64(self.data, OFF_WITHDRAWN)
+    }
+    pub fn bump(&self) -> u8 {
+        self.data[OFF_BUMP]
+    }
+}
+
+impl<'a> StatsStateMut<'a> {
+    pub fn from_slice(data: &'a mut [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn set_discriminator(&mut self, disc: &[u8; 8]) {
+        self.data[OFF_DISC..OFF_DISC + 8].copy_from_slice(disc);
+    }
+    pub fn set_bump(&mut self, val: u8) {
+        self.data[OFF_BUMP] = val;
+    }
+
+    fn add(&mut self, offset: usize, amount: u64) {
+        let total = read_u64(self.data, offset).saturating_add(amount);
+        self.data[offset..offset + 8].copy_from_slice(&total.to_le_bytes());
+    }
+
+    /// Adds `amount` to `total_pool_outflow`, saturating at `u64::MAX`.
+    pub fn record_pool_outflow(&mut self, amount: u64) {
+        self.add(OFF_POOL_OUTFLOW, amount);
+    }
+    /// Adds `amount` to `total_pool_inflow`, saturating at `u64::MAX`.
+    pub fn record_pool_inflow(&mut self, amount: u64) {
+        self.add(OFF_POOL_INFLOW, amount);
+    }
+    /// Adds `amount` to `total_user_to_company`, saturating at `u64::MAX`.
+    pub fn record_user_to_company(&mut self, amount: u64) {
+        self.add(OFF_USER_TO_COMPANY, amount);
+    }
+    /// Adds `amount` to `total_company_to_user`, saturating at `u64::MAX`.
+    pub fn record_company_to_user(&mut self, amount: u64) {
+        self.add(OFF_COMPANY_TO_USER, amount);
+    }
+    /// Adds `amount` to `total_withdrawn`, saturating at `u64::MAX`.
+    pub fn record_withdrawn(&mut self, amount: u64) {
+        self.add(OFF_WITHDRAWN, amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_state_size() {
+        assert_eq!(STATS_STATE_SIZE, OFF_BUMP + 1);
+    }
+
+    #[test]
+    fn test_stats_state_discriminator_matches_anchor() {
+        use sha2::{Sha256, Digest};
+        let hash = Sha256::digest(b"account:StatsState");
+        let expected: [u8; 8] = hash[0..8].try_into().unwrap();
+        assert_eq!(STATS_STATE_DISCRIMINATOR, expected);
+    }
+
+    #[test]
+    fn test_layout() {
+        let mut buf = [0u8; STATS_STATE_SIZE];
+        let mut state = StatsStateMut::from_slice(&mut buf);
+        state.set_discriminator(&STATS_STATE_DISCRIMINATOR);
+        state.record_pool_outflow(0x0101_0101_0101_0101);
+        state.record_pool_inflow(0x0202_0202_0202_0202);
+        state.record_user_to_company(0x0303_0303_0303_0303);
+        state.record_company_to_user(0x0404_0404_0404_0404);
+        state.record_withdrawn(0x0505_0505_0505_0505);
+        state.set_bump(0xFE);
+
+        assert_eq!(&buf[0..8], &STATS_STATE_DISCRIMINATOR);
+        assert_eq!(&buf[8..16], &[0x01; 8]);
+        assert_eq!(&buf[16..24], &[0x02; 8]);
+        assert_eq!(&buf[24..32], &[0x03; 8]);
+        assert_eq!(&buf[32..40], &[0x04; 8]);
+        assert_eq!(&buf[40..48], &[0x05; 8]);
+        assert_eq!(buf[48], 0xFE);
+    }
+
+    #[test]
+    fn test_counters_accumulate_and_saturate() {
+        let mut buf = [0u8; STATS_STATE_SIZE];
+        let mut state = StatsStateMut::from_slice(&mut buf);
+        state.record_pool_outflow(1_000);
+        state.record_pool_outflow(250);
+        state.record_user_to_company(u64::MAX);
+        state.record_user_to_company(1);
+        state.record_withdrawn(7);
+
+        let read = StatsState::from_slice(&buf);
+        assert_eq!(read.total_pool_outflow(), 1_250);
+        assert_eq!(read.total_user_to_company(), u64::MAX);
+        assert_eq!(read.total_withdrawn(), 7);
+        assert_eq!(read.total_pool_inflow(), 0);
+        assert_eq!(read.total_company_to_user(), 0);
+    }
+}
@@ -1,7 +1,25 @@
-use crate::constants::SECONDS_PER_DAY;
+use pinocchio::error::ProgramError;
+use pinocchio::{AccountView, Address};
 
-/// Zero-copy TokenState — 363 bytes total (8 discriminator + 355 data).
+use crate::constants::{SECONDS_PER_DAY, TOKEN_DECIMALS};
+#[cfg(feature = "custom-program-id")]
+use crate::constants::TOKEN_STATE_SEED;
+#[cfg(not(feature = "custom-program-id"))]
+use crate::constants::{TOKEN_STATE_BUMP, TOKEN_STATE_PDA};
+use crate::error::ZupyTokenError;
+use crate::helpers::bytes::read_array;
+
+/// Zero-copy TokenState — 363 bytes total (8 discriminator + 355 data), plus an
+/// 8-byte `max_supply` tail, the `metadata_initialized` / `metadata_frozen`
+/// flags, the `coupon_collection` mint and the `strict_registration` flag on
+/// accounts carrying those fields (layout v1). Layout v2 is `TOKEN_STATE_V2_SIZE`
+/// bytes: the v1 tails, a layout `version` byte at 406 and zeroed reserved space
+/// for new fields. Readers accept both; `migrate_token_state` upgrades v1.
 /// Anchor account discriminator: SHA256("account:TokenState")[0..8]
+///
+/// Handlers obtain it through `try_from_account` (usually via
+/// `validate_token_state_base`), which checks the account before any read.
+#[derive(Debug, Clone, Copy)]
 pub struct TokenState<'a> {
     data: &'a [u8],
 }
@@ -14,6 +32,25 @@ pub struct TokenStateMut<'a> {
 // Anchor account discriminator: SHA256("account:TokenState")[0..8]
 pub const TOKEN_STATE_DISCRIMINATOR: [u8; 8] = [218, 112, 6, 149, 55, 186, 168, 163];
 pub const TOKEN_STATE_SIZE: usize = 363;
+/// Size of a TokenState carrying `max_supply` (363..371). Accounts created at
+/// `TOKEN_STATE_SIZE` still validate and read `max_supply` as 0 (uncapped).
+pub const TOKEN_STATE_SIZE_WITH_MAX_SUPPLY: usize = 371;
+/// Size of a TokenState carrying the metadata flags (`metadata_initialized` at
+/// 371, `metadata_frozen` at 372). Shorter accounts do not track metadata state.
+pub const TOKEN_STATE_SIZE_WITH_METADATA_FLAGS: usize = 373;
+/// Size of a TokenState carrying `coupon_collection` (373..405), grown by
+/// `set_coupon_collection`. Shorter accounts have no coupon collection.
+pub const TOKEN_STATE_SIZE_WITH_COUPON_COLLECTION: usize = 405;
+/// Size of a TokenState carrying `strict_registration` (405), grown by
+/// `set_strict_registration`. Shorter accounts accept unregistered users.
+pub const TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION: usize = 406;
+/// Size of a v2 TokenState: `version` at 406, the last-operation timestamps
+/// (407..431) and reserved 431..512 (zeroed). Created by `initialize_token`,
+/// or grown from v1 by `migrate_token_state`.
+pub const TOKEN_STATE_V2_SIZE: usize = 512;
+/// Layout version of every TokenState shorter than `TOKEN_STATE_V2_SIZE`.
+pub const TOKEN_STATE_VERSION_1: u8 = 1;
+pub const TOKEN_STATE_VERSION_2: u8 = 2;
 
 // Byte offsets
 const OFF_DISC: usize = 0;
@@ -32,18 +69,72 @@ const OFF_DAILY_AUTO_LIMIT: usize = 274;
 const OFF_DAILY_MINTED: usize = 282;
 const OFF_LAST_RESET_TS: usize = 290;
 const OFF_PAUSED: usize = 298;
-// OFF_RESERVED: 299..363 (64 bytes)
+const OFF_MIN_SPLIT_BPS: usize = 299;
+const OFF_MAX_SPLIT_BPS: usize = 301;
+const OFF_WITHDRAW_FEE_BPS: usize = 303;
+const OFF_WITHDRAW_APPROVAL_THRESHOLD: usize = 305;
+const OFF_LAST_TRANSFER_NONCE: usize = 313;
+const OFF_V1_SUNSET_TS: usize = 321;
+const OFF_V1_USER_RETURNED: usize = 329;
+const OFF_V1_COMPANY_RETURNED: usize = 337;
+const OFF_TOTAL_BURNED: usize = 345;
+const OFF_TOTAL_MINTED: usize = 353;
+// decimals: Option<u8> (Borsh) — tag at 361, value at 362. Consumes the former
+// 2-byte reserved tail; older accounts have tag 0 and read `TOKEN_DECIMALS`.
+const OFF_DECIMALS_TAG: usize = 361;
+const OFF_DECIMALS: usize = 362;
+const OFF_MAX_SUPPLY: usize = 363;
+const OFF_METADATA_INITIALIZED: usize = 371;
+const OFF_METADATA_FROZEN: usize = 372;
+const OFF_COUPON_COLLECTION: usize = 373;
+const OFF_STRICT_REGISTRATION: usize = 405;
+const OFF_VERSION: usize = 406;
+const OFF_LAST_MINT_TS: usize = 407;
+const OFF_LAST_TRANSFER_TS: usize = 415;
+const OFF_LAST_WITHDRAW_TS: usize = 423;
+
+/// Instruction family whose last successful run is stamped into a v2
+/// TokenState (see `helpers::heartbeat`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LastOp {
+    /// `mint_tokens` / `mint_to_pool` → `last_mint_ts`.
+    Mint,
+    /// The transfer family → `last_transfer_ts`.
+    Transfer,
+    /// External withdrawals → `last_withdraw_ts`.
+    Withdraw,
+}
+
+impl LastOp {
+    const fn offset(self) -> usize {
+        match self {
+            LastOp::Mint => OFF_LAST_MINT_TS,
+            LastOp::Transfer => OFF_LAST_TRANSFER_TS,
+            LastOp::Withdraw => OFF_LAST_WITHDRAW_TS,
+        }
+    }
+}
 
 fn read_pubkey(data: &[u8], offset: usize) -> &[u8; 32] {
-    data[offset..offset + 32].try_into().unwrap()
+    read_array::<32>(data, offset)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(*read_array::<2>(data, offset))
 }
 
 fn read_u64(data: &[u8], offset: usize) -> u64 {
-    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+    u64::from_le_bytes(*read_array::<8>(data, offset))
+}
+
+/// Daily mint window: resets once more than `SECONDS_PER_DAY` have passed since
+/// `last_reset_timestamp` (rolling, not calendar days).
+fn daily_window_elapsed(last_reset: i64, now: i64) -> bool {
+    now.saturating_sub(last_reset) > SECONDS_PER_DAY
 }
 
 fn read_i64(data: &[u8], offset: usize) -> i64 {
-    i64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+    i64::from_le_bytes(*read_array::<8>(data, offset))
 }
 
 fn read_bool(data: &[u8], offset: usize) -> bool {
@@ -54,12 +145,56 @@ impl<'a> TokenState<'a> {
     pub const SIZE: usize = TOKEN_STATE_SIZE;
     pub const DISCRIMINATOR: [u8; 8] = TOKEN_STATE_DISCRIMINATOR;
 
-    pub fn from_slice(data: &'a [u8]) -> Self {
+    /// Unchecked view over raw bytes; `data` must hold at least
+    /// `TOKEN_STATE_SIZE` bytes. Use `try_from_account` for accounts.
+    pub(crate) fn from_slice(data: &'a [u8]) -> Self {
         Self { data }
     }
 
+    /// Checked view of `account` as this program's TokenState PDA:
+    /// 1. owned by `program_id` → `InvalidAuthority` (Spec §7.1)
+    /// 2. data length >= `TOKEN_STATE_SIZE` → `InvalidAccountData` (Spec §7.7)
+    /// 3. Anchor discriminator → `InvalidAccountDiscriminator`
+    /// 4. stored bump == `TOKEN_STATE_BUMP` and address == `TOKEN_STATE_PDA` → `InvalidPDA`
+    ///    (Spec §7.2); with `custom-program-id`, re-derived from the stored bump instead
+    ///
+    /// Does NOT check `initialized`; `validate_token_state_base` adds that.
+    pub fn try_from_account(
+        account: &'a AccountView,
+        program_id: &Address,
+    ) -> Result<Self, ProgramError> {
+        if !account.owned_by(program_id) {
+            return Err(ZupyTokenError::InvalidAuthority.into());
+        }
+        if account.data_len() < TOKEN_STATE_SIZE {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // Zero-copy read (safe: single-threaded Solana runtime)
+        let state = Self::from_slice(unsafe { account.borrow_unchecked() });
+        if state.discriminator() != &TOKEN_STATE_DISCRIMINATOR {
+            return Err(ZupyTokenError::InvalidAccountDiscriminator.into());
+        }
+
+        #[cfg(not(feature = "custom-program-id"))]
+        if state.bump() != TOKEN_STATE_BUMP || account.address() != &Address::from(TOKEN_STATE_PDA) {
+            return Err(ZupyTokenError::InvalidPDA.into());
+        }
+        #[cfg(feature = "custom-program-id")]
+        {
+            let expected_pda =
+                Address::create_program_address(&[TOKEN_STATE_SEED, &[state.bump()]], program_id)
+                    .map_err(|_| ZupyTokenError::InvalidPDA)?;
+            if account.address() != &expected_pda {
+                return Err(ZupyTokenError::InvalidPDA.into());
+            }
+        }
+
+        Ok(state)
+    }
+
     pub fn discriminator(&self) -> &[u8; 8] {
-        self.data[OFF_DISC..OFF_DISC + 8].try_into().unwrap()
+        read_array::<8>(self.data, OFF_DISC)
     }
     pub fn treasury(&self) -> &[u8; 32] {
         read_pubkey(self.data, OFF_TREASURY)
@@ -106,6 +241,118 @@ impl<'a> TokenState<'a> {
     pub fn paused(&self) -> bool {
         read_bool(self.data, OFF_PAUSED)
     }
+    pub fn min_split_bps(&self) -> u16 {
+        read_u16(self.data, OFF_MIN_SPLIT_BPS)
+    }
+    pub fn max_split_bps(&self) -> u16 {
+        read_u16(self.data, OFF_MAX_SPLIT_BPS)
+    }
+    /// Fee on external withdrawals, in basis points (0 = no fee).
+    pub fn withdraw_fee_bps(&self) -> u16 {
+        read_u16(self.data, OFF_WITHDRAW_FEE_BPS)
+    }
+    /// User withdrawals at or above this amount need `request_withdrawal` +
+    /// `approve_withdrawal` (0 = direct path only).
+    pub fn withdraw_approval_threshold(&self) -> u64 {
+        read_u64(self.data, OFF_WITHDRAW_APPROVAL_THRESHOLD)
+    }
+    /// Highest replay-protection nonce accepted on a transfer (0 = none yet).
+    pub fn last_transfer_nonce(&self) -> u64 {
+        read_u64(self.data, OFF_LAST_TRANSFER_NONCE)
+    }
+    /// Unix timestamp from which the `_v1` passthroughs are retired (0 = no sunset).
+    pub fn v1_sunset_ts(&self) -> i64 {
+        read_i64(self.data, OFF_V1_SUNSET_TS)
+    }
+    /// Total returned to the pool from user balances via `return_user_to_pool_v1`.
+    pub fn v1_user_returned(&self) -> u64 {
+        read_u64(self.data, OFF_V1_USER_RETURNED)
+    }
+    /// Total returned to the pool from company balances via `return_to_pool_v1`.
+    pub fn v1_company_returned(&self) -> u64 {
+        read_u64(self.data, OFF_V1_COMPANY_RETURNED)
+    }
+    /// Total destroyed by `burn_tokens` and `burn_from_company_pda`.
+    pub fn total_burned(&self) -> u64 {
+        read_u64(self.data, OFF_TOTAL_BURNED)
+    }
+    /// Lifetime issuance via `mint_tokens` and `mint_to_pool` (never reset).
+    pub fn total_minted(&self) -> u64 {
+        read_u64(self.data, OFF_TOTAL_MINTED)
+    }
+    /// Mint decimals fixed at `initialize_token`; `TOKEN_DECIMALS` when unset.
+    pub fn decimals(&self) -> u8 {
+        if self.data[OFF_DECIMALS_TAG] == 0 {
+            return TOKEN_DECIMALS;
+        }
+        self.data[OFF_DECIMALS]
+    }
+    /// Hard cap on mint supply, fixed at `initialize_token` (0 = uncapped,
+    /// including accounts created before the cap existed).
+    pub fn max_supply(&self) -> u64 {
+        if self.data.len() < TOKEN_STATE_SIZE_WITH_MAX_SUPPLY {
+            return 0;
+        }
+        read_u64(self.data, OFF_MAX_SUPPLY)
+    }
+    /// Set once `initialize_metadata` succeeds. `None` on accounts too short to
+    /// carry the flag (created before it existed).
+    pub fn metadata_initialized(&self) -> Option<bool> {
+        if self.data.len() < TOKEN_STATE_SIZE_WITH_METADATA_FLAGS {
+            return None;
+        }
+        Some(read_bool(self.data, OFF_METADATA_INITIALIZED))
+    }
+    /// Set by `freeze_metadata`; the metadata update authority is gone for good.
+    /// `None` on accounts too short to carry the flag.
+    pub fn metadata_frozen(&self) -> Option<bool> {
+        if self.data.len() < TOKEN_STATE_SIZE_WITH_METADATA_FLAGS {
+            return None;
+        }
+        Some(read_bool(self.data, OFF_METADATA_FROZEN))
+    }
+    /// Verified collection mint for coupon cNFTs, set by `set_coupon_collection`.
+    /// `None` when unset (all zero) or on accounts too short to carry it.
+    pub fn coupon_collection(&self) -> Option<&[u8; 32]> {
+        if self.data.len() < TOKEN_STATE_SIZE_WITH_COUPON_COLLECTION {
+            return None;
+        }
+        let collection = read_pubkey(self.data, OFF_COUPON_COLLECTION);
+        if collection == &[0u8; 32] {
+            return None;
+        }
+        Some(collection)
+    }
+    /// Whether pool and company transfers require the recipient's UserState,
+    /// set by `set_strict_registration`. `false` on accounts too short to carry it.
+    pub fn strict_registration(&self) -> bool {
+        self.data.len() >= TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION
+            && read_bool(self.data, OFF_STRICT_REGISTRATION)
+    }
+    /// Layout version: `TOKEN_STATE_VERSION_1` on accounts shorter than
+    /// `TOKEN_STATE_V2_SIZE`, otherwise the stored byte.
+    pub fn version(&self) -> u8 {
+        if self.data.len() < TOKEN_STATE_V2_SIZE {
+            return TOKEN_STATE_VERSION_1;
+        }
+        self.data[OFF_VERSION]
+    }
+    /// Unix timestamp of the last successful `op` (0 = never, or a v1 account).
+    pub fn last_op_ts(&self, op: LastOp) -> i64 {
+        if self.data.len() < TOKEN_STATE_V2_SIZE {
+            return 0;
+        }
+        read_i64(self.data, op.offset())
+    }
+    pub fn last_mint_ts(&self) -> i64 {
+        self.last_op_ts(LastOp::Mint)
+    }
+    pub fn last_transfer_ts(&self) -> i64 {
+        self.last_op_ts(LastOp::Transfer)
+    }
+    pub fn last_withdraw_ts(&self) -> i64 {
+        self.last_op_ts(LastOp::Withdraw)
+    }
 
     // Helper methods
     pub fn is_mint_authority(&self, pubkey: &[u8; 32]) -> bool {
@@ -123,6 +370,25 @@ impl<'a> TokenState<'a> {
     pub fn within_daily_limit(&self, amount: u64) -> bool {
         self.daily_minted().saturating_add(amount) <= self.daily_auto_limit()
     }
+    /// `daily_minted` as of `now`: 0 once the daily window has elapsed.
+    pub fn effective_daily_minted(&self, now: i64) -> u64 {
+        if daily_window_elapsed(self.last_reset_timestamp(), now) {
+            0
+        } else {
+            self.daily_minted()
+        }
+    }
+    pub fn within_split_range(&self, split_bps: u16) -> bool {
+        split_bps >= self.min_split_bps() && split_bps <= self.max_split_bps()
+    }
+    pub fn within_max_supply(&self, current_supply: u64, amount: u64) -> bool {
+        let cap = self.max_supply();
+        cap == 0 || current_supply.checked_add(amount).is_some_and(|s| s <= cap)
+    }
+    pub fn v1_sunset_reached(&self, now: i64) -> bool {
+        let sunset = self.v1_sunset_ts();
+        sunset != 0 && now >= sunset
+    }
 }
 
 impl<'a> TokenStateMut<'a> {
@@ -130,9 +396,19 @@ impl<'a> TokenStateMut<'a> {
         Self { data }
     }
 
+    /// Mutable view of an already-validated TokenState `account`, or `None`
+    /// when it was passed read-only. For best-effort counters on instructions
+    /// whose clients may pass token_state read-only: the update is skipped.
+    pub fn if_writable(account: &'a AccountView) -> Option<Self> {
+        if !account.is_writable() {
+            return None;
+        }
+        Some(Self::from_slice(unsafe { account.borrow_unchecked_mut() }))
+    }
+
     // Read accessors (delegate to immutable)
     pub fn discriminator(&self) -> &[u8; 8] {
-        self.data[OFF_DISC..OFF_DISC + 8].try_into().unwrap()
+        read_array::<8>(self.data, OFF_DISC)
     }
     pub fn daily_minted(&self) -> u64 {
         read_u64(self.data, OFF_DAILY_MINTED)
@@ -143,6 +419,21 @@ impl<'a> TokenStateMut<'a> {
     pub fn bump(&self) -> u8 {
         self.data[OFF_BUMP]
     }
+    pub fn last_transfer_nonce(&self) -> u64 {
+        read_u64(self.data, OFF_LAST_TRANSFER_NONCE)
+    }
+    pub fn v1_user_returned(&self) -> u64 {
+        read_u64(self.data, OFF_V1_USER_RETURNED)
+    }
+    pub fn v1_company_returned(&self) -> u64 {
+        read_u64(self.data, OFF_V1_COMPANY_RETURNED)
+    }
+    pub fn total_burned(&self) -> u64 {
+        read_u64(self.data, OFF_TOTAL_BURNED)
+    }
+    pub fn total_minted(&self) -> u64 {
+        read_u64(self.data, OFF_TOTAL_MINTED)
+    }
 
     // Write accessors
     pub fn set_discriminator(&mut self, disc: &[u8; 8]) {
@@ -197,33 +488,261 @@ impl<'a> TokenStateMut<'a> {
     pub fn set_paused(&mut self, val: bool) {
         self.data[OFF_PAUSED] = val as u8;
     }
+    pub fn set_min_split_bps(&mut self, val: u16) {
+        self.data[OFF_MIN_SPLIT_BPS..OFF_MIN_SPLIT_BPS + 2]
+            .copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_max_split_bps(&mut self, val: u16) {
+        self.data[OFF_MAX_SPLIT_BPS..OFF_MAX_SPLIT_BPS + 2]
+            .copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_withdraw_fee_bps(&mut self, val: u16) {
+        self.data[OFF_WITHDRAW_FEE_BPS..OFF_WITHDRAW_FEE_BPS + 2]
+            .copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_withdraw_approval_threshold(&mut self, val: u64) {
+        self.data[OFF_WITHDRAW_APPROVAL_THRESHOLD..OFF_WITHDRAW_APPROVAL_THRESHOLD + 8]
+            .copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_last_transfer_nonce(&mut self, val: u64) {
+        self.data[OFF_LAST_TRANSFER_NONCE..OFF_LAST_TRANSFER_NONCE + 8]
+            .copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_v1_sunset_ts(&mut self, val: i64) {
+        self.data[OFF_V1_SUNSET_TS..OFF_V1_SUNSET_TS + 8]
+            .copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_v1_user_returned(&mut self, val: u64) {
+        self.data[OFF_V1_USER_RETURNED..OFF_V1_USER_RETURNED + 8]
+            .copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_v1_company_returned(&mut self, val: u64) {
+        self.data[OFF_V1_COMPANY_RETURNED..OFF_V1_COMPANY_RETURNED + 8]
+            .copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_total_burned(&mut self, val: u64) {
+        self.data[OFF_TOTAL_BURNED..OFF_TOTAL_BURNED + 8]
+            .copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_total_minted(&mut self, val: u64) {
+        self.data[OFF_TOTAL_MINTED..OFF_TOTAL_MINTED + 8]
+            .copy_from_slice(&val.to_le_bytes());
+    }
+    /// Only written by `initialize_token`.
+    pub fn set_decimals(&mut self, val: u8) {
+        self.data[OFF_DECIMALS_TAG] = 1;
+        self.data[OFF_DECIMALS] = val;
+    }
+    /// Only written by `initialize_token`; needs a `TOKEN_STATE_SIZE_WITH_MAX_SUPPLY` buffer.
+    pub fn set_max_supply(&mut self, val: u64) {
+        self.data[OFF_MAX_SUPPLY..OFF_MAX_SUPPLY + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    /// Only written by `initialize_metadata`; needs a `TOKEN_STATE_SIZE_WITH_METADATA_FLAGS` buffer.
+    pub fn set_metadata_initialized(&mut self, val: bool) {
+        self.data[OFF_METADATA_INITIALIZED] = val as u8;
+    }
+    /// Only written by `freeze_metadata`; needs a `TOKEN_STATE_SIZE_WITH_METADATA_FLAGS` buffer.
+    pub fn set_metadata_frozen(&mut self, val: bool) {
+        self.data[OFF_METADATA_FROZEN] = val as u8;
+    }
+    /// Only written by `set_coupon_collection`; needs a `TOKEN_STATE_SIZE_WITH_COUPON_COLLECTION` buffer.
+    pub fn set_coupon_collection(&mut self, pubkey: &[u8; 32]) {
+        self.data[OFF_COUPON_COLLECTION..OFF_COUPON_COLLECTION + 32].copy_from_slice(pubkey);
+    }
+    /// Only written by `set_strict_registration`; needs a `TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION` buffer.
+    pub fn set_strict_registration(&mut self, val: bool) {
+        self.data[OFF_STRICT_REGISTRATION] = val as u8;
+    }
+    /// Written by `initialize_token` and `migrate_token_state`; needs a `TOKEN_STATE_V2_SIZE` buffer.
+    pub fn set_version(&mut self, val: u8) {
+        self.data[OFF_VERSION] = val;
+    }
+    /// Stamp `op`'s last-operation timestamp (single 8-byte store); a no-op on
+    /// v1 accounts, which have no room for it.
+    pub fn set_last_op_ts(&mut self, op: LastOp, val: i64) {
+        if self.data.len() < TOKEN_STATE_V2_SIZE {
+            return;
+        }
+        let offset = op.offset();
+        self.data[offset..offset + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    /// Default the bytes from `old_len` on after the account grew: zero them,
+    /// set `metadata_initialized` when the flags are new to the account, and
+    /// stamp `TOKEN_STATE_VERSION_2` once it reaches `TOKEN_STATE_V2_SIZE`.
+    pub fn init_grown_tail(&mut self, old_len: usize, metadata_initialized: bool) {
+        let len = self.data.len();
+        self.data[old_len..].fill(0);
+        if old_len < TOKEN_STATE_SIZE_WITH_METADATA_FLAGS && len >= TOKEN_STATE_SIZE_WITH_METADATA_FLAGS {
+            self.set_metadata_initialized(metadata_initialized);
+        }
+        if len >= TOKEN_STATE_V2_SIZE {
+            self.set_version(TOKEN_STATE_VERSION_2);
+        }
+    }
 
-    /// Reset daily minted if a new day has started.
+    /// Reset daily minted once the daily window has elapsed.
     pub fn maybe_reset_daily(&mut self, current_timestamp: i64) {
-        let current_day = current_timestamp / SECONDS_PER_DAY;
-        let last_day = self.last_reset_timestamp() / SECONDS_PER_DAY;
-        if current_day > last_day {
+        if daily_window_elapsed(self.last_reset_timestamp(), current_timestamp) {
             self.set_daily_minted(0);
             self.set_last_reset_timestamp(current_timestamp);
         }
     }
 
-    /// Record a mint operation (saturating add to daily_minted).
+    /// Record a mint operation (saturating add to daily_minted and total_minted).
     pub fn record_mint(&mut self, amount: u64) {
         let new_total = self.daily_minted().saturating_add(amount);
         self.set_daily_minted(new_total);
+        let lifetime = self.total_minted().saturating_add(amount);
+        self.set_total_minted(lifetime);
+    }
+
+    /// Record a user V1 return. Saturates at `u64::MAX` so accounting never fails
+    /// the transfer.
+    pub fn record_v1_user_returned(&mut self, amount: u64) {
+        let new_total = self.v1_user_returned().saturating_add(amount);
+        self.set_v1_user_returned(new_total);
+    }
+
+    /// Record a company V1 return (saturating, like `record_v1_user_returned`).
+    pub fn record_v1_company_returned(&mut self, amount: u64) {
+        let new_total = self.v1_company_returned().saturating_add(amount);
+        self.set_v1_company_returned(new_total);
+    }
+
+    /// Record a burn (saturating; accounting never fails the burn).
+    pub fn record_burn(&mut self, amount: u64) {
+        let new_total = self.total_burned().saturating_add(amount);
+        self.set_total_burned(new_total);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use core::mem::size_of;
+    use pinocchio::account::{RuntimeAccount, NOT_BORROWED};
+
+    use crate::constants::{PROGRAM_ID, TOKEN_STATE_SEED};
 
     #[test]
     fn test_token_state_size() {
         assert_eq!(TOKEN_STATE_SIZE, 363);
     }
 
+    // ── try_from_account ────────────────────────────────────────────────
+
+    /// RuntimeAccount buffer at the canonical TokenState PDA holding
+    /// `data_len` bytes with the discriminator and canonical bump set.
+    fn make_account_buf(data_len: usize) -> Vec<u64> {
+        let program_id = Address::from(PROGRAM_ID);
+        let (pda, bump) = Address::find_program_address(&[TOKEN_STATE_SEED], &program_id);
+
+        let header_size = size_of::<RuntimeAccount>();
+        let mut buf = vec![0u64; (header_size + data_len).div_ceil(8)];
+        let raw = buf.as_mut_ptr() as *mut RuntimeAccount;
+        unsafe {
+            (*raw).borrow_state = NOT_BORROWED;
+            (*raw).address = pda;
+            (*raw).owner = program_id;
+            (*raw).lamports = 1_000_000;
+            (*raw).data_len = data_len as u64;
+        }
+        let data = data_mut(&mut buf, data_len);
+        if data_len >= TOKEN_STATE_SIZE {
+            let mut state = TokenStateMut::from_slice(data);
+            state.set_discriminator(&TOKEN_STATE_DISCRIMINATOR);
+            state.set_bump(bump);
+        }
+        buf
+    }
+
+    fn data_mut(buf: &mut [u64], data_len: usize) -> &mut [u8] {
+        unsafe {
+            let base = buf.as_mut_ptr() as *mut u8;
+            core::slice::from_raw_parts_mut(base.add(size_of::<RuntimeAccount>()), data_len)
+        }
+    }
+
+    fn view(buf: &mut [u64]) -> AccountView {
+        unsafe { AccountView::new_unchecked(buf.as_mut_ptr() as *mut RuntimeAccount) }
+    }
+
+    fn custom(e: ZupyTokenError) -> ProgramError {
+        ProgramError::Custom(e as u32)
+    }
+
+    #[test]
+    fn test_try_from_account_happy_path() {
+        let program_id = Address::from(PROGRAM_ID);
+        let (_, bump) = Address::find_program_address(&[TOKEN_STATE_SEED], &program_id);
+        for len in [TOKEN_STATE_SIZE, TOKEN_STATE_V2_SIZE] {
+            let mut buf = make_account_buf(len);
+            let account = view(&mut buf);
+            let state = TokenState::try_from_account(&account, &program_id).unwrap();
+            assert_eq!(state.bump(), bump);
+        }
+    }
+
+    #[test]
+    fn test_try_from_account_short_data() {
+        let program_id = Address::from(PROGRAM_ID);
+        let mut buf = make_account_buf(TOKEN_STATE_SIZE - 1);
+        let account = view(&mut buf);
+        assert_eq!(
+            TokenState::try_from_account(&account, &program_id).unwrap_err(),
+            ProgramError::InvalidAccountData
+        );
+    }
+
+    #[test]
+    fn test_try_from_account_wrong_owner() {
+        let mut buf = make_account_buf(TOKEN_STATE_SIZE);
+        let account = view(&mut buf);
+        let other_program = Address::from([99u8; 32]);
+        assert_eq!(
+            TokenState::try_from_account(&account, &other_program).unwrap_err(),
+            custom(ZupyTokenError::InvalidAuthority)
+        );
+    }
+
+    #[test]
+    fn test_try_from_account_wrong_discriminator() {
+        let program_id = Address::from(PROGRAM_ID);
+        let mut buf = make_account_buf(TOKEN_STATE_SIZE);
+        data_mut(&mut buf, TOKEN_STATE_SIZE)[0] ^= 0xFF;
+        let account = view(&mut buf);
+        assert_eq!(
+            TokenState::try_from_account(&account, &program_id).unwrap_err(),
+            custom(ZupyTokenError::InvalidAccountDiscriminator)
+        );
+    }
+
+    #[test]
+    fn test_try_from_account_bad_pda() {
+        let program_id = Address::from(PROGRAM_ID);
+        let mut buf = make_account_buf(TOKEN_STATE_SIZE);
+        let raw = buf.as_mut_ptr() as *mut RuntimeAccount;
+        unsafe { (*raw).address = Address::from([0xCC; 32]); }
+        let account = view(&mut buf);
+        assert_eq!(
+            TokenState::try_from_account(&account, &program_id).unwrap_err(),
+            custom(ZupyTokenError::InvalidPDA)
+        );
+    }
+
+    #[test]
+    fn test_try_from_account_stale_bump() {
+        // Canonical address but a stored bump that no longer matches it
+        let program_id = Address::from(PROGRAM_ID);
+        let mut buf = make_account_buf(TOKEN_STATE_SIZE);
+        TokenStateMut::from_slice(data_mut(&mut buf, TOKEN_STATE_SIZE)).set_bump(253);
+        let account = view(&mut buf);
+        assert_eq!(
+            TokenState::try_from_account(&account, &program_id).unwrap_err(),
+            custom(ZupyTokenError::InvalidPDA)
+        );
+    }
+
     #[test]
     fn test_token_state_discriminator_matches_anchor() {
         use sha2::{Sha256, Digest};
@@ -291,6 +810,55 @@ mod tests {
         assert!(!read.paused());
     }
 
+    /// Each setter writes exactly its own field: a buffer filled with 0xAA
+    /// keeps every byte outside `[offset, offset + len)` after a zero write.
+    #[test]
+    fn test_setters_do_not_touch_adjacent_fields() {
+        type Setter = fn(&mut TokenStateMut);
+        let cases: &[(&str, Setter, usize, usize)] = &[
+            ("discriminator", |s| s.set_discriminator(&[0; 8]), OFF_DISC, 8),
+            ("treasury", |s| s.set_treasury(&[0; 32]), OFF_TREASURY, 32),
+            ("mint_authority", |s| s.set_mint_authority(&[0; 32]), OFF_MINT_AUTH, 32),
+            ("transfer_authority", |s| s.set_transfer_authority(&[0; 32]), OFF_TRANSFER_AUTH, 32),
+            ("pool_ata", |s| s.set_pool_ata(&[0; 32]), OFF_POOL_ATA, 32),
+            ("distribution_pool", |s| s.set_distribution_pool(&[0; 32]), OFF_DIST_POOL, 32),
+            ("incentive_pool", |s| s.set_incentive_pool(&[0; 32]), OFF_INCENTIVE_POOL, 32),
+            ("treasury_ata", |s| s.set_treasury_ata(&[0; 32]), OFF_TREASURY_ATA, 32),
+            ("mint", |s| s.set_mint(&[0; 32]), OFF_MINT, 32),
+            ("initialized", |s| s.set_initialized(false), OFF_INITIALIZED, 1),
+            ("bump", |s| s.set_bump(0), OFF_BUMP, 1),
+            ("per_tx_auto_limit", |s| s.set_per_tx_auto_limit(0), OFF_PER_TX_AUTO_LIMIT, 8),
+            ("daily_auto_limit", |s| s.set_daily_auto_limit(0), OFF_DAILY_AUTO_LIMIT, 8),
+            ("daily_minted", |s| s.set_daily_minted(0), OFF_DAILY_MINTED, 8),
+            ("last_reset_timestamp", |s| s.set_last_reset_timestamp(0), OFF_LAST_RESET_TS, 8),
+            ("paused", |s| s.set_paused(false), OFF_PAUSED, 1),
+            ("min_split_bps", |s| s.set_min_split_bps(0), OFF_MIN_SPLIT_BPS, 2),
+            ("max_split_bps", |s| s.set_max_split_bps(0), OFF_MAX_SPLIT_BPS, 2),
+            ("withdraw_fee_bps", |s| s.set_withdraw_fee_bps(0), OFF_WITHDRAW_FEE_BPS, 2),
+            ("withdraw_approval_threshold", |s| s.set_withdraw_approval_threshold(0), OFF_WITHDRAW_APPROVAL_THRESHOLD, 8),
+            ("last_transfer_nonce", |s| s.set_last_transfer_nonce(0), OFF_LAST_TRANSFER_NONCE, 8),
+            ("v1_sunset_ts", |s| s.set_v1_sunset_ts(0), OFF_V1_SUNSET_TS, 8),
+            ("v1_user_returned", |s| s.set_v1_user_returned(0), OFF_V1_USER_RETURNED, 8),
+            ("v1_company_returned", |s| s.set_v1_company_returned(0), OFF_V1_COMPANY_RETURNED, 8),
+            ("total_burned", |s| s.set_total_burned(0), OFF_TOTAL_BURNED, 8),
+            ("total_minted", |s| s.set_total_minted(0), OFF_TOTAL_MINTED, 8),
+            ("max_supply", |s| s.set_max_supply(0), OFF_MAX_SUPPLY, 8),
+            ("metadata_initialized", |s| s.set_metadata_initialized(false), OFF_METADATA_INITIALIZED, 1),
+            ("metadata_frozen", |s| s.set_metadata_frozen(false), OFF_METADATA_FROZEN, 1),
+            ("coupon_collection", |s| s.set_coupon_collection(&[0; 32]), OFF_COUPON_COLLECTION, 32),
+            ("strict_registration", |s| s.set_strict_registration(false), OFF_STRICT_REGISTRATION, 1),
+            ("version", |s| s.set_version(0), OFF_VERSION, 1),
+        ];
+        for &(name, set, offset, len) in cases {
+            let mut buf = [0xAAu8; TOKEN_STATE_V2_SIZE];
+            set(&mut TokenStateMut::from_slice(&mut buf));
+            for (i, &byte) in buf.iter().enumerate() {
+                let expected = if (offset..offset + len).contains(&i) { 0 } else { 0xAA };
+                assert_eq!(byte, expected, "set_{} wrote byte {}", name, i);
+            }
+        }
+    }
+
     #[test]
     fn test_helper_methods() {
         let mut buf = [0u8; TOKEN_STATE_SIZE];
@@ -318,6 +886,153 @@ mod tests {
         assert!(!read.within_daily_limit(3001));
     }
 
+    #[test]
+    fn test_split_bps_range() {
+        let mut buf = [0u8; TOKEN_STATE_SIZE];
+        let mut state = TokenStateMut::from_slice(&mut buf);
+
+        state.set_paused(true);
+        state.set_min_split_bps(500);
+        state.set_max_split_bps(2_000);
+
+        let read = TokenState::from_slice(&buf);
+        assert_eq!(read.min_split_bps(), 500);
+        assert_eq!(read.max_split_bps(), 2_000);
+        assert!(read.paused(), "split bps must not overlap paused flag");
+        assert!(read.within_split_range(500));
+        assert!(read.within_split_range(2_000));
+        assert!(!read.within_split_range(499));
+        assert!(!read.within_split_range(2_001));
+    }
+
+    #[test]
+    fn test_withdraw_fee_bps_round_trip() {
+        let mut buf = [0u8; TOKEN_STATE_SIZE];
+        let mut state = TokenStateMut::from_slice(&mut buf);
+
+        state.set_max_split_bps(u16::MAX);
+        state.set_withdraw_fee_bps(250);
+
+        let read = TokenState::from_slice(&buf);
+        assert_eq!(read.withdraw_fee_bps(), 250);
+        assert_eq!(read.max_split_bps(), u16::MAX, "fee bps must not overlap max_split_bps");
+        assert!(buf[OFF_WITHDRAW_FEE_BPS + 2..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_withdraw_approval_threshold_round_trip() {
+        let mut buf = [0u8; TOKEN_STATE_SIZE];
+        let mut state = TokenStateMut::from_slice(&mut buf);
+
+        state.set_withdraw_fee_bps(u16::MAX);
+        state.set_withdraw_approval_threshold(50_000_000_000);
+
+        let read = TokenState::from_slice(&buf);
+        assert_eq!(read.withdraw_approval_threshold(), 50_000_000_000);
+        assert_eq!(read.withdraw_fee_bps(), u16::MAX, "threshold must not overlap withdraw_fee_bps");
+        assert!(buf[OFF_WITHDRAW_APPROVAL_THRESHOLD + 8..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_last_transfer_nonce_round_trip() {
+        let mut buf = [0u8; TOKEN_STATE_SIZE];
+        let mut state = TokenStateMut::from_slice(&mut buf);
+
+        state.set_withdraw_approval_threshold(u64::MAX);
+        state.set_last_transfer_nonce(7);
+
+        let read = TokenState::from_slice(&buf);
+        assert_eq!(read.last_transfer_nonce(), 7);
+        assert_eq!(read.withdraw_approval_threshold(), u64::MAX, "nonce must not overlap threshold");
+        assert!(buf[OFF_LAST_TRANSFER_NONCE + 8..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_v1_sunset() {
+        let mut buf = [0u8; TOKEN_STATE_SIZE];
+        let mut state = TokenStateMut::from_slice(&mut buf);
+
+        state.set_last_transfer_nonce(u64::MAX);
+        assert!(!TokenState::from_slice(&buf).v1_sunset_reached(i64::MAX), "0 = no sunset");
+
+        let mut state = TokenStateMut::from_slice(&mut buf);
+        state.set_v1_sunset_ts(1_800_000_000);
+        let read = TokenState::from_slice(&buf);
+        assert_eq!(read.v1_sunset_ts(), 1_800_000_000);
+        assert_eq!(read.last_transfer_nonce(), u64::MAX, "sunset must not overlap nonce");
+        assert!(!read.v1_sunset_reached(1_799_999_999));
+        assert!(read.v1_sunset_reached(1_800_000_000));
+        assert!(buf[OFF_V1_SUNSET_TS + 8..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_v1_returned_counters_layout() {
+        let mut buf = [0u8; TOKEN_STATE_SIZE];
+        let mut state = TokenStateMut::from_slice(&mut buf);
+
+        state.set_v1_sunset_ts(-1);
+        state.set_v1_user_returned(0x0101_0101_0101_0101);
+        state.set_v1_company_returned(0x0202_0202_0202_0202);
+
+        let read = TokenState::from_slice(&buf);
+        assert_eq!(read.v1_sunset_ts(), -1, "counters must not overlap sunset");
+        assert_eq!(read.v1_user_returned(), 0x0101_0101_0101_0101);
+        assert_eq!(read.v1_company_returned(), 0x0202_0202_0202_0202);
+        assert_eq!(&buf[329..337], &[1u8; 8]);
+        assert_eq!(&buf[337..345], &[2u8; 8]);
+        assert!(buf[OFF_V1_COMPANY_RETURNED + 8..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_total_burned_layout() {
+        let mut buf = [0u8; TOKEN_STATE_SIZE];
+        let mut state = TokenStateMut::from_slice(&mut buf);
+
+        state.set_v1_company_returned(u64::MAX);
+        state.set_total_burned(0x0303_0303_0303_0303);
+
+        let read = TokenState::from_slice(&buf);
+        assert_eq!(OFF_TOTAL_BURNED, 345);
+        assert_eq!(read.total_burned(), 0x0303_0303_0303_0303);
+        assert_eq!(read.v1_company_returned(), u64::MAX, "total_burned must not overlap");
+        assert_eq!(&buf[345..353], &[3u8; 8]);
+        assert!(buf[353..].iter().all(|b| *b == 0), "reserved tail untouched");
+    }
+
+    #[test]
+    fn test_record_burn_saturates() {
+        let mut buf = [0u8; TOKEN_STATE_SIZE];
+        let mut state = TokenStateMut::from_slice(&mut buf);
+
+        state.record_burn(500);
+        state.record_burn(250);
+        assert_eq!(state.total_burned(), 750);
+
+        state.set_total_burned(u64::MAX - 1);
+        state.record_burn(10);
+        assert_eq!(state.total_burned(), u64::MAX);
+    }
+
+    #[test]
+    fn test_record_v1_returned() {
+        let mut buf = [0u8; TOKEN_STATE_SIZE];
+        let mut state = TokenStateMut::from_slice(&mut buf);
+
+        state.record_v1_user_returned(100);
+        state.record_v1_user_returned(50);
+        state.record_v1_company_returned(7);
+        assert_eq!(state.v1_user_returned(), 150);
+        assert_eq!(state.v1_company_returned(), 7);
+
+        // Saturates instead of failing at max
+        state.set_v1_company_returned(u64::MAX - 10);
+        state.record_v1_company_returned(20);
+        assert_eq!(state.v1_company_returned(), u64::MAX);
+        state.record_v1_company_returned(1);
+        assert_eq!(state.v1_company_returned(), u64::MAX);
+        assert_eq!(state.v1_user_returned(), 150);
+    }
+
     #[test]
     fn test_token_state_mut_read_accessors() {
         let mut buf = [0u8; TOKEN_STATE_SIZE];
@@ -353,6 +1068,28 @@ mod tests {
         assert_eq!(state.daily_minted(), 0);
     }
 
+    #[test]
+    fn test_daily_window_is_rolling() {
+        let mut buf = [0u8; TOKEN_STATE_SIZE];
+        let mut state = TokenStateMut::from_slice(&mut buf);
+        let last = 86_400 + 80_000; // late on day 1
+        state.set_daily_minted(700);
+        state.set_last_reset_timestamp(last);
+
+        // New calendar day, but the window has not elapsed
+        state.maybe_reset_daily(86_400 * 2 + 10);
+        assert_eq!(state.daily_minted(), 700);
+
+        // Exactly 24h later: still inside the window
+        assert_eq!(TokenState::from_slice(&buf).effective_daily_minted(last + 86_400), 700);
+        assert_eq!(TokenState::from_slice(&buf).effective_daily_minted(last + 86_401), 0);
+
+        let mut state = TokenStateMut::from_slice(&mut buf);
+        state.maybe_reset_daily(last + 86_401);
+        assert_eq!(state.daily_minted(), 0);
+        assert_eq!(state.last_reset_timestamp(), last + 86_401);
+    }
+
     #[test]
     fn test_record_mint() {
         let mut buf = [0u8; TOKEN_STATE_SIZE];
@@ -366,5 +1103,320 @@ mod tests {
         state.set_daily_minted(u64::MAX - 10);
         state.record_mint(20);
         assert_eq!(state.daily_minted(), u64::MAX);
+        assert_eq!(state.total_minted(), 70);
+
+        state.set_total_minted(u64::MAX - 1);
+        state.record_mint(5);
+        assert_eq!(state.total_minted(), u64::MAX);
+    }
+
+    #[test]
+    fn test_max_supply_tail() {
+        let mut buf = [0u8; TOKEN_STATE_SIZE_WITH_MAX_SUPPLY];
+        TokenStateMut::from_slice(&mut buf).set_max_supply(1_000);
+        assert_eq!(&buf[363..371], &1_000u64.to_le_bytes());
+        assert!(buf[..TOKEN_STATE_SIZE].iter().all(|b| *b == 0), "tail only");
+
+        let read = TokenState::from_slice(&buf);
+        assert_eq!(read.max_supply(), 1_000);
+        assert!(read.within_max_supply(600, 400), "exactly to the cap");
+        assert!(!read.within_max_supply(600, 401));
+        assert!(!read.within_max_supply(u64::MAX, 1));
+
+        // Legacy 363-byte account: uncapped
+        let legacy = TokenState::from_slice(&buf[..TOKEN_STATE_SIZE]);
+        assert_eq!(legacy.max_supply(), 0);
+        assert!(legacy.within_max_supply(u64::MAX - 1, 1));
+
+        // Explicit 0 on a new account: uncapped
+        TokenStateMut::from_slice(&mut buf).set_max_supply(0);
+        assert!(TokenState::from_slice(&buf).within_max_supply(u64::MAX, 0));
+    }
+
+    #[test]
+    fn test_metadata_initialized_flag() {
+        let mut buf = [0u8; TOKEN_STATE_SIZE_WITH_METADATA_FLAGS];
+        assert_eq!(TokenState::from_slice(&buf).metadata_initialized(), Some(false));
+
+        let mut state = TokenStateMut::from_slice(&mut buf);
+        state.set_max_supply(u64::MAX);
+        state.set_metadata_initialized(true);
+        assert_eq!(buf[371], 1);
+
+        let read = TokenState::from_slice(&buf);
+        assert_eq!(read.metadata_initialized(), Some(true));
+        assert_eq!(read.metadata_frozen(), Some(false));
+        assert_eq!(read.max_supply(), u64::MAX, "flag must not overlap max_supply");
+        assert!(buf[..OFF_MAX_SUPPLY].iter().all(|b| *b == 0));
+
+        // Legacy accounts do not track the flag
+        assert_eq!(TokenState::from_slice(&buf[..TOKEN_STATE_SIZE]).metadata_initialized(), None);
+        assert_eq!(
+            TokenState::from_slice(&buf[..TOKEN_STATE_SIZE_WITH_MAX_SUPPLY]).metadata_initialized(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_metadata_frozen_flag() {
+        let mut buf = [0u8; TOKEN_STATE_SIZE_WITH_METADATA_FLAGS];
+        TokenStateMut::from_slice(&mut buf).set_metadata_frozen(true);
+        assert_eq!(buf[372], 1);
+        assert!(buf[..372].iter().all(|b| *b == 0), "frozen flag only");
+
+        let read = TokenState::from_slice(&buf);
+        assert_eq!(read.metadata_frozen(), Some(true));
+        assert_eq!(read.metadata_initialized(), Some(false), "flags must not overlap");
+        assert_eq!(TokenState::from_slice(&buf[..372]).metadata_frozen(), None);
+    }
+
+    #[test]
+    fn test_coupon_collection_tail() {
+        let mut buf = [0u8; TOKEN_STATE_SIZE_WITH_COUPON_COLLECTION];
+        assert_eq!(TokenState::from_slice(&buf).coupon_collection(), None, "unset");
+
+        TokenStateMut::from_slice(&mut buf).set_coupon_collection(&[7u8; 32]);
+        assert_eq!(&buf[373..405], &[7u8; 32]);
+        assert!(buf[..TOKEN_STATE_SIZE_WITH_METADATA_FLAGS].iter().all(|b| *b == 0), "tail only");
+        assert_eq!(TokenState::from_slice(&buf).coupon_collection(), Some(&[7u8; 32]));
+
+        // Account without the tail: no collection
+        let legacy = TokenState::from_slice(&buf[..TOKEN_STATE_SIZE_WITH_METADATA_FLAGS]);
+        assert_eq!(legacy.coupon_collection(), None);
+    }
+
+    #[test]
+    fn test_strict_registration_tail() {
+        let mut buf = [0u8; TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION];
+        assert!(!TokenState::from_slice(&buf).strict_registration(), "unset");
+
+        TokenStateMut::from_slice(&mut buf).set_strict_registration(true);
+        assert_eq!(buf[405], 1);
+        assert!(buf[..TOKEN_STATE_SIZE_WITH_COUPON_COLLECTION].iter().all(|b| *b == 0), "tail only");
+        assert!(TokenState::from_slice(&buf).strict_registration());
+
+        // Account without the tail: permissive
+        let legacy = TokenState::from_slice(&buf[..TOKEN_STATE_SIZE_WITH_COUPON_COLLECTION]);
+        assert!(!legacy.strict_registration());
+    }
+
+    #[test]
+    fn test_version_tail() {
+        let mut buf = [0u8; TOKEN_STATE_V2_SIZE];
+        TokenStateMut::from_slice(&mut buf).set_version(TOKEN_STATE_VERSION_2);
+        assert_eq!(buf[406], 2);
+        assert!(buf[..406].iter().all(|b| *b == 0), "version byte only");
+        assert_eq!(TokenState::from_slice(&buf).version(), TOKEN_STATE_VERSION_2);
+
+        // Every v1 size reads version 1, whatever follows the last tail
+        for len in [
+            TOKEN_STATE_SIZE,
+            TOKEN_STATE_SIZE_WITH_MAX_SUPPLY,
+            TOKEN_STATE_SIZE_WITH_METADATA_FLAGS,
+            TOKEN_STATE_SIZE_WITH_COUPON_COLLECTION,
+            TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION,
+        ] {
+            assert_eq!(TokenState::from_slice(&buf[..len]).version(), TOKEN_STATE_VERSION_1);
+        }
+    }
+
+    /// Writes a distinct value into every field so the golden tests catch any
+    /// offset drift.
+    fn fill_all_fields(buf: &mut [u8]) {
+        let mut state = TokenStateMut::from_slice(buf);
+        state.set_discriminator(&TOKEN_STATE_DISCRIMINATOR);
+        state.set_treasury(&[1u8; 32]);
+        state.set_mint_authority(&[2u8; 32]);
+        state.set_transfer_authority(&[3u8; 32]);
+        state.set_pool_ata(&[4u8; 32]);
+        state.set_distribution_pool(&[5u8; 32]);
+        state.set_incentive_pool(&[6u8; 32]);
+        state.set_treasury_ata(&[7u8; 32]);
+        state.set_mint(&[8u8; 32]);
+        state.set_initialized(true);
+        state.set_bump(254);
+        state.set_per_tx_auto_limit(0x1111);
+        state.set_daily_auto_limit(0x2222);
+        state.set_daily_minted(0x3333);
+        state.set_last_reset_timestamp(0x4444);
+        state.set_paused(true);
+        state.set_min_split_bps(0x0102);
+        state.set_max_split_bps(0x0304);
+        state.set_withdraw_fee_bps(0x0506);
+        state.set_withdraw_approval_threshold(0x5555);
+        state.set_last_transfer_nonce(0x6666);
+        state.set_v1_sunset_ts(0x7777);
+        state.set_v1_user_returned(0x8888);
+        state.set_v1_company_returned(0x9999);
+        state.set_total_burned(0xaaaa);
+        state.set_total_minted(0xbbbb);
+        state.set_decimals(9);
+    }
+
+    fn assert_v1_base_golden(buf: &[u8]) {
+        assert_eq!(&buf[0..8], &TOKEN_STATE_DISCRIMINATOR);
+        for (i, offset) in [8, 40, 72, 104, 136, 168, 200, 232].into_iter().enumerate() {
+            assert_eq!(&buf[offset..offset + 32], &[i as u8 + 1; 32], "pubkey at {offset}");
+        }
+        assert_eq!(&buf[264..266], &[1, 254]);
+        assert_eq!(&buf[266..274], &0x1111u64.to_le_bytes());
+        assert_eq!(&buf[274..282], &0x2222u64.to_le_bytes());
+        assert_eq!(&buf[282..290], &0x3333u64.to_le_bytes());
+        assert_eq!(&buf[290..298], &0x4444i64.to_le_bytes());
+        assert_eq!(buf[298], 1);
+        assert_eq!(&buf[299..305], &[0x02, 0x01, 0x04, 0x03, 0x06, 0x05]);
+        assert_eq!(&buf[305..313], &0x5555u64.to_le_bytes());
+        assert_eq!(&buf[313..321], &0x6666u64.to_le_bytes());
+        assert_eq!(&buf[321..329], &0x7777i64.to_le_bytes());
+        assert_eq!(&buf[329..337], &0x8888u64.to_le_bytes());
+        assert_eq!(&buf[337..345], &0x9999u64.to_le_bytes());
+        assert_eq!(&buf[345..353], &0xaaaau64.to_le_bytes());
+        assert_eq!(&buf[353..361], &0xbbbbu64.to_le_bytes());
+        assert_eq!(&buf[361..363], &[1, 9]);
+    }
+
+    #[test]
+    fn test_v1_layout_golden() {
+        let mut buf = [0u8; TOKEN_STATE_SIZE];
+        fill_all_fields(&mut buf);
+        assert_v1_base_golden(&buf);
+
+        let read = TokenState::from_slice(&buf);
+        assert_eq!(read.version(), TOKEN_STATE_VERSION_1);
+        assert_eq!(read.decimals(), 9);
+        assert_eq!(read.max_supply(), 0);
+        assert_eq!(read.metadata_initialized(), None);
+        assert_eq!(read.coupon_collection(), None);
+        assert!(!read.strict_registration());
+    }
+
+    #[test]
+    fn test_v2_layout_golden() {
+        let mut buf = [0u8; TOKEN_STATE_V2_SIZE];
+        fill_all_fields(&mut buf);
+        let mut state = TokenStateMut::from_slice(&mut buf);
+        state.set_max_supply(0xcccc);
+        state.set_metadata_initialized(true);
+        state.set_metadata_frozen(true);
+        state.set_coupon_collection(&[9u8; 32]);
+        state.set_strict_registration(true);
+        state.set_version(TOKEN_STATE_VERSION_2);
+        state.set_last_op_ts(LastOp::Mint, 0xdddd);
+        state.set_last_op_ts(LastOp::Transfer, 0xeeee);
+        state.set_last_op_ts(LastOp::Withdraw, 0xffff);
+
+        // v1 base is unchanged by the v2 tail
+        assert_v1_base_golden(&buf);
+        assert_eq!(&buf[363..371], &0xccccu64.to_le_bytes());
+        assert_eq!(&buf[371..373], &[1, 1]);
+        assert_eq!(&buf[373..405], &[9u8; 32]);
+        assert_eq!(&buf[405..407], &[1, TOKEN_STATE_VERSION_2]);
+        assert_eq!(&buf[407..415], &0xddddi64.to_le_bytes());
+        assert_eq!(&buf[415..423], &0xeeeei64.to_le_bytes());
+        assert_eq!(&buf[423..431], &0xffffi64.to_le_bytes());
+        assert!(buf[431..].iter().all(|b| *b == 0), "reserved space stays zeroed");
+
+        let read = TokenState::from_slice(&buf);
+        assert_eq!(read.version(), TOKEN_STATE_VERSION_2);
+        assert_eq!(read.max_supply(), 0xcccc);
+        assert_eq!(read.metadata_initialized(), Some(true));
+        assert_eq!(read.metadata_frozen(), Some(true));
+        assert_eq!(read.coupon_collection(), Some(&[9u8; 32]));
+        assert!(read.strict_registration());
+        assert_eq!(read.total_minted(), 0xbbbb);
+        assert_eq!(read.last_mint_ts(), 0xdddd);
+        assert_eq!(read.last_transfer_ts(), 0xeeee);
+        assert_eq!(read.last_withdraw_ts(), 0xffff);
+    }
+
+    #[test]
+    fn test_last_op_ts_v1_is_noop() {
+        let mut buf = [0u8; TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION];
+        let mut state = TokenStateMut::from_slice(&mut buf);
+        state.set_last_op_ts(LastOp::Mint, 1_700_000_000);
+        state.set_last_op_ts(LastOp::Withdraw, 1_700_000_000);
+        assert!(buf.iter().all(|b| *b == 0), "v1 accounts have no timestamp slots");
+        assert_eq!(TokenState::from_slice(&buf).last_mint_ts(), 0);
+    }
+
+    #[test]
+    fn test_decimals_layout() {
+        let mut buf = [0u8; TOKEN_STATE_SIZE];
+        // Existing state (reserved bytes zeroed) keeps the compile-time default
+        assert_eq!(TokenState::from_slice(&buf).decimals(), TOKEN_DECIMALS);
+
+        TokenStateMut::from_slice(&mut buf).set_decimals(0);
+        assert_eq!(&buf[361..363], &[1, 0]);
+        assert_eq!(TokenState::from_slice(&buf).decimals(), 0);
+
+        TokenStateMut::from_slice(&mut buf).set_decimals(9);
+        assert_eq!(&buf[361..363], &[1, 9]);
+        let read = TokenState::from_slice(&buf);
+        assert_eq!(read.decimals(), 9);
+        assert_eq!(read.total_minted(), 0, "decimals must not overlap total_minted");
+        assert!(buf[..361].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_total_minted_survives_daily_reset() {
+        let mut buf = [0u8; TOKEN_STATE_SIZE];
+        let mut state = TokenStateMut::from_slice(&mut buf);
+
+        state.maybe_reset_daily(1_000_000);
+        state.record_mint(300);
+        state.maybe_reset_daily(1_000_000 + 86_401);
+        state.record_mint(200);
+        assert_eq!(state.daily_minted(), 200);
+        assert_eq!(state.total_minted(), 500);
+
+        let read = TokenState::from_slice(&buf);
+        assert_eq!(read.total_minted(), 500);
+        assert_eq!(read.total_burned(), 0, "total_minted must not overlap total_burned");
+        assert_eq!(&buf[353..361], &500u64.to_le_bytes());
+        assert!(buf[361..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_init_grown_tail_keeps_legacy_metadata_initialized() {
+        // A legacy 363-byte account grown by set_coupon_collection /
+        // set_strict_registration must still read as metadata-initialized
+        for new_len in [TOKEN_STATE_SIZE_WITH_COUPON_COLLECTION, TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION] {
+            let mut buf = vec![0xffu8; new_len];
+            fill_all_fields(&mut buf[..TOKEN_STATE_SIZE]);
+            assert_eq!(TokenState::from_slice(&buf[..TOKEN_STATE_SIZE]).metadata_initialized(), None);
+
+            TokenStateMut::from_slice(&mut buf).init_grown_tail(TOKEN_STATE_SIZE, true);
+
+            assert_v1_base_golden(&buf);
+            let read = TokenState::from_slice(&buf);
+            assert_eq!(read.metadata_initialized(), Some(true));
+            assert_eq!(read.metadata_frozen(), Some(false));
+            assert_eq!(read.max_supply(), 0);
+            assert_eq!(read.coupon_collection(), None);
+            assert!(!read.strict_registration());
+            assert_eq!(read.version(), TOKEN_STATE_VERSION_1);
+        }
+    }
+
+    #[test]
+    fn test_init_grown_tail_leaves_existing_flags() {
+        let mut buf = vec![0xffu8; TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION];
+        buf[..TOKEN_STATE_SIZE_WITH_METADATA_FLAGS].fill(0);
+        TokenStateMut::from_slice(&mut buf).init_grown_tail(TOKEN_STATE_SIZE_WITH_METADATA_FLAGS, true);
+        assert_eq!(TokenState::from_slice(&buf).metadata_initialized(), Some(false));
+        assert!(buf[TOKEN_STATE_SIZE_WITH_METADATA_FLAGS..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_init_grown_tail_to_v2_stamps_version() {
+        let mut buf = vec![0xffu8; TOKEN_STATE_V2_SIZE];
+        fill_all_fields(&mut buf[..TOKEN_STATE_SIZE]);
+        TokenStateMut::from_slice(&mut buf).init_grown_tail(TOKEN_STATE_SIZE, false);
+
+        let read = TokenState::from_slice(&buf);
+        assert_eq!(read.metadata_initialized(), Some(false));
+        assert_eq!(read.version(), TOKEN_STATE_VERSION_2);
+        assert_eq!(read.last_op_ts(LastOp::Mint), 0);
+        assert!(buf[TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION + 1..].iter().all(|b| *b == 0));
     }
 }
@@ -0,0 +1,124 @@
+use crate::helpers::bytes::read_array;
+
+/// Zero-copy UserState — 26 bytes total.
+/// Anchor account discriminator: SHA256("account:UserState")[0..8]
+///
+/// PDA `[USER_STATE_SEED, &user_id.to_le_bytes()]`: the on-chain record that
+/// `user_id` was registered by `initialize_user`. It sits next to the user PDA
+/// `[USER_SEED, user_id]`, which owns compressed tokens and stays data-less.
+/// Pool and company transfers only require it when `strict_registration` is on.
+pub struct UserState<'a> {
+    data: &'a [u8],
+}
+
+pub struct UserStateMut<'a> {
+    data: &'a mut [u8],
+}
+
+pub const USER_STATE_DISCRIMINATOR: [u8; 8] = [72, 177, 85, 249, 76, 167, 186, 126];
+pub const USER_STATE_SIZE: usize = 26;
+
+/// Status of a registered user that can receive tokens.
+pub const USER_STATUS_ACTIVE: u8 = 0;
+
+const OFF_DISC: usize = 0;
+const OFF_USER_ID: usize = 8;
+const OFF_CREATED_AT: usize = 16;
+const OFF_STATUS: usize = 24;
+const OFF_BUMP: usize = 25;
+
+impl<'a> UserState<'a> {
+    pub const SIZE: usize = USER_STATE_SIZE;
+    pub const DISCRIMINATOR: [u8; 8] = USER_STATE_DISCRIMINATOR;
+
+    pub fn from_slice(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn discriminator(&self) -> &[u8; 8] {
+        read_array::<8>(self.data, OFF_DISC)
+    }
+    pub fn user_id(&self) -> u64 {
+        u64::from_le_bytes(*read_array::<8>(self.data, OFF_USER_ID))
+    }
+    /// Unix timestamp of the registration.
+    pub fn created_at(&self) -> i64 {
+        i64::from_le_bytes(*read_array::<8>(self.data, OFF_CREATED_AT))
+    }
+    pub fn status(&self) -> u8 {
+        self.data[OFF_STATUS]
+    }
+    pub fn bump(&self) -> u8 {
+        self.data[OFF_BUMP]
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.status() == USER_STATUS_ACTIVE
+    }
+}
+
+impl<'a> UserStateMut<'a> {
+    pub fn from_slice(data: &'a mut [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn set_discriminator(&mut self, disc: &[u8; 8]) {
+        self.data[OFF_DISC..OFF_DISC + 8].copy_from_slice(disc);
+    }
+    pub fn set_user_id(&mut self, val: u64) {
+        self.data[OFF_USER_ID..OFF_USER_ID + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_created_at(&mut self, val: i64) {
+        self.data[OFF_CREATED_AT..OFF_CREATED_AT + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_status(&mut self, val: u8) {
+        self.data[OFF_STATUS] = val;
+    }
+    pub fn set_bump(&mut self, val: u8) {
+        self.data[OFF_BUMP] = val;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_state_size() {
+        assert_eq!(USER_STATE_SIZE, OFF_BUMP + 1);
+    }
+
+    #[test]
+    fn test_user_state_discriminator_matches_anchor() {
+        use sha2::{Sha256, Digest};
+        let hash = Sha256::digest(b"account:UserState");
+        let expected: [u8; 8] = hash[0..8].try_into().unwrap();
+        assert_eq!(USER_STATE_DISCRIMINATOR, expected);
+    }
+
+    #[test]
+    fn test_read_write_round_trip() {
+        let mut buf = [0u8; USER_STATE_SIZE];
+        let mut state = UserStateMut::from_slice(&mut buf);
+
+        state.set_discriminator(&USER_STATE_DISCRIMINATOR);
+        state.set_user_id(u64::MAX);
+        state.set_created_at(1_700_000_000);
+        state.set_status(3);
+        state.set_bump(251);
+
+        let read = UserState::from_slice(&buf);
+        assert_eq!(read.discriminator(), &USER_STATE_DISCRIMINATOR);
+        assert_eq!(read.user_id(), u64::MAX);
+        assert_eq!(read.created_at(), 1_700_000_000);
+        assert_eq!(read.status(), 3);
+        assert!(!read.is_active());
+        assert_eq!(read.bump(), 251);
+    }
+
+    #[test]
+    fn test_zeroed_status_is_active() {
+        let buf = [0u8; USER_STATE_SIZE];
+        assert!(UserState::from_slice(&buf).is_active());
+    }
+}
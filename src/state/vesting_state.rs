@@ -0,0 +1,130 @@
+use crate::helpers::bytes::read_array;
+
+/// Zero-copy VestingState — 49 bytes total.
+/// Anchor account discriminator: SHA256("account:VestingState")[0..8]
+///
+/// PDA `[VESTING_SEED, beneficiary_user_id]`. Tokens stay in the pool until
+/// released by `claim_vested`; `claimed` tracks the amount already paid out.
+pub struct VestingState<'a> {
+    data: &'a [u8],
+}
+
+pub struct VestingStateMut<'a> {
+    data: &'a mut [u8],
+}
+
+pub const VESTING_STATE_DISCRIMINATOR: [u8; 8] = [225, 34, 190, 79, 98, 226, 144, 101];
+pub const VESTING_STATE_SIZE: usize = 49;
+
+const OFF_DISC: usize = 0;
+const OFF_BENEFICIARY_USER_ID: usize = 8;
+const OFF_TOTAL_AMOUNT: usize = 16;
+const OFF_START_TS: usize = 24;
+const OFF_DURATION: usize = 32;
+const OFF_CLAIMED: usize = 40;
+const OFF_BUMP: usize = 48;
+
+impl<'a> VestingState<'a> {
+    pub const SIZE: usize = VESTING_STATE_SIZE;
+    pub const DISCRIMINATOR: [u8; 8] = VESTING_STATE_DISCRIMINATOR;
+
+    pub fn from_slice(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn discriminator(&self) -> &[u8; 8] {
+        read_array::<8>(self.data, OFF_DISC)
+    }
+    pub fn beneficiary_user_id(&self) -> u64 {
+        u64::from_le_bytes(
+            *read_array::<8>(self.data, OFF_BENEFICIARY_USER_ID),
+        )
+    }
+    pub fn total_amount(&self) -> u64 {
+        u64::from_le_bytes(*read_array::<8>(self.data, OFF_TOTAL_AMOUNT))
+    }
+    /// Unix timestamp at which linear release begins.
+    pub fn start_ts(&self) -> i64 {
+        i64::from_le_bytes(*read_array::<8>(self.data, OFF_START_TS))
+    }
+    /// Release duration in seconds.
+    pub fn duration(&self) -> u64 {
+        u64::from_le_bytes(*read_array::<8>(self.data, OFF_DURATION))
+    }
+    pub fn claimed(&self) -> u64 {
+        u64::from_le_bytes(*read_array::<8>(self.data, OFF_CLAIMED))
+    }
+    pub fn bump(&self) -> u8 {
+        self.data[OFF_BUMP]
+    }
+}
+
+impl<'a> VestingStateMut<'a> {
+    pub fn from_slice(data: &'a mut [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn set_discriminator(&mut self, disc: &[u8; 8]) {
+        self.data[OFF_DISC..OFF_DISC + 8].copy_from_slice(disc);
+    }
+    pub fn set_beneficiary_user_id(&mut self, val: u64) {
+        self.data[OFF_BENEFICIARY_USER_ID..OFF_BENEFICIARY_USER_ID + 8]
+            .copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_total_amount(&mut self, val: u64) {
+        self.data[OFF_TOTAL_AMOUNT..OFF_TOTAL_AMOUNT + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_start_ts(&mut self, val: i64) {
+        self.data[OFF_START_TS..OFF_START_TS + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_duration(&mut self, val: u64) {
+        self.data[OFF_DURATION..OFF_DURATION + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_claimed(&mut self, val: u64) {
+        self.data[OFF_CLAIMED..OFF_CLAIMED + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_bump(&mut self, val: u8) {
+        self.data[OFF_BUMP] = val;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vesting_state_size() {
+        assert_eq!(VESTING_STATE_SIZE, OFF_BUMP + 1);
+    }
+
+    #[test]
+    fn test_vesting_state_discriminator_matches_anchor() {
+        use sha2::{Sha256, Digest};
+        let hash = Sha256::digest(b"account:VestingState");
+        let expected: [u8; 8] = hash[0..8].try_into().unwrap();
+        assert_eq!(VESTING_STATE_DISCRIMINATOR, expected);
+    }
+
+    #[test]
+    fn test_read_write_round_trip() {
+        let mut buf = [0u8; VESTING_STATE_SIZE];
+        let mut state = VestingStateMut::from_slice(&mut buf);
+
+        state.set_discriminator(&VESTING_STATE_DISCRIMINATOR);
+        state.set_beneficiary_user_id(77);
+        state.set_total_amount(120_000_000_000);
+        state.set_start_ts(1_700_000_000);
+        state.set_duration(31_536_000);
+        state.set_claimed(10_000_000_000);
+        state.set_bump(251);
+
+        let read = VestingState::from_slice(&buf);
+        assert_eq!(read.discriminator(), &VESTING_STATE_DISCRIMINATOR);
+        assert_eq!(read.beneficiary_user_id(), 77);
+        assert_eq!(read.total_amount(), 120_000_000_000);
+        assert_eq!(read.start_ts(), 1_700_000_000);
+        assert_eq!(read.duration(), 31_536_000);
+        assert_eq!(read.claimed(), 10_000_000_000);
+        assert_eq!(read.bump(), 251);
+    }
+}
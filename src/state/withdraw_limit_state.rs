@@ -0,0 +1,172 @@
+use crate::constants::SECONDS_PER_DAY;
+use crate::helpers::bytes::read_array;
+
+/// Zero-copy WithdrawLimitState — 41 bytes total.
+/// Anchor account discriminator: SHA256("account:WithdrawLimitState")[0..8]
+///
+/// PDA `[WITHDRAW_LIMIT_SEED, user_id]`. Caps a user's `withdraw_to_external`
+/// volume per rolling 24h window starting at `window_start`.
+pub struct WithdrawLimitState<'a> {
+    data: &'a [u8],
+}
+
+pub struct WithdrawLimitStateMut<'a> {
+    data: &'a mut [u8],
+}
+
+pub const WITHDRAW_LIMIT_STATE_DISCRIMINATOR: [u8; 8] = [208, 231, 4, 90, 35, 125, 15, 231];
+pub const WITHDRAW_LIMIT_STATE_SIZE: usize = 41;
+
+const OFF_DISC: usize = 0;
+const OFF_USER_ID: usize = 8;
+const OFF_DAILY_LIMIT: usize = 16;
+const OFF_CONSUMED: usize = 24;
+const OFF_WINDOW_START: usize = 32;
+const OFF_BUMP: usize = 40;
+
+impl<'a> WithdrawLimitState<'a> {
+    pub const SIZE: usize = WITHDRAW_LIMIT_STATE_SIZE;
+    pub const DISCRIMINATOR: [u8; 8] = WITHDRAW_LIMIT_STATE_DISCRIMINATOR;
+
+    pub fn from_slice(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn discriminator(&self) -> &[u8; 8] {
+        read_array::<8>(self.data, OFF_DISC)
+    }
+    pub fn user_id(&self) -> u64 {
+        u64::from_le_bytes(*read_array::<8>(self.data, OFF_USER_ID))
+    }
+    pub fn daily_limit(&self) -> u64 {
+        u64::from_le_bytes(*read_array::<8>(self.data, OFF_DAILY_LIMIT))
+    }
+    /// Amount withdrawn in the current window.
+    pub fn consumed(&self) -> u64 {
+        u64::from_le_bytes(*read_array::<8>(self.data, OFF_CONSUMED))
+    }
+    /// Unix timestamp at which the current window opened.
+    pub fn window_start(&self) -> i64 {
+        i64::from_le_bytes(*read_array::<8>(self.data, OFF_WINDOW_START))
+    }
+    pub fn bump(&self) -> u8 {
+        self.data[OFF_BUMP]
+    }
+}
+
+impl<'a> WithdrawLimitStateMut<'a> {
+    pub fn from_slice(data: &'a mut [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn set_discriminator(&mut self, disc: &[u8; 8]) {
+        self.data[OFF_DISC..OFF_DISC + 8].copy_from_slice(disc);
+    }
+    pub fn set_user_id(&mut self, val: u64) {
+        self.data[OFF_USER_ID..OFF_USER_ID + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_daily_limit(&mut self, val: u64) {
+        self.data[OFF_DAILY_LIMIT..OFF_DAILY_LIMIT + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_consumed(&mut self, val: u64) {
+        self.data[OFF_CONSUMED..OFF_CONSUMED + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_window_start(&mut self, val: i64) {
+        self.data[OFF_WINDOW_START..OFF_WINDOW_START + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_bump(&mut self, val: u8) {
+        self.data[OFF_BUMP] = val;
+    }
+
+    /// Record a withdrawal of `amount` at `now`. Opens a fresh window once 24h
+    /// have passed since `window_start`. Returns `false` (state untouched) when
+    /// the window total would exceed `daily_limit`.
+    pub fn try_consume(&mut self, amount: u64, now: i64) -> bool {
+        let view = WithdrawLimitState::from_slice(self.data);
+        let expired = now.saturating_sub(view.window_start()) >= SECONDS_PER_DAY;
+        let consumed = if expired { 0 } else { view.consumed() };
+        let total = match consumed.checked_add(amount) {
+            Some(total) if total <= view.daily_limit() => total,
+            _ => return false,
+        };
+        if expired {
+            self.set_window_start(now);
+        }
+        self.set_consumed(total);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn state(daily_limit: u64, consumed: u64) -> [u8; WITHDRAW_LIMIT_STATE_SIZE] {
+        let mut buf = [0u8; WITHDRAW_LIMIT_STATE_SIZE];
+        let mut s = WithdrawLimitStateMut::from_slice(&mut buf);
+        s.set_daily_limit(daily_limit);
+        s.set_consumed(consumed);
+        s.set_window_start(NOW);
+        buf
+    }
+
+    #[test]
+    fn test_withdraw_limit_state_size() {
+        assert_eq!(WITHDRAW_LIMIT_STATE_SIZE, OFF_BUMP + 1);
+    }
+
+    #[test]
+    fn test_withdraw_limit_state_discriminator_matches_anchor() {
+        use sha2::{Sha256, Digest};
+        let hash = Sha256::digest(b"account:WithdrawLimitState");
+        let expected: [u8; 8] = hash[0..8].try_into().unwrap();
+        assert_eq!(WITHDRAW_LIMIT_STATE_DISCRIMINATOR, expected);
+    }
+
+    #[test]
+    fn test_exactly_hitting_limit_is_allowed() {
+        let mut buf = state(1_000, 400);
+        assert!(WithdrawLimitStateMut::from_slice(&mut buf).try_consume(600, NOW + 60));
+        assert_eq!(WithdrawLimitState::from_slice(&buf).consumed(), 1_000);
+    }
+
+    #[test]
+    fn test_exceeding_limit_by_one_is_rejected() {
+        let mut buf = state(1_000, 400);
+        assert!(!WithdrawLimitStateMut::from_slice(&mut buf).try_consume(601, NOW + 60));
+        assert_eq!(WithdrawLimitState::from_slice(&buf).consumed(), 400, "rejection must not mutate");
+    }
+
+    #[test]
+    fn test_window_rolls_over_after_24_hours() {
+        let mut buf = state(1_000, 1_000);
+        let mut s = WithdrawLimitStateMut::from_slice(&mut buf);
+        assert!(!s.try_consume(1, NOW + SECONDS_PER_DAY - 1));
+        assert!(s.try_consume(1_000, NOW + SECONDS_PER_DAY));
+        let read = WithdrawLimitState::from_slice(&buf);
+        assert_eq!(read.consumed(), 1_000);
+        assert_eq!(read.window_start(), NOW + SECONDS_PER_DAY);
+    }
+
+    #[test]
+    fn test_read_write_round_trip() {
+        let mut buf = [0u8; WITHDRAW_LIMIT_STATE_SIZE];
+        let mut s = WithdrawLimitStateMut::from_slice(&mut buf);
+        s.set_discriminator(&WITHDRAW_LIMIT_STATE_DISCRIMINATOR);
+        s.set_user_id(42);
+        s.set_daily_limit(5_000_000);
+        s.set_consumed(1_234);
+        s.set_window_start(NOW);
+        s.set_bump(253);
+
+        let read = WithdrawLimitState::from_slice(&buf);
+        assert_eq!(read.discriminator(), &WITHDRAW_LIMIT_STATE_DISCRIMINATOR);
+        assert_eq!(read.user_id(), 42);
+        assert_eq!(read.daily_limit(), 5_000_000);
+        assert_eq!(read.consumed(), 1_234);
+        assert_eq!(read.window_start(), NOW);
+        assert_eq!(read.bump(), 253);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::helpers::bytes::read_array;
+
+/// Zero-copy WithdrawalRequestState — 105 bytes total.
+/// Anchor account discriminator: SHA256("account:WithdrawalRequestState")[0..8]
+///
+/// PDA `[WITHDRAWAL_REQUEST_SEED, request_id]`. Parks a large user withdrawal
+/// until the treasury runs `approve_withdrawal` (or anyone authorized runs
+/// `cancel_withdrawal`); both close the account and refund `payer`.
+pub struct WithdrawalRequestState<'a> {
+    data: &'a [u8],
+}
+
+pub struct WithdrawalRequestStateMut<'a> {
+    data: &'a mut [u8],
+}
+
+pub const WITHDRAWAL_REQUEST_STATE_DISCRIMINATOR: [u8; 8] = [119, 212, 180, 193, 128, 226, 217, 152];
+pub const WITHDRAWAL_REQUEST_STATE_SIZE: usize = 105;
+
+const OFF_DISC: usize = 0;
+const OFF_REQUEST_ID: usize = 8;
+const OFF_USER_ID: usize = 16;
+const OFF_AMOUNT: usize = 24;
+const OFF_DESTINATION: usize = 32;
+const OFF_PAYER: usize = 64;
+const OFF_REQUESTED_AT: usize = 96;
+const OFF_BUMP: usize = 104;
+
+impl<'a> WithdrawalRequestState<'a> {
+    pub const SIZE: usize = WITHDRAWAL_REQUEST_STATE_SIZE;
+    pub const DISCRIMINATOR: [u8; 8] = WITHDRAWAL_REQUEST_STATE_DISCRIMINATOR;
+
+    pub fn from_slice(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn discriminator(&self) -> &[u8; 8] {
+        read_array::<8>(self.data, OFF_DISC)
+    }
+    pub fn request_id(&self) -> u64 {
+        u64::from_le_bytes(*read_array::<8>(self.data, OFF_REQUEST_ID))
+    }
+    pub fn user_id(&self) -> u64 {
+        u64::from_le_bytes(*read_array::<8>(self.data, OFF_USER_ID))
+    }
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(*read_array::<8>(self.data, OFF_AMOUNT))
+    }
+    /// External wallet the approved withdrawal pays out to.
+    pub fn destination(&self) -> &[u8; 32] {
+        read_array::<32>(self.data, OFF_DESTINATION)
+    }
+    /// Rent payer, refunded when the request is approved or cancelled.
+    pub fn payer(&self) -> &[u8; 32] {
+        read_array::<32>(self.data, OFF_PAYER)
+    }
+    pub fn requested_at(&self) -> i64 {
+        i64::from_le_bytes(*read_array::<8>(self.data, OFF_REQUESTED_AT))
+    }
+    pub fn bump(&self) -> u8 {
+        self.data[OFF_BUMP]
+    }
+}
+
+impl<'a> WithdrawalRequestStateMut<'a> {
+    pub fn from_slice(data: &'a mut [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn set_discriminator(&mut self, disc: &[u8; 8]) {
+        self.data[OFF_DISC..OFF_DISC + 8].copy_from_slice(disc);
+    }
+    pub fn set_request_id(&mut self, val: u64) {
+        self.data[OFF_REQUEST_ID..OFF_REQUEST_ID + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_user_id(&mut self, val: u64) {
+        self.data[OFF_USER_ID..OFF_USER_ID + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_amount(&mut self, val: u64) {
+        self.data[OFF_AMOUNT..OFF_AMOUNT + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_destination(&mut self, val: &[u8; 32]) {
+        self.data[OFF_DESTINATION..OFF_DESTINATION + 32].copy_from_slice(val);
+    }
+    pub fn set_payer(&mut self, val: &[u8; 32]) {
+        self.data[OFF_PAYER..OFF_PAYER + 32].copy_from_slice(val);
+    }
+    pub fn set_requested_at(&mut self, val: i64) {
+        self.data[OFF_REQUESTED_AT..OFF_REQUESTED_AT + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    pub fn set_bump(&mut self, val: u8) {
+        self.data[OFF_BUMP] = val;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_withdrawal_request_state_size() {
+        assert_eq!(WITHDRAWAL_REQUEST_STATE_SIZE, OFF_BUMP + 1);
+    }
+
+    #[test]
+    fn test_withdrawal_request_state_discriminator_matches_anchor() {
+        use sha2::{Sha256, Digest};
+        let hash = Sha256::digest(b"account:WithdrawalRequestState");
+        let expected: [u8; 8] = hash[0..8].try_into().unwrap();
+        assert_eq!(WITHDRAWAL_REQUEST_STATE_DISCRIMINATOR, expected);
+    }
+
+    #[test]
+    fn test_read_write_round_trip() {
+        let mut buf = [0u8; WITHDRAWAL_REQUEST_STATE_SIZE];
+        let mut state = WithdrawalRequestStateMut::from_slice(&mut buf);
+
+        state.set_discriminator(&WITHDRAWAL_REQUEST_STATE_DISCRIMINATOR);
+        state.set_request_id(9);
+        state.set_user_id(77);
+        state.set_amount(250_000_000_000);
+        state.set_destination(&[0xD5; 32]);
+        state.set_payer(&[0xFE; 32]);
+        state.set_requested_at(1_700_000_000);
+        state.set_bump(250);
+
+        let read = WithdrawalRequestState::from_slice(&buf);
+        assert_eq!(read.discriminator(), &WITHDRAWAL_REQUEST_STATE_DISCRIMINATOR);
+        assert_eq!(read.request_id(), 9);
+        assert_eq!(read.user_id(), 77);
+        assert_eq!(read.amount(), 250_000_000_000);
+        assert_eq!(read.destination(), &[0xD5; 32]);
+        assert_eq!(read.payer(), &[0xFE; 32]);
+        assert_eq!(read.requested_at(), 1_700_000_000);
+        assert_eq!(read.bump(), 250);
+    }
+}
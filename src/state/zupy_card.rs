@@ -1,4 +1,8 @@
-/// Zero-copy ZupyCard — 108 bytes total.
+use crate::helpers::bytes::read_array;
+
+/// Zero-copy ZupyCard — 108 bytes total, plus `tier` / `updated_at` (117 bytes),
+/// `status` / `reissue_count` (122 bytes) and `issued_at` (130 bytes) on newer
+/// or upgraded cards.
 /// Anchor account discriminator: SHA256("account:ZupyCard")[0..8]
 pub struct ZupyCard<'a> {
     data: &'a [u8],
@@ -10,6 +14,22 @@ pub struct ZupyCardMut<'a> {
 
 pub const ZUPY_CARD_DISCRIMINATOR: [u8; 8] = [254, 50, 30, 179, 82, 218, 229, 232];
 pub const ZUPY_CARD_SIZE: usize = 108;
+/// Size of a ZupyCard carrying `tier` (108) and `updated_at` (109..117).
+/// Legacy 108-byte cards read tier 0 until `update_card_tier` grows them.
+pub const ZUPY_CARD_SIZE_WITH_TIER: usize = 117;
+/// Size of a ZupyCard carrying `status` (117) and `reissue_count` (118..122).
+/// Shorter cards read as active with no reissues.
+pub const ZUPY_CARD_SIZE_WITH_STATUS: usize = 122;
+/// Size of a ZupyCard carrying `issued_at` (122..130). Shorter cards read
+/// `created_at` instead.
+pub const ZUPY_CARD_SIZE_WITH_ISSUED_AT: usize = 130;
+
+/// `status`: the card is valid.
+pub const CARD_STATUS_ACTIVE: u8 = 0;
+/// `status`: the card is suspended; tier changes and reissues are refused.
+pub const CARD_STATUS_FROZEN: u8 = 1;
+/// `status`: the card is invalidated and cannot be reissued.
+pub const CARD_STATUS_REVOKED: u8 = 2;
 
 const OFF_DISC: usize = 0;
 const OFF_OWNER: usize = 8;
@@ -17,6 +37,11 @@ const OFF_MINT: usize = 40;
 const OFF_USER_KSUID: usize = 72;
 const OFF_CREATED_AT: usize = 99;
 const OFF_BUMP: usize = 107;
+const OFF_TIER: usize = 108;
+const OFF_UPDATED_AT: usize = 109;
+const OFF_STATUS: usize = 117;
+const OFF_REISSUE_COUNT: usize = 118;
+const OFF_ISSUED_AT: usize = 122;
 
 impl<'a> ZupyCard<'a> {
     pub const SIZE: usize = ZUPY_CARD_SIZE;
@@ -27,23 +52,66 @@ impl<'a> ZupyCard<'a> {
     }
 
     pub fn discriminator(&self) -> &[u8; 8] {
-        self.data[OFF_DISC..OFF_DISC + 8].try_into().unwrap()
+        read_array::<8>(self.data, OFF_DISC)
     }
     pub fn owner(&self) -> &[u8; 32] {
-        self.data[OFF_OWNER..OFF_OWNER + 32].try_into().unwrap()
+        read_array::<32>(self.data, OFF_OWNER)
     }
     pub fn mint(&self) -> &[u8; 32] {
-        self.data[OFF_MINT..OFF_MINT + 32].try_into().unwrap()
+        read_array::<32>(self.data, OFF_MINT)
     }
     pub fn user_ksuid(&self) -> &[u8; 27] {
-        self.data[OFF_USER_KSUID..OFF_USER_KSUID + 27].try_into().unwrap()
+        read_array::<27>(self.data, OFF_USER_KSUID)
     }
     pub fn created_at(&self) -> i64 {
-        i64::from_le_bytes(self.data[OFF_CREATED_AT..OFF_CREATED_AT + 8].try_into().unwrap())
+        i64::from_le_bytes(*read_array::<8>(self.data, OFF_CREATED_AT))
     }
     pub fn bump(&self) -> u8 {
         self.data[OFF_BUMP]
     }
+    /// Card tier (0..=`MAX_CARD_TIER`); 0 on legacy cards.
+    pub fn tier(&self) -> u8 {
+        if self.data.len() < ZUPY_CARD_SIZE_WITH_TIER {
+            return 0;
+        }
+        self.data[OFF_TIER]
+    }
+    /// Last tier change (unix timestamp); 0 on legacy cards.
+    pub fn updated_at(&self) -> i64 {
+        if self.data.len() < ZUPY_CARD_SIZE_WITH_TIER {
+            return 0;
+        }
+        i64::from_le_bytes(*read_array::<8>(self.data, OFF_UPDATED_AT))
+    }
+    /// `CARD_STATUS_ACTIVE`, `CARD_STATUS_FROZEN` or `CARD_STATUS_REVOKED`;
+    /// active on shorter cards.
+    pub fn status(&self) -> u8 {
+        if self.data.len() < ZUPY_CARD_SIZE_WITH_STATUS {
+            return CARD_STATUS_ACTIVE;
+        }
+        self.data[OFF_STATUS]
+    }
+    pub fn is_frozen(&self) -> bool {
+        self.status() == CARD_STATUS_FROZEN
+    }
+    pub fn is_revoked(&self) -> bool {
+        self.status() == CARD_STATUS_REVOKED
+    }
+    /// Times the card was reissued via `reissue_zupy_card`; 0 on shorter cards.
+    pub fn reissue_count(&self) -> u32 {
+        if self.data.len() < ZUPY_CARD_SIZE_WITH_STATUS {
+            return 0;
+        }
+        u32::from_le_bytes(*read_array::<4>(self.data, OFF_REISSUE_COUNT))
+    }
+    /// When the current card token was issued (unix timestamp): creation or
+    /// the last reissue. Shorter cards read `created_at`.
+    pub fn issued_at(&self) -> i64 {
+        if self.data.len() < ZUPY_CARD_SIZE_WITH_ISSUED_AT {
+            return self.created_at();
+        }
+        i64::from_le_bytes(*read_array::<8>(self.data, OFF_ISSUED_AT))
+    }
 }
 
 impl<'a> ZupyCardMut<'a> {
@@ -69,6 +137,26 @@ impl<'a> ZupyCardMut<'a> {
     pub fn set_bump(&mut self, val: u8) {
         self.data[OFF_BUMP] = val;
     }
+    /// Needs a `ZUPY_CARD_SIZE_WITH_TIER` buffer.
+    pub fn set_tier(&mut self, val: u8) {
+        self.data[OFF_TIER] = val;
+    }
+    /// Needs a `ZUPY_CARD_SIZE_WITH_TIER` buffer.
+    pub fn set_updated_at(&mut self, val: i64) {
+        self.data[OFF_UPDATED_AT..OFF_UPDATED_AT + 8].copy_from_slice(&val.to_le_bytes());
+    }
+    /// Needs a `ZUPY_CARD_SIZE_WITH_STATUS` buffer.
+    pub fn set_status(&mut self, val: u8) {
+        self.data[OFF_STATUS] = val;
+    }
+    /// Needs a `ZUPY_CARD_SIZE_WITH_STATUS` buffer.
+    pub fn set_reissue_count(&mut self, val: u32) {
+        self.data[OFF_REISSUE_COUNT..OFF_REISSUE_COUNT + 4].copy_from_slice(&val.to_le_bytes());
+    }
+    /// Needs a `ZUPY_CARD_SIZE_WITH_ISSUED_AT` buffer.
+    pub fn set_issued_at(&mut self, val: i64) {
+        self.data[OFF_ISSUED_AT..OFF_ISSUED_AT + 8].copy_from_slice(&val.to_le_bytes());
+    }
 }
 
 #[cfg(test)]
@@ -112,4 +200,78 @@ mod tests {
         assert_eq!(read.created_at(), 1_700_000_000);
         assert_eq!(read.bump(), 250);
     }
+
+    #[test]
+    fn test_tier_layout() {
+        let mut buf = [0u8; ZUPY_CARD_SIZE_WITH_TIER];
+        let mut state = ZupyCardMut::from_slice(&mut buf);
+        state.set_bump(0xFF);
+        state.set_tier(3);
+        state.set_updated_at(-2);
+
+        assert_eq!(buf[108], 3);
+        assert_eq!(&buf[109..117], &(-2i64).to_le_bytes());
+        let read = ZupyCard::from_slice(&buf);
+        assert_eq!(read.tier(), 3);
+        assert_eq!(read.updated_at(), -2);
+        assert_eq!(read.bump(), 0xFF, "tier must not overlap bump");
+        assert!(buf[..OFF_BUMP].iter().all(|b| *b == 0));
+
+        // Legacy 108-byte card
+        let legacy = ZupyCard::from_slice(&buf[..ZUPY_CARD_SIZE]);
+        assert_eq!(legacy.tier(), 0);
+        assert_eq!(legacy.updated_at(), 0);
+    }
+
+    #[test]
+    fn test_status_layout() {
+        let mut buf = [0u8; ZUPY_CARD_SIZE_WITH_STATUS];
+        let mut state = ZupyCardMut::from_slice(&mut buf);
+        state.set_updated_at(i64::MAX);
+        state.set_status(CARD_STATUS_REVOKED);
+        state.set_reissue_count(0x0102_0304);
+
+        assert_eq!(buf[117], CARD_STATUS_REVOKED);
+        assert_eq!(&buf[118..122], &0x0102_0304u32.to_le_bytes());
+        let read = ZupyCard::from_slice(&buf);
+        assert!(read.is_revoked());
+        assert_eq!(read.reissue_count(), 0x0102_0304);
+        assert_eq!(read.updated_at(), i64::MAX, "status must not overlap updated_at");
+
+        // Shorter cards: active, never reissued
+        let short = ZupyCard::from_slice(&buf[..ZUPY_CARD_SIZE_WITH_TIER]);
+        assert_eq!(short.status(), CARD_STATUS_ACTIVE);
+        assert_eq!(short.reissue_count(), 0);
+    }
+
+    #[test]
+    fn test_status_values() {
+        assert_eq!((CARD_STATUS_ACTIVE, CARD_STATUS_FROZEN, CARD_STATUS_REVOKED), (0, 1, 2));
+
+        let mut buf = [0u8; ZUPY_CARD_SIZE_WITH_STATUS];
+        ZupyCardMut::from_slice(&mut buf).set_status(CARD_STATUS_FROZEN);
+        let read = ZupyCard::from_slice(&buf);
+        assert!(read.is_frozen());
+        assert!(!read.is_revoked());
+    }
+
+    #[test]
+    fn test_issued_at_layout() {
+        let mut buf = [0u8; ZUPY_CARD_SIZE_WITH_ISSUED_AT];
+        let mut state = ZupyCardMut::from_slice(&mut buf);
+        state.set_created_at(1_700_000_000);
+        state.set_reissue_count(u32::MAX);
+        state.set_issued_at(1_800_000_000);
+
+        assert_eq!(&buf[99..107], &1_700_000_000i64.to_le_bytes());
+        assert_eq!(&buf[122..130], &1_800_000_000i64.to_le_bytes());
+        let read = ZupyCard::from_slice(&buf);
+        assert_eq!(read.issued_at(), 1_800_000_000);
+        assert_eq!(read.created_at(), 1_700_000_000);
+        assert_eq!(read.reissue_count(), u32::MAX, "issued_at must not overlap reissue_count");
+
+        // Shorter cards: issued when created
+        let short = ZupyCard::from_slice(&buf[..ZUPY_CARD_SIZE_WITH_STATUS]);
+        assert_eq!(short.issued_at(), 1_700_000_000);
+    }
 }
@@ -2,24 +2,54 @@
 //!
 //! Provides account factories, instruction builders, and assertion helpers
 //! reused across all Mollusk test files.
+//!
+//! Each test binary compiles its own copy and uses a subset of it.
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+use std::rc::Rc;
 
 use mollusk_svm::Mollusk;
 use mollusk_svm::result::InstructionResult;
+use serde::{Deserialize, Serialize};
 use solana_account::Account;
 use solana_instruction::error::InstructionError;
 use solana_pubkey::Pubkey;
+use solana_svm_log_collector::LogCollector;
 
 use zupy_token_program::constants::{
     ATA_PROGRAM_ID, PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_STATE_SEED,
-    COMPANY_SEED, USER_SEED, INCENTIVE_POOL_SEED, DISTRIBUTION_POOL_SEED,
-    RATE_LIMIT_SEED, ZUPY_CARD_SEED, ZUPY_CARD_MINT_SEED, COUPON_SEED,
+    COMPANY_SEED, USER_SEED, INCENTIVE_POOL_SEED, DISTRIBUTION_POOL_SEED, ESCROW_SEED,
+    RECEIPT_SEED, VESTING_SEED, WITHDRAW_LIMIT_SEED, WITHDRAWAL_REQUEST_SEED,
+    RATE_LIMIT_SEED, ZUPY_CARD_SEED, ZUPY_CARD_MINT_SEED, COUPON_SEED, COUPON_STATE_SEED,
+    COMPANY_STATE_SEED, USER_STATE_SEED, STATS_SEED,
     TREASURY_WALLET_PUBKEY, MINT_AUTHORITY_PUBKEY,
-    BUBBLEGUM_PROGRAM_ID, SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID,
+    BUBBLEGUM_PROGRAM_ID, SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID, SPL_MEMO_PROGRAM_ID,
+    MPL_TOKEN_METADATA_PROGRAM_ID,
     LIGHT_COMPRESSED_TOKEN_PROGRAM_ID,
     LIGHT_SYSTEM_PROGRAM_ID, REGISTERED_PROGRAM_PDA,
     ACCOUNT_COMPRESSION_AUTHORITY, ACCOUNT_COMPRESSION_PROGRAM_ID,
 };
-use zupy_token_program::state::token_state::{TOKEN_STATE_DISCRIMINATOR, TOKEN_STATE_SIZE};
+use zupy_token_program::state::company_state::{COMPANY_STATE_DISCRIMINATOR, COMPANY_STATE_SIZE};
+use zupy_token_program::state::coupon_state::{COUPON_STATE_DISCRIMINATOR, COUPON_STATE_SIZE};
+use zupy_token_program::state::escrow_state::{ESCROW_STATE_DISCRIMINATOR, ESCROW_STATE_SIZE};
+use zupy_token_program::state::stats_state::{STATS_STATE_DISCRIMINATOR, STATS_STATE_SIZE};
+use zupy_token_program::state::rate_limit_state::{
+    RATE_LIMIT_STATE_DISCRIMINATOR, RATE_LIMIT_STATE_SIZE_WITH_NFT_WINDOW,
+};
+use zupy_token_program::state::receipt_state::{RECEIPT_STATE_DISCRIMINATOR, RECEIPT_STATE_SIZE};
+use zupy_token_program::state::token_state::{
+    TOKEN_STATE_DISCRIMINATOR, TOKEN_STATE_SIZE, TOKEN_STATE_SIZE_WITH_COUPON_COLLECTION,
+    TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION,
+};
+use zupy_token_program::state::user_state::{USER_STATE_DISCRIMINATOR, USER_STATE_SIZE};
+use zupy_token_program::state::vesting_state::{VESTING_STATE_DISCRIMINATOR, VESTING_STATE_SIZE};
+use zupy_token_program::state::withdraw_limit_state::{
+    WITHDRAW_LIMIT_STATE_DISCRIMINATOR, WITHDRAW_LIMIT_STATE_SIZE,
+};
+use zupy_token_program::state::withdrawal_request_state::{
+    WITHDRAWAL_REQUEST_STATE_DISCRIMINATOR, WITHDRAWAL_REQUEST_STATE_SIZE,
+};
 
 // ── Light Protocol PDA helpers ───────────────────────────────────────────
 
@@ -29,6 +59,23 @@ pub fn derive_spl_interface_pda(mint: &Pubkey) -> Pubkey {
     Pubkey::find_program_address(&[b"pool", mint.as_ref()], &ctoken_id).0
 }
 
+/// Canonical bump of the Light SPL interface PDA, sent as `spl_interface_bump`.
+pub fn derive_spl_interface_bump(mint: &Pubkey) -> u8 {
+    let ctoken_id = Pubkey::new_from_array(LIGHT_COMPRESSED_TOKEN_PROGRAM_ID);
+    Pubkey::find_program_address(&[b"pool", mint.as_ref()], &ctoken_id).1
+}
+
+/// `spl_interface_bump` value that makes the program derive the bump on-chain.
+pub const SPL_INTERFACE_BUMP_DERIVE: u8 = 0xFF;
+
+/// Derive the Token-2022 associated token account of (`wallet`, `mint`).
+pub fn derive_ata(wallet: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[wallet.as_ref(), token_2022_id().as_ref(), mint.as_ref()],
+        &ata_program_id(),
+    )
+}
+
 /// Derive the Light cToken CPI authority PDA (seeds: `["cpi_authority"]` on cToken program).
 pub fn derive_ctoken_authority() -> Pubkey {
     let ctoken_id = Pubkey::new_from_array(LIGHT_COMPRESSED_TOKEN_PROGRAM_ID);
@@ -65,6 +112,10 @@ pub fn bubblegum_program_id() -> Pubkey {
     Pubkey::new_from_array(BUBBLEGUM_PROGRAM_ID)
 }
 
+pub fn token_metadata_program_id() -> Pubkey {
+    Pubkey::new_from_array(MPL_TOKEN_METADATA_PROGRAM_ID)
+}
+
 pub fn ctoken_program_id() -> Pubkey {
     Pubkey::new_from_array(LIGHT_COMPRESSED_TOKEN_PROGRAM_ID)
 }
@@ -77,6 +128,10 @@ pub fn noop_program_id() -> Pubkey {
     Pubkey::new_from_array(SPL_NOOP_ID)
 }
 
+pub fn spl_memo_program_id() -> Pubkey {
+    Pubkey::new_from_array(SPL_MEMO_PROGRAM_ID)
+}
+
 pub fn light_system_program_id() -> Pubkey {
     Pubkey::new_from_array(LIGHT_SYSTEM_PROGRAM_ID)
 }
@@ -97,7 +152,18 @@ pub fn account_compression_program_id() -> Pubkey {
 
 /// Create Mollusk with just our program (for validation-path benchmarks).
 pub fn setup_mollusk() -> Mollusk {
-    Mollusk::new(&program_id(), "zupy_token_program")
+    let mut mollusk = Mollusk::new(&program_id(), "zupy_token_program");
+    let logs = LogCollector::new_ref_with_limit(None);
+    PROGRAM_LOGS.with(|current| *current.borrow_mut() = Rc::clone(&logs));
+    mollusk.logger = Some(logs);
+    mollusk
+}
+
+thread_local! {
+    /// Program log of the last Mollusk built by `setup_mollusk` on this test
+    /// thread (`InstructionResult` does not carry logs); read by `assert_logged`.
+    static PROGRAM_LOGS: RefCell<Rc<RefCell<LogCollector>>> =
+        RefCell::new(LogCollector::new_ref());
 }
 
 /// Create Mollusk with Token-2022, ATA, and System programs loaded.
@@ -120,11 +186,54 @@ pub fn derive_company_pda(company_id: u64) -> (Pubkey, u8) {
     Pubkey::find_program_address(&[COMPANY_SEED, &id_bytes], &program_id())
 }
 
+pub fn derive_company_state_pda(company_id: u64) -> (Pubkey, u8) {
+    let id_bytes = company_id.to_le_bytes();
+    Pubkey::find_program_address(&[COMPANY_STATE_SEED, &id_bytes], &program_id())
+}
+
+pub fn derive_user_state_pda(user_id: u64) -> (Pubkey, u8) {
+    let id_bytes = user_id.to_le_bytes();
+    Pubkey::find_program_address(&[USER_STATE_SEED, &id_bytes], &program_id())
+}
+
+pub fn derive_stats_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STATS_SEED], &program_id())
+}
+
 pub fn derive_user_pda(user_id: u64) -> (Pubkey, u8) {
     let id_bytes = user_id.to_le_bytes();
     Pubkey::find_program_address(&[USER_SEED, &id_bytes], &program_id())
 }
 
+pub fn derive_escrow_pda(escrow_id: u64) -> (Pubkey, u8) {
+    let id_bytes = escrow_id.to_le_bytes();
+    Pubkey::find_program_address(&[ESCROW_SEED, &id_bytes], &program_id())
+}
+
+pub fn derive_vesting_pda(user_id: u64) -> (Pubkey, u8) {
+    let id_bytes = user_id.to_le_bytes();
+    Pubkey::find_program_address(&[VESTING_SEED, &id_bytes], &program_id())
+}
+
+pub fn derive_receipt_pda(op_id: &[u8; 16]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RECEIPT_SEED, op_id], &program_id())
+}
+
+pub fn derive_withdraw_limit_pda(user_id: u64) -> (Pubkey, u8) {
+    let id_bytes = user_id.to_le_bytes();
+    Pubkey::find_program_address(&[WITHDRAW_LIMIT_SEED, &id_bytes], &program_id())
+}
+
+pub fn derive_withdrawal_request_pda(request_id: u64) -> (Pubkey, u8) {
+    let id_bytes = request_id.to_le_bytes();
+    Pubkey::find_program_address(&[WITHDRAWAL_REQUEST_SEED, &id_bytes], &program_id())
+}
+
+/// Bubblegum tree_config PDA of `merkle_tree`.
+pub fn derive_tree_config_pda(merkle_tree: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[merkle_tree.as_ref()], &bubblegum_program_id()).0
+}
+
 pub fn derive_incentive_pool_pda() -> (Pubkey, u8) {
     Pubkey::find_program_address(&[INCENTIVE_POOL_SEED], &program_id())
 }
@@ -149,6 +258,10 @@ pub fn derive_coupon_pda(coupon_ksuid: &[u8; 27]) -> (Pubkey, u8) {
     Pubkey::find_program_address(&[COUPON_SEED, coupon_ksuid], &program_id())
 }
 
+pub fn derive_coupon_state_pda(coupon_ksuid: &[u8; 27]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[COUPON_STATE_SEED, coupon_ksuid], &program_id())
+}
+
 // We also need user_pda derived with b"user_pda" seed (different from USER_SEED)
 pub fn derive_user_pda_by_ksuid(user_ksuid: &[u8; 27]) -> (Pubkey, u8) {
     Pubkey::find_program_address(&[b"user_pda", user_ksuid], &program_id())
@@ -158,6 +271,7 @@ pub fn derive_user_pda_by_ksuid(user_ksuid: &[u8; 27]) -> (Pubkey, u8) {
 
 /// Create a TokenState account data buffer (TOKEN_STATE_SIZE bytes).
 /// This is the full-featured version with all fields configurable.
+#[allow(clippy::too_many_arguments)]
 pub fn make_token_state_data(
     treasury: &Pubkey,
     mint_authority: &Pubkey,
@@ -192,6 +306,22 @@ pub fn make_token_state_data(
     data
 }
 
+/// Extend TokenState data to `TOKEN_STATE_SIZE_WITH_COUPON_COLLECTION` with
+/// `coupon_collection` set to `collection_mint`.
+pub fn with_coupon_collection(mut token_state: Vec<u8>, collection_mint: &Pubkey) -> Vec<u8> {
+    token_state.resize(TOKEN_STATE_SIZE_WITH_COUPON_COLLECTION, 0);
+    token_state[373..405].copy_from_slice(collection_mint.as_ref());
+    token_state
+}
+
+/// Extend TokenState data to `TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION` with
+/// `strict_registration` set to `enabled`.
+pub fn with_strict_registration(mut token_state: Vec<u8>, enabled: bool) -> Vec<u8> {
+    token_state.resize(TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION, 0);
+    token_state[405] = enabled as u8;
+    token_state
+}
+
 /// Simplified token_state builder for tests that only need transfer fields.
 pub fn make_transfer_token_state(
     transfer_auth: &Pubkey,
@@ -208,7 +338,182 @@ pub fn make_transfer_token_state(
     )
 }
 
+/// Build EscrowState account data (51 bytes) with the given fields.
+#[allow(clippy::too_many_arguments)]
+pub fn make_escrow_data(
+    escrow_id: u64,
+    source_kind: u8,
+    source_id: u64,
+    recipient_user_id: u64,
+    amount: u64,
+    expiry: i64,
+    status: u8,
+    bump: u8,
+) -> Vec<u8> {
+    let mut data = vec![0u8; ESCROW_STATE_SIZE];
+    data[0..8].copy_from_slice(&ESCROW_STATE_DISCRIMINATOR);
+    data[8..16].copy_from_slice(&escrow_id.to_le_bytes());
+    data[16] = source_kind;
+    data[17..25].copy_from_slice(&source_id.to_le_bytes());
+    data[25..33].copy_from_slice(&recipient_user_id.to_le_bytes());
+    data[33..41].copy_from_slice(&amount.to_le_bytes());
+    data[41..49].copy_from_slice(&expiry.to_le_bytes());
+    data[49] = status;
+    data[50] = bump;
+    data
+}
+
+/// Build VestingState account data (49 bytes) with the given fields.
+pub fn make_vesting_data(
+    user_id: u64,
+    total_amount: u64,
+    start_ts: i64,
+    duration: u64,
+    claimed: u64,
+    bump: u8,
+) -> Vec<u8> {
+    let mut data = vec![0u8; VESTING_STATE_SIZE];
+    data[0..8].copy_from_slice(&VESTING_STATE_DISCRIMINATOR);
+    data[8..16].copy_from_slice(&user_id.to_le_bytes());
+    data[16..24].copy_from_slice(&total_amount.to_le_bytes());
+    data[24..32].copy_from_slice(&start_ts.to_le_bytes());
+    data[32..40].copy_from_slice(&duration.to_le_bytes());
+    data[40..48].copy_from_slice(&claimed.to_le_bytes());
+    data[48] = bump;
+    data
+}
+
+/// Build WithdrawLimitState account data (41 bytes) with the given fields.
+pub fn make_withdraw_limit_data(
+    user_id: u64,
+    daily_limit: u64,
+    consumed: u64,
+    window_start: i64,
+    bump: u8,
+) -> Vec<u8> {
+    let mut data = vec![0u8; WITHDRAW_LIMIT_STATE_SIZE];
+    data[0..8].copy_from_slice(&WITHDRAW_LIMIT_STATE_DISCRIMINATOR);
+    data[8..16].copy_from_slice(&user_id.to_le_bytes());
+    data[16..24].copy_from_slice(&daily_limit.to_le_bytes());
+    data[24..32].copy_from_slice(&consumed.to_le_bytes());
+    data[32..40].copy_from_slice(&window_start.to_le_bytes());
+    data[40] = bump;
+    data
+}
+
+/// Build WithdrawalRequestState account data (105 bytes) with the given fields.
+pub fn make_withdrawal_request_data(
+    request_id: u64,
+    user_id: u64,
+    amount: u64,
+    destination: &Pubkey,
+    payer: &Pubkey,
+    requested_at: i64,
+    bump: u8,
+) -> Vec<u8> {
+    let mut data = vec![0u8; WITHDRAWAL_REQUEST_STATE_SIZE];
+    data[0..8].copy_from_slice(&WITHDRAWAL_REQUEST_STATE_DISCRIMINATOR);
+    data[8..16].copy_from_slice(&request_id.to_le_bytes());
+    data[16..24].copy_from_slice(&user_id.to_le_bytes());
+    data[24..32].copy_from_slice(&amount.to_le_bytes());
+    data[32..64].copy_from_slice(destination.as_ref());
+    data[64..96].copy_from_slice(payer.as_ref());
+    data[96..104].copy_from_slice(&requested_at.to_le_bytes());
+    data[104] = bump;
+    data
+}
+
+/// Build ReceiptState account data (65 bytes) with the given fields.
+pub fn make_receipt_data(op_id: &[u8; 16], payer: &Pubkey, created_at: i64, bump: u8) -> Vec<u8> {
+    let mut data = vec![0u8; RECEIPT_STATE_SIZE];
+    data[0..8].copy_from_slice(&RECEIPT_STATE_DISCRIMINATOR);
+    data[8..24].copy_from_slice(op_id);
+    data[24..56].copy_from_slice(payer.as_ref());
+    data[56..64].copy_from_slice(&created_at.to_le_bytes());
+    data[64] = bump;
+    data
+}
+
+/// Build CouponState account data (141 bytes) with the given fields.
+/// `value` is `(value_amount, issuer_company_id)`.
+#[allow(clippy::too_many_arguments)]
+pub fn make_coupon_state_data(
+    coupon_ksuid: &[u8; 27],
+    mint: &Pubkey,
+    redeemed: bool,
+    expires_at: i64,
+    value: (u64, u64),
+    holder: &Pubkey,
+    bump: u8,
+) -> Vec<u8> {
+    let mut data = vec![0u8; COUPON_STATE_SIZE];
+    data[0..8].copy_from_slice(&COUPON_STATE_DISCRIMINATOR);
+    data[8..35].copy_from_slice(coupon_ksuid);
+    data[35..67].copy_from_slice(mint.as_ref());
+    data[67] = redeemed as u8;
+    data[84..92].copy_from_slice(&expires_at.to_le_bytes());
+    data[92..100].copy_from_slice(&value.0.to_le_bytes());
+    data[100..108].copy_from_slice(&value.1.to_le_bytes());
+    data[108..140].copy_from_slice(holder.as_ref());
+    data[140] = bump;
+    data
+}
+
+/// Build RateLimitState account data (73 bytes) for `authority` with the
+/// given NFT mint window; token counters are zero.
+pub fn make_rate_limit_data(
+    authority: &Pubkey,
+    bump: u8,
+    nft_window_start: i64,
+    nft_mints: u64,
+) -> Vec<u8> {
+    let mut data = vec![0u8; RATE_LIMIT_STATE_SIZE_WITH_NFT_WINDOW];
+    data[0..8].copy_from_slice(&RATE_LIMIT_STATE_DISCRIMINATOR);
+    data[8..40].copy_from_slice(authority.as_ref());
+    data[56] = bump;
+    data[57..65].copy_from_slice(&nft_window_start.to_le_bytes());
+    data[65..73].copy_from_slice(&nft_mints.to_le_bytes());
+    data
+}
+
+/// Build CompanyState account data (50 bytes) for `company_id` with the
+/// canonical bump and zeroed balance counters.
+pub fn make_company_state_data(company_id: u64, created_at: i64, flags: u8) -> Vec<u8> {
+    let (_, bump) = derive_company_state_pda(company_id);
+    let mut data = vec![0u8; COMPANY_STATE_SIZE];
+    data[0..8].copy_from_slice(&COMPANY_STATE_DISCRIMINATOR);
+    data[8..16].copy_from_slice(&company_id.to_le_bytes());
+    data[16..24].copy_from_slice(&created_at.to_le_bytes());
+    data[24] = flags;
+    data[25] = bump;
+    data
+}
+
+/// Build UserState account data (26 bytes) for `user_id` with the canonical
+/// bump, registered at `created_at` with `status`.
+pub fn make_user_state_data(user_id: u64, created_at: i64, status: u8) -> Vec<u8> {
+    let (_, bump) = derive_user_state_pda(user_id);
+    let mut data = vec![0u8; USER_STATE_SIZE];
+    data[0..8].copy_from_slice(&USER_STATE_DISCRIMINATOR);
+    data[8..16].copy_from_slice(&user_id.to_le_bytes());
+    data[16..24].copy_from_slice(&created_at.to_le_bytes());
+    data[24] = status;
+    data[25] = bump;
+    data
+}
+
+/// Build StatsState account data (49 bytes) with the canonical bump and
+/// zeroed counters.
+pub fn make_stats_data() -> Vec<u8> {
+    let (_, bump) = derive_stats_pda();
+    let mut data = vec![0u8; STATS_STATE_SIZE];
+    data[0..8].copy_from_slice(&STATS_STATE_DISCRIMINATOR);
+    data[48] = bump;
+    data
+}
+
 /// Token_state builder for split-transfer tests (needs treasury + incentive_pool).
+#[allow(clippy::too_many_arguments)]
 pub fn make_split_token_state(
     treasury: &Pubkey,
     transfer_auth: &Pubkey,
@@ -255,6 +560,23 @@ pub fn make_mint_data(authority: &Pubkey, supply: u64, decimals: u8) -> Vec<u8>
     data
 }
 
+/// Create a Token-2022 mint data buffer (202 bytes) carrying the
+/// MintCloseAuthority extension, as `create_coupon_nft` creates coupon mints.
+pub fn make_closable_mint_data(
+    authority: &Pubkey,
+    supply: u64,
+    decimals: u8,
+    close_authority: &Pubkey,
+) -> Vec<u8> {
+    let mut data = make_mint_data(authority, supply, decimals);
+    data.resize(202, 0);
+    data[165] = 1; // AccountType::Mint
+    data[166..168].copy_from_slice(&3u16.to_le_bytes()); // ExtensionType::MintCloseAuthority
+    data[168..170].copy_from_slice(&32u16.to_le_bytes());
+    data[170..202].copy_from_slice(close_authority.as_ref());
+    data
+}
+
 /// Create a system account (for signers, payers, etc.).
 pub fn make_system_account(lamports: u64) -> Account {
     Account {
@@ -333,6 +655,24 @@ pub fn assert_ix_custom_err(result: &InstructionResult, expected_code: u32) {
     );
 }
 
+/// Program log lines recorded by the Mollusk from the latest `setup_mollusk` call.
+pub fn program_logs() -> Vec<String> {
+    PROGRAM_LOGS.with(|current| current.borrow().borrow().get_recorded_content().to_vec())
+}
+
+/// Assert the program logged `line` (e.g. a `zupy_evt:` supply event) on the
+/// Mollusk instance from the latest `setup_mollusk` call.
+pub fn assert_logged(result: &InstructionResult, line: &str) {
+    let logs = program_logs();
+    assert!(
+        logs.iter().any(|l| l.contains(line)),
+        "Expected log line {:?} (result {:?}), got {:#?}",
+        line,
+        result.program_result,
+        logs,
+    );
+}
+
 /// Assert Mollusk result is NotEnoughAccountKeys.
 #[allow(deprecated)] // solana_instruction renamed to MissingAccount, but runtime still returns this
 pub fn assert_ix_not_enough_keys(result: &InstructionResult) {
@@ -347,12 +687,17 @@ pub fn assert_ix_not_enough_keys(result: &InstructionResult) {
 // ── CU benchmark result tracking ─────────────────────────────────────────
 
 /// CU measurement result for a single instruction benchmark.
-#[derive(Clone, Debug)]
+///
+/// Serialized field names (`cu`, `threshold`) are the JSON schema CI trends
+/// on; see [`CuReport`].
+#[derive(Clone, Debug, Serialize)]
 pub struct CuResult {
     pub name: &'static str,
     pub classification: &'static str,
     pub anchor_est: u64,
+    #[serde(rename = "cu")]
     pub pinocchio_cu: u64,
+    #[serde(rename = "threshold")]
     pub max_allowed: u64,
     pub passed: bool,
     pub note: String,
@@ -372,3 +717,60 @@ impl CuResult {
         self.pinocchio_cu as f64 > self.max_allowed as f64 * 0.8
     }
 }
+
+/// Env var naming the file `test_cu_benchmark_report` writes its JSON report
+/// to. Unset: nothing is written.
+pub const BENCH_OUT_ENV: &str = "ZUPY_BENCH_OUT";
+
+/// JSON document written to [`BENCH_OUT_ENV`].
+#[derive(Debug, Serialize)]
+pub struct CuReport<'a> {
+    /// `ZUPY_GIT_HASH`, else `git rev-parse HEAD`, else `"unknown"`.
+    pub git_hash: String,
+    /// Size of `zupy_token_program.so` in bytes (0 if not built).
+    pub binary_size: u64,
+    pub results: &'a [CuResult],
+}
+
+/// Owned mirror of [`CuReport`] for reading an emitted report back.
+#[derive(Debug, Deserialize)]
+pub struct CuReportRecord {
+    pub git_hash: String,
+    pub binary_size: u64,
+    pub results: Vec<CuResultRecord>,
+}
+
+/// Owned mirror of [`CuResult`]'s serialized form.
+#[derive(Debug, Deserialize)]
+pub struct CuResultRecord {
+    pub name: String,
+    pub classification: String,
+    pub anchor_est: u64,
+    pub cu: u64,
+    pub threshold: u64,
+    pub passed: bool,
+    pub note: String,
+}
+
+/// Git hash recorded in the report.
+pub fn bench_git_hash() -> String {
+    if let Ok(hash) = std::env::var("ZUPY_GIT_HASH") {
+        return hash;
+    }
+    std::process::Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Write the report as JSON to `path`.
+pub fn write_cu_report(path: &std::path::Path, results: &[CuResult], binary_size: u64) {
+    let report = CuReport { git_hash: bench_git_hash(), binary_size, results };
+    let json = serde_json::to_string_pretty(&report).expect("serialize CU report");
+    std::fs::write(path, json)
+        .unwrap_or_else(|e| panic!("failed to write CU report to {}: {}", path.display(), e));
+}
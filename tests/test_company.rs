@@ -0,0 +1,235 @@
+//! Mollusk integration tests for the company registry.
+//!
+//! Tests:
+//!   - initialize_company (4 accounts, treasury / transfer_authority signed,
+//!     CompanyState creation, re-initialization rejection)
+//!   - close_company (treasury only, zero-balance guard, rent reclamation)
+//!
+//! Requires `cargo build-sbf` before running:
+//!   cargo build-sbf && cargo test --test test_company -- --nocapture
+
+mod helpers;
+use helpers::*;
+
+use solana_account::Account;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+
+use zupy_token_program::state::company_state::{COMPANY_STATE_DISCRIMINATOR, COMPANY_STATE_SIZE};
+
+const DISC_INITIALIZE_COMPANY: [u8; 8] = [75, 156, 55, 94, 184, 64, 58, 30];
+const DISC_CLOSE_COMPANY: [u8; 8] = [199, 184, 167, 41, 63, 245, 165, 93];
+
+const ERR_INVALID_AUTHORITY: u32 = 6000;
+const ERR_ALREADY_INITIALIZED: u32 = 6003;
+const ERR_INVALID_PDA: u32 = 6007;
+const ERR_NOT_INITIALIZED: u32 = 6010;
+const ERR_UNAUTHORIZED_TREASURY: u32 = 6019;
+const ERR_COMPANY_NOT_EMPTY: u32 = 6065;
+
+const COMPANY_ID: u64 = 42;
+const NOW: i64 = 1_700_000_000;
+
+fn empty_account() -> Account {
+    Account { lamports: 0, data: vec![], owner: Pubkey::default(), executable: false, rent_epoch: 0 }
+}
+
+struct Fixture {
+    transfer_auth: Pubkey,
+    metas: Vec<AccountMeta>,
+    accounts: Vec<(Pubkey, Account)>,
+}
+
+/// System program loaded, company 42 unregistered, treasury signing.
+fn setup() -> Fixture {
+    let (token_state_pda, bump) = derive_token_state_pda();
+    let (company_state, _) = derive_company_state_pda(COMPANY_ID);
+    let treasury = treasury_wallet();
+    let transfer_auth = Pubkey::new_unique();
+    let dummy = Pubkey::new_unique();
+    let ts_data = make_token_state_data(
+        &treasury, &mint_authority(), &transfer_auth, &dummy, &dummy, &dummy, &dummy,
+        &dummy, bump, true, false,
+    );
+
+    let metas = vec![
+        AccountMeta::new(treasury, true),
+        AccountMeta::new_readonly(token_state_pda, false),
+        AccountMeta::new(company_state, false),
+        AccountMeta::new_readonly(system_program_id(), false),
+    ];
+    let accounts = vec![
+        (treasury, make_system_account(1_000_000_000)),
+        (token_state_pda, make_program_account(ts_data, 1_000_000)),
+        (company_state, empty_account()),
+        mollusk_svm::program::keyed_account_for_system_program(),
+    ];
+    Fixture { transfer_auth, metas, accounts }
+}
+
+/// Makes `signer` account 0.
+fn signed_by(mut f: Fixture, signer: Pubkey) -> Fixture {
+    f.metas[0] = AccountMeta::new(signer, true);
+    f.accounts[0] = (signer, make_system_account(1_000_000_000));
+    f
+}
+
+fn run(f: &Fixture, company_id: u64, flags: u8) -> mollusk_svm::result::InstructionResult {
+    let mut mollusk = setup_mollusk();
+    mollusk.sysvars.clock.unix_timestamp = NOW;
+    let mut payload = company_id.to_le_bytes().to_vec();
+    payload.push(flags);
+    let data = build_ix_data(&DISC_INITIALIZE_COMPANY, &payload);
+    let instruction = Instruction::new_with_bytes(program_id(), &data, f.metas.clone());
+    mollusk.process_instruction(&instruction, &f.accounts)
+}
+
+#[test]
+fn test_treasury_registers_company() {
+    let f = setup();
+    let result = run(&f, COMPANY_ID, 0b0000_0001);
+    assert!(result.program_result.is_ok(), "initialize failed: {:?}", result.program_result);
+
+    let (_, bump) = derive_company_state_pda(COMPANY_ID);
+    let state = &result.resulting_accounts[2].1;
+    assert_eq!(state.owner, program_id());
+    assert_eq!(state.data.len(), COMPANY_STATE_SIZE);
+    assert_eq!(&state.data[0..8], &COMPANY_STATE_DISCRIMINATOR);
+    assert_eq!(u64::from_le_bytes(state.data[8..16].try_into().unwrap()), COMPANY_ID);
+    assert_eq!(i64::from_le_bytes(state.data[16..24].try_into().unwrap()), NOW);
+    assert_eq!(state.data[24], 0b0000_0001);
+    assert_eq!(state.data[25], bump);
+    assert!(state.data[26..50].iter().all(|b| *b == 0), "counters start at zero");
+}
+
+#[test]
+fn test_transfer_authority_registers_company() {
+    let f = setup();
+    let transfer_auth = f.transfer_auth;
+    let f = signed_by(f, transfer_auth);
+    let result = run(&f, COMPANY_ID, 0);
+    assert!(result.program_result.is_ok(), "initialize failed: {:?}", result.program_result);
+}
+
+#[test]
+fn test_reinitialization_rejected() {
+    let mut f = setup();
+    let first = run(&f, COMPANY_ID, 0);
+    assert!(first.program_result.is_ok(), "initialize failed: {:?}", first.program_result);
+
+    f.accounts = first.resulting_accounts;
+    assert_ix_custom_err(&run(&f, COMPANY_ID, 0), ERR_ALREADY_INITIALIZED);
+}
+
+#[test]
+fn test_rejects_other_signer() {
+    let f = signed_by(setup(), Pubkey::new_unique());
+    assert_ix_custom_err(&run(&f, COMPANY_ID, 0), ERR_INVALID_AUTHORITY);
+}
+
+#[test]
+fn test_rejects_mint_authority() {
+    let f = signed_by(setup(), mint_authority());
+    assert_ix_custom_err(&run(&f, COMPANY_ID, 0), ERR_INVALID_AUTHORITY);
+}
+
+#[test]
+fn test_rejects_other_company_pda() {
+    // company_state of company 42 passed for company 43
+    let f = setup();
+    assert_ix_custom_err(&run(&f, COMPANY_ID + 1, 0), ERR_INVALID_PDA);
+}
+
+#[test]
+fn test_company_pda_is_not_the_registry() {
+    let mut f = setup();
+    let (company_pda, _) = derive_company_pda(COMPANY_ID);
+    f.metas[2] = AccountMeta::new(company_pda, false);
+    f.accounts[2] = (company_pda, empty_account());
+    assert_ix_custom_err(&run(&f, COMPANY_ID, 0), ERR_INVALID_PDA);
+}
+
+// ── close_company ───────────────────────────────────────────────────────
+
+const COMPANY_STATE_RENT: u64 = 1_238_880;
+
+/// Registered company 42 with counters `(received, sent, burned)`.
+fn registered(received: u64, sent: u64, burned: u64) -> Account {
+    let mut data = make_company_state_data(COMPANY_ID, NOW, 0);
+    data[26..34].copy_from_slice(&received.to_le_bytes());
+    data[34..42].copy_from_slice(&sent.to_le_bytes());
+    data[42..50].copy_from_slice(&burned.to_le_bytes());
+    make_program_account(data, COMPANY_STATE_RENT)
+}
+
+fn run_close(f: &Fixture, company_id: u64, attested: bool) -> mollusk_svm::result::InstructionResult {
+    let mollusk = setup_mollusk();
+    let mut payload = company_id.to_le_bytes().to_vec();
+    payload.push(attested as u8);
+    let data = build_ix_data(&DISC_CLOSE_COMPANY, &payload);
+    let instruction = Instruction::new_with_bytes(program_id(), &data, f.metas[..3].to_vec());
+    mollusk.process_instruction(&instruction, &f.accounts[..3])
+}
+
+/// setup() with company 42's CompanyState already registered.
+fn setup_registered(company_state: Account) -> Fixture {
+    let mut f = setup();
+    f.accounts[2].1 = company_state;
+    f
+}
+
+#[test]
+fn test_close_reclaims_rent_to_treasury() {
+    let f = setup_registered(registered(1_000, 700, 300));
+    let treasury_before = f.accounts[0].1.lamports;
+    let result = run_close(&f, COMPANY_ID, true);
+    assert!(result.program_result.is_ok(), "close failed: {:?}", result.program_result);
+
+    assert_eq!(result.resulting_accounts[0].1.lamports, treasury_before + COMPANY_STATE_RENT);
+    let closed = &result.resulting_accounts[2].1;
+    assert_eq!(closed.lamports, 0);
+    assert!(closed.data.is_empty(), "CompanyState must be closed");
+}
+
+#[test]
+fn test_closed_company_can_register_again() {
+    let mut f = setup_registered(registered(0, 0, 0));
+    let closed = run_close(&f, COMPANY_ID, true);
+    assert!(closed.program_result.is_ok(), "close failed: {:?}", closed.program_result);
+
+    f.accounts[..3].clone_from_slice(&closed.resulting_accounts);
+    let result = run(&f, COMPANY_ID, 0);
+    assert!(result.program_result.is_ok(), "re-register failed: {:?}", result.program_result);
+}
+
+#[test]
+fn test_close_rejects_recorded_balance() {
+    let f = setup_registered(registered(1_000, 700, 299));
+    assert_ix_custom_err(&run_close(&f, COMPANY_ID, true), ERR_COMPANY_NOT_EMPTY);
+}
+
+#[test]
+fn test_close_requires_attestation() {
+    let f = setup_registered(registered(0, 0, 0));
+    assert_ix_custom_err(&run_close(&f, COMPANY_ID, false), ERR_COMPANY_NOT_EMPTY);
+}
+
+#[test]
+fn test_close_rejects_transfer_authority() {
+    let f = setup_registered(registered(0, 0, 0));
+    let transfer_auth = f.transfer_auth;
+    let f = signed_by(f, transfer_auth);
+    assert_ix_custom_err(&run_close(&f, COMPANY_ID, true), ERR_UNAUTHORIZED_TREASURY);
+}
+
+#[test]
+fn test_close_unregistered_company() {
+    let f = setup();
+    assert_ix_custom_err(&run_close(&f, COMPANY_ID, true), ERR_NOT_INITIALIZED);
+}
+
+#[test]
+fn test_close_rejects_other_company_pda() {
+    let f = setup_registered(registered(0, 0, 0));
+    assert_ix_custom_err(&run_close(&f, COMPANY_ID + 1, true), ERR_INVALID_PDA);
+}
@@ -65,7 +65,7 @@ fn test_all_instructions_execute_with_cu_measurement() {
     println!("--- End CU Benchmark ---\n");
 }
 
-/// Unknown discriminator returns InvalidInstructionData via Mollusk.
+/// Unknown discriminator returns UnknownInstruction and logs the bytes via Mollusk.
 #[test]
 fn test_unknown_discriminator_via_mollusk() {
     let mollusk = setup_mollusk();
@@ -73,7 +73,43 @@ fn test_unknown_discriminator_via_mollusk() {
 
     let instruction = Instruction::new_with_bytes(pid, &[255u8; 8], vec![]);
     let result = mollusk.process_instruction(&instruction, &[]);
-    assert!(result.program_result.is_err());
+    assert_ix_custom_err(&result, 6077);
+    assert_logged(&result, "zupy_err:UnknownInstruction:ffffffffffffffff");
+}
+
+/// With `debug-dispatch` the log opens with the dispatched instruction name.
+/// Needs the binary built with the feature too:
+///   cargo build-sbf --features debug-dispatch && cargo test --features debug-dispatch --test test_entrypoint
+#[cfg(feature = "debug-dispatch")]
+#[test]
+fn test_debug_dispatch_logs_instruction_name() {
+    let mollusk = setup_mollusk();
+    let pid = program_id();
+
+    for (disc, name) in &DISCRIMINATORS {
+        let instruction = Instruction::new_with_bytes(pid, disc, vec![]);
+        let result = mollusk.process_instruction(&instruction, &[]);
+        assert_logged(&result, &format!("zupy_ix:{}", name));
+    }
+}
+
+/// get_version needs no accounts and returns the 12-byte build version.
+#[test]
+fn test_get_version_return_data() {
+    use zupy_token_program::instructions::get_version::{ProgramVersion, PROGRAM_VERSION};
+
+    let mollusk = setup_mollusk();
+    let pid = program_id();
+
+    let instruction = Instruction::new_with_bytes(pid, &[168, 85, 244, 45, 81, 56, 130, 50], vec![]);
+    let result = mollusk.process_instruction(&instruction, &[]);
+    assert!(result.program_result.is_ok(), "{:?}", result.program_result);
+    assert_eq!(result.return_data.len(), ProgramVersion::LEN);
+    let version = ProgramVersion::from_bytes(&result.return_data).unwrap();
+    assert_eq!(version.semver, PROGRAM_VERSION.semver);
+    assert_eq!(version.layout_version, PROGRAM_VERSION.layout_version);
+    println!("get_version CU: {}", result.compute_units_consumed);
+    assert!(result.compute_units_consumed < 1_000);
 }
 
 /// Short instruction data returns error via Mollusk.
@@ -0,0 +1,265 @@
+//! Mollusk tests for strict instruction-data parsing.
+//!
+//! Every handler rejects bytes after its declared fields with
+//! `InvalidInstructionData` (see `helpers::instruction_data::expect_end`),
+//! before any account is validated. Each case below sends a well-formed
+//! payload against placeholder accounts, then the same payload plus one byte.
+//! The transfer and withdraw payloads are also cut short at every byte, which
+//! must fail the same way (`helpers::instruction_data::Reader`).
+//!
+//! The V1 passthroughs (`*_v1`, `transfer_company_to_user`, `return_*_v1`)
+//! forward their tail to Light as raw CPI data and are not covered here.
+//!
+//! Requires `cargo build-sbf` before running:
+//!   cargo build-sbf && cargo test --test test_instruction_data
+
+mod helpers;
+
+use helpers::*;
+use solana_account::Account;
+use solana_instruction::error::InstructionError;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+
+const KSUID_A: [u8; 27] = *b"2NRjKcGrXHKtGVjMXV7qptaXY2A";
+const KSUID_B: [u8; 27] = *b"2NRjKcGrXHKtGVjMXV7qptaXY2B";
+const KSUID_C: [u8; 27] = *b"2NRjKcGrXHKtGVjMXV7qptaXY2C";
+
+/// Borsh `String`: u32 LE length + UTF-8 bytes.
+fn string(s: &str) -> Vec<u8> {
+    let mut out = (s.len() as u32).to_le_bytes().to_vec();
+    out.extend_from_slice(s.as_bytes());
+    out
+}
+
+fn u64_le(v: u64) -> Vec<u8> {
+    v.to_le_bytes().to_vec()
+}
+
+fn u16_le(v: u16) -> Vec<u8> {
+    v.to_le_bytes().to_vec()
+}
+
+fn u32_le(v: u32) -> Vec<u8> {
+    v.to_le_bytes().to_vec()
+}
+
+/// One case: instruction name, discriminator, account count, well-formed payload.
+struct Case {
+    name: &'static str,
+    disc: [u8; 8],
+    accounts: usize,
+    payload: Vec<u8>,
+}
+
+fn case(name: &'static str, disc: [u8; 8], accounts: usize, parts: &[&[u8]]) -> Case {
+    Case { name, disc, accounts, payload: parts.concat() }
+}
+
+/// Every handler with a fully declared payload, with the data it accepts.
+fn cases() -> Vec<Case> {
+    let memo = string("zupy:v1:test:1");
+    let uri = string("https://zupy.com/meta.json");
+    let op_id = [7u8; 16];
+    let nonce_trailer = [&[1u8][..], &u64_le(5)].concat();
+    let leaf = [[1u8; 32], [2u8; 32], [3u8; 32]].concat();
+    let merge = merge_inputs();
+
+    vec![
+        case("initialize_token", [38, 209, 150, 50, 190, 117, 16, 54], 8,
+            &[&[1u8; 32], &[2u8; 32], &[3u8; 32], &u64_le(1_000_000), &[6]]),
+        case("initialize_metadata", [35, 215, 241, 156, 122, 208, 206, 212], 4,
+            &[&string("Zupy"), &string("ZUPY"), &uri]),
+        case("update_metadata_field", [103, 217, 144, 202, 46, 70, 233, 141], 4,
+            &[&[0], &string("Zupy")]),
+        case("mint_tokens", [59, 132, 24, 246, 122, 39, 8, 243], 5, &[&u64_le(1_000), &memo]),
+        case("treasury_restock_pool", [94, 62, 103, 106, 93, 87, 173, 24], 6,
+            &[&u64_le(1_000), &memo]),
+        case("transfer_from_pool", [136, 167, 45, 66, 74, 252, 0, 16], 17,
+            &[&u64_le(1_000), &memo, &[0xFF], &op_id, &nonce_trailer]),
+        case("return_to_pool", [36, 85, 39, 183, 30, 172, 176, 72], 11,
+            &[&u64_le(42), &u64_le(1_000), &[254], &memo]),
+        case("transfer_user_to_company", [186, 233, 22, 40, 87, 223, 252, 131], 8,
+            &[&u64_le(1), &u64_le(2), &u64_le(1_000), &[254, 253], &memo]),
+        case("execute_split_transfer", [51, 254, 61, 214, 234, 138, 101, 214], 9,
+            &[&u64_le(1), &u64_le(2), &u64_le(1_000), &[254, 253, 252], &string("purchase"), &u16_le(500)]),
+        case("burn_tokens", [76, 15, 51, 254, 229, 215, 121, 66], 6, &[&u64_le(1_000), &memo]),
+        case("burn_from_company_pda", [43, 207, 204, 77, 74, 93, 165, 34], 7,
+            &[&u64_le(2), &u64_le(1_000), &memo]),
+        case("initialize_rate_limit", [36, 132, 34, 217, 150, 48, 192, 165], 3, &[]),
+        case("set_paused", [91, 60, 125, 192, 176, 225, 166, 218], 2, &[&[1]]),
+        case("create_zupy_card", [92, 114, 17, 0, 219, 121, 112, 150], 9, &[&KSUID_A, &uri]),
+        case("create_coupon_nft", [5, 106, 153, 76, 114, 157, 63, 236], 9,
+            &[&KSUID_A, &KSUID_B, &uri, &u64_le(1_900_000_000), &u64_le(5_000), &u64_le(2)]),
+        case("mint_coupon_cnft", [75, 5, 206, 155, 96, 133, 98, 15], 15,
+            &[&string("Coupon"), &string("ZCPN"), &uri, &u16_le(500), &[1], &[9u8; 32], &[100]]),
+        case("withdraw_to_external", [114, 198, 185, 119, 169, 163, 29, 251], 13,
+            &[&u64_le(1_000), &u64_le(1), &[254], &memo, &[0xFF], &op_id, &[1, 255]]),
+        case("return_user_to_pool", [151, 33, 221, 193, 7, 214, 10, 199], 11,
+            &[&u64_le(1), &u64_le(1_000), &[254], &memo]),
+        case("batch_transfer_from_pool", [11, 232, 149, 72, 195, 180, 19, 106], 17,
+            &[&memo, &u32_le(1), &[0], &u64_le(1_000)]),
+        case("transfer_user_to_user", [180, 29, 108, 140, 251, 43, 31, 123], 8,
+            &[&u64_le(1), &u64_le(2), &u64_le(1_000), &[254, 253], &memo]),
+        case("transfer_company_to_company", [42, 241, 16, 155, 103, 235, 235, 117], 8,
+            &[&u64_le(1), &u64_le(2), &u64_le(1_000), &[254, 253], &memo]),
+        case("set_split_range", [192, 112, 184, 242, 22, 8, 14, 63], 2, &[&u16_le(100), &u16_le(900)]),
+        case("rebalance_pools", [85, 169, 193, 206, 239, 180, 209, 253], 16,
+            &[&[0], &u64_le(1_000), &[254], &memo]),
+        case("distribute_incentive", [172, 211, 121, 130, 226, 29, 17, 199], 8,
+            &[&u64_le(1), &u64_le(1_000), &[254, 253], &memo]),
+        case("transfer_pool_to_company", [203, 31, 19, 31, 105, 47, 222, 80], 16,
+            &[&u64_le(2), &u64_le(1_000), &[254], &memo]),
+        case("create_escrow", [253, 215, 165, 116, 36, 108, 68, 80], 9,
+            &[&u64_le(1), &[0], &u64_le(0), &[254], &u64_le(3), &u64_le(1_000), &u64_le(1_900_000_000), &memo]),
+        case("claim_escrow", [200, 80, 182, 159, 61, 75, 9, 205], 8, &[&u64_le(1), &[254], &memo]),
+        case("cancel_escrow", [156, 203, 54, 179, 38, 72, 33, 21], 8, &[&u64_le(1), &[254], &memo]),
+        case("create_vesting", [135, 184, 171, 156, 197, 162, 246, 44], 4,
+            &[&u64_le(1), &u64_le(1_000), &u64_le(1_700_000_000), &u64_le(86_400)]),
+        case("claim_vested", [208, 190, 166, 114, 203, 225, 140, 208], 17, &[&u64_le(1), &[254], &memo]),
+        case("close_receipt", [126, 254, 244, 203, 124, 164, 134, 89], 4, &[&op_id]),
+        case("refund_company_to_user", [52, 146, 11, 177, 127, 186, 168, 248], 8,
+            &[&u64_le(2), &u64_le(1), &u64_le(500), &u64_le(1_000), &[254, 253], &memo]),
+        case("refund_user_to_company", [211, 211, 223, 120, 221, 99, 252, 87], 8,
+            &[&u64_le(2), &u64_le(1), &u64_le(500), &u64_le(1_000), &[254, 253], &memo]),
+        case("sweep_dust", [9, 49, 242, 88, 156, 84, 109, 15], 6, &[&u64_le(0)]),
+        case("deposit_from_external", [139, 158, 73, 234, 109, 218, 37, 182], 16,
+            &[&u64_le(1_000), &u64_le(1), &[254], &memo]),
+        case("deposit_to_company", [105, 18, 104, 231, 120, 142, 27, 161], 16,
+            &[&u64_le(1_000), &u64_le(2), &[254], &memo]),
+        case("withdraw_company_to_external", [221, 137, 39, 239, 134, 153, 158, 5], 13,
+            &[&u64_le(1_000), &u64_le(2), &[254], &memo, &[0xFF], &[1, 255]]),
+        case("set_withdraw_fee", [33, 223, 102, 118, 225, 116, 8, 238], 2, &[&u16_le(50)]),
+        case("set_user_withdraw_limit", [66, 103, 224, 46, 107, 159, 198, 142], 4,
+            &[&u64_le(1), &u64_le(10_000)]),
+        case("request_withdrawal", [251, 85, 121, 205, 56, 201, 12, 177], 5,
+            &[&u64_le(1), &u64_le(1), &u64_le(1_000), &[9u8; 32]]),
+        case("approve_withdrawal", [75, 48, 146, 122, 201, 158, 210, 123], 16, &[&u64_le(1), &[254], &memo]),
+        case("cancel_withdrawal", [183, 104, 181, 250, 28, 128, 210, 70], 4, &[&u64_le(1)]),
+        case("set_withdraw_approval_threshold", [42, 9, 51, 233, 87, 88, 53, 22], 2, &[&u64_le(10_000)]),
+        case("batch_return_to_pool", [222, 46, 174, 2, 156, 238, 219, 34], 11,
+            &[&memo, &u32_le(1), &u64_le(2), &u64_le(1_000), &[254]]),
+        case("set_v1_sunset", [1, 118, 100, 229, 250, 202, 232, 49], 2, &[&u64_le(1_800_000_000)]),
+        case("mint_to_pool", [12, 29, 169, 194, 157, 181, 118, 124], 5, &[&u64_le(1_000), &memo]),
+        case("burn_pool_tokens", [189, 143, 81, 31, 173, 28, 110, 37], 5, &[&u64_le(1_000), &memo]),
+        case("update_metadata_fields", [61, 69, 228, 80, 185, 192, 143, 124], 4,
+            &[&[0b010], &string("ZUPY2")]),
+        case("freeze_metadata", [8, 61, 146, 255, 94, 108, 158, 83], 4, &[]),
+        case("revoke_zupy_card", [176, 218, 15, 77, 139, 66, 145, 34], 8, &[&KSUID_A]),
+        case("update_card_tier", [179, 143, 196, 15, 127, 132, 0, 73], 4, &[&KSUID_A, &[1]]),
+        case("reissue_zupy_card", [197, 233, 16, 159, 160, 154, 0, 138], 9, &[&KSUID_A]),
+        case("redeem_coupon_nft", [20, 168, 33, 154, 93, 33, 255, 212], 12,
+            &[&KSUID_A, &KSUID_B, &u64_le(2), &memo, &u64_le(1), &[254, 253]]),
+        case("transfer_coupon_nft", [33, 2, 46, 248, 13, 49, 16, 109], 11, &[&KSUID_C, &KSUID_A, &KSUID_B]),
+        case("close_coupon", [145, 129, 128, 62, 140, 121, 104, 203], 8, &[&KSUID_B, &KSUID_A]),
+        case("mint_coupon_cnft_batch", [109, 102, 110, 70, 92, 118, 58, 9], 15,
+            &[&u32_le(1), &[0], &string("Coupon"), &string("ZCPN"), &uri]),
+        case("set_coupon_collection", [131, 191, 144, 20, 32, 43, 205, 107], 3, &[&[9u8; 32]]),
+        case("burn_coupon_cnft", [202, 73, 131, 140, 182, 100, 104, 77], 10,
+            &[&leaf, &u64_le(4), &u32_le(4)]),
+        case("transfer_coupon_cnft", [186, 174, 51, 18, 51, 212, 240, 93], 11,
+            &[&leaf, &u64_le(4), &u32_le(4)]),
+        case("initialize_company", [75, 156, 55, 94, 184, 64, 58, 30], 4, &[&u64_le(2), &[0]]),
+        case("initialize_user", [111, 17, 185, 250, 60, 122, 38, 254], 4, &[&u64_le(1)]),
+        case("set_strict_registration", [92, 42, 138, 11, 227, 9, 178, 89], 3, &[&[1]]),
+        case("close_company", [199, 184, 167, 41, 63, 245, 165, 93], 3, &[&u64_le(2), &[1]]),
+        case("migrate_token_state", [191, 239, 37, 200, 20, 173, 31, 65], 4, &[&[1]]),
+        case("initialize_stats", [144, 201, 117, 76, 127, 118, 176, 16], 4, &[]),
+        case("return_user_to_pool_merged", [187, 144, 44, 168, 103, 162, 5, 18], 11,
+            &[&u64_le(1), &u64_le(1_000), &[254], &memo, &merge]),
+        case("withdraw_to_external_merged", [156, 165, 248, 89, 75, 159, 87, 181], 13,
+            &[&u64_le(1_000), &u64_le(1), &[254], &memo, &[0xFF], &merge, &op_id, &[1, 255]]),
+    ]
+}
+
+/// `MergeInputs` section: one 1_000-token leaf (tree 0, queue 1, proved by index),
+/// output queue 1, no proof.
+fn merge_inputs() -> Vec<u8> {
+    [&[1u8, 1][..], &u64_le(1_000), &[0, 1], &u32_le(0), &[1], &u16_le(0), &[0]].concat()
+}
+
+/// Transfer and withdraw payloads with only their required fields: any
+/// shorter prefix ends inside a field.
+fn truncation_cases() -> Vec<Case> {
+    let memo = string("zupy:v1:test:1");
+
+    vec![
+        case("transfer_from_pool", [136, 167, 45, 66, 74, 252, 0, 16], 17, &[&u64_le(1_000), &memo, &[0xFF]]),
+        case("transfer_user_to_company", [186, 233, 22, 40, 87, 223, 252, 131], 8,
+            &[&u64_le(1), &u64_le(2), &u64_le(1_000), &[254, 253], &memo]),
+        case("transfer_user_to_user", [180, 29, 108, 140, 251, 43, 31, 123], 8,
+            &[&u64_le(1), &u64_le(2), &u64_le(1_000), &[254, 253], &memo]),
+        case("transfer_company_to_company", [42, 241, 16, 155, 103, 235, 235, 117], 8,
+            &[&u64_le(1), &u64_le(2), &u64_le(1_000), &[254, 253], &memo]),
+        case("transfer_pool_to_company", [203, 31, 19, 31, 105, 47, 222, 80], 16,
+            &[&u64_le(2), &u64_le(1_000), &[254], &memo]),
+        case("withdraw_to_external", [114, 198, 185, 119, 169, 163, 29, 251], 13,
+            &[&u64_le(1_000), &u64_le(1), &[254], &memo, &[0xFF]]),
+        case("withdraw_company_to_external", [221, 137, 39, 239, 134, 153, 158, 5], 13,
+            &[&u64_le(1_000), &u64_le(2), &[254], &memo, &[0xFF]]),
+        case("request_withdrawal", [251, 85, 121, 205, 56, 201, 12, 177], 5,
+            &[&u64_le(1), &u64_le(1), &u64_le(1_000), &[9u8; 32]]),
+        case("return_user_to_pool_merged", [187, 144, 44, 168, 103, 162, 5, 18], 11,
+            &[&u64_le(1), &u64_le(1_000), &[254], &memo, &merge_inputs()]),
+        case("withdraw_to_external_merged", [156, 165, 248, 89, 75, 159, 87, 181], 13,
+            &[&u64_le(1_000), &u64_le(1), &[254], &memo, &[0xFF], &merge_inputs()]),
+    ]
+}
+
+/// Runs `disc + payload` against `count` placeholder system accounts.
+fn run(disc: &[u8; 8], count: usize, payload: &[u8]) -> mollusk_svm::result::InstructionResult {
+    let keys: Vec<Pubkey> = (0..count).map(|_| Pubkey::new_unique()).collect();
+    let metas = keys.iter().map(|k| AccountMeta::new(*k, false)).collect();
+    let accounts: Vec<(Pubkey, Account)> =
+        keys.iter().map(|k| (*k, make_system_account(1_000_000_000))).collect();
+    let instruction = Instruction::new_with_bytes(program_id(), &build_ix_data(disc, payload), metas);
+    setup_mollusk().process_instruction(&instruction, &accounts)
+}
+
+/// The well-formed payload gets past parsing: whatever fails is account validation.
+#[test]
+fn test_declared_payload_parses() {
+    for case in cases() {
+        let result = run(&case.disc, case.accounts, &case.payload);
+        assert_ne!(
+            result.raw_result,
+            Err(InstructionError::InvalidInstructionData),
+            "'{}' should accept its declared payload",
+            case.name,
+        );
+    }
+}
+
+/// One extra byte after the declared fields → InvalidInstructionData.
+#[cfg(not(feature = "lenient-ix-data"))]
+#[test]
+fn test_trailing_byte_rejected() {
+    for case in cases() {
+        let mut payload = case.payload.clone();
+        payload.push(0);
+        let result = run(&case.disc, case.accounts, &payload);
+        assert_eq!(
+            result.raw_result,
+            Err(InstructionError::InvalidInstructionData),
+            "'{}' should reject a trailing byte",
+            case.name,
+        );
+    }
+}
+
+/// Every prefix of a transfer / withdraw payload → InvalidInstructionData.
+#[test]
+fn test_truncated_payload_rejected() {
+    for case in truncation_cases() {
+        for len in 0..case.payload.len() {
+            let result = run(&case.disc, case.accounts, &case.payload[..len]);
+            assert_eq!(
+                result.raw_result,
+                Err(InstructionError::InvalidInstructionData),
+                "'{}' should reject a payload cut at byte {}",
+                case.name,
+                len,
+            );
+        }
+    }
+}
@@ -0,0 +1,351 @@
+//! Mollusk integration tests for rate-limited mints.
+//!
+//! Tests both mint instructions with Token-2022 loaded, so the MintTo CPI and
+//! the TokenState update both run:
+//!   - mint_tokens: daily cap (`daily_auto_limit` over a rolling 24h window) —
+//!     exact-limit mint, limit + 1 rejection, window rollover, treasury override
+//!   - mint_to_pool: pool balance delta, shared daily budget, pool_ata and
+//!     paused checks
+//!   - last_mint_ts: stamped on v2 TokenStates, v1 accounts left as is
+//!   - max_supply: mint exactly to the cap, cap + 1 rejection (even with the
+//!     treasury co-signing), legacy 363-byte state stays uncapped
+//!
+//! Requires `cargo build-sbf` before running:
+//!   cargo build-sbf && cargo test --test test_mint_tokens -- --nocapture
+
+mod helpers;
+use helpers::*;
+
+use solana_account::Account;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+
+use zupy_token_program::state::token_state::{
+    TOKEN_STATE_SIZE, TOKEN_STATE_V2_SIZE, TOKEN_STATE_VERSION_2,
+};
+
+// ── Instruction discriminators ─────────────────────────────────────────────
+const DISC_MINT_TOKENS: [u8; 8] = [59, 132, 24, 246, 122, 39, 8, 243];
+const DISC_MINT_TO_POOL: [u8; 8] = [12, 29, 169, 194, 157, 181, 118, 124];
+
+// ── Error codes from ZupyTokenError ──────────────────────────────────────
+const ERR_INVALID_POOL_ACCOUNT: u32 = 6017;
+const ERR_SYSTEM_PAUSED: u32 = 6018;
+const ERR_EXCEEDS_DAILY_LIMIT: u32 = 6021;
+const ERR_MAX_SUPPLY_EXCEEDED: u32 = 6045;
+
+// ── TokenState offsets ───────────────────────────────────────────────────
+const OFF_PER_TX_AUTO_LIMIT: usize = 266;
+const OFF_DAILY_AUTO_LIMIT: usize = 274;
+const OFF_DAILY_MINTED: usize = 282;
+const OFF_LAST_RESET_TS: usize = 290;
+const OFF_PAUSED: usize = 298;
+const OFF_TOTAL_MINTED: usize = 353;
+const OFF_MAX_SUPPLY: usize = 363;
+const OFF_VERSION: usize = 406;
+const OFF_LAST_MINT_TS: usize = 407;
+const OFF_LAST_TRANSFER_TS: usize = 415;
+
+const TOKEN_STATE_IDX: usize = 1;
+const DEST_IDX: usize = 3;
+
+const DAILY_LIMIT: u64 = 3_000_000;
+const POOL_BALANCE: u64 = 5_000_000;
+const NOW: i64 = 1_700_000_000;
+
+struct Fixture {
+    data: Vec<u8>,
+    metas: Vec<AccountMeta>,
+    accounts: Vec<(Pubkey, Account)>,
+    treasury_ata: Pubkey,
+}
+
+fn token_account(data: Vec<u8>) -> Account {
+    Account {
+        lamports: 10_000_000,
+        data,
+        owner: token_2022_id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// `disc` mint of `amount` with `daily_minted` already used since `last_reset`.
+/// The destination (3) is treasury_ata for mint_tokens and pool_ata (holding
+/// `POOL_BALANCE`) for mint_to_pool.
+fn setup(disc: &[u8; 8], amount: u64, daily_minted: u64, last_reset: i64) -> Fixture {
+    let (token_state_pda, bump) = derive_token_state_pda();
+    let mint_auth = mint_authority();
+    let treasury = treasury_wallet();
+    let mint = Pubkey::new_unique();
+    let treasury_ata = Pubkey::new_unique();
+    let pool_ata = Pubkey::new_unique();
+    let dummy = Pubkey::new_unique();
+
+    let mut ts_data = make_token_state_data(
+        &treasury, &mint_auth, &dummy, &pool_ata, &dummy, &dummy, &treasury_ata, &mint,
+        bump, true, false,
+    );
+    ts_data[OFF_PER_TX_AUTO_LIMIT..OFF_PER_TX_AUTO_LIMIT + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+    ts_data[OFF_DAILY_AUTO_LIMIT..OFF_DAILY_AUTO_LIMIT + 8].copy_from_slice(&DAILY_LIMIT.to_le_bytes());
+    ts_data[OFF_DAILY_MINTED..OFF_DAILY_MINTED + 8].copy_from_slice(&daily_minted.to_le_bytes());
+    ts_data[OFF_LAST_RESET_TS..OFF_LAST_RESET_TS + 8].copy_from_slice(&last_reset.to_le_bytes());
+
+    let (dest, dest_data) = if disc == &DISC_MINT_TO_POOL {
+        (pool_ata, make_token_account_data(&mint, &token_state_pda, POOL_BALANCE))
+    } else {
+        (treasury_ata, make_token_account_data(&mint, &treasury, 0))
+    };
+
+    let mut payload = Vec::new();
+    payload.extend_from_slice(&amount.to_le_bytes());
+    payload.extend_from_slice(&build_string("zupy:v1:mint:batch"));
+    let data = build_ix_data(disc, &payload);
+
+    let metas = vec![
+        AccountMeta::new(mint_auth, true),
+        AccountMeta::new(token_state_pda, false),
+        AccountMeta::new(mint, false),
+        AccountMeta::new(dest, false),
+        AccountMeta::new_readonly(token_2022_id(), false),
+    ];
+    let accounts = vec![
+        (mint_auth, make_system_account(1_000_000)),
+        (token_state_pda, make_program_account(ts_data, 1_000_000)),
+        (mint, token_account(make_mint_data(&token_state_pda, POOL_BALANCE, 6))),
+        (dest, token_account(dest_data)),
+        mollusk_svm_programs_token::token2022::keyed_account(),
+    ];
+
+    Fixture { data, metas, accounts, treasury_ata }
+}
+
+/// Appends the treasury as co-signer (signing or not).
+fn with_treasury(mut f: Fixture, signs: bool) -> Fixture {
+    let treasury = treasury_wallet();
+    f.metas.push(AccountMeta::new_readonly(treasury, signs));
+    f.accounts.push((treasury, make_system_account(1_000_000)));
+    f
+}
+
+/// Grows token_state to the 371-byte layout with `max_supply = cap`.
+fn with_max_supply(mut f: Fixture, cap: u64) -> Fixture {
+    let data = &mut f.accounts[TOKEN_STATE_IDX].1.data;
+    data.resize(OFF_MAX_SUPPLY + 8, 0);
+    data[OFF_MAX_SUPPLY..].copy_from_slice(&cap.to_le_bytes());
+    f
+}
+
+/// Grows token_state to the v2 layout (version byte set, timestamps zeroed).
+fn with_v2_layout(mut f: Fixture) -> Fixture {
+    let data = &mut f.accounts[TOKEN_STATE_IDX].1.data;
+    data.resize(TOKEN_STATE_V2_SIZE, 0);
+    data[OFF_VERSION] = TOKEN_STATE_VERSION_2;
+    f
+}
+
+fn run(f: &Fixture) -> mollusk_svm::result::InstructionResult {
+    run_at(f, NOW)
+}
+
+fn run_at(f: &Fixture, now: i64) -> mollusk_svm::result::InstructionResult {
+    let mut mollusk = setup_mollusk_with_programs();
+    mollusk.sysvars.clock.unix_timestamp = now;
+    let instruction = Instruction::new_with_bytes(program_id(), &f.data, f.metas.clone());
+    mollusk.process_instruction(&instruction, &f.accounts)
+}
+
+fn read_u64_at(result: &mollusk_svm::result::InstructionResult, idx: usize, offset: usize) -> u64 {
+    let data = &result.resulting_accounts[idx].1.data;
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn read_i64_at(result: &mollusk_svm::result::InstructionResult, idx: usize, offset: usize) -> i64 {
+    let data = &result.resulting_accounts[idx].1.data;
+    i64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// mint_tokens — daily limit
+// ═══════════════════════════════════════════════════════════════════════════
+
+mod mint_tokens {
+    use super::*;
+
+    #[test]
+    fn test_mint_up_to_exact_limit() {
+        let result = run(&setup(&DISC_MINT_TOKENS, 1_000_000, 2_000_000, NOW - 100));
+        assert!(result.program_result.is_ok(), "mint failed: {:?}", result.program_result);
+        assert_eq!(read_u64_at(&result, TOKEN_STATE_IDX, OFF_DAILY_MINTED), DAILY_LIMIT);
+        assert_eq!(read_i64_at(&result, TOKEN_STATE_IDX, OFF_LAST_RESET_TS), NOW - 100);
+        assert_eq!(read_u64_at(&result, DEST_IDX, 64), 1_000_000);
+        assert_logged(&result, "zupy_evt:mint:1000000:batch");
+        println!("mint_tokens: exact_limit CU={}", result.compute_units_consumed);
+    }
+
+    #[test]
+    fn test_mint_one_over_limit_rejected() {
+        let result = run(&setup(&DISC_MINT_TOKENS, 1_000_001, 2_000_000, NOW - 100));
+        assert_ix_custom_err(&result, ERR_EXCEEDS_DAILY_LIMIT);
+        assert!(
+            !program_logs().iter().any(|l| l.contains("zupy_evt:")),
+            "no event before validation passes"
+        );
+        println!("mint_tokens: over_limit CU={}", result.compute_units_consumed);
+    }
+
+    #[test]
+    fn test_window_still_open_at_exactly_24h() {
+        let result = run(&setup(&DISC_MINT_TOKENS, 1, DAILY_LIMIT, NOW - 86_400));
+        assert_ix_custom_err(&result, ERR_EXCEEDS_DAILY_LIMIT);
+    }
+
+    #[test]
+    fn test_mint_after_window_rolls_over() {
+        let result = run(&setup(&DISC_MINT_TOKENS, DAILY_LIMIT, DAILY_LIMIT, NOW - 86_401));
+        assert!(result.program_result.is_ok(), "mint failed: {:?}", result.program_result);
+        assert_eq!(read_u64_at(&result, TOKEN_STATE_IDX, OFF_DAILY_MINTED), DAILY_LIMIT);
+        assert_eq!(read_i64_at(&result, TOKEN_STATE_IDX, OFF_LAST_RESET_TS), NOW);
+        println!("mint_tokens: rollover CU={}", result.compute_units_consumed);
+    }
+
+    #[test]
+    fn test_total_minted_accumulates_across_daily_reset() {
+        let mut f = setup(&DISC_MINT_TOKENS, DAILY_LIMIT, 0, NOW - 100);
+        let first = run(&f);
+        assert!(first.program_result.is_ok(), "first mint failed: {:?}", first.program_result);
+        assert_eq!(read_u64_at(&first, TOKEN_STATE_IDX, OFF_TOTAL_MINTED), DAILY_LIMIT);
+
+        // Same state a day later: the daily counter resets, the lifetime one does not
+        f.accounts = first.resulting_accounts.clone();
+        let second = run_at(&f, NOW + 86_401);
+        assert!(second.program_result.is_ok(), "second mint failed: {:?}", second.program_result);
+        assert_eq!(read_u64_at(&second, TOKEN_STATE_IDX, OFF_DAILY_MINTED), DAILY_LIMIT);
+        assert_eq!(read_u64_at(&second, TOKEN_STATE_IDX, OFF_TOTAL_MINTED), 2 * DAILY_LIMIT);
+        assert_eq!(read_u64_at(&second, DEST_IDX, 64), 2 * DAILY_LIMIT);
+    }
+
+    #[test]
+    fn test_v2_state_records_last_mint_ts() {
+        let f = with_v2_layout(setup(&DISC_MINT_TOKENS, 1_000, 0, NOW - 100));
+        let result = run(&f);
+        assert!(result.program_result.is_ok(), "mint failed: {:?}", result.program_result);
+        assert_eq!(read_i64_at(&result, TOKEN_STATE_IDX, OFF_LAST_MINT_TS), NOW);
+        assert_eq!(read_i64_at(&result, TOKEN_STATE_IDX, OFF_LAST_TRANSFER_TS), 0);
+    }
+
+    #[test]
+    fn test_v1_state_has_no_heartbeat() {
+        let result = run(&setup(&DISC_MINT_TOKENS, 1_000, 0, NOW - 100));
+        assert!(result.program_result.is_ok(), "mint failed: {:?}", result.program_result);
+        assert_eq!(result.resulting_accounts[TOKEN_STATE_IDX].1.data.len(), TOKEN_STATE_SIZE);
+    }
+
+    #[test]
+    fn test_amount_overflow_rejected() {
+        let f = with_treasury(setup(&DISC_MINT_TOKENS, u64::MAX, 1, NOW - 100), true);
+        assert_ix_custom_err(&run(&f), ERR_EXCEEDS_DAILY_LIMIT);
+    }
+
+    #[test]
+    fn test_treasury_cosigner_overrides_daily_limit() {
+        let f = with_treasury(setup(&DISC_MINT_TOKENS, 1_000_001, 2_000_000, NOW - 100), true);
+        let result = run(&f);
+        assert!(result.program_result.is_ok(), "override mint failed: {:?}", result.program_result);
+        assert_eq!(read_u64_at(&result, TOKEN_STATE_IDX, OFF_DAILY_MINTED), DAILY_LIMIT + 1);
+    }
+
+    #[test]
+    fn test_treasury_without_signature_does_not_override() {
+        let f = with_treasury(setup(&DISC_MINT_TOKENS, 1_000_001, 2_000_000, NOW - 100), false);
+        assert_ix_custom_err(&run(&f), ERR_EXCEEDS_DAILY_LIMIT);
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// mint_to_pool
+// ═══════════════════════════════════════════════════════════════════════════
+
+mod mint_to_pool {
+    use super::*;
+
+    #[test]
+    fn test_mints_into_pool_ata() {
+        let result = run(&setup(&DISC_MINT_TO_POOL, 1_000_000, 0, NOW - 100));
+        assert!(result.program_result.is_ok(), "mint_to_pool failed: {:?}", result.program_result);
+        assert_eq!(read_u64_at(&result, DEST_IDX, 64), POOL_BALANCE + 1_000_000);
+        assert_eq!(read_u64_at(&result, TOKEN_STATE_IDX, OFF_DAILY_MINTED), 1_000_000);
+        assert_logged(&result, "zupy_evt:mint:1000000:batch");
+        println!("mint_to_pool: happy_path CU={}", result.compute_units_consumed);
+    }
+
+    #[test]
+    fn test_shares_daily_budget_with_mint_tokens() {
+        let ok = run(&setup(&DISC_MINT_TO_POOL, 1_000_000, 2_000_000, NOW - 100));
+        assert!(ok.program_result.is_ok(), "mint_to_pool failed: {:?}", ok.program_result);
+        assert_eq!(read_u64_at(&ok, TOKEN_STATE_IDX, OFF_DAILY_MINTED), DAILY_LIMIT);
+
+        let over = run(&setup(&DISC_MINT_TO_POOL, 1_000_001, 2_000_000, NOW - 100));
+        assert_ix_custom_err(&over, ERR_EXCEEDS_DAILY_LIMIT);
+    }
+
+    #[test]
+    fn test_treasury_ata_rejected_as_pool() {
+        let mut f = setup(&DISC_MINT_TO_POOL, 1_000_000, 0, NOW - 100);
+        let treasury_ata = f.treasury_ata;
+        f.metas[DEST_IDX] = AccountMeta::new(treasury_ata, false);
+        f.accounts[DEST_IDX].0 = treasury_ata;
+        assert_ix_custom_err(&run(&f), ERR_INVALID_POOL_ACCOUNT);
+    }
+
+    #[test]
+    fn test_system_paused() {
+        let mut f = setup(&DISC_MINT_TO_POOL, 1_000_000, 0, NOW - 100);
+        f.accounts[TOKEN_STATE_IDX].1.data[OFF_PAUSED] = 1;
+        assert_ix_custom_err(&run(&f), ERR_SYSTEM_PAUSED);
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// max_supply — mint supply starts at POOL_BALANCE
+// ═══════════════════════════════════════════════════════════════════════════
+
+mod max_supply {
+    use super::*;
+
+    const CAP: u64 = POOL_BALANCE + 1_000_000;
+
+    #[test]
+    fn test_mint_exactly_to_cap() {
+        let f = with_max_supply(setup(&DISC_MINT_TOKENS, 1_000_000, 0, NOW - 100), CAP);
+        let result = run(&f);
+        assert!(result.program_result.is_ok(), "mint failed: {:?}", result.program_result);
+        assert_eq!(read_u64_at(&result, 2, 36), CAP);
+        println!("max_supply: exact_cap CU={}", result.compute_units_consumed);
+    }
+
+    #[test]
+    fn test_mint_one_past_cap_rejected() {
+        let f = with_max_supply(setup(&DISC_MINT_TOKENS, 1_000_001, 0, NOW - 100), CAP);
+        assert_ix_custom_err(&run(&f), ERR_MAX_SUPPLY_EXCEEDED);
+    }
+
+    #[test]
+    fn test_treasury_cosigner_does_not_lift_cap() {
+        let f = with_max_supply(setup(&DISC_MINT_TOKENS, 1_000_001, 0, NOW - 100), CAP);
+        assert_ix_custom_err(&run(&with_treasury(f, true)), ERR_MAX_SUPPLY_EXCEEDED);
+    }
+
+    #[test]
+    fn test_mint_to_pool_one_past_cap_rejected() {
+        let f = with_max_supply(setup(&DISC_MINT_TO_POOL, 1_000_001, 0, NOW - 100), CAP);
+        assert_ix_custom_err(&run(&f), ERR_MAX_SUPPLY_EXCEEDED);
+    }
+
+    #[test]
+    fn test_zero_cap_is_uncapped() {
+        let f = with_max_supply(setup(&DISC_MINT_TOKENS, DAILY_LIMIT, 0, NOW - 100), 0);
+        let result = run(&f);
+        assert!(result.program_result.is_ok(), "mint failed: {:?}", result.program_result);
+    }
+}
@@ -4,6 +4,7 @@
 //!   - execute_split_transfer (9+ accounts, compressed token CPIs)
 //!   - burn_tokens (6 accounts, regular invoke)
 //!   - burn_from_company_pda (6 accounts, invoke_signed)
+//!   - burn_pool_tokens (5 accounts, invoke_signed by token_state)
 //!
 //! Requires `cargo build-sbf` before running:
 //!   cargo build-sbf && cargo test --test test_split_burns -- --nocapture
@@ -22,6 +23,7 @@ use zupy_token_program::state::token_state::TOKEN_STATE_SIZE;
 const DISC_EXECUTE_SPLIT_TRANSFER: [u8; 8] = [51, 254, 61, 214, 234, 138, 101, 214];
 const DISC_BURN_TOKENS: [u8; 8] = [76, 15, 51, 254, 229, 215, 121, 66];
 const DISC_BURN_FROM_COMPANY_PDA: [u8; 8] = [43, 207, 204, 77, 74, 93, 165, 34];
+const DISC_BURN_POOL_TOKENS: [u8; 8] = [189, 143, 81, 31, 173, 28, 110, 37];
 
 // ═══════════════════════════════════════════════════════════════════════════
 // execute_split_transfer tests
@@ -43,6 +45,7 @@ const DISC_BURN_FROM_COMPANY_PDA: [u8; 8] = [43, 207, 204, 77, 74, 93, 165, 34];
 mod execute_split_transfer {
     use super::*;
 
+    #[allow(clippy::too_many_arguments)]
     fn build_accounts(
         transfer_auth: &Pubkey,
         token_state_pda: &Pubkey,
@@ -221,6 +224,67 @@ mod execute_split_transfer {
         println!("split_transfer: invalid_operation_type CU={}", result.compute_units_consumed);
     }
 
+    #[test]
+    fn test_split_bps_out_of_range() {
+        // Default token_state has min_split_bps = max_split_bps = 0
+        let mollusk = setup_mollusk();
+        let s = setup();
+        let ts_data = make_split_token_state(
+            &Pubkey::new_unique(), &s.transfer_auth, &s.mint, &Pubkey::new_unique(),
+            &s.incentive_pool_pda, s.bump, true, false,
+        );
+
+        let mut payload = build_payload(s.user_id, s.company_id, 1_000_000, s.user_bump, s.company_bump, s.incentive_bump, "mixed_payment");
+        payload.extend_from_slice(&100u16.to_le_bytes()); // split_bps = 1%
+        let data = build_ix_data(&DISC_EXECUTE_SPLIT_TRANSFER, &payload);
+        let metas = build_ix_metas(
+            &s.transfer_auth, &s.token_state_pda, &s.mint,
+            &s.user_pda, &s.company_pda, &s.incentive_pool_pda, &s.fee_payer,
+        );
+        let instruction = Instruction::new_with_bytes(program_id(), &data, metas);
+        let accounts = build_accounts(
+            &s.transfer_auth, &s.token_state_pda, ts_data, &s.mint,
+            &s.user_pda, &s.company_pda, &s.incentive_pool_pda, &s.fee_payer,
+        );
+
+        let result = mollusk.process_instruction(&instruction, &accounts);
+        assert_ix_custom_err(&result, 6031); // InvalidSplitRatio
+        println!("split_transfer: split_bps_out_of_range CU={}", result.compute_units_consumed);
+    }
+
+    #[test]
+    fn test_near_max_split_overflows() {
+        // Range opened to 100%: at z_total ≈ u64::MAX the incentive leg takes
+        // everything and the burn leg pushes the company leg below zero.
+        const OFF_MIN_SPLIT_BPS: usize = 299;
+        const OFF_MAX_SPLIT_BPS: usize = 301;
+        let mollusk = setup_mollusk();
+        let s = setup();
+        let mut ts_data = make_split_token_state(
+            &Pubkey::new_unique(), &s.transfer_auth, &s.mint, &Pubkey::new_unique(),
+            &s.incentive_pool_pda, s.bump, true, false,
+        );
+        ts_data[OFF_MIN_SPLIT_BPS..OFF_MIN_SPLIT_BPS + 2].copy_from_slice(&0u16.to_le_bytes());
+        ts_data[OFF_MAX_SPLIT_BPS..OFF_MAX_SPLIT_BPS + 2].copy_from_slice(&10_000u16.to_le_bytes());
+
+        let mut payload = build_payload(s.user_id, s.company_id, u64::MAX - 1, s.user_bump, s.company_bump, s.incentive_bump, "mixed_payment");
+        payload.extend_from_slice(&10_000u16.to_le_bytes()); // split_bps = 100%
+        let data = build_ix_data(&DISC_EXECUTE_SPLIT_TRANSFER, &payload);
+        let metas = build_ix_metas(
+            &s.transfer_auth, &s.token_state_pda, &s.mint,
+            &s.user_pda, &s.company_pda, &s.incentive_pool_pda, &s.fee_payer,
+        );
+        let instruction = Instruction::new_with_bytes(program_id(), &data, metas);
+        let accounts = build_accounts(
+            &s.transfer_auth, &s.token_state_pda, ts_data, &s.mint,
+            &s.user_pda, &s.company_pda, &s.incentive_pool_pda, &s.fee_payer,
+        );
+
+        let result = mollusk.process_instruction(&instruction, &accounts);
+        assert_ix_custom_err(&result, 6076); // ArithmeticOverflow
+        println!("split_transfer: near_max_split_overflows CU={}", result.compute_units_consumed);
+    }
+
     #[test]
     fn test_not_initialized() {
         let mollusk = setup_mollusk();
@@ -296,7 +360,7 @@ mod execute_split_transfer {
         );
 
         let result = mollusk.process_instruction(&instruction, &accounts);
-        assert_ix_custom_err(&result, 6000); // InvalidAuthority
+        assert_ix_custom_err(&result, 6069); // AuthorityMismatch
         println!("split_transfer: wrong_authority CU={}", result.compute_units_consumed);
     }
 
@@ -982,6 +1046,57 @@ mod burn_tokens {
         println!("burn_tokens: wrong_token_program CU={}", result.compute_units_consumed);
     }
 
+    /// Full flow with Token-2022 loaded, burning 400_000 of 1_000_000 with
+    /// `total_burned` starting at 1_000. Returns the resulting `total_burned`.
+    fn run_full_burn(token_state_writable: bool) -> u64 {
+        const OFF_TOTAL_BURNED: usize = 345;
+        let mollusk = setup_mollusk_with_programs();
+        let (token_state_pda, bump) = derive_token_state_pda();
+        let treasury = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let token_account = Pubkey::new_unique();
+        let token_account_owner = Pubkey::new_unique();
+
+        let mut ts_data = make_split_token_state(
+            &treasury, &Pubkey::new_unique(), &mint, &Pubkey::new_unique(),
+            &Pubkey::new_unique(), bump, true, false,
+        );
+        ts_data[OFF_TOTAL_BURNED..OFF_TOTAL_BURNED + 8].copy_from_slice(&1_000u64.to_le_bytes());
+
+        let payload = build_payload(400_000, "zupy:v1:burn:123");
+        let data = build_ix_data(&DISC_BURN_TOKENS, &payload);
+        let mut metas = build_ix_metas(&treasury, &token_state_pda, &mint, &token_account, &token_account_owner);
+        if token_state_writable {
+            metas[1] = AccountMeta::new(token_state_pda, false);
+        }
+        let instruction = Instruction::new_with_bytes(program_id(), &data, metas);
+        let mut accounts = build_accounts(&treasury, &token_state_pda, ts_data, &mint, &token_account, &token_account_owner, 1_000_000);
+        accounts[2].1 = make_token_owned_account(make_mint_data(&token_state_pda, 1_000_000, 6));
+        accounts[5] = mollusk_svm_programs_token::token2022::keyed_account();
+
+        let result = mollusk.process_instruction(&instruction, &accounts);
+        assert!(result.program_result.is_ok(), "burn failed: {:?}", result.program_result);
+        let ata = &result.resulting_accounts[3].1.data;
+        assert_eq!(u64::from_le_bytes(ata[64..72].try_into().unwrap()), 600_000);
+        assert_logged(&result, "zupy_evt:burn:400000:123");
+        println!("burn_tokens: full_flow (writable={}) CU={}", token_state_writable, result.compute_units_consumed);
+        let ts = &result.resulting_accounts[1].1.data;
+        u64::from_le_bytes(ts[OFF_TOTAL_BURNED..OFF_TOTAL_BURNED + 8].try_into().unwrap())
+    }
+
+    /// Writable token_state: the burn lands and `total_burned` advances.
+    #[test]
+    fn test_burn_advances_total_burned() {
+        assert_eq!(run_full_burn(true), 1_000 + 400_000);
+    }
+
+    /// Read-only token_state (existing clients): the burn still lands and the
+    /// counter update is skipped.
+    #[test]
+    fn test_burn_readonly_token_state_skips_total_burned() {
+        assert_eq!(run_full_burn(false), 1_000);
+    }
+
     // ── CU Benchmark ───────────────────────────────────────────────────
 
     #[test]
@@ -1203,7 +1318,7 @@ mod burn_from_company_pda {
         let accounts = build_accounts(&wrong_auth, &token_state_pda, ts_data, &mint, &company_pda, &fee_payer);
 
         let result = mollusk.process_instruction(&instruction, &accounts);
-        assert_ix_custom_err(&result, 6000); // InvalidAuthority
+        assert_ix_custom_err(&result, 6069); // AuthorityMismatch
         println!("burn_from_company_pda: wrong_authority CU={}", result.compute_units_consumed);
     }
 
@@ -1335,14 +1450,14 @@ mod burn_from_company_pda {
 
         let payload = build_payload(company_id, 1_000_000, "zupy:v1:burn:42");
         let data = build_ix_data(&DISC_BURN_FROM_COMPANY_PDA, &payload);
-        // fee_payer at index 4 must NOT be a signer to trigger InvalidAuthority
+        // fee_payer at index 4 must NOT be a signer to trigger FeePayerNotSigner
         let mut metas = build_ix_metas(&transfer_auth, &token_state_pda, &mint, &company_pda, &fee_payer);
         metas[4] = AccountMeta::new_readonly(fee_payer, false);
         let instruction = Instruction::new_with_bytes(program_id(), &data, metas);
         let accounts = build_accounts(&transfer_auth, &token_state_pda, ts_data, &mint, &company_pda, &fee_payer);
 
         let result = mollusk.process_instruction(&instruction, &accounts);
-        assert_ix_custom_err(&result, 6000); // InvalidAuthority
+        assert_ix_custom_err(&result, 6070); // FeePayerNotSigner
         println!("burn_from_company_pda: fee_payer_not_signer CU={}", result.compute_units_consumed);
     }
 
@@ -1380,6 +1495,32 @@ mod burn_from_company_pda {
         println!("burn_from_company_pda: wrong_compressed_token_program CU={}", result.compute_units_consumed);
     }
 
+    /// Validation passes, so the event is logged before the (stubbed) Light CPI.
+    #[test]
+    fn test_event_logged_before_cpi() {
+        let mollusk = setup_mollusk();
+        let (token_state_pda, bump) = derive_token_state_pda();
+        let transfer_auth = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let company_id: u64 = 42;
+        let (company_pda, _) = derive_company_pda(company_id);
+        let fee_payer = Pubkey::new_unique();
+
+        let ts_data = make_split_token_state(
+            &Pubkey::new_unique(), &transfer_auth, &mint, &Pubkey::new_unique(),
+            &Pubkey::new_unique(), bump, true, false,
+        );
+
+        let payload = build_payload(company_id, 1_000_000, "zupy:v1:burn:42");
+        let data = build_ix_data(&DISC_BURN_FROM_COMPANY_PDA, &payload);
+        let metas = build_ix_metas(&transfer_auth, &token_state_pda, &mint, &company_pda, &fee_payer);
+        let instruction = Instruction::new_with_bytes(program_id(), &data, metas);
+        let accounts = build_accounts(&transfer_auth, &token_state_pda, ts_data, &mint, &company_pda, &fee_payer);
+
+        let result = mollusk.process_instruction(&instruction, &accounts);
+        assert_logged(&result, "zupy_evt:burn:1000000:42");
+    }
+
     // ── CU Benchmark ───────────────────────────────────────────────────
 
     #[test]
@@ -1416,6 +1557,114 @@ mod burn_from_company_pda {
     }
 }
 
+// ═══════════════════════════════════════════════════════════════════════════
+// burn_pool_tokens tests
+// ═══════════════════════════════════════════════════════════════════════════
+
+mod burn_pool_tokens {
+    use super::*;
+
+    const OFF_TOTAL_BURNED: usize = 345;
+    const POOL_BALANCE: u64 = 1_000_000;
+    const ERR_INSUFFICIENT_BALANCE: u32 = 6004;
+    const ERR_ZERO_AMOUNT: u32 = 6012;
+    const ERR_INVALID_POOL_ACCOUNT: u32 = 6017;
+    const ERR_SYSTEM_PAUSED: u32 = 6018;
+    const ERR_UNAUTHORIZED_TREASURY: u32 = 6019;
+
+    struct Fixture {
+        metas: Vec<AccountMeta>,
+        accounts: Vec<(Pubkey, Account)>,
+    }
+
+    /// Token-2022 loaded; pool_ata holds `POOL_BALANCE`, owned by token_state.
+    fn setup(paused: bool) -> Fixture {
+        let (token_state_pda, bump) = derive_token_state_pda();
+        let treasury = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let pool_ata = Pubkey::new_unique();
+
+        let ts_data = make_split_token_state(
+            &treasury, &Pubkey::new_unique(), &mint, &pool_ata,
+            &Pubkey::new_unique(), bump, true, paused,
+        );
+        let metas = vec![
+            AccountMeta::new_readonly(treasury, true),           // 0: treasury (signer)
+            AccountMeta::new(token_state_pda, false),            // 1: writable
+            AccountMeta::new(mint, false),                       // 2: writable
+            AccountMeta::new(pool_ata, false),                   // 3: writable
+            AccountMeta::new_readonly(token_2022_id(), false),   // 4
+        ];
+        let accounts = vec![
+            (treasury, make_system_account(1_000_000)),
+            (token_state_pda, make_program_account(ts_data, 1_000_000)),
+            (mint, make_token_owned_account(make_mint_data(&token_state_pda, POOL_BALANCE, 6))),
+            (pool_ata, make_token_owned_account(make_token_account_data(&mint, &token_state_pda, POOL_BALANCE))),
+            mollusk_svm_programs_token::token2022::keyed_account(),
+        ];
+        Fixture { metas, accounts }
+    }
+
+    fn run(f: &Fixture, amount: u64) -> mollusk_svm::result::InstructionResult {
+        let mollusk = setup_mollusk_with_programs();
+        let mut payload = Vec::new();
+        payload.extend_from_slice(&amount.to_le_bytes());
+        payload.extend_from_slice(&build_string("zupy:v1:burn:pool"));
+        let data = build_ix_data(&DISC_BURN_POOL_TOKENS, &payload);
+        let instruction = Instruction::new_with_bytes(program_id(), &data, f.metas.clone());
+        mollusk.process_instruction(&instruction, &f.accounts)
+    }
+
+    fn read_u64(result: &mollusk_svm::result::InstructionResult, idx: usize, offset: usize) -> u64 {
+        let data = &result.resulting_accounts[idx].1.data;
+        u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_burn_reduces_supply_and_pool_balance() {
+        let result = run(&setup(false), 400_000);
+        assert!(result.program_result.is_ok(), "burn failed: {:?}", result.program_result);
+        assert_eq!(read_u64(&result, 2, 36), POOL_BALANCE - 400_000, "mint supply");
+        assert_eq!(read_u64(&result, 3, 64), POOL_BALANCE - 400_000, "pool balance");
+        assert_eq!(read_u64(&result, 1, OFF_TOTAL_BURNED), 400_000);
+        assert_logged(&result, "zupy_evt:burn:400000:pool");
+        println!("burn_pool_tokens: full_flow CU={}", result.compute_units_consumed);
+    }
+
+    #[test]
+    fn test_zero_amount() {
+        assert_ix_custom_err(&run(&setup(false), 0), ERR_ZERO_AMOUNT);
+    }
+
+    #[test]
+    fn test_insufficient_pool_balance() {
+        assert_ix_custom_err(&run(&setup(false), POOL_BALANCE + 1), ERR_INSUFFICIENT_BALANCE);
+    }
+
+    #[test]
+    fn test_system_paused() {
+        assert_ix_custom_err(&run(&setup(true), 1), ERR_SYSTEM_PAUSED);
+    }
+
+    #[test]
+    fn test_non_treasury_signer() {
+        let mut f = setup(false);
+        let impostor = Pubkey::new_unique();
+        f.metas[0] = AccountMeta::new_readonly(impostor, true);
+        f.accounts[0].0 = impostor;
+        assert_ix_custom_err(&run(&f, 1), ERR_UNAUTHORIZED_TREASURY);
+    }
+
+    #[test]
+    fn test_wrong_pool_ata() {
+        let mut f = setup(false);
+        let other = Pubkey::new_unique();
+        f.metas[3] = AccountMeta::new(other, false);
+        f.accounts[3].0 = other;
+        assert_ix_custom_err(&run(&f, 1), ERR_INVALID_POOL_ACCOUNT);
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // CU Benchmark: Consolidated report
 // ═══════════════════════════════════════════════════════════════════════════
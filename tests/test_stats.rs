@@ -0,0 +1,123 @@
+//! Mollusk integration tests for the global Stats PDA.
+//!
+//! Tests:
+//!   - initialize_stats (4 accounts, treasury signed, StatsState creation,
+//!     re-initialization rejection)
+//!
+//! The counters themselves are written after each instruction's CPI, which the
+//! stubbed Light programs fail; their arithmetic is unit-tested in
+//! `state::stats_state` and `helpers::stats`, and `test_transfers` checks that
+//! passing the account never changes a transfer's validation outcome.
+//!
+//! Requires `cargo build-sbf` before running:
+//!   cargo build-sbf && cargo test --test test_stats -- --nocapture
+
+mod helpers;
+use helpers::*;
+
+use solana_account::Account;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+
+use zupy_token_program::state::stats_state::{STATS_STATE_DISCRIMINATOR, STATS_STATE_SIZE};
+
+const DISC_INITIALIZE_STATS: [u8; 8] = [144, 201, 117, 76, 127, 118, 176, 16];
+
+const ERR_INVALID_AUTHORITY: u32 = 6000;
+const ERR_ALREADY_INITIALIZED: u32 = 6003;
+const ERR_INVALID_PDA: u32 = 6007;
+const ERR_UNAUTHORIZED_TREASURY: u32 = 6019;
+
+fn empty_account() -> Account {
+    Account { lamports: 0, data: vec![], owner: Pubkey::default(), executable: false, rent_epoch: 0 }
+}
+
+struct Fixture {
+    metas: Vec<AccountMeta>,
+    accounts: Vec<(Pubkey, Account)>,
+}
+
+/// System program loaded, no Stats PDA yet, treasury signing.
+fn setup() -> Fixture {
+    let (token_state_pda, bump) = derive_token_state_pda();
+    let (stats, _) = derive_stats_pda();
+    let treasury = treasury_wallet();
+    let dummy = Pubkey::new_unique();
+    let ts_data = make_token_state_data(
+        &treasury, &mint_authority(), &dummy, &dummy, &dummy, &dummy, &dummy,
+        &dummy, bump, true, false,
+    );
+
+    let metas = vec![
+        AccountMeta::new(treasury, true),
+        AccountMeta::new_readonly(token_state_pda, false),
+        AccountMeta::new(stats, false),
+        AccountMeta::new_readonly(system_program_id(), false),
+    ];
+    let accounts = vec![
+        (treasury, make_system_account(1_000_000_000)),
+        (token_state_pda, make_program_account(ts_data, 1_000_000)),
+        (stats, empty_account()),
+        mollusk_svm::program::keyed_account_for_system_program(),
+    ];
+    Fixture { metas, accounts }
+}
+
+fn run(f: &Fixture) -> mollusk_svm::result::InstructionResult {
+    let mollusk = setup_mollusk();
+    let data = build_ix_data(&DISC_INITIALIZE_STATS, &[]);
+    let instruction = Instruction::new_with_bytes(program_id(), &data, f.metas.clone());
+    mollusk.process_instruction(&instruction, &f.accounts)
+}
+
+#[test]
+fn test_treasury_creates_stats() {
+    let result = run(&setup());
+    assert!(result.program_result.is_ok(), "initialize failed: {:?}", result.program_result);
+
+    let (_, bump) = derive_stats_pda();
+    let stats = &result.resulting_accounts[2].1;
+    assert_eq!(stats.owner, program_id());
+    assert_eq!(stats.data, make_stats_data());
+    assert_eq!(stats.data.len(), STATS_STATE_SIZE);
+    assert_eq!(&stats.data[0..8], &STATS_STATE_DISCRIMINATOR);
+    assert!(stats.data[8..48].iter().all(|b| *b == 0), "counters start at zero");
+    assert_eq!(stats.data[48], bump);
+    let rent = mollusk_svm::Mollusk::default().sysvars.rent;
+    assert!(stats.lamports >= rent.minimum_balance(STATS_STATE_SIZE));
+}
+
+#[test]
+fn test_reinitialization_rejected() {
+    let mut f = setup();
+    let first = run(&f);
+    assert!(first.program_result.is_ok(), "initialize failed: {:?}", first.program_result);
+
+    f.accounts = first.resulting_accounts;
+    assert_ix_custom_err(&run(&f), ERR_ALREADY_INITIALIZED);
+}
+
+#[test]
+fn test_rejects_non_treasury() {
+    let mut f = setup();
+    let impostor = Pubkey::new_unique();
+    f.metas[0] = AccountMeta::new(impostor, true);
+    f.accounts[0] = (impostor, make_system_account(1_000_000_000));
+    assert_ix_custom_err(&run(&f), ERR_UNAUTHORIZED_TREASURY);
+}
+
+#[test]
+fn test_requires_treasury_signature() {
+    let mut f = setup();
+    f.metas[0] = AccountMeta::new(treasury_wallet(), false);
+    assert_ix_custom_err(&run(&f), ERR_INVALID_AUTHORITY);
+}
+
+#[test]
+fn test_rejects_wrong_pda() {
+    let mut f = setup();
+    let wrong = Pubkey::new_unique();
+    f.metas[2] = AccountMeta::new(wrong, false);
+    f.accounts[2] = (wrong, empty_account());
+    assert_ix_custom_err(&run(&f), ERR_INVALID_PDA);
+}
@@ -0,0 +1,152 @@
+//! Mollusk integration tests for TokenState layout migration.
+//!
+//! Tests:
+//!   - migrate_token_state (treasury only, grows v1 TokenStates to v2,
+//!     preserves existing bytes, fee_payer funds the rent, v2 rejected)
+//!
+//! Layout offsets for both versions are pinned by the golden unit tests in
+//! `src/state/token_state.rs`.
+//!
+//! Requires `cargo build-sbf` before running:
+//!   cargo build-sbf && cargo test --test test_token_state -- --nocapture
+
+mod helpers;
+use helpers::*;
+
+use mollusk_svm::result::InstructionResult;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+
+use zupy_token_program::state::token_state::{
+    TOKEN_STATE_SIZE, TOKEN_STATE_SIZE_WITH_COUPON_COLLECTION, TOKEN_STATE_V2_SIZE,
+    TOKEN_STATE_VERSION_2,
+};
+
+const DISC_MIGRATE_TOKEN_STATE: [u8; 8] = [191, 239, 37, 200, 20, 173, 31, 65];
+
+const ERR_ALREADY_INITIALIZED: u32 = 6003;
+const ERR_UNAUTHORIZED_TREASURY: u32 = 6019;
+const ERR_FEE_PAYER_NOT_SIGNER: u32 = 6070;
+
+const FEE_PAYER_LAMPORTS: u64 = 1_000_000_000;
+
+/// v1 TokenState (363 bytes) with non-default fields, so preservation is visible.
+fn token_state() -> Vec<u8> {
+    let (_, bump) = derive_token_state_pda();
+    let mut data = make_token_state_data(
+        &treasury_wallet(), &mint_authority(), &Pubkey::new_unique(), &Pubkey::new_unique(),
+        &Pubkey::new_unique(), &Pubkey::new_unique(), &Pubkey::new_unique(),
+        &Pubkey::new_unique(), bump, true, true,
+    );
+    data[353..361].copy_from_slice(&123_456u64.to_le_bytes()); // total_minted
+    data
+}
+
+fn rent_exempt(len: usize) -> u64 {
+    mollusk_svm::Mollusk::default().sysvars.rent.minimum_balance(len)
+}
+
+fn run_migrate(
+    signer: &Pubkey,
+    fee_payer_signs: bool,
+    token_state: Vec<u8>,
+    metadata_initialized: bool,
+) -> InstructionResult {
+    let mollusk = setup_mollusk();
+    let (token_state_pda, _) = derive_token_state_pda();
+    let fee_payer = Pubkey::new_unique();
+    let lamports = rent_exempt(token_state.len());
+    let data = build_ix_data(&DISC_MIGRATE_TOKEN_STATE, &[metadata_initialized as u8]);
+    let instruction = Instruction::new_with_bytes(program_id(), &data, vec![
+        AccountMeta::new_readonly(*signer, true),
+        AccountMeta::new(token_state_pda, false),
+        AccountMeta::new(fee_payer, fee_payer_signs),
+        AccountMeta::new_readonly(system_program_id(), false),
+    ]);
+    let accounts = vec![
+        (*signer, make_system_account(1_000_000_000)),
+        (token_state_pda, make_program_account(token_state, lamports)),
+        (fee_payer, make_system_account(FEE_PAYER_LAMPORTS)),
+        mollusk_svm::program::keyed_account_for_system_program(),
+    ];
+    mollusk.process_instruction(&instruction, &accounts)
+}
+
+#[test]
+fn test_migrate_preserves_v1_fields() {
+    let old = token_state();
+    let result = run_migrate(&treasury_wallet(), true, old.clone(), true);
+    assert!(result.program_result.is_ok(), "migrate failed: {:?}", result.program_result);
+
+    let state = &result.resulting_accounts[1].1;
+    assert_eq!(state.data.len(), TOKEN_STATE_V2_SIZE);
+    assert_eq!(&state.data[..TOKEN_STATE_SIZE], &old[..], "existing fields kept");
+    assert_eq!(&state.data[363..371], &[0u8; 8], "max_supply defaults to uncapped");
+    assert_eq!(&state.data[371..373], &[1, 0], "metadata_initialized from data, not frozen");
+    assert_eq!(state.data[406], TOKEN_STATE_VERSION_2);
+    assert!(state.data[407..].iter().all(|b| *b == 0), "reserved space zeroed");
+}
+
+#[test]
+fn test_migrate_fee_payer_funds_rent() {
+    let result = run_migrate(&treasury_wallet(), true, token_state(), false);
+    assert!(result.program_result.is_ok(), "migrate failed: {:?}", result.program_result);
+
+    let state = &result.resulting_accounts[1].1;
+    let fee_payer = &result.resulting_accounts[2].1;
+    assert_eq!(state.lamports, rent_exempt(TOKEN_STATE_V2_SIZE));
+    assert_eq!(
+        fee_payer.lamports,
+        FEE_PAYER_LAMPORTS - (rent_exempt(TOKEN_STATE_V2_SIZE) - rent_exempt(TOKEN_STATE_SIZE))
+    );
+    assert_eq!(&state.data[371..373], &[0, 0]);
+}
+
+#[test]
+fn test_migrate_keeps_existing_tails() {
+    let collection_mint = Pubkey::new_unique();
+    let mut old = with_coupon_collection(token_state(), &collection_mint);
+    old[371] = 1; // metadata_initialized
+    old[372] = 1; // metadata_frozen
+    assert_eq!(old.len(), TOKEN_STATE_SIZE_WITH_COUPON_COLLECTION);
+
+    // Existing flags win over the instruction data
+    let result = run_migrate(&treasury_wallet(), true, old.clone(), false);
+    assert!(result.program_result.is_ok(), "migrate failed: {:?}", result.program_result);
+
+    let state = &result.resulting_accounts[1].1;
+    assert_eq!(&state.data[..TOKEN_STATE_SIZE_WITH_COUPON_COLLECTION], &old[..]);
+    assert_eq!(&state.data[405..407], &[0, TOKEN_STATE_VERSION_2]);
+}
+
+#[test]
+fn test_migrate_keeps_strict_registration() {
+    let old = with_strict_registration(token_state(), true);
+    let result = run_migrate(&treasury_wallet(), true, old.clone(), false);
+    assert!(result.program_result.is_ok(), "migrate failed: {:?}", result.program_result);
+
+    let state = &result.resulting_accounts[1].1;
+    assert_eq!(&state.data[..old.len()], &old[..]);
+    assert_eq!(state.data[406], TOKEN_STATE_VERSION_2);
+}
+
+#[test]
+fn test_migrate_rejects_v2() {
+    let result = run_migrate(&treasury_wallet(), true, token_state(), false);
+    let migrated = result.resulting_accounts[1].1.data.clone();
+
+    let result = run_migrate(&treasury_wallet(), true, migrated, false);
+    assert_ix_custom_err(&result, ERR_ALREADY_INITIALIZED);
+}
+
+#[test]
+fn test_migrate_rejects_non_treasury() {
+    let result = run_migrate(&Pubkey::new_unique(), true, token_state(), false);
+    assert_ix_custom_err(&result, ERR_UNAUTHORIZED_TREASURY);
+}
+
+#[test]
+fn test_migrate_requires_fee_payer_signature() {
+    let result = run_migrate(&treasury_wallet(), false, token_state(), false);
+    assert_ix_custom_err(&result, ERR_FEE_PAYER_NOT_SIGNER);
+}
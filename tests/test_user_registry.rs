@@ -0,0 +1,196 @@
+//! Mollusk integration tests for the user registry.
+//!
+//! Tests:
+//!   - initialize_user (4 accounts, treasury / transfer_authority signed,
+//!     UserState creation, re-initialization rejection)
+//!   - set_strict_registration (treasury only, grows older TokenStates)
+//!
+//! Transfers in strict / permissive mode are covered in `test_transfers`.
+//!
+//! Requires `cargo build-sbf` before running:
+//!   cargo build-sbf && cargo test --test test_user_registry -- --nocapture
+
+mod helpers;
+use helpers::*;
+
+use solana_account::Account;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+
+use zupy_token_program::state::token_state::TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION;
+use zupy_token_program::state::user_state::{
+    USER_STATE_DISCRIMINATOR, USER_STATE_SIZE, USER_STATUS_ACTIVE,
+};
+
+const DISC_INITIALIZE_USER: [u8; 8] = [111, 17, 185, 250, 60, 122, 38, 254];
+const DISC_SET_STRICT_REGISTRATION: [u8; 8] = [92, 42, 138, 11, 227, 9, 178, 89];
+
+const ERR_INVALID_AUTHORITY: u32 = 6000;
+const ERR_ALREADY_INITIALIZED: u32 = 6003;
+const ERR_INVALID_PDA: u32 = 6007;
+const ERR_UNAUTHORIZED_TREASURY: u32 = 6019;
+
+const USER_ID: u64 = 42;
+const NOW: i64 = 1_700_000_000;
+
+fn empty_account() -> Account {
+    Account { lamports: 0, data: vec![], owner: Pubkey::default(), executable: false, rent_epoch: 0 }
+}
+
+struct Fixture {
+    transfer_auth: Pubkey,
+    metas: Vec<AccountMeta>,
+    accounts: Vec<(Pubkey, Account)>,
+}
+
+/// System program loaded, user 42 unregistered, treasury signing.
+fn setup() -> Fixture {
+    let (token_state_pda, bump) = derive_token_state_pda();
+    let (user_state, _) = derive_user_state_pda(USER_ID);
+    let treasury = treasury_wallet();
+    let transfer_auth = Pubkey::new_unique();
+    let dummy = Pubkey::new_unique();
+    let ts_data = make_token_state_data(
+        &treasury, &mint_authority(), &transfer_auth, &dummy, &dummy, &dummy, &dummy,
+        &dummy, bump, true, false,
+    );
+
+    let metas = vec![
+        AccountMeta::new(treasury, true),
+        AccountMeta::new_readonly(token_state_pda, false),
+        AccountMeta::new(user_state, false),
+        AccountMeta::new_readonly(system_program_id(), false),
+    ];
+    let accounts = vec![
+        (treasury, make_system_account(1_000_000_000)),
+        (token_state_pda, make_program_account(ts_data, 1_000_000)),
+        (user_state, empty_account()),
+        mollusk_svm::program::keyed_account_for_system_program(),
+    ];
+    Fixture { transfer_auth, metas, accounts }
+}
+
+/// Makes `signer` account 0.
+fn signed_by(mut f: Fixture, signer: Pubkey) -> Fixture {
+    f.metas[0] = AccountMeta::new(signer, true);
+    f.accounts[0] = (signer, make_system_account(1_000_000_000));
+    f
+}
+
+fn run(f: &Fixture, user_id: u64) -> mollusk_svm::result::InstructionResult {
+    let mut mollusk = setup_mollusk();
+    mollusk.sysvars.clock.unix_timestamp = NOW;
+    let data = build_ix_data(&DISC_INITIALIZE_USER, &user_id.to_le_bytes());
+    let instruction = Instruction::new_with_bytes(program_id(), &data, f.metas.clone());
+    mollusk.process_instruction(&instruction, &f.accounts)
+}
+
+#[test]
+fn test_treasury_registers_user() {
+    let f = setup();
+    let result = run(&f, USER_ID);
+    assert!(result.program_result.is_ok(), "initialize failed: {:?}", result.program_result);
+
+    let state = &result.resulting_accounts[2].1;
+    assert_eq!(state.owner, program_id());
+    assert_eq!(state.data.len(), USER_STATE_SIZE);
+    assert_eq!(&state.data[0..8], &USER_STATE_DISCRIMINATOR);
+    assert_eq!(state.data, make_user_state_data(USER_ID, NOW, USER_STATUS_ACTIVE));
+}
+
+#[test]
+fn test_transfer_authority_registers_user() {
+    let f = setup();
+    let transfer_auth = f.transfer_auth;
+    let f = signed_by(f, transfer_auth);
+    let result = run(&f, USER_ID);
+    assert!(result.program_result.is_ok(), "initialize failed: {:?}", result.program_result);
+}
+
+#[test]
+fn test_reinitialization_rejected() {
+    let mut f = setup();
+    let first = run(&f, USER_ID);
+    assert!(first.program_result.is_ok(), "initialize failed: {:?}", first.program_result);
+
+    f.accounts = first.resulting_accounts;
+    assert_ix_custom_err(&run(&f, USER_ID), ERR_ALREADY_INITIALIZED);
+}
+
+#[test]
+fn test_rejects_other_signer() {
+    let f = signed_by(setup(), Pubkey::new_unique());
+    assert_ix_custom_err(&run(&f, USER_ID), ERR_INVALID_AUTHORITY);
+}
+
+#[test]
+fn test_rejects_other_user_pda() {
+    // user_state of user 42 passed for user 43
+    let f = setup();
+    assert_ix_custom_err(&run(&f, USER_ID + 1), ERR_INVALID_PDA);
+}
+
+#[test]
+fn test_user_pda_is_not_the_registry() {
+    let mut f = setup();
+    let (user_pda, _) = derive_user_pda(USER_ID);
+    f.metas[2] = AccountMeta::new(user_pda, false);
+    f.accounts[2] = (user_pda, empty_account());
+    assert_ix_custom_err(&run(&f, USER_ID), ERR_INVALID_PDA);
+}
+
+// ── set_strict_registration ─────────────────────────────────────────────
+
+fn run_set_strict(
+    signer: &Pubkey,
+    token_state: Vec<u8>,
+    enabled: bool,
+) -> mollusk_svm::result::InstructionResult {
+    let mollusk = setup_mollusk();
+    let (token_state_pda, _) = derive_token_state_pda();
+    let data = build_ix_data(&DISC_SET_STRICT_REGISTRATION, &[enabled as u8]);
+    let instruction = Instruction::new_with_bytes(program_id(), &data, vec![
+        AccountMeta::new(*signer, true),
+        AccountMeta::new(token_state_pda, false),
+        AccountMeta::new_readonly(system_program_id(), false),
+    ]);
+    let accounts = vec![
+        (*signer, make_system_account(1_000_000_000)),
+        (token_state_pda, make_program_account(token_state, 1_000_000_000)),
+        mollusk_svm::program::keyed_account_for_system_program(),
+    ];
+    mollusk.process_instruction(&instruction, &accounts)
+}
+
+fn token_state() -> Vec<u8> {
+    let (_, bump) = derive_token_state_pda();
+    let dummy = Pubkey::new_unique();
+    make_token_state_data(
+        &treasury_wallet(), &mint_authority(), &dummy, &dummy, &dummy, &dummy, &dummy,
+        &dummy, bump, true, false,
+    )
+}
+
+#[test]
+fn test_set_strict_registration_grows_token_state() {
+    let result = run_set_strict(&treasury_wallet(), token_state(), true);
+    assert!(result.program_result.is_ok(), "set failed: {:?}", result.program_result);
+
+    let state = &result.resulting_accounts[1].1;
+    assert_eq!(state.data.len(), TOKEN_STATE_SIZE_WITH_STRICT_REGISTRATION);
+    assert_eq!(state.data[405], 1);
+}
+
+#[test]
+fn test_set_strict_registration_turns_off() {
+    let strict = with_strict_registration(token_state(), true);
+    let result = run_set_strict(&treasury_wallet(), strict, false);
+    assert!(result.program_result.is_ok(), "set failed: {:?}", result.program_result);
+    assert_eq!(result.resulting_accounts[1].1.data[405], 0);
+}
+
+#[test]
+fn test_set_strict_registration_rejects_non_treasury() {
+    let result = run_set_strict(&Pubkey::new_unique(), token_state(), true);
+    assert_ix_custom_err(&result, ERR_UNAUTHORIZED_TREASURY);
+}
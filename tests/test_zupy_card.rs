@@ -0,0 +1,681 @@
+//! Mollusk integration tests for Zupy Cards.
+//!
+//! Tests:
+//!   - create_zupy_card (9 accounts, NonTransferable mint, soulbound, on-chain metadata)
+//!   - revoke_zupy_card (8 accounts, burn + close by user_pda, ZupyCard closed)
+//!   - update_card_tier (4 accounts, tier bound, legacy card growth, card status,
+//!     corrupted discriminators)
+//!   - reissue_zupy_card (9+2 accounts, burn old card, mint to new wallet)
+//!
+//! Requires `cargo build-sbf` before running:
+//!   cargo build-sbf && cargo test --test test_zupy_card -- --nocapture
+
+mod helpers;
+use helpers::*;
+
+use solana_account::Account;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+
+use zupy_token_program::state::zupy_card::{
+    CARD_STATUS_ACTIVE, CARD_STATUS_FROZEN, CARD_STATUS_REVOKED, ZUPY_CARD_DISCRIMINATOR, ZUPY_CARD_SIZE,
+    ZUPY_CARD_SIZE_WITH_ISSUED_AT, ZUPY_CARD_SIZE_WITH_STATUS, ZUPY_CARD_SIZE_WITH_TIER,
+};
+
+const DISC_CREATE_ZUPY_CARD: [u8; 8] = [92, 114, 17, 0, 219, 121, 112, 150];
+const DISC_REVOKE_ZUPY_CARD: [u8; 8] = [176, 218, 15, 77, 139, 66, 145, 34];
+const DISC_UPDATE_CARD_TIER: [u8; 8] = [179, 143, 196, 15, 127, 132, 0, 73];
+const DISC_REISSUE_ZUPY_CARD: [u8; 8] = [197, 233, 16, 159, 160, 154, 0, 138];
+
+mod create_zupy_card {
+    use super::*;
+
+    const USER_KSUID: [u8; 27] = *b"2NRjKcGrXHKtGVjMXV7qptaXY2A";
+    /// Token-2022 `TokenError::NonTransferable`.
+    const ERR_TOKEN_NON_TRANSFERABLE: u32 = 37;
+    const ERR_INVALID_PDA: u32 = 6007;
+    const ERR_INVALID_KSUID: u32 = 6061;
+    const ERR_CARD_ALREADY_EXISTS: u32 = 6062;
+    const CARD_URI: &str = "https://zupy.com/cards/1.json";
+    /// Token-2022 `ExtensionType::MetadataPointer` / `ExtensionType::TokenMetadata`.
+    const EXT_METADATA_POINTER: u16 = 18;
+    const EXT_TOKEN_METADATA: u16 = 19;
+
+    fn empty_account() -> Account {
+        Account { lamports: 0, data: vec![], owner: Pubkey::default(), executable: false, rent_epoch: 0 }
+    }
+
+    /// Value of extension `ext_type` in a Token-2022 mint (TLVs after byte 166).
+    fn find_extension(mint: &[u8], ext_type: u16) -> &[u8] {
+        let mut offset = 166;
+        while offset + 4 <= mint.len() {
+            let ty = u16::from_le_bytes(mint[offset..offset + 2].try_into().unwrap());
+            let len = u16::from_le_bytes(mint[offset + 2..offset + 4].try_into().unwrap()) as usize;
+            if ty == ext_type {
+                return &mint[offset + 4..offset + 4 + len];
+            }
+            offset += 4 + len;
+        }
+        panic!("extension {ext_type} not found");
+    }
+
+    /// Borsh String at `offset`: (value, next offset).
+    fn read_string(data: &[u8], offset: usize) -> (&str, usize) {
+        let len = u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap()) as usize;
+        let end = offset + 4 + len;
+        (std::str::from_utf8(&data[offset + 4..end]).unwrap(), end)
+    }
+
+    /// create_zupy_card for `USER_KSUID` with `user_pda` as account 0, against
+    /// real Token-2022 / ATA / system programs.
+    fn create_fixture(user_pda: Pubkey) -> (Instruction, Vec<(Pubkey, Account)>) {
+        let (token_state_pda, bump) = derive_token_state_pda();
+        let mint_auth = mint_authority();
+        let (card_pda, _) = derive_zupy_card_pda(&USER_KSUID);
+        let (card_mint, _) = derive_zupy_card_mint_pda(&USER_KSUID);
+        let (card_ata, _) = Pubkey::find_program_address(
+            &[user_pda.as_ref(), token_2022_id().as_ref(), card_mint.as_ref()],
+            &ata_program_id(),
+        );
+
+        let dummy = Pubkey::new_unique();
+        let ts_data = make_token_state_data(
+            &dummy, &mint_auth, &dummy, &dummy, &dummy, &dummy, &dummy,
+            &dummy, bump, true, false,
+        );
+
+        let mut payload = USER_KSUID.to_vec();
+        payload.extend_from_slice(&build_string(CARD_URI));
+        let create = Instruction::new_with_bytes(
+            program_id(),
+            &build_ix_data(&DISC_CREATE_ZUPY_CARD, &payload),
+            vec![
+                AccountMeta::new_readonly(user_pda, false),
+                AccountMeta::new(card_pda, false),
+                AccountMeta::new(card_mint, false),
+                AccountMeta::new(card_ata, false),
+                AccountMeta::new_readonly(token_state_pda, false),
+                AccountMeta::new(mint_auth, true),
+                AccountMeta::new_readonly(token_2022_id(), false),
+                AccountMeta::new_readonly(ata_program_id(), false),
+                AccountMeta::new_readonly(system_program_id(), false),
+            ],
+        );
+        let accounts = vec![
+            (user_pda, empty_account()),
+            (card_pda, empty_account()),
+            (card_mint, empty_account()),
+            (card_ata, empty_account()),
+            (token_state_pda, make_program_account(ts_data, 1_000_000)),
+            (mint_auth, make_system_account(10_000_000_000)),
+            mollusk_svm_programs_token::token2022::keyed_account(),
+            mollusk_svm_programs_token::associated_token::keyed_account(),
+            mollusk_svm::program::keyed_account_for_system_program(),
+        ];
+        (create, accounts)
+    }
+
+    /// Full flow with Token-2022 + ATA loaded: the card is minted to the
+    /// user_pda ATA with on-chain metadata, then a TransferChecked out of it is
+    /// rejected by Token-2022.
+    #[test]
+    fn test_card_has_metadata_and_is_non_transferable() {
+        let mut mollusk = setup_mollusk_with_programs();
+        mollusk.sysvars.clock.unix_timestamp = 1_700_000_000;
+        let (user_pda, _) = derive_user_pda_by_ksuid(&USER_KSUID);
+        let (card_pda, _) = derive_zupy_card_pda(&USER_KSUID);
+        let (card_mint, _) = derive_zupy_card_mint_pda(&USER_KSUID);
+        let (create, accounts) = create_fixture(user_pda);
+        let card_ata = accounts[3].0;
+        let result = mollusk.process_instruction(&create, &accounts);
+        assert!(result.program_result.is_ok(), "create failed: {:?}", result.program_result);
+
+        // ZupyCard: owner = user_pda, ksuid stored, active with no reissues
+        let card = &result.resulting_accounts[1].1.data;
+        assert_eq!(&card[8..40], user_pda.as_ref());
+        assert_eq!(&card[72..99], &USER_KSUID);
+        assert_eq!(card[117], CARD_STATUS_ACTIVE);
+        assert_eq!(&card[118..122], &[0u8; 4]);
+
+        let mint_account = &result.resulting_accounts[2].1;
+        let rent = mollusk_svm::Mollusk::default().sysvars.rent;
+        assert!(mint_account.lamports >= rent.minimum_balance(mint_account.data.len()));
+
+        // MetadataPointer: authority = zupy_card PDA, metadata = the mint itself
+        let pointer = find_extension(&mint_account.data, EXT_METADATA_POINTER);
+        assert_eq!(&pointer[0..32], card_pda.as_ref());
+        assert_eq!(&pointer[32..64], card_mint.as_ref());
+
+        // TokenMetadata: update_authority, mint, name, symbol, uri
+        let metadata = find_extension(&mint_account.data, EXT_TOKEN_METADATA);
+        assert_eq!(&metadata[0..32], card_pda.as_ref());
+        assert_eq!(&metadata[32..64], card_mint.as_ref());
+        let (name, offset) = read_string(metadata, 64);
+        let (symbol, offset) = read_string(metadata, offset);
+        let (uri, _) = read_string(metadata, offset);
+        assert_eq!((name, symbol, uri), ("Zuper Card", "ZCARD", CARD_URI));
+
+        let ata_data = &result.resulting_accounts[3].1.data;
+        assert_eq!(u64::from_le_bytes(ata_data[64..72].try_into().unwrap()), 1);
+
+        // TransferChecked (ix 12): amount 1, decimals 0 — signed by user_pda
+        let recipient = Pubkey::new_unique();
+        let recipient_ata = Pubkey::new_unique();
+        let mut transfer_data = vec![12u8];
+        transfer_data.extend_from_slice(&1u64.to_le_bytes());
+        transfer_data.push(0);
+        let transfer = Instruction::new_with_bytes(
+            token_2022_id(),
+            &transfer_data,
+            vec![
+                AccountMeta::new(card_ata, false),
+                AccountMeta::new_readonly(card_mint, false),
+                AccountMeta::new(recipient_ata, false),
+                AccountMeta::new_readonly(user_pda, true),
+            ],
+        );
+        let transfer_accounts = vec![
+            result.resulting_accounts[3].clone(),
+            result.resulting_accounts[2].clone(),
+            (recipient_ata, make_token_owned_account(make_token_account_data(&card_mint, &recipient, 0))),
+            (user_pda, make_system_account(0)),
+        ];
+        let result = mollusk.process_instruction(&transfer, &transfer_accounts);
+        assert_ix_custom_err(&result, ERR_TOKEN_NON_TRANSFERABLE);
+    }
+
+    /// created_at and issued_at both come from the Clock sysvar.
+    #[test]
+    fn test_card_stores_issue_time_from_clock() {
+        let mut mollusk = setup_mollusk_with_programs();
+        mollusk.sysvars.clock.unix_timestamp = 1_712_345_678;
+        let (user_pda, _) = derive_user_pda_by_ksuid(&USER_KSUID);
+        let (create, accounts) = create_fixture(user_pda);
+        let result = mollusk.process_instruction(&create, &accounts);
+        assert!(result.program_result.is_ok(), "create failed: {:?}", result.program_result);
+
+        let card = &result.resulting_accounts[1].1.data;
+        assert_eq!(card.len(), ZUPY_CARD_SIZE_WITH_ISSUED_AT);
+        assert_eq!(i64::from_le_bytes(card[99..107].try_into().unwrap()), 1_712_345_678);
+        assert_eq!(i64::from_le_bytes(card[122..130].try_into().unwrap()), 1_712_345_678);
+        assert_eq!(card[117], CARD_STATUS_ACTIVE);
+    }
+
+    /// A second create for the same ksuid fails with `CardAlreadyExists`
+    /// before any CPI, leaving the first card untouched.
+    #[test]
+    fn test_double_creation_rejected() {
+        let mollusk = setup_mollusk_with_programs();
+        let (user_pda, _) = derive_user_pda_by_ksuid(&USER_KSUID);
+        let (create, accounts) = create_fixture(user_pda);
+        let first = mollusk.process_instruction(&create, &accounts);
+        assert!(first.program_result.is_ok(), "create failed: {:?}", first.program_result);
+
+        let second = mollusk.process_instruction(&create, &first.resulting_accounts);
+        assert_ix_custom_err(&second, ERR_CARD_ALREADY_EXISTS);
+    }
+
+    /// A user_pda that is not `["user_pda", ksuid]` fails with `InvalidPDA`.
+    #[test]
+    fn test_mismatched_user_pda_rejected() {
+        let mollusk = setup_mollusk_with_programs();
+        let (other_user_pda, _) = derive_user_pda_by_ksuid(b"2NRjKcGrXHKtGVjMXV7qptaXY2B");
+        let (create, accounts) = create_fixture(other_user_pda);
+        assert_ix_custom_err(&mollusk.process_instruction(&create, &accounts), ERR_INVALID_PDA);
+    }
+
+    /// A non-base62 KSUID is rejected before any PDA is derived or created.
+    #[test]
+    fn test_rejects_malformed_ksuid() {
+        let mollusk = setup_mollusk();
+        let (token_state_pda, bump) = derive_token_state_pda();
+        let mint_auth = mint_authority();
+        let dummy = Pubkey::new_unique();
+        let ts_data = make_token_state_data(
+            &dummy, &mint_auth, &dummy, &dummy, &dummy, &dummy, &dummy,
+            &dummy, bump, true, false,
+        );
+
+        let mut ksuid = USER_KSUID;
+        ksuid[5] = b'-';
+        let mut payload = ksuid.to_vec();
+        payload.extend_from_slice(&build_string(CARD_URI));
+        let keys: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &build_ix_data(&DISC_CREATE_ZUPY_CARD, &payload),
+            vec![
+                AccountMeta::new_readonly(keys[0], false),
+                AccountMeta::new(keys[1], false),
+                AccountMeta::new(keys[2], false),
+                AccountMeta::new(keys[3], false),
+                AccountMeta::new_readonly(token_state_pda, false),
+                AccountMeta::new(mint_auth, true),
+                AccountMeta::new_readonly(token_2022_id(), false),
+                AccountMeta::new_readonly(ata_program_id(), false),
+                AccountMeta::new_readonly(system_program_id(), false),
+            ],
+        );
+        let accounts = vec![
+            (keys[0], empty_account()),
+            (keys[1], empty_account()),
+            (keys[2], empty_account()),
+            (keys[3], empty_account()),
+            (token_state_pda, make_program_account(ts_data, 1_000_000)),
+            (mint_auth, make_system_account(10_000_000_000)),
+            make_program_stub(&token_2022_id()),
+            make_program_stub(&ata_program_id()),
+            make_program_stub(&system_program_id()),
+        ];
+        let result = mollusk.process_instruction(&ix, &accounts);
+        assert_ix_custom_err(&result, ERR_INVALID_KSUID);
+    }
+}
+
+mod revoke_zupy_card {
+    use super::*;
+
+    const USER_KSUID: [u8; 27] = *b"2NRjKcGrXHKtGVjMXV7qptaXY2A";
+    const CARD_LAMPORTS: u64 = 1_642_560;
+    const ERR_INVALID_AUTHORITY: u32 = 6000;
+    const ERR_INVALID_CARD_BALANCE: u32 = 6052;
+
+    struct Fixture {
+        metas: Vec<AccountMeta>,
+        accounts: Vec<(Pubkey, Account)>,
+        treasury: Pubkey,
+    }
+
+    /// Token-2022 loaded; the card ATA holds `balance` and the mint supply matches.
+    fn setup(balance: u64) -> Fixture {
+        let (token_state_pda, bump) = derive_token_state_pda();
+        let mint_auth = mint_authority();
+        let treasury = Pubkey::new_unique();
+        let (user_pda, _) = derive_user_pda_by_ksuid(&USER_KSUID);
+        let (card_pda, card_bump) = derive_zupy_card_pda(&USER_KSUID);
+        let (card_mint, _) = derive_zupy_card_mint_pda(&USER_KSUID);
+        let token_account = Pubkey::new_unique();
+        let destination = Pubkey::new_unique();
+
+        let dummy = Pubkey::new_unique();
+        let ts_data = make_token_state_data(
+            &treasury, &mint_auth, &dummy, &dummy, &dummy, &dummy, &dummy,
+            &dummy, bump, true, false,
+        );
+
+        let mut card_data = vec![0u8; ZUPY_CARD_SIZE];
+        card_data[0..8].copy_from_slice(&ZUPY_CARD_DISCRIMINATOR);
+        card_data[8..40].copy_from_slice(user_pda.as_ref());
+        card_data[40..72].copy_from_slice(card_mint.as_ref());
+        card_data[72..99].copy_from_slice(&USER_KSUID);
+        card_data[107] = card_bump;
+
+        let metas = vec![
+            AccountMeta::new_readonly(mint_auth, true),          // 0: authority (signer)
+            AccountMeta::new_readonly(token_state_pda, false),   // 1
+            AccountMeta::new_readonly(user_pda, false),          // 2
+            AccountMeta::new(card_pda, false),                   // 3: writable
+            AccountMeta::new(card_mint, false),                  // 4: writable
+            AccountMeta::new(token_account, false),              // 5: writable
+            AccountMeta::new(destination, false),                // 6: writable
+            AccountMeta::new_readonly(token_2022_id(), false),   // 7
+        ];
+        let accounts = vec![
+            (mint_auth, make_system_account(1_000_000)),
+            (token_state_pda, make_program_account(ts_data, 1_000_000)),
+            (user_pda, make_system_account(0)),
+            (card_pda, make_program_account(card_data, CARD_LAMPORTS)),
+            (card_mint, make_token_owned_account(make_mint_data(&card_pda, balance, 0))),
+            (token_account, make_token_owned_account(make_token_account_data(&card_mint, &user_pda, balance))),
+            (destination, make_system_account(0)),
+            mollusk_svm_programs_token::token2022::keyed_account(),
+        ];
+        Fixture { metas, accounts, treasury }
+    }
+
+    fn run(f: &Fixture) -> mollusk_svm::result::InstructionResult {
+        let mollusk = setup_mollusk_with_programs();
+        let data = build_ix_data(&DISC_REVOKE_ZUPY_CARD, &USER_KSUID);
+        let instruction = Instruction::new_with_bytes(program_id(), &data, f.metas.clone());
+        mollusk.process_instruction(&instruction, &f.accounts)
+    }
+
+    fn assert_closed(result: &mollusk_svm::result::InstructionResult, idx: usize, name: &str) {
+        let account = &result.resulting_accounts[idx].1;
+        assert_eq!(account.lamports, 0, "{name} lamports");
+        assert!(account.data.iter().all(|b| *b == 0), "{name} data not zeroed");
+    }
+
+    #[test]
+    fn test_revoke_burns_and_closes() {
+        let f = setup(1);
+        let ata_lamports = f.accounts[5].1.lamports;
+        let result = run(&f);
+        assert!(result.program_result.is_ok(), "revoke failed: {:?}", result.program_result);
+
+        assert_closed(&result, 3, "zupy_card");
+        assert_closed(&result, 5, "token_account");
+        let mint_data = &result.resulting_accounts[4].1.data;
+        assert_eq!(u64::from_le_bytes(mint_data[36..44].try_into().unwrap()), 0, "mint supply");
+        assert_eq!(result.resulting_accounts[6].1.lamports, CARD_LAMPORTS + ata_lamports);
+        println!("revoke_zupy_card: full_flow CU={}", result.compute_units_consumed);
+    }
+
+    #[test]
+    fn test_revoke_empty_card_by_treasury() {
+        let mut f = setup(0);
+        f.metas[0] = AccountMeta::new_readonly(f.treasury, true);
+        f.accounts[0].0 = f.treasury;
+        let result = run(&f);
+        assert!(result.program_result.is_ok(), "revoke failed: {:?}", result.program_result);
+        assert_closed(&result, 3, "zupy_card");
+        assert_closed(&result, 5, "token_account");
+    }
+
+    #[test]
+    fn test_revoke_rejects_balance_above_one() {
+        assert_ix_custom_err(&run(&setup(2)), ERR_INVALID_CARD_BALANCE);
+    }
+
+    #[test]
+    fn test_revoke_rejects_other_signer() {
+        let mut f = setup(1);
+        let impostor = Pubkey::new_unique();
+        f.metas[0] = AccountMeta::new_readonly(impostor, true);
+        f.accounts[0].0 = impostor;
+        assert_ix_custom_err(&run(&f), ERR_INVALID_AUTHORITY);
+    }
+}
+
+mod update_card_tier {
+    use super::*;
+
+    const USER_KSUID: [u8; 27] = *b"2NRjKcGrXHKtGVjMXV7qptaXY2A";
+    const NOW: i64 = 1_800_000_000;
+    const ERR_INVALID_AUTHORITY: u32 = 6000;
+    const ERR_INVALID_CARD_TIER: u32 = 6053;
+    const ERR_CARD_REVOKED: u32 = 6054;
+    const ERR_INVALID_ACCOUNT_DISCRIMINATOR: u32 = 6066;
+    const ERR_CARD_FROZEN: u32 = 6067;
+
+    struct Fixture {
+        metas: Vec<AccountMeta>,
+        accounts: Vec<(Pubkey, Account)>,
+    }
+
+    /// ZupyCard of `card_len` bytes (108 = legacy, 117 = with tier, 122 = with status).
+    fn setup(card_len: usize) -> Fixture {
+        let (token_state_pda, bump) = derive_token_state_pda();
+        let mint_auth = mint_authority();
+        let (card_pda, card_bump) = derive_zupy_card_pda(&USER_KSUID);
+
+        let dummy = Pubkey::new_unique();
+        let ts_data = make_token_state_data(
+            &dummy, &mint_auth, &dummy, &dummy, &dummy, &dummy, &dummy,
+            &dummy, bump, true, false,
+        );
+
+        let mut card_data = vec![0u8; card_len];
+        card_data[0..8].copy_from_slice(&ZUPY_CARD_DISCRIMINATOR);
+        card_data[72..99].copy_from_slice(&USER_KSUID);
+        card_data[107] = card_bump;
+        let card_lamports = ((128 + card_len as u64) * 3480 * 2).max(1);
+
+        let metas = vec![
+            AccountMeta::new(mint_auth, true),                     // 0: writable signer
+            AccountMeta::new_readonly(token_state_pda, false),     // 1
+            AccountMeta::new(card_pda, false),                     // 2: writable
+            AccountMeta::new_readonly(system_program_id(), false), // 3
+        ];
+        let accounts = vec![
+            (mint_auth, make_system_account(1_000_000_000)),
+            (token_state_pda, make_program_account(ts_data, 1_000_000)),
+            (card_pda, make_program_account(card_data, card_lamports)),
+            mollusk_svm::program::keyed_account_for_system_program(),
+        ];
+        Fixture { metas, accounts }
+    }
+
+    fn run(f: &Fixture, tier: u8) -> mollusk_svm::result::InstructionResult {
+        let mut mollusk = setup_mollusk();
+        mollusk.sysvars.clock.unix_timestamp = NOW;
+        let mut payload = USER_KSUID.to_vec();
+        payload.push(tier);
+        let data = build_ix_data(&DISC_UPDATE_CARD_TIER, &payload);
+        let instruction = Instruction::new_with_bytes(program_id(), &data, f.metas.clone());
+        mollusk.process_instruction(&instruction, &f.accounts)
+    }
+
+    #[test]
+    fn test_sets_tier_and_logs_event() {
+        let result = run(&setup(ZUPY_CARD_SIZE_WITH_TIER), 3);
+        assert!(result.program_result.is_ok(), "update failed: {:?}", result.program_result);
+        let card = &result.resulting_accounts[2].1.data;
+        assert_eq!(card[108], 3);
+        assert_eq!(i64::from_le_bytes(card[109..117].try_into().unwrap()), NOW);
+        assert_logged(&result, "zupy_evt:card_tier:3:2NRjKcGrXHKtGVjMXV7qptaXY2A");
+    }
+
+    #[test]
+    fn test_tier_above_max_rejected() {
+        let f = setup(ZUPY_CARD_SIZE_WITH_TIER);
+        assert!(run(&f, 3).program_result.is_ok());
+        assert_ix_custom_err(&run(&f, 4), ERR_INVALID_CARD_TIER);
+        assert_ix_custom_err(&run(&f, u8::MAX), ERR_INVALID_CARD_TIER);
+    }
+
+    #[test]
+    fn test_legacy_card_is_grown() {
+        let result = run(&setup(ZUPY_CARD_SIZE), 1);
+        assert!(result.program_result.is_ok(), "update failed: {:?}", result.program_result);
+        let card = &result.resulting_accounts[2].1;
+        assert_eq!(card.data.len(), ZUPY_CARD_SIZE_WITH_TIER);
+        assert_eq!(card.data[108], 1);
+        assert_eq!(&card.data[72..99], &USER_KSUID, "existing fields kept");
+        let rent = mollusk_svm::Mollusk::default().sysvars.rent;
+        assert!(card.lamports >= rent.minimum_balance(ZUPY_CARD_SIZE_WITH_TIER));
+    }
+
+    #[test]
+    fn test_frozen_or_revoked_card_rejected() {
+        let mut f = setup(ZUPY_CARD_SIZE_WITH_STATUS);
+        f.accounts[2].1.data[117] = CARD_STATUS_FROZEN;
+        assert_ix_custom_err(&run(&f, 1), ERR_CARD_FROZEN);
+        f.accounts[2].1.data[117] = CARD_STATUS_REVOKED;
+        assert_ix_custom_err(&run(&f, 1), ERR_CARD_REVOKED);
+        f.accounts[2].1.data[117] = CARD_STATUS_ACTIVE;
+        assert!(run(&f, 1).program_result.is_ok());
+    }
+
+    #[test]
+    fn test_corrupted_card_discriminator_rejected() {
+        let mut f = setup(ZUPY_CARD_SIZE_WITH_TIER);
+        f.accounts[2].1.data[0..8].copy_from_slice(&[0xAB; 8]);
+        assert_ix_custom_err(&run(&f, 1), ERR_INVALID_ACCOUNT_DISCRIMINATOR);
+    }
+
+    #[test]
+    fn test_corrupted_token_state_discriminator_rejected() {
+        let mut f = setup(ZUPY_CARD_SIZE_WITH_TIER);
+        f.accounts[1].1.data[7] ^= 0x01;
+        assert_ix_custom_err(&run(&f, 1), ERR_INVALID_ACCOUNT_DISCRIMINATOR);
+    }
+
+    #[test]
+    fn test_rejects_non_mint_authority() {
+        let mut f = setup(ZUPY_CARD_SIZE_WITH_TIER);
+        let impostor = Pubkey::new_unique();
+        f.metas[0] = AccountMeta::new(impostor, true);
+        f.accounts[0].0 = impostor;
+        assert_ix_custom_err(&run(&f, 1), ERR_INVALID_AUTHORITY);
+    }
+}
+
+mod reissue_zupy_card {
+    use super::*;
+
+    const USER_KSUID: [u8; 27] = *b"2NRjKcGrXHKtGVjMXV7qptaXY2A";
+    const NOW: i64 = 1_800_000_000;
+    const ERR_INVALID_MINT: u32 = 6011;
+    const ERR_CARD_REVOKED: u32 = 6054;
+    const ERR_CARD_FROZEN: u32 = 6067;
+
+    fn empty_account() -> Account {
+        Account { lamports: 0, data: vec![], owner: Pubkey::default(), executable: false, rent_epoch: 0 }
+    }
+
+    fn card_ata(owner: &Pubkey) -> Pubkey {
+        let (card_mint, _) = derive_zupy_card_mint_pda(&USER_KSUID);
+        Pubkey::find_program_address(
+            &[owner.as_ref(), token_2022_id().as_ref(), card_mint.as_ref()],
+            &ata_program_id(),
+        )
+        .0
+    }
+
+    struct Fixture {
+        metas: Vec<AccountMeta>,
+        accounts: Vec<(Pubkey, Account)>,
+        new_wallet: Pubkey,
+    }
+
+    /// Card held (balance 1) by the user_pda ATA, with `status` set; the old
+    /// ATA is passed as the optional accounts 9-10.
+    fn setup(status: u8) -> Fixture {
+        let (token_state_pda, bump) = derive_token_state_pda();
+        let mint_auth = mint_authority();
+        let (user_pda, _) = derive_user_pda_by_ksuid(&USER_KSUID);
+        let (card_pda, card_bump) = derive_zupy_card_pda(&USER_KSUID);
+        let (card_mint, _) = derive_zupy_card_mint_pda(&USER_KSUID);
+        let old_ata = card_ata(&user_pda);
+        let new_wallet = Pubkey::new_unique();
+        let new_ata = card_ata(&new_wallet);
+
+        let dummy = Pubkey::new_unique();
+        let ts_data = make_token_state_data(
+            &dummy, &mint_auth, &dummy, &dummy, &dummy, &dummy, &dummy,
+            &dummy, bump, true, false,
+        );
+
+        let mut card_data = vec![0u8; ZUPY_CARD_SIZE_WITH_STATUS];
+        card_data[0..8].copy_from_slice(&ZUPY_CARD_DISCRIMINATOR);
+        card_data[8..40].copy_from_slice(user_pda.as_ref());
+        card_data[40..72].copy_from_slice(card_mint.as_ref());
+        card_data[72..99].copy_from_slice(&USER_KSUID);
+        card_data[107] = card_bump;
+        card_data[117] = status;
+
+        let metas = vec![
+            AccountMeta::new(mint_auth, true),                      // 0: writable signer
+            AccountMeta::new_readonly(token_state_pda, false),      // 1
+            AccountMeta::new(card_pda, false),                      // 2: writable
+            AccountMeta::new(card_mint, false),                     // 3: writable
+            AccountMeta::new_readonly(new_wallet, false),           // 4
+            AccountMeta::new(new_ata, false),                       // 5: writable
+            AccountMeta::new_readonly(token_2022_id(), false),      // 6
+            AccountMeta::new_readonly(ata_program_id(), false),     // 7
+            AccountMeta::new_readonly(system_program_id(), false),  // 8
+            AccountMeta::new_readonly(user_pda, false),             // 9: optional
+            AccountMeta::new(old_ata, false),                       // 10: optional, writable
+        ];
+        let accounts = vec![
+            (mint_auth, make_system_account(10_000_000_000)),
+            (token_state_pda, make_program_account(ts_data, 1_000_000)),
+            (card_pda, make_program_account(card_data, 10_000_000)),
+            (card_mint, make_token_owned_account(make_mint_data(&card_pda, 1, 0))),
+            (new_wallet, make_system_account(0)),
+            (new_ata, empty_account()),
+            mollusk_svm_programs_token::token2022::keyed_account(),
+            mollusk_svm_programs_token::associated_token::keyed_account(),
+            mollusk_svm::program::keyed_account_for_system_program(),
+            (user_pda, make_system_account(0)),
+            (old_ata, make_token_owned_account(make_token_account_data(&card_mint, &user_pda, 1))),
+        ];
+        Fixture { metas, accounts, new_wallet }
+    }
+
+    fn run(f: &Fixture) -> mollusk_svm::result::InstructionResult {
+        let mut mollusk = setup_mollusk_with_programs();
+        mollusk.sysvars.clock.unix_timestamp = NOW;
+        let data = build_ix_data(&DISC_REISSUE_ZUPY_CARD, &USER_KSUID);
+        let instruction = Instruction::new_with_bytes(program_id(), &data, f.metas.clone());
+        mollusk.process_instruction(&instruction, &f.accounts)
+    }
+
+    fn token_balance(account: &Account) -> u64 {
+        u64::from_le_bytes(account.data[64..72].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_reissue_active_card() {
+        let f = setup(0);
+        let result = run(&f);
+        assert!(result.program_result.is_ok(), "reissue failed: {:?}", result.program_result);
+
+        let card = &result.resulting_accounts[2].1.data;
+        assert_eq!(&card[8..40], f.new_wallet.as_ref(), "owner re-pointed");
+        assert_eq!(card[117], 0, "card active");
+        assert_eq!(u32::from_le_bytes(card[118..122].try_into().unwrap()), 1);
+        assert_eq!(i64::from_le_bytes(card[109..117].try_into().unwrap()), NOW);
+        assert_eq!(card.len(), ZUPY_CARD_SIZE_WITH_ISSUED_AT, "grown to carry issued_at");
+        assert_eq!(i64::from_le_bytes(card[122..130].try_into().unwrap()), NOW);
+        assert_eq!(&card[72..99], &USER_KSUID, "ksuid kept");
+
+        assert_eq!(token_balance(&result.resulting_accounts[10].1), 0, "old card burned");
+        assert_eq!(token_balance(&result.resulting_accounts[5].1), 1, "new card minted");
+        println!("reissue_zupy_card: full_flow CU={}", result.compute_units_consumed);
+    }
+
+    #[test]
+    fn test_reissue_count_increments() {
+        let mut f = setup(0);
+        let first = run(&f);
+        assert!(first.program_result.is_ok(), "reissue failed: {:?}", first.program_result);
+
+        // Second reissue, without the optional old-card accounts
+        f.accounts = first.resulting_accounts[..9].to_vec();
+        f.metas.truncate(9);
+        let next_wallet = Pubkey::new_unique();
+        let next_ata = card_ata(&next_wallet);
+        f.metas[4] = AccountMeta::new_readonly(next_wallet, false);
+        f.metas[5] = AccountMeta::new(next_ata, false);
+        f.accounts[4] = (next_wallet, make_system_account(0));
+        f.accounts[5] = (next_ata, empty_account());
+
+        let second = run(&f);
+        assert!(second.program_result.is_ok(), "reissue failed: {:?}", second.program_result);
+        let card = &second.resulting_accounts[2].1.data;
+        assert_eq!(u32::from_le_bytes(card[118..122].try_into().unwrap()), 2);
+        assert_eq!(&card[8..40], next_wallet.as_ref());
+        assert_eq!(token_balance(&second.resulting_accounts[5].1), 1);
+    }
+
+    #[test]
+    fn test_reissue_into_existing_ata() {
+        let mut f = setup(0);
+        let (card_mint, _) = derive_zupy_card_mint_pda(&USER_KSUID);
+        f.accounts[5].1 = make_token_owned_account(make_token_account_data(&card_mint, &f.new_wallet, 0));
+
+        let result = run(&f);
+        assert!(result.program_result.is_ok(), "reissue failed: {:?}", result.program_result);
+        assert_eq!(token_balance(&result.resulting_accounts[5].1), 1, "minted into existing ATA");
+    }
+
+    #[test]
+    fn test_reissue_existing_ata_wrong_mint() {
+        let mut f = setup(0);
+        let other_mint = Pubkey::new_unique();
+        f.accounts[5].1 = make_token_owned_account(make_token_account_data(&other_mint, &f.new_wallet, 0));
+        assert_ix_custom_err(&run(&f), ERR_INVALID_MINT);
+    }
+
+    #[test]
+    fn test_reissue_revoked_card_rejected() {
+        assert_ix_custom_err(&run(&setup(CARD_STATUS_REVOKED)), ERR_CARD_REVOKED);
+    }
+
+    #[test]
+    fn test_reissue_frozen_card_rejected() {
+        assert_ix_custom_err(&run(&setup(CARD_STATUS_FROZEN)), ERR_CARD_FROZEN);
+    }
+}